@@ -20,6 +20,14 @@ pub struct CreateDepositRequestBody {
     /// Bitcoin transaction id.
     #[serde(rename = "bitcoinTxid")]
     pub bitcoin_txid: String,
+    /// An identifier for tracing the deposit across systems, which is passed along in the status updates of the deposit. It must be at most 128 printable ASCII characters without whitespace.
+    #[serde(
+        rename = "correlationId",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub correlation_id: Option<Option<String>>,
     /// Deposit script.
     #[serde(rename = "depositScript")]
     pub deposit_script: String,
@@ -43,6 +51,7 @@ impl CreateDepositRequestBody {
         CreateDepositRequestBody {
             bitcoin_tx_output_index,
             bitcoin_txid,
+            correlation_id: None,
             deposit_script,
             reclaim_script,
             transaction_hex,
@@ -20,6 +20,14 @@ pub struct DepositUpdate {
     /// Bitcoin transaction id.
     #[serde(rename = "bitcoinTxid")]
     pub bitcoin_txid: String,
+    /// The correlation ID of the deposit, passed along by the sender of the update for tracing.
+    #[serde(
+        rename = "correlationId",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub correlation_id: Option<Option<String>>,
     #[serde(
         rename = "fulfillment",
         default,
@@ -53,6 +61,7 @@ impl DepositUpdate {
         DepositUpdate {
             bitcoin_tx_output_index,
             bitcoin_txid,
+            correlation_id: None,
             fulfillment: None,
             replaced_by_tx: None,
             status,
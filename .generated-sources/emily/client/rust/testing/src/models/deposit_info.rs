@@ -23,6 +23,14 @@ pub struct DepositInfo {
     /// Bitcoin transaction id.
     #[serde(rename = "bitcoinTxid")]
     pub bitcoin_txid: String,
+    /// An identifier that the creator of the deposit chose for tracing it across systems. It is passed along in the status updates of the deposit.
+    #[serde(
+        rename = "correlationId",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub correlation_id: Option<Option<String>>,
     /// Raw deposit script binary in hex.
     #[serde(rename = "depositScript")]
     pub deposit_script: String,
@@ -59,6 +67,7 @@ impl DepositInfo {
             amount,
             bitcoin_tx_output_index,
             bitcoin_txid,
+            correlation_id: None,
             deposit_script,
             last_update_block_hash,
             last_update_height,
@@ -155,6 +155,7 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "CorrelationId",
             ]
         });
 
@@ -178,6 +179,7 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "CorrelationId",
             ]
         });
 
@@ -203,6 +205,7 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "CorrelationId",
             ]
         });
         return table;
@@ -320,6 +320,7 @@ pub async fn create_deposit(
     tracing::debug!(
         bitcoin_txid = %body.bitcoin_txid,
         bitcoin_tx_output_index = body.bitcoin_tx_output_index,
+        correlation_id = body.correlation_id.as_deref(),
         "creating deposit"
     );
     // Internal handler so `?` can be used correctly while still returning a reply.
@@ -388,6 +389,7 @@ pub async fn create_deposit(
             reclaim_script: body.reclaim_script,
             deposit_script: body.deposit_script,
             reclaim_pubkeys_hash,
+            correlation_id: body.correlation_id,
             ..Default::default()
         };
         // Validate deposit entry.
@@ -525,10 +527,12 @@ async fn update_deposits(
 
         let bitcoin_txid = update.key.bitcoin_txid.clone();
         let bitcoin_tx_output_index = update.key.bitcoin_tx_output_index;
+        let correlation_id = update.correlation_id.clone();
 
         tracing::debug!(
             %bitcoin_txid,
             bitcoin_tx_output_index,
+            correlation_id = correlation_id.as_deref(),
             "updating deposit"
         );
 
@@ -545,6 +549,7 @@ async fn update_deposits(
                 tracing::warn!(
                     %bitcoin_txid,
                     bitcoin_tx_output_index,
+                    correlation_id = correlation_id.as_deref(),
                     "failed to update deposit. Deposit not found in the database"
                 );
                 updated_deposits.push((
@@ -561,6 +566,7 @@ async fn update_deposits(
                 tracing::warn!(
                     %bitcoin_txid,
                     bitcoin_tx_output_index,
+                    correlation_id = correlation_id.as_deref(),
                     "failed to update deposit. Such type of update is not allowed for the caller"
                 );
                 updated_deposits.push((
@@ -577,6 +583,7 @@ async fn update_deposits(
                 tracing::error!(
                     %bitcoin_txid,
                     bitcoin_tx_output_index,
+                    correlation_id = correlation_id.as_deref(),
                     %error,
                     "failed to update deposit"
                 );
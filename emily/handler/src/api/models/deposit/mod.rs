@@ -59,6 +59,11 @@ pub struct Deposit {
     /// Transaction ID of the transaction that replaced this one via RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// An identifier that the creator of the deposit chose for tracing it
+    /// across systems. It is passed along in the status updates of the
+    /// deposit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Deposit parameters.
@@ -124,6 +129,11 @@ pub struct DepositInfo {
     pub reclaim_script: String,
     /// Raw deposit script binary in hex.
     pub deposit_script: String,
+    /// An identifier that the creator of the deposit chose for tracing it
+    /// across systems. It is passed along in the status updates of the
+    /// deposit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Create a DepositInfo, which has a subset of the data within a Deposit, from a Deposit.
@@ -139,6 +149,7 @@ impl From<Deposit> for DepositInfo {
             status: deposit.status,
             reclaim_script: deposit.reclaim_script,
             deposit_script: deposit.deposit_script,
+            correlation_id: deposit.correlation_id,
         }
     }
 }
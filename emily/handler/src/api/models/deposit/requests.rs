@@ -59,8 +59,16 @@ pub struct CreateDepositRequestBody {
     pub deposit_script: String,
     /// The raw transaction hex.
     pub transaction_hex: String,
+    /// An identifier for tracing the deposit across systems, which is
+    /// passed along in the status updates of the deposit. It must be at
+    /// most 128 printable ASCII characters without whitespace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
+/// The maximum length of the correlation ID of a deposit.
+const MAX_CORRELATION_ID_LENGTH: usize = 128;
+
 fn parse_with_custom_error<T, F, E>(input: &str, parser: F, error_msg: &str) -> Result<T, Error>
 where
     F: Fn(&str) -> Result<T, E>,
@@ -72,6 +80,18 @@ impl CreateDepositRequestBody {
     /// Validates that the deposit request is valid.
     /// This includes validating the request fields and if their content matches the transaction
     pub fn validate(&self, is_mainnet: bool) -> Result<DepositInfo, Error> {
+        if let Some(correlation_id) = &self.correlation_id {
+            let is_valid = !correlation_id.is_empty()
+                && correlation_id.len() <= MAX_CORRELATION_ID_LENGTH
+                && correlation_id.bytes().all(|byte| byte.is_ascii_graphic());
+            if !is_valid {
+                return Err(Error::HttpRequest(
+                    StatusCode::BAD_REQUEST,
+                    "invalid correlation id".to_string(),
+                ));
+            }
+        }
+
         let deposit_req = CreateDepositRequest {
             outpoint: OutPoint {
                 txid: parse_with_custom_error(
@@ -133,6 +153,10 @@ pub struct DepositUpdate {
     /// Transaction ID of the transaction that replaced this one via RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// The correlation ID of the deposit, passed along by the sender of
+    /// the update for tracing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 impl DepositUpdate {
@@ -187,7 +211,11 @@ impl DepositUpdate {
             stacks_block_hash: chainstate.stacks_block_hash,
         };
         // Return the validated update.
-        Ok(ValidatedDepositUpdate { key, event })
+        Ok(ValidatedDepositUpdate {
+            key,
+            event,
+            correlation_id: self.correlation_id,
+        })
     }
 }
 
@@ -319,4 +347,19 @@ mod tests {
             format!("HTTP request failed with status code 400 Bad Request: {expected_error}")
         );
     }
+
+    #[test_case(Some("checkout-8f14e45f:deposit/1"), true; "valid")]
+    #[test_case(None, true; "missing")]
+    #[test_case(Some(""), false; "empty")]
+    #[test_case(Some("has whitespace"), false; "whitespace")]
+    #[test_case(Some("\u{e9}t\u{e9}"), false; "non_ascii")]
+    #[tokio::test]
+    async fn test_deposit_validate_correlation_id(correlation_id: Option<&str>, is_valid: bool) {
+        let mut deposit_request = parse_request(CREATE_DEPOSIT_VALID);
+        deposit_request.correlation_id = correlation_id.map(ToString::to_string);
+        assert_eq!(deposit_request.validate(true).is_ok(), is_valid);
+
+        deposit_request.correlation_id = Some("a".repeat(MAX_CORRELATION_ID_LENGTH + 1));
+        assert!(deposit_request.validate(true).is_err());
+    }
 }
@@ -204,8 +204,24 @@ pub async fn pull_and_update_deposit_with_retry(
         {
             return Ok(deposit_entry);
         }
-        let is_valid_untrusted_status_update = update.event.status == DepositStatusEntry::Accepted
-            && deposit_entry.status == DepositStatus::Pending;
+        // Likewise for failed deposits. The signers report deposits whose
+        // UTXO can no longer be swept as failed, and someone else may have
+        // failed them already.
+        if update.event.status == DepositStatusEntry::Failed
+            && deposit_entry.status == DepositStatus::Failed
+        {
+            return Ok(deposit_entry);
+        }
+        // Signers may accept pending deposits, and fail deposits that they
+        // have not fulfilled.
+        let is_valid_untrusted_status_update = match update.event.status {
+            DepositStatusEntry::Accepted => deposit_entry.status == DepositStatus::Pending,
+            DepositStatusEntry::Failed => matches!(
+                deposit_entry.status,
+                DepositStatus::Pending | DepositStatus::Accepted
+            ),
+            _ => false,
+        };
         if !is_trusted_key && !is_valid_untrusted_status_update {
             return Err(Error::Forbidden);
         }
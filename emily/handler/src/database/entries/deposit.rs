@@ -77,6 +77,10 @@ pub struct DepositEntry {
     /// Transaction ID of transaction which replaced this transaction during an RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// The identifier that the creator of the deposit chose for tracing it
+    /// across systems, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Implements versioned entry trait for the deposit entry.
@@ -277,6 +281,7 @@ impl TryFrom<DepositEntry> for Deposit {
             deposit_script: deposit_entry.deposit_script,
             fulfillment,
             replaced_by_tx,
+            correlation_id: deposit_entry.correlation_id,
         })
     }
 }
@@ -386,6 +391,10 @@ pub struct DepositInfoEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// The identifier that the creator of the deposit chose for tracing it
+    /// across systems, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -437,6 +446,7 @@ impl From<DepositInfoEntry> for DepositInfo {
             status: deposit_info_entry.key.status,
             reclaim_script: deposit_info_entry.reclaim_script,
             deposit_script: deposit_info_entry.deposit_script,
+            correlation_id: deposit_info_entry.correlation_id,
         }
     }
 }
@@ -490,6 +500,10 @@ pub struct DepositInfoByRecipientEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// The identifier that the creator of the deposit chose for tracing it
+    /// across systems, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -542,6 +556,7 @@ impl From<DepositInfoByRecipientEntry> for DepositInfo {
             status: deposit_info_entry.status,
             reclaim_script: deposit_info_entry.reclaim_script,
             deposit_script: deposit_info_entry.deposit_script,
+            correlation_id: deposit_info_entry.correlation_id,
         }
     }
 }
@@ -595,6 +610,10 @@ pub struct DepositInfoByReclaimPubkeysEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// The identifier that the creator of the deposit chose for tracing it
+    /// across systems, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -647,6 +666,7 @@ impl From<DepositInfoByReclaimPubkeysEntry> for DepositInfo {
             status: deposit_info_entry.status,
             reclaim_script: deposit_info_entry.reclaim_script,
             deposit_script: deposit_info_entry.deposit_script,
+            correlation_id: deposit_info_entry.correlation_id,
         }
     }
 }
@@ -672,6 +692,9 @@ pub struct ValidatedDepositUpdate {
     pub key: DepositEntryKey,
     /// Deposit event.
     pub event: DepositEvent,
+    /// The correlation ID of the deposit that the sender of the update
+    /// passed along, if any. It is only used for tracing.
+    pub correlation_id: Option<String>,
 }
 
 impl ValidatedDepositUpdate {
@@ -753,11 +776,13 @@ mod tests {
             history: vec![pending, accepted.clone()],
             reclaim_pubkeys_hash: None,
             replaced_by_tx: None,
+            correlation_id: None,
         };
 
         let update = ValidatedDepositUpdate {
             key: Default::default(),
             event: accepted,
+            correlation_id: None,
         };
 
         assert!(update.is_unnecessary(&deposit));
@@ -794,11 +819,13 @@ mod tests {
             history: vec![pending.clone()],
             reclaim_pubkeys_hash: None,
             replaced_by_tx: None,
+            correlation_id: None,
         };
 
         let update = ValidatedDepositUpdate {
             key: Default::default(),
             event: accepted,
+            correlation_id: None,
         };
 
         assert!(!update.is_unnecessary(&deposit));
@@ -853,6 +880,7 @@ mod tests {
             history: vec![pending.clone(), accepted.clone(), confirmed.clone()],
             reclaim_pubkeys_hash: Some(hex::encode([1u8; 32])),
             replaced_by_tx: None,
+            correlation_id: None,
         };
 
         // Ensure the deposit is valid.
@@ -162,6 +162,7 @@ async fn create_and_get_deposit_happy_path() {
         reclaim_script: reclaim_script.clone(),
         deposit_script: deposit_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     let expected_deposit = Deposit {
@@ -181,6 +182,7 @@ async fn create_and_get_deposit_happy_path() {
         status: testing_emily_client::models::DepositStatus::Pending,
         status_message: INITIAL_DEPOSIT_STATUS_MESSAGE.into(),
         replaced_by_tx: None,
+        correlation_id: None,
     };
 
     // Act.
@@ -204,6 +206,78 @@ async fn create_and_get_deposit_happy_path() {
     assert_eq!(expected_deposit, gotten_deposit);
 }
 
+#[tokio::test]
+async fn correlation_id_is_kept_across_updates() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+    let correlation_id = "checkout-8f14e45f".to_string();
+
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+
+    let request = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        transaction_hex,
+        correlation_id: Some(Some(correlation_id.clone())),
+    };
+
+    // Act.
+    // ----
+    let created_deposit = apis::deposit_api::create_deposit(&configuration, request)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    let pending_deposits =
+        apis::deposit_api::get_deposits(&configuration, DepositStatus::Pending, None, None)
+            .await
+            .expect("Received an error after making a valid get deposits api call.");
+
+    apis::deposit_api::update_deposits_signer(
+        &configuration,
+        UpdateDepositsRequestBody {
+            deposits: vec![DepositUpdate {
+                bitcoin_tx_output_index,
+                bitcoin_txid: bitcoin_txid.clone(),
+                fulfillment: None,
+                status: DepositStatus::Accepted,
+                status_message: "accepted".into(),
+                replaced_by_tx: None,
+                correlation_id: Some(Some(correlation_id.clone())),
+            }],
+        },
+    )
+    .await
+    .expect("Received an error after making a valid update deposit request api call.");
+
+    let gotten_deposit = apis::deposit_api::get_deposit(
+        &configuration,
+        &bitcoin_txid,
+        &bitcoin_tx_output_index.to_string(),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit request api call.");
+
+    // Assert.
+    // -------
+    let expected = Some(Some(correlation_id));
+    assert_eq!(created_deposit.correlation_id, expected);
+    assert_eq!(pending_deposits.deposits.len(), 1);
+    assert_eq!(pending_deposits.deposits[0].correlation_id, expected);
+    assert_eq!(gotten_deposit.status, DepositStatus::Accepted);
+    assert_eq!(gotten_deposit.correlation_id, expected);
+}
+
 #[tokio::test]
 async fn wipe_databases_test() {
     let configuration = clean_setup().await;
@@ -230,6 +304,7 @@ async fn wipe_databases_test() {
         reclaim_script,
         deposit_script,
         bitcoin_txid: bitcoin_txid.clone(),
+        correlation_id: None,
     };
 
     // Act.
@@ -289,6 +364,7 @@ async fn get_deposits_for_transaction() {
             deposit_script: deposit_script.clone(),
             reclaim_script: reclaim_script.clone(),
             transaction_hex: transaction_hex.clone(),
+            correlation_id: None,
         };
         create_requests.push(request);
 
@@ -309,6 +385,7 @@ async fn get_deposits_for_transaction() {
             status: testing_emily_client::models::DepositStatus::Pending,
             status_message: INITIAL_DEPOSIT_STATUS_MESSAGE.into(),
             replaced_by_tx: None,
+            correlation_id: None,
         };
         expected_deposits.push(expected_deposit);
     }
@@ -372,6 +449,7 @@ async fn get_deposits() {
                 deposit_script: deposit_script.clone(),
                 reclaim_script: reclaim_script.clone(),
                 transaction_hex: transaction_hex.clone(),
+                correlation_id: None,
             };
             create_requests.push(request);
 
@@ -385,6 +463,7 @@ async fn get_deposits() {
                 status: testing_emily_client::models::DepositStatus::Pending,
                 reclaim_script: reclaim_script.clone(),
                 deposit_script: deposit_script.clone(),
+                correlation_id: None,
             };
             expected_deposit_infos.push(expected_deposit_info);
         }
@@ -483,6 +562,7 @@ async fn get_deposits_for_recipient() {
                 deposit_script: deposit_script.clone(),
                 reclaim_script: reclaim_script.clone(),
                 transaction_hex: transaction_hex.clone(),
+                correlation_id: None,
             };
             create_requests.push(request);
             // Store the expected deposit info that should come from it.
@@ -496,6 +576,7 @@ async fn get_deposits_for_recipient() {
                 status: testing_emily_client::models::DepositStatus::Pending,
                 reclaim_script,
                 deposit_script,
+                correlation_id: None,
             };
             expected_deposit_infos.push(expected_deposit_info);
         }
@@ -624,6 +705,7 @@ async fn get_deposits_for_reclaim_pubkeys() {
                     deposit_script: deposit_script.clone(),
                     reclaim_script: reclaim_script.clone(),
                     transaction_hex: transaction_hex.clone(),
+                    correlation_id: None,
                 };
                 create_requests.push(request);
                 // Store the expected deposit info that should come from it.
@@ -637,6 +719,7 @@ async fn get_deposits_for_reclaim_pubkeys() {
                     status: testing_emily_client::models::DepositStatus::Pending,
                     reclaim_script,
                     deposit_script,
+                    correlation_id: None,
                 };
                 expected_deposit_infos.push(expected_deposit_info);
             }
@@ -747,6 +830,7 @@ async fn update_deposits() {
                 deposit_script: deposit_script.clone(),
                 reclaim_script: reclaim_script.clone(),
                 transaction_hex: transaction_hex.clone(),
+                correlation_id: None,
             };
             create_requests.push(create_request);
 
@@ -757,6 +841,7 @@ async fn update_deposits() {
                 status: update_status,
                 status_message: update_status_message.into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             };
             deposit_updates.push(deposit_update);
 
@@ -777,6 +862,7 @@ async fn update_deposits() {
                 status: update_status,
                 status_message: update_status_message.into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             };
             expected_deposits.push(expected_deposit);
         }
@@ -840,6 +926,7 @@ async fn create_deposit_handles_duplicates(status: DepositStatus) {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     apis::deposit_api::create_deposit(&configuration, create_deposit_body.clone())
@@ -884,6 +971,7 @@ async fn create_deposit_handles_duplicates(status: DepositStatus) {
                 status,
                 status_message: "foo".into(),
                 replaced_by_tx,
+                correlation_id: None,
             }],
         },
     )
@@ -921,7 +1009,10 @@ async fn create_deposit_handles_duplicates(status: DepositStatus) {
 #[test_case(DepositStatus::Pending, DepositStatus::Pending, true; "pending_to_pending")]
 #[test_case(DepositStatus::Pending, DepositStatus::Accepted, false; "pending_to_accepted")]
 #[test_case(DepositStatus::Pending, DepositStatus::Confirmed, true; "pending_to_confirmed")]
-#[test_case(DepositStatus::Pending, DepositStatus::Failed, true; "pending_to_failed")]
+#[test_case(DepositStatus::Pending, DepositStatus::Failed, false; "pending_to_failed")]
+#[test_case(DepositStatus::Accepted, DepositStatus::Failed, false; "accepted_to_failed")]
+#[test_case(DepositStatus::Failed, DepositStatus::Failed, false; "failed_to_failed")]
+#[test_case(DepositStatus::Confirmed, DepositStatus::Failed, true; "confirmed_to_failed")]
 #[test_case(DepositStatus::Accepted, DepositStatus::Pending, true; "accepted_to_pending")]
 #[test_case(DepositStatus::Failed, DepositStatus::Pending, true; "failed_to_pending")]
 #[test_case(DepositStatus::Confirmed, DepositStatus::Pending, true; "confirmed_to_pending")]
@@ -969,6 +1060,7 @@ async fn update_deposits_is_forbidden_for_signer(
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Update the deposit status with the privileged configuration.
@@ -1007,6 +1099,7 @@ async fn update_deposits_is_forbidden_for_signer(
                     status: previous_status,
                     status_message: "foo".into(),
                     replaced_by_tx,
+                    correlation_id: None,
                 }],
             },
         )
@@ -1042,6 +1135,7 @@ async fn update_deposits_is_forbidden_for_signer(
                 status: new_status,
                 status_message: "foo".into(),
                 replaced_by_tx,
+                correlation_id: None,
             }],
         },
     )
@@ -1128,6 +1222,7 @@ async fn update_deposits_is_not_forbidden_for_sidecar(
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Update the deposit status with the privileged configuration.
@@ -1165,6 +1260,7 @@ async fn update_deposits_is_not_forbidden_for_sidecar(
                     status: previous_status,
                     status_message: "foo".into(),
                     replaced_by_tx,
+                    correlation_id: None,
                 }],
             },
         )
@@ -1200,6 +1296,7 @@ async fn update_deposits_is_not_forbidden_for_sidecar(
                 status: new_status,
                 status_message: "foo".into(),
                 replaced_by_tx,
+                correlation_id: None,
             }],
         },
     )
@@ -1250,6 +1347,7 @@ async fn rbf_status_saved_successfully() {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Update the deposit status with the privileged configuration.
@@ -1266,6 +1364,7 @@ async fn rbf_status_saved_successfully() {
             status: DepositStatus::Rbf,
             status_message: "RBF initiated".into(),
             replaced_by_tx: Some(Some("replaced_by_txid".to_string())),
+            correlation_id: None,
         }],
     };
 
@@ -1332,6 +1431,7 @@ async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositS
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Update the deposit status with the privileged configuration.
@@ -1348,6 +1448,7 @@ async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositS
             status,
             status_message: "dummy".into(),
             replaced_by_tx: Some(Some("replaced_by_txid".to_string())),
+            correlation_id: None,
         }],
     };
 
@@ -1411,6 +1512,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     let DepositTxnData {
@@ -1428,6 +1530,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Sanity check that the two deposits are different.
@@ -1463,6 +1566,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
             status: DepositStatus::Accepted,
             status_message: "First update".into(),
             replaced_by_tx: None,
+            correlation_id: None,
         }],
     };
     let response = apis::deposit_api::update_deposits_signer(
@@ -1506,6 +1610,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             },
             DepositUpdate {
                 bitcoin_tx_output_index,
@@ -1514,6 +1619,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             },
         ],
     };
@@ -1568,6 +1674,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     let DepositTxnData {
@@ -1585,6 +1692,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
         deposit_script: deposit_script.clone(),
         reclaim_script: reclaim_script.clone(),
         transaction_hex: transaction_hex.clone(),
+        correlation_id: None,
     };
 
     // Sanity check that the two deposits are different.
@@ -1620,6 +1728,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             },
             DepositUpdate {
                 bitcoin_tx_output_index,
@@ -1628,6 +1737,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
                 replaced_by_tx: None,
+                correlation_id: None,
             },
         ],
     };
@@ -15,13 +15,12 @@ use bitcoin::taproot::LeafVersion;
 use bitcoin::taproot::NodeInfo;
 use bitcoin::taproot::TaprootSpendInfo;
 use clarity::codec::StacksMessageCodec as _;
-use clarity::types::chainstate::StacksAddress;
 use clarity::vm::types::PrincipalData;
-use clarity::vm::types::QualifiedContractIdentifier;
 use secp256k1::SECP256K1;
 use stacks_common::types::chainstate::STACKS_ADDRESS_ENCODED_SIZE;
 
 use crate::error::Error;
+use crate::principals;
 
 /// This is the length of the fixed portion of the deposit script, which
 /// is:
@@ -112,6 +111,144 @@ pub struct DepositInfo {
     pub recipient: PrincipalData,
     /// The relative lock time in the reclaim script.
     pub lock_time: LockTime,
+    /// The version of the script template that the deposit and reclaim
+    /// scripts follow.
+    pub script_version: DepositScriptVersion,
+}
+
+/// The versions of the deposit and reclaim script templates that can be
+/// decoded.
+///
+/// New templates, say ones with alternative reclaim conditions, get a new
+/// variant here. Signers only accept the versions that they have enabled,
+/// see [`DepositScriptDecoders`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepositScriptVersion {
+    /// The original template. The deposit script is
+    /// `<max-fee || recipient> OP_DROP <signers-public-key> OP_CHECKSIG`
+    /// and the reclaim script is `<lock-time> OP_CSV <user-script>`.
+    V1,
+}
+
+impl DepositScriptVersion {
+    /// Decode the deposit and reclaim scripts using this version's
+    /// template.
+    ///
+    /// This fails unless both scripts follow the template exactly, so
+    /// that scripts that decode to the same inputs are byte for byte the
+    /// same.
+    pub fn decode(
+        self,
+        deposit_script: &ScriptBuf,
+        reclaim_script: &ScriptBuf,
+    ) -> Result<DecodedDepositScripts, Error> {
+        match self {
+            DepositScriptVersion::V1 => {
+                let deposit = DepositScriptInputs::parse(deposit_script)?;
+                let reclaim = ReclaimScriptInputs::parse(reclaim_script)?;
+
+                if &deposit.deposit_script() != deposit_script {
+                    return Err(Error::InvalidDepositScript);
+                }
+                if &reclaim.reclaim_script() != reclaim_script {
+                    return Err(Error::InvalidReclaimScript);
+                }
+
+                Ok(DecodedDepositScripts {
+                    version: self,
+                    signers_public_key: deposit.signers_public_key,
+                    recipient: deposit.recipient,
+                    max_fee: deposit.max_fee,
+                    lock_time: reclaim.lock_time,
+                })
+            }
+        }
+    }
+}
+
+/// The inputs of a deposit, as decoded from its deposit and reclaim
+/// scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedDepositScripts {
+    /// The template that the scripts follow.
+    pub version: DepositScriptVersion,
+    /// The public key used in the deposit script.
+    pub signers_public_key: XOnlyPublicKey,
+    /// The stacks address to deposit the sBTC to.
+    pub recipient: PrincipalData,
+    /// The max fee amount to use for the BTC deposit transaction.
+    pub max_fee: u64,
+    /// The relative lock time in the reclaim script.
+    pub lock_time: LockTime,
+}
+
+/// The registry of deposit script templates that are accepted when
+/// validating deposits.
+///
+/// The templates are tried in order and the first one that decodes the
+/// scripts wins. The default registry only has [`DepositScriptVersion::V1`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositScriptDecoders {
+    versions: Vec<DepositScriptVersion>,
+}
+
+impl Default for DepositScriptDecoders {
+    fn default() -> Self {
+        Self {
+            versions: vec![DepositScriptVersion::V1],
+        }
+    }
+}
+
+impl DepositScriptDecoders {
+    /// Create a registry that accepts the given versions, tried in the
+    /// given order. Repeated versions are ignored.
+    pub fn new<I>(versions: I) -> Self
+    where
+        I: IntoIterator<Item = DepositScriptVersion>,
+    {
+        let mut unique = Vec::new();
+        for version in versions {
+            if !unique.contains(&version) {
+                unique.push(version);
+            }
+        }
+        Self { versions: unique }
+    }
+
+    /// The versions accepted by this registry, in the order that they are
+    /// tried.
+    pub fn versions(&self) -> &[DepositScriptVersion] {
+        &self.versions
+    }
+
+    /// Whether the given version is accepted by this registry.
+    pub fn contains(&self, version: DepositScriptVersion) -> bool {
+        self.versions.contains(&version)
+    }
+
+    /// Decode the deposit and reclaim scripts with the first accepted
+    /// version whose template they follow.
+    ///
+    /// If none of them do then the error from the first version is
+    /// returned, since that is usually the most informative one.
+    pub fn decode(
+        &self,
+        deposit_script: &ScriptBuf,
+        reclaim_script: &ScriptBuf,
+    ) -> Result<DecodedDepositScripts, Error> {
+        let mut first_error = None;
+        for version in self.versions.iter() {
+            match version.decode(deposit_script, reclaim_script) {
+                Ok(decoded) => return Ok(decoded),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(Error::UnsupportedDepositScript))
+    }
 }
 
 impl CreateDepositRequest {
@@ -127,7 +264,23 @@ impl CreateDepositRequest {
     ///   ScriptPubKey.
     /// * That the Stacks network for the recipient address matches the one
     ///   given as input to this function.
+    ///
+    /// Only the original deposit script template is accepted, use
+    /// [`CreateDepositRequest::validate_tx_with`] to accept others.
     pub fn validate_tx(&self, tx: &Transaction, is_mainnet: bool) -> Result<DepositInfo, Error> {
+        self.validate_tx_with(tx, is_mainnet, &DepositScriptDecoders::default())
+    }
+
+    /// Validate this deposit request, accepting deposit and reclaim
+    /// scripts that follow any of the templates in the given registry.
+    ///
+    /// The checks are the same as in [`CreateDepositRequest::validate_tx`].
+    pub fn validate_tx_with(
+        &self,
+        tx: &Transaction,
+        is_mainnet: bool,
+        decoders: &DepositScriptDecoders,
+    ) -> Result<DepositInfo, Error> {
         if tx.compute_txid() != self.outpoint.txid {
             // The expectation is that the transaction was fetched from the
             // blockchain using the txid, so in practice this should never
@@ -142,21 +295,13 @@ impl CreateDepositRequest {
             .tx_out(self.outpoint.vout as usize)
             .map_err(|err| Error::OutpointIndex(err, self.outpoint))?;
         // Validate that the deposit and reclaim scripts in the request
-        // match the expected formats for deposit transactions.
-        let deposit = DepositScriptInputs::parse(&self.deposit_script)?;
-        let reclaim = ReclaimScriptInputs::parse(&self.reclaim_script)?;
+        // match one of the accepted formats for deposit transactions.
+        let decoded = decoders.decode(&self.deposit_script, &self.reclaim_script)?;
         // Okay, the deposit and reclaim scripts are valid. Now make sure
         // that the ScriptPubKey in the transaction matches the one implied
         // by the given scripts. So now create the expected ScriptPubKey.
-        let deposit_script = deposit.deposit_script();
-        let reclaim_script = reclaim.reclaim_script();
-
-        if deposit_script != self.deposit_script {
-            return Err(Error::InvalidDepositScript);
-        }
-        if reclaim_script != self.reclaim_script {
-            return Err(Error::InvalidReclaimScript);
-        }
+        let deposit_script = self.deposit_script.clone();
+        let reclaim_script = self.reclaim_script.clone();
 
         let expected_script_pubkey =
             to_script_pubkey(deposit_script.clone(), reclaim_script.clone());
@@ -167,32 +312,22 @@ impl CreateDepositRequest {
         }
 
         // Check that the recipient network matches what we expect
-        if principal_is_mainnet(deposit.recipient.clone()) != is_mainnet {
-            return Err(Error::RecipientNetworkMismatch(deposit.recipient));
-        }
+        principals::validate_recipient(&decoded.recipient, is_mainnet)?;
 
         Ok(DepositInfo {
-            max_fee: deposit.max_fee,
+            max_fee: decoded.max_fee,
             deposit_script,
             reclaim_script,
-            signers_public_key: deposit.signers_public_key,
-            recipient: deposit.recipient,
-            lock_time: reclaim.lock_time,
+            signers_public_key: decoded.signers_public_key,
+            recipient: decoded.recipient,
+            lock_time: decoded.lock_time,
             amount: tx_out.value.to_sat(),
             outpoint: self.outpoint,
+            script_version: decoded.version,
         })
     }
 }
 
-/// Return whether the given principal address is a mainnet address.
-fn principal_is_mainnet(principal: PrincipalData) -> bool {
-    let standard_address = match principal {
-        PrincipalData::Contract(QualifiedContractIdentifier { issuer, .. }) => issuer,
-        PrincipalData::Standard(issuer) => issuer,
-    };
-    StacksAddress::from(standard_address).is_mainnet()
-}
-
 /// Construct the expected taproot info for a deposit UTXO on the given
 /// the deposit and reclaim scripts.
 pub fn to_taproot(deposit_script: ScriptBuf, reclaim_script: ScriptBuf) -> TaprootSpendInfo {
@@ -981,6 +1116,34 @@ mod tests {
         assert!(matches!(error, Error::InvalidReclaimScript));
     }
 
+    #[test]
+    fn deposit_scripts_are_decoded_by_the_accepted_versions() {
+        let setup: TxSetup = testing::deposits::tx_setup(150, 15000, &[500_000]);
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            reclaim_script: setup.reclaims.first().unwrap().reclaim_script(),
+            deposit_script: setup.deposits.first().unwrap().deposit_script(),
+        };
+
+        let decoders = DepositScriptDecoders::default();
+        assert_eq!(decoders.versions(), &[DepositScriptVersion::V1]);
+        let parsed = request
+            .validate_tx_with(&setup.tx, false, &decoders)
+            .unwrap();
+        assert_eq!(parsed.script_version, DepositScriptVersion::V1);
+
+        // Repeated versions are only tried once.
+        let decoders = DepositScriptDecoders::new([DepositScriptVersion::V1; 2]);
+        assert_eq!(decoders.versions(), &[DepositScriptVersion::V1]);
+
+        // Without any accepted versions nothing can be decoded.
+        let decoders = DepositScriptDecoders::new([]);
+        let error = request
+            .validate_tx_with(&setup.tx, false, &decoders)
+            .unwrap_err();
+        assert!(matches!(error, Error::UnsupportedDepositScript));
+    }
+
     #[test]
     fn unspendable_taproot_key_no_panic() {
         // The following function calls unwrap() when called the first
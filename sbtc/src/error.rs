@@ -29,6 +29,10 @@ pub enum Error {
     /// The reclaim script was invalid.
     #[error("the reclaim script format was invalid")]
     InvalidReclaimScript,
+    /// No deposit script versions are accepted, so no deposit scripts
+    /// can be decoded.
+    #[error("no deposit script versions are accepted")]
+    UnsupportedDepositScript,
     /// The reclaim script lock time was invalid
     #[error("reclaim script lock time was either too large or non-minimal: {0}")]
     ScriptNum(#[source] bitcoin::script::Error),
@@ -39,6 +43,9 @@ pub enum Error {
     /// minimal push rule.
     #[error("deposit script did not follow the minimal push rule")]
     NonMinimalPushDepositScript,
+    /// The string was not a valid stacks principal.
+    #[error("invalid stacks principal: {0}")]
+    InvalidPrincipal(String),
     /// Could not parse the Stacks principal address.
     #[error("could not parse the stacks principal address: {0}")]
     ParseStacksAddress(#[source] stacks_common::codec::Error),
@@ -124,7 +124,7 @@ pub enum EventError {
 }
 
 /// The print events emitted by the sbtc-registry clarity smart contract.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum RegistryEvent {
     /// For the `completed-deposit` topic
     CompletedDeposit(CompletedDepositEvent),
@@ -10,6 +10,7 @@ pub mod error;
 pub mod events;
 pub mod idpack;
 pub mod leb128;
+pub mod principals;
 
 #[cfg(feature = "webhooks")]
 pub mod webhooks;
@@ -0,0 +1,83 @@
+//! Validation of the stacks principals that receive sBTC.
+//!
+//! The recipient of a deposit is a stacks principal that is encoded in the
+//! deposit script. The signers only accept a deposit when its recipient is
+//! an address for the network that they run on, which they tell from the
+//! version byte of the address. For a contract principal, that is the
+//! version byte of the address of the contract's deployer.
+//!
+//! The signers validate deposits with these functions, so Emily and
+//! wallets that use them accept exactly the recipients that the signers
+//! accept.
+
+use clarity::types::chainstate::StacksAddress;
+use clarity::vm::types::PrincipalData;
+use clarity::vm::types::QualifiedContractIdentifier;
+
+use crate::error::Error;
+
+/// Return whether the given principal is a mainnet principal.
+///
+/// A principal is a mainnet principal when the version byte of its
+/// address, or of the address of the deployer of a contract principal, is
+/// one of the mainnet address versions. Any other version byte is treated
+/// as a testnet version.
+pub fn is_mainnet(principal: &PrincipalData) -> bool {
+    let standard_address = match principal {
+        PrincipalData::Contract(QualifiedContractIdentifier { issuer, .. }) => issuer,
+        PrincipalData::Standard(issuer) => issuer,
+    };
+    StacksAddress::from(standard_address.clone()).is_mainnet()
+}
+
+/// Check that the given recipient of sBTC is a principal for the expected
+/// network.
+pub fn validate_recipient(recipient: &PrincipalData, is_mainnet: bool) -> Result<(), Error> {
+    if self::is_mainnet(recipient) != is_mainnet {
+        return Err(Error::RecipientNetworkMismatch(recipient.clone()));
+    }
+    Ok(())
+}
+
+/// Parse a recipient of sBTC, either a standard principal like
+/// `SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE` or a contract principal
+/// like `SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.my-contract`, and check
+/// that it is a principal for the expected network.
+pub fn parse_recipient(recipient: &str, is_mainnet: bool) -> Result<PrincipalData, Error> {
+    let principal = PrincipalData::parse(recipient)
+        .map_err(|_| Error::InvalidPrincipal(recipient.to_string()))?;
+    validate_recipient(&principal, is_mainnet)?;
+    Ok(principal)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    const MAINNET_ADDRESS: &str = "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE";
+    const TESTNET_ADDRESS: &str = "ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y";
+
+    #[test_case(MAINNET_ADDRESS, true; "mainnet standard principal")]
+    #[test_case(TESTNET_ADDRESS, false; "testnet standard principal")]
+    #[test_case("SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.my-vault", true; "mainnet contract")]
+    #[test_case("ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y.my-vault", false; "testnet contract")]
+    fn recipients_must_match_the_network(recipient: &str, is_mainnet: bool) {
+        let principal = parse_recipient(recipient, is_mainnet).unwrap();
+        assert_eq!(super::is_mainnet(&principal), is_mainnet);
+        assert_eq!(principal.to_string(), recipient);
+
+        let error = parse_recipient(recipient, !is_mainnet).unwrap_err();
+        assert!(matches!(error, Error::RecipientNetworkMismatch(_)));
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTF"; "bad checksum")]
+    #[test_case("SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE."; "empty contract name")]
+    #[test_case("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"; "bitcoin address")]
+    fn malformed_recipients_are_rejected(recipient: &str) {
+        let error = parse_recipient(recipient, true).unwrap_err();
+        assert!(matches!(error, Error::InvalidPrincipal(_)));
+    }
+}
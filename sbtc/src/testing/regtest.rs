@@ -39,6 +39,10 @@ use bitcoincore_rpc::jsonrpc::error::RpcError;
 use secp256k1::SECP256K1;
 use std::sync::OnceLock;
 
+use crate::deposits;
+use crate::deposits::DepositScriptInputs;
+use crate::deposits::ReclaimScriptInputs;
+
 /// These must match the username and password in bitcoin.conf
 /// The username for RPC calls in bitcoin-core
 pub const BITCOIN_CORE_RPC_USERNAME: &str = "devnet";
@@ -345,6 +349,26 @@ impl Faucet {
     ///
     /// Note: only P2TR and P2WPKH addresses are supported.
     pub fn send_to(&self, amount: u64, address: &Address) -> OutPoint {
+        self.send_to_script(amount, &address.script_pubkey())
+    }
+
+    /// Send the specified amount to a deposit address made from the given
+    /// deposit and reclaim script inputs. The returned outpoint is the
+    /// deposit UTXO, which is confirmed once a block is mined.
+    pub fn fund_deposit(
+        &self,
+        amount: u64,
+        deposit: &DepositScriptInputs,
+        reclaim: &ReclaimScriptInputs,
+    ) -> OutPoint {
+        let script_pubkey =
+            deposits::to_script_pubkey(deposit.deposit_script(), reclaim.reclaim_script());
+        self.send_to_script(amount, &script_pubkey)
+    }
+
+    /// Send the specified amount to an output locked by the given
+    /// scriptPubKey.
+    pub fn send_to_script(&self, amount: u64, script_pubkey: &ScriptBuf) -> OutPoint {
         let fee = BITCOIN_CORE_FALLBACK_FEE.to_sat();
         let utxo = self.get_utxos(Some(amount + fee)).pop().unwrap();
 
@@ -360,7 +384,7 @@ impl Faucet {
             output: vec![
                 TxOut {
                     value: Amount::from_sat(amount),
-                    script_pubkey: address.script_pubkey(),
+                    script_pubkey: script_pubkey.clone(),
                 },
                 TxOut {
                     value: utxo.amount.unchecked_sub(Amount::from_sat(amount + fee)),
@@ -379,6 +403,49 @@ impl Faucet {
         self.rpc.send_raw_transaction(&tx).unwrap();
         OutPoint::new(tx.compute_txid(), 0)
     }
+
+    /// Invalidate the given number of blocks at the tip of the chain,
+    /// returning the hashes of the invalidated blocks, tip first.
+    ///
+    /// Bitcoin core never switches back to an invalidated block on its
+    /// own, so the next block mined becomes the new chain tip. The
+    /// transactions in the invalidated blocks are returned to the
+    /// mempool, unless they conflict with other transactions there.
+    pub fn invalidate_blocks(&self, num_blocks: u64) -> Vec<BlockHash> {
+        let tip_height = self.rpc.get_block_count().unwrap();
+        let invalidated: Vec<BlockHash> = (0..num_blocks)
+            .map(|depth| self.rpc.get_block_hash(tip_height - depth).unwrap())
+            .collect();
+
+        // Invalidating a block invalidates all of its descendants too.
+        if let Some(oldest) = invalidated.last() {
+            self.rpc.invalidate_block(oldest).unwrap();
+        }
+        invalidated
+    }
+
+    /// Mark the given block, and its ancestors, as valid again after they
+    /// were invalidated. Bitcoin core switches back to the block's chain
+    /// if it is the one with the most work.
+    pub fn reconsider_block(&self, block_hash: &BlockHash) {
+        self.rpc.reconsider_block(block_hash).unwrap();
+    }
+
+    /// Reorganize the chain by replacing the given number of blocks at
+    /// the tip with one more block than that, returning the hashes of
+    /// the orphaned blocks and of the blocks on the new chain, tip first
+    /// for the former and last for the latter.
+    ///
+    /// The transactions in the orphaned blocks end up in the blocks of
+    /// the new chain, unless they conflict with other transactions, so
+    /// use [`Faucet::invalidate_blocks`] directly in order to send
+    /// conflicting transactions before mining the new chain. Note that
+    /// the bitcoin-core node is shared by all tests.
+    pub fn reorg(&self, depth: u64) -> (Vec<BlockHash>, Vec<BlockHash>) {
+        let orphaned = self.invalidate_blocks(depth);
+        let new_chain = self.generate_blocks(depth + 1);
+        (orphaned, new_chain)
+    }
 }
 
 /// Extract the relevant aspects of a UTXO
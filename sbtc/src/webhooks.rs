@@ -25,11 +25,13 @@ use blockstack_lib::chainstate::stacks::StacksTransaction;
 use clarity::vm::types::QualifiedContractIdentifier;
 use clarity::vm::types::Value as ClarityValue;
 use serde::Deserialize;
+use stacks_common::bitvec::BitVec;
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::types::chainstate::BlockHeaderHash;
 use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::chainstate::StacksBlockId;
 use stacks_common::util::HexDeser;
+use stacks_common::util::secp256k1::MessageSignature;
 
 use crate::error::Error;
 
@@ -81,6 +83,41 @@ pub struct NewBlockEvent {
     pub parent_burn_block_height: u32,
     /// The timestamp in the header of the parent bitcoin burn block.
     pub parent_burn_block_timestamp: u64,
+    /// The signature of the miner over the block header. Only Nakamoto
+    /// blocks are signed by their miner, so this is [`None`] for blocks
+    /// from earlier epochs.
+    #[serde(default, deserialize_with = "deserialize_optional_hex")]
+    pub miner_signature: Option<MessageSignature>,
+    /// The height of the tenure that the stacks block belongs to. Only
+    /// newer stacks nodes send this, so it is [`None`] for older ones.
+    #[serde(default)]
+    pub tenure_height: Option<u64>,
+    /// Which of the signers of the reward cycle signed the stacks block,
+    /// in the order of the signers in the reward set. Only Nakamoto blocks
+    /// are signed by the signers, so this is [`None`] for blocks from
+    /// earlier epochs.
+    #[serde(default, deserialize_with = "deserialize_optional_webhook_codec")]
+    pub signer_bitvec: Option<BitVec<4000>>,
+}
+
+/// This struct represents the body of POST /new_burn_block events from a
+/// stacks node, which it sends whenever it processes a new bitcoin block.
+///
+/// # Note
+///
+/// This struct leaves out the reward recipients and reward slot holders
+/// that are included. For the full payload, see the source here:
+/// <https://github.com/stacks-network/stacks-core/blob/09c4b066e25104be8b066e8f7530ff0c6df4ccd5/testnet/stacks-node/src/event_dispatcher.rs>
+#[derive(Debug, Deserialize)]
+pub struct NewBurnBlockEvent {
+    /// The hash of the bitcoin block.
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub burn_block_hash: BurnchainHeaderHash,
+    /// The height of the bitcoin block.
+    pub burn_block_height: u64,
+    /// The total amount of BTC, in sats, that miners spent on block
+    /// commits in the bitcoin block.
+    pub burn_amount: u64,
 }
 
 /// This matches the json value that is defined in stacks-core[^1]. It
@@ -198,6 +235,23 @@ where
     <T as HexDeser>::try_from_hex(hex_str).map_err(serde::de::Error::custom)
 }
 
+/// This is for deserializing optional fields in webhooks that were
+/// serialized using the type's `T::to_hex` implementation. Missing fields,
+/// and fields that are `null`, are deserialized as [`None`].
+pub fn deserialize_optional_hex<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: HexDeser,
+{
+    let Some(hex_str) = <Option<String>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let hex_str = hex_str.trim_start_matches("0x");
+    <T as HexDeser>::try_from_hex(hex_str)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
 /// This is for deserializing fields in webhooks that were effectively
 /// serialized using [`StacksMessageCodec::consensus_serialize`].
 ///
@@ -218,6 +272,23 @@ where
     deserialize_codec(hex_str).map_err(serde::de::Error::custom)
 }
 
+/// This is for deserializing optional fields in webhooks that were
+/// serialized using [`StacksMessageCodec::consensus_serialize`]. Missing
+/// fields, and fields that are `null`, are deserialized as [`None`].
+pub fn deserialize_optional_webhook_codec<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: StacksMessageCodec,
+{
+    let Some(hex_str) = <Option<String>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let hex_str = hex_str.trim_start_matches("0x");
+    deserialize_codec(hex_str)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
 /// This is for deserializing stacks transactions in the raw_tx field.
 ///
 /// # Notes
@@ -357,5 +428,38 @@ mod tests {
         assert_eq!(event.block_height, 449);
         assert_eq!(event.block_hash, expected_block_hash);
         assert_eq!(event.transactions.first().unwrap().txid, expected_txid);
+
+        let expected_miner_signature = MessageSignature::from_hex(
+            "011e8135ba62a248ff78daf9e7ac9c2da2f6b8cf3b28cb1082d259db2f3a9c297816a667e09579065de2820866ca90b8eea4b43a3a2bfc350874cd11d28e251165",
+        )
+        .unwrap();
+        assert_eq!(event.miner_signature, Some(expected_miner_signature));
+
+        // The payload was captured from a node that does not send the
+        // tenure height, and all eight signers signed the block.
+        assert_eq!(event.tenure_height, None);
+        let signer_bitvec = event.signer_bitvec.unwrap();
+        assert_eq!(signer_bitvec.len(), 8);
+        assert!((0..8).all(|index| signer_bitvec.get(index) == Some(true)));
+    }
+
+    #[test]
+    fn test_new_burn_block_event_deserialization() {
+        let payload = r#"{
+            "burn_block_hash": "0x7d4db9d88dd86c31c75a351e4974940db55f6db77c9d49881dd0028946c661ac",
+            "burn_block_height": 159,
+            "reward_recipients": [],
+            "reward_slot_holders": [],
+            "burn_amount": 20000
+        }"#;
+        let event: NewBurnBlockEvent = serde_json::from_str(payload).unwrap();
+
+        let expected_block_hash = BurnchainHeaderHash::from_hex(
+            "7d4db9d88dd86c31c75a351e4974940db55f6db77c9d49881dd0028946c661ac",
+        )
+        .unwrap();
+        assert_eq!(event.burn_block_hash, expected_block_hash);
+        assert_eq!(event.burn_block_height, 159);
+        assert_eq!(event.burn_amount, 20000);
     }
 }
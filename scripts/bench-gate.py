@@ -0,0 +1,116 @@
+#!/usr/bin/env python3
+"""Compare the results of the signer benchmarks against recorded baselines.
+
+Criterion writes the estimates of the most recent run of each benchmark to
+`target/criterion/**/new/`. This script reads the mean time of each
+benchmark from there and either records them as the new baselines, or
+checks them against the recorded baselines and fails if any benchmark got
+slower than the allowed threshold.
+
+Usage:
+    bench-gate.py record [--baseline FILE]
+    bench-gate.py check [--baseline FILE] [--threshold FRACTION]
+
+Baselines are only comparable when recorded on the same machine as the
+run being checked, so record them on the machine that runs the checks. No
+baselines are committed to the repository, and `check` fails until they
+have been recorded, as well as for any benchmark that has no baseline.
+"""
+
+import argparse
+import json
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+CRITERION_DIR = ROOT / "target" / "criterion"
+DEFAULT_BASELINE = ROOT / "signer" / "benches" / "baselines" / "hot_paths.json"
+DEFAULT_THRESHOLD = 0.10
+
+
+def load_results(criterion_dir: Path) -> dict[str, float]:
+    """Return the mean time, in nanoseconds, of each benchmark in the most
+    recent run, keyed by the benchmark ID."""
+    results = {}
+    for benchmark in criterion_dir.glob("**/new/benchmark.json"):
+        estimates = benchmark.with_name("estimates.json")
+        full_id = json.loads(benchmark.read_text())["full_id"]
+        mean = json.loads(estimates.read_text())["mean"]["point_estimate"]
+        results[full_id] = mean
+    return results
+
+
+def record(results: dict[str, float], baseline: Path) -> int:
+    baseline.parent.mkdir(parents=True, exist_ok=True)
+    baseline.write_text(json.dumps(dict(sorted(results.items())), indent=2) + "\n")
+    print(f"recorded {len(results)} baselines in {baseline}")
+    return 0
+
+
+def fail(message: str) -> int:
+    print(f"error: {message}", file=sys.stderr)
+    return 1
+
+
+def check(results: dict[str, float], baseline: Path, threshold: float) -> int:
+    if not baseline.exists():
+        return fail(
+            f"no baselines at {baseline}; nothing to check against. Baselines are "
+            "machine specific and are not committed, record them on this machine "
+            "with `make bench-baseline` first"
+        )
+
+    baselines = json.loads(baseline.read_text())
+    regressions = []
+    unchecked = []
+    for full_id, mean in sorted(results.items()):
+        expected = baselines.get(full_id)
+        if expected is None:
+            print(f"{full_id}: {mean:.0f} ns (no baseline)")
+            unchecked.append(full_id)
+            continue
+
+        change = mean / expected - 1.0
+        print(f"{full_id}: {mean:.0f} ns, baseline {expected:.0f} ns ({change:+.1%})")
+        if change > threshold:
+            regressions.append(full_id)
+
+    for full_id in sorted(set(baselines) - set(results)):
+        print(f"{full_id}: missing from this run")
+
+    if regressions:
+        print(f"{len(regressions)} benchmarks regressed by more than {threshold:.0%}:")
+        for full_id in regressions:
+            print(f"  {full_id}")
+        return 1
+    if unchecked:
+        return fail(
+            f"{len(unchecked)} benchmarks have no baseline in {baseline}; "
+            "record them with `make bench-baseline`"
+        )
+    return 0
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("mode", choices=["record", "check"])
+    parser.add_argument("--baseline", type=Path, default=DEFAULT_BASELINE)
+    parser.add_argument(
+        "--threshold",
+        type=float,
+        default=DEFAULT_THRESHOLD,
+        help="the allowed slowdown, as a fraction of the baseline",
+    )
+    args = parser.parse_args()
+
+    results = load_results(CRITERION_DIR)
+    if not results:
+        return fail(f"no benchmark results in {CRITERION_DIR}; run `make bench` first")
+
+    if args.mode == "record":
+        return record(results, args.baseline)
+    return check(results, args.baseline, args.threshold)
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,346 @@
+//! Benchmarks for the hot paths of the signer.
+//!
+//! Run them with `make bench`. To guard against performance regressions,
+//! `make bench-check` compares the results with the baselines in
+//! `benches/baselines`, and `make bench-baseline` records new baselines.
+//! See `scripts/bench-gate.py` for how the comparison works.
+
+// The functions generated by `criterion_group!` do not have docs.
+#![allow(missing_docs)]
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use bitcoin::Amount;
+use bitcoin::CompressedPublicKey;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash as _;
+use bitvec::array::BitArray;
+use clarity::vm::types::PrincipalData;
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use fake::Fake as _;
+use rand::rngs::StdRng;
+use sbtc::deposits::DepositScriptInputs;
+use sbtc::events::RegistryEvent;
+use sbtc::events::TxInfo;
+use sbtc::webhooks::NewBlockEvent;
+use secp256k1::SECP256K1;
+use secp256k1::SecretKey;
+use secp256k1::XOnlyPublicKey;
+
+use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use signer::bitcoin::utxo::DepositRequest;
+use signer::bitcoin::utxo::SbtcRequests;
+use signer::bitcoin::utxo::SignerBtcState;
+use signer::bitcoin::utxo::SignerUtxo;
+use signer::bitcoin::utxo::WithdrawalRequest;
+use signer::context::SbtcLimits;
+use signer::storage::model;
+use signer::testing::get_rng;
+
+/// The webhooks with sbtc-registry events, as sent by a stacks node.
+const WEBHOOKS: [&str; 5] = [
+    include_str!("../tests/fixtures/completed-deposit-event.json"),
+    include_str!("../tests/fixtures/withdrawal-accept-event.json"),
+    include_str!("../tests/fixtures/withdrawal-create-event.json"),
+    include_str!("../tests/fixtures/withdrawal-reject-event.json"),
+    include_str!("../tests/fixtures/rotate-keys-event.json"),
+];
+
+/// The number of deposit and withdrawal requests in the sweep benchmarks.
+/// This is enough for the package to need more than one transaction.
+const NUM_REQUESTS: u64 = 40;
+
+/// The size of the webhook body in the webhook parsing benchmarks, which
+/// is close to the body limit of the `/new_block` endpoint.
+const LARGE_WEBHOOK_SIZE: usize = 8 * 1000 * 1000;
+
+/// The number of bytes currently allocated through [`PeakAllocator`].
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// The largest number of bytes allocated at once through [`PeakAllocator`]
+/// since the last call to [`peak_allocation`].
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// An allocator that keeps track of the peak memory use, so that the
+/// webhook parsing benchmarks can report it alongside their timings.
+struct PeakAllocator;
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Return the largest number of bytes that were allocated at once while
+/// running the given function, on top of what was already allocated.
+fn peak_allocation<T>(f: impl FnOnce() -> T) -> usize {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    black_box(f());
+    PEAK.load(Ordering::Relaxed) - baseline
+}
+
+/// Return the clarity values of the events in the fixture webhooks along
+/// with the transactions that emitted them.
+fn registry_event_values() -> Vec<(clarity::vm::Value, TxInfo)> {
+    WEBHOOKS
+        .iter()
+        .map(|body| serde_json::from_str::<NewBlockEvent>(body).unwrap())
+        .flat_map(|block| {
+            let block_id = block.index_block_hash;
+            block.events.into_iter().filter_map(move |event| {
+                let tx_info = TxInfo {
+                    txid: sbtc::events::StacksTxid(event.txid.0),
+                    block_id,
+                };
+                event.contract_event.map(|ev| (ev.value, tx_info))
+            })
+        })
+        .collect()
+}
+
+/// Return a webhook body of about [`LARGE_WEBHOOK_SIZE`] bytes, made by
+/// repeating the event of a fixture webhook.
+fn large_webhook() -> Vec<u8> {
+    let mut webhook: serde_json::Value = serde_json::from_str(WEBHOOKS[2]).unwrap();
+    let event = webhook["events"][0].clone();
+    let event_size = serde_json::to_vec(&event).unwrap().len();
+
+    let events = webhook["events"].as_array_mut().unwrap();
+    events.resize(LARGE_WEBHOOK_SIZE / event_size, event);
+    serde_json::to_vec(&webhook).unwrap()
+}
+
+/// Parse a webhook body the way the `/new_block` handler used to, by
+/// copying it into a `String` first.
+fn parse_webhook_from_string(body: &[u8]) -> NewBlockEvent {
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    serde_json::from_str(&body).unwrap()
+}
+
+/// Parse a webhook body the way the `/new_block` handler does, straight
+/// from the buffered bytes.
+fn parse_webhook_from_bytes(body: &[u8]) -> NewBlockEvent {
+    std::str::from_utf8(body).unwrap();
+    serde_json::from_slice(body).unwrap()
+}
+
+fn x_only_public_key(rng: &mut StdRng) -> XOnlyPublicKey {
+    SecretKey::new(rng).x_only_public_key(SECP256K1).0
+}
+
+fn outpoint(rng: &mut StdRng, vout: u32) -> OutPoint {
+    let txid = Txid::from_byte_array(fake::Faker.fake_with_rng(rng));
+    OutPoint { txid, vout }
+}
+
+fn deposit(rng: &mut StdRng, signers_public_key: XOnlyPublicKey) -> DepositRequest {
+    let recipient: model::StacksPrincipal = fake::Faker.fake_with_rng(rng);
+    let deposit_inputs = DepositScriptInputs {
+        signers_public_key,
+        max_fee: 30_000,
+        recipient: PrincipalData::from(recipient),
+    };
+
+    DepositRequest {
+        outpoint: outpoint(rng, 1),
+        max_fee: 30_000,
+        signer_bitmap: BitArray::ZERO,
+        amount: 1_000_000,
+        deposit_script: deposit_inputs.deposit_script(),
+        reclaim_script: ScriptBuf::new(),
+        reclaim_script_hash: Some(model::TaprootScriptHash::zeros()),
+        signers_public_key,
+    }
+}
+
+fn withdrawal(rng: &mut StdRng, request_id: u64) -> WithdrawalRequest {
+    let public_key = CompressedPublicKey(SecretKey::new(rng).public_key(SECP256K1));
+    WithdrawalRequest {
+        request_id,
+        txid: fake::Faker.fake_with_rng(rng),
+        block_hash: fake::Faker.fake_with_rng(rng),
+        amount: 500_000,
+        max_fee: 30_000,
+        script_pubkey: ScriptBuf::new_p2wpkh(&public_key.wpubkey_hash()).into(),
+        signer_bitmap: BitArray::ZERO,
+    }
+}
+
+/// Return pending requests that fill a package of sweep transactions.
+fn sbtc_requests() -> SbtcRequests {
+    let mut rng = get_rng();
+    let aggregate_key = x_only_public_key(&mut rng);
+
+    SbtcRequests {
+        deposits: (0..NUM_REQUESTS)
+            .map(|_| deposit(&mut rng, aggregate_key))
+            .collect(),
+        withdrawals: (0..NUM_REQUESTS)
+            .map(|request_id| withdrawal(&mut rng, request_id))
+            .collect(),
+        signer_state: SignerBtcState {
+            utxo: SignerUtxo {
+                outpoint: outpoint(&mut rng, 0),
+                amount: Amount::ONE_BTC.to_sat() * 100,
+                public_key: aggregate_key,
+            },
+            fee_rate: 5.0,
+            public_key: aggregate_key,
+            last_fees: None,
+            magic_bytes: [0; 2],
+            lock_time: LockTime::ZERO,
+        },
+        accept_threshold: 2,
+        num_signers: 3,
+        sbtc_limits: SbtcLimits::unlimited(),
+        max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
+    }
+}
+
+/// Convert a registry event into the model that the signer stores.
+fn into_model(event: RegistryEvent) {
+    match event {
+        RegistryEvent::CompletedDeposit(event) => {
+            black_box(model::CompletedDepositEvent::from(event));
+        }
+        RegistryEvent::WithdrawalAccept(event) => {
+            black_box(model::WithdrawalAcceptEvent::from(event));
+        }
+        RegistryEvent::WithdrawalReject(event) => {
+            black_box(model::WithdrawalRejectEvent::from(event));
+        }
+        RegistryEvent::WithdrawalCreate(event) => {
+            black_box(model::WithdrawalRequest::from(event));
+        }
+        RegistryEvent::KeyRotation(event) => {
+            black_box(model::KeyRotationEvent::from(event));
+        }
+    }
+}
+
+fn event_decoding(c: &mut Criterion) {
+    let values = registry_event_values();
+
+    c.bench_function("event_decoding/registry_events", |b| {
+        b.iter_batched(
+            || values.clone(),
+            |values| {
+                for (value, tx_info) in values {
+                    black_box(RegistryEvent::try_new(value, tx_info).unwrap());
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn sweep_construction(c: &mut Criterion) {
+    let requests = sbtc_requests();
+
+    c.bench_function("sweep_construction/transaction_package", |b| {
+        b.iter(|| black_box(requests.construct_transactions().unwrap()))
+    });
+}
+
+fn sighashes(c: &mut Criterion) {
+    let requests = sbtc_requests();
+    let transactions = requests.construct_transactions().unwrap();
+
+    c.bench_function("sighashes/transaction_package", |b| {
+        b.iter(|| {
+            for unsigned in transactions.iter() {
+                black_box(unsigned.construct_digests().unwrap());
+            }
+        })
+    });
+}
+
+fn model_conversions(c: &mut Criterion) {
+    let events: Vec<RegistryEvent> = registry_event_values()
+        .into_iter()
+        .map(|(value, tx_info)| RegistryEvent::try_new(value, tx_info).unwrap())
+        .collect();
+
+    c.bench_function("model_conversions/registry_events", |b| {
+        b.iter_batched(
+            || events.clone(),
+            |events| events.into_iter().for_each(into_model),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut rng = get_rng();
+    let principals: Vec<model::StacksPrincipal> = (0..100)
+        .map(|_| fake::Faker.fake_with_rng(&mut rng))
+        .collect();
+
+    c.bench_function("model_conversions/stacks_principals", |b| {
+        b.iter(|| {
+            for principal in principals.iter() {
+                let data = PrincipalData::from(principal.clone());
+                black_box(model::StacksPrincipal::from(data));
+            }
+        })
+    });
+}
+
+fn webhook_parsing(c: &mut Criterion) {
+    let body = large_webhook();
+
+    // Criterion only measures time, so we report the memory use of the
+    // two ways of parsing the body ourselves.
+    let from_string = peak_allocation(|| parse_webhook_from_string(&body));
+    let from_bytes = peak_allocation(|| parse_webhook_from_bytes(&body));
+    println!(
+        "webhook_parsing: peak allocation for a {} byte body: \
+        {from_string} bytes from a String, {from_bytes} bytes from the bytes",
+        body.len(),
+    );
+
+    let mut group = c.benchmark_group("webhook_parsing");
+    group.sample_size(10);
+    group.bench_function("large_webhook_from_string", |b| {
+        b.iter(|| black_box(parse_webhook_from_string(&body)))
+    });
+    group.bench_function("large_webhook_from_bytes", |b| {
+        b.iter(|| black_box(parse_webhook_from_bytes(&body)))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    event_decoding,
+    sweep_construction,
+    sighashes,
+    model_conversions,
+    webhook_parsing
+);
+criterion_main!(benches);
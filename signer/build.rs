@@ -57,6 +57,7 @@ pub fn compile_protos() {
         "protobufs/stacks/signer/v1/decisions.proto",
         "protobufs/stacks/signer/v1/requests.proto",
         "protobufs/stacks/signer/v1/messages.proto",
+        "protobufs/stacks/signer/v1/sync.proto",
     ]
     .map(|path| workingdir.join(path));
 
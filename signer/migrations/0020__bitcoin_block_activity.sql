@@ -0,0 +1,7 @@
+-- Records the time field of the header of every bitcoin block that the
+-- block observer processes, and whether the block contained any sBTC
+-- related transactions. Blocks that were processed before this migration
+-- have neither recorded.
+ALTER TABLE sbtc_signer.bitcoin_blocks
+    ADD COLUMN block_time BIGINT,
+    ADD COLUMN has_sbtc_activity BOOLEAN;
@@ -0,0 +1,17 @@
+-- Stores signer identity key rotations announced by peers over the P2P
+-- network. A row here means that the signer identified by
+-- `old_public_key` proved possession of `new_public_key` and will use it
+-- as its identity from now on.
+CREATE TABLE sbtc_signer.signer_identity_rotations (
+    -- The public key that the signer used before the rotation.
+    old_public_key BYTEA PRIMARY KEY,
+    -- The public key that the signer uses after the rotation.
+    new_public_key BYTEA NOT NULL,
+    -- The bitcoin chain tip of the signer when it announced the rotation.
+    bitcoin_chain_tip BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- A public key may only ever be rotated to once.
+CREATE UNIQUE INDEX uk_signer_identity_rotations_new_public_key
+    ON sbtc_signer.signer_identity_rotations(new_public_key);
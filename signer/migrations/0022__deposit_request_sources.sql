@@ -0,0 +1,26 @@
+-- The ways in which a deposit request can reach the signer.
+CREATE TYPE sbtc_signer.deposit_request_source AS ENUM (
+    'emily',
+    'api',
+    'p2p',
+    'backfill'
+);
+
+-- Records every intake source through which the signer has learned about
+-- a deposit request. A deposit request may be seen through more than one
+-- source, so this can have many rows for the same deposit request. This
+-- helps diagnose whether a missing deposit is an intake problem or a
+-- processing problem.
+CREATE TABLE sbtc_signer.deposit_request_sources (
+    -- The transaction ID of the deposit request.
+    txid BYTEA NOT NULL,
+    -- The output index of the deposit request UTXO.
+    output_index INTEGER NOT NULL,
+    -- The source that the deposit request was received from.
+    source sbtc_signer.deposit_request_source NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index, source),
+    FOREIGN KEY (txid, output_index)
+        REFERENCES sbtc_signer.deposit_requests(txid, output_index)
+        ON DELETE CASCADE
+);
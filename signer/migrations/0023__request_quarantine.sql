@@ -0,0 +1,37 @@
+-- Tracks signing failures for deposit requests that the coordinator has
+-- attempted to fulfill. Once a request has failed `failure_count` times
+-- past the configured threshold it is quarantined, and the coordinator
+-- stops including it until it is released, which deletes the row.
+CREATE TABLE sbtc_signer.deposit_request_failures (
+    -- The transaction ID of the deposit request.
+    txid BYTEA NOT NULL,
+    -- The output index of the deposit request UTXO.
+    output_index INTEGER NOT NULL,
+    -- The number of times that signing for this request has failed.
+    failure_count INTEGER NOT NULL,
+    -- The error from the most recent failure.
+    last_error TEXT NOT NULL,
+    -- When the request was quarantined, if it has been.
+    quarantined_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index),
+    FOREIGN KEY (txid, output_index)
+        REFERENCES sbtc_signer.deposit_requests(txid, output_index)
+        ON DELETE CASCADE
+);
+
+-- The same as `deposit_request_failures`, but for withdrawal requests.
+-- Withdrawal requests are identified by their request ID alone, since a
+-- request that is reorged into a different stacks block is still the
+-- same request.
+CREATE TABLE sbtc_signer.withdrawal_request_failures (
+    -- The ID of the withdrawal request generated by the smart contract.
+    request_id BIGINT PRIMARY KEY,
+    -- The number of times that signing for this request has failed.
+    failure_count INTEGER NOT NULL,
+    -- The error from the most recent failure.
+    last_error TEXT NOT NULL,
+    -- When the request was quarantined, if it has been.
+    quarantined_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,9 @@
+-- External systems that have registered, through the signer's API, to
+-- receive webhook notifications about completed deposits, fulfilled
+-- withdrawals and key rotations. Subscribers configured in the signer's
+-- config file are not stored here.
+CREATE TABLE sbtc_signer.webhook_subscribers (
+    -- The URL that notifications are posted to.
+    url TEXT PRIMARY KEY,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Stores keyed integrity hashes over security-critical rows in other
+-- tables. A hash is computed by the signer when it writes the row, using
+-- a secret key that is not stored in the database, so that rows which
+-- are modified, inserted or deleted out-of-band can be detected.
+CREATE TABLE sbtc_signer.row_integrity_hashes (
+    -- The name of the table that holds the protected row.
+    table_name TEXT NOT NULL,
+    -- The columns that identify the protected row, encoded as bytes.
+    row_key BYTEA NOT NULL,
+    -- The HMAC-SHA256 of the contents of the protected row.
+    integrity_hash BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (table_name, row_key)
+);
@@ -0,0 +1,16 @@
+-- Fees paid for the stacks transactions that this signer submitted as
+-- the coordinator. These are used to hold fee spending over a rolling
+-- period within the configured budget.
+CREATE TABLE sbtc_signer.stacks_fee_spends (
+    -- The ID of the submitted stacks transaction.
+    txid BYTEA PRIMARY KEY,
+    -- The kind of transaction, such as `complete-deposit`.
+    tx_kind TEXT NOT NULL,
+    -- The fee paid for the transaction, in microSTX.
+    fee_ustx BIGINT NOT NULL,
+    -- When the transaction was accepted by the stacks node.
+    submitted_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX ix_stacks_fee_spends_submitted_at
+    ON sbtc_signer.stacks_fee_spends(submitted_at);
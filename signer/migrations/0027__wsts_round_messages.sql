@@ -0,0 +1,19 @@
+-- Messages that the coordinator has collected for WSTS signing rounds
+-- that are still in progress. If the coordinator restarts, or a round
+-- times out, while the bitcoin chain tip stays the same, then the round
+-- is resumed from these messages instead of being started over. The
+-- messages of a round are deleted when it completes, and the messages of
+-- all rounds at other chain tips are deleted when the chain tip moves.
+CREATE TABLE sbtc_signer.wsts_round_messages (
+    id BIGSERIAL PRIMARY KEY,
+    -- The bitcoin chain tip that the signing round started at.
+    bitcoin_chain_tip BYTEA NOT NULL,
+    -- The message being signed in the signing round.
+    message BYTEA NOT NULL,
+    -- The protobuf encoded signed message from the signer.
+    payload BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX ix_wsts_round_messages_round
+    ON sbtc_signer.wsts_round_messages(bitcoin_chain_tip, message);
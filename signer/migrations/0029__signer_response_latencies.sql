@@ -0,0 +1,18 @@
+-- How long each signer took to respond to the requests of this signer
+-- while it was the coordinator, during bitcoin pre-sign ballots and WSTS
+-- signing rounds. These are kept for a rolling window so that chronically
+-- slow signers can be identified.
+CREATE TABLE sbtc_signer.signer_response_latencies (
+    id BIGSERIAL PRIMARY KEY,
+    -- The public key of the signer that responded.
+    signer_public_key BYTEA NOT NULL,
+    -- The kind of response, such as `presign-ack`.
+    response_kind TEXT NOT NULL,
+    -- The time between the request and the response, in milliseconds.
+    latency_ms BIGINT NOT NULL,
+    -- When the response was received.
+    observed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX ix_signer_response_latencies_observed_at
+    ON sbtc_signer.signer_response_latencies(observed_at);
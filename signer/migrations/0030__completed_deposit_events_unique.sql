@@ -0,0 +1,15 @@
+-- A deposit can only be completed once on a stacks fork, but it can be
+-- completed on more than one fork. The signer used to insert a new row
+-- whenever the stacks node delivered a completed-deposit event, so a
+-- redelivered event was recorded twice. Remove such duplicates, keeping
+-- the earliest row, and make sure that each deposit has at most one
+-- completed-deposit event per stacks block.
+DELETE FROM sbtc_signer.completed_deposit_events AS cde
+USING sbtc_signer.completed_deposit_events AS earlier
+WHERE cde.bitcoin_txid = earlier.bitcoin_txid
+  AND cde.output_index = earlier.output_index
+  AND cde.block_hash = earlier.block_hash
+  AND cde.id > earlier.id;
+
+CREATE UNIQUE INDEX uix_completed_deposit_events_outpoint_block_hash
+    ON sbtc_signer.completed_deposit_events(bitcoin_txid, output_index, block_hash);
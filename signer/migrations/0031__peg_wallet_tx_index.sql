@@ -0,0 +1,46 @@
+-- Every input of every bitcoin transaction that spends from, or pays to, a
+-- scriptPubKey of the signers, whether it belongs to the current
+-- aggregate key or to a past one. Unlike `bitcoin_tx_inputs`, which only
+-- has the inputs that the signers spend in their own transactions, this
+-- table has all inputs of these transactions, so that the flow of funds
+-- through the peg wallet can be traced from the signer's own database.
+CREATE TABLE sbtc_signer.peg_wallet_tx_inputs (
+    -- The ID of the transaction with the input.
+    txid BYTEA NOT NULL,
+    -- The index of the input in the transaction.
+    input_index INTEGER NOT NULL,
+    -- The outpoint of the output being spent.
+    prevout_txid BYTEA NOT NULL,
+    prevout_output_index INTEGER NOT NULL,
+    -- The amount locked in the output being spent.
+    amount BIGINT NOT NULL,
+    -- The scriptPubKey of the output being spent.
+    script_pubkey BYTEA NOT NULL,
+    -- Whether the output being spent is locked by a scriptPubKey of the
+    -- signers.
+    signers_script BOOLEAN NOT NULL,
+    -- a timestamp of when this record was created in the database.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, input_index)
+);
+
+-- Every output of the same transactions.
+CREATE TABLE sbtc_signer.peg_wallet_tx_outputs (
+    -- The ID of the transaction with the output.
+    txid BYTEA NOT NULL,
+    -- The index of the output in the transaction.
+    output_index INTEGER NOT NULL,
+    -- The amount locked in the output.
+    amount BIGINT NOT NULL,
+    -- The scriptPubKey of the output.
+    script_pubkey BYTEA NOT NULL,
+    -- Whether the output is locked by a scriptPubKey of the signers.
+    signers_script BOOLEAN NOT NULL,
+    -- a timestamp of when this record was created in the database.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index)
+);
+
+-- The spend links, from an output to the input that spends it.
+CREATE INDEX ix_peg_wallet_tx_inputs_prevout
+    ON sbtc_signer.peg_wallet_tx_inputs(prevout_txid, prevout_output_index);
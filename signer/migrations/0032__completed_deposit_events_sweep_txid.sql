@@ -0,0 +1,4 @@
+-- When a sweep transaction is orphaned by a bitcoin reorg, the signer looks
+-- up the completed-deposit events of the deposits that it swept in.
+CREATE INDEX ix_completed_deposit_events_sweep_txid
+    ON sbtc_signer.completed_deposit_events(sweep_txid);
@@ -0,0 +1,25 @@
+CREATE TYPE sbtc_signer.stale_deposit_reason AS ENUM (
+    'never_confirmed',
+    'reclaimed'
+);
+
+-- Deposit requests whose deposit UTXO is no longer available for the
+-- signers to sweep, either because the deposit transaction is unknown to
+-- bitcoin-core or because the UTXO was spent by something other than a
+-- sweep transaction. The coordinator does not attempt to fulfill stale
+-- deposit requests, and their failure is reported to Emily.
+CREATE TABLE sbtc_signer.stale_deposit_requests (
+    -- The transaction ID of the deposit request.
+    txid BYTEA NOT NULL,
+    -- The output index of the deposit request UTXO.
+    output_index INTEGER NOT NULL,
+    -- Why the deposit request is stale.
+    reason sbtc_signer.stale_deposit_reason NOT NULL,
+    -- When Emily accepted the failure of the deposit request, if it has.
+    emily_synced_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index),
+    FOREIGN KEY (txid, output_index)
+        REFERENCES sbtc_signer.deposit_requests(txid, output_index)
+        ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- The sbtc-registry contract that emitted the events of a stacks
+-- transaction. The signers may observe more than one registry contract,
+-- for example while the sBTC contracts are being migrated to a new
+-- deployment, and requests are validated against the contracts of the
+-- deployment that they were made in. Events that were recorded before
+-- this table was added came from the registry of the configured deployer.
+CREATE TABLE sbtc_signer.registry_event_sources (
+    -- The ID of the stacks transaction that emitted the events.
+    txid BYTEA NOT NULL,
+    -- The ID of the stacks block that includes the transaction.
+    block_hash BYTEA NOT NULL,
+    -- The contract identifier of the registry contract that emitted the
+    -- events.
+    registry TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, block_hash)
+);
@@ -0,0 +1,17 @@
+-- When the signers aggregate the withdrawals that pay the same recipient,
+-- one output of a sweep transaction services more than one withdrawal
+-- request. The withdrawal request is therefore part of the key of the
+-- tables that map withdrawal requests to sweep outputs.
+ALTER TABLE sbtc_signer.bitcoin_withdrawals_outputs
+  DROP CONSTRAINT bitcoin_withdrawals_outputs_pkey;
+
+ALTER TABLE sbtc_signer.bitcoin_withdrawals_outputs
+  ADD CONSTRAINT bitcoin_withdrawals_outputs_request_pkey
+  PRIMARY KEY (bitcoin_txid, output_index, request_id);
+
+ALTER TABLE sbtc_signer.bitcoin_withdrawal_tx_outputs
+  DROP CONSTRAINT bitcoin_withdrawal_tx_outputs_pkey;
+
+ALTER TABLE sbtc_signer.bitcoin_withdrawal_tx_outputs
+  ADD CONSTRAINT bitcoin_withdrawal_tx_outputs_request_pkey
+  PRIMARY KEY (txid, output_index, request_id);
@@ -0,0 +1,8 @@
+-- The stacks blocks whose events have all been handled. Stacks nodes may
+-- send the same new block webhook more than once, for example after they
+-- restart, and webhooks for blocks in this table are skipped.
+CREATE TABLE sbtc_signer.processed_stacks_blocks (
+    -- The index block hash of the stacks block.
+    block_hash BYTEA PRIMARY KEY,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
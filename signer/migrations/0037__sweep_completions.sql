@@ -0,0 +1,18 @@
+-- Links the sweep transactions of the signers on bitcoin to the stacks
+-- transactions that completed the requests that they fulfilled. A row is
+-- added for every completed-deposit and withdrawal-accept event, and a
+-- sweep that is confirmed on bitcoin without any rows here never got its
+-- stacks completion.
+CREATE TABLE sbtc_signer.sweep_completions (
+    -- The ID of the sweep transaction.
+    sweep_txid BYTEA NOT NULL,
+    -- The bitcoin block that confirmed the sweep transaction, according
+    -- to the stacks event.
+    sweep_block_hash BYTEA NOT NULL,
+    -- The ID of the stacks transaction that emitted the event.
+    stacks_txid BYTEA NOT NULL,
+    -- The stacks block that included the stacks transaction.
+    stacks_block_hash BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (sweep_txid, stacks_txid, stacks_block_hash)
+);
@@ -0,0 +1,14 @@
+-- The stacks blocks whose events could not be handled, with the number of
+-- times that handling them failed. Once a block has failed the configured
+-- maximum number of times, webhooks for it are acknowledged without its
+-- events being handled again.
+CREATE TABLE sbtc_signer.failed_stacks_blocks (
+    -- The index block hash of the stacks block.
+    block_hash BYTEA PRIMARY KEY,
+    -- The number of times that handling the events of the block failed.
+    failure_count INTEGER NOT NULL,
+    -- The error of the most recent failure.
+    last_error TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
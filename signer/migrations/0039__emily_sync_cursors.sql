@@ -0,0 +1,12 @@
+-- The cursors of the incremental syncs with Emily. Each cursor is the
+-- largest stacks block height at which Emily last updated one of the
+-- items fetched by the sync, so that the next sync only needs to fetch
+-- the items that Emily updated at or after that height.
+CREATE TABLE sbtc_signer.emily_sync_cursors (
+    -- The name of the sync, for example 'deposits'.
+    name TEXT PRIMARY KEY,
+    -- The largest last update height of the synced items.
+    last_update_height BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
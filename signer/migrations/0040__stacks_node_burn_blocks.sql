@@ -0,0 +1,14 @@
+-- The bitcoin blocks that the stacks node told us about through the
+-- `/new_burn_block` webhook. They are the bitcoin blocks that the stacks
+-- node anchors stacks blocks to, which we cross-check against the bitcoin
+-- blocks that we observed ourselves.
+CREATE TABLE sbtc_signer.stacks_node_burn_blocks (
+    -- The hash of the bitcoin block.
+    block_hash BYTEA PRIMARY KEY,
+    -- The height of the bitcoin block.
+    block_height BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX ix_stacks_node_burn_blocks_block_height
+    ON sbtc_signer.stacks_node_burn_blocks(block_height);
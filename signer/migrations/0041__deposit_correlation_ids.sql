@@ -0,0 +1,17 @@
+-- The identifiers that the creators of deposit requests gave Emily for
+-- tracing the requests across systems. We pass them along in the status
+-- updates of the deposit requests that we send to Emily, and record them
+-- in our logs.
+CREATE TABLE sbtc_signer.deposit_correlation_ids (
+    -- The transaction ID of the deposit request.
+    txid BYTEA NOT NULL,
+    -- The output index of the deposit request UTXO.
+    output_index INTEGER NOT NULL,
+    -- The correlation ID of the deposit request.
+    correlation_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index),
+    FOREIGN KEY (txid, output_index)
+        REFERENCES sbtc_signer.deposit_requests(txid, output_index)
+        ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Watches on bitcoin transactions, registered by subsystems of the signer
+-- or by external integrators through the API. A watch fires once its
+-- transaction has the given number of confirmations, after which it is
+-- deleted.
+CREATE TABLE sbtc_signer.confirmation_watches (
+    -- The ID of the watched transaction.
+    txid BYTEA NOT NULL,
+    -- The number of confirmations at which the watch fires.
+    confirmations INTEGER NOT NULL,
+    -- The URL that is notified when the watch fires, if any.
+    callback_url TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE NULLS NOT DISTINCT (txid, confirmations, callback_url)
+);
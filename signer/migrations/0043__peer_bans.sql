@@ -0,0 +1,12 @@
+-- Peers that the operator of the signer has banned through the API. The
+-- signer refuses connections from a banned peer and drops its messages
+-- until the ban expires.
+CREATE TABLE sbtc_signer.peer_bans (
+    -- The libp2p peer ID of the banned peer.
+    peer_id TEXT PRIMARY KEY,
+    -- Why the peer was banned, if the operator said.
+    reason TEXT,
+    -- When the ban stops applying.
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,15 @@
+-- The raw bodies of the `POST /new_block` webhooks of all stacks blocks,
+-- compressed, so that operators can audit exactly what the stacks node sent
+-- the signer. They are deleted once they are older than the configured
+-- retention window.
+CREATE TABLE sbtc_signer.raw_stacks_events (
+    -- The index block hash of the stacks block.
+    block_hash BYTEA PRIMARY KEY,
+    -- The body of the webhook, exactly as it was received, compressed with
+    -- zstd.
+    body BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX ix_raw_stacks_events_created_at
+    ON sbtc_signer.raw_stacks_events(created_at);
@@ -0,0 +1,9 @@
+-- The periods whose records were exported to the analytics bucket, so that
+-- the exporter picks up where it left off after a restart.
+CREATE TABLE sbtc_signer.analytics_exports (
+    -- The start of the period, inclusive.
+    period_start TIMESTAMPTZ PRIMARY KEY,
+    -- The end of the period, exclusive.
+    period_end TIMESTAMPTZ NOT NULL,
+    exported_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
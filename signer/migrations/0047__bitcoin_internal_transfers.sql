@@ -0,0 +1,12 @@
+-- The bitcoin transactions that only moved funds between the signers'
+-- addresses, such as when the signers move their UTXO from the address of
+-- a previous aggregate key to the one of the current key. These service no
+-- deposits or withdrawals, so they are left out of the sweep accounting.
+CREATE TABLE sbtc_signer.bitcoin_internal_transfers (
+    txid BYTEA NOT NULL,
+    block_hash BYTEA NOT NULL,
+    -- The total amount of the outputs of the transaction, in sats.
+    amount BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, block_hash)
+);
@@ -0,0 +1,14 @@
+-- The contract events that the stacks node sent to the event observer from
+-- contracts other than the observed sbtc-registry contracts. Such events are
+-- never handled, but recording them helps operators spot a misconfigured
+-- stacks node or an attempt to spoof sBTC events.
+CREATE TABLE sbtc_signer.quarantined_contract_events (
+    txid BYTEA NOT NULL,
+    block_hash BYTEA NOT NULL,
+    -- The index of the event among the events of the block.
+    event_index BIGINT NOT NULL,
+    contract_identifier TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (block_hash, event_index)
+);
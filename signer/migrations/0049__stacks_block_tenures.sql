@@ -0,0 +1,25 @@
+-- The reasons for a tenure change in a stacks block.
+CREATE TYPE sbtc_signer.tenure_change_kind AS ENUM (
+    'block_found',
+    'extended'
+);
+
+-- The tenure of stacks blocks and the signers that signed them, as sent by
+-- the stacks node in `POST /new_block` webhooks. This lets the coordinator
+-- reason about the liveness of the stacks chain, like when tenures last
+-- started and how many signers are signing blocks. Only blocks with a
+-- tenure change or a signer bitvec are recorded.
+CREATE TABLE sbtc_signer.stacks_block_tenures (
+    -- The index block hash of the stacks block.
+    block_hash BYTEA PRIMARY KEY,
+    -- The reason for the tenure change in the block, if it has one.
+    tenure_change sbtc_signer.tenure_change_kind,
+    -- The height of the tenure of the block. Older stacks nodes do not
+    -- send it.
+    tenure_height BIGINT,
+    -- Whether each signer of the reward cycle signed the block, in the
+    -- order of the signers in the reward set. Blocks from before Nakamoto
+    -- are not signed by the signers.
+    signer_bitvec BOOLEAN[],
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,25 @@
+-- Reports of panics in the long-lived components of the signer. Each
+-- report has what an operator needs to make sense of the panic after the
+-- fact: where it happened, the backtrace, the signals that the signer
+-- handled just before, and the chain tips that it was working with.
+CREATE TABLE sbtc_signer.crash_reports (
+    id BIGSERIAL PRIMARY KEY,
+    -- The name of the component that panicked.
+    component TEXT NOT NULL,
+    -- The message of the panic.
+    message TEXT NOT NULL,
+    -- The backtrace of the panic, if one was captured.
+    backtrace TEXT,
+    -- Descriptions of the most recent signals that the signer handled,
+    -- oldest first.
+    recent_events TEXT[] NOT NULL,
+    -- The bitcoin chain tip of the signer when the component panicked.
+    bitcoin_chain_tip BYTEA,
+    bitcoin_chain_tip_height BIGINT,
+    -- The last stacks block whose webhook the signer handled.
+    stacks_chain_tip BYTEA,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX ix_crash_reports_created_at
+    ON sbtc_signer.crash_reports(created_at);
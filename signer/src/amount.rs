@@ -0,0 +1,188 @@
+//! Typed amounts of sBTC.
+//!
+//! Amounts of bitcoin are [`bitcoin::Amount`]s. Amounts of sBTC used to be
+//! passed around the signer as raw `u64`s, which makes it easy to mix up
+//! an amount on Stacks with an amount on bitcoin. The [`SbtcAmount`] type
+//! in this module makes the unit explicit and only exposes checked
+//! arithmetic, so that every place where an amount could overflow or
+//! underflow has to handle that case.
+//!
+//! The sBTC events that we store carry these types: the amount minted in
+//! a [`CompletedDepositEvent`](crate::storage::model::CompletedDepositEvent)
+//! is an [`SbtcAmount`], and the fee paid in a
+//! [`WithdrawalAcceptEvent`](crate::storage::model::WithdrawalAcceptEvent)
+//! is an [`Amount`]. The Clarity values are converted when the events are
+//! parsed, and the database columns when the events are read and written.
+//!
+//! The sBTC token has the same number of decimals as bitcoin, so one sBTC
+//! base unit is always worth exactly one satoshi. The two types are kept
+//! distinct anyway, so that converting between an amount on bitcoin and
+//! an amount on Stacks is always explicit.
+
+use std::num::TryFromIntError;
+
+use bitcoin::Amount;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// An amount of sBTC, denominated in the base unit of the sBTC token.
+///
+/// One base unit of sBTC is pegged to one satoshi.
+#[derive(
+    Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct SbtcAmount(u64);
+
+impl SbtcAmount {
+    /// The zero amount.
+    pub const ZERO: Self = Self(0);
+
+    /// Create a new amount from the given number of base units.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Return the number of base units in this amount.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Checked addition. Returns `None` if the result overflows.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Checked subtraction. Returns `None` if the result would be
+    /// negative.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Behaves same as u64.saturating_sub
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Add the given amount, returning an error if the result overflows.
+    pub fn try_add(self, rhs: Self) -> Result<Self, Error> {
+        self.checked_add(rhs)
+            .ok_or(Error::AmountOverflow { lhs: self.0, rhs: rhs.0 })
+    }
+
+    /// Subtract the given amount, returning an error if the result would
+    /// be negative.
+    pub fn try_sub(self, rhs: Self) -> Result<Self, Error> {
+        self.checked_sub(rhs)
+            .ok_or(Error::AmountUnderflow { lhs: self.0, rhs: rhs.0 })
+    }
+
+    /// Sum the given amounts, returning an error on overflow.
+    pub fn try_sum<I>(amounts: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Self::ZERO, |total, amount| total.try_add(amount))
+    }
+}
+
+impl From<u64> for SbtcAmount {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SbtcAmount> for u128 {
+    fn from(value: SbtcAmount) -> Self {
+        value.0 as u128
+    }
+}
+
+impl TryFrom<u128> for SbtcAmount {
+    type Error = TryFromIntError;
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        u64::try_from(value).map(Self)
+    }
+}
+
+impl TryFrom<i64> for SbtcAmount {
+    type Error = TryFromIntError;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u64::try_from(value).map(Self)
+    }
+}
+
+impl TryFrom<SbtcAmount> for i64 {
+    type Error = TryFromIntError;
+    fn try_from(value: SbtcAmount) -> Result<Self, Self::Error> {
+        i64::try_from(value.0)
+    }
+}
+
+impl From<Amount> for SbtcAmount {
+    fn from(value: Amount) -> Self {
+        Self(value.to_sat())
+    }
+}
+
+impl From<SbtcAmount> for Amount {
+    fn from(value: SbtcAmount) -> Self {
+        Amount::from_sat(value.0)
+    }
+}
+
+impl std::fmt::Display for SbtcAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} sBTC base units", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_arithmetic_catches_overflow() {
+        let max = SbtcAmount::new(u64::MAX);
+        let one = SbtcAmount::new(1);
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(SbtcAmount::ZERO.checked_sub(one), None);
+        assert_eq!(one.checked_add(one), Some(SbtcAmount::new(2)));
+        assert_eq!(max.checked_sub(one), Some(SbtcAmount::new(u64::MAX - 1)));
+
+        assert!(matches!(
+            max.try_add(one),
+            Err(Error::AmountOverflow { lhs: u64::MAX, rhs: 1 })
+        ));
+        assert!(matches!(
+            SbtcAmount::ZERO.try_sub(one),
+            Err(Error::AmountUnderflow { lhs: 0, rhs: 1 })
+        ));
+    }
+
+    #[test]
+    fn try_sum_catches_overflow() {
+        let amounts = [1, 2, 3].map(SbtcAmount::new);
+        assert_eq!(SbtcAmount::try_sum(amounts).unwrap(), SbtcAmount::new(6));
+
+        let amounts = [u64::MAX, 1].map(SbtcAmount::new);
+        assert!(SbtcAmount::try_sum(amounts).is_err());
+    }
+
+    #[test]
+    fn conversions_preserve_value() {
+        let amount = Amount::from_sat(123_456_789);
+        let sbtc = SbtcAmount::from(amount);
+        assert_eq!(sbtc.get(), amount.to_sat());
+        assert_eq!(Amount::from(sbtc), amount);
+
+        assert!(SbtcAmount::try_from(u64::MAX as u128 + 1).is_err());
+        assert!(SbtcAmount::try_from(-1i64).is_err());
+        assert!(i64::try_from(SbtcAmount::new(u64::MAX)).is_err());
+    }
+}
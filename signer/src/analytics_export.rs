@@ -0,0 +1,449 @@
+//! # Analytics export
+//!
+//! The [`AnalyticsExporter`] uploads the tables of a data export, see
+//! [`crate::export`], to an S3-compatible bucket on a schedule, so that
+//! analytics teams can load sBTC activity into their data warehouse
+//! without querying the signer's database.
+//!
+//! Time is split into periods of
+//! [`interval`](crate::config::AnalyticsExportConfig::interval) seconds,
+//! aligned to the unix epoch, and every period is exported once, shortly
+//! after it is over. The exported periods are recorded in the database,
+//! so that the exporter picks up where it left off after a restart. The
+//! first period exported is the last one that was over when the exporter
+//! first ran; earlier periods can be exported with the `signer export`
+//! command.
+//!
+//! Each table is uploaded as its own object, under
+//! `<prefix>/<table>/<start>_<end>.csv`, where the start and the end of
+//! the period are UTC times in `YYYYMMDDTHHMMSSZ` form, so that each table
+//! can be loaded from a single location in the bucket. In JSON format
+//! there is one object for each period, under `<prefix>/export/..`.
+//! Parquet is not supported, since it would pull in a columnar data stack
+//! that the signer has no other use for, and warehouses load CSV just as
+//! well.
+//!
+//! Uploads are plain `PUT` requests signed with AWS Signature Version 4,
+//! which AWS S3 and the S3-compatible services all accept.
+
+use std::time::Duration;
+
+use bitcoin::hashes::Hash as _;
+use bitcoin::hashes::HashEngine as _;
+use bitcoin::hashes::hmac::Hmac;
+use bitcoin::hashes::hmac::HmacEngine;
+use bitcoin::hashes::sha256;
+use sha2::Digest as _;
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::config::AnalyticsExportConfig;
+use crate::context::Context;
+use crate::error::Error;
+use crate::export::Export;
+use crate::export::ExportFile;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+
+/// How often the exporter checks whether a period is over.
+const EXPORT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long after the end of a period the exporter waits before exporting
+/// it, so that the records written just before the end are committed.
+const EXPORT_DELAY: time::Duration = time::Duration::seconds(60);
+
+/// The maximum amount of time to wait for a single upload.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A client that uploads objects to an S3-compatible bucket.
+#[derive(Debug, Clone)]
+pub struct ObjectStoreClient {
+    client: reqwest::Client,
+    config: AnalyticsExportConfig,
+}
+
+impl ObjectStoreClient {
+    /// Create a new client for the bucket in the given config.
+    pub fn new(config: AnalyticsExportConfig) -> Result<Self, Error> {
+        let client = reqwest::Client::builder()
+            .timeout(UPLOAD_TIMEOUT)
+            .build()
+            .map_err(Error::AnalyticsExportClient)?;
+
+        Ok(Self { client, config })
+    }
+
+    /// Return the path-style URL of the object with the given key.
+    pub fn object_url(&self, key: &str) -> Url {
+        let mut url = self.config.endpoint.clone();
+        let path = format!(
+            "{}/{}/{}",
+            url.path().trim_end_matches('/'),
+            uri_encode(&self.config.bucket, false),
+            uri_encode(key, true),
+        );
+        url.set_path(&path);
+        url
+    }
+
+    /// Upload an object with the given key and contents, replacing the
+    /// object if it already exists.
+    pub async fn put_object(
+        &self,
+        key: &str,
+        contents: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), Error> {
+        let url = self.object_url(key);
+        let payload_hash = hex::encode(sha2::Sha256::digest(&contents));
+        let amz_date = amz_timestamp(OffsetDateTime::now_utc());
+        let authorization = self.authorization(&url, &payload_hash, &amz_date);
+
+        self.client
+            .put(url)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .body(contents)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| Error::AnalyticsExportUpload(err, key.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Return the `Authorization` header of a `PUT` request for the given
+    /// URL, signed with AWS Signature Version 4 over the `host`,
+    /// `x-amz-content-sha256` and `x-amz-date` headers.
+    fn authorization(&self, url: &Url, payload_hash: &str, amz_date: &str) -> String {
+        // reqwest sends the port in the host header only when it is not
+        // the default port of the scheme, which is also when `Url::port`
+        // returns it.
+        let host = url.host_str().unwrap_or_default();
+        let host = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n\
+            {signed_headers}\n{payload_hash}",
+            url.path(),
+        );
+
+        let date = &amz_date[..8];
+        let scope = format!("{date}/{}/s3/aws4_request", self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(sha2::Sha256::digest(canonical_request.as_bytes())),
+        );
+
+        let key = signing_key(
+            &self.config.secret_access_key,
+            date,
+            &self.config.region,
+            "s3",
+        );
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, \
+            Signature={signature}",
+            self.config.access_key_id,
+        )
+    }
+}
+
+/// Exports the records of every completed period to the analytics bucket.
+pub struct AnalyticsExporter<C> {
+    /// Signer context.
+    context: C,
+    /// The client for the analytics bucket.
+    store: ObjectStoreClient,
+    /// The analytics export settings.
+    config: AnalyticsExportConfig,
+}
+
+impl<C> AnalyticsExporter<C>
+where
+    C: Context,
+{
+    /// Creates a new AnalyticsExporter with the given context and
+    /// settings.
+    pub fn new(context: C, config: AnalyticsExportConfig) -> Result<Self, Error> {
+        let store = ObjectStoreClient::new(config.clone())?;
+        Ok(Self { context, store, config })
+    }
+
+    /// Runs the AnalyticsExporter, which exports the periods that are
+    /// over right away and then checks for more once every minute.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        self.export().await;
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(EXPORT_CHECK_INTERVAL) => {
+                    self.export().await;
+                }
+            }
+        }
+        tracing::info!("analytics exporter has stopped");
+    }
+
+    /// Export the periods that are over and have not been exported yet.
+    /// A period that fails to be exported is exported again on the next
+    /// check, before any later period.
+    #[tracing::instrument(skip_all, name = "analytics-exporter")]
+    pub async fn export(&self) {
+        if let Err(error) = self.export_pending(OffsetDateTime::now_utc()).await {
+            tracing::warn!(%error, "could not export analytics data");
+            metrics::counter!(Metrics::AnalyticsExportsTotal, "status" => "failure").increment(1);
+        }
+    }
+
+    /// Export the periods that were over at least [`EXPORT_DELAY`] before
+    /// `now`, in order.
+    pub async fn export_pending(&self, now: OffsetDateTime) -> Result<(), Error> {
+        let interval = self.interval();
+        let mut from = match self
+            .context
+            .get_storage()
+            .get_last_analytics_export_end()
+            .await?
+        {
+            Some(last_end) => *last_end,
+            None => period_start(now, interval) - interval,
+        };
+
+        loop {
+            // The end is aligned even if the start is not, which happens
+            // when the interval was changed since the last export.
+            let to = period_start(from + interval, interval);
+            if to + EXPORT_DELAY > now {
+                return Ok(());
+            }
+            self.export_period(from, to).await?;
+            metrics::counter!(Metrics::AnalyticsExportsTotal, "status" => "success").increment(1);
+            from = to;
+        }
+    }
+
+    /// Upload the export of the records of the given period, and record
+    /// that it was exported.
+    async fn export_period(&self, from: OffsetDateTime, to: OffsetDateTime) -> Result<(), Error> {
+        let storage = self.context.get_storage_mut();
+        let export = Export::collect(&storage, from.into(), to.into()).await?;
+
+        for file in export.files(self.config.format)? {
+            let key = object_key(&self.config.prefix, from, to, &file);
+            let content_type = content_type(&file);
+            self.store
+                .put_object(&key, file.contents, content_type)
+                .await?;
+        }
+
+        storage
+            .write_analytics_export(from.into(), to.into())
+            .await?;
+
+        tracing::info!(
+            from = %from,
+            to = %to,
+            deposits = export.deposits.len(),
+            withdrawals = export.withdrawals.len(),
+            sweeps = export.sweeps.len(),
+            "exported analytics data"
+        );
+        Ok(())
+    }
+
+    fn interval(&self) -> time::Duration {
+        time::Duration::seconds(self.config.interval.as_secs() as i64)
+    }
+}
+
+/// Return the start of the period of the given length that contains the
+/// given time, with periods aligned to the unix epoch.
+fn period_start(time: OffsetDateTime, interval: time::Duration) -> OffsetDateTime {
+    let interval = interval.whole_seconds().max(1);
+    let timestamp = time.unix_timestamp();
+    let start = timestamp - timestamp.rem_euclid(interval);
+    OffsetDateTime::from_unix_timestamp(start).unwrap_or(time)
+}
+
+/// Return the key of the object that the given file of the export of the
+/// given period is uploaded to.
+fn object_key(prefix: &str, from: OffsetDateTime, to: OffsetDateTime, file: &ExportFile) -> String {
+    let (table, extension) = file.name.rsplit_once('.').unwrap_or((&file.name, ""));
+    let period = format!("{}_{}", amz_timestamp(from), amz_timestamp(to));
+    let prefix = prefix.trim_matches('/');
+
+    if prefix.is_empty() {
+        format!("{table}/{period}.{extension}")
+    } else {
+        format!("{prefix}/{table}/{period}.{extension}")
+    }
+}
+
+fn content_type(file: &ExportFile) -> &'static str {
+    if file.name.ends_with(".json") {
+        "application/json"
+    } else {
+        "text/csv"
+    }
+}
+
+/// Format the given time in the `YYYYMMDDTHHMMSSZ` form that AWS
+/// signatures use.
+fn amz_timestamp(time: OffsetDateTime) -> String {
+    let time = time.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+    )
+}
+
+/// Percent-encode the given string the way AWS signatures expect, leaving
+/// slashes alone if `is_path` is set.
+fn uri_encode(value: &str, is_path: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if is_path => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut engine = HmacEngine::<sha256::Hash>::new(key);
+    engine.input(data);
+    Hmac::from_engine(engine).to_byte_array()
+}
+
+/// Derive the key that AWS Signature Version 4 signs requests to the
+/// given service on the given day with.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::ExportFormat;
+    use crate::storage::DbRead as _;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    fn config(endpoint: &str) -> AnalyticsExportConfig {
+        AnalyticsExportConfig {
+            endpoint: endpoint.parse().unwrap(),
+            bucket: "sbtc-analytics".to_string(),
+            region: "us-east-1".to_string(),
+            prefix: "signer-1/".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            interval: Duration::from_secs(3600),
+            format: ExportFormat::Csv,
+        }
+    }
+
+    #[test]
+    fn signing_key_matches_the_aws_example() {
+        // The example from the AWS documentation on deriving a signing
+        // key for Signature Version 4.
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn objects_are_keyed_by_table_and_period() {
+        let from = OffsetDateTime::from_unix_timestamp(1_709_164_800).unwrap();
+        let to = from + time::Duration::hours(1);
+        let file = ExportFile {
+            name: "deposits.csv".to_string(),
+            contents: Vec::new(),
+        };
+
+        let key = object_key("/signer-1/", from, to, &file);
+        assert_eq!(
+            key,
+            "signer-1/deposits/20240229T000000Z_20240229T010000Z.csv"
+        );
+        let key = object_key("", from, to, &file);
+        assert_eq!(key, "deposits/20240229T000000Z_20240229T010000Z.csv");
+
+        let client = ObjectStoreClient::new(config("https://s3.example.com/storage")).unwrap();
+        let url = client.object_url("a b/c.csv");
+        assert_eq!(
+            url.as_str(),
+            "https://s3.example.com/storage/sbtc-analytics/a%20b/c.csv"
+        );
+    }
+
+    #[test]
+    fn periods_are_aligned_to_the_interval() {
+        let time = OffsetDateTime::from_unix_timestamp(1_709_166_000).unwrap();
+        let start = period_start(time, time::Duration::hours(1));
+        assert_eq!(start.unix_timestamp(), 1_709_164_800);
+        assert_eq!(period_start(start, time::Duration::hours(1)), start);
+    }
+
+    #[tokio::test]
+    async fn completed_periods_are_exported_once() {
+        let mut server = mockito::Server::new_async().await;
+        let path = mockito::Matcher::Regex("^/sbtc-analytics/signer-1/".to_string());
+        let authorization = mockito::Matcher::Regex("^AWS4-HMAC-SHA256 ".to_string());
+        let mock = server
+            .mock("PUT", path)
+            .match_header("authorization", authorization)
+            .match_header("x-amz-content-sha256", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(6)
+            .create_async()
+            .await;
+
+        let context = TestContext::default_mocked();
+        let exporter = AnalyticsExporter::new(context.clone(), config(&server.url())).unwrap();
+
+        // Half past an hour, so the previous hour is exported, once.
+        let now = OffsetDateTime::from_unix_timestamp(1_709_166_600).unwrap();
+        exporter.export_pending(now).await.unwrap();
+        exporter.export_pending(now).await.unwrap();
+
+        mock.assert_async().await;
+        let last_end = context
+            .get_storage()
+            .get_last_analytics_export_end()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(last_end.unix_timestamp(), 1_709_164_800);
+    }
+}
@@ -0,0 +1,239 @@
+//! This module contains middleware for logging the requests to, and the
+//! responses from, selected endpoints of the signer API.
+//!
+//! The access log is meant for reconstructing what the stacks node
+//! actually delivered to the signer during an incident. It is enabled per
+//! endpoint through the configuration, and is written under the
+//! `signer::api::access_log` tracing target at the info level, so it does
+//! not require enabling debug logging globally. Sensitive fields of JSON
+//! bodies are redacted before they are logged.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::body::Bytes;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+use serde_json::Value;
+
+use crate::config::AccessLogConfig;
+
+use super::compression::ContentEncoding;
+use super::new_block::EVENT_OBSERVER_BODY_LIMIT;
+use super::new_block::EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT;
+
+/// The value that replaces redacted fields in the logged bodies.
+const REDACTED: &str = "[REDACTED]";
+
+/// Log the request and response of the configured endpoints, including
+/// the sizes of the bodies and, up to the configured size, their redacted
+/// contents. Requests to other endpoints pass through untouched.
+pub async fn log_access(
+    State(config): State<Arc<AccessLogConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !config.is_logged(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let start = Instant::now();
+
+    let (parts, body) = request.into_parts();
+    let request_body = match axum::body::to_bytes(body, EVENT_OBSERVER_BODY_LIMIT).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(%method, %path, %error, "could not read the request body");
+            return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+        }
+    };
+    let logged_request = capture_body(&config, &parts.headers, &request_body);
+
+    let request = Request::from_parts(parts, Body::from(request_body.clone()));
+    let (parts, body) = next.run(request).await.into_parts();
+
+    let response_body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(%method, %path, %error, "could not read the response body");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let logged_response = capture_body(&config, &parts.headers, &response_body);
+
+    tracing::info!(
+        target: "signer::api::access_log",
+        %method,
+        %path,
+        status = parts.status.as_u16(),
+        duration_ms = start.elapsed().as_millis(),
+        request_body_size = request_body.len(),
+        request_body = logged_request.as_deref(),
+        response_body_size = response_body.len(),
+        response_body = logged_response.as_deref(),
+        "api access"
+    );
+
+    Response::from_parts(parts, Body::from(response_body))
+}
+
+/// Return the redacted contents of the body as they should be logged.
+///
+/// Bodies sent with `Content-Encoding: gzip` or `Content-Encoding: deflate`
+/// are decompressed first. Only
+/// JSON bodies are captured, since the fields of other bodies cannot be
+/// redacted, and the captured contents are truncated to the configured
+/// size.
+fn capture_body(config: &AccessLogConfig, headers: &HeaderMap, body: &Bytes) -> Option<String> {
+    if config.max_body_bytes == 0 || body.is_empty() {
+        return None;
+    }
+
+    let encoding = ContentEncoding::from_headers(headers)?;
+
+    let decompressed;
+    let contents = if encoding == ContentEncoding::Identity {
+        body.as_ref()
+    } else {
+        decompressed = encoding
+            .decompress(body, EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT)
+            .ok()?;
+        decompressed.as_slice()
+    };
+
+    let Ok(mut value) = serde_json::from_slice::<Value>(contents) else {
+        return Some("<non-JSON body omitted>".to_string());
+    };
+    redact(&mut value, &config.redact_fields);
+
+    let mut captured = value.to_string();
+    if captured.len() > config.max_body_bytes {
+        let mut end = config.max_body_bytes;
+        while !captured.is_char_boundary(end) {
+            end -= 1;
+        }
+        captured.truncate(end);
+        captured.push_str("...");
+    }
+
+    Some(captured)
+}
+
+/// Replace the values of the object fields with any of the given names,
+/// at any depth of the JSON value.
+pub fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for (name, field) in object.iter_mut() {
+                if fields
+                    .iter()
+                    .any(|redacted| redacted.eq_ignore_ascii_case(name))
+                {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field, fields);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact(value, fields)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config(redact_fields: &[&str], max_body_bytes: usize) -> AccessLogConfig {
+        AccessLogConfig {
+            endpoints: vec!["/new_block".to_string()],
+            redact_fields: redact_fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            max_body_bytes,
+        }
+    }
+
+    #[test]
+    fn redact_replaces_fields_at_any_depth() {
+        let mut value = json!({
+            "block_height": 123,
+            "Raw_Tx": "0x00",
+            "transactions": [
+                {"txid": "0x01", "raw_tx": "0x02"},
+                {"txid": "0x03", "nested": {"raw_tx": {"bytes": "0x04"}}},
+            ],
+        });
+        redact(&mut value, &["raw_tx".to_string()]);
+
+        let expected = json!({
+            "block_height": 123,
+            "Raw_Tx": REDACTED,
+            "transactions": [
+                {"txid": "0x01", "raw_tx": REDACTED},
+                {"txid": "0x03", "nested": {"raw_tx": REDACTED}},
+            ],
+        });
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn capture_body_redacts_and_truncates() {
+        let headers = HeaderMap::new();
+        let body = Bytes::from(r#"{"raw_tx":"0x0203"}"#);
+
+        let captured = capture_body(&config(&["raw_tx"], 1024), &headers, &body);
+        assert_eq!(captured.unwrap(), r#"{"raw_tx":"[REDACTED]"}"#);
+
+        let captured = capture_body(&config(&["raw_tx"], 10), &headers, &body);
+        assert_eq!(captured.unwrap(), r#"{"raw_tx":..."#);
+
+        assert!(capture_body(&config(&[], 0), &headers, &body).is_none());
+
+        let captured = capture_body(&config(&[], 1024), &headers, &Bytes::from("hello"));
+        assert_eq!(captured.unwrap(), "<non-JSON body omitted>");
+    }
+
+    #[tokio::test]
+    async fn middleware_preserves_the_request_and_response() {
+        use axum::Router;
+        use axum::routing::post;
+        use tower::ServiceExt as _;
+
+        let app: Router = Router::new()
+            .route("/new_block", post(|body: String| async move { body }))
+            .route("/info", post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(config(&["raw_tx"], 1024)),
+                log_access,
+            ));
+
+        for path in ["/new_block", "/info"] {
+            let data = r#"{"raw_tx":"0x00"}"#;
+            let request = Request::builder()
+                .method("POST")
+                .uri(path)
+                .body(Body::from(data))
+                .unwrap();
+
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(body, data);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Authentication of the requests to the admin endpoints of the signer
+//! API.
+//!
+//! The admin endpoints change how the signer behaves, or return things
+//! that only its operator should see, so their requests are signed with
+//! the secret in `signer.api.admin_secret`. This is not the secret of the
+//! `POST /new_block` webhooks, so that the stacks node, or a proxy in
+//! front of it, cannot make admin requests.
+//!
+//! The [`ADMIN_TIMESTAMP_HEADER`] header holds the unix time, in seconds,
+//! at which the request was made, and the [`ADMIN_SIGNATURE_HEADER`]
+//! header holds the hex encoded HMAC-SHA256 of the request method, path
+//! and query, timestamp and body, keyed by the secret, see
+//! [`sign_admin_request`]. The signature does not carry over to another
+//! endpoint or another body, and requests whose timestamp is more than
+//! [`MAX_ADMIN_REQUEST_AGE`] away from the clock of the signer are
+//! rejected, so a captured request cannot be replayed for long. The admin
+//! endpoints respond with 404 Not Found when no secret is configured, so
+//! that they cannot be used without authentication.
+
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::FromRequest;
+use axum::extract::Request;
+use axum::http::HeaderMap;
+use axum::http::Method;
+use axum::http::StatusCode;
+use bitcoin::hashes::Hash as _;
+use bitcoin::hashes::HashEngine as _;
+use bitcoin::hashes::hmac::Hmac;
+use bitcoin::hashes::hmac::HmacEngine;
+use bitcoin::hashes::sha256;
+use serde::de::DeserializeOwned;
+use time::OffsetDateTime;
+
+use crate::context::Context;
+
+use super::ApiState;
+use super::new_block_auth::constant_time_eq;
+
+/// The header with the unix time, in seconds, at which an admin request
+/// was made.
+pub const ADMIN_TIMESTAMP_HEADER: &str = "x-sbtc-admin-timestamp";
+
+/// The header with the hex encoded HMAC-SHA256 of an admin request.
+pub const ADMIN_SIGNATURE_HEADER: &str = "x-sbtc-admin-signature";
+
+/// How far the timestamp of an admin request may be from the clock of the
+/// signer, in either direction, for the request to be accepted.
+const MAX_ADMIN_REQUEST_AGE: Duration = Duration::from_secs(300);
+
+/// Hex encode the HMAC-SHA256, keyed by the given secret, of an admin
+/// request with the given method, path and query, timestamp and body.
+///
+/// The method, path and timestamp are each followed by a newline, and the
+/// body, which may be empty, comes last.
+pub fn sign_admin_request(
+    secret: &str,
+    method: &Method,
+    path_and_query: &str,
+    timestamp: u64,
+    body: &[u8],
+) -> String {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(format!("{method}\n{path_and_query}\n{timestamp}\n").as_bytes());
+    engine.input(body);
+    hex::encode(Hmac::from_engine(engine).to_byte_array())
+}
+
+/// An authenticated request to one of the admin endpoints.
+///
+/// Extracting this rejects the request with 404 Not Found if no secret is
+/// configured, and with 401 Unauthorized if the request is not signed
+/// with the secret or is too old. Since it consumes the request body, it
+/// must be the last argument of a handler.
+#[derive(Debug)]
+pub struct AdminRequest {
+    body: Bytes,
+}
+
+impl AdminRequest {
+    /// Deserialize the JSON body of the request.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, StatusCode> {
+        serde_json::from_slice(&self.body).map_err(|error| {
+            tracing::debug!(%error, "could not deserialize the body of an admin request");
+            StatusCode::UNPROCESSABLE_ENTITY
+        })
+    }
+}
+
+impl<C: Context> FromRequest<ApiState<C>> for AdminRequest {
+    type Rejection = StatusCode;
+
+    async fn from_request(request: Request, state: &ApiState<C>) -> Result<Self, Self::Rejection> {
+        let Some(secret) = state.ctx.config().signer.api.admin_secret.clone() else {
+            return Err(StatusCode::NOT_FOUND);
+        };
+
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let path_and_query = request
+            .uri()
+            .path_and_query()
+            .map_or_else(|| path.clone(), ToString::to_string);
+        let headers = request.headers().clone();
+        let body = Bytes::from_request(request, state)
+            .await
+            .map_err(|rejection| rejection.status())?;
+
+        let now = u64::try_from(OffsetDateTime::now_utc().unix_timestamp()).unwrap_or_default();
+        if !is_signed(&secret, &method, &path_and_query, &headers, &body, now) {
+            tracing::warn!(%method, %path, "rejecting an unauthenticated admin request");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        Ok(Self { body })
+    }
+}
+
+/// Whether the admin request with the given method, path and query,
+/// headers and body is signed with the given secret, at a time close
+/// enough to the given unix time.
+fn is_signed(
+    secret: &str,
+    method: &Method,
+    path_and_query: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: u64,
+) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let Some(timestamp) = header(ADMIN_TIMESTAMP_HEADER).and_then(|value| value.parse().ok())
+    else {
+        return false;
+    };
+    if now.abs_diff(timestamp) > MAX_ADMIN_REQUEST_AGE.as_secs() {
+        return false;
+    }
+
+    let Some(signature) = header(ADMIN_SIGNATURE_HEADER) else {
+        return false;
+    };
+    let expected = sign_admin_request(secret, method, path_and_query, timestamp, body);
+    let signature = signature.to_ascii_lowercase();
+    constant_time_eq(signature.as_bytes(), expected.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::HeaderValue;
+    use axum::http::Request;
+    use axum::routing::post;
+    use test_case::test_case;
+    use tower::ServiceExt as _;
+
+    use crate::testing::api::ADMIN_SECRET;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    const PATH: &str = "/admin/value?verbose=true";
+    const BODY: &[u8] = br#"{"value":7}"#;
+    const NOW: u64 = 1_700_000_000;
+
+    #[derive(serde::Deserialize)]
+    struct Value {
+        value: u64,
+    }
+
+    async fn value_handler<C: Context>(
+        _: axum::extract::State<ApiState<C>>,
+        request: AdminRequest,
+    ) -> Result<String, StatusCode> {
+        let Value { value } = request.json()?;
+        Ok(value.to_string())
+    }
+
+    fn headers(timestamp: u64, signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ADMIN_TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+        let signature = HeaderValue::from_str(signature).unwrap();
+        headers.insert(ADMIN_SIGNATURE_HEADER, signature);
+        headers
+    }
+
+    /// Whether a `POST` request to [`PATH`] with [`BODY`] and the given
+    /// headers, received at [`NOW`], is signed with [`ADMIN_SECRET`].
+    fn is_post_signed(headers: &HeaderMap) -> bool {
+        is_signed(ADMIN_SECRET, &Method::POST, PATH, headers, BODY, NOW)
+    }
+
+    #[test_case(ADMIN_SECRET, &Method::POST, PATH, NOW, BODY, true; "signed")]
+    #[test_case(ADMIN_SECRET, &Method::POST, PATH, NOW - 300, BODY, true; "oldest")]
+    #[test_case(ADMIN_SECRET, &Method::POST, PATH, NOW - 301, BODY, false; "too-old")]
+    #[test_case(ADMIN_SECRET, &Method::POST, PATH, NOW + 301, BODY, false; "too-new")]
+    #[test_case("another-admin-secret", &Method::POST, PATH, NOW, BODY, false; "wrong-secret")]
+    #[test_case(ADMIN_SECRET, &Method::DELETE, PATH, NOW, BODY, false; "another-method")]
+    #[test_case(ADMIN_SECRET, &Method::POST, "/admin/value", NOW, BODY, false; "another-path")]
+    #[test_case(ADMIN_SECRET, &Method::POST, PATH, NOW, b"{}", false; "another-body")]
+    fn admin_requests_must_be_signed_recently(
+        secret: &str,
+        method: &Method,
+        path_and_query: &str,
+        timestamp: u64,
+        body: &[u8],
+        expected: bool,
+    ) {
+        let signature = sign_admin_request(secret, method, path_and_query, timestamp, body);
+        let headers = headers(timestamp, &signature);
+        assert_eq!(is_post_signed(&headers), expected);
+    }
+
+    #[test]
+    fn admin_requests_without_a_signature_are_rejected() {
+        let mut headers = headers(NOW, "");
+        headers.remove(ADMIN_SIGNATURE_HEADER);
+        assert!(!is_post_signed(&headers));
+
+        let signature = sign_admin_request(ADMIN_SECRET, &Method::POST, PATH, NOW, BODY);
+        let mut headers = headers(NOW, &signature);
+        headers.remove(ADMIN_TIMESTAMP_HEADER);
+        assert!(!is_post_signed(&headers));
+    }
+
+    #[test_case(false, true, StatusCode::NOT_FOUND; "no-secret")]
+    #[test_case(true, false, StatusCode::UNAUTHORIZED; "unsigned")]
+    #[test_case(true, true, StatusCode::OK; "signed")]
+    #[tokio::test]
+    async fn admin_requests_are_authenticated(
+        with_secret: bool,
+        signed: bool,
+        expected_status: StatusCode,
+    ) {
+        let ctx = if with_secret {
+            TestContext::with_admin_secret()
+        } else {
+            TestContext::default_mocked()
+        };
+        let app = Router::new()
+            .route("/admin/value", post(value_handler))
+            .with_state(ApiState { ctx });
+
+        let body = serde_json::json!({ "value": 7 });
+        let request = if signed {
+            admin_request(Method::POST, PATH, Some(body))
+        } else {
+            Request::builder()
+                .uri(PATH)
+                .method(Method::POST)
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected_status);
+    }
+}
@@ -0,0 +1,223 @@
+//! Response caching with ETag support for the public read endpoints.
+//!
+//! Explorer frontends tend to poll the read endpoints frequently, and
+//! each request can hit the database, bitcoin-core and the stacks node.
+//! The [`etag_cache`] middleware serves a cached copy of the response for
+//! a short period of time, and answers conditional requests that carry a
+//! matching `If-None-Match` header with a `304 Not Modified`.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::body::Bytes;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+use sha2::Digest as _;
+use tokio::sync::Mutex;
+
+/// The amount of time that a cached response for a public read endpoint
+/// is served before the underlying handler is called again.
+pub const PUBLIC_READ_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// A cache holding the most recent successful response of an endpoint.
+#[derive(Debug)]
+pub struct ResponseCache {
+    /// How long a cached response is considered fresh.
+    ttl: Duration,
+    /// The cached response, if any. The lock is held while the response
+    /// is being refreshed, so that concurrent requests wait for the
+    /// refreshed response instead of all calling the handler.
+    entry: Mutex<Option<CachedResponse>>,
+}
+
+impl ResponseCache {
+    /// Create a new empty cache whose entries are fresh for the given
+    /// duration.
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entry: Mutex::new(None) }
+    }
+}
+
+/// A successful response along with the ETag computed from its body.
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    headers: HeaderMap,
+    body: Bytes,
+    etag: HeaderValue,
+    created_at: Instant,
+}
+
+impl CachedResponse {
+    fn new(headers: HeaderMap, body: Bytes) -> Self {
+        let digest = sha2::Sha256::digest(&body);
+        let etag = format!("\"{}\"", hex::encode(digest));
+        Self {
+            headers,
+            body,
+            // A hex encoded digest in quotes is always a valid header value.
+            etag: HeaderValue::from_str(&etag).expect("ETag is a valid header value"),
+            created_at: Instant::now(),
+        }
+    }
+
+    /// Whether the ETag of this response matches any of the entity tags in
+    /// the given `If-None-Match` header value.
+    fn matches(&self, if_none_match: &HeaderValue) -> bool {
+        let Ok(tags) = if_none_match.to_str() else {
+            return false;
+        };
+        let Ok(etag) = self.etag.to_str() else {
+            return false;
+        };
+
+        tags.split(',')
+            .map(str::trim)
+            .map(|tag| tag.strip_prefix("W/").unwrap_or(tag))
+            .any(|tag| tag == "*" || tag == etag)
+    }
+
+    fn respond(self, if_none_match: Option<&HeaderValue>, ttl: Duration) -> Response {
+        let cache_control = format!("public, max-age={}", ttl.as_secs());
+        let cache_control =
+            HeaderValue::from_str(&cache_control).expect("Cache-Control is a valid header value");
+
+        let mut response = if if_none_match.is_some_and(|value| self.matches(value)) {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            let mut response = Response::new(Body::from(self.body));
+            *response.headers_mut() = self.headers;
+            response
+        };
+
+        let headers = response.headers_mut();
+        headers.insert(header::ETAG, self.etag);
+        headers.insert(header::CACHE_CONTROL, cache_control);
+        response
+    }
+}
+
+/// Middleware that caches successful responses of the wrapped endpoint
+/// and handles `If-None-Match` conditional requests.
+///
+/// Only `200 OK` responses are cached; any other response is passed
+/// through untouched.
+pub async fn etag_cache(
+    State(cache): State<Arc<ResponseCache>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let mut entry = cache.entry.lock().await;
+
+    let cached = match entry.as_ref() {
+        Some(cached) if cached.created_at.elapsed() < cache.ttl => cached.clone(),
+        _ => {
+            let response = next.run(request).await;
+            if response.status() != StatusCode::OK {
+                return response;
+            }
+
+            let (parts, body) = response.into_parts();
+            let body = match axum::body::to_bytes(body, usize::MAX).await {
+                Ok(body) => body,
+                Err(error) => {
+                    tracing::warn!(%error, "could not read the body of a response to cache");
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            };
+
+            let cached = CachedResponse::new(parts.headers, body);
+            *entry = Some(cached.clone());
+            cached
+        }
+    };
+    drop(entry);
+
+    cached.respond(if_none_match.as_ref(), cache.ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use axum::Router;
+    use axum::http::Method;
+    use axum::middleware;
+    use axum::routing::get;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    fn router(ttl: Duration, calls: Arc<AtomicUsize>) -> Router {
+        let cache = Arc::new(ResponseCache::new(ttl));
+        let handler = move || {
+            let count = calls.fetch_add(1, Ordering::SeqCst);
+            async move { format!("response {count}") }
+        };
+
+        Router::new().route(
+            "/stats",
+            get(handler).layer(middleware::from_fn_with_state(cache, etag_cache)),
+        )
+    }
+
+    fn request(if_none_match: Option<&HeaderValue>) -> Request {
+        let mut builder = Request::builder().uri("/stats").method(Method::GET);
+        if let Some(value) = if_none_match {
+            builder = builder.header(header::IF_NONE_MATCH, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_responses_honor_if_none_match() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = router(Duration::from_secs(60), calls.clone());
+
+        let response = app.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+        // A matching ETag gets a 304 without calling the handler again.
+        let response = app.clone().oneshot(request(Some(&etag))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG), Some(&etag));
+
+        // A stale ETag gets the full cached response.
+        let stale = HeaderValue::from_static("\"stale\"");
+        let response = app.clone().oneshot(request(Some(&stale))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "response 0");
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_responses_are_refreshed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = router(Duration::ZERO, calls.clone());
+
+        let response = app.clone().oneshot(request(None)).await.unwrap();
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+        // The body changed, so the old ETag no longer matches.
+        let response = app.clone().oneshot(request(Some(&etag))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(header::ETAG), Some(&etag));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,419 @@
+//! This module contains middleware for accepting compressed request
+//! bodies.
+//!
+//! Some deployments route the webhooks of the stacks node through
+//! compressing proxies, which send the body with `Content-Encoding: gzip`
+//! or `Content-Encoding: deflate`. The middleware here decompresses such
+//! bodies before they reach the handler. The size of the body is limited
+//! both as it is sent and after it is decompressed, so that a small
+//! compressed body cannot expand into an unbounded one.
+
+use axum::body::Body;
+use axum::body::Bytes;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+
+/// The CRC-32 algorithm used in the gzip trailer.
+const GZIP_CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+/// The flags in the gzip header that signal optional header fields.
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+/// The reasons that a compressed body cannot be decompressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecompressionError {
+    /// The body is not well-formed for its content encoding.
+    Malformed,
+    /// The decompressed body exceeds the size limit.
+    TooLarge,
+}
+
+impl From<DecompressionError> for StatusCode {
+    fn from(value: DecompressionError) -> Self {
+        match value {
+            DecompressionError::Malformed => StatusCode::BAD_REQUEST,
+            DecompressionError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
+/// The content encodings that request bodies may be sent with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// The body is not compressed.
+    Identity,
+    /// The body is a gzip member, as described in RFC 1952.
+    Gzip,
+    /// The body is a zlib stream, as described in RFC 1950.
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Return the content encoding of a body sent with the given headers,
+    /// or `None` if the encoding is not supported.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let Some(value) = headers.get(header::CONTENT_ENCODING) else {
+            return Some(Self::Identity);
+        };
+        let encoding = value.to_str().ok()?.trim();
+
+        [
+            ("identity", Self::Identity),
+            ("gzip", Self::Gzip),
+            ("deflate", Self::Deflate),
+        ]
+        .into_iter()
+        .find(|(name, _)| encoding.eq_ignore_ascii_case(name))
+        .map(|(_, content_encoding)| content_encoding)
+    }
+
+    /// Decompress a body with this content encoding, failing if the
+    /// decompressed data would be larger than `limit` bytes.
+    pub fn decompress(self, data: &[u8], limit: usize) -> Result<Vec<u8>, DecompressionError> {
+        match self {
+            Self::Identity if data.len() > limit => Err(DecompressionError::TooLarge),
+            Self::Identity => Ok(data.to_vec()),
+            Self::Gzip => gunzip(data, limit),
+            Self::Deflate => inflate_zlib(data, limit),
+        }
+    }
+}
+
+/// The limits on the size of a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// The maximum size of the body as it is sent, which for compressed
+    /// bodies is their compressed size.
+    pub encoded: usize,
+    /// The maximum size of a compressed body once it is decompressed.
+    pub decoded: usize,
+}
+
+/// Decompress a request body sent with `Content-Encoding: gzip` or
+/// `Content-Encoding: deflate`, so that the handler sees the plain body.
+/// Bodies without a content encoding, or with the `identity` encoding,
+/// pass through untouched, while any other encoding is rejected with
+/// `415 Unsupported Media Type`.
+///
+/// Bodies larger than the encoded limit are rejected with `413 Payload Too
+/// Large`, whatever their encoding, and so are compressed bodies that are
+/// larger than the decoded limit once they are decompressed.
+pub async fn decompress_body(
+    State(limits): State<BodyLimits>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(encoding) = ContentEncoding::from_headers(request.headers()) else {
+        let encoding = request.headers().get(header::CONTENT_ENCODING);
+        tracing::warn!(?encoding, "unsupported content encoding in the request");
+        return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let encoded = match axum::body::to_bytes(body, limits.encoded).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(%error, ?encoding, "could not read the request body");
+            return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+        }
+    };
+
+    if encoding == ContentEncoding::Identity {
+        return next
+            .run(Request::from_parts(parts, Body::from(encoded)))
+            .await;
+    }
+
+    let decompressed = match encoding.decompress(&encoded, limits.decoded) {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(
+                ?error,
+                ?encoding,
+                compressed_size = encoded.len(),
+                "could not decompress the request body"
+            );
+            return StatusCode::from(error).into_response();
+        }
+    };
+
+    tracing::trace!(
+        ?encoding,
+        compressed_size = encoded.len(),
+        decompressed_size = decompressed.len(),
+        "decompressed request body"
+    );
+
+    parts.headers.remove(header::CONTENT_ENCODING);
+    parts.headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from(decompressed.len()),
+    );
+
+    let body = Body::from(Bytes::from(decompressed));
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Decompress a single gzip member, as described in RFC 1952, failing if
+/// the decompressed data would be larger than `limit` bytes.
+fn gunzip(data: &[u8], limit: usize) -> Result<Vec<u8>, DecompressionError> {
+    // The fixed part of the header is ten bytes: the magic number, the
+    // compression method, flags, modification time, extra flags and the
+    // operating system.
+    let header = data.get(..10).ok_or(DecompressionError::Malformed)?;
+    if header[..3] != [0x1f, 0x8b, 0x08] {
+        return Err(DecompressionError::Malformed);
+    }
+    let flags = header[3];
+    let mut position = 10;
+
+    if flags & FLAG_EXTRA != 0 {
+        let length = data
+            .get(position..position + 2)
+            .ok_or(DecompressionError::Malformed)?;
+        position += 2 + u16::from_le_bytes([length[0], length[1]]) as usize;
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            let terminator = data
+                .get(position..)
+                .and_then(|rest| rest.iter().position(|byte| *byte == 0))
+                .ok_or(DecompressionError::Malformed)?;
+            position += terminator + 1;
+        }
+    }
+    if flags & FLAG_HCRC != 0 {
+        position += 2;
+    }
+
+    // The compressed blocks are followed by an eight byte trailer with
+    // the CRC-32 and the size, modulo 2^32, of the decompressed data.
+    let trailer_start = data
+        .len()
+        .checked_sub(8)
+        .filter(|start| *start >= position)
+        .ok_or(DecompressionError::Malformed)?;
+    let deflated = &data[position..trailer_start];
+    let trailer = &data[trailer_start..];
+
+    let inflated = miniz_oxide::inflate::decompress_to_vec_with_limit(deflated, limit)
+        .map_err(inflate_error)?;
+
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if GZIP_CRC.checksum(&inflated) != crc || inflated.len() as u32 != size {
+        return Err(DecompressionError::Malformed);
+    }
+
+    Ok(inflated)
+}
+
+/// Decompress a zlib stream, as described in RFC 1950, failing if the
+/// decompressed data would be larger than `limit` bytes. This is what
+/// HTTP calls the `deflate` content encoding.
+fn inflate_zlib(data: &[u8], limit: usize) -> Result<Vec<u8>, DecompressionError> {
+    // The Adler-32 checksum in the trailer of the stream is checked while
+    // it is decompressed.
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, limit).map_err(inflate_error)
+}
+
+/// Classify the reason that the compressed blocks could not be inflated.
+fn inflate_error(error: miniz_oxide::inflate::DecompressError) -> DecompressionError {
+    match error.status {
+        miniz_oxide::inflate::TINFLStatus::HasMoreOutput => DecompressionError::TooLarge,
+        _ => DecompressionError::Malformed,
+    }
+}
+
+/// Compress the given data into a gzip member.
+#[cfg(test)]
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut member = vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff];
+    member.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    member.extend_from_slice(&GZIP_CRC.checksum(data).to_le_bytes());
+    member.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    member
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gunzip_round_trips() {
+        let data = br#"{"block_height": 123, "events": []}"#;
+        let compressed = gzip(data);
+        assert_eq!(gunzip(&compressed, 1024).unwrap(), data);
+
+        assert_eq!(gunzip(&gzip(&[]), 1024).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn gunzip_enforces_the_decompressed_size_limit() {
+        let data = vec![b'a'; 100_000];
+        let compressed = gzip(&data);
+
+        assert_eq!(gunzip(&compressed, 2 * data.len()).unwrap(), data);
+        assert_eq!(
+            gunzip(&compressed, data.len() / 2),
+            Err(DecompressionError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn gunzip_rejects_malformed_bodies() {
+        let data = b"hello world";
+        let compressed = gzip(data);
+
+        assert_eq!(
+            gunzip(b"hello world", 1024),
+            Err(DecompressionError::Malformed)
+        );
+        assert_eq!(
+            gunzip(&compressed[..12], 1024),
+            Err(DecompressionError::Malformed)
+        );
+
+        let mut corrupted = compressed.clone();
+        let last = corrupted.len() - 9;
+        corrupted[last] ^= 0xff;
+        assert_eq!(gunzip(&corrupted, 1024), Err(DecompressionError::Malformed));
+    }
+
+    #[test]
+    fn deflate_bodies_are_zlib_streams() {
+        let data = br#"{"block_height": 123, "events": []}"#;
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+        let deflate = ContentEncoding::Deflate;
+        assert_eq!(deflate.decompress(&compressed, 1024).unwrap(), data);
+
+        assert_eq!(
+            deflate.decompress(&compressed, data.len() - 1),
+            Err(DecompressionError::TooLarge)
+        );
+
+        // A corrupted Adler-32 checksum in the trailer is caught.
+        let mut corrupted = compressed.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert_eq!(
+            deflate.decompress(&corrupted, 1024),
+            Err(DecompressionError::Malformed)
+        );
+    }
+
+    #[test]
+    fn content_encoding_is_read_from_the_headers() {
+        let encoding = |value: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(value) = value {
+                headers.insert(
+                    header::CONTENT_ENCODING,
+                    HeaderValue::from_str(value).unwrap(),
+                );
+            }
+            ContentEncoding::from_headers(&headers)
+        };
+
+        assert_eq!(encoding(None), Some(ContentEncoding::Identity));
+        assert_eq!(encoding(Some("identity")), Some(ContentEncoding::Identity));
+        assert_eq!(encoding(Some(" GZIP ")), Some(ContentEncoding::Gzip));
+        assert_eq!(encoding(Some("deflate")), Some(ContentEncoding::Deflate));
+        assert_eq!(encoding(Some("br")), None);
+        assert_eq!(encoding(Some("gzip, br")), None);
+    }
+
+    async fn post_body(encoding: Option<&str>, body: Vec<u8>) -> (StatusCode, Bytes) {
+        use axum::Router;
+        use axum::routing::post;
+        use tower::ServiceExt as _;
+
+        let app: Router = Router::new()
+            .route("/", post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn_with_state(
+                BodyLimits { encoded: 64, decoded: 256 },
+                decompress_body,
+            ));
+
+        let mut request = Request::builder().method("POST").uri("/");
+        if let Some(encoding) = encoding {
+            request = request.header(header::CONTENT_ENCODING, encoding);
+        }
+        let request = request.body(Body::from(body)).unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn middleware_decompresses_bodies() {
+        let data = b"a new block".to_vec();
+
+        let (status, body) = post_body(Some("gzip"), gzip(&data)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, data);
+
+        let deflated = miniz_oxide::deflate::compress_to_vec_zlib(&data, 6);
+        let (status, body) = post_body(Some("deflate"), deflated).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, data);
+
+        let (status, body) = post_body(None, data.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, data);
+
+        let (status, _) = post_body(Some("br"), data.clone()).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (status, _) = post_body(Some("gzip"), data).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // The compressed body fits within the limit but the decompressed
+        // one does not.
+        let compressed = gzip(&[b'a'; 1000]);
+        assert!(compressed.len() < 64);
+        let (status, _) = post_body(Some("gzip"), compressed).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn middleware_limits_the_size_of_bodies_as_they_are_sent() {
+        // Compressed bodies may decompress to more than the encoded limit.
+        let data = vec![b'a'; 200];
+        let compressed = gzip(&data);
+        assert!(compressed.len() < 64);
+        let (status, body) = post_body(Some("gzip"), compressed).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, data);
+
+        // But the encoded limit applies to what is sent, so random data,
+        // which does not compress, is rejected before it is decompressed.
+        let noise: Vec<u8> = (0..128).map(|_| rand::random::<u8>()).collect();
+        let compressed = gzip(&noise);
+        assert!(compressed.len() > 64);
+        let (status, _) = post_body(Some("gzip"), compressed).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let deflated = miniz_oxide::deflate::compress_to_vec_zlib(&noise, 6);
+        let (status, _) = post_body(Some("deflate"), deflated).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Plain bodies are held to the encoded limit.
+        let (status, _) = post_body(None, data).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
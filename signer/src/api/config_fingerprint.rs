@@ -0,0 +1,137 @@
+//! Handler for the `GET /config/fingerprint` endpoint, which reports the
+//! fingerprint of this signer's consensus-critical configuration along
+//! with the fingerprints that its peers sent in their heartbeats.
+//!
+//! Operators of the federation can compare the fingerprints to check that
+//! all signers run the same validation policies. The response also
+//! contains the configuration that the fingerprint was computed over, so
+//! that operators can tell which values differ. It only holds values that
+//! the members of the federation are expected to agree on, so it never
+//! includes secrets or endpoints.
+
+use axum::Json;
+use axum::extract::State;
+use serde::Serialize;
+
+use crate::config::fingerprint::ConsensusConfig;
+use crate::context::Context;
+
+use super::ApiState;
+
+/// The configuration fingerprint that a peer sent us.
+#[derive(Debug, Serialize)]
+pub struct PeerFingerprint {
+    /// The public key of the peer.
+    pub public_key: String,
+    /// The hex encoded fingerprint from the most recent heartbeat of the
+    /// peer.
+    pub fingerprint: String,
+    /// Whether the fingerprint of the peer matches ours.
+    pub matches: bool,
+}
+
+/// The response of the `GET /config/fingerprint` endpoint.
+#[derive(Debug, Serialize)]
+pub struct ConfigFingerprintResponse {
+    /// The hex encoded fingerprint of our consensus-critical
+    /// configuration.
+    pub fingerprint: String,
+    /// The consensus-critical configuration that the fingerprint was
+    /// computed over.
+    pub config: ConsensusConfig,
+    /// The fingerprints of the peers that sent us one, sorted by public
+    /// key.
+    pub peers: Vec<PeerFingerprint>,
+}
+
+/// Handler for the `GET /config/fingerprint` endpoint.
+pub async fn config_fingerprint_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Json<ConfigFingerprintResponse> {
+    let config = ConsensusConfig::new(state.ctx.config());
+    let fingerprint = config.fingerprint();
+
+    let mut peers: Vec<PeerFingerprint> = state
+        .ctx
+        .state()
+        .peer_config_fingerprints()
+        .into_iter()
+        .map(|(public_key, peer_fingerprint)| PeerFingerprint {
+            public_key: public_key.to_string(),
+            fingerprint: hex::encode(peer_fingerprint),
+            matches: peer_fingerprint == fingerprint,
+        })
+        .collect();
+    peers.sort_by(|a, b| a.public_key.cmp(&b.public_key));
+
+    Json(ConfigFingerprintResponse {
+        fingerprint: hex::encode(fingerprint),
+        config,
+        peers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use axum::http::StatusCode;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::keys::PublicKey;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fingerprints_of_peers_are_compared() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let fingerprint = ConsensusConfig::new(context.config()).fingerprint();
+        let same: PublicKey = Faker.fake();
+        let divergent: PublicKey = Faker.fake();
+        context
+            .state()
+            .set_peer_config_fingerprint(same, fingerprint);
+        context
+            .state()
+            .set_peer_config_fingerprint(divergent, [1; 32]);
+
+        let request = Request::builder()
+            .uri("/config/fingerprint")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["fingerprint"], hex::encode(fingerprint));
+        assert_eq!(
+            body["config"]["network"],
+            context.config().signer.network.to_string()
+        );
+        assert!(body["config"].get("db_endpoint").is_none());
+
+        let peers = body["peers"].as_array().unwrap();
+        assert_eq!(peers.len(), 2);
+        let find = |public_key: PublicKey| {
+            peers
+                .iter()
+                .find(|peer| peer["public_key"] == public_key.to_string())
+                .unwrap()
+        };
+        assert_eq!(find(same)["matches"], true);
+        assert_eq!(find(divergent)["matches"], false);
+        assert_eq!(find(divergent)["fingerprint"], hex::encode([1; 32]));
+    }
+}
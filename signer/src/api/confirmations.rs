@@ -0,0 +1,327 @@
+//! Handlers for managing confirmation watches.
+//!
+//! A watch asks for a notification once a bitcoin transaction has a given
+//! number of confirmations, see [`crate::confirmation_watches`].
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::ConfirmationWatch;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// The largest number of confirmations that a watch can wait for.
+const MAX_CONFIRMATIONS: u32 = 10_000;
+
+/// The largest number of watches with a callback URL that may be waiting
+/// to fire at any time. Each of them is persisted and checked against
+/// every new bitcoin block, so further watches are refused until some of
+/// them fire or are removed.
+const MAX_WATCHES: usize = 1_000;
+
+/// A watch on a bitcoin transaction.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfirmationWatchBody {
+    /// The ID of the transaction to watch.
+    pub txid: bitcoin::Txid,
+    /// The number of confirmations at which the watch fires.
+    pub confirmations: u32,
+    /// The URL to notify when the watch fires.
+    pub callback_url: String,
+}
+
+impl ConfirmationWatchBody {
+    /// Convert the body into a watch, returning `None` if the number of
+    /// confirmations is out of range or the callback URL is not an HTTP
+    /// or HTTPS URL.
+    fn to_watch(&self) -> Option<ConfirmationWatch> {
+        if !(1..=MAX_CONFIRMATIONS).contains(&self.confirmations) {
+            return None;
+        }
+        // URLs are stored in their normalized form, like the URLs of
+        // webhook subscribers.
+        let url: Url = self.callback_url.parse().ok()?;
+        if !["http", "https"].contains(&url.scheme()) {
+            return None;
+        }
+        Some(ConfirmationWatch {
+            txid: self.txid.into(),
+            confirmations: self.confirmations,
+            callback_url: Some(url.to_string()),
+        })
+    }
+}
+
+/// Handler for the `GET /confirmations` endpoint, which lists the watches
+/// with a callback URL that have not fired yet. Like the URLs of webhook
+/// subscribers, callback URLs may carry credentials, so they are only
+/// listed for admin requests.
+pub async fn list_watches_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> Result<Json<Vec<ConfirmationWatchBody>>, StatusCode> {
+    let watches = state
+        .ctx
+        .get_storage()
+        .get_confirmation_watches()
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch confirmation watches");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let watches = watches
+        .into_iter()
+        .filter_map(|watch| {
+            Some(ConfirmationWatchBody {
+                txid: watch.txid.into(),
+                confirmations: watch.confirmations,
+                callback_url: watch.callback_url?,
+            })
+        })
+        .collect();
+    Ok(Json(watches))
+}
+
+/// Handler for the `POST /confirmations` endpoint, which registers a
+/// watch. Returns `429 Too Many Requests` if [`MAX_WATCHES`] watches are
+/// already waiting to fire.
+pub async fn register_watch_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let body: ConfirmationWatchBody = match request.json() {
+        Ok(body) => body,
+        Err(status) => return status,
+    };
+    let Some(watch) = body.to_watch() else {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    };
+
+    let watches = match state.ctx.get_storage().get_confirmation_watches().await {
+        Ok(watches) => watches,
+        Err(error) => {
+            tracing::error!(%error, "could not fetch confirmation watches");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+    // Registering a watch that already exists is a no-op, so it is not
+    // refused when we are at the limit.
+    let registered = watches.iter().filter(|w| w.callback_url.is_some()).count();
+    if registered >= MAX_WATCHES && !watches.contains(&watch) {
+        tracing::warn!(txid = %watch.txid, "too many confirmation watches; refusing watch");
+        return StatusCode::TOO_MANY_REQUESTS;
+    }
+
+    match state
+        .ctx
+        .get_storage_mut()
+        .write_confirmation_watch(&watch)
+        .await
+    {
+        Ok(()) => {
+            tracing::info!(
+                txid = %watch.txid,
+                confirmations = watch.confirmations,
+                "registered confirmation watch"
+            );
+            StatusCode::CREATED
+        }
+        Err(error) => {
+            tracing::error!(%error, txid = %watch.txid, "could not register confirmation watch");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handler for the `DELETE /confirmations` endpoint, which removes a watch
+/// that has not fired yet.
+pub async fn remove_watch_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let body: ConfirmationWatchBody = match request.json() {
+        Ok(body) => body,
+        Err(status) => return status,
+    };
+    let Some(watch) = body.to_watch() else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    match state
+        .ctx
+        .get_storage_mut()
+        .delete_confirmation_watch(&watch)
+        .await
+    {
+        Ok(true) => {
+            tracing::info!(txid = %watch.txid, "removed confirmation watch");
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(error) => {
+            tracing::error!(%error, txid = %watch.txid, "could not remove confirmation watch");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    const TXID: &str = "0c3f1f9a4e7bf6e0b1a4d8a5d9cfa7e2b6c4f1d0e9a8b7c6d5e4f3a2b1c0d9e8";
+
+    fn request(method: Method, confirmations: u32, url: &str) -> Request<Body> {
+        let body = serde_json::json!({
+            "txid": TXID,
+            "confirmations": confirmations,
+            "callback_url": url,
+        });
+        admin_request(method, "/confirmations", Some(body))
+    }
+
+    #[tokio::test]
+    async fn watches_can_be_registered_and_removed() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+        let url = "https://example.com/confirmed";
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, 6, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let watches = context
+            .get_storage()
+            .get_confirmation_watches()
+            .await
+            .unwrap();
+        let txid: bitcoin::Txid = TXID.parse().unwrap();
+        let watch = ConfirmationWatch {
+            txid: txid.into(),
+            confirmations: 6,
+            callback_url: Some(url.to_string()),
+        };
+        assert_eq!(watches, [watch]);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::GET, "/confirmations", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed[0]["callback_url"], url);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, 6, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, 6, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn invalid_watches_are_rejected() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        for (confirmations, url) in [
+            (0, "https://example.com/confirmed"),
+            (MAX_CONFIRMATIONS + 1, "https://example.com/confirmed"),
+            (6, "ftp://example.com/confirmed"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(request(Method::POST, confirmations, url))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        let watches = context
+            .get_storage()
+            .get_confirmation_watches()
+            .await
+            .unwrap();
+        assert!(watches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn watches_are_refused_beyond_the_limit() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+        let url = "https://example.com/confirmed";
+
+        let storage = context.get_storage_mut();
+        for confirmations in 1..=MAX_WATCHES as u32 {
+            let watch = ConfirmationWatch {
+                txid: TXID.parse::<bitcoin::Txid>().unwrap().into(),
+                confirmations,
+                callback_url: Some(url.to_string()),
+            };
+            storage.write_confirmation_watch(&watch).await.unwrap();
+        }
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, MAX_WATCHES as u32 + 1, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Registering a watch that already exists is still accepted, and
+        // removing one makes room for another.
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, 6, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, 6, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, MAX_WATCHES as u32 + 1, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+}
@@ -0,0 +1,114 @@
+//! Handler for inspecting the crash reports of the signer.
+//!
+//! A crash report is written whenever one of the long-lived components of
+//! the signer panics, see [`crate::crash_report`]. This endpoint lets an
+//! operator look at the most recent reports without access to the
+//! database.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::CrashReport;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// The maximum number of crash reports that are listed.
+const MAX_CRASH_REPORTS: u16 = 50;
+
+/// The crash report of a panic in a component of the signer.
+#[derive(Debug, Serialize)]
+pub struct CrashReportView {
+    /// The name of the component that panicked.
+    pub component: String,
+    /// The message of the panic.
+    pub message: String,
+    /// The backtrace of the panic, if one was captured.
+    pub backtrace: Option<String>,
+    /// Descriptions of the signals that the signer handled before the
+    /// panic, oldest first.
+    pub recent_events: Vec<String>,
+    /// The hash of the bitcoin chain tip at the time of the panic.
+    pub bitcoin_chain_tip: Option<String>,
+    /// The height of the bitcoin chain tip at the time of the panic.
+    pub bitcoin_chain_tip_height: Option<BitcoinBlockHeight>,
+    /// The last stacks block whose webhook the signer handled before the
+    /// panic.
+    pub stacks_chain_tip: Option<String>,
+    /// When the component panicked.
+    pub created_at: String,
+}
+
+impl From<CrashReport> for CrashReportView {
+    fn from(report: CrashReport) -> Self {
+        Self {
+            component: report.component,
+            message: report.message,
+            backtrace: report.backtrace,
+            recent_events: report.recent_events,
+            bitcoin_chain_tip: report.bitcoin_chain_tip.map(|hash| hash.to_string()),
+            bitcoin_chain_tip_height: report.bitcoin_chain_tip_height,
+            stacks_chain_tip: report.stacks_chain_tip.map(|hash| hash.to_string()),
+            created_at: report.created_at.to_string(),
+        }
+    }
+}
+
+/// Handler for the `GET /admin/crash_reports` endpoint, which lists the
+/// most recent crash reports, newest first.
+pub async fn list_crash_reports_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> Result<Json<Vec<CrashReportView>>, StatusCode> {
+    let reports = state
+        .ctx
+        .get_storage()
+        .get_crash_reports(MAX_CRASH_REPORTS)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch crash reports");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(reports.into_iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::http::Method;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::crash_report::isolate;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn crash_reports_are_listed() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let result = isolate("faulty", &context, async { panic!("boom") }).await;
+        assert!(result.is_err());
+
+        let request = admin_request(Method::GET, "/admin/crash_reports", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed[0]["component"], "faulty");
+        assert_eq!(listed[0]["message"], "boom");
+        assert!(listed[0]["backtrace"].is_string());
+    }
+}
@@ -0,0 +1,87 @@
+//! Handler for submitting deposit requests directly to the signer.
+//!
+//! Deposit requests normally reach the signer through Emily. This lets an
+//! operator hand the signer a deposit request that Emily does not know
+//! about, say while Emily is down. The request goes through the same
+//! validation as the ones from Emily and is recorded with the `api` intake
+//! source.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use sbtc::deposits::CreateDepositRequest;
+use serde::Deserialize;
+
+use crate::block_observer::BlockObserver;
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::model::DepositRequestSource;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// A deposit request submitted to the signer.
+#[derive(Debug, Deserialize)]
+pub struct SubmitDepositRequest {
+    /// The transaction ID of the deposit transaction.
+    pub bitcoin_txid: Txid,
+    /// The index of the deposit UTXO in the deposit transaction.
+    pub bitcoin_tx_output_index: u32,
+    /// The hex encoded reclaim script of the deposit.
+    pub reclaim_script: ScriptBuf,
+    /// The hex encoded deposit script of the deposit.
+    pub deposit_script: ScriptBuf,
+}
+
+/// Handler for the `POST /admin/deposits` endpoint, which validates the
+/// given deposit request and stores it if it is valid.
+///
+/// Responds with 201 Created once the deposit request is stored, and with
+/// 422 Unprocessable Entity if it did not pass validation.
+pub async fn submit_deposit_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let deposit: SubmitDepositRequest = match request.json() {
+        Ok(deposit) => deposit,
+        Err(status) => return status,
+    };
+    let request = CreateDepositRequest {
+        outpoint: OutPoint::new(deposit.bitcoin_txid, deposit.bitcoin_tx_output_index),
+        reclaim_script: deposit.reclaim_script,
+        deposit_script: deposit.deposit_script,
+    };
+    let outpoint = request.outpoint;
+
+    let processor = BlockObserver {
+        context: state.ctx.clone(),
+        bitcoin_block_source: (),
+    };
+    if let Err(error) = processor
+        .load_requests(&[request], DepositRequestSource::Api)
+        .await
+    {
+        tracing::error!(%error, %outpoint, "could not load the submitted deposit request");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    let txid = outpoint.txid.into();
+    match state
+        .ctx
+        .get_storage()
+        .deposit_request_exists(&txid, outpoint.vout)
+        .await
+    {
+        Ok(true) => {
+            tracing::info!(%outpoint, "stored a deposit request submitted to the API");
+            StatusCode::CREATED
+        }
+        Ok(false) => StatusCode::UNPROCESSABLE_ENTITY,
+        Err(error) => {
+            tracing::error!(%error, %outpoint, "could not check for the deposit request");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
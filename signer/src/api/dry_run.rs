@@ -0,0 +1,147 @@
+//! Handlers for controlling coordinator dry runs.
+//!
+//! See [`crate::dry_run`] for what a dry-run tenure does.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::dry_run::DryRunPackage;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// The body of a `POST /dry-run` request.
+#[derive(Debug, Deserialize)]
+pub struct ScheduleDryRun {
+    /// The number of upcoming coordinator tenures to run in dry-run mode.
+    #[serde(default = "ScheduleDryRun::default_tenures")]
+    pub tenures: u32,
+}
+
+impl ScheduleDryRun {
+    fn default_tenures() -> u32 {
+        1
+    }
+}
+
+/// The dry-run status of the signer.
+#[derive(Debug, Serialize)]
+pub struct DryRunStatus {
+    /// The number of upcoming coordinator tenures that will be run in
+    /// dry-run mode.
+    pub remaining_tenures: u32,
+    /// The outcome of the most recent dry-run tenure, if there has been
+    /// one since the signer started.
+    pub last_package: Option<DryRunPackage>,
+}
+
+/// Handler for the `GET /dry-run` endpoint, which returns the dry-run
+/// status along with the package of the most recent dry-run tenure.
+pub async fn dry_run_status_handler<C: Context>(state: State<ApiState<C>>) -> Json<DryRunStatus> {
+    let signer_state = state.ctx.state();
+    Json(DryRunStatus {
+        remaining_tenures: signer_state.dry_run_tenures(),
+        last_package: signer_state.last_dry_run_package(),
+    })
+}
+
+/// Handler for the `POST /dry-run` endpoint, which schedules the next
+/// coordinator tenures to be run in dry-run mode.
+pub async fn schedule_dry_run_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let request: ScheduleDryRun = match request.json() {
+        Ok(request) => request,
+        Err(status) => return status,
+    };
+    if request.tenures == 0 {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    tracing::info!(tenures = %request.tenures, "scheduled dry-run tenures");
+    state.ctx.state().set_dry_run_tenures(request.tenures);
+    StatusCode::ACCEPTED
+}
+
+/// Handler for the `DELETE /dry-run` endpoint, which cancels any
+/// scheduled dry-run tenures.
+pub async fn cancel_dry_run_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> StatusCode {
+    tracing::info!("cancelled dry-run tenures");
+    state.ctx.state().set_dry_run_tenures(0);
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    fn request(method: Method, body: Option<serde_json::Value>) -> Request<Body> {
+        admin_request(method, "/dry-run", body)
+    }
+
+    #[tokio::test]
+    async fn dry_run_tenures_can_be_scheduled_and_cancelled() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let body = serde_json::json!({ "tenures": 2 });
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, Some(body)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(context.state().dry_run_tenures(), 2);
+
+        // Each coordinator tenure uses up one of the dry-run tenures.
+        assert!(context.state().take_dry_run_tenure());
+        assert_eq!(context.state().dry_run_tenures(), 1);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::GET, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let status: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status["remaining_tenures"], 1);
+        assert!(status["last_package"].is_null());
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!context.state().take_dry_run_tenure());
+
+        let body = serde_json::json!({ "tenures": 0 });
+        let response = app
+            .oneshot(request(Method::POST, Some(body)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(context.state().dry_run_tenures(), 0);
+    }
+}
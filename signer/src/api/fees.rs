@@ -0,0 +1,74 @@
+//! Handler for the `GET /fees/stacks` endpoint, which reports the fees
+//! paid for the stacks transactions that this signer submitted over the
+//! current fee budget period.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::context::Context;
+use crate::stacks::fee_budget::StacksFeeReport;
+
+use super::ApiState;
+
+/// Handler for the `GET /fees/stacks` endpoint.
+pub async fn stacks_fees_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<StacksFeeReport>, StatusCode> {
+    let report = StacksFeeReport::load(&state.ctx).await.map_err(|error| {
+        tracing::error!(%error, "could not fetch the stacks fee spends");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::storage::model::StacksTxId;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stacks_fees_are_reported() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let txid: StacksTxId = Faker.fake();
+        crate::stacks::fee_budget::record_stacks_fee_spend(
+            &context,
+            txid,
+            "complete-deposit",
+            1234,
+        )
+        .await
+        .unwrap();
+
+        let request = Request::builder()
+            .uri("/fees/stacks")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["budget_ustx"], serde_json::Value::Null);
+        assert_eq!(report["spent_ustx"], 1234);
+        assert_eq!(report["transactions"][0]["txid"], txid.to_string());
+        assert_eq!(report["transactions"][0]["tx_kind"], "complete-deposit");
+    }
+}
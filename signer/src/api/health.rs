@@ -0,0 +1,282 @@
+//! Handlers for the `GET /healthz` and `GET /readyz` endpoints.
+//!
+//! Liveness only says that the API is up, so that an orchestrator restarts
+//! the signer when it stops responding. Readiness probes the dependencies
+//! that the signer needs in order to do useful work, namely the database,
+//! bitcoin-core and the stacks node, and reports the status of each one.
+//! Each probe is given a short timeout, so that a hung dependency shows up
+//! as not ready rather than as a hung readiness check. The signer is also
+//! not ready while bitcoin-core is bootstrapping, which is reported with
+//! the state of bitcoin-core from the most recent warm-up check.
+
+use std::future::Future;
+use std::time::Duration;
+use std::time::Instant;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::bitcoin::BitcoinInteract as _;
+use crate::bitcoin::warm_up::BitcoinNodeState;
+use crate::context::Context;
+use crate::error::Error;
+use crate::stacks::api::StacksInteract as _;
+use crate::storage::DbRead as _;
+
+use super::ApiState;
+
+/// The maximum amount of time to wait for a dependency to respond to a
+/// probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The outcome of probing a dependency.
+#[derive(Debug, Serialize)]
+pub struct DependencyStatus {
+    /// Whether the dependency responded successfully in time.
+    pub healthy: bool,
+    /// How long the probe took, in milliseconds.
+    pub latency_ms: u64,
+    /// Why the probe failed, if it did.
+    pub error: Option<String>,
+}
+
+/// The readiness of the signer and the status of its dependencies.
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    /// Whether all of the dependencies are healthy and bitcoin-core is
+    /// not bootstrapping.
+    pub ready: bool,
+    /// The status of the database.
+    pub postgres: DependencyStatus,
+    /// The status of bitcoin-core.
+    pub bitcoin: DependencyStatus,
+    /// Whether bitcoin-core is synced or still bootstrapping, as of the
+    /// most recent warm-up check.
+    pub bitcoin_sync: BitcoinNodeState,
+    /// The status of the stacks node.
+    pub stacks: DependencyStatus,
+}
+
+/// Run a probe of a dependency with the probe timeout.
+async fn probe<T, F>(future: F) -> DependencyStatus
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let started_at = Instant::now();
+    let error = match tokio::time::timeout(PROBE_TIMEOUT, future).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(error)) => Some(error.to_string()),
+        Err(_) => Some(format!("timed out after {}ms", PROBE_TIMEOUT.as_millis())),
+    };
+
+    DependencyStatus {
+        healthy: error.is_none(),
+        latency_ms: started_at
+            .elapsed()
+            .as_millis()
+            .try_into()
+            .unwrap_or(u64::MAX),
+        error,
+    }
+}
+
+/// Handler for the `GET /healthz` endpoint, which responds with 200 OK as
+/// long as the API is up.
+pub async fn liveness_handler() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Handler for the `GET /readyz` endpoint, which responds with 200 OK if
+/// all of the dependencies are healthy and bitcoin-core is not
+/// bootstrapping, and with 503 Service Unavailable otherwise, along with
+/// the status of each dependency.
+pub async fn readiness_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> (StatusCode, Json<Readiness>) {
+    let storage = state.ctx.get_storage();
+    let bitcoin_client = state.ctx.get_bitcoin_client();
+    let stacks_client = state.ctx.get_stacks_client();
+
+    let (postgres, bitcoin, stacks) = tokio::join!(
+        probe(storage.ping()),
+        probe(bitcoin_client.get_best_block_hash()),
+        probe(stacks_client.get_node_info()),
+    );
+
+    let bitcoin_sync = state.ctx.state().bitcoin_node_state();
+    let ready = postgres.healthy && bitcoin.healthy && stacks.healthy && bitcoin_sync.is_ready();
+    for (dependency, status) in [
+        ("postgres", &postgres),
+        ("bitcoin", &bitcoin),
+        ("stacks", &stacks),
+    ] {
+        if let Some(error) = &status.error {
+            tracing::warn!(dependency, %error, "readiness probe failed");
+        }
+    }
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let readiness = Readiness {
+        ready,
+        postgres,
+        bitcoin,
+        bitcoin_sync,
+        stacks,
+    };
+    (status, Json(readiness))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::stacks::api::GetNodeInfoResponse;
+    use crate::storage::model::BitcoinBlockHash;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    static NODE_INFO_RESPONSE: LazyLock<GetNodeInfoResponse> = LazyLock::new(|| {
+        let json = include_str!("../../tests/fixtures/stacksapi-get-node-info-test-data.json");
+        serde_json::from_str(json).unwrap()
+    });
+
+    async fn get(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .uri(uri)
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn liveness_does_not_probe_dependencies() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let (status, _) = get(app, "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ready_when_all_dependencies_are_healthy() {
+        let context = TestContext::default_mocked();
+        let block_hash: BitcoinBlockHash = Faker.fake();
+        context
+            .with_bitcoin_client(|client| {
+                client
+                    .expect_get_best_block_hash()
+                    .once()
+                    .returning(move || Box::pin(async move { Ok(block_hash.into()) }));
+            })
+            .await;
+        context
+            .with_stacks_client(|client| {
+                client
+                    .expect_get_node_info()
+                    .once()
+                    .returning(|| Box::pin(async { Ok(NODE_INFO_RESPONSE.clone()) }));
+            })
+            .await;
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let (status, body) = get(app, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["bitcoin_sync"]["state"], "ready");
+        for dependency in ["postgres", "bitcoin", "stacks"] {
+            assert_eq!(body[dependency]["healthy"], true);
+            assert_eq!(body[dependency]["error"], serde_json::Value::Null);
+        }
+    }
+
+    #[tokio::test]
+    async fn not_ready_when_a_dependency_fails() {
+        let context = TestContext::default_mocked();
+        let block_hash: BitcoinBlockHash = Faker.fake();
+        context
+            .with_bitcoin_client(|client| {
+                client
+                    .expect_get_best_block_hash()
+                    .once()
+                    .returning(move || Box::pin(async move { Ok(block_hash.into()) }));
+            })
+            .await;
+        context
+            .with_stacks_client(|client| {
+                client
+                    .expect_get_node_info()
+                    .once()
+                    .returning(|| Box::pin(async { Err(Error::Dummy) }));
+            })
+            .await;
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let (status, body) = get(app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["postgres"]["healthy"], true);
+        assert_eq!(body["bitcoin"]["healthy"], true);
+        assert_eq!(body["stacks"]["healthy"], false);
+        assert_eq!(body["stacks"]["error"], Error::Dummy.to_string());
+    }
+
+    #[tokio::test]
+    async fn not_ready_while_bitcoin_core_warms_up() {
+        let context = TestContext::default_mocked();
+        let block_hash: BitcoinBlockHash = Faker.fake();
+        context
+            .with_bitcoin_client(|client| {
+                client
+                    .expect_get_best_block_hash()
+                    .once()
+                    .returning(move || Box::pin(async move { Ok(block_hash.into()) }));
+            })
+            .await;
+        context
+            .with_stacks_client(|client| {
+                client
+                    .expect_get_node_info()
+                    .once()
+                    .returning(|| Box::pin(async { Ok(NODE_INFO_RESPONSE.clone()) }));
+            })
+            .await;
+        context
+            .state()
+            .set_bitcoin_node_state(BitcoinNodeState::InitialBlockDownload {
+                blocks: 100,
+                headers: 200,
+                verification_progress: 0.5,
+            });
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let (status, body) = get(app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["bitcoin"]["healthy"], true);
+        assert_eq!(body["bitcoin_sync"]["state"], "initial_block_download");
+        assert_eq!(body["bitcoin_sync"]["verification_progress"], 0.5);
+    }
+}
@@ -121,6 +121,9 @@ impl IntoResponse for InfoResponse {
 
 /// Handler for the `/info` endpoint. This method is infallible and returns
 /// `null` for any missing information.
+///
+/// The router serves this endpoint through the ETag cache, so the response
+/// (including its `timestamp`) may be up to a few seconds old.
 pub async fn info_handler<C: Context>(state: State<ApiState<C>>) -> InfoResponse {
     build_info(&state.ctx).await
 }
@@ -0,0 +1,87 @@
+//! Handler for verifying the integrity of the database on demand.
+//!
+//! The signer also verifies the protected rows in the database
+//! periodically, see [`crate::integrity`]. This endpoint lets an operator,
+//! or a compliance process, run the same verification and get the
+//! results back.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::context::Context;
+use crate::integrity::TableIntegrityReport;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// Handler for the `GET /integrity` endpoint, which verifies all
+/// protected rows in the database against their integrity hashes.
+///
+/// Returns `501 Not Implemented` when no integrity key is configured.
+pub async fn verify_integrity_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> Result<Json<Vec<TableIntegrityReport>>, StatusCode> {
+    let Some(key) = &state.ctx.config().signer.integrity_key else {
+        return Err(StatusCode::NOT_IMPLEMENTED);
+    };
+
+    let storage = state.ctx.get_storage();
+    crate::integrity::verify_integrity(&storage, key)
+        .await
+        .map(Json)
+        .map_err(|error| {
+            tracing::error!(%error, "could not verify the integrity of the database");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::integrity::IntegrityKey;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    fn request() -> Request<Body> {
+        admin_request(Method::GET, "/integrity", None)
+    }
+
+    #[tokio::test]
+    async fn integrity_is_verified_when_a_key_is_configured() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .with_admin_secret()
+            .modify_settings(|settings| {
+                settings.signer.integrity_key = Some(IntegrityKey::new([1; 32]));
+            })
+            .build();
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let reports: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reports[0]["table_name"], "completed_deposit_events");
+        assert_eq!(reports[0]["verified"], 0);
+        assert_eq!(reports[1]["table_name"], "rotate_keys_transactions");
+    }
+}
@@ -0,0 +1,387 @@
+//! Limits on the requests to, and connections of, the signer API server.
+//!
+//! The signer API also serves the event observer, so a flood of requests,
+//! like bogus `POST /new_block` webhooks, competes with the webhooks of
+//! the stacks node for the handlers and the database, and delays the
+//! handling of real blocks. The limits in [`ApiConfig`] are enforced by
+//! the [`enforce_limits`] middleware, which rejects requests
+//!
+//! * from a client address that is over its rate limit, with a `429 Too
+//!   Many Requests` response,
+//! * above the maximum number of requests that are handled at the same
+//!   time, with a `503 Service Unavailable` response, and
+//! * that take longer than the request timeout, with a `408 Request
+//!   Timeout` response,
+//!
+//! and by the [`CappedListener`], which stops accepting connections while
+//! the maximum number of connections are open.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Instant;
+
+use axum::extract::ConnectInfo;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+use axum::serve::Listener;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+use crate::config::ApiConfig;
+use crate::metrics::Metrics;
+
+/// The number of client addresses with rate limits that are tracked
+/// before the addresses that have not sent a request in a while are
+/// forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// The state of the limits on the requests to the signer API.
+#[derive(Debug)]
+pub struct ApiLimits {
+    /// The configured limits.
+    config: ApiConfig,
+    /// A permit for each of the requests that may be handled at the same
+    /// time, if the number is limited.
+    requests: Option<Semaphore>,
+    /// The rate limit of each client address that sent a request.
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+impl ApiLimits {
+    /// Create the state of the given limits.
+    pub fn new(config: ApiConfig) -> Self {
+        let requests = Some(config.max_concurrent_requests)
+            .filter(|max| *max > 0)
+            .map(Semaphore::new);
+
+        Self {
+            config,
+            requests,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a request from the given client address is within its rate
+    /// limit. The request counts towards the limit if it is.
+    fn within_rate_limit(&self, client: IpAddr, now: Instant) -> bool {
+        let Some(burst) = self.config.rate_limit_burst() else {
+            return true;
+        };
+        if self.config.rate_limit_exempt.contains(&client) {
+            return true;
+        }
+
+        let rate = f64::from(self.config.rate_limit);
+        let capacity = f64::from(burst);
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("BUG: a thread panicked while holding the rate limits");
+
+        // Clients with full buckets are where they would be if they had
+        // never sent a request, so they are safe to forget.
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            buckets.retain(|_, bucket| bucket.refill(rate, capacity, now) < capacity);
+        }
+
+        buckets
+            .entry(client)
+            .or_insert(TokenBucket {
+                tokens: capacity,
+                updated_at: now,
+            })
+            .try_take(rate, capacity, now)
+    }
+}
+
+/// The rate limit of a client address. The bucket holds up to the burst
+/// size in tokens and gains the rate limit in tokens each second, and
+/// each request takes a token.
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// Add the tokens gained since the bucket was last updated, and
+    /// return the number of tokens in the bucket.
+    fn refill(&mut self, rate: f64, capacity: f64, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(capacity);
+        self.updated_at = now;
+        self.tokens
+    }
+
+    /// Take a token from the bucket, if there is one.
+    fn try_take(&mut self, rate: f64, capacity: f64, now: Instant) -> bool {
+        if self.refill(rate, capacity, now) < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Middleware that enforces the rate limit, the concurrency limit and the
+/// timeout of the requests to the signer API.
+///
+/// Requests are only rate limited when the address of the client is
+/// known, which requires serving the API with
+/// [`axum::Router::into_make_service_with_connect_info`].
+pub async fn enforce_limits(
+    State(limits): State<Arc<ApiLimits>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    if client.is_some_and(|client| !limits.within_rate_limit(client, Instant::now())) {
+        tracing::debug!(?client, uri = %request.uri(), "rejecting a request over the rate limit");
+        Metrics::increment_api_requests_rejected("rate");
+        return StatusCode::TOO_MANY_REQUESTS.into_response();
+    }
+
+    let _permit = match &limits.requests {
+        Some(requests) => match requests.try_acquire() {
+            Ok(permit) => Some(permit),
+            Err(_) => {
+                tracing::warn!(uri = %request.uri(), "too many concurrent requests; rejecting");
+                Metrics::increment_api_requests_rejected("concurrency");
+                return StatusCode::SERVICE_UNAVAILABLE.into_response();
+            }
+        },
+        None => None,
+    };
+
+    let Some(timeout) = limits.config.request_timeout() else {
+        return next.run(request).await;
+    };
+
+    let uri = request.uri().clone();
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!(%uri, "request timed out");
+            Metrics::increment_api_requests_rejected("timeout");
+            StatusCode::REQUEST_TIMEOUT.into_response()
+        }
+    }
+}
+
+/// A TCP listener for the signer API that keeps at most the given number
+/// of connections open. Once that many are open, further connections wait
+/// in the listen backlog until one of the open connections closes.
+#[derive(Debug)]
+pub struct CappedListener {
+    listener: TcpListener,
+    /// A permit for each connection that may be open, if the number is
+    /// limited.
+    connections: Option<Arc<Semaphore>>,
+}
+
+impl CappedListener {
+    /// Wrap the listener, capping the number of open connections at the
+    /// given maximum, unless it is zero.
+    pub fn new(listener: TcpListener, max_connections: usize) -> Self {
+        let connections = Some(max_connections)
+            .filter(|max| *max > 0)
+            .map(|max| Arc::new(Semaphore::new(max)));
+
+        Self { listener, connections }
+    }
+}
+
+impl Listener for CappedListener {
+    type Io = CappedConnection;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let permit = match &self.connections {
+            Some(connections) => Some(
+                Arc::clone(connections)
+                    .acquire_owned()
+                    .await
+                    .expect("BUG: the connection semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        let (stream, addr) = Listener::accept(&mut self.listener).await;
+        (CappedConnection { stream, _permit: permit }, addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+/// A connection accepted by the [`CappedListener`], which frees up its
+/// place when it is dropped.
+#[derive(Debug)]
+pub struct CappedConnection {
+    stream: TcpStream,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl AsyncRead for CappedConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for CappedConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    use axum::Router;
+    use axum::body::Body;
+    use axum::middleware;
+    use axum::routing::get;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    fn router(config: ApiConfig, handler_delay: Duration) -> Router {
+        let limits = Arc::new(ApiLimits::new(config));
+        let handler = move || async move {
+            tokio::time::sleep(handler_delay).await;
+            "ok"
+        };
+
+        Router::new()
+            .route("/", get(handler))
+            .layer(middleware::from_fn_with_state(limits, enforce_limits))
+    }
+
+    fn request() -> Request {
+        let mut request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let addr = SocketAddr::new(CLIENT, 4000);
+        request.extensions_mut().insert(ConnectInfo(addr));
+        request
+    }
+
+    #[test]
+    fn rate_limits_allow_bursts_and_refill() {
+        let config = ApiConfig {
+            rate_limit: 2,
+            rate_limit_burst: 3,
+            ..Default::default()
+        };
+        let limits = ApiLimits::new(config);
+        let now = Instant::now();
+
+        assert!((0..3).all(|_| limits.within_rate_limit(CLIENT, now)));
+        assert!(!limits.within_rate_limit(CLIENT, now));
+
+        // Other clients have their own limits.
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        assert!(limits.within_rate_limit(other, now));
+
+        // Two tokens are gained each second.
+        let later = now + Duration::from_secs(1);
+        assert!(limits.within_rate_limit(CLIENT, later));
+        assert!(limits.within_rate_limit(CLIENT, later));
+        assert!(!limits.within_rate_limit(CLIENT, later));
+    }
+
+    #[test]
+    fn exempt_clients_are_not_rate_limited() {
+        let config = ApiConfig {
+            rate_limit: 1,
+            rate_limit_exempt: vec![CLIENT],
+            ..Default::default()
+        };
+        let limits = ApiLimits::new(config);
+        let now = Instant::now();
+
+        assert!((0..10).all(|_| limits.within_rate_limit(CLIENT, now)));
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limits_are_rejected() {
+        let config = ApiConfig {
+            rate_limit: 1,
+            ..Default::default()
+        };
+        let app = router(config, Duration::ZERO);
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let config = ApiConfig {
+            max_concurrent_requests: 1,
+            ..Default::default()
+        };
+        let app = router(config, Duration::from_millis(200));
+        let (first, second) = tokio::join!(app.clone().oneshot(request()), app.oneshot(request()));
+        let mut statuses = [first.unwrap().status(), second.unwrap().status()];
+        statuses.sort();
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::SERVICE_UNAVAILABLE]);
+
+        let config = ApiConfig {
+            request_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let app = router(config, Duration::from_secs(5));
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+}
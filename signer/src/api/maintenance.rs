@@ -0,0 +1,124 @@
+//! Handlers for toggling read-only maintenance mode.
+//!
+//! In maintenance mode the signer keeps observing the bitcoin and stacks
+//! blockchains, serving its API and recording events, but it does not vote
+//! on requests, sign anything or coordinate tenures. The mode is
+//! advertised to the other signers in heartbeats.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::context::Context;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// The maintenance mode status of the signer and its peers.
+#[derive(Debug, Serialize)]
+pub struct MaintenanceStatus {
+    /// Whether this signer is in maintenance mode.
+    pub enabled: bool,
+    /// The public keys of the peers that said in their most recent
+    /// heartbeat that they are in maintenance mode.
+    pub peers_in_maintenance: Vec<String>,
+}
+
+/// Handler for the `GET /maintenance` endpoint, which returns whether the
+/// signer and its peers are in maintenance mode.
+pub async fn maintenance_status_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Json<MaintenanceStatus> {
+    let signer_state = state.ctx.state();
+    let mut peers_in_maintenance: Vec<String> = signer_state
+        .peers_in_maintenance()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    peers_in_maintenance.sort();
+
+    Json(MaintenanceStatus {
+        enabled: signer_state.maintenance_mode(),
+        peers_in_maintenance,
+    })
+}
+
+/// Handler for the `POST /maintenance` endpoint, which puts the signer in
+/// maintenance mode.
+pub async fn enter_maintenance_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> StatusCode {
+    tracing::info!("entering maintenance mode; we will not vote, sign or coordinate");
+    state.ctx.state().set_maintenance_mode(true);
+    StatusCode::NO_CONTENT
+}
+
+/// Handler for the `DELETE /maintenance` endpoint, which takes the signer
+/// out of maintenance mode.
+pub async fn leave_maintenance_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> StatusCode {
+    tracing::info!("leaving maintenance mode");
+    state.ctx.state().set_maintenance_mode(false);
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::keys::PublicKey;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    fn request(method: Method) -> Request<Body> {
+        admin_request(method, "/maintenance", None)
+    }
+
+    async fn status(app: &Router) -> serde_json::Value {
+        let response = app.clone().oneshot(request(Method::GET)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn maintenance_mode_can_be_toggled() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let peer: PublicKey = Faker.fake_with_rng(&mut get_rng());
+        context.state().set_peer_maintenance_mode(peer, true);
+
+        let status_before = status(&app).await;
+        assert_eq!(status_before["enabled"], false);
+        assert_eq!(
+            status_before["peers_in_maintenance"],
+            serde_json::json!([peer.to_string()])
+        );
+
+        let response = app.clone().oneshot(request(Method::POST)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(context.state().maintenance_mode());
+        assert_eq!(status(&app).await["enabled"], true);
+
+        let response = app.clone().oneshot(request(Method::DELETE)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!context.state().maintenance_mode());
+    }
+}
@@ -0,0 +1,93 @@
+//! Handler for the `GET /metrics` endpoint, which renders the signer's
+//! metrics in the Prometheus text exposition format.
+//!
+//! The endpoint is only served when `prometheus_api_metrics` is enabled in
+//! the config, so that operators can scrape the signer through its API
+//! instead of through the dedicated scrape endpoint.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::http::header;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+use metrics_exporter_prometheus::PrometheusHandle;
+
+use crate::context::Context;
+
+use super::ApiState;
+
+/// The content type of the Prometheus text exposition format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Handler for the `GET /metrics` endpoint. It responds with 404 Not Found
+/// when serving metrics on the API is disabled.
+pub async fn metrics_handler<C: Context>(state: State<ApiState<C>>) -> Response {
+    if !state.ctx.config().signer.prometheus_api_metrics {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match crate::metrics::prometheus_handle() {
+        Some(handle) => render_metrics(handle),
+        None => {
+            tracing::warn!("metrics were requested before the prometheus recorder was set up");
+            StatusCode::SERVICE_UNAVAILABLE.into_response()
+        }
+    }
+}
+
+/// Render the metrics of the given recorder handle.
+fn render_metrics(handle: &PrometheusHandle) -> Response {
+    (
+        [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        handle.render(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn metrics_are_not_served_unless_enabled() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let request = Request::builder()
+            .uri("/metrics")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn metrics_are_rendered_in_the_exposition_format() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("sweeps_total").increment(3);
+        });
+
+        let response = render_metrics(&recorder.handle());
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response.headers().get(header::CONTENT_TYPE).unwrap();
+        assert_eq!(content_type, PROMETHEUS_CONTENT_TYPE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("sweeps_total 3"));
+    }
+}
@@ -1,14 +1,57 @@
 //! This module contains functions and structs for the Signer API.
 //!
 
+mod access_log;
+mod admin;
+mod cache;
+mod compression;
+mod config_fingerprint;
+mod confirmations;
+mod crash_reports;
+mod deposits;
+mod dry_run;
+mod fees;
+mod health;
 mod info;
+mod integrity;
+mod limits;
+mod maintenance;
+mod metrics;
 mod new_block;
+mod new_block_auth;
+mod new_burn_block;
+mod peer_bans;
+mod peers;
+mod quarantine;
+mod queue;
 mod router;
+mod signing;
 mod status;
+mod sweeps;
+mod tls;
+mod trace_context;
+#[cfg(feature = "ui")]
+mod ui;
+mod webhooks;
 
+pub use access_log::log_access;
+pub use admin::ADMIN_SIGNATURE_HEADER;
+pub use admin::ADMIN_TIMESTAMP_HEADER;
+pub use admin::sign_admin_request;
 pub use info::build_info;
+pub use limits::ApiLimits;
+pub use limits::CappedListener;
+pub use limits::enforce_limits;
+pub use new_block::EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT;
 pub use new_block::new_block_handler;
+pub use new_block_auth::NEW_BLOCK_SIGNATURE_HEADER;
+pub use new_block_auth::sign_new_block_body;
+pub use new_burn_block::new_burn_block_handler;
 pub use router::get_router;
+pub use signing::ResponseSigner;
+pub use signing::sign_response;
+pub use tls::TlsListener;
+pub use tls::tls_acceptor;
 
 /// A struct with state data necessary for runtime operation.
 #[derive(Debug, Clone)]
@@ -2,41 +2,51 @@
 //! which is for processing new block webhooks from a stacks node.
 //!
 
+use axum::body::Bytes;
+use axum::extract::Path;
 use axum::extract::State;
+use axum::http::HeaderMap;
 use axum::http::StatusCode;
-use clarity::vm::representations::ContractName;
-use clarity::vm::types::QualifiedContractIdentifier;
-use clarity::vm::types::StandardPrincipalData;
+use clarity::vm::Value as ClarityValue;
+use clarity::vm::types::PrincipalData;
 use sbtc::events::RegistryEvent;
 use sbtc::events::TxInfo;
-use std::sync::OnceLock;
+use stacks_common::types::chainstate::StacksBlockId;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+use std::time::Instant;
+use tracing::Instrument as _;
 
 use crate::context::Context;
+use crate::context::StacksBlockObserved;
 use crate::error::Error;
+use crate::integrity;
 use crate::metrics::Metrics;
 use crate::metrics::STACKS_BLOCKCHAIN;
+use crate::raw_stacks_events;
+use crate::stacks::tenure::TenureChange;
+use crate::storage::DbRead as _;
 use crate::storage::DbWrite as _;
 use crate::storage::model::CompletedDepositEvent;
 use crate::storage::model::KeyRotationEvent;
+use crate::storage::model::QuarantinedContractEvent;
+use crate::storage::model::RegistryEventSource;
 use crate::storage::model::StacksBlock;
+use crate::storage::model::StacksBlockEvent;
+use crate::storage::model::StacksBlockEvents;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksBlockTenure;
 use crate::storage::model::WithdrawalAcceptEvent;
 use crate::storage::model::WithdrawalRejectEvent;
 use crate::storage::model::WithdrawalRequest;
+use crate::webhooks::WebhookEvent;
 use sbtc::webhooks::NewBlockEvent;
 
 use super::ApiState;
-use super::SBTC_REGISTRY_CONTRACT_NAME;
-
-/// The address for the sbtc-registry smart contract. This value is
-/// populated using the deployer variable in the config.
-///
-/// Although the stacks node is supposed to only send sbtc-registry events,
-/// the node can be misconfigured or have some bug where it sends other
-/// events as well. Accepting such events would be a security issue, so we
-/// filter out events that are not from the sbtc-registry.
-///
-/// See https://github.com/stacks-network/sbtc/issues/501.
-static SBTC_REGISTRY_IDENTIFIER: OnceLock<QualifiedContractIdentifier> = OnceLock::new();
+use super::admin::AdminRequest;
+use super::compression::BodyLimits;
+use super::new_block_auth::is_authenticated;
+use super::trace_context::TraceContext;
 
 /// Maximum request body size for the event observer endpoint.
 ///
@@ -45,8 +55,40 @@ static SBTC_REGISTRY_IDENTIFIER: OnceLock<QualifiedContractIdentifier> = OnceLoc
 /// subscribed sbtc events. Luckily, the size of the sbtc events themselves are
 /// bounded by the size of the transactions that create them, so a limit of 8 MB
 /// will be fine since it is twice as high as required.
+///
+/// Bodies sent with a `Content-Encoding` are held to this limit before they
+/// are decompressed, and to [`EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT`]
+/// after.
 pub const EVENT_OBSERVER_BODY_LIMIT: usize = 8 * 1024 * 1024;
 
+/// Maximum size of a compressed request body for the event observer
+/// endpoint once it has been decompressed.
+///
+/// Nakamoto blocks with many sbtc events can come close to
+/// [`EVENT_OBSERVER_BODY_LIMIT`], and compressing them is how a node keeps
+/// them under it. Event bodies are JSON, which compresses well, so the
+/// decompressed limit is a few times higher than the compressed one.
+pub const EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT: usize = 32 * 1024 * 1024;
+
+/// The limits on the size of the bodies of event observer requests.
+pub const EVENT_OBSERVER_BODY_LIMITS: BodyLimits = BodyLimits {
+    encoded: EVENT_OBSERVER_BODY_LIMIT,
+    decoded: EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT,
+};
+
+/// The maximum number of times that handling a stacks event is attempted
+/// when the event could not be written to the database.
+const MAX_EVENT_HANDLING_ATTEMPTS: u32 = 5;
+
+/// The delay before the first retry of handling a stacks event. The delay
+/// doubles after every failed attempt.
+const EVENT_HANDLING_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// The minimum number of sbtc events in a stacks block for their Clarity
+/// values to be transformed on more than one thread. Below this, handing
+/// the work to other threads costs more than it saves.
+const PARALLEL_TRANSFORM_MIN_EVENTS: usize = 32;
+
 /// A handler of `POST /new_block` webhook events.
 ///
 /// # Notes
@@ -56,12 +98,27 @@ pub const EVENT_OBSERVER_BODY_LIMIT: usize = 8 * 1024 * 1024;
 /// to connect to one of the observers, or if the response from the
 /// observer is not a 200-299 response code, then it sleeps for 1 second
 /// and tries again[^1]. From the looks of it, the node will not stop
-/// trying to send the webhook until there is a success. Because of this,
-/// unless we encounter an error where retrying in a second might succeed,
-/// we will return a 200 OK status code.
+/// trying to send the webhook until there is a success, and a slow
+/// response holds up the delivery of the next block.
+///
+/// Because of this, the handler only parses the webhook and hands the
+/// events off to a background task, waiting for them to be handled for at
+/// most the configured latency budget. It always returns a 200 OK status
+/// code, and events that could not be written to the database are retried
+/// by the background task rather than by the stacks node.
+///
+/// The stacks node may also send the same webhook more than once, for
+/// example after it restarts. Once all the events of a block have been
+/// handled the block is recorded in the database, and later webhooks for
+/// the same block are answered with a 200 OK without being handled again.
+/// Blocks without sBTC events are cheap to handle again, so they are not
+/// recorded. The same goes for blocks whose events could not be handled
+/// the configured maximum number of times, so that a persistent failure
+/// does not have the signer handle a block over and over.
 ///
-/// TODO: We need to be careful to only return a non success status code a
-/// fixed number of times.
+/// The one exception is when webhooks must be authenticated, see the
+/// `new_block_auth` module. Webhooks that are not authenticated are
+/// rejected with a 401 Unauthorized status code before they are parsed.
 ///
 /// [^1]: <https://github.com/stacks-network/stacks-core/blob/09c4b066e25104be8b066e8f7530ff0c6df4ccd5/testnet/stacks-node/src/event_dispatcher.rs#L317-L385>
 #[tracing::instrument(skip_all, name = "new-block", fields(
@@ -69,31 +126,54 @@ pub const EVENT_OBSERVER_BODY_LIMIT: usize = 8 * 1024 * 1024;
     block_height = tracing::field::Empty,
     parent_hash = tracing::field::Empty,
     bitcoin_anchor = tracing::field::Empty,
+    correlation_id = tracing::field::Empty,
+    parent_span_id = tracing::field::Empty,
 ))]
-pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: String) -> StatusCode {
+pub async fn new_block_handler<C: Context + 'static>(
+    state: State<ApiState<C>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let received_at = Instant::now();
+    let trace_context = TraceContext::from_headers(&headers);
+    trace_context.record(&tracing::span::Span::current());
+
+    let auth_config = &state.ctx.config().signer.event_observer.new_block_auth;
+    if !is_authenticated(auth_config, &headers, &body) {
+        tracing::warn!("rejecting an unauthenticated POST /new_block webhook");
+        return StatusCode::UNAUTHORIZED;
+    }
+
     metrics::counter!(
         Metrics::BlocksObservedTotal,
         "blockchain" => STACKS_BLOCKCHAIN,
     )
     .increment(1);
 
-    let api = state.0;
+    // The body is taken as the buffered bytes and only borrowed as a
+    // `str`, since extracting a `String` copies it, which doubles the
+    // memory used by webhooks of up to EVENT_OBSERVER_BODY_LIMIT bytes.
+    // As with bodies that fail to deserialize below, retrying the webhook
+    // would not help, so we return `200 OK`.
+    let Ok(body_str) = std::str::from_utf8(&body) else {
+        tracing::error!("could not deserialize POST /new_block webhook: body is not UTF-8");
+        return StatusCode::OK;
+    };
 
-    let registry_address = SBTC_REGISTRY_IDENTIFIER.get_or_init(|| {
-        // Although the following line can panic, our unit tests hit this
-        // code path so if tests pass then this will work in production.
-        let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
-        let issuer = StandardPrincipalData::from(api.ctx.config().signer.deployer.clone());
-        QualifiedContractIdentifier::new(issuer, contract_name)
-    });
+    if let Some(capture) = crate::capture::get() {
+        capture.record_webhook("/new_block", body_str);
+    }
+
+    let api = state.0;
 
-    let new_block_event: NewBlockEvent = match serde_json::from_str(&body) {
+    let new_block_event: NewBlockEvent = match serde_json::from_slice(&body) {
         Ok(value) => value,
         // If we are here, then we failed to deserialize the webhook body
         // into the expected type. It's unlikely that retying this webhook
         // will lead to success, so we log the error and return `200 OK` so
         // that the node does not retry the webhook.
         Err(error) => {
+            let body = body_str;
             tracing::error!(%body, %error, "could not deserialize POST /new_block webhook:");
             return StatusCode::OK;
         }
@@ -112,184 +192,740 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
     span.record("parent_hash", stacks_chaintip.parent_hash.to_hex());
     span.record("bitcoin_anchor", stacks_chaintip.bitcoin_anchor.to_string());
 
+    let block_hash = stacks_chaintip.block_hash;
+
+    // The block is published before the checks below, so that the
+    // `StacksBlockGapFiller` sees every webhook when it checks whether the
+    // stacks node skipped the webhooks of some blocks.
+    api.ctx
+        .events()
+        .publish(StacksBlockObserved { block: stacks_chaintip });
+
+    let storage = api.ctx.get_storage();
+    match storage.is_stacks_block_processed(&block_hash).await {
+        Ok(true) => {
+            tracing::debug!("the events of the stacks block were already handled; skipping");
+            return StatusCode::OK;
+        }
+        Ok(false) => {}
+        // Handling the events again is safe, it is just wasteful.
+        Err(error) => {
+            tracing::warn!(%error, "could not check whether the stacks block was processed")
+        }
+    }
+
+    // The raw body is kept for auditing and for replaying the events of
+    // the block, see the `raw_stacks_events` module. Compressing it can
+    // take a while for large bodies, so the stacks node does not wait for
+    // it.
+    let ctx = api.ctx.clone();
+    let raw_body = body.clone();
+    let raw_event = async move { raw_stacks_events::store(&ctx, block_hash, raw_body).await };
+    tokio::spawn(raw_event.in_current_span());
+
+    let max_block_failures = api.ctx.config().signer.event_observer.max_block_failures;
+    match storage.get_stacks_block_failure_count(&block_hash).await {
+        Ok(failure_count) if failure_count >= max_block_failures.get() => {
+            tracing::warn!(
+                %failure_count,
+                "handling the events of the stacks block failed too many times; skipping"
+            );
+            return StatusCode::OK;
+        }
+        Ok(_) => {}
+        Err(error) => {
+            tracing::warn!(%error, "could not check whether the stacks block failed before")
+        }
+    }
+
     tracing::debug!("received a new block event from stacks-core");
 
+    let tenure_change = TenureChange::from_block_event(&new_block_event);
+    if let Some(tenure_change) = tenure_change {
+        observe_tenure_change(&api.ctx, tenure_change);
+    }
+    record_stacks_block_tenure(&api.ctx, block_hash, &new_block_event, tenure_change).await;
+
+    quarantine_filtered_events(&api.ctx, block_hash, &new_block_event).await;
+
+    let registry_events = extract_registry_events(&api.ctx, block_hash, new_block_event).await;
+    if registry_events.is_empty() {
+        // If there are no events to process, we return early with a 200 OK
+        // status code so that the node does not retry the webhook.
+        return StatusCode::OK;
+    }
+
+    tracing::debug!(count = %registry_events.len(), "handing off events for new stacks block");
+
+    // The handling of the events continues in the background if it does
+    // not finish within the latency budget, since dropping the join
+    // handle does not cancel the task.
+    let handling = handle_events(
+        api.ctx.clone(),
+        block_hash,
+        registry_events,
+        received_at,
+        trace_context.correlation_id,
+    );
+    let handling = tokio::spawn(handling.in_current_span());
+    let latency_budget = api.ctx.config().signer.event_observer.latency_budget;
+    if tokio::time::timeout(latency_budget, handling)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            ?latency_budget,
+            "the events were not handled within the latency budget; continuing in the background"
+        );
+        metrics::counter!(Metrics::NewBlockLatencyBudgetExceededTotal).increment(1);
+    }
+
+    StatusCode::OK
+}
+
+/// Handler for the `POST /admin/replay_block/{block_hash}` endpoint, which
+/// handles the events of a stacks block again, from the stored body of its
+/// `POST /new_block` webhook.
+///
+/// This is for when a bug in the handling of the events caused some of
+/// them to be dropped, since the stacks node does not send a webhook again
+/// once it has been acknowledged. Webhook bodies are only stored while
+/// `raw_events_retention` is set, see [`raw_stacks_events`], so only the
+/// blocks received within the retention window can be replayed. The
+/// events are handled even if the block was recorded as processed, or was
+/// given up on after failing too many times. Events that are already in
+/// the database are left as they are, and webhook subscribers are not
+/// notified about them again.
+///
+/// The endpoint responds with 404 Not Found if no webhook body is stored
+/// for the block, and with 200 OK once the events have been handled.
+#[tracing::instrument(skip_all, name = "replay-block", fields(
+    %block_hash,
+    correlation_id = tracing::field::Empty,
+    parent_span_id = tracing::field::Empty,
+))]
+pub async fn replay_block_handler<C: Context + 'static>(
+    state: State<ApiState<C>>,
+    Path(block_hash): Path<String>,
+    headers: HeaderMap,
+    _: AdminRequest,
+) -> StatusCode {
+    let trace_context = TraceContext::from_headers(&headers);
+    trace_context.record(&tracing::span::Span::current());
+
+    let Ok(block_id) = StacksBlockId::from_hex(&block_hash) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    let block_hash = StacksBlockHash::from(block_id);
+
+    let ctx = &state.ctx;
+    let webhook = match raw_stacks_events::load(ctx, &block_hash).await {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(error) => {
+            tracing::error!(%error, "could not fetch the stored webhook of the stacks block");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    let new_block_event: NewBlockEvent = match serde_json::from_slice(&webhook) {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(%error, "could not deserialize the stored webhook of the stacks block");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // Only the events that were dropped are handled again, so that they
+    // are not written twice and webhook subscribers are not notified
+    // about the others a second time.
+    let mut events: Vec<(StacksBlockEvent, RegistryEventSource)> = Vec::new();
+    for (event, source) in extract_registry_events(ctx, block_hash, new_block_event).await {
+        let event = StacksBlockEvent::from(event);
+        match ctx.get_storage().is_stacks_block_event_stored(&event).await {
+            Ok(true) => {}
+            Ok(false) => events.push((event, source)),
+            Err(error) => {
+                tracing::error!(%error, "could not check whether a stacks event was stored");
+                return StatusCode::INTERNAL_SERVER_ERROR;
+            }
+        }
+    }
+
+    let _stacks_event = ctx.state().chain_event_scheduler().stacks_event().await;
+    let correlation_id = trace_context.correlation_id.as_deref();
+    match handle_block_events(ctx, block_hash, &events, correlation_id).await {
+        Ok(()) => {
+            tracing::info!(count = %events.len(), "replayed the events of the stacks block");
+            StatusCode::OK
+        }
+        Err(error) => {
+            tracing::error!(%error, "could not replay the events of the stacks block");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Record the given tenure change from a stacks block, along with any
+/// flash blocks that it shows.
+fn observe_tenure_change(ctx: &impl Context, tenure_change: TenureChange) {
+    let kind: &'static str = tenure_change.kind.into();
+    tracing::debug!(
+        kind,
+        burn_block_height = %tenure_change.burn_block_height,
+        "observed a stacks tenure change"
+    );
+    metrics::counter!(Metrics::StacksTenureChangesTotal, "kind" => kind).increment(1);
+
+    if let Some(flash_blocks) = ctx.state().observe_tenure_change(tenure_change) {
+        let count = *flash_blocks.end() - *flash_blocks.start() + 1;
+        tracing::info!(
+            first_height = %flash_blocks.start(),
+            last_height = %flash_blocks.end(),
+            %count,
+            "no stacks tenure started or was extended in some bitcoin blocks"
+        );
+        metrics::counter!(Metrics::StacksFlashBlocksTotal).increment(count);
+    }
+}
+
+/// Record the tenure change and the signer bitvec of the stacks block in
+/// the given webhook, so that the coordinator can reason about the
+/// liveness of the stacks chain. Blocks from before Nakamoto have neither,
+/// so they are not recorded.
+async fn record_stacks_block_tenure(
+    ctx: &impl Context,
+    block_hash: StacksBlockHash,
+    new_block_event: &NewBlockEvent,
+    tenure_change: Option<TenureChange>,
+) {
+    let signer_bitvec: Option<Vec<bool>> = new_block_event.signer_bitvec.as_ref().map(|bitvec| {
+        (0..bitvec.len())
+            .map(|index| bitvec.get(index).unwrap_or_default())
+            .collect()
+    });
+    if tenure_change.is_none() && signer_bitvec.is_none() {
+        return;
+    }
+
+    let tenure = StacksBlockTenure {
+        block_hash,
+        tenure_change: tenure_change.map(|change| change.kind),
+        tenure_height: new_block_event.tenure_height,
+        signer_bitvec,
+    };
+    tracing::trace!(
+        tenure_height = ?tenure.tenure_height,
+        signed_count = ?tenure.signed_count(),
+        "recording the tenure of the stacks block"
+    );
+
+    let result = ctx
+        .get_storage_mut()
+        .write_stacks_block_tenure(&tenure)
+        .await;
+    if let Err(error) = result {
+        tracing::warn!(%error, "could not record the tenure of the stacks block");
+    }
+}
+
+/// Count, and optionally record, the contract events in the given webhook
+/// that do not come from one of the sbtc-registry contracts that we
+/// observe. These events are dropped by [`extract_registry_events`], and
+/// they only show up if the stacks node is misconfigured or someone is
+/// trying to pass off events of their own contract as sBTC events.
+async fn quarantine_filtered_events(
+    ctx: &impl Context,
+    block_hash: StacksBlockHash,
+    new_block_event: &NewBlockEvent,
+) {
+    let signer_config = &ctx.config().signer;
+    let registry_contracts = signer_config.observed_registry_contracts();
+    let quarantine = signer_config.event_observer.quarantine_filtered_events;
+
+    let filtered_events = new_block_event.events.iter().filter_map(|event| {
+        let contract_event = event.contract_event.as_ref()?;
+        let contract = &contract_event.contract_identifier;
+        (!registry_contracts.contains(contract)).then_some((event, contract_event))
+    });
+
+    for (event, contract_event) in filtered_events {
+        let contract_identifier = contract_event.contract_identifier.to_string();
+        tracing::warn!(
+            contract = %contract_identifier,
+            txid = %event.txid,
+            "dropping a contract event that is not from an sbtc-registry contract"
+        );
+        metrics::counter!(
+            Metrics::FilteredContractEventsTotal,
+            "contract" => contract_identifier.clone(),
+        )
+        .increment(1);
+
+        if !quarantine {
+            continue;
+        }
+        let quarantined_event = QuarantinedContractEvent {
+            txid: event.txid.into(),
+            block_hash,
+            event_index: event.event_index,
+            contract_identifier,
+            topic: contract_event.topic.clone(),
+        };
+        let result = ctx
+            .get_storage_mut()
+            .write_quarantined_contract_event(&quarantined_event)
+            .await;
+        if let Err(error) = result {
+            tracing::warn!(%error, "could not quarantine the contract event");
+        }
+    }
+}
+
+/// Extract the sBTC registry events from the given webhook for the stacks
+/// block with the given index block hash, along with the registry
+/// contract that emitted each of them. Events that could not be decoded
+/// are logged and skipped.
+async fn extract_registry_events(
+    ctx: &impl Context,
+    block_hash: StacksBlockHash,
+    new_block_event: NewBlockEvent,
+) -> Vec<(RegistryEvent, RegistryEventSource)> {
+    // Although the stacks node is supposed to only send sbtc-registry
+    // events, the node can be misconfigured or have some bug where it
+    // sends other events as well. Accepting such events would be a
+    // security issue, so we filter out events that are not from one of
+    // the sbtc-registry contracts that we observe.
+    //
+    // See https://github.com/stacks-network/sbtc/issues/501.
+    let registry_contracts = ctx.config().signer.observed_registry_contracts();
+
     // Although transactions can fail, only successful transactions emit
     // sBTC print events, since those events are emitted at the very end of
     // the contract call.
-    let events = new_block_event
+    let print_events: Vec<PrintEvent> = new_block_event
         .events
         .into_iter()
         .filter(|x| x.committed)
         .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid)))
-        .filter(|(ev, _)| &ev.contract_identifier == registry_address && ev.topic == "print")
-        .collect::<Vec<_>>();
+        .filter(|(ev, _)| registry_contracts.contains(&ev.contract_identifier))
+        .filter(|(ev, _)| ev.topic == "print")
+        .map(|(ev, txid)| {
+            let tx_info = TxInfo {
+                txid: sbtc::events::StacksTxid(txid.0),
+                block_id: block_hash.into(),
+            };
+            let source = RegistryEventSource {
+                txid: txid.into(),
+                block_hash,
+                registry: PrincipalData::Contract(ev.contract_identifier).into(),
+            };
+            (ev.value, tx_info, source)
+        })
+        .collect();
+
+    transform_print_events(print_events).await
+}
 
-    if events.is_empty() {
-        // If there are no events to process, we return early with a 200 OK
-        // status code so that the node does not retry the webhook.
-        return StatusCode::OK;
+/// The Clarity value of a print event of a registry contract, along with
+/// the transaction that emitted it and where it came from.
+type PrintEvent = (ClarityValue, TxInfo, RegistryEventSource);
+
+/// Transform the Clarity values of the given print events into registry
+/// events, keeping them in the order of the block.
+///
+/// Decoding Clarity values is CPU-bound, so blocks with at least
+/// [`PARALLEL_TRANSFORM_MIN_EVENTS`] events are split into one chunk per
+/// available CPU, and the chunks are transformed concurrently on the
+/// blocking thread pool. The chunks are put back together in order, so the
+/// events are still written in the order that they were emitted in, and
+/// the events of any one request are written in the order they happened.
+async fn transform_print_events(
+    print_events: Vec<PrintEvent>,
+) -> Vec<(RegistryEvent, RegistryEventSource)> {
+    if print_events.len() < PARALLEL_TRANSFORM_MIN_EVENTS {
+        return transform_print_event_chunk(print_events);
     }
 
-    tracing::debug!(count = %events.len(), "processing events for new stacks block");
+    let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = print_events.len().div_ceil(parallelism);
+    let num_events = print_events.len();
 
-    for (ev, txid) in events {
-        let tx_info = TxInfo {
-            txid: sbtc::events::StacksTxid(txid.0),
-            block_id: stacks_chaintip.block_hash.into(),
-        };
-        let res = match RegistryEvent::try_new(ev.value, tx_info) {
-            Ok(RegistryEvent::CompletedDeposit(event)) => {
-                handle_completed_deposit(&api.ctx, event.into()).await
+    let mut print_events = print_events.into_iter();
+    let mut tasks = Vec::with_capacity(parallelism);
+    loop {
+        let chunk: Vec<PrintEvent> = print_events.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        tasks.push(tokio::task::spawn_blocking(move || {
+            transform_print_event_chunk(chunk)
+        }));
+    }
+
+    let mut events = Vec::with_capacity(num_events);
+    for task in futures::future::join_all(tasks).await {
+        match task {
+            Ok(chunk) => events.extend(chunk),
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            // Blocking tasks are only cancelled when the runtime shuts
+            // down, in which case the events are not written anyway.
+            Err(error) => tracing::warn!(%error, "transforming registry events was cancelled"),
+        }
+    }
+    events
+}
+
+/// Transform the Clarity values of the given print events into registry
+/// events, skipping the ones that cannot be transformed.
+fn transform_print_event_chunk(
+    print_events: Vec<PrintEvent>,
+) -> Vec<(RegistryEvent, RegistryEventSource)> {
+    print_events
+        .into_iter()
+        .filter_map(|(value, tx_info, source)| {
+            let txid = source.txid;
+            RegistryEvent::try_new(value, tx_info)
+                .inspect_err(|error| {
+                    tracing::error!(%error, %txid, "got an error when transforming the event ClarityValue");
+                    Metrics::increment_registry_event_decode_failures(error, &source.registry);
+                })
+                .ok()
+                .inspect(|event| Metrics::increment_registry_events(event, &source.registry))
+                .map(|event| (event, source))
+        })
+        .collect()
+}
+
+/// Handle the registry events of the stacks block with the given index
+/// block hash, in order, along with the registry contract that emitted
+/// them.
+///
+/// The events are written to the database in a single database
+/// transaction, together with the record that the block was processed,
+/// so a failure part way through the block leaves nothing behind. If we
+/// got an error writing to the database, this might be an issue that will
+/// resolve itself if we try again in a few moments, so the block is
+/// retried with a jittered exponential backoff up to
+/// [`MAX_EVENT_HANDLING_ATTEMPTS`] times. Other errors are logged and the
+/// block is skipped, and it is handled again if the stacks node sends its
+/// webhook again; we rely on the redundancy of the other sBTC signers to
+/// ensure that the update is sent to Emily.
+async fn handle_events(
+    ctx: impl Context,
+    block_hash: StacksBlockHash,
+    events: Vec<(RegistryEvent, RegistryEventSource)>,
+    received_at: Instant,
+    correlation_id: Option<String>,
+) {
+    // Bitcoin chain tip updates take priority over the events, and only
+    // a bounded number of webhooks are digested at the same time.
+    let _stacks_event = ctx.state().chain_event_scheduler().stacks_event().await;
+
+    let events: Vec<(StacksBlockEvent, RegistryEventSource)> = events
+        .into_iter()
+        .map(|(event, source)| (event.into(), source))
+        .collect();
+
+    let mut retry_delay = EVENT_HANDLING_RETRY_DELAY;
+    for attempt in 1..=MAX_EVENT_HANDLING_ATTEMPTS {
+        match handle_block_events(&ctx, block_hash, &events, correlation_id.as_deref()).await {
+            Ok(()) => {
+                Metrics::record_stacks_block_processing_duration(received_at.elapsed());
+                break;
             }
-            Ok(RegistryEvent::WithdrawalAccept(event)) => {
-                handle_withdrawal_accept(&api.ctx, event.into()).await
+            Err(
+                Error::SqlxQuery(error)
+                | Error::SqlxBeginTransaction(error)
+                | Error::SqlxCommitTransaction(error),
+            ) if attempt < MAX_EVENT_HANDLING_ATTEMPTS => {
+                tracing::warn!(%error, attempt, "could not write the events; retrying");
+                tokio::time::sleep(ctx.rng().jitter(retry_delay)).await;
+                retry_delay *= 2;
             }
-            Ok(RegistryEvent::WithdrawalReject(event)) => {
-                handle_withdrawal_reject(&api.ctx, event.into()).await
+            Err(error) => {
+                tracing::error!(%error, attempt, "could not handle the events of the stacks block");
+                record_block_failure(&ctx, &block_hash, &error).await;
+                break;
             }
-            Ok(RegistryEvent::WithdrawalCreate(event)) => {
-                handle_withdrawal_create(&api.ctx, event.into()).await
+        }
+    }
+}
+
+/// Record that handling the events of the stacks block with the given
+/// index block hash failed with the given error. Once the block has
+/// failed [`EventObserverConfig::max_block_failures`] times, it is given
+/// up on and its webhooks are no longer handled.
+///
+/// [`EventObserverConfig::max_block_failures`]: crate::config::EventObserverConfig::max_block_failures
+async fn record_block_failure(ctx: &impl Context, block_hash: &StacksBlockHash, error: &Error) {
+    let storage = ctx.get_storage_mut();
+    let failure_count = match storage
+        .write_stacks_block_failure(block_hash, &error.to_string())
+        .await
+    {
+        Ok(failure_count) => failure_count,
+        Err(error) => {
+            tracing::warn!(%error, "could not record the failure of the stacks block");
+            return;
+        }
+    };
+
+    let max_block_failures = ctx.config().signer.event_observer.max_block_failures;
+    if failure_count >= max_block_failures.get() {
+        tracing::error!(
+            %failure_count,
+            "giving up on the events of the stacks block; they will not be handled again"
+        );
+    }
+}
+
+/// The rows to write for the events of a stacks block, and the webhook
+/// events to send once they have been written.
+struct EventBatch {
+    /// The events of the block that are written to the database.
+    rows: StacksBlockEvents,
+    /// The events that webhook subscribers are notified about.
+    notifications: Vec<WebhookEvent>,
+    /// The ID that correlates the handling of the events with the logs of
+    /// the sender of the webhook, see [`TraceContext`].
+    correlation_id: Option<String>,
+}
+
+impl EventBatch {
+    /// Add the given event, emitted by the given source, to the rows
+    /// that are written to the database.
+    fn push(&mut self, event: StacksBlockEvent, source: RegistryEventSource) {
+        self.rows.events.push((event, source));
+    }
+}
+
+/// Handle the given events of the stacks block with the given index block
+/// hash with the handler for their topic, write all of them to the
+/// database with [`DbWrite::write_stacks_block_events`] and then notify
+/// webhook subscribers about them. The spans of the handlers carry the
+/// given correlation ID, if there is one.
+///
+/// [`DbWrite::write_stacks_block_events`]: crate::storage::DbWrite::write_stacks_block_events
+async fn handle_block_events(
+    ctx: &impl Context,
+    block_hash: StacksBlockHash,
+    events: &[(StacksBlockEvent, RegistryEventSource)],
+    correlation_id: Option<&str>,
+) -> Result<(), Error> {
+    let mut batch = EventBatch {
+        rows: StacksBlockEvents {
+            block_hash,
+            events: Vec::with_capacity(events.len()),
+            integrity_hashes: Vec::new(),
+        },
+        notifications: Vec::new(),
+        correlation_id: correlation_id.map(ToString::to_string),
+    };
+
+    for (event, source) in events.iter().cloned() {
+        match event {
+            StacksBlockEvent::CompletedDeposit(event) => {
+                handle_completed_deposit(ctx, &mut batch, event, source).await?
             }
-            Ok(RegistryEvent::KeyRotation(event)) => {
-                handle_key_rotation(&api.ctx, event.into()).await
+            StacksBlockEvent::WithdrawalAccept(event) => {
+                handle_withdrawal_accept(&mut batch, event, source)
             }
-            Err(error) => {
-                tracing::error!(%error, %txid, "got an error when transforming the event ClarityValue");
-                continue;
+            StacksBlockEvent::WithdrawalReject(event) => {
+                handle_withdrawal_reject(&mut batch, event, source)
+            }
+            StacksBlockEvent::WithdrawalCreate(event) => {
+                handle_withdrawal_create(&mut batch, event, source)
+            }
+            StacksBlockEvent::KeyRotation(event) => {
+                handle_key_rotation(ctx, &mut batch, event, source)
             }
-        };
-        // If we got an error writing to the database, this might be an
-        // issue that will resolve itself if we try again in a few moments.
-        // So we return a non success status code so that the node retries
-        // in a second.
-        if let Err(Error::SqlxQuery(error)) = res {
-            tracing::error!(%error, "could not write an event to the database");
-            return StatusCode::INTERNAL_SERVER_ERROR;
-        // If we got an error processing the event, we log the error and
-        // return a success status code so that the node does not retry the
-        // webhook. We rely on the redundancy of the other sBTC signers to
-        // ensure that the update is sent to Emily.
-        } else if let Err(error) = res {
-            tracing::error!(%error, "could not process an event");
         }
     }
 
-    StatusCode::OK
+    ctx.get_storage_mut()
+        .write_stacks_block_events(&batch.rows)
+        .await?;
+
+    tracing::debug!(count = %batch.rows.events.len(), "handled the events of the stacks block");
+    for event in batch.notifications {
+        notify_webhook_subscribers(ctx, event);
+    }
+    Ok(())
 }
 
-/// Processes a completed deposit event by adding the event to the database.
+/// Processes a completed deposit event by adding the event to the batch.
+///
+/// The registry contract only lets a deposit be completed once on a stacks
+/// fork, but the same deposit can be completed on competing forks, and the
+/// stacks node may deliver an event more than once. Events that are
+/// already in the database are ignored. Events for deposits that were
+/// completed on another fork are recorded alongside the earlier ones, but
+/// webhook subscribers are only notified about them if none of the
+/// earlier events is on the canonical stacks blockchain, so that a
+/// deposit is not reported as completed twice.
 ///
 /// # Parameters
 /// - `ctx`: Shared application context containing configuration and database access.
+/// - `batch`: The batch of rows and notifications for the stacks block.
 /// - `event`: The deposit event to be processed.
+/// - `source`: The registry contract that emitted the event.
 ///
 /// # Returns
 /// - `Result<(), Error>`: In case of a database error, returns an `Error`
 #[tracing::instrument(skip_all, fields(
+    correlation_id = batch.correlation_id.as_deref(),
     bitcoin_outpoint = %event.outpoint,
     stacks_txid = %event.txid
 ))]
 async fn handle_completed_deposit(
     ctx: &impl Context,
+    batch: &mut EventBatch,
     event: CompletedDepositEvent,
+    source: RegistryEventSource,
 ) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_completed_deposit_event(&event)
+    let storage = ctx.get_storage();
+    let recorded = storage
+        .get_completed_deposit_events_for_outpoint(&event.outpoint)
         .await?;
+    if recorded.contains(&event) {
+        tracing::debug!(topic = "completed-deposit", "stacks event already handled");
+        return Ok(());
+    }
+
+    let reported = if recorded.is_empty() {
+        None
+    } else {
+        let block_ids: Vec<_> = recorded.iter().map(|event| event.block_id).collect();
+        tracing::warn!(
+            other_block_ids = ?block_ids,
+            "deposit was completed on more than one stacks fork"
+        );
+        Metrics::increment_duplicate_completed_deposits();
+
+        match ctx.state().bitcoin_chain_tip() {
+            Some(chain_tip) => {
+                storage
+                    .get_canonical_completed_deposit_event(&chain_tip.block_hash, &event.outpoint)
+                    .await?
+            }
+            None => None,
+        }
+    };
 
-    tracing::debug!(topic = "completed-deposit", "handled stacks event");
+    // The event and its integrity hash are committed together, so that
+    // the integrity verifier never sees one without the other.
+    batch
+        .rows
+        .integrity_hashes
+        .extend(integrity::integrity_hash(ctx, &event));
+    match reported {
+        Some(canonical) => tracing::debug!(
+            canonical_block_id = %canonical.block_id,
+            "deposit completion already reported on the canonical stacks fork"
+        ),
+        None => batch
+            .notifications
+            .push(WebhookEvent::DepositCompleted(event.clone())),
+    }
+    batch.push(StacksBlockEvent::CompletedDeposit(event), source);
     Ok(())
 }
 
-/// Handles a withdrawal acceptance event by adding the event to the database.
+/// Processes a withdrawal acceptance event by adding the event to the
+/// batch.
 ///
 /// # Parameters
-/// - `ctx`: Shared application context with configuration and database access.
+/// - `batch`: The batch of rows and notifications for the stacks block.
 /// - `event`: The withdrawal acceptance event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
+/// - `source`: The registry contract that emitted the event.
 #[tracing::instrument(skip_all, fields(
+    correlation_id = batch.correlation_id.as_deref(),
     stacks_txid = %event.txid,
     request_id = %event.request_id
 ))]
-async fn handle_withdrawal_accept(
-    ctx: &impl Context,
+fn handle_withdrawal_accept(
+    batch: &mut EventBatch,
     event: WithdrawalAcceptEvent,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_accept_event(&event)
-        .await?;
-
-    tracing::debug!(topic = "withdrawal-accept", "handled stacks event");
-
-    Ok(())
+    source: RegistryEventSource,
+) {
+    batch
+        .notifications
+        .push(WebhookEvent::WithdrawalAccepted(event.clone()));
+    batch.push(StacksBlockEvent::WithdrawalAccept(event), source);
 }
 
-/// Processes a withdrawal creation event by adding the event to the database.
+/// Processes a withdrawal creation event by adding the event to the batch.
 ///
 /// # Parameters
-/// - `ctx`: Shared application context containing configuration and database access.
+/// - `batch`: The batch of rows and notifications for the stacks block.
 /// - `event`: The withdrawal creation event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
+/// - `source`: The registry contract that emitted the event.
 #[tracing::instrument(skip_all, fields(
+    correlation_id = batch.correlation_id.as_deref(),
     stacks_txid = %event.txid,
     request_id = %event.request_id
 ))]
-async fn handle_withdrawal_create(
-    ctx: &impl Context,
+fn handle_withdrawal_create(
+    batch: &mut EventBatch,
     event: WithdrawalRequest,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_request(&event)
-        .await?;
-
-    tracing::debug!(topic = "withdrawal-create", "handled stacks event");
-
-    Ok(())
+    source: RegistryEventSource,
+) {
+    batch.push(StacksBlockEvent::WithdrawalCreate(event), source);
 }
 
-/// Processes a withdrawal rejection event by adding the event to the database.
+/// Processes a withdrawal rejection event by adding the event to the
+/// batch.
 ///
 /// # Parameters
-/// - `ctx`: Shared application context containing configuration and database access.
+/// - `batch`: The batch of rows and notifications for the stacks block.
 /// - `event`: The withdrawal rejection event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
+/// - `source`: The registry contract that emitted the event.
 #[tracing::instrument(skip_all, fields(
+    correlation_id = batch.correlation_id.as_deref(),
     stacks_txid = %event.txid,
     request_id = %event.request_id
 ))]
-async fn handle_withdrawal_reject(
-    ctx: &impl Context,
+fn handle_withdrawal_reject(
+    batch: &mut EventBatch,
     event: WithdrawalRejectEvent,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_reject_event(&event)
-        .await?;
-
-    tracing::debug!(topic = "withdrawal-reject", "handled stacks event");
-
-    Ok(())
+    source: RegistryEventSource,
+) {
+    batch
+        .notifications
+        .push(WebhookEvent::WithdrawalRejected(event.clone()));
+    batch.push(StacksBlockEvent::WithdrawalReject(event), source);
 }
 
 #[tracing::instrument(skip_all, fields(
+    correlation_id = batch.correlation_id.as_deref(),
     stacks_txid = %event.txid,
     address = %event.address,
     aggregate_key = %event.aggregate_key
 ))]
-async fn handle_key_rotation(ctx: &impl Context, event: KeyRotationEvent) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_rotate_keys_transaction(&event)
-        .await?;
-
-    tracing::debug!(topic = "key-rotation", "handled stacks event");
+fn handle_key_rotation(
+    ctx: &impl Context,
+    batch: &mut EventBatch,
+    event: KeyRotationEvent,
+    source: RegistryEventSource,
+) {
+    batch
+        .rows
+        .integrity_hashes
+        .extend(integrity::integrity_hash(ctx, &event));
+    batch
+        .notifications
+        .push(WebhookEvent::KeysRotated(event.clone()));
+    batch.push(StacksBlockEvent::KeyRotation(event), source);
+}
 
-    Ok(())
+/// Signal the [`WebhookDispatcher`](crate::webhooks::WebhookDispatcher)
+/// to notify subscribers about a handled event.
+///
+/// We use the signal sender directly, rather than [`Context::signal`],
+/// since having no one listening for webhook events is not an error.
+fn notify_webhook_subscribers(ctx: &impl Context, event: WebhookEvent) {
+    let _ = ctx.get_signal_sender().send(event.into());
 }
 
 #[cfg(test)]
@@ -301,19 +937,28 @@ mod tests {
     use axum::http::Request;
     use bitcoin::OutPoint;
     use bitvec::array::BitArray;
-    use clarity::vm::types::PrincipalData;
+    use clarity::vm::representations::ContractName;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::types::StandardPrincipalData;
     use fake::Fake as _;
-    use rand::rngs::OsRng;
     use sbtc::events::KeyRotationEvent;
     use secp256k1::SECP256K1;
-    use stacks_common::types::chainstate::StacksBlockId;
     use test_case::test_case;
     use tower::ServiceExt as _;
 
+    use crate::amount::SbtcAmount;
+    use crate::api::NEW_BLOCK_SIGNATURE_HEADER;
+    use crate::api::SBTC_REGISTRY_CONTRACT_NAME;
+    use crate::api::compression::gzip;
     use crate::api::get_router;
+    use crate::api::sign_new_block_body;
+    use crate::context::SignerEvent;
+    use crate::context::SignerSignal;
     use crate::storage::memory::Store;
+    use crate::storage::model;
     use crate::storage::model::DepositRequest;
     use crate::storage::model::StacksPrincipal;
+    use crate::testing::api::admin_request;
     use crate::testing::context::*;
     use crate::testing::get_rng;
     use crate::testing::storage::model::TestData;
@@ -340,6 +985,16 @@ mod tests {
     const ROTATE_KEYS_AND_INVALID_EVENT_WEBHOOK: &str =
         include_str!("../../tests/fixtures/rotate-keys-and-invalid-event.json");
 
+    /// The secret that authenticates `/new_block` webhooks in the tests
+    /// that require authentication.
+    const NEW_BLOCK_SECRET: &str = "a-shared-secret-for-new-blocks";
+
+    /// Handle the given event as the only event of its stacks block.
+    async fn handle_event(ctx: &impl Context, event: StacksBlockEvent) -> Result<(), Error> {
+        let source: RegistryEventSource = fake::Faker.fake_with_rng(&mut get_rng());
+        handle_block_events(ctx, source.block_hash, &[(event, source)], None).await
+    }
+
     #[test_case(COMPLETED_DEPOSIT_WEBHOOK, |db| !db.completed_deposit_events.contains_key(&OutPoint::null()); "completed-deposit")]
     #[test_case(WITHDRAWAL_CREATE_WEBHOOK, |db| !db.withdrawal_requests.contains_key(&(1, StacksBlockId::from_hex("75b02b9884ec41c05f2cfa6e20823328321518dd0b027e7b609b63d4d1ea7c78").unwrap().into())); "withdrawal-create")]
     #[test_case(WITHDRAWAL_ACCEPT_WEBHOOK, |db| !db.withdrawal_accept_events.contains_key(&1); "withdrawal-accept")]
@@ -365,12 +1020,65 @@ mod tests {
         let state = State(api);
         let body = body_str.to_string();
 
-        let res = new_block_handler(state, body).await;
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
         assert_eq!(res, StatusCode::OK);
         // Now there should be something here
         assert!(!table_is_empty(db.lock().await));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn events_of_large_blocks_are_transformed_in_order() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        // Enough copies of the event of the webhook for them to be
+        // transformed on more than one thread, each with its own txid.
+        let mut webhook: serde_json::Value =
+            serde_json::from_str(WITHDRAWAL_CREATE_WEBHOOK).unwrap();
+        let event = webhook["events"][0].clone();
+        let num_events = PARALLEL_TRANSFORM_MIN_EVENTS * 3 + 1;
+        let events: Vec<serde_json::Value> = (0..num_events)
+            .map(|index| {
+                let mut event = event.clone();
+                event["txid"] = format!("0x{index:064x}").into();
+                event
+            })
+            .collect();
+        webhook["events"] = events.into();
+        let new_block_event: NewBlockEvent = serde_json::from_value(webhook).unwrap();
+
+        let block_hash = new_block_event.index_block_hash.into();
+        let events = extract_registry_events(&ctx, block_hash, new_block_event).await;
+
+        assert_eq!(events.len(), num_events);
+        for (index, (event, source)) in events.iter().enumerate() {
+            assert!(matches!(event, RegistryEvent::WithdrawalCreate(_)));
+            assert_eq!(source.txid.to_string(), format!("{index:064x}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn bodies_that_are_not_utf8_are_acknowledged() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let mut body = WITHDRAWAL_CREATE_WEBHOOK.as_bytes().to_vec();
+        body.push(0xff);
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let db = ctx.inner_storage();
+        let db = db.lock().await;
+        assert!(db.withdrawal_requests.is_empty());
+        assert!(db.raw_stacks_events.is_empty());
+    }
+
     #[test_case(COMPLETED_DEPOSIT_WEBHOOK, |db| !db.completed_deposit_events.contains_key(&OutPoint::null()); "completed-deposit")]
     #[test_case(WITHDRAWAL_CREATE_WEBHOOK, |db| !db.withdrawal_requests.contains_key(&(1, StacksBlockId::from_hex("75b02b9884ec41c05f2cfa6e20823328321518dd0b027e7b609b63d4d1ea7c78").unwrap().into())); "withdrawal-create")]
     #[test_case(WITHDRAWAL_ACCEPT_WEBHOOK, |db| !db.withdrawal_accept_events.contains_key(&1); "withdrawal-accept")]
@@ -401,7 +1109,7 @@ mod tests {
         let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
         let identifier = QualifiedContractIdentifier::new(issuer, contract_name.clone());
 
-        let fishy_principal: StacksPrincipal = fake::Faker.fake_with_rng(&mut OsRng);
+        let fishy_principal: StacksPrincipal = fake::Faker.fake_with_rng(&mut ctx.rng());
         let fishy_issuer = match PrincipalData::from(fishy_principal) {
             PrincipalData::Contract(contract) => contract.issuer,
             PrincipalData::Standard(standard) => standard,
@@ -432,7 +1140,7 @@ mod tests {
 
         // Okay now to do the check.
         let state = State(api.clone());
-        let res = new_block_handler(state, body).await;
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
         assert_eq!(res, StatusCode::OK);
 
         // This event should be filtered out, so the table should still be
@@ -440,6 +1148,265 @@ mod tests {
         assert!(table_is_empty(db.lock().await));
     }
 
+    /// Check that events from an additional configured registry contract
+    /// are handled and tagged with the contract that emitted them.
+    #[tokio::test]
+    async fn events_from_additional_registry_contracts_are_tagged() {
+        let other_registry = QualifiedContractIdentifier::parse(
+            "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.sbtc-registry",
+        )
+        .unwrap();
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.registry_contracts = vec![other_registry.clone()];
+            })
+            .build();
+
+        let issuer = StandardPrincipalData::from(ctx.config().signer.deployer.clone());
+        let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
+        let identifier = QualifiedContractIdentifier::new(issuer, contract_name);
+        let body =
+            WITHDRAWAL_CREATE_WEBHOOK.replace(&identifier.to_string(), &other_registry.to_string());
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let db = ctx.inner_storage();
+        let store = db.lock().await;
+        assert!(!store.withdrawal_requests.is_empty());
+
+        let expected = StacksPrincipal::from(PrincipalData::Contract(other_registry));
+        assert!(!store.registry_event_sources.is_empty());
+        assert!(
+            store
+                .registry_event_sources
+                .values()
+                .all(|registry| registry == &expected)
+        );
+    }
+
+    /// Check that events from contracts that are not observed registry
+    /// contracts are dropped, and only recorded when quarantine is on.
+    #[test_case(false; "dropped")]
+    #[test_case(true; "quarantined")]
+    #[tokio::test]
+    async fn events_from_unobserved_contracts_are_filtered(quarantine: bool) {
+        let spoofed_registry = QualifiedContractIdentifier::parse(
+            "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.sbtc-registry",
+        )
+        .unwrap();
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.quarantine_filtered_events = quarantine;
+            })
+            .build();
+
+        let issuer = StandardPrincipalData::from(ctx.config().signer.deployer.clone());
+        let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
+        let identifier = QualifiedContractIdentifier::new(issuer, contract_name);
+        let body = WITHDRAWAL_CREATE_WEBHOOK
+            .replace(&identifier.to_string(), &spoofed_registry.to_string());
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let db = ctx.inner_storage();
+        let store = db.lock().await;
+        assert!(store.withdrawal_requests.is_empty());
+
+        let quarantined = &store.quarantined_contract_events;
+        assert_eq!(!quarantined.is_empty(), quarantine);
+        assert!(
+            quarantined
+                .values()
+                .all(|event| event.contract_identifier == spoofed_registry.to_string())
+        );
+    }
+
+    /// Check that the tenure height and the signer bitvec in a webhook are
+    /// recorded for the stacks block.
+    #[tokio::test]
+    async fn stacks_block_tenures_are_recorded() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let new_block_event = serde_json::from_str::<NewBlockEvent>(ROTATE_KEYS_WEBHOOK).unwrap();
+        let block_hash = StacksBlockHash::from(new_block_event.index_block_hash);
+        let tenure_change = TenureChange::from_block_event(&new_block_event);
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let body = ROTATE_KEYS_WEBHOOK.to_string();
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let tenure = ctx
+            .get_storage()
+            .get_stacks_block_tenure(&block_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        let tenure_change_kind = tenure_change.map(|change| change.kind);
+        assert_eq!(tenure.tenure_change, tenure_change_kind);
+        assert_eq!(tenure.tenure_height, Some(32));
+        assert_eq!(tenure.signer_bitvec, Some(vec![true; 8]));
+        assert_eq!(tenure.signed_count(), Some(8));
+    }
+
+    /// Check that the events of a stacks block are not handled again when
+    /// the stacks node sends the same webhook twice.
+    #[tokio::test]
+    async fn repeated_webhooks_are_skipped() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let body = WITHDRAWAL_CREATE_WEBHOOK.to_string();
+        let new_block_event = serde_json::from_str::<NewBlockEvent>(&body).unwrap();
+        let block_hash = StacksBlockHash::from(new_block_event.index_block_hash);
+
+        let db = ctx.inner_storage();
+        let processed = db.lock().await.processed_stacks_blocks.clone();
+        assert!(!processed.contains(&block_hash));
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.clone().into()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        // All the events were handled, so the block is recorded.
+        {
+            let mut store = db.lock().await;
+            assert!(store.processed_stacks_blocks.contains(&block_hash));
+            assert!(!store.withdrawal_requests.is_empty());
+            store.withdrawal_requests.clear();
+        }
+
+        // The same webhook again is acknowledged without its events being
+        // handled again.
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+        assert!(db.lock().await.withdrawal_requests.is_empty());
+
+        // Raw webhook bodies are not kept by default.
+        assert!(db.lock().await.raw_stacks_events.is_empty());
+    }
+
+    /// Check that all the events of a stacks block are written together,
+    /// along with the sweep completions that they reference, and that the
+    /// block is recorded as processed.
+    #[tokio::test]
+    async fn block_events_are_written_together() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let mut rng = get_rng();
+
+        let block_hash: StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let source = RegistryEventSource {
+            block_hash,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        let deposit = CompletedDepositEvent {
+            block_id: block_hash,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        let request = WithdrawalRequest {
+            block_hash,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        let accept = WithdrawalAcceptEvent {
+            block_id: block_hash,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        let events = [
+            StacksBlockEvent::CompletedDeposit(deposit.clone()),
+            StacksBlockEvent::WithdrawalCreate(request.clone()),
+            StacksBlockEvent::WithdrawalAccept(accept.clone()),
+        ]
+        .map(|event| (event, source.clone()));
+
+        handle_block_events(&ctx, block_hash, &events, None)
+            .await
+            .unwrap();
+
+        let db = ctx.inner_storage();
+        assert!(db.is_stacks_block_processed(&block_hash).await.unwrap());
+        let deposits = db
+            .get_completed_deposit_events_for_outpoint(&deposit.outpoint)
+            .await
+            .unwrap();
+        assert_eq!(deposits, vec![deposit.clone()]);
+        for sweep_txid in [deposit.sweep_txid, accept.sweep_txid] {
+            let completions = db.get_sweep_completions(&sweep_txid).await.unwrap();
+            assert_eq!(completions.len(), 1);
+        }
+
+        let store = db.lock().await;
+        let key = (request.request_id, block_hash);
+        assert!(store.withdrawal_requests.contains_key(&key));
+        assert!(
+            store
+                .withdrawal_accept_events
+                .contains_key(&accept.request_id)
+        );
+    }
+
+    /// Check that the webhooks of a stacks block are no longer handled once
+    /// handling its events failed the maximum number of times.
+    #[tokio::test]
+    async fn blocks_that_failed_too_often_are_skipped() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let body = WITHDRAWAL_CREATE_WEBHOOK.to_string();
+        let new_block_event = serde_json::from_str::<NewBlockEvent>(&body).unwrap();
+        let block_hash = StacksBlockHash::from(new_block_event.index_block_hash);
+        let max_block_failures = ctx.config().signer.event_observer.max_block_failures.get();
+
+        let db = ctx.inner_storage();
+        for failure_count in 1..max_block_failures {
+            let count = db
+                .write_stacks_block_failure(&block_hash, "database unavailable")
+                .await
+                .unwrap();
+            assert_eq!(count, failure_count);
+        }
+
+        // The block has not failed often enough to be given up on.
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.clone().into()).await;
+        assert_eq!(res, StatusCode::OK);
+        {
+            let mut store = db.lock().await;
+            assert!(!store.withdrawal_requests.is_empty());
+            store.withdrawal_requests.clear();
+            store.processed_stacks_blocks.clear();
+        }
+
+        db.write_stacks_block_failure(&block_hash, "database unavailable")
+            .await
+            .unwrap();
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+        assert!(db.lock().await.withdrawal_requests.is_empty());
+    }
+
     /// Tests handling a completed deposit event.
     /// This function validates that a completed deposit is correctly processed,
     /// including verifying the successful database update.
@@ -481,12 +1448,12 @@ mod tests {
             outpoint: deposit_request.outpoint(),
             txid: stacks_txid,
             block_id: stacks_chaintip.block_hash,
-            amount: deposit_request.amount - btc_fee,
+            amount: SbtcAmount::new(deposit_request.amount - btc_fee),
             sweep_block_hash: bitcoin_block.block_hash,
             sweep_block_height: bitcoin_block.block_height,
             sweep_txid: txid,
         };
-        let res = handle_completed_deposit(&ctx, event).await;
+        let res = handle_event(&ctx, StacksBlockEvent::CompletedDeposit(event)).await;
         assert!(res.is_ok());
         let db = db.lock().await;
         assert_eq!(db.completed_deposit_events.len(), 1);
@@ -496,6 +1463,111 @@ mod tests {
         );
     }
 
+    /// Check that completed-deposit events for the same deposit on
+    /// competing stacks forks are all recorded, while webhook subscribers
+    /// are not notified twice about a completion on the canonical fork.
+    #[tokio::test]
+    async fn completed_deposits_on_competing_forks() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let db = ctx.inner_storage();
+
+        let bitcoin_block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut ctx.rng());
+        db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        ctx.state()
+            .set_bitcoin_chain_tip(bitcoin_block.clone().into());
+
+        // Three forks branch off the same stacks block. The highest one
+        // is the canonical fork.
+        let parent_hash: model::StacksBlockHash = fake::Faker.fake_with_rng(&mut ctx.rng());
+        let stacks_block = |block_height: u64| model::StacksBlock {
+            block_hash: fake::Faker.fake_with_rng(&mut ctx.rng()),
+            block_height: block_height.into(),
+            parent_hash,
+            bitcoin_anchor: bitcoin_block.block_hash,
+        };
+        let canonical = stacks_block(2);
+        let orphaned = stacks_block(1);
+        let competing = stacks_block(1);
+        for block in [&canonical, &orphaned, &competing] {
+            db.write_stacks_block(block).await.unwrap();
+        }
+
+        let outpoint = OutPoint::new(fake::Faker.fake::<model::BitcoinTxId>().into(), 0);
+        let completed_in = |block: &model::StacksBlock| CompletedDepositEvent {
+            outpoint,
+            block_id: block.block_hash,
+            ..fake::Faker.fake_with_rng(&mut ctx.rng())
+        };
+        let orphaned_event = completed_in(&orphaned);
+        let canonical_event = completed_in(&canonical);
+        let competing_event = completed_in(&competing);
+
+        let mut signals = ctx.get_signal_receiver();
+
+        // The first completion is reported, and redelivering it is a
+        // no-op.
+        handle_event(
+            &ctx,
+            StacksBlockEvent::CompletedDeposit(orphaned_event.clone()),
+        )
+        .await
+        .unwrap();
+        handle_event(
+            &ctx,
+            StacksBlockEvent::CompletedDeposit(orphaned_event.clone()),
+        )
+        .await
+        .unwrap();
+        // The earlier completion is not on the canonical fork, so this
+        // one is reported too.
+        handle_event(
+            &ctx,
+            StacksBlockEvent::CompletedDeposit(canonical_event.clone()),
+        )
+        .await
+        .unwrap();
+        // But once the deposit was completed on the canonical fork, it is
+        // not reported again.
+        handle_event(
+            &ctx,
+            StacksBlockEvent::CompletedDeposit(competing_event.clone()),
+        )
+        .await
+        .unwrap();
+
+        let mut notified = Vec::new();
+        while let Ok(signal) = signals.try_recv() {
+            if let SignerSignal::Event(SignerEvent::WebhookEvent(event)) = signal {
+                notified.push(*event);
+            }
+        }
+        assert_eq!(
+            notified,
+            vec![
+                WebhookEvent::DepositCompleted(orphaned_event.clone()),
+                WebhookEvent::DepositCompleted(canonical_event.clone()),
+            ]
+        );
+
+        let events = db
+            .get_completed_deposit_events_for_outpoint(&outpoint)
+            .await
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![orphaned_event, canonical_event.clone(), competing_event]
+        );
+
+        let event = db
+            .get_canonical_completed_deposit_event(&bitcoin_block.block_hash, &outpoint)
+            .await
+            .unwrap();
+        assert_eq!(event, Some(canonical_event));
+    }
+
     /// Tests handling a withdrawal acceptance event.
     /// This function validates that when a withdrawal is accepted, the handler
     /// correctly updates the database and returns the expected response.
@@ -531,14 +1603,14 @@ mod tests {
             outpoint: OutPoint { txid: *txid, vout: 0 },
             txid: fake::Faker.fake_with_rng(&mut rng),
             block_id: stacks_block.block_hash,
-            fee: 1,
+            fee: bitcoin::Amount::from_sat(1),
             signer_bitmap: BitArray::<_>::ZERO,
             sweep_block_hash: bitcoin_block.block_hash,
             sweep_block_height: bitcoin_block.block_height,
             sweep_txid: txid,
         };
 
-        let res = handle_withdrawal_accept(&ctx, event).await;
+        let res = handle_event(&ctx, StacksBlockEvent::WithdrawalAccept(event)).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -584,7 +1656,7 @@ mod tests {
             bitcoin_block_height: test_data.bitcoin_blocks[0].block_height,
         };
 
-        let res = handle_withdrawal_create(&ctx, event).await;
+        let res = handle_event(&ctx, StacksBlockEvent::WithdrawalCreate(event)).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -633,7 +1705,7 @@ mod tests {
             signer_bitmap: BitArray::<_>::ZERO,
         };
 
-        let res = handle_withdrawal_reject(&ctx, event).await;
+        let res = handle_event(&ctx, StacksBlockEvent::WithdrawalReject(event)).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -667,7 +1739,7 @@ mod tests {
         };
 
         let event: crate::storage::model::KeyRotationEvent = event.into();
-        let res = handle_key_rotation(&ctx, event.clone()).await;
+        let res = handle_event(&ctx, StacksBlockEvent::KeyRotation(event.clone())).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -713,6 +1785,163 @@ mod tests {
         }
     }
 
+    #[test_case(EVENT_OBSERVER_BODY_LIMIT + 1, StatusCode::OK; "compressed event over limit")]
+    #[test_case(EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT, StatusCode::OK; "decompressed within limit")]
+    #[test_case(EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT + 1, StatusCode::PAYLOAD_TOO_LARGE; "decompressed over limit")]
+    #[tokio::test]
+    async fn test_big_compressed_event(event_size: usize, expected_status: StatusCode) {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let state = ApiState { ctx: ctx.clone() };
+        let app = get_router().with_state(state);
+
+        let db = ctx.inner_storage();
+        assert!(db.lock().await.rotate_keys_transactions.is_empty());
+
+        let mut event: String = " ".repeat(event_size - ROTATE_KEYS_WEBHOOK.len());
+        event.push_str(ROTATE_KEYS_WEBHOOK);
+        let compressed = gzip(event.as_bytes());
+        assert!(compressed.len() < EVENT_OBSERVER_BODY_LIMIT);
+
+        let request = Request::builder()
+            .uri("/new_block")
+            .method(Method::POST)
+            .header("content-encoding", "gzip")
+            .body(Body::from(compressed))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected_status);
+
+        let stored = !db.lock().await.rotate_keys_transactions.is_empty();
+        assert_eq!(stored, expected_status == StatusCode::OK);
+    }
+
+    /// Return the signature of the given webhook body, made with the
+    /// secret in [`NEW_BLOCK_SECRET`].
+    fn signature(body: &str) -> String {
+        sign_new_block_body(NEW_BLOCK_SECRET, body.as_bytes())
+    }
+
+    #[test_case(None, StatusCode::UNAUTHORIZED; "no-auth-header")]
+    #[test_case(Some(("authorization", "not-the-secret".to_string())), StatusCode::UNAUTHORIZED; "wrong-secret")]
+    #[test_case(Some(("authorization", format!("Bearer {NEW_BLOCK_SECRET}"))), StatusCode::OK; "shared-secret")]
+    #[test_case(Some((NEW_BLOCK_SIGNATURE_HEADER, signature(ROTATE_KEYS_WEBHOOK))), StatusCode::OK; "body-signature")]
+    #[test_case(Some((NEW_BLOCK_SIGNATURE_HEADER, signature("{}"))), StatusCode::UNAUTHORIZED; "signature-of-another-body")]
+    #[tokio::test]
+    async fn new_block_webhooks_are_authenticated(
+        header: Option<(&'static str, String)>,
+        expected_status: StatusCode,
+    ) {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                let auth = &mut settings.signer.event_observer.new_block_auth;
+                auth.secret = Some(NEW_BLOCK_SECRET.to_string());
+            })
+            .build();
+
+        let app = get_router().with_state(ApiState { ctx: ctx.clone() });
+        let db = ctx.inner_storage();
+
+        let mut request = Request::builder().uri("/new_block").method(Method::POST);
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        let request = request.body(Body::from(ROTATE_KEYS_WEBHOOK)).unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected_status);
+
+        // Only authenticated webhooks get their events handled.
+        let is_handled = !db.lock().await.rotate_keys_transactions.is_empty();
+        assert_eq!(is_handled, expected_status == StatusCode::OK);
+    }
+
+    /// Send an authenticated request to replay the stacks block with the
+    /// given index block hash.
+    fn replay_request(block_hash: &str) -> Request<Body> {
+        let uri = format!("/admin/replay_block/{block_hash}");
+        admin_request(Method::POST, &uri, None)
+    }
+
+    #[tokio::test]
+    async fn dropped_events_are_restored_by_replaying_the_block() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .with_admin_secret()
+            .modify_settings(|settings| {
+                let event_observer = &mut settings.signer.event_observer;
+                event_observer.raw_events_retention = Duration::from_secs(3600);
+            })
+            .build();
+
+        let app = get_router().with_state(ApiState { ctx: ctx.clone() });
+        let db = ctx.inner_storage();
+
+        let webhook = serde_json::from_str::<NewBlockEvent>(ROTATE_KEYS_WEBHOOK).unwrap();
+        let block_hash = StacksBlockHash::from(webhook.index_block_hash).to_string();
+
+        // Nothing is stored for the block until its webhook arrives.
+        let response = app.clone().oneshot(replay_request(&block_hash)).await;
+        assert_eq!(response.unwrap().status(), StatusCode::NOT_FOUND);
+
+        let request = Request::builder()
+            .uri("/new_block")
+            .method(Method::POST)
+            .body(Body::from(ROTATE_KEYS_WEBHOOK))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The raw body of the webhook is stored in the background.
+        let stacks_block_hash = StacksBlockHash::from(webhook.index_block_hash);
+        for _ in 0..100 {
+            if db
+                .lock()
+                .await
+                .raw_stacks_events
+                .contains_key(&stacks_block_hash)
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Pretend that a bug had the event dropped even though the block
+        // was recorded as processed.
+        db.lock().await.rotate_keys_transactions.clear();
+
+        let mut signals = ctx.get_signal_receiver();
+        let mut notifications = || {
+            let mut count = 0;
+            while let Ok(signal) = signals.try_recv() {
+                if let SignerSignal::Event(SignerEvent::WebhookEvent(_)) = signal {
+                    count += 1;
+                }
+            }
+            count
+        };
+
+        let response = app.clone().oneshot(replay_request(&block_hash)).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        let rotations = db.lock().await.rotate_keys_transactions.clone();
+        assert_eq!(rotations.values().flatten().count(), 1);
+        assert_eq!(notifications(), 1);
+
+        // Replaying the block again finds the event in the database, so
+        // it is neither written again nor sent to subscribers again.
+        let response = app.oneshot(replay_request(&block_hash)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(db.lock().await.rotate_keys_transactions, rotations);
+        assert_eq!(notifications(), 0);
+    }
+
     #[tokio::test]
     async fn test_invalid_event() {
         let ctx = TestContext::builder()
@@ -744,10 +1973,45 @@ mod tests {
             .is_err()
         );
 
-        let res = new_block_handler(state, body).await;
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
 
         // But we expect the second (valid) event to be processed anyway
         assert_eq!(res, StatusCode::OK);
         assert!(!db.lock().await.rotate_keys_transactions.is_empty());
     }
+
+    #[tokio::test]
+    async fn events_are_handled_after_the_latency_budget_runs_out() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.latency_budget = Duration::from_millis(10);
+            })
+            .build();
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let body = ROTATE_KEYS_WEBHOOK.to_string();
+        let db = ctx.inner_storage();
+
+        // A bitcoin chain tip update holds back the handling of the
+        // events, but the webhook is still acknowledged.
+        let scheduler = ctx.state().chain_event_scheduler();
+        let bitcoin_tip_update = scheduler.bitcoin_tip_update().await;
+
+        let res = new_block_handler(state, HeaderMap::new(), body.into()).await;
+        assert_eq!(res, StatusCode::OK);
+        assert!(db.lock().await.rotate_keys_transactions.is_empty());
+
+        // Once the update is done, the events are handled in the
+        // background.
+        drop(bitcoin_tip_update);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while db.lock().await.rotate_keys_transactions.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
 }
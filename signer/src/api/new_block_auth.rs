@@ -0,0 +1,145 @@
+//! Authentication of the webhooks sent to the `POST /new_block` endpoint.
+//!
+//! When `signer.event_observer.new_block_auth.secret` is set, the
+//! `/new_block` endpoint only accepts webhooks that prove knowledge of the
+//! secret in one of two ways:
+//!
+//! * The [`AUTHORIZATION`] header holds the secret itself, optionally
+//!   prefixed by `Bearer `. This is what a stacks node, or a proxy in
+//!   front of it, can send without computing anything per request.
+//! * The [`NEW_BLOCK_SIGNATURE_HEADER`] header holds the hex encoded
+//!   HMAC-SHA256 of the request body, keyed by the secret. Unlike the
+//!   shared secret, the signature also protects the body from being
+//!   tampered with in transit and does not reveal the secret.
+//!
+//! The signature is over the body as the handler receives it, so after
+//! any `Content-Encoding` has been removed. Every webhook is accepted when
+//! no secret is configured.
+
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use bitcoin::hashes::Hash as _;
+use bitcoin::hashes::HashEngine as _;
+use bitcoin::hashes::hmac::Hmac;
+use bitcoin::hashes::hmac::HmacEngine;
+use bitcoin::hashes::sha256;
+
+use crate::config::NewBlockAuthConfig;
+
+/// The header with the hex encoded HMAC-SHA256 of the body of a webhook.
+pub const NEW_BLOCK_SIGNATURE_HEADER: &str = "x-sbtc-new-block-signature";
+
+/// Hex encode the HMAC-SHA256 of the given webhook body, keyed by the
+/// given secret.
+pub fn sign_new_block_body(secret: &str, body: &[u8]) -> String {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(body);
+    hex::encode(Hmac::from_engine(engine).to_byte_array())
+}
+
+/// Whether the webhook with the given headers and body is authenticated
+/// according to the given config.
+pub fn is_authenticated(config: &NewBlockAuthConfig, headers: &HeaderMap, body: &[u8]) -> bool {
+    let Some(secret) = config.secret.as_deref() else {
+        return true;
+    };
+
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(token) = header(AUTHORIZATION.as_str()) {
+        let token = token.strip_prefix("Bearer ").unwrap_or(token);
+        if constant_time_eq(token.as_bytes(), secret.as_bytes()) {
+            return true;
+        }
+    }
+
+    if let Some(signature) = header(NEW_BLOCK_SIGNATURE_HEADER) {
+        let expected = sign_new_block_body(secret, body);
+        let signature = signature.to_ascii_lowercase();
+        if constant_time_eq(signature.as_bytes(), expected.as_bytes()) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Compare the two byte strings in time that only depends on their
+/// lengths, so that the comparison does not leak how much of a guess was
+/// right.
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    const SECRET: &str = "a-shared-secret-for-new-blocks";
+
+    fn config(secret: Option<&str>) -> NewBlockAuthConfig {
+        NewBlockAuthConfig {
+            secret: secret.map(ToString::to_string),
+        }
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn everything_is_authenticated_without_a_secret() {
+        assert!(is_authenticated(&config(None), &HeaderMap::new(), b"{}"));
+    }
+
+    #[test]
+    fn shared_secret_authenticates() {
+        let config = config(Some(SECRET));
+        let body = b"{}";
+
+        assert!(!is_authenticated(&config, &HeaderMap::new(), body));
+        assert!(is_authenticated(
+            &config,
+            &headers("authorization", SECRET),
+            body
+        ));
+
+        let bearer = format!("Bearer {SECRET}");
+        assert!(is_authenticated(
+            &config,
+            &headers("authorization", &bearer),
+            body
+        ));
+
+        let wrong = headers("authorization", "a-shared-secret-for-new-block");
+        assert!(!is_authenticated(&config, &wrong, body));
+    }
+
+    #[test]
+    fn body_signature_authenticates() {
+        let config = config(Some(SECRET));
+        let body = b"{\"block_height\": 1}";
+
+        let signature = sign_new_block_body(SECRET, body);
+        let signed = headers(NEW_BLOCK_SIGNATURE_HEADER, &signature);
+        assert!(is_authenticated(&config, &signed, body));
+
+        let upper = headers(NEW_BLOCK_SIGNATURE_HEADER, &signature.to_uppercase());
+        assert!(is_authenticated(&config, &upper, body));
+
+        // The signature does not carry over to another body, or to one
+        // made with another secret.
+        assert!(!is_authenticated(
+            &config,
+            &signed,
+            b"{\"block_height\": 2}"
+        ));
+        let other = sign_new_block_body("another-secret", body);
+        let other = headers(NEW_BLOCK_SIGNATURE_HEADER, &other);
+        assert!(!is_authenticated(&config, &other, body));
+    }
+}
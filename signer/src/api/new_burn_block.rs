@@ -0,0 +1,253 @@
+//! This module contains the handler for the `POST /new_burn_block`
+//! endpoint, which is for processing new burn block webhooks from a stacks
+//! node.
+//!
+//! The stacks node sends this webhook whenever it processes a new bitcoin
+//! block, which is one that it may anchor stacks blocks to. We record the
+//! block and cross-check it against the bitcoin blockchain that the block
+//! observer got from bitcoin-core. A bitcoin block that the stacks node
+//! processed but that is not on our canonical bitcoin blockchain means
+//! that our bitcoin-core node and the stacks node disagree, and it is
+//! counted in the [`Metrics::BurnBlockDivergencesTotal`] metric.
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use sbtc::webhooks::NewBurnBlockEvent;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::StacksNodeBurnBlock;
+
+use super::ApiState;
+use super::new_block_auth::is_authenticated;
+
+/// The divergence reason for a bitcoin block that is not in our database
+/// even though our chain tip is at least as high.
+const UNKNOWN_BLOCK: &str = "unknown_block";
+
+/// The divergence reason for a bitcoin block that is in our database but
+/// not on our canonical bitcoin blockchain.
+const NOT_CANONICAL: &str = "not_canonical";
+
+/// A handler of `POST /new_burn_block` webhook events.
+///
+/// Like the `/new_block` handler, this always returns a 200 OK status
+/// code for authenticated webhooks, even when the block could not be
+/// recorded, so that the stacks node does not hold up the delivery of
+/// other webhooks by retrying this one. Webhooks are authenticated the
+/// same way as `/new_block` webhooks.
+#[tracing::instrument(skip_all, name = "new-burn-block", fields(
+    block_hash = tracing::field::Empty,
+    block_height = tracing::field::Empty,
+))]
+pub async fn new_burn_block_handler<C: Context>(
+    state: State<ApiState<C>>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    let auth_config = &state.ctx.config().signer.event_observer.new_block_auth;
+    if !is_authenticated(auth_config, &headers, body.as_bytes()) {
+        tracing::warn!("rejecting an unauthenticated POST /new_burn_block webhook");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    if let Some(capture) = crate::capture::get() {
+        capture.record_webhook("/new_burn_block", &body);
+    }
+
+    let event: NewBurnBlockEvent = match serde_json::from_str(&body) {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(%body, %error, "could not deserialize POST /new_burn_block webhook");
+            return StatusCode::OK;
+        }
+    };
+
+    let block = StacksNodeBurnBlock {
+        block_hash: event.burn_block_hash.into(),
+        block_height: event.burn_block_height.into(),
+    };
+
+    let span = tracing::span::Span::current();
+    span.record("block_hash", block.block_hash.to_string());
+    span.record("block_height", *block.block_height);
+
+    tracing::debug!("received a new burn block event from stacks-core");
+    let ctx = &state.ctx;
+    if let Err(error) = ctx
+        .get_storage_mut()
+        .write_stacks_node_burn_block(&block)
+        .await
+    {
+        tracing::warn!(%error, "could not record the burn block of the stacks node");
+    }
+
+    match check_burn_block(ctx, &block).await {
+        Ok(None) => {}
+        Ok(Some(reason)) => {
+            tracing::warn!(
+                %reason,
+                "the stacks node processed a bitcoin block that disagrees with our bitcoin view"
+            );
+            Metrics::increment_burn_block_divergences(reason);
+        }
+        Err(error) => {
+            tracing::warn!(%error, "could not cross-check the burn block of the stacks node")
+        }
+    }
+
+    StatusCode::OK
+}
+
+/// Check the given bitcoin block that the stacks node processed against
+/// our view of the bitcoin blockchain, and return the reason that they
+/// disagree if they do.
+///
+/// A bitcoin block above our chain tip does not disagree with our view,
+/// since the block observer may not have caught up with the stacks node
+/// yet.
+async fn check_burn_block(
+    ctx: &impl Context,
+    block: &StacksNodeBurnBlock,
+) -> Result<Option<&'static str>, Error> {
+    let Some(chain_tip) = ctx.state().bitcoin_chain_tip() else {
+        return Ok(None);
+    };
+    if block.block_height > chain_tip.block_height {
+        return Ok(None);
+    }
+
+    let storage = ctx.get_storage();
+    if !storage
+        .is_known_bitcoin_block_hash(&block.block_hash)
+        .await?
+    {
+        return Ok(Some(UNKNOWN_BLOCK));
+    }
+
+    let block_ref = BitcoinBlockRef {
+        block_hash: block.block_hash,
+        block_height: block.block_height,
+    };
+    if !storage
+        .in_canonical_bitcoin_blockchain(&chain_tip, &block_ref)
+        .await?
+    {
+        return Ok(Some(NOT_CANONICAL));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    fn webhook(block: &model::BitcoinBlock) -> Request<Body> {
+        let body = serde_json::json!({
+            "burn_block_hash": format!("0x{}", block.block_hash),
+            "burn_block_height": *block.block_height,
+            "reward_recipients": [],
+            "reward_slot_holders": [],
+            "burn_amount": 0,
+        });
+        Request::builder()
+            .uri("/new_burn_block")
+            .method(Method::POST)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    /// The burn blocks of the stacks node are recorded, and only the ones
+    /// that are not on our canonical bitcoin blockchain diverge.
+    #[tokio::test]
+    async fn burn_blocks_are_recorded_and_cross_checked() {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+        let app: Router = get_router().with_state(ApiState { ctx: ctx.clone() });
+        let mut rng = get_rng();
+
+        let parent: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+        let chain_tip = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: parent.block_height + 1,
+            parent_hash: parent.block_hash,
+        };
+        db.write_bitcoin_block(&parent).await.unwrap();
+        db.write_bitcoin_block(&chain_tip).await.unwrap();
+        ctx.state().set_bitcoin_chain_tip((&chain_tip).into());
+
+        let response = app.clone().oneshot(webhook(&chain_tip)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let latest = db.get_latest_stacks_node_burn_block().await.unwrap();
+        assert_eq!(
+            latest.map(|block| block.block_hash),
+            Some(chain_tip.block_hash)
+        );
+
+        for block in [&parent, &chain_tip] {
+            assert_eq!(
+                check_burn_block(&ctx, &burn_block(block)).await.unwrap(),
+                None
+            );
+        }
+
+        // A block at the height of our chain tip that we do not know of.
+        let unknown = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            ..chain_tip.clone()
+        };
+        let reason = check_burn_block(&ctx, &burn_block(&unknown)).await.unwrap();
+        assert_eq!(reason, Some(UNKNOWN_BLOCK));
+
+        // A block that we know of, but on a fork that is not canonical.
+        let fork = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            ..chain_tip.clone()
+        };
+        db.write_bitcoin_block(&fork).await.unwrap();
+        let reason = check_burn_block(&ctx, &burn_block(&fork)).await.unwrap();
+        assert_eq!(reason, Some(NOT_CANONICAL));
+
+        // A block above our chain tip, which we have not observed yet.
+        let next = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: chain_tip.block_height + 1,
+            parent_hash: chain_tip.block_hash,
+        };
+        let response = app.clone().oneshot(webhook(&next)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            check_burn_block(&ctx, &burn_block(&next)).await.unwrap(),
+            None
+        );
+        let latest = db.get_latest_stacks_node_burn_block().await.unwrap();
+        assert_eq!(latest.map(|block| block.block_hash), Some(next.block_hash));
+    }
+
+    fn burn_block(block: &model::BitcoinBlock) -> StacksNodeBurnBlock {
+        StacksNodeBurnBlock {
+            block_hash: block.block_hash,
+            block_height: block.block_height,
+        }
+    }
+}
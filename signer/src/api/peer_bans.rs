@@ -0,0 +1,235 @@
+//! Handlers for banning and unbanning peers.
+//!
+//! Banned peers are disconnected and have their gossip dropped until the
+//! ban expires, regardless of whether they are in the current signer set.
+//! Bans are kept in the database so that they survive restarts.
+
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use libp2p::PeerId;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::PeerBan;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// The longest that a peer may be banned for in one go.
+const MAX_BAN_DURATION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A ban of a peer, as listed by the API.
+#[derive(Debug, Serialize)]
+pub struct PeerBanInfo {
+    /// The libp2p peer ID of the banned peer.
+    pub peer_id: String,
+    /// Why the peer was banned, if the operator said.
+    pub reason: Option<String>,
+    /// When the ban expires.
+    pub expires_at: String,
+}
+
+impl From<PeerBan> for PeerBanInfo {
+    fn from(ban: PeerBan) -> Self {
+        Self {
+            peer_id: ban.peer_id.to_string(),
+            reason: ban.reason,
+            expires_at: ban.expires_at.to_string(),
+        }
+    }
+}
+
+/// A request to ban a peer.
+#[derive(Debug, Deserialize)]
+pub struct BanPeerRequest {
+    /// The libp2p peer ID of the peer to ban.
+    pub peer_id: String,
+    /// How long to ban the peer for, in seconds.
+    pub duration_secs: u64,
+    /// Why the peer is being banned.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Handler for the `GET /admin/peers/bans` endpoint, which lists the bans
+/// that have not expired.
+pub async fn list_bans_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> Result<Json<Vec<PeerBanInfo>>, StatusCode> {
+    let bans = state
+        .ctx
+        .get_storage()
+        .get_peer_bans()
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch peer bans");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(bans.into_iter().map(Into::into).collect()))
+}
+
+/// Handler for the `POST /admin/peers/bans` endpoint, which bans a peer
+/// for the given duration, replacing any ban that it already has.
+pub async fn ban_peer_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let request: BanPeerRequest = match request.json() {
+        Ok(request) => request,
+        Err(status) => return status,
+    };
+    let Ok(peer_id) = request.peer_id.parse::<PeerId>() else {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    };
+    let duration = Duration::from_secs(request.duration_secs);
+    if duration.is_zero() || duration > MAX_BAN_DURATION {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    let expires_at = OffsetDateTime::now_utc() + duration;
+    let ban = PeerBan {
+        peer_id: peer_id.into(),
+        reason: request.reason,
+        expires_at: expires_at.into(),
+    };
+
+    if let Err(error) = state.ctx.get_storage_mut().write_peer_ban(&ban).await {
+        tracing::error!(%error, %peer_id, "could not ban peer");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    // The event loop disconnects the peer the next time that it hears
+    // from it.
+    state.ctx.state().ban_peer(peer_id, expires_at);
+    tracing::info!(%peer_id, %expires_at, reason = ?ban.reason, "banned peer");
+    StatusCode::CREATED
+}
+
+/// Handler for the `DELETE /admin/peers/bans/{peer_id}` endpoint, which
+/// lifts the ban of a peer.
+pub async fn unban_peer_handler<C: Context>(
+    state: State<ApiState<C>>,
+    Path(peer_id): Path<String>,
+    _: AdminRequest,
+) -> StatusCode {
+    let Ok(peer_id) = peer_id.parse::<PeerId>() else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    let deleted = match state.ctx.get_storage_mut().delete_peer_ban(&peer_id).await {
+        Ok(deleted) => deleted,
+        Err(error) => {
+            tracing::error!(%error, %peer_id, "could not unban peer");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // Always clear the in-memory ban, so that it cannot outlive the row.
+    let unbanned = state.ctx.state().unban_peer(&peer_id);
+    if deleted || unbanned {
+        tracing::info!(%peer_id, "unbanned peer");
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::http::Method;
+    use test_case::test_case;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn peers_can_be_banned_and_unbanned() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+        let peer_id = PeerId::random();
+
+        let body = serde_json::json!({
+            "peer_id": peer_id.to_string(),
+            "duration_secs": 3600,
+            "reason": "spamming",
+        });
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::POST, "/admin/peers/bans", Some(body)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(context.state().is_peer_banned(&peer_id));
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::GET, "/admin/peers/bans", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed[0]["peer_id"], peer_id.to_string());
+        assert_eq!(listed[0]["reason"], "spamming");
+
+        let uri = format!("/admin/peers/bans/{peer_id}");
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::DELETE, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!context.state().is_peer_banned(&peer_id));
+        let bans = context.get_storage().get_peer_bans().await.unwrap();
+        assert!(bans.is_empty());
+
+        // Unbanning it again finds nothing to unban.
+        let response = app
+            .oneshot(admin_request(Method::DELETE, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test_case("not-a-peer-id", 3600; "invalid peer id")]
+    #[test_case("", 0; "zero duration")]
+    #[test_case("", MAX_BAN_DURATION.as_secs() + 1; "duration too long")]
+    #[tokio::test]
+    async fn invalid_bans_are_rejected(peer_id: &str, duration_secs: u64) {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let peer_id = match peer_id {
+            "" => PeerId::random().to_string(),
+            peer_id => peer_id.to_string(),
+        };
+        let body = serde_json::json!({
+            "peer_id": peer_id,
+            "duration_secs": duration_secs,
+        });
+        let response = app
+            .oneshot(admin_request(Method::POST, "/admin/peers/bans", Some(body)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let bans = context.get_storage().get_peer_bans().await.unwrap();
+        assert!(bans.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Handler for the `GET /peers` endpoint, which reports protocol
+//! statistics for each peer that the signer is connected to.
+//!
+//! The statistics are collected by the libp2p event loop and only live in
+//! memory, so they start over whenever the signer restarts. The exception
+//! is the response latencies of the signers, which this signer observed
+//! while it was the coordinator and keeps in the database.
+
+use std::collections::BTreeMap;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::network::stats::PeerStats;
+use crate::response_latency::ResponseLatencySummary;
+use crate::response_latency::load_response_latency_summaries;
+
+use super::ApiState;
+
+/// Protocol statistics for a connected peer.
+#[derive(Debug, Serialize)]
+pub struct PeerInfo {
+    /// The libp2p peer ID of the peer.
+    pub peer_id: String,
+    /// The public key of the signer behind the peer ID, if the peer is in
+    /// the current signer set.
+    pub public_key: Option<String>,
+    /// The transports used by the open connections to the peer.
+    pub transports: Vec<&'static str>,
+    /// The most recent round trip time to the peer, in milliseconds.
+    pub last_rtt_millis: Option<f64>,
+    /// A smoothed estimate of the round trip time to the peer, in
+    /// milliseconds.
+    pub smoothed_rtt_millis: Option<f64>,
+    /// The number of messages, by message type, that we accepted from the
+    /// peer.
+    pub message_counts: BTreeMap<&'static str, u64>,
+    /// The number of messages from the peer that we rejected.
+    pub rejected_messages: u64,
+    /// The fraction of messages from the peer that we accepted.
+    pub reputation_score: f64,
+    /// The bitcoin chain tip in the most recent heartbeat from the peer.
+    pub last_heartbeat_chain_tip: Option<String>,
+    /// When we received the most recent heartbeat from the peer.
+    pub last_heartbeat_at: Option<String>,
+    /// How long the signer took to respond to our requests while we were
+    /// the coordinator, by kind of response.
+    pub response_latencies: BTreeMap<String, ResponseLatencySummary>,
+}
+
+impl PeerInfo {
+    fn new(
+        peer_id: String,
+        public_key: Option<String>,
+        stats: PeerStats,
+        response_latencies: BTreeMap<String, ResponseLatencySummary>,
+    ) -> Self {
+        Self {
+            peer_id,
+            public_key,
+            transports: stats.transports().iter().map(|t| t.as_str()).collect(),
+            last_rtt_millis: stats.last_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+            smoothed_rtt_millis: stats.smoothed_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+            reputation_score: stats.reputation_score(),
+            rejected_messages: stats.rejected_messages,
+            last_heartbeat_chain_tip: stats
+                .last_heartbeat
+                .map(|tip| tip.bitcoin_chain_tip.to_string()),
+            last_heartbeat_at: stats.last_heartbeat.map(|tip| tip.received_at.to_string()),
+            message_counts: stats.message_counts,
+            response_latencies,
+        }
+    }
+}
+
+/// Handler for the `GET /peers` endpoint.
+pub async fn peers_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<Vec<PeerInfo>>, StatusCode> {
+    let mut latencies = load_response_latency_summaries(&state.ctx)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch the signer response latencies");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let signer_state = state.ctx.state();
+    let signer_set = signer_state.current_signer_set();
+
+    let mut peers: Vec<PeerInfo> = signer_state
+        .peer_statistics()
+        .connected_peers()
+        .into_iter()
+        .map(|(peer_id, stats)| {
+            let public_key = signer_set.get_pubkey_for_peer(&peer_id);
+            let response_latencies = public_key
+                .and_then(|key| latencies.remove(&key))
+                .unwrap_or_default();
+            let public_key = public_key.map(|key| key.to_string());
+            PeerInfo::new(peer_id.to_string(), public_key, stats, response_latencies)
+        })
+        .collect();
+    peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+
+    Ok(Json(peers))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use axum::http::StatusCode;
+    use fake::Fake as _;
+    use fake::Faker;
+    use libp2p::PeerId;
+    use libp2p::swarm::ConnectionId;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::keys::PublicKey;
+    use crate::response_latency::ResponseKind;
+    use crate::response_latency::record_response_latency;
+    use crate::storage::model::BitcoinBlockHash;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn connected_peers_are_reported() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let peer_id = PeerId::random();
+        let chain_tip: BitcoinBlockHash = Faker.fake();
+        let statistics = context.state().peer_statistics();
+        let address = "/ip4/127.0.0.1/udp/4122/quic-v1".parse().unwrap();
+        statistics.connection_established(peer_id, ConnectionId::new_unchecked(1), &address);
+        statistics.record_rtt(peer_id, Duration::from_millis(8));
+        statistics.record_message(peer_id, "SignerHeartbeat");
+        statistics.record_heartbeat(peer_id, chain_tip);
+
+        let request = Request::builder()
+            .uri("/peers")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let peers: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(peers.as_array().unwrap().len(), 1);
+        assert_eq!(peers[0]["peer_id"], peer_id.to_string());
+        assert_eq!(peers[0]["public_key"], serde_json::Value::Null);
+        assert_eq!(peers[0]["transports"], serde_json::json!(["quic"]));
+        assert_eq!(peers[0]["last_rtt_millis"], 8.0);
+        assert_eq!(peers[0]["message_counts"]["SignerHeartbeat"], 1);
+        assert_eq!(peers[0]["reputation_score"], 1.0);
+        assert_eq!(peers[0]["last_heartbeat_chain_tip"], chain_tip.to_string());
+        assert_eq!(peers[0]["response_latencies"], serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn response_latencies_of_signers_are_reported() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let public_key: PublicKey = Faker.fake();
+        context.state().current_signer_set().add_signer(public_key);
+        let peer_id = PeerId::from(public_key);
+
+        let statistics = context.state().peer_statistics();
+        let address = "/ip4/127.0.0.1/udp/4122/quic-v1".parse().unwrap();
+        statistics.connection_established(peer_id, ConnectionId::new_unchecked(1), &address);
+
+        let kind = ResponseKind::PreSignAck;
+        for millis in [100, 300] {
+            let latency = Duration::from_millis(millis);
+            record_response_latency(&context, public_key, kind, latency)
+                .await
+                .unwrap();
+        }
+
+        let request = Request::builder()
+            .uri("/peers")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let peers: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(peers[0]["public_key"], public_key.to_string());
+        let latencies = &peers[0]["response_latencies"]["presign-ack"];
+        assert_eq!(latencies["responses"], 2);
+        assert_eq!(latencies["mean_millis"], 200.0);
+        assert_eq!(latencies["max_millis"], 300);
+    }
+}
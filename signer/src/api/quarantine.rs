@@ -0,0 +1,182 @@
+//! Handlers for inspecting and releasing quarantined requests.
+//!
+//! The coordinator quarantines deposit and withdrawal requests that keep
+//! failing to be signed, so that they stop consuming every tenure. These
+//! endpoints let an operator see which requests are quarantined and
+//! release them once the cause has been investigated.
+
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::RequestFailures;
+use crate::storage::model::SbtcRequestKey;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// A quarantined deposit or withdrawal request.
+#[derive(Debug, Serialize)]
+pub struct QuarantinedRequest {
+    /// Either `deposit` or `withdrawal`.
+    pub kind: &'static str,
+    /// The transaction ID of a deposit request.
+    pub txid: Option<String>,
+    /// The output index of a deposit request.
+    pub output_index: Option<u32>,
+    /// The request ID of a withdrawal request.
+    pub request_id: Option<u64>,
+    /// The number of times that signing for the request has failed.
+    pub failure_count: u32,
+    /// The error from the most recent failure.
+    pub last_error: String,
+    /// When the request was quarantined.
+    pub quarantined_at: Option<String>,
+}
+
+impl From<RequestFailures> for QuarantinedRequest {
+    fn from(failures: RequestFailures) -> Self {
+        let (kind, txid, output_index, request_id) = match failures.request {
+            SbtcRequestKey::Deposit { txid, output_index } => {
+                ("deposit", Some(txid.to_string()), Some(output_index), None)
+            }
+            SbtcRequestKey::Withdrawal { request_id } => {
+                ("withdrawal", None, None, Some(request_id))
+            }
+        };
+
+        Self {
+            kind,
+            txid,
+            output_index,
+            request_id,
+            failure_count: failures.failure_count,
+            last_error: failures.last_error,
+            quarantined_at: failures.quarantined_at.map(|at| at.to_string()),
+        }
+    }
+}
+
+/// Handler for the `GET /quarantine` endpoint, which lists all quarantined
+/// requests.
+pub async fn list_quarantined_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<Vec<QuarantinedRequest>>, StatusCode> {
+    let quarantined = state
+        .ctx
+        .get_storage()
+        .get_quarantined_requests()
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch quarantined requests");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(quarantined.into_iter().map(Into::into).collect()))
+}
+
+/// Handler for the `DELETE /quarantine/deposits/{txid}/{output_index}`
+/// endpoint, which releases a deposit request from quarantine.
+pub async fn release_deposit_handler<C: Context>(
+    state: State<ApiState<C>>,
+    Path((txid, output_index)): Path<(bitcoin::Txid, u32)>,
+    _: AdminRequest,
+) -> StatusCode {
+    let request = SbtcRequestKey::Deposit {
+        txid: txid.into(),
+        output_index,
+    };
+    release_request(&state.ctx, request).await
+}
+
+/// Handler for the `DELETE /quarantine/withdrawals/{request_id}` endpoint,
+/// which releases a withdrawal request from quarantine.
+pub async fn release_withdrawal_handler<C: Context>(
+    state: State<ApiState<C>>,
+    Path(request_id): Path<u64>,
+    _: AdminRequest,
+) -> StatusCode {
+    let request = SbtcRequestKey::Withdrawal { request_id };
+    release_request(&state.ctx, request).await
+}
+
+/// Remove the recorded failures of the given request, returning `404 Not
+/// Found` if none had been recorded.
+async fn release_request<C: Context>(ctx: &C, request: SbtcRequestKey) -> StatusCode {
+    match ctx
+        .get_storage_mut()
+        .release_request_failures(&request)
+        .await
+    {
+        Ok(true) => {
+            tracing::info!(%request, "request released from quarantine");
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(error) => {
+            tracing::error!(%error, %request, "could not release request from quarantine");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::http::Method;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn quarantined_requests_can_be_listed_and_released() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let withdrawal = SbtcRequestKey::Withdrawal { request_id: 42 };
+        let storage = context.get_storage_mut();
+        storage
+            .write_request_failure(&withdrawal, "boom", 1)
+            .await
+            .unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::GET, "/quarantine", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed[0]["kind"], "withdrawal");
+        assert_eq!(listed[0]["request_id"], 42);
+        assert_eq!(listed[0]["last_error"], "boom");
+
+        let uri = "/quarantine/withdrawals/42";
+        let response = app
+            .clone()
+            .oneshot(admin_request(Method::DELETE, uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(storage.get_quarantined_requests().await.unwrap().is_empty());
+
+        // Releasing it again finds nothing to release.
+        let response = app
+            .oneshot(admin_request(Method::DELETE, uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,75 @@
+//! Handler for the `GET /queue` endpoint, which reports the coordinator's
+//! view of the pending deposit and withdrawal requests.
+//!
+//! See [`crate::bitcoin::queue`] for how the requests are ordered and how
+//! their inclusion is estimated. Quarantined requests are left out of the
+//! queue and are listed by the `/quarantine` endpoint instead.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::bitcoin::queue::PendingQueue;
+use crate::context::Context;
+
+use super::ApiState;
+
+/// Handler for the `GET /queue` endpoint. This returns `404 Not Found` if
+/// the signer has not been the coordinator since it started.
+pub async fn pending_queue_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<PendingQueue>, StatusCode> {
+    state
+        .ctx
+        .state()
+        .pending_queue()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::storage::model::BitcoinBlockRef;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    fn request() -> Request<Body> {
+        Request::builder()
+            .uri("/queue")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn queue_is_served_once_recorded() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let chain_tip: BitcoinBlockRef = Faker.fake_with_rng(&mut context.rng());
+        let queue = PendingQueue::new(&chain_tip, None);
+        context.state().set_pending_queue(queue);
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let queue: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(queue["bitcoin_block_height"], *chain_tip.block_height);
+        assert_eq!(queue["requests"], serde_json::json!([]));
+    }
+}
@@ -1,35 +1,145 @@
 //! This module contains the default router for the signers api
 //!
 
+use std::sync::Arc;
+
 use axum::{
     Router,
-    extract::DefaultBodyLimit,
-    routing::{get, post},
+    extract::{DefaultBodyLimit, MatchedPath},
+    middleware,
+    routing::{delete, get, post},
 };
 
 use crate::context::Context;
+use crate::metrics::Metrics;
 
 use axum::http::StatusCode;
 
-use super::{ApiState, info, new_block, status};
+use super::{
+    ApiState,
+    cache::{self, PUBLIC_READ_CACHE_TTL, ResponseCache},
+    compression, config_fingerprint, confirmations, crash_reports, deposits, dry_run, fees, health,
+    info, integrity, maintenance, metrics, new_block, new_burn_block, peer_bans, peers, quarantine,
+    queue, status, sweeps, webhooks,
+};
 
-async fn new_attachment_handler() -> StatusCode {
+/// Acknowledge an event from the stacks node that the signer has no use
+/// for. The stacks node retries events that are not acknowledged forever,
+/// which happens when it is configured to send more events than the
+/// signer asks for.
+async fn ignored_event_handler(path: MatchedPath) -> StatusCode {
+    tracing::debug!(
+        endpoint = path.as_str(),
+        "ignoring event from the stacks node"
+    );
+    Metrics::increment_ignored_observer_events(path.as_str());
     StatusCode::OK
 }
 
 /// Return the default router
 pub fn get_router<C: Context + 'static>() -> Router<ApiState<C>> {
-    Router::new()
+    let router = Router::new()
         .route("/", get(status::status_handler))
-        .route("/info", get(info::info_handler))
+        .route(
+            "/admin/replay_block/{block_hash}",
+            post(new_block::replay_block_handler),
+        )
+        .route(
+            "/admin/crash_reports",
+            get(crash_reports::list_crash_reports_handler),
+        )
+        .route("/admin/deposits", post(deposits::submit_deposit_handler))
+        .route(
+            "/admin/peers/bans",
+            get(peer_bans::list_bans_handler).post(peer_bans::ban_peer_handler),
+        )
+        .route(
+            "/admin/peers/bans/{peer_id}",
+            delete(peer_bans::unban_peer_handler),
+        )
+        .route(
+            "/info",
+            get(info::info_handler).layer(middleware::from_fn_with_state(
+                Arc::new(ResponseCache::new(PUBLIC_READ_CACHE_TTL)),
+                cache::etag_cache,
+            )),
+        )
         .route(
             "/new_block",
             post(new_block::new_block_handler)
-                .layer(DefaultBodyLimit::max(new_block::EVENT_OBSERVER_BODY_LIMIT)),
+                // The body has already been held to the limit as it was
+                // sent, so this only limits the decompressed size.
+                .layer(DefaultBodyLimit::max(
+                    new_block::EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    new_block::EVENT_OBSERVER_BODY_LIMITS,
+                    compression::decompress_body,
+                )),
+        )
+        .route(
+            "/new_burn_block",
+            post(new_burn_block::new_burn_block_handler).layer(middleware::from_fn_with_state(
+                new_block::EVENT_OBSERVER_BODY_LIMITS,
+                compression::decompress_body,
+            )),
+        )
+        .route(
+            "/config/fingerprint",
+            get(config_fingerprint::config_fingerprint_handler),
+        )
+        .route(
+            "/confirmations",
+            get(confirmations::list_watches_handler)
+                .post(confirmations::register_watch_handler)
+                .delete(confirmations::remove_watch_handler),
+        )
+        .route(
+            "/dry-run",
+            get(dry_run::dry_run_status_handler)
+                .post(dry_run::schedule_dry_run_handler)
+                .delete(dry_run::cancel_dry_run_handler),
+        )
+        .route("/fees/stacks", get(fees::stacks_fees_handler))
+        .route("/healthz", get(health::liveness_handler))
+        .route("/integrity", get(integrity::verify_integrity_handler))
+        .route(
+            "/maintenance",
+            get(maintenance::maintenance_status_handler)
+                .post(maintenance::enter_maintenance_handler)
+                .delete(maintenance::leave_maintenance_handler),
+        )
+        .route("/metrics", get(metrics::metrics_handler))
+        .route("/peers", get(peers::peers_handler))
+        .route("/quarantine", get(quarantine::list_quarantined_handler))
+        .route("/queue", get(queue::pending_queue_handler))
+        .route("/readyz", get(health::readiness_handler))
+        .route("/status", get(status::signer_status_handler))
+        .route("/sweeps", get(sweeps::recent_sweeps_handler))
+        .route(
+            "/quarantine/deposits/{txid}/{output_index}",
+            delete(quarantine::release_deposit_handler),
+        )
+        .route(
+            "/quarantine/withdrawals/{request_id}",
+            delete(quarantine::release_withdrawal_handler),
+        )
+        .route(
+            "/webhooks",
+            get(webhooks::list_subscribers_handler)
+                .post(webhooks::register_subscriber_handler)
+                .delete(webhooks::remove_subscriber_handler),
         )
         // TODO: remove this once https://github.com/stacks-network/stacks-core/issues/5558
         // is addressed
-        .route("/attachments/new", post(new_attachment_handler))
+        .route("/attachments/new", post(ignored_event_handler))
+        .route("/new_mempool_tx", post(ignored_event_handler))
+        .route("/drop_mempool_tx", post(ignored_event_handler));
+
+    #[cfg(feature = "ui")]
+    let router = router.route("/ui", get(super::ui::ui_handler));
+
+    router
 }
 
 #[cfg(test)]
@@ -39,6 +149,7 @@ mod tests {
         body::Body,
         http::{Method, Request, StatusCode},
     };
+    use test_case::test_case;
     use tower::ServiceExt as _;
 
     use crate::{
@@ -46,20 +157,69 @@ mod tests {
         testing::context::TestContext,
     };
 
+    #[test_case("/attachments/new"; "attachments")]
+    #[test_case("/new_mempool_tx"; "new mempool tx")]
+    #[test_case("/drop_mempool_tx"; "drop mempool tx")]
     #[tokio::test]
-    async fn test_new_attachment() {
+    async fn test_ignored_events(uri: &str) {
         let context = TestContext::default_mocked();
 
         let state = ApiState { ctx: context.clone() };
         let app: Router = get_router().with_state(state);
 
         let request = Request::builder()
-            .uri("/attachments/new")
+            .uri(uri)
             .method(Method::POST)
-            .body(Body::empty())
+            .body(Body::from(r#"["0x00"]"#))
             .unwrap();
 
         let response = app.oneshot(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    const RELEASE_DEPOSIT_URI: &str = concat!(
+        "/quarantine/deposits/",
+        "0000000000000000000000000000000000000000000000000000000000000000/0",
+    );
+
+    const REPLAY_BLOCK_URI: &str = concat!(
+        "/admin/replay_block/",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+    );
+
+    #[test_case(Method::POST, "/admin/deposits"; "submit deposit")]
+    #[test_case(Method::DELETE, RELEASE_DEPOSIT_URI; "release deposit")]
+    #[test_case(Method::DELETE, "/quarantine/withdrawals/42"; "release withdrawal")]
+    #[test_case(Method::GET, "/webhooks"; "list webhook subscribers")]
+    #[test_case(Method::POST, "/webhooks"; "register webhook subscriber")]
+    #[test_case(Method::DELETE, "/webhooks"; "remove webhook subscriber")]
+    #[test_case(Method::GET, "/integrity"; "verify integrity")]
+    #[test_case(Method::POST, "/dry-run"; "schedule dry run")]
+    #[test_case(Method::DELETE, "/dry-run"; "cancel dry run")]
+    #[test_case(Method::POST, "/maintenance"; "enter maintenance mode")]
+    #[test_case(Method::DELETE, "/maintenance"; "leave maintenance mode")]
+    #[test_case(Method::GET, "/confirmations"; "list confirmation watches")]
+    #[test_case(Method::POST, "/confirmations"; "register confirmation watch")]
+    #[test_case(Method::DELETE, "/confirmations"; "remove confirmation watch")]
+    #[test_case(Method::GET, "/admin/peers/bans"; "list peer bans")]
+    #[test_case(Method::POST, "/admin/peers/bans"; "ban peer")]
+    #[test_case(Method::DELETE, "/admin/peers/bans/peer"; "unban peer")]
+    #[test_case(Method::GET, "/admin/crash_reports"; "list crash reports")]
+    #[test_case(Method::POST, REPLAY_BLOCK_URI; "replay block")]
+    #[tokio::test]
+    async fn admin_endpoints_require_authentication(method: Method, uri: &str) {
+        let context = TestContext::with_admin_secret();
+
+        let state = ApiState { ctx: context.clone() };
+        let app: Router = get_router().with_state(state);
+
+        let request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .body(Body::from("{}"))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
 }
@@ -0,0 +1,167 @@
+//! This module contains middleware for signing the responses of selected
+//! endpoints of the signer API.
+//!
+//! Consumers that aggregate data from several signers can use the
+//! signatures to authenticate each reply, and to cross-compare the replies
+//! of different signers. The signature is a hex encoded compact ECDSA
+//! signature, made with the signer's private key, over the SHA-256 digest
+//! of the request path, a newline, and the response body. Including the
+//! path means that the signed response of one endpoint cannot be passed
+//! off as the response of another. The signature is sent in the
+//! [`SIGNATURE_HEADER`] header, alongside the signer's public key in the
+//! [`PUBLIC_KEY_HEADER`] header, just like for webhook notifications.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::IntoResponse as _;
+use axum::response::Response;
+use sha2::Digest as _;
+
+use crate::config::SignedResponsesConfig;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::webhooks::PUBLIC_KEY_HEADER;
+use crate::webhooks::SIGNATURE_HEADER;
+
+/// The state of the [`sign_response`] middleware.
+#[derive(Debug)]
+pub struct ResponseSigner {
+    /// The endpoints whose responses are signed.
+    config: SignedResponsesConfig,
+    /// The private key that responses are signed with.
+    private_key: PrivateKey,
+    /// The public key of the signer, sent with each signature.
+    public_key: HeaderValue,
+}
+
+impl ResponseSigner {
+    /// Create a new response signer for the configured endpoints.
+    pub fn new(config: SignedResponsesConfig, private_key: PrivateKey) -> Self {
+        let public_key = PublicKey::from_private_key(&private_key).to_string();
+        Self {
+            config,
+            private_key,
+            // A hex encoded public key is always a valid header value.
+            public_key: HeaderValue::from_str(&public_key).expect("valid header value"),
+        }
+    }
+}
+
+/// Hex encode the compact ECDSA signature of the SHA-256 digest of the
+/// request path, a newline, and the response body.
+pub fn sign_response_body(private_key: &PrivateKey, path: &str, body: &[u8]) -> String {
+    let digest: [u8; 32] = sha2::Sha256::new()
+        .chain_update(path.as_bytes())
+        .chain_update(b"\n")
+        .chain_update(body)
+        .finalize()
+        .into();
+    let msg = secp256k1::Message::from_digest(digest);
+    hex::encode(private_key.sign_ecdsa(&msg).serialize_compact())
+}
+
+/// Sign the successful responses of the configured endpoints. Requests to
+/// other endpoints, and responses with any status other than `200 OK`,
+/// pass through untouched.
+pub async fn sign_response(
+    State(signer): State<Arc<ResponseSigner>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if !signer.config.is_signed(&path) {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(%path, %error, "could not read the body of a response to sign");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let signature = sign_response_body(&signer.private_key, &path, &body);
+    // A hex encoded signature is always a valid header value.
+    let signature = HeaderValue::from_str(&signature).expect("valid header value");
+    parts.headers.insert(SIGNATURE_HEADER, signature);
+    parts
+        .headers
+        .insert(PUBLIC_KEY_HEADER, signer.public_key.clone());
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+    use secp256k1::SECP256K1;
+    use tower::ServiceExt as _;
+
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn signed_responses_verify() {
+        let private_key = PrivateKey::new(&mut get_rng());
+        let public_key = PublicKey::from_private_key(&private_key);
+        let config = SignedResponsesConfig {
+            endpoints: vec!["/info".to_string()],
+        };
+
+        let app: Router = Router::new()
+            .route("/info", get(|| async { "signer info" }))
+            .route("/peers", get(|| async { "peers" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(ResponseSigner::new(config, private_key)),
+                sign_response,
+            ));
+
+        let request = Request::builder().uri("/info").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "signer info");
+        assert_eq!(
+            headers.get(PUBLIC_KEY_HEADER).unwrap(),
+            public_key.to_string().as_str()
+        );
+
+        let signature = headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        let signature =
+            secp256k1::ecdsa::Signature::from_compact(&hex::decode(signature).unwrap()).unwrap();
+        let digest: [u8; 32] = sha2::Sha256::digest(b"/info\nsigner info").into();
+        let msg = secp256k1::Message::from_digest(digest);
+        SECP256K1
+            .verify_ecdsa(&msg, &signature, &public_key.into())
+            .unwrap();
+
+        // Responses of the other endpoints are not signed.
+        let request = Request::builder()
+            .uri("/peers")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(SIGNATURE_HEADER).is_none());
+        assert!(response.headers().get(PUBLIC_KEY_HEADER).is_none());
+    }
+}
@@ -1,8 +1,169 @@
-//! This module is for the `GET /` endpoint, which just returns the status.
+//! This module is for the `GET /` endpoint, which just returns the status,
+//! and the `GET /status` endpoint, which reports what the signer is doing.
 
+use axum::Json;
+use axum::extract::State;
 use axum::http::StatusCode;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+
+use super::ApiState;
 
 /// A basic handler that responds with 200 OK
 pub async fn status_handler() -> StatusCode {
     StatusCode::OK
 }
+
+/// A block at the tip of a blockchain.
+#[derive(Debug, Serialize)]
+pub struct ChainTip {
+    /// The hash of the block.
+    pub block_hash: String,
+    /// The height of the block.
+    pub block_height: u64,
+}
+
+/// The current state of the signer.
+#[derive(Debug, Serialize)]
+pub struct SignerStatus {
+    /// The bitcoin chain tip that the block observer last processed.
+    pub bitcoin_chain_tip: Option<ChainTip>,
+    /// The stacks chain tip anchored to the bitcoin chain tip.
+    pub stacks_chain_tip: Option<ChainTip>,
+    /// The aggregate key from the most recent DKG round.
+    pub aggregate_key: Option<String>,
+    /// The kind of round with the other signers that this signer is
+    /// coordinating, if any. One of `dkg`, `bitcoin_signing` or
+    /// `stacks_signing`.
+    pub round_in_flight: Option<&'static str>,
+    /// The number of P2P peers that the signer is connected to.
+    pub peers_connected: usize,
+}
+
+/// Handler for the `GET /status` endpoint.
+pub async fn signer_status_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<SignerStatus>, StatusCode> {
+    let signer_state = state.ctx.state();
+    let storage = state.ctx.get_storage();
+    let bitcoin_chain_tip = signer_state.bitcoin_chain_tip();
+
+    let stacks_chain_tip = match bitcoin_chain_tip {
+        Some(chain_tip) => storage.get_stacks_chain_tip(&chain_tip.block_hash).await,
+        None => Ok(None),
+    };
+    let stacks_chain_tip = stacks_chain_tip.map_err(|error| {
+        tracing::error!(%error, "could not fetch the stacks chain tip");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let dkg_shares = storage
+        .get_latest_encrypted_dkg_shares()
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch the latest DKG shares");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(SignerStatus {
+        bitcoin_chain_tip: bitcoin_chain_tip.map(|block| ChainTip {
+            block_hash: block.block_hash.to_string(),
+            block_height: *block.block_height,
+        }),
+        stacks_chain_tip: stacks_chain_tip.map(|block| ChainTip {
+            block_hash: block.block_hash.to_string(),
+            block_height: *block.block_height,
+        }),
+        aggregate_key: dkg_shares.map(|shares| shares.aggregate_key.to_string()),
+        round_in_flight: signer_state.round_in_flight().map(|kind| kind.as_str()),
+        peers_connected: signer_state.peer_statistics().connected_peers().len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use fake::Fake as _;
+    use fake::Faker;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::context::RoundKind;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    async fn get_status(app: Router) -> serde_json::Value {
+        let request = Request::builder()
+            .uri("/status")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn status_of_a_new_signer() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+
+        let status = get_status(app).await;
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "bitcoin_chain_tip": null,
+                "stacks_chain_tip": null,
+                "aggregate_key": null,
+                "round_in_flight": null,
+                "peers_connected": 0,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn status_reports_chain_tips_and_rounds() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+        let storage = context.get_storage_mut();
+
+        let bitcoin_block: model::BitcoinBlock = Faker.fake();
+        let stacks_block = model::StacksBlock {
+            bitcoin_anchor: bitcoin_block.block_hash,
+            ..Faker.fake()
+        };
+        let shares: model::EncryptedDkgShares = Faker.fake();
+        storage.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        storage.write_stacks_block(&stacks_block).await.unwrap();
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        context
+            .state()
+            .set_bitcoin_chain_tip((&bitcoin_block).into());
+        context.state().set_round_in_flight(Some(RoundKind::Dkg));
+
+        let status = get_status(app).await;
+        assert_eq!(
+            status["bitcoin_chain_tip"]["block_hash"],
+            bitcoin_block.block_hash.to_string()
+        );
+        assert_eq!(
+            status["stacks_chain_tip"]["block_hash"],
+            stacks_block.block_hash.to_string()
+        );
+        assert_eq!(status["aggregate_key"], shares.aggregate_key.to_string());
+        assert_eq!(status["round_in_flight"], "dkg");
+    }
+}
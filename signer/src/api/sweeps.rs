@@ -0,0 +1,41 @@
+//! Handler for the `GET /sweeps` endpoint, which lists the sweep
+//! transactions that the signer recorded recently.
+
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::context::Context;
+use crate::export::SweepRecord;
+use crate::storage::DbRead as _;
+
+use super::ApiState;
+
+/// How far back the `/sweeps` endpoint looks for sweep transactions.
+const RECENT_SWEEPS_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Handler for the `GET /sweeps` endpoint. This returns the sweep
+/// transactions confirmed in blocks that the signer recorded over the
+/// last day, most recent first.
+pub async fn recent_sweeps_handler<C: Context>(
+    state: State<ApiState<C>>,
+) -> Result<Json<Vec<SweepRecord>>, StatusCode> {
+    let to = time::OffsetDateTime::now_utc();
+    let from = to - RECENT_SWEEPS_WINDOW;
+
+    let sweeps = state
+        .ctx
+        .get_storage()
+        .get_sweep_transactions_recorded_between(from.into(), to.into())
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "could not load the recent sweep transactions");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(
+        sweeps.into_iter().rev().map(SweepRecord::from).collect(),
+    ))
+}
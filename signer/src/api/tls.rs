@@ -0,0 +1,292 @@
+//! TLS for the signer API server.
+//!
+//! The signer API is served over plain HTTP by default, which is fine when
+//! the stacks node runs on the same host as the signer. When it does not,
+//! the API can be served over TLS instead, and clients can be required to
+//! present a certificate issued by a configured authority, so that the
+//! webhooks of the stacks node are encrypted and their sender is
+//! authenticated before any of them are handled.
+
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::serve::Listener;
+use rustls_pemfile::Item;
+use tokio::task::JoinSet;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::Certificate;
+use tokio_rustls::rustls::PrivateKey;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::server::TlsStream;
+
+use crate::config::ApiTlsConfig;
+use crate::error::Error;
+
+use super::limits::CappedConnection;
+use super::limits::CappedListener;
+
+/// The maximum amount of time that a client has to complete the TLS
+/// handshake after it connects.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The result of the TLS handshake with the client at an address.
+type Handshake = (io::Result<TlsStream<CappedConnection>>, SocketAddr);
+
+/// Build the acceptor of TLS connections to the signer API from the
+/// configured certificates and private key.
+pub fn tls_acceptor(config: &ApiTlsConfig) -> Result<TlsAcceptor, Error> {
+    let certificates: Vec<Certificate> = read_pem(&config.certificate)?
+        .into_iter()
+        .filter_map(certificate_der)
+        .map(Certificate)
+        .collect();
+    if certificates.is_empty() {
+        return Err(invalid_file(
+            "there are no certificates",
+            &config.certificate,
+        ));
+    }
+
+    let private_key = read_pem(&config.private_key)?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(der) | Item::RSAKey(der) | Item::ECKey(der) => Some(PrivateKey(der)),
+            _ => None,
+        })
+        .ok_or_else(|| invalid_file("there is no private key", &config.private_key))?;
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = match &config.client_ca {
+        Some(path) => {
+            let ders: Vec<Vec<u8>> = read_pem(path)?
+                .into_iter()
+                .filter_map(certificate_der)
+                .collect();
+            let mut roots = RootCertStore::empty();
+            let (valid, invalid) = roots.add_parsable_certificates(&ders);
+            if valid == 0 || invalid > 0 {
+                return Err(invalid_file("there are invalid or no certificates", path));
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certificates, private_key)
+        .map_err(Error::ApiTls)?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Read all of the items in the PEM file at the given path.
+fn read_pem(path: &Path) -> Result<Vec<Item>, Error> {
+    let file = File::open(path).map_err(|error| Error::ApiTlsFile(error, path.to_path_buf()))?;
+    rustls_pemfile::read_all(&mut BufReader::new(file))
+        .map_err(|error| Error::ApiTlsFile(error, path.to_path_buf()))
+}
+
+/// The DER encoding of the item, if it is a certificate.
+fn certificate_der(item: Item) -> Option<Vec<u8>> {
+    match item {
+        Item::X509Certificate(der) => Some(der),
+        _ => None,
+    }
+}
+
+/// The error for a PEM file whose contents are not what they should be.
+fn invalid_file(message: &str, path: &Path) -> Error {
+    let error = io::Error::new(io::ErrorKind::InvalidData, message);
+    Error::ApiTlsFile(error, path.to_path_buf())
+}
+
+/// A listener for the signer API that serves connections over TLS.
+///
+/// The TLS handshakes are done concurrently, so that a client that is slow
+/// to complete its handshake does not hold up the others. Connections
+/// whose handshake fails are closed without being handed to the server.
+pub struct TlsListener {
+    listener: CappedListener,
+    acceptor: TlsAcceptor,
+    handshakes: JoinSet<Handshake>,
+}
+
+impl TlsListener {
+    /// Serve the connections of the listener over TLS, with the given
+    /// acceptor.
+    pub fn new(listener: CappedListener, acceptor: TlsAcceptor) -> Self {
+        Self {
+            listener,
+            acceptor,
+            handshakes: JoinSet::new(),
+        }
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<CappedConnection>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            tokio::select! {
+                (stream, addr) = self.listener.accept() => {
+                    let acceptor = self.acceptor.clone();
+                    self.handshakes.spawn(async move {
+                        let handshake = acceptor.accept(stream);
+                        let result = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, handshake)
+                            .await
+                            .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()));
+                        (result, addr)
+                    });
+                }
+                Some(handshake) = self.handshakes.join_next() => match handshake {
+                    Ok((Ok(stream), addr)) => return (stream, addr),
+                    Ok((Err(error), addr)) => {
+                        tracing::debug!(%error, %addr, "TLS handshake with a client failed");
+                    }
+                    Err(error) => tracing::warn!(%error, "TLS handshake task failed"),
+                },
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+    use rcgen::BasicConstraints;
+    use rcgen::CertificateParams;
+    use rcgen::ExtendedKeyUsagePurpose;
+    use rcgen::IsCa;
+    use tokio::io::AsyncReadExt as _;
+    use tokio::io::AsyncWriteExt as _;
+    use tokio::net::TcpListener;
+    use tokio::net::TcpStream;
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::ClientConfig;
+    use tokio_rustls::rustls::ServerName;
+
+    use super::*;
+
+    fn certificate(
+        subject_alt_names: Vec<String>,
+        usage: Option<ExtendedKeyUsagePurpose>,
+    ) -> rcgen::Certificate {
+        let mut params = CertificateParams::new(subject_alt_names);
+        match usage {
+            Some(usage) => params.extended_key_usages = vec![usage],
+            None => params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained),
+        }
+        rcgen::Certificate::from_params(params).unwrap()
+    }
+
+    /// Send a request over TLS to the server at the given address, with the
+    /// given client certificate and the authority that issued it, and
+    /// return the response, if there is one.
+    async fn request(
+        addr: SocketAddr,
+        ca: &rcgen::Certificate,
+        client: Option<(&rcgen::Certificate, &rcgen::Certificate)>,
+    ) -> Option<String> {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(&Certificate(ca.serialize_der().unwrap()))
+            .unwrap();
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots);
+        let config = match client {
+            Some((client, issuer)) => {
+                let chain = vec![Certificate(
+                    client.serialize_der_with_signer(issuer).unwrap(),
+                )];
+                let key = PrivateKey(client.serialize_private_key_der());
+                builder.with_client_auth_cert(chain, key).unwrap()
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let connector = TlsConnector::from(Arc::new(config));
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut stream = connector.connect(server_name, stream).await.ok()?;
+
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.ok()?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.ok()?;
+        Some(response).filter(|response| !response.is_empty())
+    }
+
+    #[tokio::test]
+    async fn clients_must_present_a_certificate_from_the_client_ca() {
+        let ca = certificate(Vec::new(), None);
+        let server = certificate(
+            vec!["localhost".to_string()],
+            Some(ExtendedKeyUsagePurpose::ServerAuth),
+        );
+        let client = certificate(Vec::new(), Some(ExtendedKeyUsagePurpose::ClientAuth));
+        let other_ca = certificate(Vec::new(), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ApiTlsConfig {
+            certificate: dir.path().join("server.crt"),
+            private_key: dir.path().join("server.key"),
+            client_ca: Some(dir.path().join("ca.crt")),
+        };
+        let server_pem = server.serialize_pem_with_signer(&ca).unwrap();
+        std::fs::write(&config.certificate, server_pem).unwrap();
+        std::fs::write(&config.private_key, server.serialize_private_key_pem()).unwrap();
+        std::fs::write(dir.path().join("ca.crt"), ca.serialize_pem().unwrap()).unwrap();
+
+        let acceptor = tls_acceptor(&config).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = TlsListener::new(CappedListener::new(listener, 0), acceptor);
+        let app = Router::new().route("/", get(|| async { "ok" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let response = request(addr, &ca, Some((&client, &ca))).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("ok"));
+
+        // Without a certificate, or with one from another authority, the
+        // server closes the connection without a response.
+        assert_eq!(request(addr, &ca, None).await, None);
+        let other_client = certificate(Vec::new(), Some(ExtendedKeyUsagePurpose::ClientAuth));
+        assert_eq!(
+            request(addr, &ca, Some((&other_client, &other_ca))).await,
+            None
+        );
+    }
+
+    #[test]
+    fn missing_and_empty_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ApiTlsConfig {
+            certificate: dir.path().join("server.crt"),
+            private_key: dir.path().join("server.key"),
+            client_ca: None,
+        };
+        let error = tls_acceptor(&config).unwrap_err();
+        assert!(matches!(error, Error::ApiTlsFile(_, path) if path == config.certificate));
+
+        std::fs::write(&config.certificate, "").unwrap();
+        let error = tls_acceptor(&config).unwrap_err();
+        assert!(matches!(error, Error::ApiTlsFile(_, path) if path == config.certificate));
+    }
+}
@@ -0,0 +1,193 @@
+//! Correlation of the webhooks sent to the `POST /new_block` endpoint with
+//! the logs of their sender.
+//!
+//! A webhook may carry a [W3C trace context] in the [`TRACEPARENT_HEADER`]
+//! header, or an opaque correlation ID in the [`CORRELATION_ID_HEADER`]
+//! header. The stacks node does not send either itself, but a proxy in
+//! front of it can. The ID is recorded in the span of the handler of the
+//! webhook and in the spans of the handlers of each of the events of the
+//! block, so that the handling of a block can be followed from the logs
+//! of the stacks node into the logs of the signer. The trace ID of a valid
+//! `traceparent` header takes precedence over a correlation ID, and
+//! headers that are malformed are ignored.
+//!
+//! [W3C trace context]: https://www.w3.org/TR/trace-context/
+
+use axum::http::HeaderMap;
+use tracing::Span;
+
+/// The header with the W3C trace context of a webhook.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The header with an opaque ID that correlates a webhook with the logs
+/// of its sender.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// The maximum length of an accepted correlation ID.
+const MAX_CORRELATION_ID_LEN: usize = 128;
+
+/// The trace context that a webhook was sent with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceContext {
+    /// The ID that correlates the handling of the webhook with the logs
+    /// of its sender. This is the trace ID of a `traceparent` header, or
+    /// else the value of the correlation ID header.
+    pub correlation_id: Option<String>,
+    /// The ID of the span of the sender that sent the webhook, from the
+    /// `traceparent` header.
+    pub parent_span_id: Option<String>,
+}
+
+impl TraceContext {
+    /// Read the trace context from the given headers of a webhook.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let traceparent = header(TRACEPARENT_HEADER).and_then(parse_traceparent);
+        if let Some((trace_id, parent_id)) = traceparent {
+            return Self {
+                correlation_id: Some(trace_id.to_string()),
+                parent_span_id: Some(parent_id.to_string()),
+            };
+        }
+
+        let correlation_id = header(CORRELATION_ID_HEADER)
+            .map(str::trim)
+            .filter(|id| is_valid_correlation_id(id))
+            .map(ToString::to_string);
+
+        Self {
+            correlation_id,
+            parent_span_id: None,
+        }
+    }
+
+    /// Record the trace context in the `correlation_id` and
+    /// `parent_span_id` fields of the given span.
+    pub fn record(&self, span: &Span) {
+        if let Some(correlation_id) = &self.correlation_id {
+            span.record("correlation_id", correlation_id.as_str());
+        }
+        if let Some(parent_span_id) = &self.parent_span_id {
+            span.record("parent_span_id", parent_span_id.as_str());
+        }
+    }
+}
+
+/// Parse the value of a `traceparent` header, returning the trace ID and
+/// the parent ID in it.
+///
+/// The value is `{version}-{trace-id}-{parent-id}-{trace-flags}`, all in
+/// lowercase hex. Version `ff` is invalid, and versions after `00` may
+/// append more fields, which are ignored. Trace and parent IDs of all
+/// zeros are invalid.
+fn parse_traceparent(value: &str) -> Option<(&str, &str)> {
+    let mut fields = value.trim().split('-');
+    let version = fields.next().filter(|version| is_lower_hex(version, 2))?;
+    let trace_id = fields.next().filter(|id| is_lower_hex(id, 32))?;
+    let parent_id = fields.next().filter(|id| is_lower_hex(id, 16))?;
+    fields.next().filter(|flags| is_lower_hex(flags, 2))?;
+
+    let is_all_zeros = |id: &str| id.bytes().all(|byte| byte == b'0');
+    let has_extra_fields = fields.next().is_some();
+    if version == "ff" || (version == "00" && has_extra_fields) {
+        return None;
+    }
+    if is_all_zeros(trace_id) || is_all_zeros(parent_id) {
+        return None;
+    }
+
+    Some((trace_id, parent_id))
+}
+
+/// Whether the string has the given length and only lowercase hex digits.
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    let is_lower_hex_digit = |byte: u8| matches!(byte, b'0'..=b'9' | b'a'..=b'f');
+    value.len() == len && value.bytes().all(is_lower_hex_digit)
+}
+
+/// Whether the correlation ID is short and only has visible ASCII
+/// characters, so that it cannot be used to forge log lines.
+fn is_valid_correlation_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_CORRELATION_ID_LEN
+        && id.bytes().all(|byte| byte.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use test_case::test_case;
+
+    use super::*;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT_ID: &str = "00f067aa0ba902b7";
+
+    fn headers_with(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn traceparent_takes_precedence() {
+        let traceparent = format!("00-{TRACE_ID}-{PARENT_ID}-01");
+        let headers = headers_with(&[
+            (TRACEPARENT_HEADER, &traceparent),
+            (CORRELATION_ID_HEADER, "block-1234"),
+        ]);
+
+        let trace_context = TraceContext::from_headers(&headers);
+        assert_eq!(trace_context.correlation_id.as_deref(), Some(TRACE_ID));
+        assert_eq!(trace_context.parent_span_id.as_deref(), Some(PARENT_ID));
+    }
+
+    #[test]
+    fn correlation_id_is_used_without_a_valid_traceparent() {
+        let headers = headers_with(&[
+            (TRACEPARENT_HEADER, "not-a-trace-context"),
+            (CORRELATION_ID_HEADER, " block-1234 "),
+        ]);
+
+        let trace_context = TraceContext::from_headers(&headers);
+        assert_eq!(trace_context.correlation_id.as_deref(), Some("block-1234"));
+        assert_eq!(trace_context.parent_span_id, None);
+    }
+
+    #[test]
+    fn webhooks_without_trace_context_have_none() {
+        let trace_context = TraceContext::from_headers(&HeaderMap::new());
+        assert_eq!(trace_context, TraceContext::default());
+
+        let invalid = "block 1234\tforged=true";
+        let headers = headers_with(&[(CORRELATION_ID_HEADER, invalid)]);
+        assert_eq!(
+            TraceContext::from_headers(&headers),
+            TraceContext::default()
+        );
+
+        let too_long = "a".repeat(MAX_CORRELATION_ID_LEN + 1);
+        let headers = headers_with(&[(CORRELATION_ID_HEADER, &too_long)]);
+        assert_eq!(
+            TraceContext::from_headers(&headers),
+            TraceContext::default()
+        );
+    }
+
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", true; "sampled")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", true; "not-sampled")]
+    #[test_case("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-ext", true; "version-01")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-ext", false; "extra")]
+    #[test_case("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", false; "version-ff")]
+    #[test_case("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01", false; "uppercase")]
+    #[test_case("00-00000000000000000000000000000000-00f067aa0ba902b7-01", false; "zero-trace")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01", false; "zero-parent")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01", false; "short-trace")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7", false; "missing-flags")]
+    fn traceparent_parsing(value: &str, valid: bool) {
+        assert_eq!(parse_traceparent(value).is_some(), valid);
+    }
+}
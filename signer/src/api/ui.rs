@@ -0,0 +1,49 @@
+//! Handler for the `GET /ui` endpoint, which serves a small static page
+//! for operators that want to see the state of their signer at a glance.
+//!
+//! The page has no backend of its own. It polls the read-only `/info`,
+//! `/queue`, `/sweeps` and `/peers` endpoints from the browser, so it
+//! shows exactly what those endpoints report. It is only compiled in with
+//! the `ui` feature.
+
+use axum::response::Html;
+
+/// The page served at `/ui`.
+const INDEX_HTML: &str = include_str!("ui/index.html");
+
+/// Handler for the `GET /ui` endpoint.
+pub async fn ui_handler() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use axum::http::StatusCode;
+    use axum::http::header::CONTENT_TYPE;
+    use tower::ServiceExt as _;
+
+    use crate::api::ApiState;
+    use crate::api::get_router;
+    use crate::testing::context::TestContext;
+
+    #[tokio::test]
+    async fn ui_is_served_as_html() {
+        let context = TestContext::default_mocked();
+        let app: Router = get_router().with_state(ApiState { ctx: context });
+
+        let request = Request::builder()
+            .uri("/ui")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response.headers().get(CONTENT_TYPE).unwrap();
+        assert!(content_type.to_str().unwrap().starts_with("text/html"));
+    }
+}
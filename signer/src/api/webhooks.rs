@@ -0,0 +1,179 @@
+//! Handlers for managing webhook subscribers.
+//!
+//! Subscribers registered here are notified in addition to the ones in
+//! the `[webhooks]` section of the config, see [`crate::webhooks`].
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use crate::context::Context;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::webhooks::redact_url;
+
+use super::ApiState;
+use super::admin::AdminRequest;
+
+/// A webhook subscriber.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookSubscriber {
+    /// The URL that notifications are posted to.
+    pub url: String,
+}
+
+impl WebhookSubscriber {
+    /// Parse the subscriber's URL, returning `None` if it is not a valid
+    /// HTTP or HTTPS URL.
+    fn parse_url(&self) -> Option<Url> {
+        let url: Url = self.url.parse().ok()?;
+        ["http", "https"].contains(&url.scheme()).then_some(url)
+    }
+}
+
+/// Handler for the `GET /webhooks` endpoint, which lists the subscribers
+/// that were registered through the API. Their URLs may carry credentials,
+/// so they are only listed for admin requests.
+pub async fn list_subscribers_handler<C: Context>(
+    state: State<ApiState<C>>,
+    _: AdminRequest,
+) -> Result<Json<Vec<String>>, StatusCode> {
+    state
+        .ctx
+        .get_storage()
+        .get_webhook_subscribers()
+        .await
+        .map(Json)
+        .map_err(|error| {
+            tracing::error!(%error, "could not fetch webhook subscribers");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Handler for the `POST /webhooks` endpoint, which registers a
+/// subscriber.
+pub async fn register_subscriber_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let subscriber: WebhookSubscriber = match request.json() {
+        Ok(subscriber) => subscriber,
+        Err(status) => return status,
+    };
+    let Some(url) = subscriber.parse_url() else {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    };
+
+    let redacted_url = redact_url(&url);
+    match state
+        .ctx
+        .get_storage_mut()
+        .write_webhook_subscriber(url.as_str())
+        .await
+    {
+        Ok(()) => {
+            tracing::info!(url = %redacted_url, "registered webhook subscriber");
+            StatusCode::CREATED
+        }
+        Err(error) => {
+            tracing::error!(%error, url = %redacted_url, "could not register webhook subscriber");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handler for the `DELETE /webhooks` endpoint, which removes a registered
+/// subscriber. Subscribers from the config cannot be removed this way.
+pub async fn remove_subscriber_handler<C: Context>(
+    state: State<ApiState<C>>,
+    request: AdminRequest,
+) -> StatusCode {
+    let subscriber: WebhookSubscriber = match request.json() {
+        Ok(subscriber) => subscriber,
+        Err(status) => return status,
+    };
+    // URLs are stored in their normalized form, so we normalize this one
+    // too before looking it up.
+    let Some(url) = subscriber.parse_url() else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    let redacted_url = redact_url(&url);
+    match state
+        .ctx
+        .get_storage_mut()
+        .delete_webhook_subscriber(url.as_str())
+        .await
+    {
+        Ok(true) => {
+            tracing::info!(url = %redacted_url, "removed webhook subscriber");
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(error) => {
+            tracing::error!(%error, url = %redacted_url, "could not remove webhook subscriber");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::testing::api::admin_request;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    fn request(method: Method, url: &str) -> Request<Body> {
+        let body = serde_json::json!({ "url": url });
+        admin_request(method, "/webhooks", Some(body))
+    }
+
+    #[tokio::test]
+    async fn subscribers_can_be_registered_and_removed() {
+        let context = TestContext::with_admin_secret();
+        let app: Router = get_router().with_state(ApiState { ctx: context.clone() });
+        let url = "https://example.com/hook";
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::POST, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let storage = context.get_storage();
+        assert_eq!(storage.get_webhook_subscribers().await.unwrap(), vec![url]);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(storage.get_webhook_subscribers().await.unwrap().is_empty());
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::DELETE, url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(request(Method::POST, "ftp://example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
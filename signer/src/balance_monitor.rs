@@ -0,0 +1,221 @@
+//! # Balance monitoring
+//!
+//! The fees of the stacks transactions that the signers submit are paid
+//! from the signers' multi-sig STX address, and operators need to top it
+//! up before it runs dry, since contract calls fail to be submitted once
+//! it does. The [`BalanceMonitor`] periodically looks up the balance of
+//! that address, projects how long it will last given the fees that were
+//! paid over the last fee budget period, and logs an error when the
+//! balance falls below the configured threshold. It also reports the
+//! amount locked in the signers' bitcoin UTXO, which pays the fees of
+//! sweep transactions.
+
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::context::BitcoinBlockObserved;
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::stacks::api::StacksInteract as _;
+use crate::stacks::fee_budget::StacksFeeReport;
+use crate::stacks::wallet::SignerWallet;
+use crate::storage::DbRead as _;
+
+/// The balance of the signers' STX address and how long it is projected
+/// to last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StxBalanceStatus {
+    /// The unlocked balance of the signers' STX address, in microSTX.
+    pub available_ustx: u128,
+    /// The fees paid over the last fee budget period, in microSTX.
+    pub spent_ustx: u64,
+    /// The length of the fee budget period.
+    pub period: Duration,
+}
+
+impl StxBalanceStatus {
+    /// How long the available balance will last if fees continue to be
+    /// paid at the rate of the last fee budget period. There is no
+    /// projection if no fees were paid over the period.
+    pub fn runway(&self) -> Option<Duration> {
+        if self.spent_ustx == 0 || self.period.is_zero() {
+            return None;
+        }
+        let ustx_per_second = self.spent_ustx as f64 / self.period.as_secs_f64();
+        Some(Duration::from_secs_f64(
+            self.available_ustx as f64 / ustx_per_second,
+        ))
+    }
+
+    /// Whether the available balance is below the given threshold.
+    pub fn is_low(&self, threshold_ustx: u64) -> bool {
+        self.available_ustx < u128::from(threshold_ustx)
+    }
+}
+
+/// Look up the balance of the signers' STX address and the fees paid
+/// from it over the last fee budget period.
+pub async fn stx_balance_status<C: Context>(ctx: &C) -> Result<StxBalanceStatus, Error> {
+    let wallet = SignerWallet::load(ctx).await?;
+    let account = ctx
+        .get_stacks_client()
+        .get_account(wallet.address())
+        .await?;
+    let fees = StacksFeeReport::load(ctx).await?;
+
+    Ok(StxBalanceStatus {
+        available_ustx: account.balance.saturating_sub(account.locked),
+        spent_ustx: fees.spent_ustx,
+        period: Duration::from_secs(fees.period_seconds),
+    })
+}
+
+/// Periodically checks the balances of the signers' wallets, recording
+/// them as metrics and logging an error when the STX balance is low.
+pub struct BalanceMonitor<C> {
+    /// Signer context.
+    context: C,
+    /// Check interval.
+    interval: Duration,
+}
+
+impl<C> BalanceMonitor<C>
+where
+    C: Context,
+{
+    /// Creates a new BalanceMonitor with the given context and interval.
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self { context, interval }
+    }
+
+    /// Runs the BalanceMonitor, which checks the balances right away, then
+    /// once every interval, and whenever the block observer has processed
+    /// a new bitcoin block, since that is when the signers' UTXO changes.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        let mut blocks = self.context.events().subscribe::<BitcoinBlockObserved>();
+        self.check().await;
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.check().await;
+                }
+                event = blocks.recv() => match event {
+                    Ok(_) | Err(RecvError::Lagged(_)) => self.check().await,
+                    // The bus lives as long as the context, so this only
+                    // happens on the way down.
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        tracing::info!("balance monitor has stopped");
+    }
+
+    #[tracing::instrument(skip_all, name = "balance-monitor")]
+    async fn check(&self) {
+        self.check_stx_balance().await;
+        self.check_btc_balance().await;
+    }
+
+    async fn check_stx_balance(&self) {
+        let status = match stx_balance_status(&self.context).await {
+            Ok(status) => status,
+            Err(error) => {
+                tracing::warn!(%error, "could not check the balance of the signers' STX address");
+                return;
+            }
+        };
+
+        let runway = status.runway();
+        Metrics::record_stx_balance(status.available_ustx, runway);
+
+        let threshold = self.context.config().signer.stx_low_balance_threshold_ustx;
+        let runway_hours = runway.map(|runway| runway.as_secs() / 3600);
+        if status.is_low(threshold.get()) {
+            tracing::error!(
+                available_ustx = %status.available_ustx,
+                threshold_ustx = %threshold,
+                ?runway_hours,
+                "the signers' STX balance is low; top it up before stacks transactions fail"
+            );
+        } else {
+            tracing::debug!(
+                available_ustx = %status.available_ustx,
+                ?runway_hours,
+                "checked the signers' STX balance"
+            );
+        }
+    }
+
+    async fn check_btc_balance(&self) {
+        let Some(chain_tip) = self.context.state().bitcoin_chain_tip() else {
+            return;
+        };
+
+        let storage = self.context.get_storage();
+        match storage.get_signer_utxo(&chain_tip.block_hash).await {
+            Ok(Some(utxo)) => Metrics::record_signers_btc_balance(utxo.amount),
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(%error, "could not check the balance of the signers' UTXO");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::stacks::api::AccountInfo;
+    use crate::stacks::fee_budget::record_stacks_fee_spend;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[test]
+    fn runway_is_projected_from_the_spend_rate() {
+        let status = StxBalanceStatus {
+            available_ustx: 2_000_000,
+            spent_ustx: 1_000_000,
+            period: Duration::from_secs(86_400),
+        };
+        assert_eq!(status.runway(), Some(Duration::from_secs(2 * 86_400)));
+        assert!(status.is_low(2_000_001));
+        assert!(!status.is_low(2_000_000));
+
+        let idle = StxBalanceStatus { spent_ustx: 0, ..status };
+        assert_eq!(idle.runway(), None);
+    }
+
+    #[tokio::test]
+    async fn balance_status_uses_recorded_fees() {
+        let ctx = TestContext::default_mocked();
+        ctx.with_stacks_client(|client| {
+            client.expect_get_account().returning(|_| {
+                Box::pin(std::future::ready(Ok(AccountInfo {
+                    balance: 5_000_000,
+                    locked: 1_000_000,
+                    unlock_height: 0u64.into(),
+                    nonce: 1,
+                })))
+            });
+        })
+        .await;
+
+        record_stacks_fee_spend(&ctx, Faker.fake(), "complete-deposit", 1_000)
+            .await
+            .unwrap();
+
+        let status = stx_balance_status(&ctx).await.unwrap();
+        assert_eq!(status.available_ustx, 4_000_000);
+        assert_eq!(status.spent_ustx, 1_000);
+        assert!(status.runway().is_some());
+    }
+}
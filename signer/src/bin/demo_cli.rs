@@ -343,6 +343,7 @@ async fn exec_deposit(ctx: &Context, args: DepositArgs) -> Result<(), Error> {
             deposit_script: deposit_script.deposit_script().to_hex_string(),
             reclaim_script: reclaim_script.reclaim_script().to_hex_string(),
             transaction_hex: serialize_hex(&unsigned_tx),
+            correlation_id: None,
         },
     )
     .await
@@ -0,0 +1,135 @@
+//! # Deposit cap reservations
+//!
+//! The amount of sBTC that may still be minted is the total cap less the
+//! current sBTC supply, but the supply only reflects a deposit once its
+//! `complete-deposit` contract call has been confirmed. Until then, the
+//! capacity that a swept deposit will take up looks free, both to the
+//! coordinator packaging the next sweep and to the signers validating
+//! it, and it could be handed out a second time, producing a sweep whose
+//! deposits cannot all be minted.
+//!
+//! To prevent this, the amounts of the deposits that have been swept by
+//! a sweep transaction confirmed on the canonical bitcoin blockchain, but
+//! that have not been minted on the canonical stacks blockchain, are set
+//! aside whenever deposits are checked against the cap. These
+//! reservations are derived from chain data alone, so every signer
+//! computes the same ones for the same chain tip, whichever signers
+//! acknowledged the sweep and however long ago. A reservation starts when
+//! the sweep confirms and ends when the mint does, or when a reorg undoes
+//! either of them.
+
+use bitcoin::Amount;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockHash;
+
+/// The total amount reserved against the sBTC supply cap for deposits
+/// that were swept in a sweep transaction confirmed on the canonical
+/// bitcoin blockchain identified by the given chain tip, but that have not
+/// been minted yet.
+///
+/// Only sweeps within the signer's context window are considered. A
+/// deposit whose `complete-deposit` event is anchored to a bitcoin block
+/// outside the context window, while its sweep is inside it, is reserved
+/// even though it has been minted, which errs on the side of leaving cap
+/// unused.
+pub async fn reserved_mintable_amount<C>(
+    ctx: &C,
+    chain_tip: &BitcoinBlockHash,
+) -> Result<Amount, Error>
+where
+    C: Context,
+{
+    let context_window = ctx.config().signer.context_window;
+    let swept = ctx
+        .get_storage()
+        .get_swept_deposit_requests(chain_tip, context_window)
+        .await?;
+
+    let reserved = swept
+        .iter()
+        .fold(0u64, |total, deposit| total.saturating_add(deposit.amount));
+
+    Ok(Amount::from_sat(reserved))
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::amount::SbtcAmount;
+    use crate::context::SbtcLimits;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn swept_deposits_are_reserved_until_minted() {
+        let ctx = TestContext::default_mocked();
+        let storage = ctx.get_storage_mut();
+
+        let block: model::BitcoinBlock = Faker.fake();
+        storage.write_bitcoin_block(&block).await.unwrap();
+        let stacks_block = model::StacksBlock {
+            bitcoin_anchor: block.block_hash,
+            ..Faker.fake()
+        };
+        storage.write_stacks_block(&stacks_block).await.unwrap();
+
+        let minted: model::DepositRequest = Faker.fake();
+        let pending: model::DepositRequest = Faker.fake();
+        let unswept: model::DepositRequest = Faker.fake();
+        for request in [&minted, &pending, &unswept] {
+            storage.write_deposit_request(request).await.unwrap();
+        }
+
+        // The sweep transaction spends two of the deposits and is
+        // confirmed in the chain tip.
+        let sweep_txid: model::BitcoinTxId = Faker.fake();
+        let tx_ref = model::BitcoinTxRef {
+            txid: sweep_txid,
+            block_hash: block.block_hash,
+        };
+        storage.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        for request in [&minted, &pending] {
+            let prevout = model::TxPrevout {
+                txid: sweep_txid,
+                prevout_txid: request.txid,
+                prevout_output_index: request.output_index,
+                prevout_type: model::TxPrevoutType::Deposit,
+                ..Faker.fake()
+            };
+            storage.write_tx_prevout(&prevout).await.unwrap();
+        }
+
+        let reserved = reserved_mintable_amount(&ctx, &block.block_hash)
+            .await
+            .unwrap();
+        assert_eq!(reserved, Amount::from_sat(minted.amount + pending.amount));
+
+        // Once the deposit is minted on the canonical stacks blockchain
+        // its reservation is released.
+        let event = model::CompletedDepositEvent {
+            block_id: stacks_block.block_hash,
+            outpoint: minted.outpoint(),
+            amount: SbtcAmount::new(minted.amount),
+            ..Faker.fake()
+        };
+        storage.write_completed_deposit_event(&event).await.unwrap();
+
+        let reserved = reserved_mintable_amount(&ctx, &block.block_hash)
+            .await
+            .unwrap();
+        assert_eq!(reserved, Amount::from_sat(pending.amount));
+
+        let cap = Amount::from_sat(pending.amount + 5_000);
+        let limits = SbtcLimits::new(None, None, None, None, None, None, None, Some(cap));
+        let limits = limits.with_reserved_mintable(reserved);
+        assert_eq!(limits.max_mintable_cap(), Amount::from_sat(5_000));
+    }
+}
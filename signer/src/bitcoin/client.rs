@@ -14,11 +14,14 @@
 //! - Example when trying to get a block that doesn't exist:
 //!   JsonRpc(Rpc(RpcError { code: -5, message: "Block not found", data: None }))
 
+use std::collections::BTreeMap;
+
 use bitcoin::BlockHash;
 use bitcoin::Txid;
 use bitcoincore_rpc_json::GetTxOutResult;
 use url::Url;
 
+use crate::config::Settings;
 use crate::{error::Error, util::ApiFallbackClient};
 
 use super::BitcoinInteract;
@@ -28,6 +31,8 @@ use super::rpc::BitcoinBlockInfo;
 use super::rpc::BitcoinCoreClient;
 use super::rpc::BitcoinTxInfo;
 use super::rpc::GetTxResponse;
+use super::rpc::IndexInfo;
+use super::warm_up;
 
 /// Implement the [`TryFrom`] trait for a slice of [`Url`]s to allow for a
 /// [`ApiFallbackClient`] to be implicitly created from a list of URLs.
@@ -43,6 +48,23 @@ impl TryFrom<&[Url]> for ApiFallbackClient<BitcoinCoreClient> {
     }
 }
 
+/// Implement the [`TryFrom`] trait for the [`Settings`] to create a
+/// [`ApiFallbackClient`] for the configured RPC endpoints, connecting
+/// with the configured outbound settings.
+impl TryFrom<&Settings> for ApiFallbackClient<BitcoinCoreClient> {
+    type Error = Error;
+    fn try_from(settings: &Settings) -> Result<Self, Self::Error> {
+        let clients = settings
+            .bitcoin
+            .rpc_endpoints
+            .iter()
+            .map(|url| BitcoinCoreClient::new_from_url(url, &settings.outbound))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(clients).map_err(Into::into)
+    }
+}
+
 impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
     async fn get_block(
         &self,
@@ -127,8 +149,18 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
     async fn get_blockchain_info(
         &self,
     ) -> Result<bitcoincore_rpc_json::GetBlockchainInfoResult, Error> {
-        self.exec(|client, _| async { client.get_blockchain_info() })
-            .await
+        self.exec(|client, retry| async move {
+            let result = client.get_blockchain_info();
+            // A node that is starting up answers with a warm-up error,
+            // which is passed on when it is the last node that we try so
+            // that the caller can tell it apart from an unreachable node.
+            let is_last_attempt = retry.current_attempt() == retry.total_attempts();
+            if is_last_attempt && result.as_ref().is_err_and(warm_up::is_warm_up_error) {
+                retry.abort();
+            }
+            result
+        })
+        .await
     }
 
     async fn get_network_info(&self) -> Result<bitcoincore_rpc_json::GetNetworkInfoResult, Error> {
@@ -140,4 +172,9 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         self.exec(|client, _| async { client.get_best_block_hash() })
             .await
     }
+
+    async fn get_index_info(&self) -> Result<BTreeMap<String, IndexInfo>, Error> {
+        self.exec(|client, _| async { client.get_index_info() })
+            .await
+    }
 }
@@ -0,0 +1,261 @@
+//! A cache of validated deposit scripts.
+//!
+//! The same deposit request is looked at many times over its life. The
+//! block observer validates every pending deposit request that Emily
+//! returns, on every new bitcoin block, and the coordinator and signers
+//! rebuild the taproot tree of each deposit UTXO whenever they estimate
+//! the size of a sweep transaction, construct its prevouts, or compute
+//! its sighashes. Parsing the scripts and tweaking the taproot key are
+//! the expensive parts of this, and their results only depend on the
+//! outpoint and the scripts, so we cache them here.
+//!
+//! Entries are keyed by outpoint and remember the scripts that they were
+//! computed from. A lookup with different scripts is treated as a miss
+//! and replaces the entry, so a deposit request with made-up scripts for
+//! a real outpoint never gets the result computed for the real scripts.
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Transaction;
+use bitcoin::taproot::TaprootSpendInfo;
+use lru::LruCache;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositInfo;
+use sbtc::deposits::DepositScriptDecoders;
+
+/// The maximum number of deposit outpoints kept in the cache.
+pub const DEPOSIT_SCRIPT_CACHE_SIZE: NonZeroUsize =
+    NonZeroUsize::new(10_000).expect("10,000 is non zero");
+
+/// The process-wide cache of validated deposit scripts.
+pub static DEPOSIT_SCRIPT_CACHE: LazyLock<DepositScriptCache> =
+    LazyLock::new(|| DepositScriptCache::new(DEPOSIT_SCRIPT_CACHE_SIZE));
+
+/// What we know about the scripts of a deposit UTXO.
+#[derive(Debug)]
+struct CachedDepositScripts {
+    /// The deposit script that the entry was computed from.
+    deposit_script: ScriptBuf,
+    /// The reclaim script that the entry was computed from.
+    reclaim_script: ScriptBuf,
+    /// The taproot spend info for the deposit UTXO.
+    taproot: Option<Arc<TaprootSpendInfo>>,
+    /// The result of validating the deposit request against its
+    /// transaction, along with whether it was validated for mainnet.
+    info: Option<(bool, DepositInfo)>,
+}
+
+impl CachedDepositScripts {
+    fn matches(&self, deposit_script: &ScriptBuf, reclaim_script: &ScriptBuf) -> bool {
+        &self.deposit_script == deposit_script && &self.reclaim_script == reclaim_script
+    }
+}
+
+/// A cache of the results of parsing and validating deposit scripts,
+/// keyed by the outpoint of the deposit UTXO.
+#[derive(Debug)]
+pub struct DepositScriptCache {
+    entries: Mutex<LruCache<OutPoint, CachedDepositScripts>>,
+}
+
+/// NOTE: We should never fail to acquire a lock from the Mutex so that it panics.
+/// If we do, then things have gone very wrong.
+#[allow(clippy::expect_used)]
+impl DepositScriptCache {
+    /// Create a new cache holding at most `capacity` outpoints.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Run the function on the entry for the outpoint, replacing the
+    /// entry if it was computed from different scripts.
+    fn with_entry<T>(
+        &self,
+        outpoint: OutPoint,
+        deposit_script: &ScriptBuf,
+        reclaim_script: &ScriptBuf,
+        f: impl FnOnce(&mut CachedDepositScripts) -> T,
+    ) -> T {
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("BUG: Failed to acquire lock of deposit script cache");
+
+        let entry = entries.get_or_insert_mut(outpoint, || CachedDepositScripts {
+            deposit_script: deposit_script.clone(),
+            reclaim_script: reclaim_script.clone(),
+            taproot: None,
+            info: None,
+        });
+        if !entry.matches(deposit_script, reclaim_script) {
+            *entry = CachedDepositScripts {
+                deposit_script: deposit_script.clone(),
+                reclaim_script: reclaim_script.clone(),
+                taproot: None,
+                info: None,
+            };
+        }
+        f(entry)
+    }
+
+    /// Return the taproot spend info of the deposit UTXO with the given
+    /// deposit and reclaim scripts.
+    pub fn taproot_spend_info(
+        &self,
+        outpoint: OutPoint,
+        deposit_script: &ScriptBuf,
+        reclaim_script: &ScriptBuf,
+    ) -> Arc<TaprootSpendInfo> {
+        let cached = self.with_entry(outpoint, deposit_script, reclaim_script, |entry| {
+            entry.taproot.clone()
+        });
+        if let Some(taproot) = cached {
+            return taproot;
+        }
+
+        // We compute the spend info without holding the lock. Another
+        // thread may do the same for the same outpoint, but they will
+        // compute the same thing.
+        let taproot = Arc::new(sbtc::deposits::to_taproot(
+            deposit_script.clone(),
+            reclaim_script.clone(),
+        ));
+        self.with_entry(outpoint, deposit_script, reclaim_script, |entry| {
+            entry.taproot = Some(taproot.clone());
+        });
+        taproot
+    }
+
+    /// Validate the deposit request against the transaction that created
+    /// its UTXO, using a cached result when the request has been
+    /// validated before.
+    ///
+    /// Only successful validations are cached, so a request that fails
+    /// validation is fully validated every time. A cached result is only
+    /// used if the version of its scripts is accepted by the given
+    /// decoders.
+    pub fn validate_tx(
+        &self,
+        request: &CreateDepositRequest,
+        tx: &Transaction,
+        is_mainnet: bool,
+        decoders: &DepositScriptDecoders,
+    ) -> Result<DepositInfo, sbtc::error::Error> {
+        let outpoint = request.outpoint;
+        let deposit_script = &request.deposit_script;
+        let reclaim_script = &request.reclaim_script;
+
+        // The cached result was computed from the transaction with the
+        // outpoint's txid, so it only applies to that transaction.
+        if tx.compute_txid() == outpoint.txid {
+            let cached = self.with_entry(outpoint, deposit_script, reclaim_script, |entry| {
+                entry
+                    .info
+                    .as_ref()
+                    .filter(|(mainnet, _)| *mainnet == is_mainnet)
+                    .filter(|(_, info)| decoders.contains(info.script_version))
+                    .map(|(_, info)| info.clone())
+            });
+            if let Some(info) = cached {
+                return Ok(info);
+            }
+        }
+
+        let info = request.validate_tx_with(tx, is_mainnet, decoders)?;
+        self.with_entry(outpoint, deposit_script, reclaim_script, |entry| {
+            entry.info = Some((is_mainnet, info.clone()));
+        });
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Faker;
+    use sbtc::testing::deposits::TxSetup;
+
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    #[test]
+    fn validated_deposits_are_cached() {
+        let cache = DepositScriptCache::new(NonZeroUsize::new(10).unwrap());
+        let setup: TxSetup = sbtc::testing::deposits::tx_setup(14, 1000, &[10_000]);
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            deposit_script: setup.deposits[0].deposit_script(),
+            reclaim_script: setup.reclaims[0].reclaim_script(),
+        };
+
+        let decoders = DepositScriptDecoders::default();
+
+        let info = cache
+            .validate_tx(&request, &setup.tx, false, &decoders)
+            .unwrap();
+        let cached = cache
+            .validate_tx(&request, &setup.tx, false, &decoders)
+            .unwrap();
+        assert_eq!(cached.deposit_script, info.deposit_script);
+        assert_eq!(cached.amount, 10_000);
+        // The result for testnet does not carry over to mainnet.
+        assert!(
+            cache
+                .validate_tx(&request, &setup.tx, true, &decoders)
+                .is_err()
+        );
+        // Nor to decoders that do not accept the version of its scripts.
+        let no_decoders = DepositScriptDecoders::new([]);
+        assert!(
+            cache
+                .validate_tx(&request, &setup.tx, false, &no_decoders)
+                .is_err()
+        );
+
+        // A request for the same outpoint with different scripts does not
+        // get the cached result.
+        let mut bogus_request = request.clone();
+        bogus_request.reclaim_script = ScriptBuf::new();
+        assert!(
+            cache
+                .validate_tx(&bogus_request, &setup.tx, false, &decoders)
+                .is_err()
+        );
+
+        // Nor does a request validated against a different transaction.
+        let mut other_tx = setup.tx.clone();
+        other_tx.lock_time = bitcoin::absolute::LockTime::from_consensus(1);
+        assert!(
+            cache
+                .validate_tx(&request, &other_tx, false, &decoders)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn taproot_spend_info_matches_the_scripts() {
+        let cache = DepositScriptCache::new(NonZeroUsize::new(1).unwrap());
+        let mut rng = get_rng();
+        let outpoint = OutPoint::new(crate::testing::dummy::txid(&Faker, &mut rng), 0);
+        let deposit_script = ScriptBuf::from_bytes(vec![1, 2, 3]);
+        let reclaim_script = ScriptBuf::from_bytes(vec![4, 5, 6]);
+
+        let expected = sbtc::deposits::to_taproot(deposit_script.clone(), reclaim_script.clone());
+        let taproot = cache.taproot_spend_info(outpoint, &deposit_script, &reclaim_script);
+        assert_eq!(*taproot, expected);
+        let again = cache.taproot_spend_info(outpoint, &deposit_script, &reclaim_script);
+        assert!(Arc::ptr_eq(&taproot, &again));
+
+        let other_script = ScriptBuf::from_bytes(vec![7, 8, 9]);
+        let expected = sbtc::deposits::to_taproot(deposit_script.clone(), other_script.clone());
+        let taproot = cache.taproot_spend_info(outpoint, &deposit_script, &other_script);
+        assert_eq!(*taproot, expected);
+    }
+}
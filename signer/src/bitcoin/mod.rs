@@ -1,5 +1,6 @@
 //! Contains functionality for interacting with the Bitcoin blockchain
 
+use std::collections::BTreeMap;
 use std::future::Future;
 
 use bitcoin::BlockHash;
@@ -11,15 +12,20 @@ use rpc::BitcoinBlockHeader;
 use rpc::BitcoinBlockInfo;
 use rpc::BitcoinTxInfo;
 use rpc::GetTxResponse;
+use rpc::IndexInfo;
 
 use crate::error::Error;
 
+pub mod cap_reservation;
 pub mod client;
+pub mod deposit_cache;
 pub mod packaging;
 pub mod poller;
+pub mod queue;
 pub mod rpc;
 pub mod utxo;
 pub mod validation;
+pub mod warm_up;
 
 /// Result of a call to `get_transaction_fee`.
 #[derive(Debug, Clone)]
@@ -150,6 +156,12 @@ pub trait BitcoinInteract: Sync + Send {
 
     /// Gets the best (canonical, chain tip from chain with most work) block hash from the Bitcoin node.
     fn get_best_block_hash(&self) -> impl Future<Output = Result<BlockHash, Error>> + Send;
+
+    /// Gets the sync status of the optional indexes of the Bitcoin node,
+    /// keyed by the name of the index.
+    fn get_index_info(
+        &self,
+    ) -> impl Future<Output = Result<BTreeMap<String, IndexInfo>, Error>> + Send;
 }
 
 /// A trait for providing a stream of block hashes to be used by the block observer.
@@ -5,6 +5,7 @@ use sbtc::idpack::Segmenter as _;
 
 use crate::MAX_MEMPOOL_PACKAGE_SIZE;
 use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+use crate::MAX_STANDARD_TX_VSIZE;
 
 use super::utxo::MAX_BASE_TX_VSIZE;
 use super::utxo::OP_RETURN_AVAILABLE_SIZE;
@@ -26,6 +27,18 @@ use super::utxo::OP_RETURN_AVAILABLE_SIZE;
 const PACKAGE_MAX_VSIZE: u64 =
     ((MAX_MEMPOOL_PACKAGE_SIZE - MAX_MEMPOOL_PACKAGE_TX_COUNT * MAX_BASE_TX_VSIZE) / 5000) * 5000;
 
+/// The maximum vsize of the items in a bag, for transactions with at most
+/// the given vsize.
+///
+/// The items of a bag do not account for the signers' input and output and
+/// the OP_RETURN output of the transaction, so we set aside
+/// [`MAX_BASE_TX_VSIZE`] for them.
+fn bag_max_vsize(max_tx_vsize: u64) -> u64 {
+    max_tx_vsize
+        .min(MAX_STANDARD_TX_VSIZE)
+        .saturating_sub(MAX_BASE_TX_VSIZE)
+}
+
 /// Package a list of items into optimal bags according to specified
 /// constraints.
 ///
@@ -42,12 +55,23 @@ const PACKAGE_MAX_VSIZE: u64 =
 /// 3. Withdrawal IDs must fit within the OP_RETURN size limit (~77 bytes)
 /// 4. The total virtual size across all bags must not exceed
 ///    [`PACKAGE_MAX_VSIZE`]
+/// 5. The virtual size of the transaction for a bag must not exceed
+///    `max_tx_vsize`
+///
+/// When the items do not fit in a single bag, they are spread across as
+/// many bags as needed, each of which becomes one transaction in a chain
+/// of transactions.
 ///
 /// ## Parameters
 /// - `items`: Collection of items to be packaged
 /// - `max_votes_against`: Maximum allowed votes against for any bag
 /// - `max_needs_signature`: Maximum number of items requiring signatures in a
 ///   bag
+/// - `max_tx_vsize`: Maximum virtual size of the transaction for any bag,
+///   capped at [`MAX_STANDARD_TX_VSIZE`]
+/// - `aggregate_withdrawals`: Whether withdrawals paying the same recipient
+///   share an output, in which case room is left in the OP_RETURN output
+///   for the map of the withdrawals onto the outputs
 ///
 /// ## Notes
 /// - Items that exceed constraints individually are silently ignored
@@ -59,6 +83,8 @@ pub fn compute_optimal_packages<I, T>(
     items: I,
     max_votes_against: u32,
     max_needs_signature: u16,
+    max_tx_vsize: u64,
+    aggregate_withdrawals: bool,
 ) -> impl Iterator<Item = Vec<T>>
 where
     I: IntoIterator<Item = T>,
@@ -67,7 +93,11 @@ where
     // Now we just add each item into a bag, and return the
     // collection of bags afterward.
     // Create config and packager
-    let config = PackagerConfig::new(max_votes_against, max_needs_signature);
+    let config = PackagerConfig {
+        max_bag_vsize: bag_max_vsize(max_tx_vsize),
+        reserve_output_map: aggregate_withdrawals,
+        ..PackagerConfig::new(max_votes_against, max_needs_signature)
+    };
     let mut packager = BestFitPackager::new(config);
 
     for item in items {
@@ -159,11 +189,21 @@ struct PackagerConfig {
     /// Derived from Bitcoin Core's package relay limits to ensure transactions
     /// are accepted by the network.
     max_total_vsize: u64,
+    /// Maximum virtual size of the items in a single bag.
+    ///
+    /// Keeps each transaction under the standard transaction size, or
+    /// under the smaller size that the signer is configured with, so that
+    /// large backlogs are split across a chain of transactions.
+    max_bag_vsize: u64,
     /// Maximum available size for encoding withdrawal IDs in OP_RETURN.
     ///
     /// Enforcement of this limit prevents transaction rejection due to
     /// oversized OP_RETURN outputs.
     max_op_return_size: usize,
+    /// Whether to leave room in the OP_RETURN output for the map of the
+    /// withdrawals onto the withdrawal outputs, which takes one byte plus
+    /// one byte per withdrawal.
+    reserve_output_map: bool,
 }
 
 impl PackagerConfig {
@@ -181,7 +221,9 @@ impl PackagerConfig {
             max_votes_against,
             max_signatures,
             max_total_vsize: PACKAGE_MAX_VSIZE,
+            max_bag_vsize: bag_max_vsize(MAX_STANDARD_TX_VSIZE),
             max_op_return_size: OP_RETURN_AVAILABLE_SIZE,
+            reserve_output_map: false,
         }
     }
 }
@@ -275,6 +317,7 @@ where
     /// 1. Combined votes against ≤ max_votes_against
     /// 2. Combined signature requirements ≤ max_signatures
     /// 3. Withdrawal ID (if any) fits within remaining OP_RETURN space
+    /// 4. Combined vsize ≤ max_bag_vsize
     ///
     /// ## Parameters
     /// - `item`: Item to check for compatibility
@@ -284,6 +327,7 @@ where
     fn is_compatible(&self, item: &T) -> bool {
         self.votes_compatible(item)
             && self.signatures_compatible(item)
+            && self.vsize_compatible(item)
             && self.withdrawal_id_compatible(item)
     }
 
@@ -311,6 +355,17 @@ where
         self.items_needing_signatures + sig <= self.config.max_signatures
     }
 
+    /// Check if an item's vsize is compatible with this bag.
+    ///
+    /// ## Parameters
+    /// - `item`: Item to check for vsize compatibility
+    ///
+    /// ## Returns
+    /// `true` if adding the item wouldn't exceed the bag vsize limit.
+    fn vsize_compatible(&self, item: &T) -> bool {
+        self.vsize + item.vsize() <= self.config.max_bag_vsize
+    }
+
     /// Check if an item's withdrawal ID is compatible with this bag.
     ///
     /// ## Parameters
@@ -393,6 +448,11 @@ where
         if ids.is_empty() {
             return true;
         }
+        let reserved = if self.config.reserve_output_map {
+            1 + ids.len()
+        } else {
+            0
+        };
 
         BitmapSegmenter
             .estimate_size(ids)
@@ -401,7 +461,7 @@ where
                     tracing::warn!(%error, withdrawal_ids = ?ids, "error estimating packaged withdrawal id size");
                     false
                 },
-                |size| size <= self.config.max_op_return_size
+                |size| size + reserved <= self.config.max_op_return_size
             )
     }
 }
@@ -413,6 +473,7 @@ where
 /// 2. Respect signature limits for each bag
 /// 3. Ensure withdrawal IDs fit within OP_RETURN size limits
 /// 4. Keep total virtual size within Bitcoin network limits
+/// 5. Keep each bag within the transaction size limit
 ///
 /// ## Implementation Notes
 /// - Items that exceed individual limits are silently ignored
@@ -470,6 +531,7 @@ impl<T: Weighted> BestFitPackager<T> {
 
         // Early exits for items exceeding our bag-independent limits.
         if votes_against > self.config.max_votes_against
+            || item.vsize() > self.config.max_bag_vsize
             || total_package_vsize > self.config.max_total_vsize
         {
             return;
@@ -742,8 +804,13 @@ mod tests {
         expected_bag_vsizes: [0, 0],
     } ; "votes-against-placement")]
     fn returns_optimal_placements<const N: usize>(case: VotesTestCase<N>) {
-        let ans =
-            compute_optimal_packages(case.items, case.max_votes_against, case.max_needs_signature);
+        let ans = compute_optimal_packages(
+            case.items,
+            case.max_votes_against,
+            case.max_needs_signature,
+            MAX_STANDARD_TX_VSIZE,
+            false,
+        );
         let collection = ans.collect::<Vec<_>>();
         let iter = collection
             .iter()
@@ -761,6 +828,33 @@ mod tests {
         }
     }
 
+    /// Items that do not fit in a single transaction of the maximum size
+    /// are spread across as many bags as needed, instead of being left out
+    /// of the package, while items that could never fit are left out.
+    #[test]
+    fn splits_when_bag_vsize_exceeds_max_tx_vsize() {
+        let max_tx_vsize = 10_000 + MAX_BASE_TX_VSIZE;
+        let mut items = vec![RequestItem::no_votes().vsize(4000); 7];
+        items.push(RequestItem::no_votes().vsize(max_tx_vsize));
+
+        let bags = compute_optimal_packages(items, 1, 100, max_tx_vsize, false).collect::<Vec<_>>();
+
+        let bag_sizes: Vec<usize> = bags.iter().map(Vec::len).collect();
+        assert_eq!(bag_sizes, [2, 2, 2, 1]);
+        for bag in bags {
+            let bag_vsize = bag.iter().map(|item| item.vsize()).sum::<u64>();
+            more_asserts::assert_le!(bag_vsize + MAX_BASE_TX_VSIZE, max_tx_vsize);
+        }
+    }
+
+    #[test_case(50_000 => 50_000 - MAX_BASE_TX_VSIZE; "below-standard-size")]
+    #[test_case(100_000 => 100_000 - MAX_BASE_TX_VSIZE; "standard-size")]
+    #[test_case(u64::MAX => MAX_STANDARD_TX_VSIZE - MAX_BASE_TX_VSIZE; "above-standard-size")]
+    #[test_case(0 => 0; "zero")]
+    fn test_bag_max_vsize(max_tx_vsize: u64) -> u64 {
+        bag_max_vsize(max_tx_vsize)
+    }
+
     /// Tests that the OP_RETURN size estimation correctly identifies both small sets that fit
     /// and large sets that exceed the size limit.
     #[test]
@@ -788,14 +882,25 @@ mod tests {
 
         let max_needs_signature = 100;
         let max_votes_against = 3;
-        let packages1 =
-            compute_optimal_packages(items.clone(), max_votes_against, max_needs_signature)
-                .collect::<Vec<_>>();
+        let packages1 = compute_optimal_packages(
+            items.clone(),
+            max_votes_against,
+            max_needs_signature,
+            MAX_STANDARD_TX_VSIZE,
+            false,
+        )
+        .collect::<Vec<_>>();
 
         items.shuffle(&mut rng);
 
-        let packages2 = compute_optimal_packages(items, max_votes_against, max_needs_signature)
-            .collect::<Vec<_>>();
+        let packages2 = compute_optimal_packages(
+            items,
+            max_votes_against,
+            max_needs_signature,
+            MAX_STANDARD_TX_VSIZE,
+            false,
+        )
+        .collect::<Vec<_>>();
 
         assert_ne!(packages1, packages2);
     }
@@ -1150,7 +1255,8 @@ mod tests {
         items.push(RequestItem::with_vote(1).wid(3000)); // Different vote pattern
         items.push(RequestItem::no_votes().wid(10000)); // Large ID
 
-        let bags = compute_optimal_packages(items, 1, 5).collect::<Vec<_>>();
+        let bags =
+            compute_optimal_packages(items, 1, 5, MAX_STANDARD_TX_VSIZE, false).collect::<Vec<_>>();
 
         // Verify multiple bags were created due to both vote and withdrawal ID constraints
         assert!(bags.len() > 1);
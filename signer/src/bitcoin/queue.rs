@@ -0,0 +1,393 @@
+//! # The pending requests queue
+//!
+//! Each time the coordinator prepares a sweep package it records its view
+//! of the pending deposit and withdrawal requests as a [`PendingQueue`],
+//! which is served by the `/queue` endpoint of the API. The queue orders
+//! the requests the way the packaging algorithm prioritizes them, and
+//! estimates the tenure in which each request will be swept by repeatedly
+//! packaging the requests that did not fit in earlier tenures. Requests
+//! that cannot be swept at all are listed along with the reason why.
+//!
+//! The estimates assume that no new requests arrive and that the fee rate
+//! and the sBTC limits stay the same, so they become less accurate the
+//! further out they are.
+
+use std::collections::HashSet;
+
+use bitcoin::Amount;
+use serde::Serialize;
+
+use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+use crate::bitcoin::packaging::Weighted as _;
+use crate::bitcoin::packaging::compute_optimal_packages;
+use crate::bitcoin::utxo::RequestPreprocessor;
+use crate::bitcoin::utxo::RequestRef;
+use crate::bitcoin::utxo::SbtcRequests;
+use crate::storage::model::BitcoinBlockRef;
+
+/// The maximum number of tenures that the queue looks ahead when
+/// estimating when requests will be swept.
+pub const MAX_ESTIMATED_TENURES: u32 = 10;
+
+/// The reason that a pending request cannot be swept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockingReason {
+    /// The max fee of the request does not cover its share of the sweep
+    /// transaction fee at the current fee rate.
+    FeeTooLow,
+    /// The amount of the request, less fees, is below the dust limit.
+    BelowDustLimit,
+    /// The deposit amount is below the per-deposit minimum.
+    BelowPerDepositMinimum,
+    /// The deposit amount is above the per-deposit cap.
+    AbovePerDepositCap,
+    /// Minting the deposit would exceed the maximum mintable cap.
+    AboveMaxMintableCap,
+    /// The withdrawal amount is above the per-withdrawal cap.
+    AbovePerWithdrawalCap,
+    /// The withdrawal would exceed the rolling withdrawal cap.
+    AboveRollingWithdrawalCap,
+    /// Too many signers voted against the request for it to be signed.
+    TooManyVotesAgainst,
+    /// The request was not packaged within [`MAX_ESTIMATED_TENURES`]
+    /// tenures.
+    NotPackaged,
+}
+
+/// A pending request, as seen by the coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueuedRequest {
+    /// Either `deposit` or `withdrawal`.
+    pub kind: &'static str,
+    /// The transaction ID of a deposit request.
+    pub txid: Option<String>,
+    /// The output index of a deposit request.
+    pub output_index: Option<u32>,
+    /// The request ID of a withdrawal request.
+    pub request_id: Option<u64>,
+    /// The amount of the request, in sats.
+    pub amount: u64,
+    /// The maximum fee of the request, in sats.
+    pub max_fee: u64,
+    /// The number of signers that voted against the request.
+    pub votes_against: u32,
+    /// The position of the request in the queue, starting at zero.
+    pub priority: usize,
+    /// The number of tenures after the one of the queue's chain tip
+    /// before the request is swept, where zero means that the request is
+    /// in the package of the current tenure. This is `None` for blocked
+    /// requests.
+    pub estimated_tenure: Option<u32>,
+    /// The index of the sweep transaction, within the package of its
+    /// estimated tenure, that sweeps the request.
+    pub transaction_index: Option<usize>,
+    /// Why the request cannot be swept, if it cannot be.
+    pub blocking_reason: Option<BlockingReason>,
+}
+
+impl QueuedRequest {
+    fn new(request: RequestRef<'_>) -> Self {
+        let votes_against = request.votes().count_ones();
+        match request {
+            RequestRef::Deposit(deposit) => Self {
+                kind: "deposit",
+                txid: Some(deposit.outpoint.txid.to_string()),
+                output_index: Some(deposit.outpoint.vout),
+                request_id: None,
+                amount: deposit.amount,
+                max_fee: deposit.max_fee,
+                votes_against,
+                priority: 0,
+                estimated_tenure: None,
+                transaction_index: None,
+                blocking_reason: None,
+            },
+            RequestRef::Withdrawal(withdrawal) => Self {
+                kind: "withdrawal",
+                txid: None,
+                output_index: None,
+                request_id: Some(withdrawal.request_id),
+                amount: withdrawal.amount,
+                max_fee: withdrawal.max_fee,
+                votes_against,
+                priority: 0,
+                estimated_tenure: None,
+                transaction_index: None,
+                blocking_reason: None,
+            },
+        }
+    }
+
+    fn scheduled(request: RequestRef<'_>, tenure: u32, transaction_index: usize) -> Self {
+        Self {
+            estimated_tenure: Some(tenure),
+            transaction_index: Some(transaction_index),
+            ..Self::new(request)
+        }
+    }
+
+    fn blocked(request: RequestRef<'_>, reason: BlockingReason) -> Self {
+        Self {
+            blocking_reason: Some(reason),
+            ..Self::new(request)
+        }
+    }
+}
+
+/// The coordinator's view of the pending requests at a bitcoin chain tip.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PendingQueue {
+    /// The hash of the bitcoin chain tip of the view.
+    pub bitcoin_chain_tip: String,
+    /// The height of the bitcoin chain tip of the view.
+    pub bitcoin_block_height: u64,
+    /// When the view was recorded.
+    pub created_at: String,
+    /// The fee rate, in sats per vbyte, that the requests were evaluated
+    /// with. This is `None` when there were no pending requests.
+    pub fee_rate: Option<f64>,
+    /// The pending requests, in priority order. Requests that will be
+    /// swept come first, followed by the blocked requests.
+    pub requests: Vec<QueuedRequest>,
+}
+
+impl PendingQueue {
+    /// Create the queue of the given pending requests at the given chain
+    /// tip.
+    pub fn new(bitcoin_chain_tip: &BitcoinBlockRef, requests: Option<&SbtcRequests>) -> Self {
+        Self {
+            bitcoin_chain_tip: bitcoin_chain_tip.block_hash.to_string(),
+            bitcoin_block_height: *bitcoin_chain_tip.block_height,
+            created_at: time::OffsetDateTime::now_utc().to_string(),
+            fee_rate: requests.map(|requests| requests.signer_state.fee_rate),
+            requests: requests.map(prioritize).unwrap_or_default(),
+        }
+    }
+}
+
+/// Order the given requests the way the packaging algorithm prioritizes
+/// them, estimating the tenure in which each of them will be swept.
+pub fn prioritize(requests: &SbtcRequests) -> Vec<QueuedRequest> {
+    let preprocessor = RequestPreprocessor::new(
+        &requests.sbtc_limits,
+        requests.signer_state.fee_rate,
+        requests.signer_state.last_fees,
+    );
+
+    let mut blocked = Vec::new();
+    let mut eligible = Vec::new();
+
+    let mut amount_to_mint = Amount::from_sat(0);
+    for deposit in requests.deposits.iter() {
+        match preprocessor.validate_deposit_amount(&mut amount_to_mint, deposit) {
+            Ok(request) => eligible.push(request),
+            Err(reason) => {
+                blocked.push(QueuedRequest::blocked(RequestRef::Deposit(deposit), reason))
+            }
+        }
+    }
+
+    // Withdrawals are validated in the order of their request IDs.
+    let mut withdrawals: Vec<_> = requests.withdrawals.iter().collect();
+    withdrawals.sort_by_key(|withdrawal| withdrawal.request_id);
+    let mut withdrawn_total = requests
+        .sbtc_limits
+        .rolling_withdrawal_limits()
+        .withdrawn_total;
+    for withdrawal in withdrawals {
+        match preprocessor.validate_withdrawal_amounts(&mut withdrawn_total, withdrawal) {
+            Ok(request) => eligible.push(request),
+            Err(reason) => blocked.push(QueuedRequest::blocked(
+                RequestRef::Withdrawal(withdrawal),
+                reason,
+            )),
+        }
+    }
+
+    let max_votes_against = requests.reject_capacity();
+    let max_needs_signature = requests.max_deposits_per_bitcoin_tx;
+
+    let mut queue = Vec::new();
+    for tenure in 0..MAX_ESTIMATED_TENURES {
+        let packages: Vec<Vec<RequestRef>> = compute_optimal_packages(
+            eligible.iter().copied(),
+            max_votes_against,
+            max_needs_signature,
+            requests.max_tx_vsize,
+            requests.aggregate_withdrawal_outputs,
+        )
+        .take(MAX_MEMPOOL_PACKAGE_TX_COUNT as usize)
+        .collect();
+
+        let mut packaged = HashSet::new();
+        for (transaction_index, package) in packages.into_iter().enumerate() {
+            for request in package {
+                packaged.insert(request);
+                queue.push(QueuedRequest::scheduled(request, tenure, transaction_index));
+            }
+        }
+
+        if packaged.is_empty() {
+            break;
+        }
+        eligible.retain(|request| !packaged.contains(request));
+    }
+
+    // Whatever is left could not be packaged, either because too many
+    // signers voted against it or because it is too far down the queue.
+    queue.extend(eligible.into_iter().map(|request| {
+        let reason = if request.votes().count_ones() > max_votes_against {
+            BlockingReason::TooManyVotesAgainst
+        } else {
+            BlockingReason::NotPackaged
+        };
+        QueuedRequest::blocked(request, reason)
+    }));
+    queue.extend(blocked);
+
+    for (priority, request) in queue.iter_mut().enumerate() {
+        request.priority = priority;
+    }
+    queue
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::OutPoint;
+    use bitcoin::ScriptBuf;
+    use bitcoin::Txid;
+    use bitcoin::WPubkeyHash;
+    use bitcoin::hashes::Hash as _;
+    use bitvec::array::BitArray;
+    use fake::Fake as _;
+    use rand::rngs::OsRng;
+    use secp256k1::SECP256K1;
+    use secp256k1::SecretKey;
+    use secp256k1::XOnlyPublicKey;
+    use test_case::test_case;
+
+    use crate::MAX_STANDARD_TX_VSIZE;
+    use crate::bitcoin::utxo::DepositRequest;
+    use crate::bitcoin::utxo::SignerBtcState;
+    use crate::bitcoin::utxo::SignerUtxo;
+    use crate::bitcoin::utxo::WithdrawalRequest;
+    use crate::context::SbtcLimits;
+
+    use super::*;
+
+    fn public_key() -> XOnlyPublicKey {
+        SecretKey::new(&mut OsRng).x_only_public_key(SECP256K1).0
+    }
+
+    fn deposit(vout: u32, max_fee: u64, votes_against: usize) -> DepositRequest {
+        let mut signer_bitmap = BitArray::ZERO;
+        signer_bitmap[..votes_against].fill(true);
+        DepositRequest {
+            outpoint: OutPoint::new(Txid::all_zeros(), vout),
+            max_fee,
+            signer_bitmap,
+            amount: 100_000,
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            reclaim_script_hash: None,
+            signers_public_key: public_key(),
+        }
+    }
+
+    fn withdrawal(request_id: u64, max_fee: u64) -> WithdrawalRequest {
+        WithdrawalRequest {
+            request_id,
+            txid: fake::Faker.fake_with_rng(&mut OsRng),
+            block_hash: fake::Faker.fake_with_rng(&mut OsRng),
+            amount: 50_000,
+            max_fee,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()).into(),
+            signer_bitmap: BitArray::ZERO,
+        }
+    }
+
+    fn requests(
+        deposits: Vec<DepositRequest>,
+        withdrawals: Vec<WithdrawalRequest>,
+        max_deposits_per_bitcoin_tx: u16,
+    ) -> SbtcRequests {
+        SbtcRequests {
+            deposits,
+            withdrawals,
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::null(),
+                    amount: 1_000_000,
+                    public_key: public_key(),
+                },
+                fee_rate: 1.0,
+                public_key: public_key(),
+                last_fees: None,
+                magic_bytes: [0; 2],
+                lock_time: bitcoin::absolute::LockTime::ZERO,
+            },
+            accept_threshold: 4,
+            num_signers: 5,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
+        }
+    }
+
+    #[test]
+    fn requests_are_scheduled_across_tenures() {
+        // Only one deposit fits in each transaction, and a package has at
+        // most 25 transactions, so the last five deposits spill over into
+        // the next tenure.
+        let deposits = (0..30).map(|vout| deposit(vout, 10_000, 0)).collect();
+        let queue = prioritize(&requests(deposits, Vec::new(), 1));
+
+        assert_eq!(queue.len(), 30);
+        assert!(
+            queue
+                .iter()
+                .all(|request| request.blocking_reason.is_none())
+        );
+        assert_eq!(queue[0].estimated_tenure, Some(0));
+        assert_eq!(queue[24].estimated_tenure, Some(0));
+        assert_eq!(queue[25].estimated_tenure, Some(1));
+        assert_eq!(queue[25].transaction_index, Some(0));
+
+        let tenures: Vec<_> = queue
+            .iter()
+            .map(|request| request.estimated_tenure)
+            .collect();
+        assert!(tenures.is_sorted());
+        let priorities: Vec<_> = queue.iter().map(|request| request.priority).collect();
+        assert_eq!(priorities, (0..30).collect::<Vec<_>>());
+    }
+
+    #[test_case(deposit(0, 10_000, 2), BlockingReason::TooManyVotesAgainst; "votes against")]
+    #[test_case(deposit(0, 100, 0), BlockingReason::FeeTooLow; "fee too low")]
+    fn blocked_deposits_come_last(blocked: DepositRequest, reason: BlockingReason) {
+        let deposits = vec![blocked, deposit(1, 10_000, 0)];
+        let queue = prioritize(&requests(deposits, Vec::new(), 25));
+
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].output_index, Some(1));
+        assert_eq!(queue[0].estimated_tenure, Some(0));
+        assert_eq!(queue[1].output_index, Some(0));
+        assert_eq!(queue[1].estimated_tenure, None);
+        assert_eq!(queue[1].blocking_reason, Some(reason));
+    }
+
+    #[test]
+    fn withdrawals_with_low_fees_are_blocked() {
+        let withdrawals = vec![withdrawal(2, 10_000), withdrawal(1, 1)];
+        let queue = prioritize(&requests(Vec::new(), withdrawals, 25));
+
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].request_id, Some(2));
+        assert_eq!(queue[0].estimated_tenure, Some(0));
+        assert_eq!(queue[1].request_id, Some(1));
+        assert_eq!(queue[1].blocking_reason, Some(BlockingReason::FeeTooLow));
+    }
+}
@@ -1,5 +1,6 @@
 //! Contains client wrappers for bitcoin core and electrum.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use bitcoin::Amount;
@@ -23,6 +24,7 @@ use serde::Deserialize;
 use url::Url;
 
 use crate::bitcoin::BitcoinInteract;
+use crate::config::OutboundConfig;
 use crate::error::Error;
 use crate::storage::model::BitcoinBlockHeight;
 
@@ -316,6 +318,19 @@ pub struct BitcoinBlockHeader {
     pub previous_block_hash: BlockHash,
 }
 
+/// The sync status of one of the optional indexes of bitcoin-core, like
+/// the transaction index, from a `getindexinfo` RPC call.
+///
+/// The docs for the getindexinfo RPC call can be found here:
+/// <https://bitcoincore.org/en/doc/25.0.0/rpc/util/getindexinfo/>.
+#[derive(Clone, PartialEq, Eq, Debug, serde::Deserialize, serde::Serialize)]
+pub struct IndexInfo {
+    /// Whether the index has caught up with the chain tip of the node.
+    pub synced: bool,
+    /// The height of the most recent block in the index.
+    pub best_block_height: u64,
+}
+
 /// A struct representing the recommended fee, in sats per vbyte, from a
 /// particular source.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -337,6 +352,19 @@ impl TryFrom<&Url> for BitcoinCoreClient {
     type Error = Error;
 
     fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        Self::new_from_url(url, &OutboundConfig::default())
+    }
+}
+
+impl BitcoinCoreClient {
+    /// Return a bitcoin-core RPC client for the given URL, which includes
+    /// the username and password, that connects using the given outbound
+    /// settings.
+    ///
+    /// # Notes
+    ///
+    /// This function does not attempt to establish a connection to bitcoin-core.
+    pub fn new_from_url(url: &Url, outbound: &OutboundConfig) -> Result<Self, Error> {
         let username = url.username().to_string();
         let password = url.password().unwrap_or_default().to_string();
         let host = url
@@ -346,11 +374,49 @@ impl TryFrom<&Url> for BitcoinCoreClient {
 
         let endpoint = format!("{}://{host}:{port}", url.scheme());
 
-        Self::new(&endpoint, username, password)
+        match outbound.proxy_for(url) {
+            Some(proxy) => Self::new_with_proxy(&endpoint, username, password, proxy),
+            None => Self::new(&endpoint, username, password),
+        }
+    }
+
+    /// Return a bitcoin-core RPC client that connects through the given
+    /// SOCKS5 proxy. Will error if the URL is an invalid URL or if the
+    /// proxy is not a SOCKS5 proxy.
+    ///
+    /// # Notes
+    ///
+    /// This function does not attempt to establish a connection to the
+    /// proxy or to bitcoin-core.
+    pub fn new_with_proxy(
+        url: &str,
+        username: String,
+        password: String,
+        proxy: &Url,
+    ) -> Result<Self, Error> {
+        let proxy_addr = match (proxy.host_str(), proxy.port()) {
+            (Some(host), Some(port)) if OutboundConfig::is_socks5(proxy) => {
+                format!("{host}:{port}")
+            }
+            _ => return Err(Error::UnsupportedBitcoinRpcProxy(proxy.clone())),
+        };
+
+        let mut builder = jsonrpc::simple_http::Builder::new()
+            .url(url)
+            .and_then(|builder| builder.proxy_addr(proxy_addr))
+            .map_err(|err| Error::BitcoinCoreRpcTransport(err, url.to_string()))?
+            .auth(username, Some(password));
+        if !proxy.username().is_empty() {
+            let proxy_password = proxy.password().unwrap_or_default();
+            builder = builder.proxy_auth(proxy.username(), proxy_password);
+        }
+
+        let client = jsonrpc::Client::with_transport(builder.build());
+        let inner = Arc::new(bitcoincore_rpc::Client::from_jsonrpc(client));
+
+        Ok(Self { inner })
     }
-}
 
-impl BitcoinCoreClient {
     /// Return a bitcoin-core RPC client. Will error if the URL is an invalid URL.
     ///
     /// # Notes
@@ -662,6 +728,18 @@ impl BitcoinCoreClient {
     pub fn get_network_info(&self) -> Result<GetNetworkInfoResult, Error> {
         self.inner.get_network_info().map_err(Error::BitcoinCoreRpc)
     }
+
+    /// Gets the sync status of the optional indexes of the Bitcoin node,
+    /// keyed by the name of the index. Indexes that are not enabled are
+    /// not included.
+    ///
+    /// Documentation for the `getindexinfo` RPC call can be found here:
+    /// https://bitcoincore.org/en/doc/25.0.0/rpc/util/getindexinfo/
+    pub fn get_index_info(&self) -> Result<BTreeMap<String, IndexInfo>, Error> {
+        self.inner
+            .call("getindexinfo", &[])
+            .map_err(Error::BitcoinCoreRpc)
+    }
 }
 
 impl BitcoinInteract for BitcoinCoreClient {
@@ -815,6 +893,10 @@ impl BitcoinInteract for BitcoinCoreClient {
     async fn get_best_block_hash(&self) -> Result<BlockHash, Error> {
         self.get_best_block_hash()
     }
+
+    async fn get_index_info(&self) -> Result<BTreeMap<String, IndexInfo>, Error> {
+        self.get_index_info()
+    }
 }
 
 #[cfg(test)]
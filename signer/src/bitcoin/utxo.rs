@@ -1,6 +1,7 @@
 //! Utxo management and transaction construction
 
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::sync::LazyLock;
 
 use bitcoin::Amount;
@@ -24,7 +25,6 @@ use bitcoin::script::PushBytesBuf;
 use bitcoin::sighash::Prevouts;
 use bitcoin::sighash::SighashCache;
 use bitcoin::taproot::LeafVersion;
-use bitcoin::taproot::NodeInfo;
 use bitcoin::taproot::Signature;
 use bitcoin::taproot::TaprootSpendInfo;
 use bitcoin::transaction::Version;
@@ -41,9 +41,15 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::DEPOSIT_DUST_LIMIT;
+use crate::MAX_DONATION_INPUTS_PER_SWEEP;
+use crate::MAX_MEMPOOL_PACKAGE_SIZE;
 use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+use crate::MAX_STANDARD_TX_VSIZE;
+use crate::MIN_BITCOIN_INPUT_VSIZE;
+use crate::bitcoin::deposit_cache::DEPOSIT_SCRIPT_CACHE;
 use crate::bitcoin::packaging::Weighted;
 use crate::bitcoin::packaging::compute_optimal_packages;
+use crate::bitcoin::queue::BlockingReason;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::context::SbtcLimits;
 use crate::error::Error;
@@ -95,6 +101,11 @@ const SATS_PER_VBYTE_INCREMENT: f64 = 0.001;
 /// transactions.
 const OP_RETURN_VERSION: u8 = 1;
 
+/// The OP_RETURN version byte for sweep transactions where at least one
+/// withdrawal output pays more than one withdrawal request. The encoded
+/// withdrawal IDs are preceded by a [`WithdrawalOutputMap`].
+const OP_RETURN_AGGREGATED_VERSION: u8 = 2;
+
 /// The OP_RETURN header size (magic bytes + version)
 const OP_RETURN_HEADER_SIZE: usize = 3;
 
@@ -141,6 +152,9 @@ pub struct RequestPreprocessor<'a> {
     /// The total fee amount and the fee rate for the last transaction that
     /// used this UTXO as an input.
     last_fees: Option<Fees>,
+    /// The amount, in sats, of the fees that are paid for by donation
+    /// UTXOs rather than by the requests.
+    fee_subsidy: u64,
 }
 
 impl<'a> RequestPreprocessor<'a> {
@@ -150,22 +164,33 @@ impl<'a> RequestPreprocessor<'a> {
             sbtc_limits,
             fee_rate,
             last_fees,
+            fee_subsidy: 0,
         }
     }
 
+    /// Set the amount of the fees that donation UTXOs pay for, which
+    /// lowers the minimum fee required from each request.
+    pub fn with_fee_subsidy(mut self, fee_subsidy: u64) -> Self {
+        self.fee_subsidy = fee_subsidy;
+        self
+    }
+
     /// Validate deposit requests based on four constraints:
     /// 1. The user's max fee must be >= our minimum required fee for deposits
     ///    (based on fixed deposit tx size)
     /// 2. The deposit amount must be greater than or equal to the per-deposit minimum
     /// 3. The deposit amount must be less than or equal to the per-deposit cap
     /// 4. The total amount being minted must stay under the peg cap
-    fn validate_deposit_amount(
+    ///
+    /// The error is the first constraint that the deposit fails to meet.
+    pub(super) fn validate_deposit_amount(
         &self,
         amount_to_mint: &mut Amount,
         req: &'a DepositRequest,
-    ) -> Option<RequestRef<'a>> {
+    ) -> Result<RequestRef<'a>, BlockingReason> {
         let minimum_fee =
-            compute_transaction_fee(SOLO_DEPOSIT_TX_VSIZE, self.fee_rate, self.last_fees);
+            compute_transaction_fee(SOLO_DEPOSIT_TX_VSIZE, self.fee_rate, self.last_fees)
+                .saturating_sub(self.fee_subsidy);
 
         let is_fee_valid = req.max_fee.min(req.amount) >= minimum_fee;
         let is_above_dust = req.amount.saturating_sub(minimum_fee) >= DEPOSIT_DUST_LIMIT;
@@ -179,16 +204,19 @@ impl<'a> RequestPreprocessor<'a> {
                 false
             };
 
-        if is_fee_valid
-            && is_above_dust
-            && is_above_per_deposit_minimum
-            && is_within_per_deposit_cap
-            && is_within_max_mintable_cap
-        {
-            *amount_to_mint += req_amount;
-            Some(RequestRef::Deposit(req))
+        if !is_fee_valid {
+            Err(BlockingReason::FeeTooLow)
+        } else if !is_above_dust {
+            Err(BlockingReason::BelowDustLimit)
+        } else if !is_above_per_deposit_minimum {
+            Err(BlockingReason::BelowPerDepositMinimum)
+        } else if !is_within_per_deposit_cap {
+            Err(BlockingReason::AbovePerDepositCap)
+        } else if !is_within_max_mintable_cap {
+            Err(BlockingReason::AboveMaxMintableCap)
         } else {
-            None
+            *amount_to_mint += req_amount;
+            Ok(RequestRef::Deposit(req))
         }
     }
 
@@ -200,11 +228,14 @@ impl<'a> RequestPreprocessor<'a> {
     ///    per-withdrawal cap.
     /// 3. The total amount being withdrawn must stay under the rolling
     ///    withdrawal limits.
-    fn validate_withdrawal_amounts(
+    ///
+    /// The error is the first constraint that the withdrawal fails to
+    /// meet.
+    pub(super) fn validate_withdrawal_amounts(
         &self,
         withdrawal_amounts: &mut u64,
         req: &'a WithdrawalRequest,
-    ) -> Option<RequestRef<'a>> {
+    ) -> Result<RequestRef<'a>, BlockingReason> {
         let rolling_limits = self.sbtc_limits.rolling_withdrawal_limits();
 
         let new_cumulative_total = withdrawal_amounts.saturating_add(req.amount);
@@ -219,14 +250,21 @@ impl<'a> RequestPreprocessor<'a> {
         let is_above_minimum = req.script_pubkey.minimal_non_dust().to_sat() <= req.amount;
 
         let tx_vsize = BASE_WITHDRAWAL_TX_VSIZE + req.vsize() as f64;
-        let is_fee_valid =
-            req.max_fee >= compute_transaction_fee(tx_vsize, self.fee_rate, self.last_fees);
-
-        if is_within_rolling_limits && is_fee_valid && is_within_cap && is_above_minimum {
-            *withdrawal_amounts = new_cumulative_total;
-            Some(RequestRef::Withdrawal(req))
+        let minimum_fee = compute_transaction_fee(tx_vsize, self.fee_rate, self.last_fees)
+            .saturating_sub(self.fee_subsidy);
+        let is_fee_valid = req.max_fee >= minimum_fee;
+
+        if !is_fee_valid {
+            Err(BlockingReason::FeeTooLow)
+        } else if !is_above_minimum {
+            Err(BlockingReason::BelowDustLimit)
+        } else if !is_within_cap {
+            Err(BlockingReason::AbovePerWithdrawalCap)
+        } else if !is_within_rolling_limits {
+            Err(BlockingReason::AboveRollingWithdrawalCap)
         } else {
-            None
+            *withdrawal_amounts = new_cumulative_total;
+            Ok(RequestRef::Withdrawal(req))
         }
     }
 
@@ -235,7 +273,7 @@ impl<'a> RequestPreprocessor<'a> {
         deposits
             .iter()
             .scan(Amount::from_sat(0), |amount_to_mint, deposit| {
-                Some(self.validate_deposit_amount(amount_to_mint, deposit))
+                Some(self.validate_deposit_amount(amount_to_mint, deposit).ok())
             })
             .flatten()
             .collect()
@@ -257,7 +295,10 @@ impl<'a> RequestPreprocessor<'a> {
         reqs.iter()
             .filter_map(RequestRef::as_withdrawal)
             .scan(withdrawn_total, |withdrawal_amounts, req| {
-                Some(self.validate_withdrawal_amounts(withdrawal_amounts, req))
+                Some(
+                    self.validate_withdrawal_amounts(withdrawal_amounts, req)
+                        .ok(),
+                )
             })
             .flatten()
             .collect()
@@ -280,6 +321,25 @@ pub struct SignerBtcState {
     /// Two byte prefix for BTC transactions that are related to the Stacks
     /// blockchain.
     pub magic_bytes: [u8; 2],
+    /// The lock time of the transactions. This is set to the height of
+    /// the bitcoin chain tip to discourage fee sniping, or to zero when
+    /// the `sweep_lock_time` setting is off.
+    pub lock_time: LockTime,
+}
+
+/// Return the lock time of sweep transactions that are constructed on top
+/// of a bitcoin chain tip with the given height.
+///
+/// Following bitcoin-core's wallet, the lock time is the height of the
+/// chain tip, so the transaction can only be included in the next block
+/// or a later one. A miner that reorgs the chain tip cannot take the fees
+/// of the transaction in the replacement block, which removes an
+/// incentive to do so.
+pub fn sweep_lock_time(chain_tip_height: model::BitcoinBlockHeight) -> Result<LockTime, Error> {
+    u32::try_from(*chain_tip_height)
+        .ok()
+        .and_then(|height| LockTime::from_height(height).ok())
+        .ok_or(Error::TypeConversion)
 }
 
 /// The set of sBTC requests with additional relevant
@@ -305,6 +365,21 @@ pub struct SbtcRequests {
     /// that there is enough time for the signers to sign all the inputs
     /// during the tenure of a single bitcoin block.
     pub max_deposits_per_bitcoin_tx: u16,
+    /// The maximum virtual size of each transaction in the package. When
+    /// the requests do not fit in a single transaction of this size they
+    /// are split across a chain of transactions, up to the mempool limits
+    /// on transaction packages. Values above
+    /// [`MAX_STANDARD_TX_VSIZE`](crate::MAX_STANDARD_TX_VSIZE) are
+    /// treated as that limit.
+    pub max_tx_vsize: u64,
+    /// Unspent donation UTXOs locked by the signers' aggregate key. These
+    /// are only spent when the max fees of some requests are too low for
+    /// the current fee rates, in which case they top up the fees of the
+    /// first transaction in the package.
+    pub donations: Vec<SignerUtxo>,
+    /// Whether withdrawal requests that pay the same recipient are
+    /// serviced by a single output of the sweep transaction.
+    pub aggregate_withdrawal_outputs: bool,
 }
 
 impl SbtcRequests {
@@ -319,43 +394,200 @@ impl SbtcRequests {
             return Ok(Vec::new());
         }
 
-        let request_preprocessor = RequestPreprocessor {
-            sbtc_limits: &self.sbtc_limits,
-            fee_rate: self.signer_state.fee_rate,
-            last_fees: self.signer_state.last_fees,
-        };
+        let request_preprocessor = RequestPreprocessor::new(
+            &self.sbtc_limits,
+            self.signer_state.fee_rate,
+            self.signer_state.last_fees,
+        );
         let deposits = request_preprocessor.filter_deposits(&self.deposits);
         let withdrawals = request_preprocessor.preprocess_withdrawals(&self.withdrawals);
 
         // Create a list of requests where each request can be approved on its own.
-        let items = deposits.into_iter().chain(withdrawals);
+        let items: Vec<_> = deposits.into_iter().chain(withdrawals).collect();
+
+        // Requests whose max fees are too low for the current fee rates
+        // are serviced in a transaction of their own at the front of the
+        // package, with the donations topping up its fees.
+        let subsidized_tx = self.construct_subsidized_transaction(&items);
+        let mut signer_state = self.signer_state;
+        if let Some(tx) = subsidized_tx.as_ref() {
+            signer_state.utxo = tx.new_signer_utxo();
+            signer_state.last_fees = None;
+        }
 
         let max_votes_against = self.reject_capacity();
         let max_needs_signature = self.max_deposits_per_bitcoin_tx;
-        compute_optimal_packages(items, max_votes_against, max_needs_signature)
-            .scan(self.signer_state, |state, request_refs| {
-                let requests = Requests::new(request_refs);
-                let tx = UnsignedTransaction::new(requests, state);
-                if let Ok(tx_ref) = tx.as_ref() {
-                    state.utxo = tx_ref.new_signer_utxo();
-                    // The first transaction is the only one whose input
-                    // UTXOs that have all been confirmed. Moreover, the
-                    // fees that it sets aside are enough to make up for
-                    // the remaining transactions in the transaction package.
-                    // With that in mind, we do not need to bump their fees
-                    // anymore in order for them to be accepted by the
-                    // network.
-                    state.last_fees = None;
-                }
-                Some(tx)
-            })
+        let aggregate = self.aggregate_withdrawal_outputs;
+        let packages = compute_optimal_packages(
+            items,
+            max_votes_against,
+            max_needs_signature,
+            self.max_tx_vsize,
+            aggregate,
+        );
+        let transactions = packages.scan(signer_state, |state, request_refs| {
+            let requests = Requests::new(request_refs).with_aggregated_withdrawals(aggregate);
+            let tx = UnsignedTransaction::new(requests, state);
+            if let Ok(tx_ref) = tx.as_ref() {
+                state.utxo = tx_ref.new_signer_utxo();
+                // The first transaction is the only one whose input
+                // UTXOs that have all been confirmed. Moreover, the
+                // fees that it sets aside are enough to make up for
+                // the remaining transactions in the transaction package.
+                // With that in mind, we do not need to bump their fees
+                // anymore in order for them to be accepted by the
+                // network.
+                state.last_fees = None;
+            }
+            Some(tx)
+        });
+
+        // The packager keeps the requests that it packages under the
+        // mempool limits, but it does not know about the subsidized
+        // transaction, so we enforce the size limit here as well.
+        let mut package_vsize = 0;
+        subsidized_tx
+            .map(Ok)
+            .into_iter()
+            .chain(transactions)
             .take(MAX_MEMPOOL_PACKAGE_TX_COUNT as usize)
+            .take_while(|tx| {
+                package_vsize += tx.as_ref().map_or(0, |tx| u64::from(tx.tx_vsize));
+                package_vsize <= MAX_MEMPOOL_PACKAGE_SIZE
+            })
             .collect()
     }
 
-    fn reject_capacity(&self) -> u32 {
+    pub(super) fn reject_capacity(&self) -> u32 {
         self.num_signers.saturating_sub(self.accept_threshold) as u32
     }
+
+    /// Return the requests that are blocked because their max fees are
+    /// too low for the current fee rates, but that would not be if the
+    /// donations paid for part of the fees.
+    ///
+    /// The amounts of the `accepted` requests count toward the sBTC
+    /// limits.
+    fn subsidized_requests(&self, accepted: &[RequestRef<'_>]) -> Vec<RequestRef<'_>> {
+        let fee_subsidy = self
+            .donations
+            .iter()
+            .map(|donation| donation.amount)
+            .fold(0u64, u64::saturating_add);
+        if fee_subsidy == 0 {
+            return Vec::new();
+        }
+
+        let preprocessor = RequestPreprocessor::new(
+            &self.sbtc_limits,
+            self.signer_state.fee_rate,
+            self.signer_state.last_fees,
+        )
+        .with_fee_subsidy(fee_subsidy);
+
+        let mut amount_to_mint = accepted
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| Amount::from_sat(req.amount))
+            .sum::<Amount>();
+        let mut withdrawal_amounts = accepted
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(|req| req.amount)
+            .fold(
+                self.sbtc_limits.rolling_withdrawal_limits().withdrawn_total,
+                u64::saturating_add,
+            );
+
+        let deposits = self
+            .deposits
+            .iter()
+            .filter(|req| !accepted.contains(&RequestRef::Deposit(req)))
+            .filter_map(|req| {
+                preprocessor
+                    .validate_deposit_amount(&mut amount_to_mint, req)
+                    .ok()
+            });
+
+        let mut withdrawals: Vec<_> = self
+            .withdrawals
+            .iter()
+            .map(RequestRef::Withdrawal)
+            .filter(|req| !accepted.contains(req))
+            .collect();
+        withdrawals.sort();
+        let withdrawals = withdrawals
+            .into_iter()
+            .filter_map(|req| req.as_withdrawal())
+            .filter_map(|req| {
+                preprocessor
+                    .validate_withdrawal_amounts(&mut withdrawal_amounts, req)
+                    .ok()
+            });
+
+        deposits.chain(withdrawals).collect()
+    }
+
+    /// Construct a transaction for the requests that can only be serviced
+    /// with the help of the donations, spending as few of the donations
+    /// as necessary for the fees assessed to each request to be within
+    /// its max fee.
+    ///
+    /// `None` is returned if there are no such requests, or if the
+    /// donations are not enough to service them.
+    fn construct_subsidized_transaction(
+        &self,
+        accepted: &[RequestRef<'_>],
+    ) -> Option<UnsignedTransaction<'_>> {
+        let subsidized = self.subsidized_requests(accepted);
+        if subsidized.is_empty() {
+            return None;
+        }
+
+        // The packager does not know about the donation inputs, so we set
+        // aside room for as many of them as we may spend. Donation inputs
+        // are key-spend inputs, which have the minimum input vsize.
+        let max_tx_vsize = self.max_tx_vsize.min(MAX_STANDARD_TX_VSIZE);
+        let num_donations = self.donations.len().min(MAX_DONATION_INPUTS_PER_SWEEP) as u64;
+        let max_bag_tx_vsize =
+            max_tx_vsize.saturating_sub(num_donations.saturating_mul(MIN_BITCOIN_INPUT_VSIZE));
+
+        let max_votes_against = self.reject_capacity();
+        let max_needs_signature = self.max_deposits_per_bitcoin_tx;
+        let aggregate = self.aggregate_withdrawal_outputs;
+        let request_refs = compute_optimal_packages(
+            subsidized,
+            max_votes_against,
+            max_needs_signature,
+            max_bag_tx_vsize,
+            aggregate,
+        )
+        .next()?;
+
+        // The donations are spent largest first, so that we spend as few
+        // of them as possible.
+        let mut donations = self.donations.clone();
+        donations.sort_by_key(|donation| std::cmp::Reverse(donation.amount));
+
+        let tx = (1..=donations.len().min(MAX_DONATION_INPUTS_PER_SWEEP)).find_map(|count| {
+            let requests =
+                Requests::new(request_refs.clone()).with_aggregated_withdrawals(aggregate);
+            let donations = donations[..count].to_vec();
+            UnsignedTransaction::new_with_donations(requests, &self.signer_state, donations)
+                .ok()
+                .filter(UnsignedTransaction::is_within_max_fees)
+                .filter(|tx| u64::from(tx.tx_vsize) <= max_tx_vsize)
+        });
+
+        if tx.is_none() {
+            tracing::warn!(
+                num_requests = request_refs.len(),
+                num_donations = donations.len(),
+                "the donations are not enough to top up the fees of the requests"
+            );
+        }
+        tx
+    }
 }
 
 /// Calculate the total fee necessary for a transaction of the given size
@@ -399,6 +631,58 @@ fn compute_transaction_fee(tx_vsize: f64, fee_rate: f64, last_fees: Option<Fees>
     }
 }
 
+/// Sum the given amounts, returning an error on overflow.
+fn try_sum<I>(amounts: I) -> Result<Amount, Error>
+where
+    I: IntoIterator<Item = Amount>,
+{
+    amounts.into_iter().try_fold(Amount::ZERO, |total, amount| {
+        total.checked_add(amount).ok_or(Error::AmountOverflow {
+            lhs: total.to_sat(),
+            rhs: amount.to_sat(),
+        })
+    })
+}
+
+/// Ceilings on the fee of a sweep transaction, used as a final backstop
+/// against absurd fees. They are checked independently of the fee rate
+/// estimate, so that a faulty estimate, or a coordinator bumping fees
+/// without bound, cannot have the signers burn the funds that they are
+/// sweeping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SweepFeeLimits {
+    /// The maximum fee, in sats, of any sweep transaction.
+    pub max_fee_sats: u64,
+    /// The maximum fee of a sweep transaction, as a fraction of the
+    /// amount that it sweeps, in basis points.
+    pub max_fee_fraction_bps: u16,
+}
+
+impl SweepFeeLimits {
+    /// The maximum fee, in sats, of a sweep transaction that sweeps the
+    /// given amount.
+    pub fn max_fee(&self, swept_amount: u64) -> u64 {
+        let fraction = u128::from(swept_amount) * u128::from(self.max_fee_fraction_bps) / 10_000;
+        u64::try_from(fraction)
+            .unwrap_or(u64::MAX)
+            .min(self.max_fee_sats)
+    }
+
+    /// Check that the fee of the given transaction is not absurd.
+    pub fn check(&self, tx: &UnsignedTransaction) -> Result<(), Error> {
+        let swept_amount = tx.swept_amount();
+        let max_fee = self.max_fee(swept_amount);
+        if tx.tx_fee > max_fee {
+            return Err(Error::AbsurdSweepFee {
+                fee: tx.tx_fee,
+                swept_amount,
+                max_fee,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// An accepted or pending deposit request.
 ///
 /// Deposit requests are assumed to happen via taproot BTC spend where the
@@ -448,8 +732,7 @@ impl DepositRequest {
 
     /// Construct the deposit UTXO associated with this deposit request.
     fn as_tx_out(&self) -> TxOut {
-        let ver = LeafVersion::TapScript;
-        let merkle_root = self.construct_taproot_info(ver).merkle_root();
+        let merkle_root = self.construct_taproot_info().merkle_root();
         let internal_key = *sbtc::UNSPENDABLE_TAPROOT_KEY;
 
         TxOut {
@@ -476,7 +759,7 @@ impl DepositRequest {
     /// public key.
     pub fn construct_witness_data(&self, signature: Signature) -> Witness {
         let ver = LeafVersion::TapScript;
-        let taproot = self.construct_taproot_info(ver);
+        let taproot = self.construct_taproot_info();
 
         // TaprootSpendInfo::control_block returns None if the key given,
         // (script, version), is not in the tree. But this key is definitely
@@ -496,20 +779,16 @@ impl DepositRequest {
 
     /// Constructs the taproot spending information for the UTXO associated
     /// with this deposit request.
-    fn construct_taproot_info(&self, ver: LeafVersion) -> TaprootSpendInfo {
-        // For such a simple tree, we construct it by hand.
-        let leaf1 = NodeInfo::new_leaf_with_ver(self.deposit_script.clone(), ver);
-        let leaf2 = NodeInfo::new_leaf_with_ver(self.reclaim_script.clone(), ver);
-
-        // A Result::Err is returned by NodeInfo::combine if the depth of
-        // our taproot tree exceeds the maximum depth of taproot trees,
-        // which is 128. We have two nodes so the depth is 1 so this will
-        // never panic.
-        let node =
-            NodeInfo::combine(leaf1, leaf2).expect("This tree depth greater than max of 128");
-        let internal_key = *sbtc::UNSPENDABLE_TAPROOT_KEY;
-
-        TaprootSpendInfo::from_node_info(SECP256K1, internal_key, node)
+    ///
+    /// This is needed every time we estimate the size of a transaction
+    /// sweeping in the deposit or compute its sighashes, so the result is
+    /// cached.
+    fn construct_taproot_info(&self) -> Arc<TaprootSpendInfo> {
+        DEPOSIT_SCRIPT_CACHE.taproot_spend_info(
+            self.outpoint,
+            &self.deposit_script,
+            &self.reclaim_script,
+        )
     }
 
     /// Try convert from a model::DepositRequest with some additional info.
@@ -677,6 +956,9 @@ impl Weighted for RequestRef<'_> {
 pub struct Requests<'a> {
     /// A sorted list of requests.
     request_refs: Vec<RequestRef<'a>>,
+    /// Whether withdrawal requests that pay the same recipient share a
+    /// single output.
+    aggregate_withdrawals: bool,
 }
 
 impl<'a> std::ops::Deref for Requests<'a> {
@@ -693,7 +975,28 @@ impl<'a> Requests<'a> {
         // We sort them so that we are guaranteed to create the same
         // bitcoin transaction with the same input requests.
         request_refs.sort();
-        Self { request_refs }
+        Self {
+            request_refs,
+            aggregate_withdrawals: false,
+        }
+    }
+
+    /// Set whether withdrawal requests that pay the same recipient share
+    /// a single output.
+    pub fn with_aggregated_withdrawals(mut self, aggregate: bool) -> Self {
+        self.aggregate_withdrawals = aggregate;
+        self
+    }
+
+    /// Return how the withdrawal requests map onto the withdrawal outputs
+    /// of the transaction.
+    pub fn withdrawal_output_map(&self) -> WithdrawalOutputMap {
+        let recipients = self
+            .request_refs
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(|req| &req.script_pubkey);
+        WithdrawalOutputMap::new(recipients, self.aggregate_withdrawals)
     }
 
     /// Return an iterator for the transaction inputs for the deposit
@@ -706,11 +1009,143 @@ impl<'a> Requests<'a> {
     }
 
     /// Return an iterator for the transaction outputs for the withdrawal
-    /// requests.
+    /// requests. Withdrawal requests that share an output are paid the
+    /// sum of their amounts.
     pub fn tx_outs(&'a self) -> impl Iterator<Item = TxOut> + 'a {
-        self.request_refs
+        let output_map = self.withdrawal_output_map();
+        let withdrawals = self
+            .request_refs
+            .iter()
+            .filter_map(RequestRef::as_withdrawal);
+
+        let mut outputs: Vec<TxOut> = Vec::new();
+        for (withdrawal, position) in withdrawals.zip(output_map.positions) {
+            match outputs.get_mut(position) {
+                Some(output) => output.value += Amount::from_sat(withdrawal.amount),
+                None => outputs.push(withdrawal.as_tx_output()),
+            }
+        }
+        outputs.into_iter()
+    }
+}
+
+/// How the withdrawal requests serviced by a sweep transaction map onto
+/// its withdrawal outputs.
+///
+/// Without aggregation each withdrawal request has an output of its own.
+/// With aggregation, withdrawal requests that pay the same recipient
+/// share the output of the first of them, so the outputs are in the
+/// order that their recipients first appear in the withdrawal requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalOutputMap {
+    /// The position, among the withdrawal outputs, of the output paying
+    /// each withdrawal request, in the order of the withdrawal requests.
+    positions: Vec<usize>,
+}
+
+impl WithdrawalOutputMap {
+    /// Map withdrawal requests paying the given recipients onto the
+    /// withdrawal outputs.
+    pub fn new<'b, I>(recipients: I, aggregate: bool) -> Self
+    where
+        I: IntoIterator<Item = &'b ScriptPubKey>,
+    {
+        let mut outputs: Vec<&ScriptPubKey> = Vec::new();
+        let positions = recipients
+            .into_iter()
+            .map(|recipient| {
+                let shared = outputs.iter().position(|output| *output == recipient);
+                match shared.filter(|_| aggregate) {
+                    Some(position) => position,
+                    None => {
+                        outputs.push(recipient);
+                        outputs.len() - 1
+                    }
+                }
+            })
+            .collect();
+
+        Self { positions }
+    }
+
+    /// The output index of the output paying the withdrawal request at
+    /// the given `index` among the withdrawal requests.
+    pub fn vout(&self, index: usize) -> Option<usize> {
+        // The withdrawal outputs come after the signers' two outputs.
+        self.positions.get(index).map(|position| position + 2)
+    }
+
+    /// The number of withdrawal requests paid by the same output as the
+    /// withdrawal request at the given `index`, itself included.
+    pub fn shares(&self, index: usize) -> usize {
+        let Some(position) = self.positions.get(index) else {
+            return 0;
+        };
+        self.positions.iter().filter(|p| *p == position).count()
+    }
+
+    /// The number of withdrawal outputs.
+    pub fn num_outputs(&self) -> usize {
+        self.positions
             .iter()
-            .filter_map(|req| Some(req.as_withdrawal()?.as_tx_output()))
+            .max()
+            .map_or(0, |position| position + 1)
+    }
+
+    /// Whether any output pays more than one withdrawal request.
+    pub fn is_aggregated(&self) -> bool {
+        self.num_outputs() < self.positions.len()
+    }
+
+    /// Encode the map for the OP_RETURN output of a sweep transaction, as
+    /// the number of withdrawal requests followed by the position of the
+    /// output paying each of them, one byte each.
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        std::iter::once(self.positions.len())
+            .chain(self.positions.iter().copied())
+            .map(u8::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|_| Error::OpReturnSizeLimitExceeded {
+                size: OP_RETURN_HEADER_SIZE + 1 + self.positions.len(),
+                max_size: OP_RETURN_MAX_SIZE,
+            })
+    }
+
+    /// Decode a map that was encoded with [`WithdrawalOutputMap::encode`]
+    /// from the front of the given bytes, returning the map along with the
+    /// remaining bytes.
+    ///
+    /// The map must be the one that [`WithdrawalOutputMap::new`] creates
+    /// for a sweep transaction with `num_outputs` withdrawal outputs, at
+    /// least one of which is shared. That is, every output pays at least
+    /// one withdrawal request, and the outputs are in the order that they
+    /// are first used.
+    fn decode(bytes: &[u8], num_outputs: usize) -> Result<(Self, &[u8]), Error> {
+        let (&count, rest) = bytes
+            .split_first()
+            .ok_or(Error::SbtcTxOpReturnFormatError)?;
+        let count = usize::from(count);
+        if rest.len() < count {
+            return Err(Error::SbtcTxOpReturnFormatError);
+        }
+        let (positions, rest) = rest.split_at(count);
+
+        let mut outputs = 0;
+        for &position in positions {
+            match usize::from(position) {
+                position if position < outputs => {}
+                position if position == outputs => outputs += 1,
+                _ => return Err(Error::SbtcTxMalformed),
+            }
+        }
+
+        let map = Self {
+            positions: positions.iter().copied().map(usize::from).collect(),
+        };
+        if outputs != num_outputs || !map.is_aggregated() {
+            return Err(Error::SbtcTxMalformed);
+        }
+        Ok((map, rest))
     }
 }
 
@@ -785,10 +1220,11 @@ pub struct UnsignedMockTransaction {
 ///
 /// The Bitcoin transaction has the following layout:
 /// 1. The signer input UTXO is the first input.
-/// 2. All other inputs are deposit inputs.
-/// 3. The signer output UTXO is the first output.
-/// 4. The second output is the OP_RETURN data output.
-/// 5. All other outputs are withdrawal outputs.
+/// 2. The next inputs are deposit inputs.
+/// 3. All other inputs, if any, are donation inputs.
+/// 4. The signer output UTXO is the first output.
+/// 5. The second output is the OP_RETURN data output.
+/// 6. All other outputs are withdrawal outputs.
 #[derive(Debug)]
 pub struct UnsignedTransaction<'a> {
     /// The requests used to construct the transaction.
@@ -803,6 +1239,8 @@ pub struct UnsignedTransaction<'a> {
     pub tx_fee: u64,
     /// The total virtual size of the transaction.
     pub tx_vsize: u32,
+    /// The donation UTXOs spent by this transaction to top up its fees.
+    pub donations: Vec<SignerUtxo>,
 }
 
 /// A struct containing Taproot-tagged hashes used for computing taproot
@@ -822,6 +1260,9 @@ pub struct SignatureHashes<'a> {
     /// transaction. This field contains digests/signature hashes that need
     /// Schnorr signatures and the associated deposit request for each hash.
     pub deposits: Vec<(&'a DepositRequest, TapSighash)>,
+    /// The sighashes of the donation UTXOs spent by the transaction, which
+    /// are key-spend only taproot UTXOs like the signers' UTXO.
+    pub donations: Vec<(SignerUtxo, TapSighash)>,
 }
 
 /// A signature hash of a transaction with the associated outpoint.
@@ -856,6 +1297,20 @@ impl SignatureHashes<'_> {
             .collect()
     }
 
+    /// Get the sighashes of the donation inputs.
+    pub fn donation_sighashes(&self) -> Vec<SignatureHash> {
+        self.donations
+            .iter()
+            .map(|(donation, sighash)| SignatureHash {
+                txid: self.txid,
+                outpoint: donation.outpoint,
+                sighash: *sighash,
+                prevout_type: TxPrevoutType::SignersInput,
+                aggregate_key: donation.public_key,
+            })
+            .collect()
+    }
+
     /// Get the signers' sighash
     pub fn signer_sighash(&self) -> SignatureHash {
         SignatureHash {
@@ -957,6 +1412,18 @@ impl UnsignedMockTransaction {
 }
 
 impl<'a> UnsignedTransaction<'a> {
+    /// The total amount, in sats, of the deposits and withdrawals that
+    /// this transaction services.
+    pub fn swept_amount(&self) -> u64 {
+        self.requests
+            .iter()
+            .map(|req| match req {
+                RequestRef::Deposit(deposit) => deposit.amount,
+                RequestRef::Withdrawal(withdrawal) => withdrawal.amount,
+            })
+            .fold(0u64, u64::saturating_add)
+    }
+
     /// Construct an unsigned transaction.
     ///
     /// This function can fail if the output amounts are greater than the
@@ -970,10 +1437,24 @@ impl<'a> UnsignedTransaction<'a> {
     ///   4. Each input needs a signature in the witness data.
     ///   5. There is no witness data for deposit UTXOs.
     pub fn new(requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
+        Self::new_with_donations(requests, state, Vec::new())
+    }
+
+    /// Construct an unsigned transaction that also spends the given
+    /// donation UTXOs to top up its fees.
+    ///
+    /// The donation inputs come after the deposit inputs, and the donated
+    /// amounts go to the signers' output. See [`UnsignedTransaction::new`]
+    /// for the other properties of the returned transaction.
+    pub fn new_with_donations(
+        requests: Requests<'a>,
+        state: &SignerBtcState,
+        donations: Vec<SignerUtxo>,
+    ) -> Result<Self, Error> {
         // Construct a transaction. This transaction's inputs have witness
         // data with dummy signatures so that our virtual size estimates
         // are accurate. Afterward we remove the witness data.
-        let mut unsigned = Self::new_stub(requests, state)?;
+        let mut unsigned = Self::new_stub_with_donations(requests, state, donations)?;
         // Now we can reset the witness data, since this is an unsigned
         // transaction.
         unsigned.reset_witness_data();
@@ -995,13 +1476,26 @@ impl<'a> UnsignedTransaction<'a> {
     ///   5. All witness data is correctly set, except for the fake
     ///      signatures from (4).
     pub fn new_stub(requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
+        Self::new_stub_with_donations(requests, state, Vec::new())
+    }
+
+    /// Construct a transaction with stub witness data that also spends
+    /// the given donation UTXOs to top up its fees.
+    ///
+    /// See [`UnsignedTransaction::new_stub`] for the properties of the
+    /// returned transaction.
+    pub fn new_stub_with_donations(
+        requests: Requests<'a>,
+        state: &SignerBtcState,
+        donations: Vec<SignerUtxo>,
+    ) -> Result<Self, Error> {
         if requests.is_empty() {
             return Err(Error::BitcoinNoRequests);
         }
         // Construct a transaction base. This transaction's inputs have
         // witness data with dummy signatures so that our virtual size
         // estimates are accurate. Later we will update the fees.
-        let mut tx = Self::new_transaction(&requests, state)?;
+        let mut tx = Self::new_transaction(&requests, state, &donations)?;
         // We now compute the total fees for the transaction.
         let tx_vsize: u32 = tx.vsize().try_into().map_err(|_| Error::TypeConversion)?;
 
@@ -1017,6 +1511,7 @@ impl<'a> UnsignedTransaction<'a> {
             signer_utxo: *state,
             tx_fee,
             tx_vsize,
+            donations,
         })
     }
 
@@ -1029,18 +1524,22 @@ impl<'a> UnsignedTransaction<'a> {
     /// upheld. They are
     /// 1. The first input to the Transaction in the `tx` field is the signers'
     ///    UTXO.
-    /// 2. The other inputs to the Transaction in the `tx` field are ordered
+    /// 2. The next inputs to the Transaction in the `tx` field are ordered
     ///    the same order as DepositRequests in the `requests` field.
+    /// 3. The remaining inputs are ordered the same as the donation UTXOs
+    ///    in the `donations` field.
     ///
-    /// Other noteworthy assumptions is that the signers' UTXO is always a
-    /// key-spend path only taproot UTXO.
+    /// Other noteworthy assumptions is that the signers' UTXO, and each
+    /// donation UTXO, is always a key-spend path only taproot UTXO.
     pub fn construct_digests(&self) -> Result<SignatureHashes<'_>, Error> {
         let deposit_requests = self.requests.iter().filter_map(RequestRef::as_deposit);
         let deposit_utxos = deposit_requests.clone().map(DepositRequest::as_tx_out);
+        let donation_utxos = self.donations.iter().map(SignerUtxo::as_tx_output);
         // All the transaction's inputs are used to construct the sighash
         // That is eventually signed
         let input_utxos: Vec<TxOut> = std::iter::once(self.signer_utxo.utxo.as_tx_output())
             .chain(deposit_utxos)
+            .chain(donation_utxos)
             .collect();
 
         let prevouts = Prevouts::All(input_utxos.as_slice());
@@ -1054,7 +1553,7 @@ impl<'a> UnsignedTransaction<'a> {
         // Each deposit UTXO is spendable by using the script path spend
         // of the taproot address. These UTXO inputs are after the sole
         // signer UTXO input.
-        let deposit_sighashes = deposit_requests
+        let deposit_sighashes: Vec<_> = deposit_requests
             .enumerate()
             .map(|(input_index, deposit)| {
                 let index = input_index + 1;
@@ -1067,6 +1566,21 @@ impl<'a> UnsignedTransaction<'a> {
                     .map_err(Error::from)
             })
             .collect::<Result<_, _>>()?;
+        // The donation UTXOs are spent using the taproot key-spend path,
+        // just like the signers' UTXO, and they come after the deposit
+        // inputs.
+        let donation_sighashes = self
+            .donations
+            .iter()
+            .enumerate()
+            .map(|(donation_index, donation)| {
+                let index = donation_index + deposit_sighashes.len() + 1;
+                sighasher
+                    .taproot_key_spend_signature_hash(index, &prevouts, sighash_type)
+                    .map(|sighash| (*donation, sighash))
+                    .map_err(Error::from)
+            })
+            .collect::<Result<_, _>>()?;
 
         // Combine them all together to get an ordered list of taproot
         // signature hashes.
@@ -1076,6 +1590,7 @@ impl<'a> UnsignedTransaction<'a> {
             signers_aggregate_key: self.signer_utxo.utxo.public_key,
             signers: signer_sighash,
             deposits: deposit_sighashes,
+            donations: donation_sighashes,
         })
     }
 
@@ -1085,10 +1600,36 @@ impl<'a> UnsignedTransaction<'a> {
             .iter()
             .filter_map(RequestRef::as_deposit)
             .map(|dep| dep.amount)
+            .chain(self.donations.iter().map(|donation| donation.amount))
             .chain([self.signer_utxo.utxo.amount])
             .sum()
     }
 
+    /// Whether the fees assessed to each request serviced by this
+    /// transaction are within the max fee of the request, and leave
+    /// deposits with a mintable amount above the dust limit.
+    pub fn is_within_max_fees(&self) -> bool {
+        let tx_fee = Amount::from_sat(self.tx_fee);
+        let output_map = self.requests.withdrawal_output_map();
+        let mut withdrawal_indices = 0..;
+
+        self.requests.iter().all(|req| match req {
+            RequestRef::Deposit(deposit) => self
+                .assess_input_fee(&deposit.outpoint, tx_fee)
+                .is_some_and(|fee| {
+                    fee.to_sat() <= deposit.max_fee.min(deposit.amount)
+                        && deposit.amount - fee.to_sat() >= DEPOSIT_DUST_LIMIT
+                }),
+            RequestRef::Withdrawal(withdrawal) => withdrawal_indices
+                .next()
+                .and_then(|index| {
+                    let vout = output_map.vout(index)?;
+                    self.assess_shared_output_fee(vout, output_map.shares(index), tx_fee)
+                })
+                .is_some_and(|fee| fee.to_sat() <= withdrawal.max_fee),
+        })
+    }
+
     /// Compute the sum of the output amounts of the transaction.
     pub fn output_amounts(&self) -> u64 {
         self.tx.output.iter().map(|out| out.value.to_sat()).sum()
@@ -1102,17 +1643,27 @@ impl<'a> UnsignedTransaction<'a> {
     ///
     /// An Err is returned if the amounts withdrawn is greater than the sum
     /// of all the input amounts.
-    fn new_transaction(reqs: &Requests, state: &SignerBtcState) -> Result<Transaction, Error> {
+    fn new_transaction(
+        reqs: &Requests,
+        state: &SignerBtcState,
+        donations: &[SignerUtxo],
+    ) -> Result<Transaction, Error> {
         let signature = *DUMMY_SIGNATURE;
 
         let signer_input = state.utxo.as_tx_input(&signature);
-        let signer_output_sats = Self::compute_signer_amount(reqs, state)?;
+        let donation_inputs = donations
+            .iter()
+            .map(|donation| donation.as_tx_input(&signature));
+        let signer_output_sats = Self::compute_signer_amount(reqs, state, donations)?;
         let signer_output = SignerUtxo::new_tx_output(state.public_key, signer_output_sats);
 
         Ok(Transaction {
             version: Version::TWO,
-            lock_time: LockTime::ZERO,
-            input: std::iter::once(signer_input).chain(reqs.tx_ins()).collect(),
+            lock_time: state.lock_time,
+            input: std::iter::once(signer_input)
+                .chain(reqs.tx_ins())
+                .chain(donation_inputs)
+                .collect(),
             output: std::iter::once(signer_output)
                 .chain(Some(Self::new_op_return_output(reqs, state)?))
                 .chain(reqs.tx_outs())
@@ -1154,13 +1705,30 @@ impl<'a> UnsignedTransaction<'a> {
     /// - encoded IDs: withdrawal request IDs encoded using idpack (variable
     ///   length, if there are withdrawals serviced by the transaction)
     ///
+    /// When some withdrawal output pays more than one withdrawal request,
+    /// the version byte is 2 and the encoded IDs are preceded by the
+    /// encoded [`WithdrawalOutputMap`]:
+    ///
+    /// ```text
+    ///  0       2    3     4            4+n                       X<80
+    ///  |-------|----|-----|-------------|-------------------------|
+    ///    magic   op   n     [positions]   [encoded withdrawal IDs]
+    /// ```
+    ///
     /// ## Returns
     /// - `Some(TxOut)`: the resulting OP_RETURN output
     fn new_op_return_output(reqs: &Requests, state: &SignerBtcState) -> Result<TxOut, Error> {
         // Create OP_RETURN data
         let mut data = PushBytesBuf::with_capacity(OP_RETURN_MAX_SIZE);
         data.extend_from_slice(&state.magic_bytes)?;
-        data.push(OP_RETURN_VERSION)?;
+
+        let output_map = reqs.withdrawal_output_map();
+        if output_map.is_aggregated() {
+            data.push(OP_RETURN_AGGREGATED_VERSION)?;
+            data.extend_from_slice(&output_map.encode()?)?;
+        } else {
+            data.push(OP_RETURN_VERSION)?;
+        }
 
         // Extract all withdrawal request IDs
         let withdrawal_ids: Vec<u64> = reqs.iter().filter_map(|req| req.withdrawal_id()).collect();
@@ -1192,24 +1760,41 @@ impl<'a> UnsignedTransaction<'a> {
     }
 
     /// Compute the final amount for the signers' UTXO given the current
-    /// UTXO amount and the incoming requests.
+    /// UTXO amount, the incoming requests and the donations being spent.
     ///
     /// This amount does not take into account fees.
-    fn compute_signer_amount(reqs: &Requests, state: &SignerBtcState) -> Result<u64, Error> {
-        let amount = reqs
+    fn compute_signer_amount(
+        reqs: &Requests,
+        state: &SignerBtcState,
+        donations: &[SignerUtxo],
+    ) -> Result<u64, Error> {
+        let deposits = reqs.iter().filter_map(|req| match req {
+            RequestRef::Deposit(req) => Some(Amount::from_sat(req.amount)),
+            RequestRef::Withdrawal(_) => None,
+        });
+        let withdrawals = reqs.iter().filter_map(|req| match req {
+            RequestRef::Deposit(_) => None,
+            RequestRef::Withdrawal(req) => Some(Amount::from_sat(req.amount)),
+        });
+
+        let donated = donations
             .iter()
-            .fold(state.utxo.amount as i64, |amount, req| match req {
-                RequestRef::Deposit(req) => amount + req.amount as i64,
-                RequestRef::Withdrawal(req) => amount - req.amount as i64,
-            });
+            .map(|donation| Amount::from_sat(donation.amount));
+
+        let signer_utxo = Amount::from_sat(state.utxo.amount);
+        let inputs = try_sum([signer_utxo, try_sum(deposits)?, try_sum(donated)?])?;
+        let outputs = try_sum(withdrawals)?;
 
         // This should never happen
-        if amount < 0 {
+        let Some(amount) = inputs.checked_sub(outputs) else {
             tracing::error!("withdrawal amounts were greater than the input amounts!");
-            return Err(Error::InvalidAmount(amount));
-        }
+            return Err(Error::AmountUnderflow {
+                lhs: inputs.to_sat(),
+                rhs: outputs.to_sat(),
+            });
+        };
 
-        Ok(amount as u64)
+        Ok(amount.to_sat())
     }
 
     /// Adjust the amounts for each output given the transaction fee.
@@ -1261,6 +1846,15 @@ pub trait BitcoinInputsOutputs {
     fn outputs(&self) -> &[TxOut] {
         &self.tx_ref().output
     }
+
+    /// Returns the amount of the input at the given index if it spends a
+    /// donation to the signers, and `None` otherwise.
+    ///
+    /// The default implementation assumes that there are no donation
+    /// inputs.
+    fn donation_amount(&self, _index: usize) -> Option<Amount> {
+        None
+    }
 }
 
 /// A trait for figuring out the fees assessed to deposit prevouts and
@@ -1279,11 +1873,13 @@ pub trait FeeAssessment: BitcoinInputsOutputs {
     ///
     /// Each input and output is assessed a fee that is proportional to
     /// their weight amount all the requests serviced by this transaction.
+    /// The fees paid for by donation inputs are not assessed to anyone.
     ///
     /// This function assumes that this transaction is an sBTC transaction,
     /// which implies that the first input and the first two outputs are
     /// always the signers'. So `None` is returned if there is no input,
-    /// after the first input, with the given `outpoint`.
+    /// after the first input, with the given `outpoint`, or if that input
+    /// is a donation input.
     ///
     /// The logic for the fee assessment is from
     /// <https://github.com/stacks-network/sbtc/issues/182>.
@@ -1293,17 +1889,20 @@ pub trait FeeAssessment: BitcoinInputsOutputs {
         let request_weight = self.request_weight().to_wu();
         // We skip the first input because that is always the signers'
         // input UTXO.
-        let input_weight = self
+        let (index, tx_in) = self
             .inputs()
             .iter()
+            .enumerate()
             .skip(1)
-            .find(|tx_in| &tx_in.previous_output == outpoint)?
-            .segwit_weight()
-            .to_wu();
+            .find(|(_, tx_in)| &tx_in.previous_output == outpoint)?;
+        if self.donation_amount(index).is_some() {
+            return None;
+        }
+        let input_weight = tx_in.segwit_weight().to_wu();
 
         // This computation follows the logic laid out in
         // <https://github.com/stacks-network/sbtc/issues/182>.
-        let fee_sats = (input_weight * tx_fee.to_sat()).div_ceil(request_weight);
+        let fee_sats = (input_weight * self.request_fee(tx_fee).to_sat()).div_ceil(request_weight);
         Some(Amount::from_sat(fee_sats))
     }
 
@@ -1333,19 +1932,56 @@ pub trait FeeAssessment: BitcoinInputsOutputs {
 
         // This computation follows the logic laid out in
         // <https://github.com/stacks-network/sbtc/issues/182>.
-        let fee_sats = (output_weight * tx_fee.to_sat()).div_ceil(request_weight);
+        let fee_sats = (output_weight * self.request_fee(tx_fee).to_sat()).div_ceil(request_weight);
         Some(Amount::from_sat(fee_sats))
     }
 
+    /// Assess how much of the bitcoin miner fee should be apportioned to
+    /// each of the `shares` withdrawal requests paid by the output at the
+    /// given output index `vout`.
+    ///
+    /// # Notes
+    ///
+    /// The fee assessed to the output is split evenly among the requests,
+    /// rounding up, so together they pay at least the fee of the output.
+    /// `None` is returned if [`FeeAssessment::assess_output_fee`] returns
+    /// `None` or if `shares` is zero.
+    fn assess_shared_output_fee(
+        &self,
+        vout: usize,
+        shares: usize,
+        tx_fee: Amount,
+    ) -> Option<Amount> {
+        let output_fee = self.assess_output_fee(vout, tx_fee)?;
+        let shares = u64::try_from(shares).ok().filter(|shares| *shares > 0)?;
+        Some(Amount::from_sat(output_fee.to_sat().div_ceil(shares)))
+    }
+
+    /// Computes the part of the transaction fee that is apportioned to
+    /// the requests, which is whatever the donation inputs do not cover.
+    fn request_fee(&self, tx_fee: Amount) -> Amount {
+        let donated = (0..self.inputs().len())
+            .filter_map(|index| self.donation_amount(index))
+            .fold(Amount::ZERO, |total, amount| {
+                total.checked_add(amount).unwrap_or(Amount::MAX)
+            });
+        tx_fee.checked_sub(donated).unwrap_or(Amount::ZERO)
+    }
+
     /// Computes the total weight of the inputs and the outputs, excluding
-    /// the ones related to the signers.
+    /// the ones related to the signers, including donation inputs.
     fn request_weight(&self) -> Weight {
         // We skip the first input and first two outputs because those are
         // always the signers' UTXO input and outputs.
-        self.inputs()
+        let request_inputs = self
+            .inputs()
             .iter()
+            .enumerate()
             .skip(1)
-            .map(|x| x.segwit_weight())
+            .filter(|(index, _)| self.donation_amount(*index).is_none())
+            .map(|(_, tx_in)| tx_in.segwit_weight());
+
+        request_inputs
             .chain(self.outputs().iter().skip(2).map(TxOut::weight))
             .sum()
     }
@@ -1363,12 +1999,33 @@ impl BitcoinInputsOutputs for UnsignedTransaction<'_> {
     fn tx_ref(&self) -> &Transaction {
         &self.tx
     }
+
+    fn donation_amount(&self, index: usize) -> Option<Amount> {
+        let outpoint = self.tx.input.get(index)?.previous_output;
+        self.donations
+            .iter()
+            .find(|donation| donation.outpoint == outpoint)
+            .map(|donation| Amount::from_sat(donation.amount))
+    }
 }
 
 impl BitcoinInputsOutputs for BitcoinTxInfo {
     fn tx_ref(&self) -> &Transaction {
         &self.tx
     }
+
+    /// Donation inputs are the inputs, other than the first one, that
+    /// spend an output locked by the same `scriptPubKey` as the signers'
+    /// input. Deposit inputs cannot be such inputs because they are not
+    /// key-spend only outputs.
+    fn donation_amount(&self, index: usize) -> Option<Amount> {
+        if index == 0 {
+            return None;
+        }
+        let signers_prevout = self.prevout(0)?;
+        let prevout = self.prevout(index)?;
+        (prevout.script_pubkey == signers_prevout.script_pubkey).then_some(prevout.amount)
+    }
 }
 
 impl BitcoinTxInfo {
@@ -1382,6 +2039,20 @@ impl BitcoinTxInfo {
     pub fn assess_output_fee(&self, vout: usize) -> Option<Amount> {
         FeeAssessment::assess_output_fee(self, vout, self.fee?)
     }
+    /// Assess how much of the bitcoin miner fee should be apportioned to
+    /// each of the withdrawal requests paid by the output at the given
+    /// output index `vout`.
+    ///
+    /// The withdrawal requests paid by the output are read from the
+    /// OP_RETURN output of the transaction. An output is taken to pay a
+    /// single withdrawal request if the OP_RETURN output does not say
+    /// otherwise.
+    pub fn assess_withdrawal_fee(&self, vout: usize) -> Option<Amount> {
+        let shares = self
+            .withdrawal_request_ids(vout)
+            .map_or(1, |request_ids| request_ids.len().max(1));
+        FeeAssessment::assess_shared_output_fee(self, vout, shares, self.fee?)
+    }
 }
 
 /// An output used as an input into a transaction, a previous output.
@@ -1426,11 +2097,71 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
             .enumerate()
             .filter_map(|(index, _)| match index {
                 0 => self.vin_to_prevout(index, TxPrevoutType::SignersInput),
+                // Donation inputs are locked by the signers' key, and are
+                // spent just like the signers' UTXO.
+                _ if self.spends_signers_output(index, signer_script_pubkeys) => {
+                    self.vin_to_prevout(index, TxPrevoutType::SignersInput)
+                }
                 _ => self.vin_to_prevout(index, TxPrevoutType::Deposit),
             })
             .collect()
     }
 
+    /// Whether the input at the given index spends an output locked by
+    /// one of the `signer_script_pubkeys`.
+    fn spends_signers_output(
+        &self,
+        index: usize,
+        signer_script_pubkeys: &HashSet<ScriptBuf>,
+    ) -> bool {
+        self.prevout(index)
+            .is_some_and(|prevout| signer_script_pubkeys.contains(prevout.script_pubkey))
+    }
+
+    /// Return every input of this transaction for the peg wallet index,
+    /// marking the ones that spend an output locked by one of the
+    /// `signer_script_pubkeys`.
+    fn to_peg_wallet_inputs(
+        &self,
+        signer_script_pubkeys: &HashSet<ScriptBuf>,
+    ) -> Vec<model::PegWalletTxInput> {
+        let txid = BitcoinTxId::from(self.tx_ref().compute_txid());
+        (0..self.inputs().len())
+            .filter_map(|index| {
+                let prevout = self.prevout(index)?;
+                Some(model::PegWalletTxInput {
+                    txid,
+                    input_index: index as u32,
+                    prevout_txid: BitcoinTxId::from(*prevout.txid),
+                    prevout_output_index: prevout.output_index,
+                    amount: prevout.amount.to_sat(),
+                    script_pubkey: prevout.script_pubkey.clone().into(),
+                    signers_script: signer_script_pubkeys.contains(prevout.script_pubkey),
+                })
+            })
+            .collect()
+    }
+
+    /// Return every output of this transaction for the peg wallet index,
+    /// marking the ones locked by one of the `signer_script_pubkeys`.
+    fn to_peg_wallet_outputs(
+        &self,
+        signer_script_pubkeys: &HashSet<ScriptBuf>,
+    ) -> Vec<model::PegWalletTxOutput> {
+        let txid = BitcoinTxId::from(self.tx_ref().compute_txid());
+        self.outputs()
+            .iter()
+            .enumerate()
+            .map(|(index, tx_out)| model::PegWalletTxOutput {
+                txid,
+                output_index: index as u32,
+                amount: tx_out.value.to_sat(),
+                script_pubkey: tx_out.script_pubkey.clone().into(),
+                signers_script: signer_script_pubkeys.contains(&tx_out.script_pubkey),
+            })
+            .collect()
+    }
+
     /// Return all outputs in this transaction that are related to the signers
     /// and any relevant withdrawal output.
     fn to_outputs(
@@ -1465,6 +2196,21 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
                 .collect();
         }
 
+        // An internal transfer has the signers' UTXO as its first output,
+        // and any other outputs are spendable by the signers just like
+        // donations. None of them service withdrawals.
+        if self.is_internal_transfer(signer_script_pubkeys) {
+            return self
+                .outputs()
+                .iter()
+                .enumerate()
+                .filter_map(|(index, _)| match index {
+                    0 => self.vout_to_output(index, TxOutputType::SignersOutput),
+                    _ => self.vout_to_output(index, TxOutputType::Donation),
+                })
+                .collect();
+        }
+
         self.outputs()
             .iter()
             .enumerate()
@@ -1510,63 +2256,41 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
             return Err(Error::SbtcTxMalformed);
         }
 
-        let op_return_instructions: Vec<_> = op_return_output
-            .script_pubkey
-            .as_script()
-            .instructions()
-            .collect();
-
-        // The op return script must be a OP_RETURN and a push bytes
-        let [
-            Ok(Instruction::Op(OP_RETURN)),
-            Ok(Instruction::PushBytes(push_bytes)),
-        ] = op_return_instructions[..]
+        let script = op_return_output.script_pubkey.as_script();
+        let Some(withdrawals) = decode_withdrawal_op_return(script, tx_withdrawals_outputs.len())?
         else {
-            return Err(Error::SbtcTxOpReturnFormatError);
+            return Ok(Vec::new());
         };
 
-        let raw_bytes = push_bytes.as_bytes();
-        if raw_bytes.len() < OP_RETURN_HEADER_SIZE {
-            return Err(Error::SbtcTxOpReturnFormatError);
-        }
-
-        // First two bytes are magic bytes, we don't care about them.
-        // The third one is the version byte.
-        // SAFETY: 2 < OP_RETURN_HEADER_SIZE (3)
-        let version = raw_bytes[2];
+        Ok(withdrawals
+            .into_iter()
+            .map(|(request_id, vout)| WithdrawalTxOutput {
+                txid: op_return_output.txid,
+                output_index: vout as u32,
+                request_id,
+            })
+            .collect())
+    }
 
-        if version == 0 {
-            // In version 0 we didn't store withdrawal ids
+    /// Return the IDs of the withdrawal requests paid by the output at
+    /// the given output index `vout`, according to the OP_RETURN output
+    /// of this transaction.
+    ///
+    /// This function assumes that this transaction is an sBTC transaction,
+    /// so that the second output is the OP_RETURN output and the
+    /// remaining outputs are withdrawal outputs.
+    fn withdrawal_request_ids(&self, vout: usize) -> Result<Vec<u64>, Error> {
+        let Some(op_return) = self.outputs().get(1) else {
             return Ok(Vec::new());
-        } else if version != OP_RETURN_VERSION {
-            // Unknown version byte
-            return Err(Error::SbtcTxOpReturnFormatError);
-        }
-
-        // SAFETY: We've verified raw_bytes.len() >= OP_RETURN_HEADER_SIZE (3),
-        // so starting a slice at index 3 is safe due to slice behavior.
-        // If raw_bytes.len() is exactly 3, this produces an empty slice rather
-        // than panicking.
-        let encoded_withdrawal_ids = &raw_bytes[OP_RETURN_HEADER_SIZE..];
-        let withdrawal_ids: Vec<_> = Segments::decode(encoded_withdrawal_ids)
-            .map_err(Error::IdPackDecode)?
-            .values()
-            .collect();
-
-        // We checked that the first two outputs are signers output and op
-        // return, and that the rest of outputs are withdrawals.
-        if withdrawal_ids.len() != tx_outputs.len() - 2 {
-            return Err(Error::SbtcTxMalformed);
-        }
+        };
+        let num_outputs = self.outputs().len() - 2;
+        let script = op_return.script_pubkey.as_script();
+        let withdrawals = decode_withdrawal_op_return(script, num_outputs)?.unwrap_or_default();
 
-        Ok(tx_withdrawals_outputs
-            .iter()
-            .zip(withdrawal_ids)
-            .map(|(out, request_id)| WithdrawalTxOutput {
-                txid: out.txid,
-                output_index: out.output_index,
-                request_id,
-            })
+        Ok(withdrawals
+            .into_iter()
+            .filter(|(_, output_index)| *output_index == vout)
+            .map(|(request_id, _)| request_id)
             .collect())
     }
 
@@ -1608,6 +2332,93 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
 
         signer_script_pubkeys.contains(signer_input.script_pubkey)
     }
+
+    /// Whether this transaction only moves funds between the signers'
+    /// addresses, like when the signers move their UTXO from the address
+    /// of a previous aggregate key to the one of the current key.
+    ///
+    /// Such a transaction spends only outputs locked by one of the
+    /// `signer_script_pubkeys`, and every one of its outputs is locked by
+    /// one of them as well, so it has no `OP_RETURN` output and services
+    /// no deposits or withdrawals.
+    fn is_internal_transfer(&self, signer_script_pubkeys: &HashSet<ScriptBuf>) -> bool {
+        let outputs = self.outputs();
+        !outputs.is_empty()
+            && (0..self.inputs().len())
+                .all(|index| self.spends_signers_output(index, signer_script_pubkeys))
+            && outputs
+                .iter()
+                .all(|tx_out| signer_script_pubkeys.contains(&tx_out.script_pubkey))
+    }
+}
+
+/// Decode the OP_RETURN output `script` of a sweep transaction with
+/// `num_outputs` withdrawal outputs into the IDs of the withdrawal
+/// requests serviced by the transaction, each paired with the output
+/// index of the output paying it.
+///
+/// `None` is returned for version 0 OP_RETURN outputs, which do not
+/// record the withdrawal request IDs. See
+/// [`UnsignedTransaction::new_op_return_output`] for the wire format.
+fn decode_withdrawal_op_return(
+    script: &bitcoin::Script,
+    num_outputs: usize,
+) -> Result<Option<Vec<(u64, usize)>>, Error> {
+    let op_return_instructions: Vec<_> = script.instructions().collect();
+
+    // The op return script must be a OP_RETURN and a push bytes
+    let [
+        Ok(Instruction::Op(OP_RETURN)),
+        Ok(Instruction::PushBytes(push_bytes)),
+    ] = op_return_instructions[..]
+    else {
+        return Err(Error::SbtcTxOpReturnFormatError);
+    };
+
+    let raw_bytes = push_bytes.as_bytes();
+    if raw_bytes.len() < OP_RETURN_HEADER_SIZE {
+        return Err(Error::SbtcTxOpReturnFormatError);
+    }
+
+    // First two bytes are magic bytes, we don't care about them.
+    // The third one is the version byte.
+    // SAFETY: 2 < OP_RETURN_HEADER_SIZE (3)
+    let version = raw_bytes[2];
+
+    // SAFETY: We've verified raw_bytes.len() >= OP_RETURN_HEADER_SIZE (3),
+    // so starting a slice at index 3 is safe due to slice behavior.
+    // If raw_bytes.len() is exactly 3, this produces an empty slice rather
+    // than panicking.
+    let payload = &raw_bytes[OP_RETURN_HEADER_SIZE..];
+    let (output_map, encoded_withdrawal_ids) = match version {
+        // In version 0 we didn't store withdrawal ids
+        0 => return Ok(None),
+        OP_RETURN_VERSION => (None, payload),
+        OP_RETURN_AGGREGATED_VERSION => {
+            let (output_map, rest) = WithdrawalOutputMap::decode(payload, num_outputs)?;
+            (Some(output_map), rest)
+        }
+        // Unknown version byte
+        _ => return Err(Error::SbtcTxOpReturnFormatError),
+    };
+
+    let withdrawal_ids: Vec<_> = Segments::decode(encoded_withdrawal_ids)
+        .map_err(Error::IdPackDecode)?
+        .values()
+        .collect();
+
+    // Without an output map each withdrawal request has an output of its
+    // own, in the same order as the withdrawal requests.
+    let output_map = output_map.unwrap_or_else(|| WithdrawalOutputMap {
+        positions: (0..withdrawal_ids.len()).collect(),
+    });
+    if withdrawal_ids.len() != output_map.positions.len() || output_map.num_outputs() != num_outputs
+    {
+        return Err(Error::SbtcTxMalformed);
+    }
+
+    let vouts = (0..withdrawal_ids.len()).filter_map(|index| output_map.vout(index));
+    Ok(Some(withdrawal_ids.into_iter().zip(vouts).collect()))
 }
 
 impl TxDeconstructor for BitcoinTxInfo {
@@ -1651,6 +2462,7 @@ mod tests {
 
     use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
     use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+    use crate::MAX_STANDARD_TX_VSIZE;
     use crate::context::RollingWithdrawalLimits;
     use crate::testing;
     use crate::testing::btc::base_signer_transaction;
@@ -1857,6 +2669,54 @@ mod tests {
             .expect_err("signature verification should have failed");
     }
 
+    #[test]
+    fn sweep_transactions_use_the_anti_fee_sniping_lock_time() {
+        let lock_time = sweep_lock_time(850_000u64.into()).unwrap();
+        assert_eq!(lock_time, LockTime::from_height(850_000).unwrap());
+        // Heights that cannot be expressed as a block height lock time are
+        // rejected rather than being interpreted as timestamps.
+        assert!(sweep_lock_time(500_000_000u64.into()).is_err());
+
+        let requests = SbtcRequests {
+            deposits: vec![create_deposit(123456, 30_000, 0)],
+            withdrawals: vec![create_withdrawal(154_321, 40_000, 0)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(550_000_000, 0),
+                    amount: 550_000_000,
+                    public_key: generate_x_only_public_key(),
+                },
+                fee_rate: 5.0,
+                public_key: generate_x_only_public_key(),
+                last_fees: None,
+                magic_bytes: [0; 2],
+                lock_time,
+            },
+            num_signers: 10,
+            accept_threshold: 2,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert!(!transactions.is_empty());
+        for unsigned in transactions {
+            assert_eq!(unsigned.tx.lock_time, lock_time);
+            // The lock time is only enforced if at least one input has a
+            // non-final sequence number.
+            assert!(
+                unsigned
+                    .tx
+                    .input
+                    .iter()
+                    .any(|tx_in| tx_in.sequence.enables_absolute_lock_time())
+            );
+        }
+    }
+
     #[test]
     fn calculate_solo_tx_sizes_for_consts() {
         // For solo deposits
@@ -1873,11 +2733,15 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 2,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
         let keypair = Keypair::new_global(&mut OsRng);
 
@@ -1999,11 +2863,15 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2062,6 +2930,7 @@ mod tests {
             public_key,
             last_fees: None,
             magic_bytes: [0; 2],
+            lock_time: LockTime::ZERO,
         };
 
         let requests = Requests::new(Vec::new());
@@ -2095,11 +2964,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [b'S', b'T'],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // Generate transactions
@@ -2185,6 +3058,125 @@ mod tests {
         }
     }
 
+    #[test_case(false; "separate outputs")]
+    #[test_case(true; "aggregated outputs")]
+    fn withdrawals_to_the_same_recipient_share_an_output(aggregate: bool) {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let recipient = generate_address();
+        let mut withdrawal1 = create_withdrawal(10_000, 10_000, 0).wid(1);
+        let withdrawal2 = create_withdrawal(20_000, 10_000, 0).wid(2);
+        let mut withdrawal3 = create_withdrawal(30_000, 10_000, 0).wid(3);
+        withdrawal1.script_pubkey = recipient.clone();
+        withdrawal3.script_pubkey = recipient.clone();
+
+        let requests = SbtcRequests {
+            deposits: Vec::new(),
+            withdrawals: vec![withdrawal1, withdrawal2, withdrawal3],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(500_000_000, 0),
+                    amount: 500_000_000,
+                    public_key,
+                },
+                fee_rate: 1.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [b'S', b'T'],
+                lock_time: LockTime::ZERO,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: aggregate,
+        };
+
+        let mut transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let unsigned = transactions.pop().unwrap();
+        assert!(unsigned.is_within_max_fees());
+
+        let tx_fee = Amount::from_sat(unsigned.tx_fee);
+        let tx_info = BitcoinTxInfo::from_tx(unsigned.tx.clone(), tx_fee);
+        let script = unsigned.tx.output[1].script_pubkey.as_script();
+        let withdrawals = decode_withdrawal_op_return(script, unsigned.tx.output.len() - 2)
+            .unwrap()
+            .unwrap();
+
+        if !aggregate {
+            assert_eq!(unsigned.tx.output.len(), 5);
+            assert_eq!(withdrawals, [(1, 2), (2, 3), (3, 4)]);
+            assert_eq!(
+                tx_info.assess_withdrawal_fee(2),
+                tx_info.assess_output_fee(2)
+            );
+            return;
+        }
+
+        // The first and third withdrawals share the first withdrawal
+        // output, which pays the sum of their amounts.
+        let outputs = &unsigned.tx.output;
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(outputs[2].script_pubkey, *recipient);
+        assert_eq!(outputs[2].value, Amount::from_sat(40_000));
+        assert_eq!(outputs[3].value, Amount::from_sat(20_000));
+
+        let output_map = unsigned.requests.withdrawal_output_map();
+        assert!(output_map.is_aggregated());
+        assert_eq!(output_map.vout(0), Some(2));
+        assert_eq!(output_map.vout(1), Some(3));
+        assert_eq!(output_map.vout(2), Some(2));
+        assert_eq!(output_map.shares(0), 2);
+        assert_eq!(output_map.shares(1), 1);
+
+        // The OP_RETURN output records which output pays which request.
+        assert_eq!(withdrawals, [(1, 2), (2, 3), (3, 2)]);
+        assert_eq!(tx_info.withdrawal_request_ids(2).unwrap(), [1, 3]);
+        assert_eq!(tx_info.withdrawal_request_ids(3).unwrap(), [2]);
+
+        // The fee of the shared output is split between its requests.
+        let output_fee = tx_info.assess_output_fee(2).unwrap().to_sat();
+        let withdrawal_fee = tx_info.assess_withdrawal_fee(2).unwrap().to_sat();
+        assert_eq!(withdrawal_fee, output_fee.div_ceil(2));
+        assert_eq!(
+            tx_info.assess_withdrawal_fee(3),
+            tx_info.assess_output_fee(3)
+        );
+    }
+
+    #[test_case(&[3, 0, 1, 0], 2; "aggregated")]
+    #[test_case(&[3, 0, 0, 0], 1; "all in one output")]
+    fn valid_withdrawal_output_maps_are_decoded(map: &[u8], num_outputs: usize) {
+        let (output_map, rest) = WithdrawalOutputMap::decode(map, num_outputs).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(output_map.encode().unwrap(), map);
+        assert_eq!(output_map.num_outputs(), num_outputs);
+    }
+
+    #[test_case(&[], 1; "empty")]
+    #[test_case(&[3, 0, 1], 2; "truncated")]
+    #[test_case(&[3, 1, 0, 0], 2; "outputs out of order")]
+    #[test_case(&[3, 0, 2, 0], 3; "output skipped")]
+    #[test_case(&[3, 0, 1, 0], 3; "unused output")]
+    #[test_case(&[2, 0, 1], 2; "nothing aggregated")]
+    fn malformed_withdrawal_output_maps_are_rejected(map: &[u8], num_outputs: usize) {
+        assert!(WithdrawalOutputMap::decode(map, num_outputs).is_err());
+    }
+
+    #[test]
+    fn withdrawal_output_maps_must_cover_every_withdrawal_id() {
+        let ids = BitmapSegmenter.package(&[1, 2]).unwrap().encode();
+        let data = [b'S', b'T', OP_RETURN_AGGREGATED_VERSION, 3, 0, 1, 0]
+            .into_iter()
+            .chain(ids)
+            .collect::<Vec<_>>();
+        let script = ScriptBuf::new_op_return(PushBytesBuf::try_from(data).unwrap());
+
+        assert!(decode_withdrawal_op_return(&script, 2).is_err());
+    }
+
     /// Deposit requests add to the signers' UTXO.
     #[test]
     fn deposits_with_low_amount_and_high_max_fee() {
@@ -2209,11 +3201,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2233,6 +3229,184 @@ mod tests {
         assert_eq!(input_amount, signer_amount + 345678)
     }
 
+    #[test]
+    fn donations_top_up_fees_of_requests_with_low_max_fees() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let deposit = create_deposit(50_000, 100, 0);
+        let donation = SignerUtxo {
+            outpoint: generate_outpoint(100_000, 1),
+            amount: 100_000,
+            public_key,
+        };
+        let mut requests = SbtcRequests {
+            deposits: vec![deposit.clone()],
+            withdrawals: Vec::new(),
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(1_000_000, 0),
+                    amount: 1_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
+            },
+            num_signers: 10,
+            accept_threshold: 0,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
+        };
+
+        // The max fee of the deposit is too low for the fee rate, so
+        // without any donations there is nothing to sweep.
+        let transactions = requests.construct_transactions().unwrap();
+        assert!(transactions.is_empty());
+
+        requests.donations = vec![donation];
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+
+        // The donation is spent after the signers' and the deposit
+        // inputs, and its amount goes to the signers.
+        let unsigned_tx = &transactions[0];
+        assert_eq!(unsigned_tx.donations, vec![donation]);
+        assert_eq!(unsigned_tx.tx.input.len(), 3);
+        assert_eq!(unsigned_tx.tx.input[2].previous_output, donation.outpoint);
+        assert!(unsigned_tx.is_within_max_fees());
+
+        let signer_amount = unsigned_tx.tx.output[0].value.to_sat();
+        assert_eq!(
+            signer_amount,
+            1_000_000 + 100_000 + deposit.amount - unsigned_tx.tx_fee
+        );
+
+        // The donation paid for the entire fee, so the deposit is not
+        // charged anything.
+        let tx_fee = Amount::from_sat(unsigned_tx.tx_fee);
+        let assessed_fee = unsigned_tx.assess_input_fee(&deposit.outpoint, tx_fee);
+        assert_eq!(assessed_fee, Some(Amount::ZERO));
+        assert_eq!(
+            unsigned_tx.assess_input_fee(&donation.outpoint, tx_fee),
+            None
+        );
+
+        let sighashes = unsigned_tx.construct_digests().unwrap();
+        assert_eq!(sighashes.donations.len(), 1);
+    }
+
+    #[test]
+    fn subsidized_transactions_stay_within_the_max_tx_vsize() {
+        const MAX_TX_VSIZE: u64 = 1_000;
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        // The max fees of the withdrawals are too low for the fee rate, so
+        // they can only be serviced with the help of the donations, and
+        // there are more of them than fit in one transaction.
+        let withdrawals = (0..100)
+            .map(|id| create_withdrawal(10_000, 100, 0).wid(id))
+            .collect();
+        let donations = (0..MAX_DONATION_INPUTS_PER_SWEEP as u32)
+            .map(|vout| SignerUtxo {
+                outpoint: generate_outpoint(100_000, vout),
+                amount: 100_000,
+                public_key,
+            })
+            .collect();
+        let requests = SbtcRequests {
+            deposits: Vec::new(),
+            withdrawals,
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(100_000_000, 0),
+                    amount: 100_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
+            },
+            num_signers: 10,
+            accept_threshold: 0,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_TX_VSIZE,
+            donations,
+            aggregate_withdrawal_outputs: false,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let unsigned_tx = &transactions[0];
+        assert!(!unsigned_tx.donations.is_empty());
+        more_asserts::assert_le!(u64::from(unsigned_tx.tx_vsize), MAX_TX_VSIZE);
+    }
+
+    #[test]
+    fn sweep_fee_limits_reject_absurd_fees() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![create_deposit(100_000, 100_000, 0)],
+            withdrawals: vec![create_withdrawal(50_000, 100_000, 0)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::null(),
+                    amount: 1_000_000,
+                    public_key,
+                },
+                fee_rate: 100.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
+            },
+            num_signers: 10,
+            accept_threshold: 0,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
+        };
+
+        let mut transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let unsigned_tx = transactions.pop().unwrap();
+        assert_eq!(unsigned_tx.swept_amount(), 150_000);
+
+        let limits = SweepFeeLimits {
+            max_fee_sats: unsigned_tx.tx_fee,
+            max_fee_fraction_bps: 10_000,
+        };
+        assert!(limits.check(&unsigned_tx).is_ok());
+
+        // The absolute ceiling applies regardless of the amount swept.
+        let limits = SweepFeeLimits {
+            max_fee_sats: unsigned_tx.tx_fee - 1,
+            ..limits
+        };
+        assert!(matches!(
+            limits.check(&unsigned_tx),
+            Err(Error::AbsurdSweepFee { .. })
+        ));
+
+        // And so does the fraction of the amount swept.
+        let limits = SweepFeeLimits {
+            max_fee_sats: u64::MAX,
+            max_fee_fraction_bps: 1,
+        };
+        assert_eq!(limits.max_fee(150_000), 15);
+        assert!(matches!(
+            limits.check(&unsigned_tx),
+            Err(Error::AbsurdSweepFee { max_fee: 15, .. })
+        ));
+    }
+
     /// Deposit requests add to the signers' UTXO.
     #[test]
     fn deposits_increase_signers_utxo_amount() {
@@ -2254,11 +3428,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2305,11 +3483,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2353,11 +3535,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2413,11 +3599,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2513,11 +3703,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2579,11 +3773,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // In the below code, we need to make sure that we take the _first_
@@ -2660,11 +3858,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
         // If multiple_txs is specified, we add a withdrawal that will
         // cause the transaction to be split into two.
@@ -2705,11 +3907,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let transactions = requests.construct_transactions();
@@ -2763,11 +3969,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -3066,11 +4276,15 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             num_signers: 11,
             accept_threshold: 6,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // Let's construct the unsigned transaction and check to see if we
@@ -3112,11 +4326,15 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             accept_threshold: 127,
             num_signers: 128,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -3168,11 +4386,15 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                lock_time: LockTime::ZERO,
             },
             accept_threshold: 10,
             num_signers: 14,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -7,12 +7,20 @@ use bitcoin::Amount;
 use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
 use bitcoin::XOnlyPublicKey;
+use bitcoin::absolute;
 use bitcoin::relative::LockTime;
 
 use crate::DEPOSIT_DUST_LIMIT;
 use crate::DEPOSIT_LOCKTIME_BLOCK_BUFFER;
+use crate::MAX_DONATION_INPUTS_PER_SWEEP;
+use crate::MAX_MEMPOOL_PACKAGE_SIZE;
+use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+use crate::MAX_STANDARD_TX_VSIZE;
+use crate::MAX_SWEEP_LOCK_TIME_AGE;
 use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
+use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::utxo::BitcoinInputsOutputs;
 use crate::bitcoin::utxo::FeeAssessment;
 use crate::bitcoin::utxo::SignerBtcState;
 use crate::context::Context;
@@ -32,11 +40,14 @@ use crate::storage::model::QualifiedRequestId;
 use crate::storage::model::SignerVotes;
 use crate::storage::model::TaprootScriptHash;
 
+use super::cap_reservation;
 use super::utxo::DepositRequest;
 use super::utxo::RequestRef;
 use super::utxo::Requests;
 use super::utxo::SignatureHash;
+use super::utxo::SignerUtxo;
 use super::utxo::UnsignedTransaction;
+use super::utxo::WithdrawalOutputMap;
 use super::utxo::WithdrawalRequest;
 
 /// Cached validation data to avoid repeated DB queries
@@ -146,10 +157,90 @@ impl BitcoinPreSignRequest {
             return Err(Error::DuplicateRequests);
         }
 
+        // The transactions in the package form a chain, and bitcoin core
+        // does not accept chains of unconfirmed transactions that are any
+        // longer than this.
+        let num_transactions = self.request_package.len();
+        if num_transactions > MAX_MEMPOOL_PACKAGE_TX_COUNT as usize {
+            return Err(Error::PreSignTooManyTransactions(num_transactions));
+        }
+
         if self.fee_rate <= 0.0 {
             return Err(Error::PreSignInvalidFeeRate(self.fee_rate));
         }
 
+        if self.donations.len() > MAX_DONATION_INPUTS_PER_SWEEP {
+            return Err(Error::PreSignTooManyDonations(self.donations.len()));
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the donation UTXOs that the coordinator asks us to spend,
+    /// checking that each one is an unspent donation locked by the
+    /// signers' aggregate key, other than the signers' UTXO, and that
+    /// none of them is spent twice.
+    async fn fetch_donations<D>(
+        &self,
+        db: &D,
+        btc_ctx: &BitcoinTxContext,
+        signer_utxo: &SignerUtxo,
+    ) -> Result<Vec<SignerUtxo>, Error>
+    where
+        D: DbRead,
+    {
+        if self.donations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut available: HashMap<OutPoint, SignerUtxo> = db
+            .get_donation_utxos(&btc_ctx.chain_tip, &btc_ctx.aggregate_key)
+            .await?
+            .into_iter()
+            .filter(|donation| donation.outpoint != signer_utxo.outpoint)
+            .map(|donation| (donation.outpoint, donation))
+            .collect();
+
+        self.donations
+            .iter()
+            .map(|outpoint| {
+                available
+                    .remove(outpoint)
+                    .ok_or(Error::PreSignInvalidDonation(*outpoint))
+            })
+            .collect()
+    }
+
+    /// Check that the lock time of the transactions is a block height at
+    /// or below the height of the bitcoin chain tip, and at most
+    /// [`MAX_SWEEP_LOCK_TIME_AGE`] blocks below it.
+    ///
+    /// A lock time in this window means that the transactions cannot be
+    /// included in a block that replaces the chain tip, so miners have no
+    /// incentive to reorg the chain tip to take their fees. When the
+    /// `sweep_lock_time` setting is off, the lock time must be zero.
+    fn validate_lock_time(
+        &self,
+        chain_tip_height: BitcoinBlockHeight,
+        sweep_lock_time: bool,
+    ) -> Result<(), Error> {
+        let is_valid = match self.lock_time {
+            lock_time if !sweep_lock_time => lock_time == absolute::LockTime::ZERO,
+            absolute::LockTime::Blocks(height) => {
+                let height = u64::from(height.to_consensus_u32());
+                height <= *chain_tip_height
+                    && height.saturating_add(MAX_SWEEP_LOCK_TIME_AGE) >= *chain_tip_height
+            }
+            absolute::LockTime::Seconds(_) => false,
+        };
+
+        if !is_valid {
+            return Err(Error::PreSignInvalidLockTime {
+                lock_time: self.lock_time,
+                chain_tip_height,
+            });
+        }
+
         Ok(())
     }
 
@@ -216,6 +307,57 @@ impl BitcoinPreSignRequest {
         Ok(cache)
     }
 
+    /// Check that each deposit UTXO that we believe to be unspent is still
+    /// in bitcoin-core's UTXO set, marking the ones that are not as
+    /// reclaimed.
+    ///
+    /// A deposit UTXO can only be spent by the signers or by the depositor
+    /// through the reclaim path, and we already know about the signers'
+    /// sweeps. We leave out the mempool here, since our own sweep
+    /// transactions may spend the deposit there when we are asked to sign
+    /// a replacement for them.
+    ///
+    /// Deposits are only marked as reclaimed when the given chain tip is
+    /// bitcoin-core's best block. Otherwise the UTXO may have been spent
+    /// by one of our sweeps in a block that we have not processed yet.
+    async fn mark_reclaimed_deposits<B>(
+        bitcoin_client: &B,
+        chain_tip: &BitcoinBlockHash,
+        cache: &mut ValidationCache<'_>,
+    ) -> Result<(), Error>
+    where
+        B: BitcoinInteract,
+    {
+        let mut is_caught_up = None;
+
+        for (outpoint, (report, _)) in cache.deposit_reports.iter_mut() {
+            if !matches!(report.status, DepositConfirmationStatus::Confirmed(..)) {
+                continue;
+            }
+
+            let txout = bitcoin_client
+                .get_transaction_output(outpoint, false)
+                .await?;
+            if txout.is_some() {
+                continue;
+            }
+
+            if is_caught_up.is_none() {
+                let best_block_hash = bitcoin_client.get_best_block_hash().await?;
+                is_caught_up = Some(&BitcoinBlockHash::from(best_block_hash) == chain_tip);
+            }
+            if is_caught_up == Some(false) {
+                tracing::debug!(%outpoint, "deposit UTXO is spent but we are behind bitcoin-core");
+                continue;
+            }
+
+            tracing::warn!(%outpoint, "deposit UTXO has been spent by a transaction other than a sweep");
+            report.status = DepositConfirmationStatus::Reclaimed;
+        }
+
+        Ok(())
+    }
+
     fn assert_request_amount_limits(
         cache: &ValidationCache<'_>,
         limits: &SbtcLimits,
@@ -277,18 +419,35 @@ impl BitcoinPreSignRequest {
     {
         // Let's do basic validation of the request object itself.
         self.pre_validation()?;
+        let sweep_lock_time = ctx.config().signer.sweep_lock_time;
+        self.validate_lock_time(btc_ctx.chain_tip_height, sweep_lock_time)?;
         let db = ctx.get_storage();
-        let cache = self.fetch_all_reports(&db, btc_ctx).await?;
-
-        // We now check that the withdrawal amounts adhere to the rolling
-        // limits. We check the individual withdrawal caps later.
-        let limits = ctx.state().get_current_limits();
+        let mut cache = self.fetch_all_reports(&db, btc_ctx).await?;
+
+        // Our database does not know about deposits being reclaimed, so we
+        // check with bitcoin-core that the deposits are still unspent
+        // right before we sign for them.
+        let bitcoin_client = ctx.get_bitcoin_client();
+        Self::mark_reclaimed_deposits(&bitcoin_client, &btc_ctx.chain_tip, &mut cache).await?;
+
+        // We now check that the deposit amounts fit under the sBTC supply
+        // cap, less the amounts reserved for deposits that have been
+        // swept but not minted yet, and that the withdrawal amounts
+        // adhere to the rolling limits. We check the individual caps
+        // later.
+        let reserved = cap_reservation::reserved_mintable_amount(ctx, &btc_ctx.chain_tip).await?;
+        let limits = ctx
+            .state()
+            .get_current_limits()
+            .with_reserved_mintable(reserved);
         Self::assert_request_amount_limits(&cache, &limits)?;
 
         let signer_utxo = db
             .get_signer_utxo(&btc_ctx.chain_tip)
             .await?
             .ok_or(Error::MissingSignerUtxo)?;
+        // Only the first transaction in the package spends donations.
+        let mut donations = self.fetch_donations(&db, btc_ctx, &signer_utxo).await?;
 
         let mut signer_state = SignerBtcState {
             fee_rate: self.fee_rate,
@@ -296,17 +455,27 @@ impl BitcoinPreSignRequest {
             public_key: bitcoin::XOnlyPublicKey::from(btc_ctx.aggregate_key),
             last_fees: self.last_fees,
             magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            lock_time: self.lock_time,
         };
         let mut outputs = Vec::new();
+        let mut package_vsize = 0;
 
         for requests in self.request_package.iter() {
-            let (output, new_signer_state) = self
-                .construct_tx_sighashes(ctx, btc_ctx, requests, signer_state, &cache)
+            let donations = std::mem::take(&mut donations);
+            let (output, new_signer_state, tx_vsize) = self
+                .construct_tx_sighashes(ctx, btc_ctx, requests, signer_state, donations, &cache)
                 .await?;
             signer_state = new_signer_state;
+            package_vsize += tx_vsize;
             outputs.push(output);
         }
 
+        // The package is signed and broadcast as a unit, so it must also
+        // fit within the mempool limits as a whole.
+        if package_vsize > MAX_MEMPOOL_PACKAGE_SIZE {
+            return Err(Error::PreSignPackageTooLarge(package_vsize));
+        }
+
         Ok(outputs)
     }
 
@@ -315,15 +484,17 @@ impl BitcoinPreSignRequest {
     ///
     /// This function returns the new signer bitcoin state if we were to
     /// sign and confirmed the bitcoin transaction created using the given
-    /// inputs and outputs.
+    /// inputs and outputs, along with the given donation UTXOs, and the
+    /// virtual size of the signed transaction.
     async fn construct_tx_sighashes<'a, C>(
         &self,
         ctx: &C,
         btc_ctx: &BitcoinTxContext,
         requests: &'a TxRequestIds,
         signer_state: SignerBtcState,
+        donations: Vec<SignerUtxo>,
         cache: &ValidationCache<'a>,
-    ) -> Result<(BitcoinTxValidationData, SignerBtcState), Error>
+    ) -> Result<(BitcoinTxValidationData, SignerBtcState, u64), Error>
     where
         C: Context + Send + Sync,
     {
@@ -354,9 +525,18 @@ impl BitcoinPreSignRequest {
             deposits,
             withdrawals,
             signer_state,
+            donations,
+            aggregate_withdrawal_outputs: ctx.config().signer.aggregate_withdrawal_outputs,
         };
         let mut signer_state = signer_state;
         let tx = reports.create_transaction()?;
+        let tx_vsize = u64::from(tx.tx_vsize);
+        if tx_vsize > MAX_STANDARD_TX_VSIZE {
+            return Err(Error::PreSignTransactionTooLarge(tx_vsize));
+        }
+        // This is a backstop against absurd fees that is independent of
+        // the fee rate checks on the individual requests.
+        ctx.config().signer.sweep_fee_limits().check(&tx)?;
         let sighashes = tx.construct_digests()?;
 
         signer_state.utxo = tx.new_signer_utxo();
@@ -369,6 +549,7 @@ impl BitcoinPreSignRequest {
         signer_state.last_fees = None;
         let out = BitcoinTxValidationData {
             signer_sighash: sighashes.signer_sighash(),
+            donation_sighashes: sighashes.donation_sighashes(),
             deposit_sighashes: sighashes.deposit_sighashes(),
             chain_tip: btc_ctx.chain_tip,
             tx: tx.tx.clone(),
@@ -378,7 +559,7 @@ impl BitcoinPreSignRequest {
             sbtc_limits: ctx.state().get_current_limits(),
         };
 
-        Ok((out, signer_state))
+        Ok((out, signer_state, tx_vsize))
     }
 }
 
@@ -391,6 +572,8 @@ pub struct BitcoinTxValidationData {
     pub signer_sighash: SignatureHash,
     /// The sighash of each of the deposit request prevout
     pub deposit_sighashes: Vec<SignatureHash>,
+    /// The sighash of each of the donation prevouts
+    pub donation_sighashes: Vec<SignatureHash>,
     /// The computed deposits and withdrawals reports.
     pub reports: SbtcReports,
     /// The chain tip at the time that this signer received the sign
@@ -428,12 +611,7 @@ impl BitcoinTxValidationData {
         let is_valid_tx = self.is_valid_tx();
 
         let validation_results = self.reports.deposits.iter().map(|(_, report)| {
-            report.validate(
-                self.chain_tip_height,
-                &self.tx,
-                self.tx_fee,
-                &self.sbtc_limits,
-            )
+            report.validate(self.chain_tip_height, self, self.tx_fee, &self.sbtc_limits)
         });
 
         // just a sanity check
@@ -449,10 +627,17 @@ impl BitcoinTxValidationData {
         // from our database, so we know it is unspent and valid. Later,
         // each of the signer's inputs were created as part of a
         // transaction chain, so each one is unspent and locked by the
-        // signers' "aggregate" private key.
+        // signers' "aggregate" private key. The same goes for the
+        // donation inputs, which we checked against our database.
+        let donation_sighashes = self
+            .donation_sighashes
+            .iter()
+            .map(|sighash| (*sighash, InputValidationResult::Ok));
+
         [(self.signer_sighash, InputValidationResult::Ok)]
             .into_iter()
             .chain(deposit_sighashes)
+            .chain(donation_sighashes)
             .map(|(sighash, validation_result)| BitcoinTxSigHash {
                 txid: sighash.txid.into(),
                 sighash: sighash.sighash.into(),
@@ -474,6 +659,7 @@ impl BitcoinTxValidationData {
         let bitcoin_txid = self.tx.compute_txid().into();
 
         let is_valid_tx = self.is_valid_tx();
+        let output_map = self.reports.withdrawal_output_map();
         // If we ever construct a transaction with more than u32::MAX then
         // we are dealing with a very different Bitcoin and Stacks than we
         // started with, and there are other things that we need to change
@@ -482,21 +668,27 @@ impl BitcoinTxValidationData {
             .withdrawals
             .iter()
             .enumerate()
-            .map(|(output_index, (_, report))| BitcoinWithdrawalOutput {
-                bitcoin_txid,
-                bitcoin_chain_tip: self.chain_tip,
-                output_index: output_index as u32 + 2,
-                request_id: report.id.request_id,
-                stacks_txid: report.id.txid,
-                stacks_block_hash: report.id.block_hash,
-                validation_result: report.validate(
-                    self.chain_tip_height,
-                    output_index + 2,
-                    &self.tx,
-                    self.tx_fee,
-                    &self.sbtc_limits,
-                ),
-                is_valid_tx,
+            .map(|(index, (_, report))| {
+                // The map covers every withdrawal request, so the default
+                // is never used.
+                let output_index = output_map.vout(index).unwrap_or_default();
+                BitcoinWithdrawalOutput {
+                    bitcoin_txid,
+                    bitcoin_chain_tip: self.chain_tip,
+                    output_index: output_index as u32,
+                    request_id: report.id.request_id,
+                    stacks_txid: report.id.txid,
+                    stacks_block_hash: report.id.block_hash,
+                    validation_result: report.validate(
+                        self.chain_tip_height,
+                        output_index,
+                        output_map.shares(index),
+                        self,
+                        self.tx_fee,
+                        &self.sbtc_limits,
+                    ),
+                    is_valid_tx,
+                }
             })
             .collect()
     }
@@ -519,7 +711,7 @@ impl BitcoinTxValidationData {
         }
 
         let chain_tip_height = self.chain_tip_height;
-        let tx = &self.tx;
+        let tx = self;
         let tx_fee = self.tx_fee;
         let sbtc_limits = &self.sbtc_limits;
 
@@ -533,15 +725,25 @@ impl BitcoinTxValidationData {
             )
         });
 
+        let output_map = self.reports.withdrawal_output_map();
         let withdrawal_validation_results =
             self.reports
                 .withdrawals
                 .iter()
                 .enumerate()
                 .all(|(index, (_, report))| {
-                    let output_index = index + 2;
-                    let result =
-                        report.validate(chain_tip_height, output_index, tx, tx_fee, sbtc_limits);
+                    let Some(output_index) = output_map.vout(index) else {
+                        return false;
+                    };
+                    let shares = output_map.shares(index);
+                    let result = report.validate(
+                        chain_tip_height,
+                        output_index,
+                        shares,
+                        tx,
+                        tx_fee,
+                        sbtc_limits,
+                    );
                     result == WithdrawalValidationResult::Ok
                 });
 
@@ -549,6 +751,21 @@ impl BitcoinTxValidationData {
     }
 }
 
+impl BitcoinInputsOutputs for BitcoinTxValidationData {
+    fn tx_ref(&self) -> &bitcoin::Transaction {
+        &self.tx
+    }
+
+    fn donation_amount(&self, index: usize) -> Option<Amount> {
+        let outpoint = self.tx.input.get(index)?.previous_output;
+        self.reports
+            .donations
+            .iter()
+            .find(|donation| donation.outpoint == outpoint)
+            .map(|donation| Amount::from_sat(donation.amount))
+    }
+}
+
 /// The set of sBTC requests with additional relevant
 /// information used to construct the next transaction package.
 #[derive(Debug)]
@@ -560,6 +777,11 @@ pub struct SbtcReports {
     /// Summary of the Signers' UTXO and information necessary for
     /// constructing their next UTXO.
     pub signer_state: SignerBtcState,
+    /// The donation UTXOs spent by the transaction to top up its fees.
+    pub donations: Vec<SignerUtxo>,
+    /// Whether withdrawal requests that pay the same recipient are
+    /// serviced by a single output of the transaction.
+    pub aggregate_withdrawal_outputs: bool,
 }
 
 impl SbtcReports {
@@ -575,15 +797,30 @@ impl SbtcReports {
             .map(|(request, _)| RequestRef::Withdrawal(request));
 
         let state = &self.signer_state;
-        let requests = Requests::new(deposits.chain(withdrawals).collect());
+        let requests = Requests::new(deposits.chain(withdrawals).collect())
+            .with_aggregated_withdrawals(self.aggregate_withdrawal_outputs);
+        let donations = self.donations.clone();
+
+        UnsignedTransaction::new_stub_with_donations(requests, state, donations)
+    }
 
-        UnsignedTransaction::new_stub(requests, state)
+    /// Return how the withdrawal requests map onto the withdrawal outputs
+    /// of the transaction, in the order of the withdrawal requests.
+    pub fn withdrawal_output_map(&self) -> WithdrawalOutputMap {
+        let recipients = self
+            .withdrawals
+            .iter()
+            .map(|(request, _)| &request.script_pubkey);
+        WithdrawalOutputMap::new(recipients, self.aggregate_withdrawal_outputs)
     }
 }
 
 /// The responses for validation of a sweep transaction on bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::IntoStaticStr,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[strum(serialize_all = "kebab-case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum InputValidationResult {
     /// The deposit request passed validation
@@ -611,6 +848,9 @@ pub enum InputValidationResult {
     TxNotOnBestChain,
     /// The deposit UTXO has already been spent.
     DepositUtxoSpent,
+    /// The deposit UTXO has been spent by the depositor through the
+    /// reclaim path.
+    DepositReclaimed,
     /// The DKG shares associated with the aggregate key locking the
     /// deposit spend path of the deposit UTXO has failed verification.
     DkgSharesVerifyFailed,
@@ -646,8 +886,11 @@ impl InputValidationResult {
 
 /// The responses for validation of the outputs of a sweep transaction on
 /// bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::IntoStaticStr,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[strum(serialize_all = "kebab-case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum WithdrawalValidationResult {
     /// The withdrawal request passed validation
@@ -762,6 +1005,11 @@ pub enum DepositConfirmationStatus {
     /// in another bitcoin transaction that has been confirmed on the
     /// canonical bitcoin blockchain.
     Spent(BitcoinTxId),
+    /// The deposit request transaction has been confirmed on the canonical
+    /// bitcoin blockchain, but bitcoin-core reports that the deposit UTXO
+    /// has been spent by a transaction that is not one of our sweeps. This
+    /// means that the depositor has reclaimed their deposit.
+    Reclaimed,
     /// We have a record of the deposit request transaction, and it has not
     /// been confirmed on the canonical bitcoin blockchain.
     ///
@@ -838,6 +1086,11 @@ impl DepositRequestReport {
             DepositConfirmationStatus::Spent(_) => {
                 return InputValidationResult::DepositUtxoSpent;
             }
+            // The depositor spent the deposit UTXO through the reclaim
+            // path, so there is nothing left for us to sweep.
+            DepositConfirmationStatus::Reclaimed => {
+                return InputValidationResult::DepositReclaimed;
+            }
             // The deposit has been confirmed on the canonical bitcoin
             // blockchain and remains unspent by us.
             DepositConfirmationStatus::Confirmed(block_height, _) => block_height,
@@ -979,12 +1232,16 @@ pub struct WithdrawalRequestReport {
 impl WithdrawalRequestReport {
     /// Validate that the withdrawal request is okay given the report.
     ///
+    /// The withdrawal request is paid by the output at `output_index`,
+    /// which pays `shares` withdrawal requests in total.
+    ///
     /// See https://github.com/stacks-network/sbtc/issues/741 for the
     /// validation rules for withdrawal requests.
     pub fn validate<F>(
         &self,
         bitcoin_chain_tip_height: BitcoinBlockHeight,
         output_index: usize,
+        shares: usize,
         tx: &F,
         tx_fee: Amount,
         sbtc_limits: &SbtcLimits,
@@ -1025,7 +1282,7 @@ impl WithdrawalRequestReport {
             return WithdrawalValidationResult::RequestExpired;
         }
 
-        let Some(assessed_fee) = tx.assess_output_fee(output_index, tx_fee) else {
+        let Some(assessed_fee) = tx.assess_shared_output_fee(output_index, shares, tx_fee) else {
             // If we hit this, then there is a programming error somewhere
             return WithdrawalValidationResult::Unknown;
         };
@@ -1069,6 +1326,7 @@ mod tests {
     use crate::storage::model::BitcoinBlockHeight;
     use crate::storage::model::StacksBlockHash;
     use crate::storage::model::StacksTxId;
+    use crate::testing::context::WrappedMockBitcoinInteract;
 
     use super::*;
 
@@ -1121,6 +1379,25 @@ mod tests {
         chain_tip_height: 2u64.into(),
         limits: SbtcLimits::new_per_deposit(0, u64::MAX),
     }; "deposit-spent")]
+    #[test_case(DepositReportErrorMapping {
+        report: DepositRequestReport {
+            status: DepositConfirmationStatus::Reclaimed,
+            can_sign: Some(true),
+            can_accept: Some(true),
+            amount: 100_000_000,
+            max_fee: u64::MAX,
+            lock_time: LockTime::from_height(u16::MAX),
+            outpoint: OutPoint::null(),
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            reclaim_script_hash: Some(TaprootScriptHash::zeros()),
+            signers_public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+            dkg_shares_status: Some(DkgSharesStatus::Verified),
+        },
+        status: InputValidationResult::DepositReclaimed,
+        chain_tip_height: 2u64.into(),
+        limits: SbtcLimits::new_per_deposit(0, u64::MAX),
+    }; "deposit-reclaimed")]
     #[test_case(DepositReportErrorMapping {
         report: DepositRequestReport {
             status: DepositConfirmationStatus::Confirmed(0u64.into(), BitcoinBlockHash::from([0; 32])),
@@ -1716,9 +1993,10 @@ mod tests {
         let chain_tip_height = mapping.chain_tip_height;
         let limits = &mapping.limits;
 
-        let status = mapping
-            .report
-            .validate(chain_tip_height, output_index, &tx, TX_FEE, limits);
+        let status =
+            mapping
+                .report
+                .validate(chain_tip_height, output_index, 1, &tx, TX_FEE, limits);
 
         assert_eq!(status, mapping.status);
     }
@@ -1752,7 +2030,14 @@ mod tests {
         let bitcoin_chain_tip_height = WITHDRAWAL_MIN_CONFIRMATIONS.into();
         let limits = &SbtcLimits::unlimited();
 
-        let status = report.validate(bitcoin_chain_tip_height, output_index, &tx, TX_FEE, limits);
+        let status = report.validate(
+            bitcoin_chain_tip_height,
+            output_index,
+            1,
+            &tx,
+            TX_FEE,
+            limits,
+        );
 
         assert_eq!(status, WithdrawalValidationResult::Unknown);
     }
@@ -1785,6 +2070,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, true; "unique-requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1814,6 +2101,8 @@ mod tests {
             }],
             fee_rate: 0.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "unique-requests-zero-fee-rate")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1843,6 +2132,8 @@ mod tests {
             }],
             fee_rate: -1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "unique-requests-negative-fee-rate")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1872,6 +2163,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "duplicate-deposits-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1901,6 +2194,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "duplicate-withdrawals-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1932,6 +2227,8 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "duplicate-withdrawal-request-ids-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1970,12 +2267,16 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "duplicate-requests-in-different-txs")]
     #[test_case(
         BitcoinPreSignRequest {
             request_package: Vec::new(),
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "empty-package_requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1991,6 +2292,8 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "basically-empty-package_requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -2026,11 +2329,63 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
         }, false; "contains-empty-tx-requests")]
     fn test_pre_validation(requests: BitcoinPreSignRequest, result: bool) {
         assert_eq!(requests.pre_validation().is_ok(), result);
     }
 
+    /// The transactions of a package form a chain, so a package can have
+    /// at most as many transactions as the mempool accepts in a chain.
+    #[test_case(MAX_MEMPOOL_PACKAGE_TX_COUNT as u32, true; "at-mempool-limit")]
+    #[test_case(MAX_MEMPOOL_PACKAGE_TX_COUNT as u32 + 1, false; "above-mempool-limit")]
+    fn test_pre_validation_transaction_count(num_transactions: u32, result: bool) {
+        let request_package = (0..num_transactions)
+            .map(|vout| TxRequestIds {
+                deposits: vec![OutPoint {
+                    txid: Txid::from_byte_array([1; 32]),
+                    vout,
+                }],
+                withdrawals: Vec::new(),
+            })
+            .collect();
+        let requests = BitcoinPreSignRequest {
+            request_package,
+            fee_rate: 1.0,
+            last_fees: None,
+            lock_time: absolute::LockTime::ZERO,
+            donations: Vec::new(),
+        };
+        assert_eq!(requests.pre_validation().is_ok(), result);
+    }
+
+    #[test_case(1000, 1000, true, true; "at-chain-tip")]
+    #[test_case(900, 1000, true, true; "oldest-allowed")]
+    #[test_case(899, 1000, true, false; "too-old")]
+    #[test_case(1001, 1000, true, false; "above-chain-tip")]
+    #[test_case(0, 1000, true, false; "zero")]
+    #[test_case(0, 50, true, true; "zero-near-genesis")]
+    #[test_case(1_700_000_000, 1000, true, false; "timestamp")]
+    #[test_case(0, 1000, false, true; "disabled-zero")]
+    #[test_case(1000, 1000, false, false; "disabled-at-chain-tip")]
+    fn test_validate_lock_time(
+        lock_time: u32,
+        chain_tip_height: u64,
+        sweep_lock_time: bool,
+        result: bool,
+    ) {
+        let request = BitcoinPreSignRequest {
+            request_package: Vec::new(),
+            fee_rate: 1.0,
+            last_fees: None,
+            lock_time: absolute::LockTime::from_consensus(lock_time),
+            donations: Vec::new(),
+        };
+        let status = request.validate_lock_time(chain_tip_height.into(), sweep_lock_time);
+        assert_eq!(status.is_ok(), result);
+    }
+
     fn create_deposit_report(idx: u8, amount: u64) -> (DepositRequestReport, SignerVotes) {
         (
             DepositRequestReport {
@@ -2288,4 +2643,76 @@ mod tests {
             (result, expected) => panic!("Expected {expected:?}, got {result:?}"),
         };
     }
+
+    fn confirmed_deposit_report(outpoint: OutPoint) -> (DepositRequestReport, SignerVotes) {
+        let report = DepositRequestReport {
+            status: DepositConfirmationStatus::Confirmed(
+                0u64.into(),
+                BitcoinBlockHash::from([0; 32]),
+            ),
+            can_sign: Some(true),
+            can_accept: Some(true),
+            amount: 100_000_000,
+            max_fee: u64::MAX,
+            lock_time: LockTime::from_height(u16::MAX),
+            outpoint,
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            reclaim_script_hash: Some(TaprootScriptHash::zeros()),
+            signers_public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+            dkg_shares_status: Some(DkgSharesStatus::Verified),
+        };
+        (report, SignerVotes::from(Vec::new()))
+    }
+
+    /// Check that confirmed deposits whose UTXO is no longer in the UTXO
+    /// set are marked as reclaimed when we are caught up with
+    /// bitcoin-core, and that the others are left alone.
+    #[test_case(true; "caught up")]
+    #[test_case(false; "behind bitcoin-core")]
+    #[tokio::test]
+    async fn spent_deposit_utxos_are_marked_reclaimed(is_caught_up: bool) {
+        let unspent = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let reclaimed = OutPoint::new(Txid::from_byte_array([2; 32]), 0);
+        let chain_tip = BitcoinBlockHash::from([3; 32]);
+        let best_block_hash = if is_caught_up {
+            chain_tip
+        } else {
+            BitcoinBlockHash::from([4; 32])
+        };
+
+        let bitcoin_client = WrappedMockBitcoinInteract::default();
+        bitcoin_client.spend_output(reclaimed);
+        bitcoin_client
+            .lock()
+            .await
+            .expect_get_best_block_hash()
+            .once()
+            .returning(move || Box::pin(async move { Ok(best_block_hash.into()) }));
+
+        let mut cache = ValidationCache::default();
+        cache.deposit_reports = [&unspent, &reclaimed]
+            .into_iter()
+            .map(|outpoint| (outpoint, confirmed_deposit_report(*outpoint)))
+            .collect();
+
+        BitcoinPreSignRequest::mark_reclaimed_deposits(&bitcoin_client, &chain_tip, &mut cache)
+            .await
+            .unwrap();
+
+        let (report, _) = &cache.deposit_reports[&unspent];
+        assert!(matches!(
+            report.status,
+            DepositConfirmationStatus::Confirmed(..)
+        ));
+        let (report, _) = &cache.deposit_reports[&reclaimed];
+        if is_caught_up {
+            assert_eq!(report.status, DepositConfirmationStatus::Reclaimed);
+        } else {
+            assert!(matches!(
+                report.status,
+                DepositConfirmationStatus::Confirmed(..)
+            ));
+        }
+    }
 }
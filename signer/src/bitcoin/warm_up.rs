@@ -0,0 +1,292 @@
+//! # Bitcoin node warm-up
+//!
+//! A bitcoin node that is bootstrapping is not much use to the signer.
+//! While it loads its block index it answers every RPC call with a
+//! warm-up error, during initial block download its chain tip is far
+//! behind the network's, and while its optional indexes, like the
+//! transaction index, are being built it cannot find older transactions.
+//!
+//! The [`BitcoinWarmUpMonitor`] checks the state of the bitcoin node at
+//! startup and then periodically. Until the node is ready, the signer is
+//! in a warm-up mode where it does not process bitcoin blocks, vote on
+//! requests, coordinate or validate, and it logs the progress of the node
+//! instead. The state is reported by the `GET /readyz` endpoint of the
+//! signer API.
+
+use std::time::Duration;
+
+use bitcoincore_rpc::Error as BtcRpcError;
+use bitcoincore_rpc::jsonrpc::error::Error as JsonRpcError;
+use bitcoincore_rpc::jsonrpc::error::RpcError;
+use serde::Serialize;
+
+use crate::bitcoin::BitcoinInteract as _;
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+
+/// The code of the RPC error that bitcoin-core responds with while it is
+/// starting up, `RPC_IN_WARMUP`.
+const RPC_IN_WARMUP: i32 = -28;
+
+/// The state of the bitcoin node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BitcoinNodeState {
+    /// The node is synced with the network and its indexes are built.
+    Ready,
+    /// The node is starting up, say because it is loading its block
+    /// index, and is not answering RPC calls yet.
+    Starting {
+        /// What the node says that it is doing.
+        message: String,
+    },
+    /// The node is in initial block download.
+    InitialBlockDownload {
+        /// The height of the chain tip of the node.
+        blocks: u64,
+        /// The height of the most-work header that the node knows of.
+        headers: u64,
+        /// The estimated fraction of the blockchain that the node has
+        /// verified, between 0 and 1.
+        verification_progress: f64,
+    },
+    /// Some of the optional indexes of the node are still being built.
+    BuildingIndexes {
+        /// The height of the chain tip of the node.
+        blocks: u64,
+        /// The names of the indexes that are being built, along with the
+        /// height of the most recent block in each.
+        indexes: Vec<(String, u64)>,
+    },
+}
+
+impl BitcoinNodeState {
+    /// Whether the node is ready for the signer to use.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready)
+    }
+}
+
+/// Whether the error is the warm-up error that bitcoin-core responds with
+/// while it is starting up.
+pub fn is_warm_up_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::BitcoinCoreRpc(BtcRpcError::JsonRpc(JsonRpcError::Rpc(RpcError {
+            code: RPC_IN_WARMUP,
+            ..
+        })))
+    )
+}
+
+/// Ask the bitcoin node for its state.
+pub async fn check_bitcoin_node<C: Context>(ctx: &C) -> Result<BitcoinNodeState, Error> {
+    let bitcoin_client = ctx.get_bitcoin_client();
+    let info = match bitcoin_client.get_blockchain_info().await {
+        Ok(info) => info,
+        Err(Error::BitcoinCoreRpc(BtcRpcError::JsonRpc(JsonRpcError::Rpc(error))))
+            if error.code == RPC_IN_WARMUP =>
+        {
+            return Ok(BitcoinNodeState::Starting { message: error.message });
+        }
+        Err(error) => return Err(error),
+    };
+
+    if info.initial_block_download {
+        return Ok(BitcoinNodeState::InitialBlockDownload {
+            blocks: info.blocks,
+            headers: info.headers,
+            verification_progress: info.verification_progress,
+        });
+    }
+
+    let indexes: Vec<(String, u64)> = bitcoin_client
+        .get_index_info()
+        .await?
+        .into_iter()
+        .filter(|(_, index)| !index.synced)
+        .map(|(name, index)| (name, index.best_block_height))
+        .collect();
+
+    if !indexes.is_empty() {
+        return Ok(BitcoinNodeState::BuildingIndexes { blocks: info.blocks, indexes });
+    }
+
+    Ok(BitcoinNodeState::Ready)
+}
+
+/// Periodically checks the state of the bitcoin node, and records it in
+/// the signer state.
+pub struct BitcoinWarmUpMonitor<C> {
+    /// Signer context.
+    context: C,
+    /// Check interval.
+    interval: Duration,
+}
+
+impl<C> BitcoinWarmUpMonitor<C>
+where
+    C: Context,
+{
+    /// Creates a new BitcoinWarmUpMonitor with the given context and
+    /// interval.
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self { context, interval }
+    }
+
+    /// Runs the BitcoinWarmUpMonitor, which checks once every interval.
+    /// The startup check is expected to have been done with
+    /// [`BitcoinWarmUpMonitor::check`] before this is called.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.check().await;
+                }
+            }
+        }
+        tracing::info!("bitcoin warm-up monitor has stopped");
+    }
+
+    /// Check the state of the bitcoin node and update the signer state
+    /// with it.
+    ///
+    /// If the check cannot be done, say because the bitcoin node is
+    /// unreachable, then the signer state is left as it is.
+    #[tracing::instrument(skip_all, name = "bitcoin-warm-up")]
+    pub async fn check(&self) {
+        let node_state = match check_bitcoin_node(&self.context).await {
+            Ok(node_state) => node_state,
+            Err(error) => {
+                tracing::warn!(%error, "could not check the state of the bitcoin node");
+                return;
+            }
+        };
+
+        match &node_state {
+            BitcoinNodeState::Ready => {}
+            BitcoinNodeState::Starting { message } => {
+                tracing::info!(%message, "the bitcoin node is starting up; warming up");
+            }
+            BitcoinNodeState::InitialBlockDownload {
+                blocks,
+                headers,
+                verification_progress,
+            } => {
+                let progress_percent = format!("{:.2}", verification_progress * 100.0);
+                tracing::info!(
+                    %blocks,
+                    %headers,
+                    %progress_percent,
+                    "the bitcoin node is in initial block download; warming up"
+                );
+            }
+            BitcoinNodeState::BuildingIndexes { blocks, indexes } => {
+                for (index, best_block_height) in indexes {
+                    tracing::info!(
+                        %index,
+                        %best_block_height,
+                        %blocks,
+                        "the bitcoin node is building an index; warming up"
+                    );
+                }
+            }
+        }
+
+        let state = self.context.state();
+        let was_ready = state.bitcoin_node_state().is_ready();
+        let is_ready = node_state.is_ready();
+        if is_ready && !was_ready {
+            tracing::info!("the bitcoin node is ready; resuming participation");
+        }
+        state.set_bitcoin_node_state(node_state);
+        Metrics::record_bitcoin_node_ready(is_ready);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bitcoincore_rpc_json::GetBlockchainInfoResult;
+
+    use crate::bitcoin::rpc::IndexInfo;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    fn blockchain_info(initial_block_download: bool) -> GetBlockchainInfoResult {
+        let json = include_str!("../../tests/fixtures/bitcoind-getblockchaininfo-data.json");
+        let mut info: GetBlockchainInfoResult = serde_json::from_str(json).unwrap();
+        info.initial_block_download = initial_block_download;
+        info
+    }
+
+    async fn check(
+        info: Result<GetBlockchainInfoResult, Error>,
+        indexes: BTreeMap<String, IndexInfo>,
+    ) -> BitcoinNodeState {
+        let ctx = TestContext::default_mocked();
+        ctx.with_bitcoin_client(|client| {
+            client
+                .expect_get_blockchain_info()
+                .once()
+                .return_once(move || Box::pin(async move { info }));
+            client
+                .expect_get_index_info()
+                .returning(move || Box::pin(std::future::ready(Ok(indexes.clone()))));
+        })
+        .await;
+
+        BitcoinWarmUpMonitor::new(ctx.clone(), Duration::from_secs(1))
+            .check()
+            .await;
+        ctx.state().bitcoin_node_state()
+    }
+
+    #[tokio::test]
+    async fn synced_nodes_are_ready() {
+        let index = IndexInfo {
+            synced: true,
+            best_block_height: 100,
+        };
+        let indexes = BTreeMap::from([("txindex".to_string(), index)]);
+        let node_state = check(Ok(blockchain_info(false)), indexes).await;
+        assert_eq!(node_state, BitcoinNodeState::Ready);
+    }
+
+    #[tokio::test]
+    async fn bootstrapping_nodes_are_not_ready() {
+        let rpc_error = RpcError {
+            code: RPC_IN_WARMUP,
+            message: "Loading block index...".to_string(),
+            data: None,
+        };
+        let error = Error::BitcoinCoreRpc(BtcRpcError::JsonRpc(JsonRpcError::Rpc(rpc_error)));
+        assert!(is_warm_up_error(&error));
+        let node_state = check(Err(error), BTreeMap::new()).await;
+        assert!(matches!(node_state, BitcoinNodeState::Starting { .. }));
+
+        let node_state = check(Ok(blockchain_info(true)), BTreeMap::new()).await;
+        assert!(matches!(
+            node_state,
+            BitcoinNodeState::InitialBlockDownload { .. }
+        ));
+
+        let index = IndexInfo {
+            synced: false,
+            best_block_height: 10,
+        };
+        let indexes = BTreeMap::from([("txindex".to_string(), index)]);
+        let node_state = check(Ok(blockchain_info(false)), indexes).await;
+        let BitcoinNodeState::BuildingIndexes { indexes, .. } = node_state else {
+            panic!("expected the node to be building indexes, got {node_state:?}");
+        };
+        assert_eq!(indexes, vec![("txindex".to_string(), 10)]);
+    }
+}
@@ -7,6 +7,7 @@
 //!
 //! The following information is extracted by the block observer:
 //! - Bitcoin blocks
+//! - Bitcoin block times, and whether the blocks had any sBTC activity
 //! - Stacks blocks
 //! - Deposit requests
 //! - sBTC transactions
@@ -19,21 +20,31 @@
 
 use std::future::Future;
 use std::time::Duration;
+use std::time::Instant;
 
 use crate::bitcoin::BitcoinBlockHashStreamProvider;
 use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::deposit_cache::DEPOSIT_SCRIPT_CACHE;
 use crate::bitcoin::rpc::BitcoinBlockHeader;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::bitcoin::utxo::TxDeconstructor as _;
+use crate::config::EmilyClientConfig;
+use crate::context::BitcoinBlockObserved;
 use crate::context::Context;
+use crate::context::ContextRng;
+use crate::context::P2PEvent;
 use crate::context::SbtcLimits;
+use crate::context::SignerCommand;
 use crate::context::SignerEvent;
+use crate::context::SignerSignal;
+use crate::emily_client::EmilyDepositRequest;
 use crate::emily_client::EmilyInteract as _;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::keys::SignerScriptPubKey as _;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
 use crate::metrics::Metrics;
+use crate::network::sync::BlockSummaryRequest;
 use crate::stacks::api::SignerSetInfo;
 use crate::stacks::api::StacksInteract as _;
 use crate::stacks::api::TenureBlockHeaders;
@@ -43,16 +54,21 @@ use crate::storage::DbWrite;
 use crate::storage::Transactable as _;
 use crate::storage::TransactionHandle as _;
 use crate::storage::model;
+use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::BitcoinBlockRef;
 use crate::storage::model::EncryptedDkgShares;
 use crate::util::FutureExt as _;
 use bitcoin::Amount;
 use bitcoin::BlockHash;
+use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
 use futures::stream::StreamExt as _;
 use sbtc::deposits::CreateDepositRequest;
 use sbtc::deposits::DepositInfo;
+use sbtc::deposits::DepositScriptDecoders;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use tokio::sync::broadcast::error::RecvError;
 
 /// Block observer
 #[derive(Debug)]
@@ -63,6 +79,97 @@ pub struct BlockObserver<Context, BlockSource> {
     pub bitcoin_block_source: BlockSource,
 }
 
+/// The kinds of polls of Emily for deposit requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmilyPoll {
+    /// Fetch the deposit requests that Emily updated since the previous
+    /// poll.
+    Delta,
+    /// Like [`EmilyPoll::Delta`], and also retry the deposit requests of
+    /// earlier polls that were not stored, since their transactions may
+    /// have been confirmed in the new bitcoin block.
+    NewBlock,
+    /// Fetch all deposit requests in Emily.
+    Full,
+}
+
+/// The schedule of the polls of Emily for deposit requests.
+///
+/// Between bitcoin blocks, the block observer polls Emily for the deposit
+/// requests that it updated since the previous poll. The interval between
+/// these polls goes back to the minimum after a poll that finds new
+/// deposit requests, and doubles after each poll that does not, up to the
+/// maximum. Each interval gets some jitter so that the signers do not all
+/// poll Emily at once. Every bitcoin block also triggers a poll, and every
+/// `deposit_full_sync_blocks` bitcoin blocks that poll fetches all of the
+/// deposit requests in Emily.
+#[derive(Debug)]
+struct EmilyDepositPolling {
+    /// The minimum interval between polls.
+    min_interval: Duration,
+    /// The maximum interval between polls.
+    max_interval: Duration,
+    /// The number of bitcoin blocks between full syncs.
+    full_sync_blocks: u16,
+    /// The current interval between polls, without jitter.
+    interval: Duration,
+    /// When the next poll is due.
+    next_poll: Instant,
+    /// The number of bitcoin blocks observed since the last full sync.
+    blocks_since_full_sync: u16,
+    /// The deposit requests from Emily that were not stored, usually
+    /// because their transactions were not confirmed yet. They are retried
+    /// on every bitcoin block until the next full sync.
+    unstored: HashMap<OutPoint, EmilyDepositRequest>,
+}
+
+impl EmilyDepositPolling {
+    /// Create a new schedule with the first poll due at the given time.
+    /// The poll for the first bitcoin block is a full sync.
+    fn new(config: &EmilyClientConfig, now: Instant) -> Self {
+        let full_sync_blocks = config.deposit_full_sync_blocks.get();
+        Self {
+            min_interval: config.deposit_poll_min_interval,
+            max_interval: config.deposit_poll_max_interval,
+            full_sync_blocks,
+            interval: config.deposit_poll_min_interval,
+            next_poll: now,
+            blocks_since_full_sync: full_sync_blocks - 1,
+            unstored: HashMap::new(),
+        }
+    }
+
+    /// Whether a poll between bitcoin blocks is due at the given time.
+    fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_poll
+    }
+
+    /// Record that a new bitcoin block was observed and return the kind of
+    /// poll that it triggers.
+    fn observe_bitcoin_block(&mut self) -> EmilyPoll {
+        self.blocks_since_full_sync = self.blocks_since_full_sync.saturating_add(1);
+        if self.blocks_since_full_sync < self.full_sync_blocks {
+            return EmilyPoll::NewBlock;
+        }
+        // A full sync fetches all the deposit requests again, so there is
+        // no need to keep the ones that were not stored around.
+        self.blocks_since_full_sync = 0;
+        self.unstored.clear();
+        EmilyPoll::Full
+    }
+
+    /// Schedule the next poll after a poll that completed at the given
+    /// time, given whether it found new deposit requests.
+    fn schedule_next(&mut self, now: Instant, found_new: bool, rng: &ContextRng) {
+        self.interval = if found_new {
+            self.min_interval
+        } else {
+            (self.interval * 2).min(self.max_interval)
+        };
+        self.next_poll = now + rng.jitter(self.interval);
+    }
+}
+
 /// A full "deposit", containing the bitcoin transaction and a fully
 /// extracted and verified `scriptPubKey` from one of the transaction's
 /// UTXOs.
@@ -78,7 +185,12 @@ pub struct Deposit {
 }
 
 impl DepositRequestValidator for CreateDepositRequest {
-    async fn validate<C>(&self, client: &C, is_mainnet: bool) -> Result<Option<Deposit>, Error>
+    async fn validate<C>(
+        &self,
+        client: &C,
+        is_mainnet: bool,
+        decoders: &DepositScriptDecoders,
+    ) -> Result<Option<Deposit>, Error>
     where
         C: BitcoinInteract,
     {
@@ -109,7 +221,7 @@ impl DepositRequestValidator for CreateDepositRequest {
         tx_info.validate()?;
 
         Ok(Some(Deposit {
-            info: self.validate_tx(&tx_info.tx, is_mainnet)?,
+            info: DEPOSIT_SCRIPT_CACHE.validate_tx(self, &tx_info.tx, is_mainnet, decoders)?,
             tx_info,
             block_hash,
         }))
@@ -123,11 +235,13 @@ pub trait DepositRequestValidator {
     ///
     /// This function fetches the transaction using the given client and
     /// checks that the transaction has been submitted. The transaction
-    /// need not be confirmed.
+    /// need not be confirmed. The deposit and reclaim scripts must follow
+    /// one of the templates accepted by the given decoders.
     fn validate<C>(
         &self,
         client: &C,
         is_mainnet: bool,
+        decoders: &DepositScriptDecoders,
     ) -> impl Future<Output = Result<Option<Deposit>, Error>>
     where
         C: BitcoinInteract;
@@ -149,6 +263,8 @@ where
     pub async fn run(self) -> Result<(), Error> {
         let term = self.context.get_termination_handle();
         let mut bitcoin_blocks = self.bitcoin_block_source.get_block_hash_stream();
+        let mut emily_polling =
+            EmilyDepositPolling::new(&self.context.config().emily, Instant::now());
 
         loop {
             if term.shutdown_signalled() {
@@ -171,6 +287,26 @@ where
                     )
                     .increment(1);
 
+                    // The blocks that a bootstrapping bitcoin node sends us
+                    // are far behind the chain tip. We skip them, and the
+                    // first block after the node is ready brings in all of
+                    // the blocks that we skipped.
+                    if !self.context.state().bitcoin_node_ready() {
+                        tracing::debug!(%block_hash, "bitcoin node is warming up; skipping block");
+                        continue;
+                    }
+
+                    // Webhooks from stacks-core are not digested while we
+                    // update the chain tip, so that validators never see
+                    // stacks events anchored to bitcoin blocks that we have
+                    // not processed yet.
+                    let tip_update = self
+                        .context
+                        .state()
+                        .chain_event_scheduler()
+                        .bitcoin_tip_update()
+                        .await;
+
                     if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
                         tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
                     }
@@ -192,12 +328,15 @@ where
                             continue;
                         }
                     };
+                    drop(tip_update);
 
-                    tracing::info!("loading latest deposit requests from Emily");
-                    if let Err(error) = self.load_latest_deposit_requests().await {
-                        tracing::warn!(%error, "could not load latest deposit requests from Emily");
-                    }
+                    let poll = emily_polling.observe_bitcoin_block();
+                    tracing::info!(?poll, "loading latest deposit requests from Emily");
+                    self.poll_emily_deposits(&mut emily_polling, poll).await;
 
+                    self.context
+                        .events()
+                        .publish(BitcoinBlockObserved { chain_tip });
                     self.context
                         .signal(SignerEvent::BitcoinBlockObserved(chain_tip).into())?;
                 }
@@ -205,6 +344,15 @@ where
                     tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
                     continue;
                 }
+                // Deposit requests are only validated against a chain tip,
+                // so we only poll Emily once we have one.
+                _ if emily_polling.is_due(Instant::now())
+                    && self.context.state().bitcoin_chain_tip().is_some() =>
+                {
+                    tracing::debug!("polling Emily for updated deposit requests");
+                    self.poll_emily_deposits(&mut emily_polling, EmilyPoll::Delta)
+                        .await;
+                }
                 _ => continue,
             };
         }
@@ -216,16 +364,106 @@ where
 }
 
 impl<C: Context, B> BlockObserver<C, B> {
+    /// Poll Emily for deposit requests and schedule the next poll, which
+    /// comes sooner if this one found new deposit requests.
+    async fn poll_emily_deposits(&self, polling: &mut EmilyDepositPolling, poll: EmilyPoll) {
+        let found_new = match self.load_emily_deposit_requests(polling, poll).await {
+            Ok(new_requests) => new_requests > 0,
+            Err(error) => {
+                tracing::warn!(%error, ?poll, "could not load deposit requests from Emily");
+                false
+            }
+        };
+        polling.schedule_next(Instant::now(), found_new, &self.context.rng());
+    }
+
     /// Fetch deposit requests from Emily and store the ones that pass
     /// validation into the database.
+    ///
+    /// Unless this is a full sync, only the deposit requests that Emily
+    /// updated at or after the stored cursor are fetched. The cursor is
+    /// then advanced to the largest height at which Emily updated one of
+    /// the fetched requests. Returns the number of fetched deposit
+    /// requests that Emily updated after the cursor.
     #[tracing::instrument(skip_all)]
-    async fn load_latest_deposit_requests(&self) -> Result<(), Error> {
-        let requests = self.context.get_emily_client().get_deposits().await?;
-        self.load_requests(&requests).await
+    async fn load_emily_deposit_requests(
+        &self,
+        polling: &mut EmilyDepositPolling,
+        poll: EmilyPoll,
+    ) -> Result<usize, Error> {
+        let db = self.context.get_storage_mut();
+        let cursor = db.get_emily_deposit_cursor().await?;
+        let since = match poll {
+            EmilyPoll::Full => 0,
+            EmilyPoll::Delta | EmilyPoll::NewBlock => cursor.unwrap_or_default(),
+        };
+        let deposits = self
+            .context
+            .get_emily_client()
+            .get_deposits_updated_since(since)
+            .await?;
+
+        let mut fetched = match poll {
+            EmilyPoll::NewBlock => polling.unstored.clone(),
+            EmilyPoll::Delta | EmilyPoll::Full => HashMap::new(),
+        };
+        for deposit in &deposits {
+            fetched.insert(deposit.request.outpoint, deposit.clone());
+        }
+        let requests: Vec<_> = fetched
+            .values()
+            .map(|deposit| deposit.request.clone())
+            .collect();
+        self.load_requests(&requests, model::DepositRequestSource::Emily)
+            .await?;
+
+        if poll == EmilyPoll::NewBlock {
+            polling.unstored.clear();
+        }
+        for (outpoint, deposit) in fetched {
+            let txid: model::BitcoinTxId = outpoint.txid.into();
+            let stored = db.get_deposit_request(&txid, outpoint.vout).await?;
+            if stored.is_none() {
+                polling.unstored.insert(outpoint, deposit);
+                continue;
+            }
+            // Keep the correlation ID so that the later stages can tie
+            // their work, and their updates to Emily, back to the request.
+            let Some(correlation_id) = deposit.correlation_id else {
+                continue;
+            };
+            tracing::debug!(
+                %outpoint,
+                %correlation_id,
+                "storing the correlation ID of a deposit request"
+            );
+            let correlation_id = model::DepositCorrelationId {
+                txid,
+                output_index: outpoint.vout,
+                correlation_id,
+            };
+            db.write_deposit_correlation_id(&correlation_id).await?;
+        }
+
+        let new_requests = deposits
+            .iter()
+            .filter(|deposit| cursor.is_none_or(|cursor| deposit.last_update_height > cursor))
+            .count();
+        let max_height = deposits
+            .iter()
+            .map(|deposit| deposit.last_update_height)
+            .max()
+            .filter(|height| cursor.is_none_or(|cursor| *height > cursor));
+        if let Some(max_height) = max_height {
+            db.write_emily_deposit_cursor(max_height).await?;
+        }
+
+        Ok(new_requests)
     }
 
     /// Validate the given deposit requests and store the ones that pass
-    /// validation into the database.
+    /// validation into the database, noting the intake source that they
+    /// were received from.
     ///
     /// There are three types of errors that can happen during validation
     /// 1. The transaction fails primary validation. This means the deposit
@@ -237,21 +475,29 @@ impl<C: Context, B> BlockObserver<C, B> {
     ///    block, or when we encountered some unexpected error when
     ///    reaching out to bitcoin-core or our database.
     #[tracing::instrument(skip_all)]
-    pub async fn load_requests(&self, requests: &[CreateDepositRequest]) -> Result<(), Error> {
+    pub async fn load_requests(
+        &self,
+        requests: &[CreateDepositRequest],
+        source: model::DepositRequestSource,
+    ) -> Result<(), Error> {
         let mut deposit_requests = Vec::new();
         let mut deposit_request_txs = Vec::new();
         let bitcoin_client = self.context.get_bitcoin_client();
         let is_mainnet = self.context.config().signer.network.is_mainnet();
+        let decoders = self.context.config().signer.deposit_script_decoders();
 
         for request in requests {
+            let instant = std::time::Instant::now();
             let deposit = request
-                .validate(&bitcoin_client, is_mainnet)
+                .validate(&bitcoin_client, is_mainnet, &decoders)
                 .await
-                .inspect_err(|error| tracing::warn!(%error, "could not validate deposit request"));
+                .inspect_err(
+                    |error| tracing::warn!(%error, %source, "could not validate deposit request"),
+                );
 
             // We log the error above, so we just need to extract the
             // deposit now.
-            Metrics::increment_deposit_total(&deposit);
+            Metrics::increment_deposit_total(instant.elapsed(), &deposit, source);
             let Ok(Some(deposit)) = deposit else { continue };
 
             self.process_bitcoin_blocks_until(deposit.block_hash)
@@ -266,9 +512,27 @@ impl<C: Context, B> BlockObserver<C, B> {
             deposit_request_txs.push(tx);
         }
 
-        let db = self.context.get_storage_mut();
-        db.write_bitcoin_transactions(deposit_request_txs).await?;
-        db.write_deposit_requests(deposit_requests).await?;
+        let intakes: Vec<_> = deposit_requests
+            .iter()
+            .map(|request| model::DepositRequestIntake {
+                txid: request.txid,
+                output_index: request.output_index,
+                source,
+            })
+            .collect();
+
+        // The intake sources reference the deposit requests, so they are
+        // written together.
+        let storage = self.context.get_storage_mut();
+        let storage_tx = storage.begin_transaction().await?;
+        storage_tx
+            .write_bitcoin_transactions(deposit_request_txs)
+            .await?;
+        storage_tx.write_deposit_requests(deposit_requests).await?;
+        for intake in intakes {
+            storage_tx.write_deposit_request_intake(&intake).await?;
+        }
+        storage_tx.commit().await?;
 
         tracing::debug!("finished processing deposit requests");
         Ok(())
@@ -313,8 +577,23 @@ impl<C: Context, B> BlockObserver<C, B> {
     /// of writing) into memory.
     #[tracing::instrument(skip_all, fields(%block_hash))]
     pub async fn next_headers_to_process(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<Vec<BitcoinBlockHeader>, Error> {
+        self.next_headers_to_process_with_hints(block_hash, HashMap::new())
+            .await
+    }
+
+    /// Like [`BlockObserver::next_headers_to_process`], except that headers
+    /// found in `hints` are used instead of fetching them from
+    /// bitcoin-core.
+    ///
+    /// The hints come from block summaries sent by our peers, so they are
+    /// checked against bitcoin-core when the blocks are processed.
+    async fn next_headers_to_process_with_hints(
         &self,
         mut block_hash: BlockHash,
+        mut hints: HashMap<BlockHash, BitcoinBlockHeader>,
     ) -> Result<Vec<BitcoinBlockHeader>, Error> {
         self.set_sbtc_bitcoin_start_height().await?;
 
@@ -324,9 +603,15 @@ impl<C: Context, B> BlockObserver<C, B> {
         let bitcoin_client = self.context.get_bitcoin_client();
 
         while !db.is_known_bitcoin_block_hash(&block_hash.into()).await? {
-            let Some(header) = bitcoin_client.get_block_header(&block_hash).await? else {
-                tracing::error!(%block_hash, "bitcoin-core does not know about block header");
-                return Err(Error::BitcoinCoreUnknownBlockHeader(block_hash));
+            let header = match hints.remove(&block_hash) {
+                Some(header) => header,
+                None => match bitcoin_client.get_block_header(&block_hash).await? {
+                    Some(header) => header,
+                    None => {
+                        tracing::error!(%block_hash, "bitcoin-core does not know about block header");
+                        return Err(Error::BitcoinCoreUnknownBlockHeader(block_hash));
+                    }
+                },
             };
 
             // We don't even try to write blocks to the database if the
@@ -348,6 +633,113 @@ impl<C: Context, B> BlockObserver<C, B> {
         Ok(headers.into())
     }
 
+    /// Ask our peers on the bulk sync topic for summaries of the blocks
+    /// leading up to the given chain tip, and return them as block headers
+    /// keyed by their block hash.
+    ///
+    /// An empty map is returned if bulk sync is disabled, if we are not
+    /// far enough behind for it to be worth it, or if none of our peers
+    /// responded in time.
+    async fn block_summary_hints(
+        &self,
+        chain_tip: BlockHash,
+    ) -> HashMap<BlockHash, BitcoinBlockHeader> {
+        if !self.context.config().signer.p2p.enable_bulk_sync {
+            return HashMap::new();
+        }
+
+        self.request_block_summaries(chain_tip)
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!(%error, "could not request block summaries from peers");
+                HashMap::new()
+            })
+    }
+
+    /// Request block summaries from our peers and wait for a response.
+    /// See [`BlockObserver::block_summary_hints`].
+    #[tracing::instrument(skip_all, fields(%chain_tip))]
+    async fn request_block_summaries(
+        &self,
+        chain_tip: BlockHash,
+    ) -> Result<HashMap<BlockHash, BitcoinBlockHeader>, Error> {
+        let db = self.context.get_storage();
+        let bitcoin_client = self.context.get_bitcoin_client();
+
+        if db.is_known_bitcoin_block_hash(&chain_tip.into()).await? {
+            return Ok(HashMap::new());
+        }
+
+        let Some(tip_header) = bitcoin_client.get_block_header(&chain_tip).await? else {
+            return Err(Error::BitcoinCoreUnknownBlockHeader(chain_tip));
+        };
+
+        let known_height = match db.get_bitcoin_canonical_chain_tip_ref().await? {
+            Some(block_ref) => block_ref.block_height,
+            None => self.context.state().get_sbtc_bitcoin_start_height(),
+        };
+        let missing_blocks = *tip_header.height.saturating_sub(known_height);
+        if missing_blocks < crate::BULK_SYNC_MIN_BLOCKS {
+            return Ok(HashMap::new());
+        }
+
+        // Our peers are unlikely to have processed the new chain tip
+        // yet, since they learn about it at the same time as we do, so we
+        // ask for the blocks leading up to its parent.
+        let request = BlockSummaryRequest {
+            chain_tip: tip_header.previous_block_hash.into(),
+            max_blocks: u32::try_from(missing_blocks)
+                .unwrap_or(u32::MAX)
+                .min(crate::BULK_SYNC_MAX_BLOCKS),
+        };
+
+        // We subscribe before sending the request so that we cannot miss
+        // the response.
+        let mut signal_rx = self.context.get_signal_receiver();
+        self.context
+            .get_signal_sender()
+            .send(SignerCommand::P2PPublishSync(Box::new(request.into())).into())
+            .map_err(|_| Error::SignerShutdown)?;
+
+        tracing::debug!(
+            max_blocks = request.max_blocks,
+            "requested block summaries from peers"
+        );
+
+        let response = async {
+            loop {
+                match signal_rx.recv().await {
+                    Ok(SignerSignal::Event(SignerEvent::P2P(
+                        P2PEvent::BlockSummariesReceived(batch),
+                    ))) if batch.is_chain_ending_at(&request.chain_tip) => return Some(*batch),
+                    Err(RecvError::Closed) => return None,
+                    _ => continue,
+                }
+            }
+        };
+
+        let timeout = Duration::from_secs(crate::BULK_SYNC_RESPONSE_TIMEOUT_SECS);
+        let Ok(Some(batch)) = response.with_timeout(timeout).await else {
+            tracing::debug!("no block summaries received from peers");
+            return Ok(HashMap::new());
+        };
+
+        tracing::debug!(
+            num_blocks = batch.blocks.len(),
+            "received block summaries from peers"
+        );
+
+        let mut hints: HashMap<BlockHash, BitcoinBlockHeader> = batch
+            .blocks
+            .into_iter()
+            .map(BitcoinBlockHeader::from)
+            .map(|header| (header.hash, header))
+            .collect();
+        hints.insert(tip_header.hash, tip_header);
+
+        Ok(hints)
+    }
+
     /// Process bitcoin blocks until we get caught up to the given
     /// `block_hash`.
     ///
@@ -365,10 +757,42 @@ impl<C: Context, B> BlockObserver<C, B> {
     /// subsequent calls to this function will properly pick up from where
     /// we left off and update the database.
     async fn process_bitcoin_blocks_until(&self, block_hash: BlockHash) -> Result<(), Error> {
-        let block_headers = self.next_headers_to_process(block_hash).await?;
+        let hints = self.block_summary_hints(block_hash).await;
+        if hints.is_empty() {
+            return self.process_bitcoin_blocks(block_hash, hints).await;
+        }
+
+        match self.process_bitcoin_blocks(block_hash, hints).await {
+            Err(Error::BlockSummaryMismatch(mismatch)) => {
+                tracing::warn!(
+                    %mismatch,
+                    "block summaries from peers do not match bitcoin-core; falling back to bitcoin-core"
+                );
+                self.process_bitcoin_blocks(block_hash, HashMap::new())
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    /// Process the bitcoin blocks leading up to the given `block_hash`,
+    /// using the given block header hints. See
+    /// [`BlockObserver::process_bitcoin_blocks_until`].
+    async fn process_bitcoin_blocks(
+        &self,
+        block_hash: BlockHash,
+        hints: HashMap<BlockHash, BitcoinBlockHeader>,
+    ) -> Result<(), Error> {
+        let block_headers = self
+            .next_headers_to_process_with_hints(block_hash, hints)
+            .await?;
 
         for block_header in block_headers {
-            self.process_bitcoin_block(block_header).await?;
+            // Every block before the one that we were asked to process is
+            // history that we are catching up on.
+            let is_backfill = block_header.hash != block_hash;
+            self.process_bitcoin_block(block_header, is_backfill)
+                .await?;
         }
 
         Ok(())
@@ -376,14 +800,33 @@ impl<C: Context, B> BlockObserver<C, B> {
 
     /// Write the bitcoin block and any transactions that spend to any of
     /// the signers `scriptPubKey`s to the database.
-    #[tracing::instrument(skip_all, fields(block_hash = %block_header.hash))]
-    async fn process_bitcoin_block(&self, block_header: BitcoinBlockHeader) -> Result<(), Error> {
+    ///
+    /// When backfilling, the transactions are matched against the
+    /// `scriptPubKey`s that the signers used at the height of the block,
+    /// rather than the ones that they use now, see
+    /// [`extract_backfilled_sbtc_transactions`].
+    #[tracing::instrument(skip_all, fields(block_hash = %block_header.hash, is_backfill))]
+    async fn process_bitcoin_block(
+        &self,
+        block_header: BitcoinBlockHeader,
+        is_backfill: bool,
+    ) -> Result<(), Error> {
         let block = self
             .context
             .get_bitcoin_client()
             .get_block(&block_header.hash)
             .await?
             .ok_or(Error::BitcoinCoreMissingBlock(block_header.hash))?;
+
+        // The header may have been built from a block summary sent by one
+        // of our peers, so we check it against the block from bitcoin-core.
+        let is_consistent = block.previous_block_hash == block_header.previous_block_hash
+            && block.height == block_header.height
+            && block.time == block_header.time;
+        if !is_consistent {
+            return Err(Error::BlockSummaryMismatch(block_header.hash));
+        }
+
         let db_block = model::BitcoinBlock::from(&block);
 
         let storage = self.context.get_storage_mut();
@@ -402,13 +845,32 @@ impl<C: Context, B> BlockObserver<C, B> {
 
         // Extract the sBTC-related transactions from the block and write them
         // to the database (within the transaction).
-        extract_sbtc_transactions(
-            &storage_tx,
-            bootstrap_script_pubkey,
-            block_header.hash,
-            &block.transactions,
-        )
-        .await?;
+        let sbtc_tx_count = if is_backfill {
+            extract_backfilled_sbtc_transactions(
+                &storage_tx,
+                bootstrap_script_pubkey,
+                block_header.hash,
+                block_header.height,
+                &block.transactions,
+            )
+            .await?
+        } else {
+            extract_sbtc_transactions(
+                &storage_tx,
+                bootstrap_script_pubkey,
+                block_header.hash,
+                &block.transactions,
+            )
+            .await?
+        };
+
+        // We record the time and sBTC activity of every block that we
+        // process, including blocks without any sBTC activity.
+        let activity = model::BitcoinBlockActivity::new(&block_header, sbtc_tx_count > 0);
+        storage_tx.write_bitcoin_block_activity(&activity).await?;
+        if sbtc_tx_count == 0 {
+            tracing::debug!("bitcoin block contains no sbtc transactions");
+        }
 
         // Commit the storage transaction.
         storage_tx.commit().await?;
@@ -595,7 +1057,8 @@ impl<C: Context, B> BlockObserver<C, B> {
 }
 
 /// Extract all BTC transactions from the block where one of the UTXOs
-/// can be spent by the signers.
+/// can be spent by the signers, returning the number of such transactions
+/// found.
 ///
 /// # Note
 ///
@@ -607,7 +1070,46 @@ pub async fn extract_sbtc_transactions<Storage>(
     bootstrap_aggregate_key: Option<PublicKey>,
     block_hash: BlockHash,
     txs: &[BitcoinTxInfo],
-) -> Result<(), Error>
+) -> Result<usize, Error>
+where
+    Storage: DbRead + DbWrite,
+{
+    extract_sbtc_transactions_at(db, bootstrap_aggregate_key, block_hash, None, txs).await
+}
+
+/// Like [`extract_sbtc_transactions`], except that the transactions are
+/// matched against the signers' `scriptPubKey`s that were in use at the
+/// given height of the block, instead of the ones from the last year.
+///
+/// This is for backfilling historical blocks, so that the imported history
+/// is held to the rules that applied when the blocks were mined: outputs
+/// locked with an aggregate key from a later DKG round are not taken for
+/// the signers', and keys that were rotated out long ago are still
+/// recognized.
+pub async fn extract_backfilled_sbtc_transactions<Storage>(
+    db: &Storage,
+    bootstrap_aggregate_key: Option<PublicKey>,
+    block_hash: BlockHash,
+    block_height: BitcoinBlockHeight,
+    txs: &[BitcoinTxInfo],
+) -> Result<usize, Error>
+where
+    Storage: DbRead + DbWrite,
+{
+    let height = Some(block_height);
+    extract_sbtc_transactions_at(db, bootstrap_aggregate_key, block_hash, height, txs).await
+}
+
+/// Extract the sBTC related transactions from the given block, matching
+/// them against the signers' `scriptPubKey`s at the given block height if
+/// there is one, or against the recent ones otherwise.
+async fn extract_sbtc_transactions_at<Storage>(
+    db: &Storage,
+    bootstrap_aggregate_key: Option<PublicKey>,
+    block_hash: BlockHash,
+    block_height: Option<BitcoinBlockHeight>,
+    txs: &[BitcoinTxInfo],
+) -> Result<usize, Error>
 where
     Storage: DbRead + DbWrite,
 {
@@ -620,10 +1122,13 @@ where
     // transactions and write them to the database.
     let extract_fut = || async {
         // We store all the scriptPubKeys associated with the signers'
-        // aggregate public key. Let's get the last years worth of them.
-        let signer_script_pubkeys: HashSet<ScriptBuf> = db
-            .get_signers_script_pubkeys()
-            .await?
+        // aggregate public key. Let's get the ones in use at the height of
+        // the block if we know it, and the last years worth otherwise.
+        let script_pubkeys = match block_height {
+            Some(height) => db.get_signers_script_pubkeys_at(height).await?,
+            None => db.get_signers_script_pubkeys().await?,
+        };
+        let signer_script_pubkeys: HashSet<ScriptBuf> = script_pubkeys
             .into_iter()
             .map(ScriptBuf::from_bytes)
             .chain(bootstrap_script_pubkey.clone())
@@ -691,11 +1196,41 @@ where
             for output in withdrawal_outputs {
                 db.write_withdrawal_tx_output(&output).await?;
             }
+
+            // Index every input and output of the transaction, including
+            // the ones that the signers do not control, so that the flow
+            // of funds through the peg wallet can be traced later on.
+            for input in tx_info.to_peg_wallet_inputs(&signer_script_pubkeys) {
+                db.write_peg_wallet_tx_input(&input).await?;
+            }
+            for output in tx_info.to_peg_wallet_outputs(&signer_script_pubkeys) {
+                db.write_peg_wallet_tx_output(&output).await?;
+            }
+
+            // Moving funds between the signers' own addresses neither
+            // sweeps in deposits nor pays out withdrawals, so we record
+            // these transactions to keep them apart from sweeps. We look
+            // at each block twice, so we only count them the first time.
+            if tx_info.is_internal_transfer(&signer_script_pubkeys) {
+                let outputs = tx_info.tx.output.iter();
+                let transfer = model::BitcoinInternalTransfer {
+                    txid: txid.into(),
+                    block_hash: block_hash.into(),
+                    amount: outputs.map(|tx_out| tx_out.value.to_sat()).sum(),
+                };
+                if !db.is_bitcoin_internal_transfer(&transfer.txid).await? {
+                    let amount = transfer.amount;
+                    tracing::info!(%txid, %amount, "observed an internal transfer of the signers");
+                    metrics::counter!(Metrics::BitcoinInternalTransfersTotal).increment(1);
+                }
+                db.write_bitcoin_internal_transfer(&transfer).await?;
+            }
         }
 
         // Write these transactions into storage.
+        let sbtc_tx_count = sbtc_txs.len();
         db.write_bitcoin_transactions(sbtc_txs).await?;
-        Ok(())
+        Ok(sbtc_tx_count)
     };
 
     // The first time, we get all sweep transactions with inputs that
@@ -775,9 +1310,11 @@ mod tests {
     use fake::Fake as _;
     use model::BitcoinTxId;
     use model::ScriptPubKey;
+    use test_case::test_case;
     use test_log::test;
 
     use crate::bitcoin::rpc::GetTxResponse;
+    use crate::config::Settings;
     use crate::context::SignerSignal;
     use crate::keys::PublicKey;
     use crate::keys::SignerScriptPubKey as _;
@@ -835,7 +1372,7 @@ mod tests {
         handle.abort();
     }
 
-    /// Test that `BlockObserver::load_latest_deposit_requests` takes
+    /// Test that `BlockObserver::load_emily_deposit_requests` takes
     /// deposits from emily, validates them and only keeps the ones that
     /// pass validation and have been confirmed.
     #[tokio::test]
@@ -950,7 +1487,13 @@ mod tests {
             assert_eq!(db.deposit_requests.len(), 0);
         }
 
-        block_observer.load_latest_deposit_requests().await.unwrap();
+        let emily_config = &block_observer.context.config().emily;
+        let mut polling = EmilyDepositPolling::new(emily_config, Instant::now());
+        let new_requests = block_observer
+            .load_emily_deposit_requests(&mut polling, EmilyPoll::Full)
+            .await
+            .unwrap();
+        assert_eq!(new_requests, 3);
         // Only the transaction from tx_setup0 was valid. Note that, since
         // we are not using a real block hash stored in the database. Our
         // DbRead function won't actually find it. And in prod we won't
@@ -962,10 +1505,80 @@ mod tests {
         };
 
         assert_eq!(deposit.outpoint(), req0.outpoint);
+
+        // Every poll of Emily, including the full syncs, is attributed to
+        // Emily.
+        let sources = storage
+            .get_deposit_request_sources(&deposit.txid, deposit.output_index)
+            .await
+            .unwrap();
+        assert_eq!(sources, vec![model::DepositRequestSource::Emily]);
+
+        // The correlation ID that Emily gave for it is kept too.
+        let correlation_id = storage
+            .get_deposit_correlation_id(&deposit.txid, deposit.output_index)
+            .await
+            .unwrap();
+        assert_eq!(correlation_id, Some(req0.outpoint.to_string()));
+
+        // The other two deposit requests are retried on the next bitcoin
+        // block, and the cursor is at the height at which Emily last
+        // updated the deposit requests.
+        assert_eq!(polling.unstored.len(), 2);
+        assert_eq!(storage.get_emily_deposit_cursor().await.unwrap(), Some(0));
+
+        // Emily did not update any deposit request since the last poll.
+        let new_requests = block_observer
+            .load_emily_deposit_requests(&mut polling, EmilyPoll::Delta)
+            .await
+            .unwrap();
+        assert_eq!(new_requests, 0);
+    }
+
+    #[test]
+    fn emily_deposit_polling_adapts_to_activity() {
+        let mut config = Settings::new_from_default_config().unwrap().emily;
+        config.deposit_poll_min_interval = Duration::from_secs(5);
+        config.deposit_poll_max_interval = Duration::from_secs(30);
+        config.deposit_full_sync_blocks = std::num::NonZeroU16::new(3).unwrap();
+
+        let rng = ContextRng::new(Some(42));
+        let now = Instant::now();
+        let mut polling = EmilyDepositPolling::new(&config, now);
+        assert!(polling.is_due(now));
+
+        // The interval doubles after each poll without new deposits, up to
+        // the maximum, and the next poll has up to 25% of jitter.
+        for expected in [10, 20, 30, 30] {
+            polling.schedule_next(now, false, &rng);
+            let interval = Duration::from_secs(expected);
+            assert_eq!(polling.interval, interval);
+            assert!(polling.next_poll >= now + interval);
+            assert!(polling.next_poll <= now + interval + interval / 4);
+            assert!(!polling.is_due(now + interval - Duration::from_secs(1)));
+        }
+
+        // A poll that finds new deposits goes back to the minimum.
+        polling.schedule_next(now, true, &rng);
+        assert_eq!(polling.interval, Duration::from_secs(5));
+
+        // The first bitcoin block, and every third one after it, triggers
+        // a full sync.
+        let polls: Vec<_> = (0..7).map(|_| polling.observe_bitcoin_block()).collect();
+        let expected = [
+            EmilyPoll::Full,
+            EmilyPoll::NewBlock,
+            EmilyPoll::NewBlock,
+            EmilyPoll::Full,
+            EmilyPoll::NewBlock,
+            EmilyPoll::NewBlock,
+            EmilyPoll::Full,
+        ];
+        assert_eq!(polls, expected);
     }
 
     /// Test that `BlockObserver::extract_deposit_requests` after
-    /// `BlockObserver::load_latest_deposit_requests` stores validated
+    /// `BlockObserver::load_emily_deposit_requests` stores validated
     /// deposit requests into "storage".
     #[tokio::test]
     async fn extract_deposit_requests_stores_validated_deposits() {
@@ -1030,7 +1643,12 @@ mod tests {
             bitcoin_block_source: (),
         };
 
-        block_observer.load_latest_deposit_requests().await.unwrap();
+        let emily_config = &block_observer.context.config().emily;
+        let mut polling = EmilyDepositPolling::new(emily_config, Instant::now());
+        block_observer
+            .load_emily_deposit_requests(&mut polling, EmilyPoll::Full)
+            .await
+            .unwrap();
 
         let storage = storage.lock().await;
         assert_eq!(storage.deposit_requests.len(), 1);
@@ -1154,4 +1772,110 @@ mod tests {
         assert_eq!(tx_ids.len(), 1);
         assert!(tx_ids.contains(&expected_tx_id));
     }
+
+    /// Test that a transaction moving the signers' funds from the address
+    /// of a previous aggregate key to the one of the current key is
+    /// recorded as an internal transfer, with no deposits or withdrawals.
+    #[tokio::test]
+    async fn internal_transfers_get_recorded() {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([3u8; 32]);
+
+        let storage = storage::memory::Store::new_shared();
+        let mut script_pubkeys = Vec::new();
+        for height in [1u64, 2] {
+            let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+            let script_pubkey: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+            let shares = model::EncryptedDkgShares {
+                aggregate_key,
+                tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+                script_pubkey: script_pubkey.clone(),
+                encrypted_private_shares: Vec::new(),
+                public_shares: Vec::new(),
+                signer_set_public_keys: vec![aggregate_key],
+                signature_share_threshold: 1,
+                dkg_shares_status: DkgSharesStatus::Verified,
+                started_at_bitcoin_block_hash: block_hash.into(),
+                started_at_bitcoin_block_height: height.into(),
+            };
+            storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+            script_pubkeys.push(script_pubkey);
+        }
+
+        // The transaction spends an output locked by the previous key and
+        // pays everything to the current key.
+        let mut tx_info: BitcoinTxInfo = fake::Faker.fake_with_rng(&mut rng);
+        let prevout = tx_info.vin[0].prevout.as_mut().unwrap();
+        prevout.script_pubkey.script = script_pubkeys[0].clone().into();
+        tx_info.tx.output[0].script_pubkey = script_pubkeys[1].clone().into();
+        let txid: BitcoinTxId = tx_info.compute_txid().into();
+
+        let count = extract_sbtc_transactions(&storage, None, block_hash, &[tx_info])
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(storage.is_bitcoin_internal_transfer(&txid).await.unwrap());
+
+        let store = storage.lock().await;
+        let outputs = store.bitcoin_outputs.get(&txid).unwrap();
+        let output_types: Vec<_> = outputs.iter().map(|output| output.output_type).collect();
+        assert_eq!(output_types, [model::TxOutputType::SignersOutput]);
+        assert!(store.bitcoin_withdrawal_outputs.is_empty());
+
+        let prevouts = store.bitcoin_prevouts.get(&txid).unwrap();
+        assert!(
+            prevouts
+                .iter()
+                .all(|prevout| prevout.prevout_type == model::TxPrevoutType::SignersInput)
+        );
+    }
+
+    /// Test that `extract_backfilled_sbtc_transactions` only matches the
+    /// transactions of a block against the signers' `scriptPubKey`s from
+    /// DKG rounds that had started by the height of the block.
+    #[test_case(9, false; "before the key was generated")]
+    #[test_case(10, true; "when the key was generated")]
+    #[test_case(11, true; "after the key was generated")]
+    #[tokio::test]
+    async fn backfilled_sbtc_transactions_use_historical_keys(block_height: u64, stored: bool) {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([2u8; 32]);
+        let signers_script_pubkey: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+
+        let storage = storage::memory::Store::new_shared();
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let shares = model::EncryptedDkgShares {
+            aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: signers_script_pubkey.clone(),
+            encrypted_private_shares: Vec::new(),
+            public_shares: Vec::new(),
+            signer_set_public_keys: vec![aggregate_key],
+            signature_share_threshold: 1,
+            dkg_shares_status: DkgSharesStatus::Verified,
+            started_at_bitcoin_block_hash: fake::Faker.fake_with_rng(&mut rng),
+            started_at_bitcoin_block_height: 10u64.into(),
+        };
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        let mut tx_setup = sbtc::testing::deposits::tx_setup(0, 0, &[100]);
+        tx_setup.tx.output.push(TxOut {
+            value: Amount::ONE_BTC,
+            script_pubkey: signers_script_pubkey.into(),
+        });
+        let txs = [tx_setup.tx.fake_with_rng(&mut rng)];
+
+        let height = block_height.into();
+        let count = extract_backfilled_sbtc_transactions(&storage, None, block_hash, height, &txs)
+            .await
+            .unwrap();
+        assert_eq!(count > 0, stored);
+
+        // The same block is always matched against the recent keys when
+        // it is not being backfilled.
+        let count = extract_sbtc_transactions(&storage, None, block_hash, &txs)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
 }
@@ -0,0 +1,242 @@
+//! # Fixture capture
+//!
+//! Many of our tests run against JSON fixtures in `tests/fixtures` that
+//! were recorded from a devnet: webhook bodies from the stacks node,
+//! the sBTC clarity events inside of them, and responses from the stacks
+//! node's RPC API. When the contracts or the node change, these fixtures
+//! need to be refreshed.
+//!
+//! Starting the signer with `--capture-fixtures <DIR>` enables capturing
+//! here, and the signer then writes everything that it receives from
+//! these sources to `DIR` while running normally. Everything is
+//! anonymized before it is written, see [`anonymize`], and failures to
+//! write a fixture are logged and otherwise ignored.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use serde_json::Value;
+use sha2::Digest as _;
+
+use crate::error::Error;
+
+/// Object keys whose string values identify the machines in a devnet
+/// and are replaced when anonymizing.
+const REDACTED_KEYS: [&str; 4] = [
+    "node_public_key",
+    "node_public_key_hash",
+    "server_version",
+    "peer_host",
+];
+
+/// The fixture capture, if it was enabled at startup.
+static FIXTURE_CAPTURE: OnceLock<FixtureCapture> = OnceLock::new();
+
+/// Enable fixture capture for the rest of the process, writing fixtures
+/// to the given directory. The directory is created if it does not
+/// exist.
+///
+/// Capture can only be enabled once, later calls do nothing.
+pub fn enable(dir: impl Into<PathBuf>) -> Result<(), Error> {
+    let capture = FixtureCapture::new(dir)?;
+    let _ = FIXTURE_CAPTURE.set(capture);
+    Ok(())
+}
+
+/// Return the fixture capture if it has been enabled.
+pub fn get() -> Option<&'static FixtureCapture> {
+    FIXTURE_CAPTURE.get()
+}
+
+/// Writes anonymized fixtures to a directory.
+#[derive(Debug)]
+pub struct FixtureCapture {
+    dir: PathBuf,
+    /// A counter used to give each fixture a unique file name. The
+    /// numbers also order the fixtures by when they were captured.
+    sequence: AtomicU64,
+}
+
+impl FixtureCapture {
+    /// Create a new capture that writes fixtures to the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|err| Error::FixtureCapture(err, dir.clone()))?;
+        Ok(Self {
+            dir,
+            sequence: AtomicU64::new(0),
+        })
+    }
+
+    /// The directory that fixtures are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record the body of a webhook received on the given endpoint, along
+    /// with each of the clarity events within it.
+    pub fn record_webhook(&self, endpoint: &str, body: &str) {
+        let Ok(mut webhook) = serde_json::from_str::<Value>(body) else {
+            tracing::warn!(%endpoint, "could not capture a webhook body that is not JSON");
+            return;
+        };
+        anonymize(&mut webhook);
+
+        let endpoint = endpoint.trim_matches('/').replace('/', "-");
+        self.write(&format!("webhook-{endpoint}"), &webhook);
+
+        let events = webhook["events"].as_array().into_iter().flatten();
+        for event in events.filter(|event| event["contract_event"].is_object()) {
+            self.write("clarity-event", event);
+        }
+    }
+
+    /// Record the body of a response from the stacks node's RPC API. The
+    /// name should describe the request, such as `get-node-info`.
+    pub fn record_stacks_response(&self, name: &str, body: &[u8]) {
+        let Ok(mut response) = serde_json::from_slice::<Value>(body) else {
+            tracing::warn!(%name, "could not capture a stacks response that is not JSON");
+            return;
+        };
+        anonymize(&mut response);
+        self.write(&format!("stacksapi-{name}"), &response);
+    }
+
+    fn write(&self, prefix: &str, value: &Value) {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        let path = self.dir.join(format!("{prefix}-{sequence:06}.json"));
+
+        let result = serde_json::to_vec_pretty(value)
+            .map_err(Error::JsonSerialize)
+            .and_then(|contents| {
+                std::fs::write(&path, contents)
+                    .map_err(|err| Error::FixtureCapture(err, path.clone()))
+            });
+        match result {
+            Ok(()) => tracing::debug!(path = %path.display(), "captured a fixture"),
+            Err(error) => tracing::warn!(%error, "could not write a captured fixture"),
+        }
+    }
+}
+
+/// Anonymize a JSON value in place.
+///
+/// The values of the keys in [`REDACTED_KEYS`] are replaced. Hex strings
+/// are replaced with hex strings of the same length derived from a hash
+/// of the original, so the replacement is consistent across fixtures and
+/// still parses as the same type, while other strings are replaced with
+/// `"redacted"`. The host of any URL is replaced with `localhost`.
+///
+/// Everything else, such as block hashes, txids, and contract events, is
+/// public chain data that the tests depend on and is left alone.
+pub fn anonymize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if REDACTED_KEYS.contains(&key.as_str()) => {
+                        *s = pseudonym(s);
+                    }
+                    _ => anonymize(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(anonymize),
+        Value::String(s) => {
+            if let Some(redacted) = redact_url(s) {
+                *s = redacted;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Return a replacement for a value that identifies a machine.
+fn pseudonym(value: &str) -> String {
+    let (prefix, hex) = match value.strip_prefix("0x") {
+        Some(hex) => ("0x", hex),
+        None => ("", value),
+    };
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return "redacted".to_string();
+    }
+
+    let digest = hex::encode(sha2::Sha256::digest(value.as_bytes()));
+    let replacement: String = digest.chars().cycle().take(hex.len()).collect();
+    format!("{prefix}{replacement}")
+}
+
+/// Return the string with the host replaced, if it is a URL with a host.
+fn redact_url(value: &str) -> Option<String> {
+    let mut url = url::Url::parse(value).ok()?;
+    if url.host_str().is_none_or(|host| host == "localhost") {
+        return None;
+    }
+    url.set_host(Some("localhost")).ok()?;
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anonymize_replaces_identifying_values() {
+        let mut value = serde_json::json!({
+            "node_public_key": "035379aa40c02890d253cfa577964116eb5295570ae9f7287cbae5f2585f5b2c7c",
+            "server_version": "stacks-node 0.0.1 (debug build, linux [x86_64])",
+            "stacks_tip": "ffd652ff665bb1b07b19e537a5a007d44ea1e8cd0ddfd8753d9f95f915aaee41",
+            "nested": [{ "peer_host": "0xabcd", "url": "http://stacks-node:20443/v2/info" }],
+        });
+        anonymize(&mut value);
+
+        let public_key = value["node_public_key"].as_str().unwrap();
+        assert_eq!(public_key.len(), 66);
+        assert!(!public_key.starts_with("035379aa"));
+        assert!(hex::decode(public_key).is_ok());
+        assert_eq!(value["server_version"], "redacted");
+        assert_ne!(value["nested"][0]["peer_host"], "0xabcd");
+        assert_eq!(value["nested"][0]["url"], "http://localhost:20443/v2/info");
+        // Chain data is left alone.
+        assert_eq!(
+            value["stacks_tip"],
+            "ffd652ff665bb1b07b19e537a5a007d44ea1e8cd0ddfd8753d9f95f915aaee41"
+        );
+
+        // The replacement is deterministic.
+        let mut again = serde_json::json!({
+            "node_public_key": "035379aa40c02890d253cfa577964116eb5295570ae9f7287cbae5f2585f5b2c7c",
+        });
+        anonymize(&mut again);
+        assert_eq!(again["node_public_key"], value["node_public_key"]);
+    }
+
+    #[test]
+    fn webhooks_and_their_events_are_captured() {
+        let dir = tempfile::tempdir().unwrap();
+        let capture = FixtureCapture::new(dir.path()).unwrap();
+
+        let body = include_str!("../tests/fixtures/completed-deposit-event.json");
+        capture.record_webhook("/new_block", body);
+
+        let mut names: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names[0], "webhook-new_block-000000.json");
+        assert!(names.len() > 1);
+        assert!(
+            names[1..]
+                .iter()
+                .all(|name| name.starts_with("clarity-event-"))
+        );
+
+        // The captured webhook still parses as a webhook.
+        let captured = std::fs::read_to_string(dir.path().join(&names[0])).unwrap();
+        serde_json::from_str::<sbtc::webhooks::NewBlockEvent>(&captured).unwrap();
+    }
+}
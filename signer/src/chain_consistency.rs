@@ -0,0 +1,261 @@
+//! # Chain consistency checks
+//!
+//! The signer learns about the bitcoin blockchain from its bitcoin node
+//! and about the stacks blockchain from its stacks node. Each stacks
+//! tenure is anchored to a bitcoin block, so if the two nodes agree with
+//! each other, then the bitcoin anchor of the stacks chain tip is an
+//! ancestor of (or the same as) our bitcoin chain tip. When it is not,
+//! one of the nodes is lagging or on a fork, and decisions made on these
+//! views, such as votes on deposit and withdrawal requests or the
+//! validation of a coordinator's transactions, can be wrong.
+//!
+//! The [`ChainConsistencyChecker`] checks this at startup and then
+//! periodically. While the views disagree, the signer does not
+//! participate as a coordinator or validator, and it resumes once a later
+//! check passes.
+
+use std::time::Duration;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::stacks::api::StacksInteract as _;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockRef;
+
+/// The outcome of a chain consistency check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainConsistency {
+    /// The bitcoin anchor of the stacks chain tip is on our canonical
+    /// bitcoin blockchain.
+    Consistent,
+    /// The bitcoin anchor of the stacks chain tip is not on our canonical
+    /// bitcoin blockchain.
+    Inconsistent {
+        /// The bitcoin block that the stacks chain tip is anchored to.
+        stacks_anchor: BitcoinBlockRef,
+        /// Our bitcoin chain tip.
+        bitcoin_chain_tip: BitcoinBlockRef,
+    },
+    /// We have not observed any bitcoin blocks yet, so there is nothing
+    /// to compare against.
+    Unknown,
+}
+
+/// Check whether the bitcoin anchor of the stacks node's chain tip is an
+/// ancestor of our bitcoin chain tip.
+pub async fn check_chain_consistency<C: Context>(ctx: &C) -> Result<ChainConsistency, Error> {
+    let db = ctx.get_storage();
+    let bitcoin_chain_tip = match ctx.state().bitcoin_chain_tip() {
+        Some(chain_tip) => chain_tip,
+        None => match db.get_bitcoin_canonical_chain_tip_ref().await? {
+            Some(chain_tip) => chain_tip,
+            None => return Ok(ChainConsistency::Unknown),
+        },
+    };
+
+    let stacks_client = ctx.get_stacks_client();
+    let tenure_info = stacks_client.get_tenure_info().await?;
+    let sortition = stacks_client
+        .get_sortition_info(&tenure_info.consensus_hash)
+        .await?;
+    let stacks_anchor = BitcoinBlockRef {
+        block_hash: sortition.burn_block_hash.into(),
+        block_height: sortition.burn_block_height.into(),
+    };
+
+    let is_ancestor = stacks_anchor.block_height <= bitcoin_chain_tip.block_height
+        && db
+            .in_canonical_bitcoin_blockchain(&bitcoin_chain_tip, &stacks_anchor)
+            .await?;
+
+    if is_ancestor {
+        Ok(ChainConsistency::Consistent)
+    } else {
+        Ok(ChainConsistency::Inconsistent {
+            stacks_anchor,
+            bitcoin_chain_tip,
+        })
+    }
+}
+
+/// Periodically checks that our views of the bitcoin and stacks
+/// blockchains are consistent, and records the outcome in the signer
+/// state.
+pub struct ChainConsistencyChecker<C> {
+    /// Signer context.
+    context: C,
+    /// Check interval.
+    interval: Duration,
+}
+
+impl<C> ChainConsistencyChecker<C>
+where
+    C: Context,
+{
+    /// Creates a new ChainConsistencyChecker with the given context and
+    /// interval.
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self { context, interval }
+    }
+
+    /// Runs the ChainConsistencyChecker, which checks once every
+    /// interval. The startup check is expected to have been done with
+    /// [`ChainConsistencyChecker::check`] before this is called.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.check().await;
+                }
+            }
+        }
+        tracing::info!("chain consistency checker has stopped");
+    }
+
+    /// Check the consistency of our views of the bitcoin and stacks
+    /// blockchains and update the signer state with the outcome.
+    ///
+    /// If the check cannot be done, say because the stacks node is
+    /// unreachable, then the signer state is left as it is.
+    #[tracing::instrument(skip_all, name = "chain-consistency")]
+    pub async fn check(&self) {
+        let consistency = match check_chain_consistency(&self.context).await {
+            Ok(consistency) => consistency,
+            Err(error) => {
+                tracing::warn!(%error, "could not check the consistency of the chain views");
+                return;
+            }
+        };
+
+        let state = self.context.state();
+        let was_consistent = state.chain_views_consistent();
+        let is_consistent = match consistency {
+            ChainConsistency::Consistent | ChainConsistency::Unknown => true,
+            ChainConsistency::Inconsistent {
+                stacks_anchor,
+                bitcoin_chain_tip,
+            } => {
+                tracing::error!(
+                    stacks_anchor_hash = %stacks_anchor.block_hash,
+                    stacks_anchor_height = %stacks_anchor.block_height,
+                    bitcoin_tip_hash = %bitcoin_chain_tip.block_hash,
+                    bitcoin_tip_height = %bitcoin_chain_tip.block_height,
+                    "the stacks chain tip is not anchored to our bitcoin blockchain; \
+                    holding back from coordinating and validating"
+                );
+                false
+            }
+        };
+
+        if is_consistent && !was_consistent {
+            tracing::info!("the chain views are consistent again; resuming participation");
+        }
+        state.set_chain_views_consistent(is_consistent);
+        Metrics::record_chain_views_consistent(is_consistent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockstack_lib::net::api::getsortition::SortitionInfo;
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::storage::DbWrite as _;
+    use crate::storage::memory::SharedStore;
+    use crate::storage::model::BitcoinBlock;
+    use crate::testing::context::TestContext;
+    use crate::testing::context::WrappedMockBitcoinInteract;
+    use crate::testing::context::WrappedMockEmilyInteract;
+    use crate::testing::context::WrappedMockStacksInteract;
+    use crate::testing::stacks::DUMMY_SORTITION_INFO;
+    use crate::testing::stacks::DUMMY_TENURE_INFO;
+
+    use super::*;
+
+    type MockedContext = TestContext<
+        SharedStore,
+        WrappedMockBitcoinInteract,
+        WrappedMockStacksInteract,
+        WrappedMockEmilyInteract,
+    >;
+
+    /// Set up a context with a two block bitcoin blockchain, returning the
+    /// context and the two blocks.
+    async fn setup() -> (MockedContext, BitcoinBlock, BitcoinBlock) {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+
+        let genesis = BitcoinBlock {
+            block_hash: Faker.fake(),
+            block_height: 0u64.into(),
+            parent_hash: Faker.fake(),
+        };
+        let tip = BitcoinBlock {
+            block_hash: Faker.fake(),
+            block_height: 1u64.into(),
+            parent_hash: genesis.block_hash,
+        };
+        db.write_bitcoin_block(&genesis).await.unwrap();
+        db.write_bitcoin_block(&tip).await.unwrap();
+        ctx.state().set_bitcoin_chain_tip((&tip).into());
+
+        (ctx, genesis, tip)
+    }
+
+    /// Run a check with the stacks node reporting a chain tip that is
+    /// anchored to the given bitcoin block.
+    async fn check_with_anchor(ctx: &MockedContext, anchor: BitcoinBlockRef) -> bool {
+        ctx.with_stacks_client(|client| {
+            client
+                .expect_get_tenure_info()
+                .returning(|| Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO))));
+            client.expect_get_sortition_info().returning(move |_| {
+                let sortition = SortitionInfo {
+                    burn_block_hash: anchor.block_hash.into(),
+                    burn_block_height: *anchor.block_height,
+                    ..DUMMY_SORTITION_INFO
+                };
+                Box::pin(std::future::ready(Ok(sortition)))
+            });
+        })
+        .await;
+
+        ChainConsistencyChecker::new(ctx.clone(), Duration::from_secs(1))
+            .check()
+            .await;
+        ctx.state().chain_views_consistent()
+    }
+
+    #[tokio::test]
+    async fn anchors_on_our_blockchain_are_consistent() {
+        let (ctx, genesis, _) = setup().await;
+        assert!(check_with_anchor(&ctx, (&genesis).into()).await);
+
+        let (ctx, _, tip) = setup().await;
+        assert!(check_with_anchor(&ctx, (&tip).into()).await);
+    }
+
+    #[tokio::test]
+    async fn anchors_off_our_blockchain_are_inconsistent() {
+        let (ctx, _, _) = setup().await;
+        let unknown = BitcoinBlockRef {
+            block_hash: Faker.fake(),
+            block_height: 1u64.into(),
+        };
+        assert!(!check_with_anchor(&ctx, unknown).await);
+
+        // The stacks node being ahead of us is inconsistent too.
+        let (ctx, _, tip) = setup().await;
+        let ahead = BitcoinBlockRef {
+            block_hash: Faker.fake(),
+            block_height: tip.block_height + 1,
+        };
+        assert!(!check_with_anchor(&ctx, ahead).await);
+    }
+}
@@ -0,0 +1,249 @@
+//! # State checkpoints
+//!
+//! A signer whose database silently diverged from the rest of the
+//! federation, say because rows were lost in a botched restore or a
+//! stacks event was never digested, keeps running and only shows up as
+//! confusing signing failures much later. To catch this early, each
+//! signer summarizes the state it has processed in a [`StateCheckpoint`]
+//! and sends it in its heartbeats, which are signed. Receivers recompute
+//! the summary from their own database and report any difference.
+//!
+//! A checkpoint is anchored to a stacks block. Two signers that agree on
+//! the stacks blockchain ending at that block must have the same events
+//! in it, regardless of how far each of them has progressed since, so
+//! checkpoints can be verified by any signer that knows the block.
+
+use std::collections::BTreeSet;
+
+use sha2::Digest as _;
+use sha2::Sha256;
+
+use crate::error::Error;
+use crate::integrity::ProtectedRow;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::CompletedDepositEvent;
+use crate::storage::model::KeyRotationEvent;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksBlockHeight;
+
+/// A summary of the state that a signer has processed, up to and
+/// including a stacks block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct StateCheckpoint {
+    /// The hash of the bitcoin chain tip when the checkpoint was taken.
+    pub bitcoin_block_hash: BitcoinBlockHash,
+    /// The height of the bitcoin chain tip when the checkpoint was taken.
+    pub bitcoin_block_height: BitcoinBlockHeight,
+    /// The hash of the stacks block that the checkpoint is anchored to.
+    pub stacks_block_hash: StacksBlockHash,
+    /// The height of the stacks block that the checkpoint is anchored to.
+    pub stacks_block_height: StacksBlockHeight,
+    /// The state summarized by the checkpoint.
+    pub summary: StateSummary,
+}
+
+/// The events in a stacks blockchain, summarized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct StateSummary {
+    /// The number of distinct completed deposit events.
+    pub completed_deposit_events: u64,
+    /// The number of distinct key rotation events.
+    pub key_rotation_events: u64,
+    /// A SHA-256 hash over the contents of all of these events.
+    pub state_hash: [u8; 32],
+}
+
+/// The outcome of verifying a checkpoint of a peer against our own
+/// database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointVerification {
+    /// Our database holds the same state as the peer's.
+    Matches,
+    /// Our database holds a different state than the peer's. The summary
+    /// is the one computed from our database.
+    Diverges(StateSummary),
+    /// We do not know the stacks block that the checkpoint is anchored
+    /// to, so we cannot tell.
+    UnknownStacksBlock,
+}
+
+impl StateCheckpoint {
+    /// Take a checkpoint of the state anchored to the stacks chain tip
+    /// of the given bitcoin chain tip. Returns `None` if we have not seen
+    /// any stacks block anchored to it yet.
+    pub async fn new<S: DbRead>(
+        storage: &S,
+        bitcoin_chain_tip: &BitcoinBlockRef,
+    ) -> Result<Option<Self>, Error> {
+        let Some(stacks_chain_tip) = storage
+            .get_stacks_chain_tip(&bitcoin_chain_tip.block_hash)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let summary = StateSummary::new(storage, &stacks_chain_tip.block_hash).await?;
+        Ok(Some(Self {
+            bitcoin_block_hash: bitcoin_chain_tip.block_hash,
+            bitcoin_block_height: bitcoin_chain_tip.block_height,
+            stacks_block_hash: stacks_chain_tip.block_hash,
+            stacks_block_height: stacks_chain_tip.block_height,
+            summary,
+        }))
+    }
+
+    /// Verify this checkpoint, presumably taken by a peer, against the
+    /// state in our database.
+    pub async fn verify<S: DbRead>(&self, storage: &S) -> Result<CheckpointVerification, Error> {
+        if storage
+            .get_stacks_block(&self.stacks_block_hash)
+            .await?
+            .is_none()
+        {
+            return Ok(CheckpointVerification::UnknownStacksBlock);
+        }
+
+        let summary = StateSummary::new(storage, &self.stacks_block_hash).await?;
+        Ok(self.compare(summary))
+    }
+
+    /// Compare this checkpoint against a summary of the state anchored
+    /// to the same stacks block.
+    pub fn compare(&self, summary: StateSummary) -> CheckpointVerification {
+        if self.summary == summary {
+            CheckpointVerification::Matches
+        } else {
+            CheckpointVerification::Diverges(summary)
+        }
+    }
+}
+
+impl StateSummary {
+    /// Summarize the events in the stacks blockchain that ends at the
+    /// given stacks block.
+    pub async fn new<S: DbRead>(
+        storage: &S,
+        stacks_chain_tip: &StacksBlockHash,
+    ) -> Result<Self, Error> {
+        let completed_deposits = storage
+            .get_stacks_chain_completed_deposit_events(stacks_chain_tip)
+            .await?;
+        let key_rotations = storage
+            .get_stacks_chain_key_rotation_events(stacks_chain_tip)
+            .await?;
+
+        // The same event may have been written more than once, so we
+        // deduplicate the rows before counting and hashing them. The
+        // sets also give us an order that does not depend on when the
+        // rows were written.
+        let completed_deposits = distinct_rows(&completed_deposits);
+        let key_rotations = distinct_rows(&key_rotations);
+
+        let mut hasher = Sha256::new();
+        hash_table::<CompletedDepositEvent>(&mut hasher, &completed_deposits);
+        hash_table::<KeyRotationEvent>(&mut hasher, &key_rotations);
+
+        Ok(Self {
+            completed_deposit_events: completed_deposits.len() as u64,
+            key_rotation_events: key_rotations.len() as u64,
+            state_hash: hasher.finalize().into(),
+        })
+    }
+}
+
+fn distinct_rows<R: ProtectedRow>(rows: &[R]) -> BTreeSet<Vec<u8>> {
+    rows.iter().map(ProtectedRow::row_data).collect()
+}
+
+/// Feed the given rows of a table into the hasher. Rows have variable
+/// lengths, so each is prefixed with its length to keep the encoding
+/// unambiguous.
+fn hash_table<R: ProtectedRow>(hasher: &mut Sha256, rows: &BTreeSet<Vec<u8>>) {
+    hasher.update(R::TABLE_NAME.as_bytes());
+    hasher.update([0]);
+    hasher.update((rows.len() as u64).to_be_bytes());
+    for row in rows {
+        hasher.update((row.len() as u64).to_be_bytes());
+        hasher.update(row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::storage::DbWrite as _;
+    use crate::storage::memory::Store;
+    use crate::storage::model::StacksBlock;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn checkpoints_detect_divergent_state() {
+        let rng = &mut rand::rngs::OsRng;
+        let store = Store::new_shared();
+
+        let parent: StacksBlock = Faker.fake_with_rng(rng);
+        let child = StacksBlock {
+            parent_hash: parent.block_hash,
+            block_height: (*parent.block_height + 1).into(),
+            ..Faker.fake_with_rng(rng)
+        };
+        store.write_stacks_block(&parent).await.unwrap();
+        store.write_stacks_block(&child).await.unwrap();
+
+        let event = CompletedDepositEvent {
+            block_id: parent.block_hash,
+            ..Faker.fake_with_rng(rng)
+        };
+        store.write_completed_deposit_event(&event).await.unwrap();
+
+        // Events in the ancestors of the anchor are part of the state.
+        let summary = StateSummary::new(&store, &child.block_hash).await.unwrap();
+        assert_eq!(summary.completed_deposit_events, 1);
+        assert_eq!(summary.key_rotation_events, 0);
+
+        let checkpoint = StateCheckpoint {
+            bitcoin_block_hash: child.bitcoin_anchor,
+            bitcoin_block_height: Faker.fake_with_rng(rng),
+            stacks_block_hash: child.block_hash,
+            stacks_block_height: child.block_height,
+            summary,
+        };
+        let verification = checkpoint.verify(&store).await.unwrap();
+        assert_eq!(verification, CheckpointVerification::Matches);
+
+        // An event that the peer did not see makes our state diverge.
+        let missed = CompletedDepositEvent {
+            block_id: child.block_hash,
+            ..Faker.fake_with_rng(rng)
+        };
+        store.write_completed_deposit_event(&missed).await.unwrap();
+        let verification = checkpoint.verify(&store).await.unwrap();
+        let CheckpointVerification::Diverges(ours) = verification else {
+            panic!("expected the checkpoint to diverge, got {verification:?}");
+        };
+        assert_eq!(ours.completed_deposit_events, 2);
+
+        // Events in other forks are not part of the state.
+        let parent_checkpoint = StateCheckpoint {
+            stacks_block_hash: parent.block_hash,
+            ..checkpoint
+        };
+        let verification = parent_checkpoint.verify(&store).await.unwrap();
+        assert_eq!(verification, CheckpointVerification::Matches);
+
+        let unknown = StateCheckpoint {
+            stacks_block_hash: Faker.fake_with_rng(rng),
+            ..checkpoint
+        };
+        let verification = unknown.verify(&store).await.unwrap();
+        assert_eq!(verification, CheckpointVerification::UnknownStacksBlock);
+    }
+}
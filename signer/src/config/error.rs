@@ -22,6 +22,15 @@ pub enum SignerConfigError {
     #[error("The network set in the config must match the network kind of the deployer address")]
     NetworkDeployerMismatch,
 
+    /// The additional registry contracts must be sbtc-registry contracts
+    /// deployed on the network set in the config.
+    #[error("Registry contract {0} is not an sbtc-registry on the configured network")]
+    InvalidRegistryContract(String),
+
+    /// At least one deposit script version must be accepted.
+    #[error("The config must accept at least one deposit script version")]
+    NoDepositScriptVersions,
+
     /// Invalid P2P URI
     #[error("Invalid P2P URI: Only schemes 'tcp' and 'quic-v1' are supported; got '{0}'")]
     InvalidP2PScheme(String),
@@ -50,11 +59,9 @@ pub enum SignerConfigError {
     #[error("Invalid P2P URI: Host is required")]
     P2PHostRequired,
 
-    /// When the network kind is 'mainnet' or 'testnet', at least one P2P seed peer is required.
-    /// Otherwise, we'll allow mDNS to discover any local peers (for testing).
-    #[error(
-        "At least one P2P seed peer is required when the network kind is 'mainnet' or 'testnet'."
-    )]
+    /// When the network kind is anything other than 'regtest', at least one P2P seed peer is
+    /// required. Otherwise, we'll allow mDNS to discover any local peers (for testing).
+    #[error("At least one P2P seed peer is required when the network kind is not 'regtest'.")]
     P2PSeedPeerRequired,
 
     /// A public endpoint uses a protocol which is not enabled in the listen_on
@@ -78,6 +85,29 @@ pub enum SignerConfigError {
     #[error("The provided requests processing delay must be smaller than {0}s, got {1}s")]
     InvalidRequestsProcessingDelay(u64, u64),
 
+    /// An error returned when the maximum sweep transaction size is above
+    /// the standard transaction size.
+    #[error("max_sweep_tx_vsize must be at most {0}, got {1}")]
+    InvalidMaxSweepTxVsize(u64, u32),
+
+    /// An error returned when the sweep fee fraction is greater than one.
+    #[error("sweep_fee_max_fraction_bps must be at most 10000, got {0}")]
+    InvalidSweepFeeFraction(u16),
+
+    /// An error returned when the high-impact quorum threshold is lower
+    /// than the ordinary one or greater than the number of signers.
+    #[error(
+        "high_impact_signatures_required must be between bootstrap_signatures_required ({ordinary}) and the size of the bootstrap signing set ({num_signers}), got {high_impact}"
+    )]
+    InvalidHighImpactSignaturesRequired {
+        /// The configured high-impact threshold.
+        high_impact: u16,
+        /// The ordinary signing threshold.
+        ordinary: u16,
+        /// The number of signers in the bootstrap signing set.
+        num_signers: usize,
+    },
+
     /// An error returned for duration parameters that must be positive.
     #[error("Duration for {0} must be nonzero")]
     ZeroDurationForbidden(&'static str),
@@ -92,4 +122,8 @@ pub enum SignerConfigError {
     /// See https://github.com/stacks-sbtc/sbtc/issues/1694
     #[error("Bootstrap signer set must be at most 16 signers, but it contains {0} signers")]
     TooManySigners(usize),
+
+    /// Invalid integrity key
+    #[error("The integrity key must be 64 hex characters long")]
+    InvalidIntegrityKey,
 }
@@ -0,0 +1,143 @@
+//! # Configuration fingerprints
+//!
+//! Every signer in the federation has to validate requests and
+//! transactions in the same way, or they will disagree on what to sign
+//! and signing rounds will fail in confusing ways. This module extracts
+//! the parts of the configuration that determine these validation
+//! policies into a [`ConsensusConfig`], and hashes it into a fingerprint
+//! that signers exchange in their heartbeats.
+//!
+//! The [`ConsensusConfig`] only holds values that the members of the
+//! federation are expected to agree on, so it contains no secrets or
+//! endpoints and can be shown to anyone who can reach the signer's API.
+
+use sbtc::deposits::DepositScriptVersion;
+use serde::Serialize;
+use sha2::Digest as _;
+use sha2::Sha256;
+
+use crate::storage::model::BitcoinBlockHeight;
+
+use super::Settings;
+
+/// The consensus-critical part of the signer's configuration.
+///
+/// Two signers with the same [`ConsensusConfig`] apply the same
+/// validation policies. Fields are hashed in their declaration order, so
+/// new fields should be appended at the end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConsensusConfig {
+    /// The network that the signer operates on.
+    pub network: String,
+    /// The address that deployed the sBTC smart contracts.
+    pub deployer: String,
+    /// The public keys of the bootstrap signing set, in sorted order.
+    pub bootstrap_signing_set: Vec<String>,
+    /// The number of signatures required by the bootstrap signing set.
+    pub bootstrap_signatures_required: u16,
+    /// The aggregate key that the signers were bootstrapped with, if any.
+    pub bootstrap_aggregate_key: Option<String>,
+    /// The number of bitcoin blocks that the signer looks back for
+    /// pending requests.
+    pub context_window: u16,
+    /// The bitcoin block height at which sBTC starts.
+    pub sbtc_bitcoin_start_height: Option<BitcoinBlockHeight>,
+    /// The maximum number of deposits in a single sweep transaction.
+    pub max_deposits_per_bitcoin_tx: u16,
+    /// The bitcoin block height after which DKG may be run.
+    pub dkg_min_bitcoin_block_height: Option<BitcoinBlockHeight>,
+    /// The number of DKG rounds that the signers run.
+    pub dkg_target_rounds: u32,
+    /// The number of bitcoin blocks in which a new aggregate key must be
+    /// verified.
+    pub dkg_verification_window: u16,
+    /// The maximum fee, in microSTX, for stacks transactions.
+    pub stacks_fees_max_ustx: u64,
+    /// The maximum fee, in satoshis, for sweep transactions.
+    pub sweep_fee_max_sats: u64,
+    /// The maximum fee of a sweep transaction as a fraction, in basis
+    /// points, of the amount swept.
+    pub sweep_fee_max_fraction_bps: u16,
+    /// The number of signatures required for high-impact actions, if
+    /// different from the ordinary threshold.
+    pub high_impact_signatures_required: Option<u16>,
+    /// The swept amount, in satoshis, above which a sweep is high impact.
+    pub high_impact_sweep_amount: Option<u64>,
+    /// Whether requests are screened by a blocklist client.
+    pub blocklist_client_enabled: bool,
+    /// The deposit script templates that deposits may follow, in the
+    /// order that they are tried.
+    pub deposit_script_versions: Vec<DepositScriptVersion>,
+    /// Whether withdrawals to the same recipient share a sweep output.
+    pub aggregate_withdrawal_outputs: bool,
+    /// Whether sweep transactions set an anti-fee-sniping lock time.
+    pub sweep_lock_time: bool,
+}
+
+impl ConsensusConfig {
+    /// Extract the consensus-critical part of the given configuration.
+    pub fn new(settings: &Settings) -> Self {
+        let signer = &settings.signer;
+        Self {
+            network: signer.network.to_string(),
+            deployer: signer.deployer.to_string(),
+            bootstrap_signing_set: signer
+                .bootstrap_signing_set
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            bootstrap_signatures_required: signer.bootstrap_signatures_required,
+            bootstrap_aggregate_key: signer.bootstrap_aggregate_key.map(|key| key.to_string()),
+            context_window: signer.context_window,
+            sbtc_bitcoin_start_height: signer.sbtc_bitcoin_start_height,
+            max_deposits_per_bitcoin_tx: signer.max_deposits_per_bitcoin_tx.get(),
+            dkg_min_bitcoin_block_height: signer.dkg_min_bitcoin_block_height,
+            dkg_target_rounds: signer.dkg_target_rounds.get(),
+            dkg_verification_window: signer.dkg_verification_window,
+            stacks_fees_max_ustx: signer.stacks_fees_max_ustx.get(),
+            sweep_fee_max_sats: signer.sweep_fee_max_sats.get(),
+            sweep_fee_max_fraction_bps: signer.sweep_fee_max_fraction_bps.get(),
+            high_impact_signatures_required: signer
+                .high_impact_signatures_required
+                .map(|required| required.get()),
+            high_impact_sweep_amount: signer.high_impact_sweep_amount.map(|amount| amount.get()),
+            blocklist_client_enabled: settings.blocklist_client.is_some(),
+            deposit_script_versions: signer.deposit_script_decoders().versions().to_vec(),
+            aggregate_withdrawal_outputs: signer.aggregate_withdrawal_outputs,
+            sweep_lock_time: signer.sweep_lock_time,
+        }
+    }
+
+    /// The SHA-256 hash of the JSON encoding of this configuration.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        // Serializing a struct of plain values into JSON cannot fail.
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        Sha256::digest(json).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::*;
+
+    #[test]
+    fn fingerprint_changes_with_consensus_config_only() {
+        crate::testing::clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let fingerprint = ConsensusConfig::new(&settings).fingerprint();
+        assert_eq!(ConsensusConfig::new(&settings).fingerprint(), fingerprint);
+
+        // Endpoints and local tuning knobs do not affect the fingerprint.
+        let mut local = settings.clone();
+        local.signer.db_endpoint = "postgres://other:5432/signer".parse().unwrap();
+        local.signer.bitcoin_processing_delay = std::time::Duration::from_secs(7);
+        assert_eq!(ConsensusConfig::new(&local).fingerprint(), fingerprint);
+
+        let mut divergent = settings.clone();
+        divergent.signer.sweep_fee_max_sats = NonZeroU64::new(1).unwrap();
+        assert_ne!(ConsensusConfig::new(&divergent).fingerprint(), fingerprint);
+    }
+}
@@ -2,9 +2,14 @@
 use config::Config;
 use config::ConfigError;
 use config::Environment;
+use clarity::vm::representations::ContractName;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::types::StandardPrincipalData;
 use config::File;
 use libp2p::Multiaddr;
 use libp2p::multiaddr::Protocol;
+use sbtc::deposits::DepositScriptDecoders;
+use sbtc::deposits::DepositScriptVersion;
 use serde::Deserialize;
 use stacks_common::types::chainstate::StacksAddress;
 use std::collections::BTreeSet;
@@ -14,22 +19,43 @@ use std::num::NonZeroU64;
 use std::path::Path;
 use url::Url;
 
+use crate::DEFAULT_INTEGRITY_CHECK_INTERVAL_SECONDS;
 use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use crate::DEFAULT_MAX_WITHDRAWAL_REJECTS_PER_TENURE;
+use crate::DEFAULT_REQUEST_QUARANTINE_THRESHOLD;
+use crate::DEFAULT_STACKS_FEE_BUDGET_PERIOD_SECONDS;
+use crate::DEFAULT_STALE_DEPOSIT_CHECK_INTERVAL_SECONDS;
+use crate::DEFAULT_STALE_DEPOSIT_MIN_AGE_SECONDS;
+use crate::DEFAULT_STX_LOW_BALANCE_THRESHOLD_USTX;
+use crate::DEFAULT_SWEEP_COMPLETION_DEADLINE;
+use crate::DEFAULT_SWEEP_FEE_MAX_FRACTION_BPS;
+use crate::DEFAULT_SWEEP_FEE_MAX_SATS;
+use crate::MAX_STANDARD_TX_VSIZE;
+use crate::bitcoin::utxo::SweepFeeLimits;
 use crate::config::error::SignerConfigError;
+use crate::config::serialization::contract_identifier_deserializer_vec;
 use crate::config::serialization::duration_milliseconds_deserializer;
 use crate::config::serialization::duration_seconds_deserializer;
+use crate::config::serialization::optional_integrity_key_deserializer;
+use crate::config::serialization::optional_private_key_deserializer;
+use crate::config::serialization::optional_url_deserializer;
 use crate::config::serialization::p2p_multiaddr_deserializer_vec;
 use crate::config::serialization::parse_stacks_address;
 use crate::config::serialization::private_key_deserializer;
 use crate::config::serialization::url_deserializer_single;
 use crate::config::serialization::url_deserializer_vec;
+use crate::export::ExportFormat;
+use crate::integrity::IntegrityKey;
 use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::network::libp2p::MultiaddrExt as _;
+use crate::quorum::QuorumThresholds;
+use crate::stacks::contracts::SmartContract;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::model::BitcoinBlockHeight;
 
 mod error;
+pub mod fingerprint;
 mod serialization;
 
 /// Maximum configurable delay (in seconds) before processing new Bitcoin blocks.
@@ -61,8 +87,14 @@ trait Validatable {
 pub enum NetworkKind {
     /// The mainnet network
     Mainnet,
-    /// The testnet network
+    /// The testnet network. On bitcoin this is testnet3.
     Testnet,
+    /// The bitcoin testnet4 network. This is equivalent to Testnet when
+    /// constructing Stacks addresses and transactions.
+    Testnet4,
+    /// The bitcoin signet network. This is equivalent to Testnet when
+    /// constructing Stacks addresses and transactions.
+    Signet,
     /// The regtest network. This is equivalent to Testnet when
     /// constructing Stacks addresses and transactions.
     Regtest,
@@ -82,6 +114,8 @@ impl std::fmt::Display for NetworkKind {
         match self {
             NetworkKind::Mainnet => write!(f, "mainnet"),
             NetworkKind::Testnet => write!(f, "testnet"),
+            NetworkKind::Testnet4 => write!(f, "testnet4"),
+            NetworkKind::Signet => write!(f, "signet"),
             NetworkKind::Regtest => write!(f, "regtest"),
         }
     }
@@ -91,7 +125,9 @@ impl From<NetworkKind> for bitcoin::KnownHrp {
     fn from(value: NetworkKind) -> Self {
         match value {
             NetworkKind::Mainnet => bitcoin::KnownHrp::Mainnet,
-            NetworkKind::Testnet => bitcoin::KnownHrp::Testnets,
+            NetworkKind::Testnet | NetworkKind::Testnet4 | NetworkKind::Signet => {
+                bitcoin::KnownHrp::Testnets
+            }
             NetworkKind::Regtest => bitcoin::KnownHrp::Regtest,
         }
     }
@@ -102,6 +138,8 @@ impl From<NetworkKind> for bitcoin::Network {
         match network {
             NetworkKind::Mainnet => bitcoin::Network::Bitcoin,
             NetworkKind::Testnet => bitcoin::Network::Testnet,
+            NetworkKind::Testnet4 => bitcoin::Network::Testnet4,
+            NetworkKind::Signet => bitcoin::Network::Signet,
             NetworkKind::Regtest => bitcoin::Network::Regtest,
         }
     }
@@ -112,6 +150,12 @@ impl NetworkKind {
     pub fn is_mainnet(&self) -> bool {
         self == &NetworkKind::Mainnet
     }
+
+    /// Returns whether the network is a public network, where other
+    /// signers cannot be discovered locally.
+    pub fn is_public(&self) -> bool {
+        self != &NetworkKind::Regtest
+    }
 }
 
 /// Top-level configuration for the signer
@@ -127,6 +171,15 @@ pub struct Settings {
     pub stacks: StacksConfig,
     /// Emily client configuration
     pub emily: EmilyClientConfig,
+    /// Outbound webhook configuration
+    pub webhooks: Option<WebhooksConfig>,
+    /// Configuration of the scheduled export of analytics data to an
+    /// S3-compatible bucket.
+    pub analytics_export: Option<AnalyticsExportConfig>,
+    /// Configuration for the connections of the bitcoin RPC, stacks RPC
+    /// and Emily API clients.
+    #[serde(default)]
+    pub outbound: OutboundConfig,
 }
 
 /// Configuration used for the [`BitcoinCoreClient`](sbtc::rpc::BitcoinCoreClient).
@@ -201,6 +254,17 @@ pub struct P2PNetworkConfig {
     /// testing and development.
     #[serde(default)]
     pub enable_mdns: bool,
+    /// Enable the bulk sync topic for the P2P network. When enabled, the
+    /// signer answers requests from lagging peers for summaries of recent
+    /// bitcoin blocks, and asks its peers for them when it falls behind.
+    #[serde(default)]
+    pub enable_bulk_sync: bool,
+    /// Publish DKG and signing messages on their own gossipsub topics,
+    /// rather than on the operational `sbtc-signer` topic. Signers always
+    /// listen on all topics, so this should only be enabled once every
+    /// signer runs a version that does.
+    #[serde(default)]
+    pub split_gossip_topics: bool,
 }
 
 impl P2PNetworkConfig {
@@ -216,9 +280,7 @@ impl P2PNetworkConfig {
 
 impl Validatable for P2PNetworkConfig {
     fn validate(&self, cfg: &Settings) -> Result<(), ConfigError> {
-        if [NetworkKind::Mainnet, NetworkKind::Testnet].contains(&cfg.signer.network)
-            && self.seeds.is_empty()
-        {
+        if cfg.signer.network.is_public() && self.seeds.is_empty() {
             return Err(ConfigError::Message(
                 SignerConfigError::P2PSeedPeerRequired.to_string(),
             ));
@@ -278,6 +340,350 @@ impl BlocklistClientConfig {
         std::time::Duration::from_secs(1)
     }
 }
+
+/// Outbound webhook configuration.
+#[derive(Deserialize, Clone, Debug)]
+pub struct WebhooksConfig {
+    /// The URLs that are sent notifications about completed deposits,
+    /// fulfilled withdrawals and key rotations, in addition to any that
+    /// are registered through the API.
+    #[serde(default, deserialize_with = "url_deserializer_vec")]
+    pub endpoints: Vec<Url>,
+    /// The maximum number of times delivery of a notification to a single
+    /// subscriber is attempted.
+    #[serde(default = "WebhooksConfig::max_attempts_default")]
+    pub max_attempts: NonZeroU32,
+    /// The delay, in milliseconds, before the first retry of a failed
+    /// delivery. The delay doubles after every failed attempt.
+    #[serde(
+        default = "WebhooksConfig::retry_delay_default",
+        deserialize_with = "duration_milliseconds_deserializer"
+    )]
+    pub retry_delay: std::time::Duration,
+}
+
+impl WebhooksConfig {
+    fn max_attempts_default() -> NonZeroU32 {
+        NonZeroU32::new(5).expect("5 is non-zero")
+    }
+
+    fn retry_delay_default() -> std::time::Duration {
+        std::time::Duration::from_secs(1)
+    }
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            max_attempts: Self::max_attempts_default(),
+            retry_delay: Self::retry_delay_default(),
+        }
+    }
+}
+
+impl Validatable for WebhooksConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        for endpoint in &self.endpoints {
+            if !["http", "https"].contains(&endpoint.scheme()) {
+                return Err(ConfigError::Message(
+                    "[webhooks.endpoints] Invalid URL scheme: must be HTTP or HTTPS".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration of the scheduled export of analytics data to an
+/// S3-compatible bucket, see [`crate::analytics_export`].
+#[derive(Deserialize, Clone)]
+pub struct AnalyticsExportConfig {
+    /// The endpoint of the object storage service. Objects are addressed
+    /// path-style, as `<endpoint>/<bucket>/<key>`.
+    #[serde(deserialize_with = "url_deserializer_single")]
+    pub endpoint: Url,
+    /// The bucket that the export files are uploaded to.
+    pub bucket: String,
+    /// The region of the bucket, which is part of the request signatures.
+    #[serde(default = "AnalyticsExportConfig::region_default")]
+    pub region: String,
+    /// The prefix of the keys of the uploaded objects.
+    #[serde(default)]
+    pub prefix: String,
+    /// The ID of the access key that uploads are signed with.
+    pub access_key_id: String,
+    /// The secret of the access key that uploads are signed with.
+    pub secret_access_key: String,
+    /// The length, in seconds, of the periods that are exported. A period
+    /// is exported once it is over, so this is also how often new files
+    /// are uploaded.
+    #[serde(
+        default = "AnalyticsExportConfig::interval_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub interval: std::time::Duration,
+    /// The file format of the export.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+impl AnalyticsExportConfig {
+    fn region_default() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn interval_default() -> std::time::Duration {
+        std::time::Duration::from_secs(3600)
+    }
+}
+
+impl std::fmt::Debug for AnalyticsExportConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnalyticsExportConfig")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("prefix", &self.prefix)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field("interval", &self.interval)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl Validatable for AnalyticsExportConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        if !["http", "https"].contains(&self.endpoint.scheme()) {
+            return Err(ConfigError::Message(
+                "[analytics_export.endpoint] Invalid URL scheme: must be HTTP or HTTPS".to_string(),
+            ));
+        }
+        if self.bucket.is_empty() {
+            return Err(ConfigError::Message(
+                "[analytics_export.bucket] Must not be empty".to_string(),
+            ));
+        }
+        if self.interval.as_secs() < MIN_ANALYTICS_EXPORT_INTERVAL_SECONDS {
+            return Err(ConfigError::Message(format!(
+                "[analytics_export.interval] Must be at least \
+                {MIN_ANALYTICS_EXPORT_INTERVAL_SECONDS} seconds"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The shortest period that the analytics export may be configured with,
+/// so that the signer does not flood the bucket with tiny files.
+const MIN_ANALYTICS_EXPORT_INTERVAL_SECONDS: u64 = 60;
+
+/// The DNS resolver used by the HTTP clients.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsResolver {
+    /// The resolver of the operating system, which is called on a blocking
+    /// thread pool.
+    #[default]
+    System,
+    /// An asynchronous resolver that queries the DNS servers of the system
+    /// configuration directly, without blocking any threads.
+    Async,
+}
+
+/// Configuration for the connections of the bitcoin RPC, stacks RPC and
+/// Emily API clients.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OutboundConfig {
+    /// The proxy that connections are routed through. The `socks5`,
+    /// `socks5h`, `http` and `https` schemes are supported, and with
+    /// `socks5h` host names are resolved by the proxy. The bitcoin RPC
+    /// client only supports SOCKS5 proxies.
+    #[serde(default, deserialize_with = "optional_url_deserializer")]
+    pub proxy: Option<Url>,
+    /// The hosts that are connected to directly rather than through the
+    /// proxy. An entry matches the host itself and all of its subdomains.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// The DNS resolver used by the stacks RPC and Emily API clients.
+    #[serde(default)]
+    pub dns_resolver: DnsResolver,
+    /// The maximum number of idle connections that the stacks RPC and
+    /// Emily API clients keep open to each host, ready for the next
+    /// request.
+    #[serde(default = "OutboundConfig::pool_max_idle_per_host_default")]
+    pub pool_max_idle_per_host: usize,
+    /// How long, in seconds, an idle connection is kept open before it is
+    /// closed.
+    #[serde(
+        default = "OutboundConfig::pool_idle_timeout_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub pool_idle_timeout: std::time::Duration,
+    /// How often, in seconds, TCP keep-alive probes are sent on open
+    /// connections, so that idle connections are not silently dropped by
+    /// the network. Zero disables the probes.
+    #[serde(
+        default = "OutboundConfig::keep_alive_interval_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub keep_alive_interval: std::time::Duration,
+    /// Whether the stacks RPC and Emily API clients may use HTTP/2, which
+    /// is negotiated with hosts that support it over TLS. Requests are
+    /// then multiplexed over a single connection to each host.
+    #[serde(default)]
+    pub http2: bool,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            no_proxy: Vec::new(),
+            dns_resolver: DnsResolver::default(),
+            pool_max_idle_per_host: Self::pool_max_idle_per_host_default(),
+            pool_idle_timeout: Self::pool_idle_timeout_default(),
+            keep_alive_interval: Self::keep_alive_interval_default(),
+            http2: false,
+        }
+    }
+}
+
+impl OutboundConfig {
+    /// The URL schemes of the supported proxies.
+    const PROXY_SCHEMES: [&str; 4] = ["socks5", "socks5h", "http", "https"];
+
+    fn pool_max_idle_per_host_default() -> usize {
+        32
+    }
+
+    fn pool_idle_timeout_default() -> std::time::Duration {
+        std::time::Duration::from_secs(90)
+    }
+
+    fn keep_alive_interval_default() -> std::time::Duration {
+        std::time::Duration::from_secs(30)
+    }
+
+    /// The interval of the TCP keep-alive probes, if they are enabled.
+    fn keep_alive(&self) -> Option<std::time::Duration> {
+        Some(self.keep_alive_interval).filter(|interval| !interval.is_zero())
+    }
+
+    /// Return the proxy that connections to the given endpoint are routed
+    /// through, if any.
+    pub fn proxy_for(&self, endpoint: &Url) -> Option<&Url> {
+        let host = endpoint.host_str()?.trim_matches(['[', ']']);
+        let is_bypassed = self.no_proxy.iter().any(|entry| {
+            let entry = entry.trim().trim_start_matches('.');
+            entry == "*"
+                || host.eq_ignore_ascii_case(entry)
+                || host
+                    .to_ascii_lowercase()
+                    .ends_with(&format!(".{}", entry.to_ascii_lowercase()))
+        });
+
+        self.proxy.as_ref().filter(|_| !is_bypassed)
+    }
+
+    /// Whether the given proxy is a SOCKS5 proxy.
+    pub fn is_socks5(proxy: &Url) -> bool {
+        ["socks5", "socks5h"].contains(&proxy.scheme())
+    }
+
+    /// Return a builder for an HTTP client that connects to the given
+    /// endpoint with these settings.
+    pub fn http_client_builder(&self, endpoint: &Url) -> reqwest::Result<reqwest::ClientBuilder> {
+        let builder = reqwest::Client::builder()
+            .trust_dns(self.dns_resolver == DnsResolver::Async)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.keep_alive());
+        let builder = if self.http2 {
+            builder
+                .http2_adaptive_window(true)
+                .http2_keep_alive_interval(self.keep_alive())
+                .http2_keep_alive_while_idle(true)
+        } else {
+            builder.http1_only()
+        };
+
+        match self.proxy_for(endpoint) {
+            Some(proxy) => Ok(builder.proxy(reqwest::Proxy::all(proxy.as_str())?)),
+            // The proxies in the environment are ignored for bypassed hosts.
+            None if self.proxy.is_some() => Ok(builder.no_proxy()),
+            None => Ok(builder),
+        }
+    }
+
+    /// Return a builder for an HTTP client, of the version of reqwest used
+    /// by the Emily API client, that connects to the given endpoint with
+    /// these settings.
+    pub fn emily_http_client_builder(
+        &self,
+        endpoint: &Url,
+    ) -> emily_reqwest::Result<emily_reqwest::ClientBuilder> {
+        let builder = emily_reqwest::Client::builder()
+            .hickory_dns(self.dns_resolver == DnsResolver::Async)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.keep_alive());
+        let builder = if self.http2 {
+            builder
+                .http2_adaptive_window(true)
+                .http2_keep_alive_interval(self.keep_alive())
+                .http2_keep_alive_while_idle(true)
+        } else {
+            builder.http1_only()
+        };
+
+        match self.proxy_for(endpoint) {
+            Some(proxy) => Ok(builder.proxy(emily_reqwest::Proxy::all(proxy.as_str())?)),
+            None if self.proxy.is_some() => Ok(builder.no_proxy()),
+            None => Ok(builder),
+        }
+    }
+}
+
+impl Validatable for OutboundConfig {
+    fn validate(&self, cfg: &Settings) -> Result<(), ConfigError> {
+        let Some(proxy) = &self.proxy else {
+            return Ok(());
+        };
+
+        if !Self::PROXY_SCHEMES.contains(&proxy.scheme()) {
+            return Err(ConfigError::Message(
+                "[outbound.proxy] Invalid URL scheme: must be SOCKS5, SOCKS5H, HTTP or HTTPS"
+                    .to_string(),
+            ));
+        }
+
+        if proxy.host_str().is_none() || proxy.port_or_known_default().is_none() {
+            return Err(ConfigError::Message(
+                "[outbound.proxy] Invalid URL: host and port are required".to_string(),
+            ));
+        }
+
+        let proxies_bitcoin_rpc = cfg
+            .bitcoin
+            .rpc_endpoints
+            .iter()
+            .any(|endpoint| self.proxy_for(endpoint).is_some());
+        if !Self::is_socks5(proxy) && proxies_bitcoin_rpc {
+            return Err(ConfigError::Message(
+                "[outbound.proxy] The bitcoin RPC client only supports SOCKS5 proxies; \
+                 list the bitcoin RPC hosts in outbound.no_proxy to use an HTTP proxy"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// Emily API configuration.
 #[derive(Deserialize, Clone, Debug)]
 pub struct EmilyClientConfig {
@@ -287,6 +693,41 @@ pub struct EmilyClientConfig {
     /// Pagination timeout in seconds.
     #[serde(deserialize_with = "duration_seconds_deserializer")]
     pub pagination_timeout: std::time::Duration,
+    /// The minimum amount of time, in seconds, between polls of Emily for
+    /// deposits that were updated since the previous poll. Polling happens
+    /// at this interval while Emily has new deposits.
+    #[serde(
+        default = "EmilyClientConfig::deposit_poll_min_interval_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub deposit_poll_min_interval: std::time::Duration,
+    /// The maximum amount of time, in seconds, between polls of Emily for
+    /// deposits. The interval doubles after each poll without new deposits
+    /// until it reaches this maximum.
+    #[serde(
+        default = "EmilyClientConfig::deposit_poll_max_interval_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub deposit_poll_max_interval: std::time::Duration,
+    /// The number of bitcoin blocks between full syncs of the deposits in
+    /// Emily. The other polls only fetch the deposits that Emily updated
+    /// since the previous poll.
+    #[serde(default = "EmilyClientConfig::deposit_full_sync_blocks_default")]
+    pub deposit_full_sync_blocks: NonZeroU16,
+}
+
+impl EmilyClientConfig {
+    fn deposit_poll_min_interval_default() -> std::time::Duration {
+        std::time::Duration::from_secs(5)
+    }
+
+    fn deposit_poll_max_interval_default() -> std::time::Duration {
+        std::time::Duration::from_secs(60)
+    }
+
+    fn deposit_full_sync_blocks_default() -> NonZeroU16 {
+        NonZeroU16::new(6).expect("6 is non-zero")
+    }
 }
 
 impl Validatable for EmilyClientConfig {
@@ -313,6 +754,18 @@ impl Validatable for EmilyClientConfig {
             }
         }
 
+        if self.deposit_poll_min_interval.is_zero() {
+            return Err(ConfigError::Message(
+                "[emily.deposit_poll_min_interval] Must be greater than zero".to_string(),
+            ));
+        }
+        if self.deposit_poll_min_interval > self.deposit_poll_max_interval {
+            return Err(ConfigError::Message(
+                "[emily.deposit_poll_max_interval] Must not be less than the minimum interval"
+                    .to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -323,20 +776,75 @@ pub struct SignerConfig {
     /// The private key of the signer
     #[serde(deserialize_with = "private_key_deserializer")]
     pub private_key: PrivateKey,
+    /// The private key that the signer is rotating its identity to. When
+    /// set, the signer announces the new public key to its peers.
+    #[serde(default, deserialize_with = "optional_private_key_deserializer")]
+    pub next_private_key: Option<PrivateKey>,
+    /// The private key that the signer used before rotating its identity
+    /// to `private_key`. When set, it is the key of the signer's slot in
+    /// the signer set, used for its messages, DKG shares and Stacks
+    /// signatures.
+    #[serde(default, deserialize_with = "optional_private_key_deserializer")]
+    pub previous_private_key: Option<PrivateKey>,
     /// P2P network configuration
     pub p2p: P2PNetworkConfig,
     /// P2P network configuration
     pub network: NetworkKind,
     /// Event observer server configuration
     pub event_observer: EventObserverConfig,
+    /// Limits on the requests to, and connections of, the signer API
+    /// server, and the TLS that it is served with.
+    #[serde(default)]
+    pub api: ApiConfig,
     /// The address of the deployer of the sBTC smart contracts.
     #[serde(deserialize_with = "parse_stacks_address")]
     pub deployer: StacksAddress,
+    /// The sbtc-registry contracts whose events are observed in addition
+    /// to the one of the [`deployer`](SignerConfig::deployer), for example
+    /// while the sBTC contracts are migrated to a new deployment.
+    #[serde(default, deserialize_with = "contract_identifier_deserializer_vec")]
+    pub registry_contracts: Vec<QualifiedContractIdentifier>,
+    /// The versions of the deposit script templates that deposits may
+    /// follow. Deposits with scripts that follow any other template are
+    /// rejected when they are validated.
+    #[serde(default = "SignerConfig::deposit_script_versions_default")]
+    pub deposit_script_versions: Vec<DepositScriptVersion>,
+    /// Whether withdrawal requests that pay the same recipient are
+    /// serviced by a single output of the sweep transaction.
+    #[serde(default)]
+    pub aggregate_withdrawal_outputs: bool,
+    /// Whether sweep transactions set their lock time to the height of the
+    /// bitcoin chain tip, to discourage fee sniping. When this is off the
+    /// lock time of sweep transactions is zero.
+    #[serde(default)]
+    pub sweep_lock_time: bool,
+    /// Whether the signer starts in read-only maintenance mode, where it
+    /// observes the chains, serves the API and records events, but does
+    /// not vote, sign or coordinate. The mode can also be toggled through
+    /// the `/maintenance` endpoint of the API.
+    #[serde(default)]
+    pub maintenance_mode: bool,
+    /// Whether the signer runs the serialization audit, which checks that
+    /// consensus-critical structures serialize deterministically, and
+    /// cross-checks the digest of the audit with the other signers in
+    /// heartbeats. See [`crate::serialization_audit`].
+    #[serde(default)]
+    pub serialization_audit: bool,
     /// The postgres database endpoint
     #[serde(deserialize_with = "url_deserializer_single")]
     pub db_endpoint: Url,
     /// The scrape endpoint for exporting metrics for Prometheus.
     pub prometheus_exporter_endpoint: Option<std::net::SocketAddr>,
+    /// Whether the signer API serves metrics for Prometheus at `/metrics`,
+    /// in addition to or instead of the dedicated scrape endpoint.
+    #[serde(default)]
+    pub prometheus_api_metrics: bool,
+    /// The `host:port` endpoint of a StatsD server to send metrics to.
+    pub statsd_endpoint: Option<String>,
+    /// The OTLP/HTTP endpoint of an OpenTelemetry collector to push
+    /// metrics to.
+    #[serde(default, deserialize_with = "optional_url_deserializer")]
+    pub otlp_metrics_endpoint: Option<Url>,
     /// The public keys of the signer sit during the bootstrapping phase of
     /// the signers.
     pub bootstrap_signing_set: BTreeSet<PublicKey>,
@@ -353,6 +861,17 @@ pub struct SignerConfig {
     /// (allowing the bitcoin block to propagate to the others signers)
     #[serde(deserialize_with = "duration_seconds_deserializer")]
     pub requests_processing_delay: std::time::Duration,
+    /// The maximum number of seconds the coordinator will wait, after
+    /// handling the bitcoin transactions of a new bitcoin block, for a
+    /// stacks tenure to start or be extended in that block before it
+    /// submits stacks transactions. The stacks chain only learns about the
+    /// bitcoin block once that happens, so transactions that depend on it
+    /// cannot be mined before then. Zero disables the wait.
+    #[serde(
+        default = "SignerConfig::stacks_tenure_wait_default",
+        deserialize_with = "duration_seconds_deserializer"
+    )]
+    pub stacks_tenure_wait: std::time::Duration,
     /// How many bitcoin blocks back from the chain tip the signer will
     /// look for requests.
     pub context_window: u16,
@@ -378,6 +897,18 @@ pub struct SignerConfig {
     /// receiving a DKG begin message before relaying to give the other
     /// signers time to catch up.
     pub dkg_begin_pause: Option<u64>,
+    /// The seed of the random number generator shared through the
+    /// [`Context`](crate::context::Context). When set, all randomized
+    /// behavior that is not security critical, like the jitter of retry
+    /// delays, is deterministic, which helps with reproducing test
+    /// failures and bug reports. Key generation and signing nonces never
+    /// use this generator. When this is not set, the generator is seeded
+    /// from the operating system.
+    pub rng_seed: Option<u64>,
+    /// The path of the local socket over which a new signer process takes
+    /// over from this one during an upgrade. When this is not set,
+    /// upgrades require a plain restart. See [`crate::handover`].
+    pub handover_socket: Option<std::path::PathBuf>,
     /// The minimum bitcoin block height for which the sbtc signers will
     /// backfill bitcoin blocks to.
     pub sbtc_bitcoin_start_height: Option<BitcoinBlockHeight>,
@@ -388,6 +919,51 @@ pub struct SignerConfig {
     /// arrives. The default here is controlled by the
     /// [`MAX_DEPOSITS_PER_BITCOIN_TX`] constant
     pub max_deposits_per_bitcoin_tx: NonZeroU16,
+    /// The maximum virtual size of each sweep transaction that the
+    /// coordinator constructs. When the pending requests do not fit in a
+    /// single transaction of this size, they are split across a chain of
+    /// sweep transactions in the same tenure. This must be at most
+    /// [`MAX_STANDARD_TX_VSIZE`], which is also the default.
+    pub max_sweep_tx_vsize: NonZeroU32,
+    /// The maximum number of withdrawal reject transactions that the
+    /// coordinator signs and submits during a single tenure. The sign
+    /// requests for these transactions are sent out together, with
+    /// sequential nonces, instead of one signing round after the other.
+    /// The default here is controlled by the
+    /// [`DEFAULT_MAX_WITHDRAWAL_REJECTS_PER_TENURE`] constant.
+    pub max_withdrawal_rejects_per_tenure: NonZeroU16,
+    /// The number of signing failures after which the coordinator
+    /// quarantines a deposit or withdrawal request. Quarantined requests
+    /// are skipped until they are released through the API. The default
+    /// here is controlled by the [`DEFAULT_REQUEST_QUARANTINE_THRESHOLD`]
+    /// constant.
+    pub request_quarantine_threshold: NonZeroU32,
+    /// The secret used to compute keyed integrity hashes over
+    /// security-critical rows in the database. Integrity hashes are
+    /// neither written nor verified when this is not set.
+    #[serde(default, deserialize_with = "optional_integrity_key_deserializer")]
+    pub integrity_key: Option<IntegrityKey>,
+    /// The amount of time between integrity verifications of the
+    /// protected rows in the database. The default here is controlled by
+    /// the [`DEFAULT_INTEGRITY_CHECK_INTERVAL_SECONDS`] constant.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub integrity_check_interval: std::time::Duration,
+    /// The amount of time between checks for deposit requests whose
+    /// deposit UTXO was never confirmed or was reclaimed. The default here
+    /// is controlled by the [`DEFAULT_STALE_DEPOSIT_CHECK_INTERVAL_SECONDS`]
+    /// constant.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub stale_deposit_check_interval: std::time::Duration,
+    /// How long ago a deposit request must have been recorded before it
+    /// can be marked as stale. The default here is controlled by the
+    /// [`DEFAULT_STALE_DEPOSIT_MIN_AGE_SECONDS`] constant.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub stale_deposit_min_age: std::time::Duration,
+    /// The number of bitcoin blocks that a sweep transaction may be
+    /// confirmed for before it is flagged for never having had any of its
+    /// requests completed on stacks. The default here is controlled by
+    /// the [`DEFAULT_SWEEP_COMPLETION_DEADLINE`] constant.
+    pub sweep_completion_deadline: NonZeroU16,
     /// Configures a DKG re-run Bitcoin block height. If this is set and DKG has
     /// already been run, the coordinator will attempt to re-run DKG after this
     /// block height is met if `dkg_target_rounds` has not been reached. If DKG
@@ -404,6 +980,37 @@ pub struct SignerConfig {
     pub dkg_verification_window: u16,
     /// The maximum stacks fee in microSTX that the signer will accept for any stacks transaction.
     pub stacks_fees_max_ustx: NonZeroU64,
+    /// The maximum amount of fees, in microSTX, that the coordinator will
+    /// spend on non-critical stacks transactions over a budget period.
+    /// There is no budget when this is not set.
+    pub stacks_fee_budget_ustx: Option<NonZeroU64>,
+    /// The length of the rolling period over which the stacks fee budget
+    /// applies. The default here is controlled by the
+    /// [`DEFAULT_STACKS_FEE_BUDGET_PERIOD_SECONDS`] constant.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub stacks_fee_budget_period: std::time::Duration,
+    /// The unlocked balance, in microSTX, of the signers' STX address
+    /// below which the balance monitor logs an error. The default here is
+    /// controlled by the [`DEFAULT_STX_LOW_BALANCE_THRESHOLD_USTX`]
+    /// constant.
+    pub stx_low_balance_threshold_ustx: NonZeroU64,
+    /// The maximum fee, in sats, of a sweep transaction that the signer
+    /// will construct or sign. The default here is controlled by the
+    /// [`DEFAULT_SWEEP_FEE_MAX_SATS`] constant.
+    pub sweep_fee_max_sats: NonZeroU64,
+    /// The maximum fee of a sweep transaction that the signer will
+    /// construct or sign, as a fraction of the amount that it sweeps, in
+    /// basis points. The default here is controlled by the
+    /// [`DEFAULT_SWEEP_FEE_MAX_FRACTION_BPS`] constant.
+    pub sweep_fee_max_fraction_bps: NonZeroU16,
+    /// The number of signers that must approve high-impact actions: key
+    /// rotations, contract deployments and sweep packages that move more
+    /// than `high_impact_sweep_amount` sats. When this is not set,
+    /// high-impact actions need the same approvals as any other.
+    pub high_impact_signatures_required: Option<NonZeroU16>,
+    /// The total amount, in sats, above which a sweep package is a
+    /// high-impact action.
+    pub high_impact_sweep_amount: Option<NonZeroU64>,
     /// The aggregate key constructed during the signers' first DKG. It was
     /// used to lock the first UTXO created by the signers.
     pub bootstrap_aggregate_key: Option<PublicKey>,
@@ -412,6 +1019,10 @@ pub struct SignerConfig {
 impl Validatable for SignerConfig {
     fn validate(&self, cfg: &Settings) -> Result<(), ConfigError> {
         self.p2p.validate(cfg)?;
+        self.event_observer.access_log.validate(cfg)?;
+        self.event_observer.signed_responses.validate(cfg)?;
+        self.event_observer.new_block_auth.validate(cfg)?;
+        self.api.validate(cfg)?;
 
         if !self.bootstrap_signing_set.contains(&self.public_key()) {
             let err = SignerConfigError::MissingPubkeyInBootstrapSignerSet;
@@ -427,6 +1038,20 @@ impl Validatable for SignerConfig {
             let err = SignerConfigError::NetworkDeployerMismatch;
             return Err(ConfigError::Message(err.to_string()));
         }
+
+        let registry_name = SmartContract::SbtcRegistry.contract_name();
+        for contract in &self.registry_contracts {
+            let is_mainnet = StacksAddress::from(contract.issuer.clone()).is_mainnet();
+            if contract.name.as_str() != registry_name || is_mainnet != self.network.is_mainnet() {
+                let err = SignerConfigError::InvalidRegistryContract(contract.to_string());
+                return Err(ConfigError::Message(err.to_string()));
+            }
+        }
+
+        if self.deposit_script_versions.is_empty() {
+            let err = SignerConfigError::NoDepositScriptVersions;
+            return Err(ConfigError::Message(err.to_string()));
+        }
         // At least perform a simple check to see if the database endpoint is
         // valid for the supported database drivers. We only support PostgreSQL
         // for now. The rest of the URI we delegate to the database driver for
@@ -484,6 +1109,58 @@ impl Validatable for SignerConfig {
                 SignerConfigError::ZeroDurationForbidden("signer_round_max_duration").to_string(),
             ));
         }
+        if cfg.signer.integrity_check_interval == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("integrity_check_interval").to_string(),
+            ));
+        }
+        if cfg.signer.stale_deposit_check_interval == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("stale_deposit_check_interval")
+                    .to_string(),
+            ));
+        }
+        if cfg.signer.stale_deposit_min_age == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("stale_deposit_min_age").to_string(),
+            ));
+        }
+        if cfg.signer.stacks_fee_budget_period == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("stacks_fee_budget_period").to_string(),
+            ));
+        }
+        let max_sweep_tx_vsize = cfg.signer.max_sweep_tx_vsize.get();
+        if u64::from(max_sweep_tx_vsize) > MAX_STANDARD_TX_VSIZE {
+            return Err(ConfigError::Message(
+                SignerConfigError::InvalidMaxSweepTxVsize(
+                    MAX_STANDARD_TX_VSIZE,
+                    max_sweep_tx_vsize,
+                )
+                .to_string(),
+            ));
+        }
+        let sweep_fee_max_fraction_bps = cfg.signer.sweep_fee_max_fraction_bps.get();
+        if sweep_fee_max_fraction_bps > 10_000 {
+            return Err(ConfigError::Message(
+                SignerConfigError::InvalidSweepFeeFraction(sweep_fee_max_fraction_bps).to_string(),
+            ));
+        }
+        if let Some(high_impact) = cfg.signer.high_impact_signatures_required {
+            let high_impact = high_impact.get();
+            let ordinary = cfg.signer.bootstrap_signatures_required;
+            let num_signers = cfg.signer.bootstrap_signing_set.len();
+            if high_impact < ordinary || usize::from(high_impact) > num_signers {
+                return Err(ConfigError::Message(
+                    SignerConfigError::InvalidHighImpactSignaturesRequired {
+                        high_impact,
+                        ordinary,
+                        num_signers,
+                    }
+                    .to_string(),
+                ));
+            }
+        }
         // db_endpoint note: we don't validate the host because we will never
         // get here; the URL deserializer will fail if the host is empty.
         Ok(())
@@ -491,10 +1168,72 @@ impl Validatable for SignerConfig {
 }
 
 impl SignerConfig {
+    fn stacks_tenure_wait_default() -> std::time::Duration {
+        std::time::Duration::from_secs(30)
+    }
+
+    fn deposit_script_versions_default() -> Vec<DepositScriptVersion> {
+        vec![DepositScriptVersion::V1]
+    }
+
+    /// Return the registry of the deposit script templates that deposits
+    /// may follow.
+    pub fn deposit_script_decoders(&self) -> DepositScriptDecoders {
+        DepositScriptDecoders::new(self.deposit_script_versions.iter().copied())
+    }
+
     /// Return the public key of the signer.
     pub fn public_key(&self) -> PublicKey {
         PublicKey::from_private_key(&self.private_key)
     }
+
+    /// Return the private key of the signer's slot in the signer set.
+    ///
+    /// This is the private key that the signer had before it rotated its
+    /// identity, if it did, since its DKG shares were encrypted with it
+    /// and the signer set in the registry contract holds its public key.
+    pub fn slot_private_key(&self) -> &PrivateKey {
+        self.previous_private_key
+            .as_ref()
+            .unwrap_or(&self.private_key)
+    }
+
+    /// Return the public key of the signer's slot in the signer set.
+    pub fn slot_public_key(&self) -> PublicKey {
+        PublicKey::from_private_key(self.slot_private_key())
+    }
+
+    /// Return the sbtc-registry contracts whose events are observed. The
+    /// registry of the configured deployer comes first.
+    pub fn observed_registry_contracts(&self) -> Vec<QualifiedContractIdentifier> {
+        let issuer = StandardPrincipalData::from(self.deployer.clone());
+        let name = ContractName::from(SmartContract::SbtcRegistry.contract_name());
+        let mut contracts = vec![QualifiedContractIdentifier::new(issuer, name)];
+        for contract in &self.registry_contracts {
+            if !contracts.contains(contract) {
+                contracts.push(contract.clone());
+            }
+        }
+        contracts
+    }
+
+    /// Return the limits on the fees of sweep transactions.
+    pub fn sweep_fee_limits(&self) -> SweepFeeLimits {
+        SweepFeeLimits {
+            max_fee_sats: self.sweep_fee_max_sats.get(),
+            max_fee_fraction_bps: self.sweep_fee_max_fraction_bps.get(),
+        }
+    }
+
+    /// Return the quorum thresholds for high-impact actions.
+    pub fn quorum_thresholds(&self) -> QuorumThresholds {
+        QuorumThresholds {
+            high_impact_signatures_required: self
+                .high_impact_signatures_required
+                .map(NonZeroU16::get),
+            high_impact_sweep_amount: self.high_impact_sweep_amount.map(NonZeroU64::get),
+        }
+    }
 }
 
 /// Configuration for the Stacks event observer server (hosted within the signer).
@@ -502,47 +1241,372 @@ impl SignerConfig {
 pub struct EventObserverConfig {
     /// The address and port to bind the server to.
     pub bind: std::net::SocketAddr,
+    /// Access logging of the requests to, and responses from, the server.
+    #[serde(default)]
+    pub access_log: AccessLogConfig,
+    /// Signing of the responses of selected endpoints of the server.
+    #[serde(default)]
+    pub signed_responses: SignedResponsesConfig,
+    /// Authentication of the webhooks sent to the `/new_block` endpoint.
+    #[serde(default)]
+    pub new_block_auth: NewBlockAuthConfig,
+    /// The maximum amount of time, in milliseconds, that the `/new_block`
+    /// endpoint waits for the events of a block to be handled before it
+    /// responds. Events that are still being handled when the budget runs
+    /// out continue to be handled in the background.
+    #[serde(
+        default = "EventObserverConfig::latency_budget_default",
+        deserialize_with = "duration_milliseconds_deserializer"
+    )]
+    pub latency_budget: std::time::Duration,
+    /// The maximum number of times that handling the events of a stacks
+    /// block may fail before the block is given up on. Webhooks for a
+    /// block that was given up on are acknowledged without its events
+    /// being handled again.
+    #[serde(default = "EventObserverConfig::max_block_failures_default")]
+    pub max_block_failures: NonZeroU32,
+    /// How long, in seconds, the compressed raw bodies of the `/new_block`
+    /// webhooks are kept in the database for auditing and for replaying
+    /// the events of their blocks. The bodies are not stored when this is
+    /// zero, which is the default.
+    #[serde(default, deserialize_with = "duration_seconds_deserializer")]
+    pub raw_events_retention: std::time::Duration,
+
+    /// Whether contract events in the `/new_block` webhooks that do not
+    /// come from an observed sbtc-registry contract are recorded in the
+    /// database, in addition to being counted and dropped.
+    #[serde(default)]
+    pub quarantine_filtered_events: bool,
 }
 
-impl Settings {
-    /// Initializing the global config first with default values and then with
-    /// provided/overwritten environment variables. The explicit separator with
-    /// double underscores is needed to correctly parse the nested config structure.
-    ///
-    /// The environment variables are prefixed with `SIGNER_` and the nested
-    /// fields are separated with double underscores. For example, the path
-    /// `signer.p2p.listen_on` is parsed as following:
-    ///
-    /// ```text
-    /// SIGNER_SIGNER__P2P__LISTEN_ON
-    /// ^^^^^^ ^^^^^^  ^^^  ^^^^^^^^^
-    ///    │  ^  │   ^^ │ ^^   │  ^
-    ///    │  │  │   │  │ │    │  └ The underscore in the `listen_on` field
-    ///    │  │  │   │  │ │    └ The `listen_on` field of the `p2p` object
-    ///    │  │  │   │  │ └ separator("__")
-    ///    │  │  │   │  └ The `p2p` field of the `signer` object
-    ///    │  │  │   └ separator("__")
-    ///    │  │  └ The `signer` field of the root object (`Settings`)
-    ///    │  └ prefix_separator("_")
-    ///    └ with_prefix("SIGNER")
-    /// ```
-    pub fn new(config_path: Option<impl AsRef<Path>>) -> Result<Self, ConfigError> {
-        // To properly parse lists from both environment and config files while
-        // using a custom deserializer, we need to specify the list separator,
-        // enable try_parsing and specify the keys which should be parsed as lists.
-        // If the keys aren't specified, the deserializer will try to parse all
-        // Strings as lists which will result in an error.
-        let env = Environment::with_prefix("SIGNER")
-            .separator("__")
-            .list_separator(",")
-            .try_parsing(true)
-            .with_list_parse_key("signer.bootstrap_signing_set")
+impl EventObserverConfig {
+    fn latency_budget_default() -> std::time::Duration {
+        std::time::Duration::from_millis(500)
+    }
+
+    fn max_block_failures_default() -> NonZeroU32 {
+        NonZeroU32::new(3).expect("3 is non-zero")
+    }
+}
+
+/// Limits on the requests to, and connections of, the signer API server,
+/// so that a flood of requests cannot starve the handling of the webhooks
+/// of the stacks node, along with the TLS that the server is served with
+/// and the secret of its admin endpoints. A limit is disabled when it is
+/// zero, which is the default for all of them.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ApiConfig {
+    /// The number of requests per second that each client address may
+    /// send on average. Requests above the rate are rejected with a `429
+    /// Too Many Requests` response.
+    #[serde(default)]
+    pub rate_limit: u32,
+    /// The number of requests that each client address may send at once
+    /// before it is held to the rate limit. The rate limit is used when
+    /// this is zero.
+    #[serde(default)]
+    pub rate_limit_burst: u32,
+    /// The client addresses that are not rate limited, like the address of
+    /// the stacks node.
+    #[serde(default)]
+    pub rate_limit_exempt: Vec<std::net::IpAddr>,
+    /// The maximum number of requests that are handled at the same time.
+    /// Requests above the limit are rejected with a `503 Service
+    /// Unavailable` response.
+    #[serde(default)]
+    pub max_concurrent_requests: usize,
+    /// The maximum number of open connections. Further connections are
+    /// not accepted until one of the open connections closes.
+    #[serde(default)]
+    pub max_connections: usize,
+    /// The maximum amount of time, in milliseconds, that handling a
+    /// request, including receiving its body, may take. Requests that take
+    /// longer are answered with a `408 Request Timeout` response.
+    #[serde(default, deserialize_with = "duration_milliseconds_deserializer")]
+    pub request_timeout: std::time::Duration,
+    /// Serve the API over TLS, rather than over plain HTTP, which is the
+    /// default.
+    #[serde(default)]
+    pub tls: Option<ApiTlsConfig>,
+    /// The secret that the requests to the admin endpoints of the API are
+    /// signed with. It must differ from the secret of the `/new_block`
+    /// webhooks, so that the stacks node cannot make admin requests. The
+    /// admin endpoints are disabled when this is not set.
+    #[serde(default)]
+    pub admin_secret: Option<String>,
+}
+
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let admin_secret = self.admin_secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("ApiConfig")
+            .field("rate_limit", &self.rate_limit)
+            .field("rate_limit_burst", &self.rate_limit_burst)
+            .field("rate_limit_exempt", &self.rate_limit_exempt)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .field("max_connections", &self.max_connections)
+            .field("request_timeout", &self.request_timeout)
+            .field("tls", &self.tls)
+            .field("admin_secret", &admin_secret)
+            .finish()
+    }
+}
+
+/// TLS for the signer API server, optionally with client certificate
+/// verification, so that the webhooks of a stacks node on another host are
+/// encrypted and the sender is authenticated.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ApiTlsConfig {
+    /// The path to the PEM file with the certificate chain of the server,
+    /// starting with the certificate of the server itself.
+    pub certificate: std::path::PathBuf,
+    /// The path to the PEM file with the private key of the server.
+    pub private_key: std::path::PathBuf,
+    /// The path to the PEM file with the certificates of the authorities
+    /// that issue client certificates. When this is set, a client must
+    /// present a certificate issued by one of them to connect.
+    #[serde(default)]
+    pub client_ca: Option<std::path::PathBuf>,
+}
+
+impl ApiConfig {
+    /// The number of requests that each client address may send at once,
+    /// if requests are rate limited.
+    pub fn rate_limit_burst(&self) -> Option<u32> {
+        let burst = if self.rate_limit_burst == 0 {
+            self.rate_limit
+        } else {
+            self.rate_limit_burst
+        };
+        Some(burst).filter(|_| self.rate_limit > 0)
+    }
+
+    /// The maximum amount of time that handling a request may take, if
+    /// there is one.
+    pub fn request_timeout(&self) -> Option<std::time::Duration> {
+        Some(self.request_timeout).filter(|timeout| !timeout.is_zero())
+    }
+}
+
+impl Validatable for ApiConfig {
+    fn validate(&self, cfg: &Settings) -> Result<(), ConfigError> {
+        // The `/new_block` endpoint waits up to the latency budget before
+        // it responds, so a shorter timeout would cut off every slow block.
+        let latency_budget = cfg.signer.event_observer.latency_budget;
+        if self
+            .request_timeout()
+            .is_some_and(|timeout| timeout <= latency_budget)
+        {
+            return Err(ConfigError::Message(format!(
+                "[signer.api.request_timeout] Must be longer than the latency budget of \
+                the event observer, {} milliseconds",
+                latency_budget.as_millis()
+            )));
+        }
+
+        if let Some(admin_secret) = self.admin_secret.as_ref() {
+            let min = MIN_NEW_BLOCK_SECRET_LENGTH;
+            if admin_secret.len() < min {
+                return Err(ConfigError::Message(format!(
+                    "[signer.api.admin_secret] Must be at least {min} bytes long"
+                )));
+            }
+            let new_block_secret = cfg.signer.event_observer.new_block_auth.secret.as_ref();
+            if new_block_secret == Some(admin_secret) {
+                return Err(ConfigError::Message(
+                    "[signer.api.admin_secret] Must differ from the secret of the \
+                    `/new_block` webhooks"
+                        .to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration for logging the requests to, and responses from, selected
+/// endpoints of the signer API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AccessLogConfig {
+    /// The paths of the endpoints whose requests and responses are logged,
+    /// like `/new_block`. Access logging is disabled when this is empty.
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+    /// The names of JSON object fields whose values are replaced with
+    /// `[REDACTED]` in the logged bodies. Names are matched case
+    /// insensitively, at any depth.
+    #[serde(default)]
+    pub redact_fields: Vec<String>,
+    /// The maximum number of bytes of each body that is captured in the
+    /// log. Only the body sizes are logged when this is zero.
+    #[serde(default = "AccessLogConfig::max_body_bytes_default")]
+    pub max_body_bytes: usize,
+}
+
+impl AccessLogConfig {
+    fn max_body_bytes_default() -> usize {
+        4096
+    }
+
+    /// Whether the requests to the endpoint with the given path are logged.
+    pub fn is_logged(&self, path: &str) -> bool {
+        self.endpoints.iter().any(|endpoint| endpoint == path)
+    }
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            redact_fields: Vec::new(),
+            max_body_bytes: Self::max_body_bytes_default(),
+        }
+    }
+}
+
+impl Validatable for AccessLogConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        if self
+            .endpoints
+            .iter()
+            .any(|endpoint| !endpoint.starts_with('/'))
+        {
+            return Err(ConfigError::Message(
+                "[signer.event_observer.access_log.endpoints] Endpoint paths must start with '/'"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration for authenticating the webhooks sent to the `/new_block`
+/// endpoint of the signer API.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct NewBlockAuthConfig {
+    /// The secret shared with the stacks node, or the proxy in front of
+    /// it. Webhooks must carry the secret, or an HMAC of their body keyed
+    /// by it, to be accepted. Webhooks are not authenticated when this is
+    /// not set.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+impl std::fmt::Debug for NewBlockAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secret = self.secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("NewBlockAuthConfig")
+            .field("secret", &secret)
+            .finish()
+    }
+}
+
+impl Validatable for NewBlockAuthConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        let min = MIN_NEW_BLOCK_SECRET_LENGTH;
+        if self
+            .secret
+            .as_ref()
+            .is_some_and(|secret| secret.len() < min)
+        {
+            return Err(ConfigError::Message(format!(
+                "[signer.event_observer.new_block_auth.secret] Must be at least {min} bytes long"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The minimum length of the secrets that authenticate `/new_block`
+/// webhooks and admin requests, so that they cannot be guessed.
+const MIN_NEW_BLOCK_SECRET_LENGTH: usize = 16;
+
+/// Configuration for signing the responses of selected endpoints of the
+/// signer API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SignedResponsesConfig {
+    /// The paths of the endpoints whose successful responses are signed
+    /// with the signer's private key, like `/info`. Responses are not
+    /// signed when this is empty.
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+}
+
+impl SignedResponsesConfig {
+    /// Whether the responses of the endpoint with the given path are
+    /// signed.
+    pub fn is_signed(&self, path: &str) -> bool {
+        self.endpoints.iter().any(|endpoint| endpoint == path)
+    }
+}
+
+impl Validatable for SignedResponsesConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        if self
+            .endpoints
+            .iter()
+            .any(|endpoint| !endpoint.starts_with('/'))
+        {
+            return Err(ConfigError::Message(
+                "[signer.event_observer.signed_responses.endpoints] Endpoint paths must start with '/'"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Settings {
+    /// Initializing the global config first with default values and then with
+    /// provided/overwritten environment variables. The explicit separator with
+    /// double underscores is needed to correctly parse the nested config structure.
+    ///
+    /// The environment variables are prefixed with `SIGNER_` and the nested
+    /// fields are separated with double underscores. For example, the path
+    /// `signer.p2p.listen_on` is parsed as following:
+    ///
+    /// ```text
+    /// SIGNER_SIGNER__P2P__LISTEN_ON
+    /// ^^^^^^ ^^^^^^  ^^^  ^^^^^^^^^
+    ///    │  ^  │   ^^ │ ^^   │  ^
+    ///    │  │  │   │  │ │    │  └ The underscore in the `listen_on` field
+    ///    │  │  │   │  │ │    └ The `listen_on` field of the `p2p` object
+    ///    │  │  │   │  │ └ separator("__")
+    ///    │  │  │   │  └ The `p2p` field of the `signer` object
+    ///    │  │  │   └ separator("__")
+    ///    │  │  └ The `signer` field of the root object (`Settings`)
+    ///    │  └ prefix_separator("_")
+    ///    └ with_prefix("SIGNER")
+    /// ```
+    pub fn new(config_path: Option<impl AsRef<Path>>) -> Result<Self, ConfigError> {
+        // To properly parse lists from both environment and config files while
+        // using a custom deserializer, we need to specify the list separator,
+        // enable try_parsing and specify the keys which should be parsed as lists.
+        // If the keys aren't specified, the deserializer will try to parse all
+        // Strings as lists which will result in an error.
+        let env = Environment::with_prefix("SIGNER")
+            .separator("__")
+            .list_separator(",")
+            .try_parsing(true)
+            .with_list_parse_key("signer.bootstrap_signing_set")
+            .with_list_parse_key("signer.registry_contracts")
+            .with_list_parse_key("signer.deposit_script_versions")
             .with_list_parse_key("signer.p2p.seeds")
             .with_list_parse_key("signer.p2p.listen_on")
             .with_list_parse_key("signer.p2p.public_endpoints")
+            .with_list_parse_key("signer.event_observer.access_log.endpoints")
+            .with_list_parse_key("signer.event_observer.access_log.redact_fields")
+            .with_list_parse_key("signer.event_observer.signed_responses.endpoints")
+            .with_list_parse_key("signer.api.rate_limit_exempt")
             .with_list_parse_key("bitcoin.rpc_endpoints")
             .with_list_parse_key("stacks.endpoints")
             .with_list_parse_key("emily.endpoints")
+            .with_list_parse_key("webhooks.endpoints")
+            .with_list_parse_key("outbound.no_proxy")
             .prefix_separator("_");
 
         let mut cfg_builder = Config::builder();
@@ -560,10 +1624,50 @@ impl Settings {
             "signer.max_deposits_per_bitcoin_tx",
             DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
         )?;
+        cfg_builder =
+            cfg_builder.set_default("signer.max_sweep_tx_vsize", MAX_STANDARD_TX_VSIZE)?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.max_withdrawal_rejects_per_tenure",
+            DEFAULT_MAX_WITHDRAWAL_REJECTS_PER_TENURE,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.request_quarantine_threshold",
+            DEFAULT_REQUEST_QUARANTINE_THRESHOLD,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.integrity_check_interval",
+            DEFAULT_INTEGRITY_CHECK_INTERVAL_SECONDS,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.stale_deposit_check_interval",
+            DEFAULT_STALE_DEPOSIT_CHECK_INTERVAL_SECONDS,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.stale_deposit_min_age",
+            DEFAULT_STALE_DEPOSIT_MIN_AGE_SECONDS,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.sweep_completion_deadline",
+            DEFAULT_SWEEP_COMPLETION_DEADLINE,
+        )?;
         cfg_builder = cfg_builder.set_default("signer.dkg_target_rounds", 1)?;
         cfg_builder = cfg_builder.set_default("emily.pagination_timeout", 10)?;
         cfg_builder = cfg_builder.set_default("signer.dkg_verification_window", 10)?;
         cfg_builder = cfg_builder.set_default("signer.stacks_fees_max_ustx", 1_500_000)?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.stacks_fee_budget_period",
+            DEFAULT_STACKS_FEE_BUDGET_PERIOD_SECONDS,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.stx_low_balance_threshold_ustx",
+            DEFAULT_STX_LOW_BALANCE_THRESHOLD_USTX,
+        )?;
+        cfg_builder =
+            cfg_builder.set_default("signer.sweep_fee_max_sats", DEFAULT_SWEEP_FEE_MAX_SATS)?;
+        cfg_builder = cfg_builder.set_default(
+            "signer.sweep_fee_max_fraction_bps",
+            DEFAULT_SWEEP_FEE_MAX_FRACTION_BPS,
+        )?;
         cfg_builder = cfg_builder.set_default("bitcoin.chain_tip_polling_interval", 5)?;
 
         if let Some(path) = config_path {
@@ -586,6 +1690,13 @@ impl Settings {
         self.signer.validate(self)?;
         self.stacks.validate(self)?;
         self.emily.validate(self)?;
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.validate(self)?;
+        }
+        if let Some(analytics_export) = &self.analytics_export {
+            analytics_export.validate(self)?;
+        }
+        self.outbound.validate(self)?;
 
         Ok(())
     }
@@ -614,6 +1725,7 @@ impl Validatable for StacksConfig {
 #[cfg(test)]
 mod tests {
     use std::net::SocketAddr;
+    use std::path::PathBuf;
     use std::str::FromStr as _;
     use std::time::Duration;
 
@@ -692,6 +1804,14 @@ mod tests {
             settings.signer.max_deposits_per_bitcoin_tx,
             NonZeroU16::new(DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX).unwrap()
         );
+        assert_eq!(
+            settings.signer.max_withdrawal_rejects_per_tenure.get(),
+            DEFAULT_MAX_WITHDRAWAL_REJECTS_PER_TENURE
+        );
+        assert_eq!(
+            settings.signer.request_quarantine_threshold.get(),
+            DEFAULT_REQUEST_QUARANTINE_THRESHOLD
+        );
         assert!(!settings.signer.bootstrap_signing_set.is_empty());
         assert!(settings.signer.dkg_begin_pause.is_none());
         assert_eq!(
@@ -753,6 +1873,8 @@ mod tests {
             "tcp://seed-1:4122,tcp://seed-2:4122",
         );
         set_var("SIGNER_SIGNER__P2P__LISTEN_ON", "tcp://1.2.3.4:1234");
+        set_var("SIGNER_SIGNER__P2P__ENABLE_BULK_SYNC", "true");
+        set_var("SIGNER_SIGNER__P2P__SPLIT_GOSSIP_TOPICS", "true");
 
         let settings = Settings::new_from_default_config().unwrap();
 
@@ -767,6 +1889,8 @@ mod tests {
             settings.signer.p2p.listen_on,
             vec![multiaddr("tcp://1.2.3.4:1234")]
         );
+        assert!(settings.signer.p2p.enable_bulk_sync);
+        assert!(settings.signer.p2p.split_gossip_topics);
     }
 
     #[test]
@@ -888,6 +2012,18 @@ mod tests {
         assert!(Settings::new_from_default_config().is_err());
     }
 
+    #[test]
+    fn max_withdrawal_rejects_per_tenure_from_environment() {
+        clear_env();
+
+        set_var("SIGNER_SIGNER__MAX_WITHDRAWAL_REJECTS_PER_TENURE", "42");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.max_withdrawal_rejects_per_tenure.get(), 42);
+
+        set_var("SIGNER_SIGNER__MAX_WITHDRAWAL_REJECTS_PER_TENURE", "0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
     #[test]
     fn default_config_toml_loads_dkg_min_bitcoin_block_height() {
         clear_env();
@@ -933,6 +2069,44 @@ mod tests {
         assert_eq!(settings.signer.dkg_verification_window, 42);
     }
 
+    #[test]
+    fn loading_next_private_key() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.next_private_key.is_none());
+
+        let private_key_str = "a1a6ec5a3d9c2b1f0e8d7c6b5a49382716f5e4d3c2b1a0f9e8d7c6b5a4938271";
+        let expected_private_key = PrivateKey::from_str(private_key_str).unwrap();
+
+        set_var("SIGNER_SIGNER__NEXT_PRIVATE_KEY", private_key_str);
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.next_private_key, Some(expected_private_key));
+
+        set_var("SIGNER_SIGNER__NEXT_PRIVATE_KEY", "deadbeef");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn loading_previous_private_key() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.previous_private_key.is_none());
+        assert_eq!(settings.signer.slot_public_key(), settings.signer.public_key());
+
+        let private_key_str = "a1a6ec5a3d9c2b1f0e8d7c6b5a49382716f5e4d3c2b1a0f9e8d7c6b5a4938271";
+        let expected_private_key = PrivateKey::from_str(private_key_str).unwrap();
+
+        set_var("SIGNER_SIGNER__PREVIOUS_PRIVATE_KEY", private_key_str);
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.previous_private_key,
+            Some(expected_private_key)
+        );
+        assert_eq!(settings.signer.slot_private_key(), &expected_private_key);
+    }
+
     #[test]
     fn loading_bootstrap_aggregate_key() {
         clear_env();
@@ -987,89 +2161,272 @@ mod tests {
     }
 
     #[test]
-    fn prometheus_exporter_endpoint_with_environment() {
+    fn rng_seed() {
         clear_env();
 
-        set_var("SIGNER_SIGNER__PROMETHEUS_EXPORTER_ENDPOINT", "[::]:9851");
-
         let settings = Settings::new_from_default_config().unwrap();
-        let endpoint = settings.signer.prometheus_exporter_endpoint.unwrap();
-
-        assert!(endpoint.ip().is_unspecified());
-        assert!(endpoint.is_ipv6());
-        assert_eq!(endpoint.port(), 9851);
+        assert!(settings.signer.rng_seed.is_none());
 
-        set_var(
-            "SIGNER_SIGNER__PROMETHEUS_EXPORTER_ENDPOINT",
-            "0.0.0.0:9852",
-        );
+        set_var("SIGNER_SIGNER__RNG_SEED", "42");
 
         let settings = Settings::new_from_default_config().unwrap();
-        let endpoint = settings.signer.prometheus_exporter_endpoint.unwrap();
-
-        assert!(endpoint.ip().is_unspecified());
-        assert!(endpoint.is_ipv4());
-        assert_eq!(endpoint.port(), 9852);
+        assert_eq!(settings.signer.rng_seed, Some(42));
     }
 
     #[test]
-    fn default_config_toml_loads_with_environment() {
+    fn registry_contracts() {
         clear_env();
 
-        // The default toml used here specifies http://127.0.0.1:20443
-        // as the stacks node endpoint.
         let settings = Settings::new_from_default_config().unwrap();
-        let host = settings.stacks.endpoints[0].host();
-        let ip: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
-        assert_eq!(host, Some(url::Host::Ipv4(ip)));
-        assert_eq!(settings.stacks.endpoints[0].port(), Some(20443));
-
-        set_var(
-            "SIGNER_STACKS__ENDPOINTS",
-            "http://whatever:1234,http://whateva:4321",
+        assert!(settings.signer.registry_contracts.is_empty());
+        let observed = settings.signer.observed_registry_contracts();
+        assert_eq!(observed.len(), 1);
+        assert_eq!(
+            observed[0].to_string(),
+            "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry"
         );
 
-        let settings = Settings::new_from_default_config().unwrap();
-        let host = settings.stacks.endpoints[0].host();
-        assert_eq!(host, Some(url::Host::Domain("whatever")));
-        assert_eq!(settings.stacks.endpoints[0].port(), Some(1234));
-        let host = settings.stacks.endpoints[1].host();
-        assert_eq!(host, Some(url::Host::Domain("whateva")));
-        assert_eq!(settings.stacks.endpoints[1].port(), Some(4321));
-
-        set_var("SIGNER_STACKS__ENDPOINTS", "http://127.0.0.1:5678");
+        let other = "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.sbtc-registry";
+        set_var("SIGNER_SIGNER__REGISTRY_CONTRACTS", other);
 
         let settings = Settings::new_from_default_config().unwrap();
-        let ip: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
+        let observed: Vec<String> = settings
+            .signer
+            .observed_registry_contracts()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
         assert_eq!(
-            settings.stacks.endpoints[0].host(),
-            Some(url::Host::Ipv4(ip))
+            observed,
+            [
+                "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry",
+                other
+            ]
         );
-        assert_eq!(settings.stacks.endpoints[0].port(), Some(5678));
 
-        set_var("SIGNER_STACKS__ENDPOINTS", "http://[::1]:9101");
+        // Only sbtc-registry contracts are observed.
+        let token = "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.sbtc-token";
+        set_var("SIGNER_SIGNER__REGISTRY_CONTRACTS", token);
+        assert!(Settings::new_from_default_config().is_err());
 
-        let settings = Settings::new_from_default_config().unwrap();
-        let ip: std::net::Ipv6Addr = "::1".parse().unwrap();
-        assert_eq!(
-            settings.stacks.endpoints[0].host(),
-            Some(url::Host::Ipv6(ip))
-        );
-        assert_eq!(settings.stacks.endpoints[0].port(), Some(9101));
+        // And only those on the configured network.
+        let mainnet = "SP000000000000000000002Q6VF78.sbtc-registry";
+        set_var("SIGNER_SIGNER__REGISTRY_CONTRACTS", mainnet);
+        assert!(Settings::new_from_default_config().is_err());
+    }
 
-        let delay = 42;
-        set_var("SIGNER_SIGNER__BITCOIN_PROCESSING_DELAY", delay.to_string());
+    #[test]
+    fn deposit_script_versions() {
+        clear_env();
 
         let settings = Settings::new_from_default_config().unwrap();
         assert_eq!(
-            settings.signer.bitcoin_processing_delay,
-            std::time::Duration::from_secs(delay),
+            settings.signer.deposit_script_versions,
+            [DepositScriptVersion::V1]
         );
-
-        let delay = 42;
-        set_var(
-            "SIGNER_SIGNER__REQUESTS_PROCESSING_DELAY",
-            delay.to_string(),
+        assert_eq!(
+            settings.signer.deposit_script_decoders(),
+            DepositScriptDecoders::default()
+        );
+
+        set_var("SIGNER_SIGNER__DEPOSIT_SCRIPT_VERSIONS", "v1,v1");
+        let settings = Settings::new_from_default_config().unwrap();
+        let decoders = settings.signer.deposit_script_decoders();
+        assert_eq!(decoders.versions(), [DepositScriptVersion::V1]);
+
+        set_var("SIGNER_SIGNER__DEPOSIT_SCRIPT_VERSIONS", "v0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn aggregate_withdrawal_outputs() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.aggregate_withdrawal_outputs);
+
+        set_var("SIGNER_SIGNER__AGGREGATE_WITHDRAWAL_OUTPUTS", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.aggregate_withdrawal_outputs);
+    }
+
+    #[test]
+    fn sweep_lock_time() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.sweep_lock_time);
+
+        set_var("SIGNER_SIGNER__SWEEP_LOCK_TIME", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.sweep_lock_time);
+    }
+
+    #[test]
+    fn maintenance_mode() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.maintenance_mode);
+
+        set_var("SIGNER_SIGNER__MAINTENANCE_MODE", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.maintenance_mode);
+    }
+
+    #[test]
+    fn serialization_audit() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.serialization_audit);
+
+        set_var("SIGNER_SIGNER__SERIALIZATION_AUDIT", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.serialization_audit);
+    }
+
+    #[test]
+    fn handover_socket() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.handover_socket.is_none());
+
+        set_var(
+            "SIGNER_SIGNER__HANDOVER_SOCKET",
+            "/run/sbtc-signer/handover.sock",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.handover_socket.as_deref(),
+            Some(Path::new("/run/sbtc-signer/handover.sock"))
+        );
+    }
+
+    #[test]
+    fn prometheus_exporter_endpoint_with_environment() {
+        clear_env();
+
+        set_var("SIGNER_SIGNER__PROMETHEUS_EXPORTER_ENDPOINT", "[::]:9851");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let endpoint = settings.signer.prometheus_exporter_endpoint.unwrap();
+
+        assert!(endpoint.ip().is_unspecified());
+        assert!(endpoint.is_ipv6());
+        assert_eq!(endpoint.port(), 9851);
+
+        set_var(
+            "SIGNER_SIGNER__PROMETHEUS_EXPORTER_ENDPOINT",
+            "0.0.0.0:9852",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let endpoint = settings.signer.prometheus_exporter_endpoint.unwrap();
+
+        assert!(endpoint.ip().is_unspecified());
+        assert!(endpoint.is_ipv4());
+        assert_eq!(endpoint.port(), 9852);
+    }
+
+    #[test]
+    fn prometheus_api_metrics() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.prometheus_api_metrics);
+
+        set_var("SIGNER_SIGNER__PROMETHEUS_API_METRICS", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.prometheus_api_metrics);
+    }
+
+    #[test]
+    fn metrics_backends_with_environment() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.statsd_endpoint.is_none());
+        assert!(settings.signer.otlp_metrics_endpoint.is_none());
+
+        set_var("SIGNER_SIGNER__STATSD_ENDPOINT", "statsd.local:8125");
+        set_var(
+            "SIGNER_SIGNER__OTLP_METRICS_ENDPOINT",
+            "http://collector.local:4318/v1/metrics",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.statsd_endpoint.as_deref(),
+            Some("statsd.local:8125")
+        );
+        let endpoint = settings.signer.otlp_metrics_endpoint.unwrap();
+        assert_eq!(endpoint.as_str(), "http://collector.local:4318/v1/metrics");
+
+        set_var("SIGNER_SIGNER__OTLP_METRICS_ENDPOINT", "not a url");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn default_config_toml_loads_with_environment() {
+        clear_env();
+
+        // The default toml used here specifies http://127.0.0.1:20443
+        // as the stacks node endpoint.
+        let settings = Settings::new_from_default_config().unwrap();
+        let host = settings.stacks.endpoints[0].host();
+        let ip: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
+        assert_eq!(host, Some(url::Host::Ipv4(ip)));
+        assert_eq!(settings.stacks.endpoints[0].port(), Some(20443));
+
+        set_var(
+            "SIGNER_STACKS__ENDPOINTS",
+            "http://whatever:1234,http://whateva:4321",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let host = settings.stacks.endpoints[0].host();
+        assert_eq!(host, Some(url::Host::Domain("whatever")));
+        assert_eq!(settings.stacks.endpoints[0].port(), Some(1234));
+        let host = settings.stacks.endpoints[1].host();
+        assert_eq!(host, Some(url::Host::Domain("whateva")));
+        assert_eq!(settings.stacks.endpoints[1].port(), Some(4321));
+
+        set_var("SIGNER_STACKS__ENDPOINTS", "http://127.0.0.1:5678");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let ip: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
+        assert_eq!(
+            settings.stacks.endpoints[0].host(),
+            Some(url::Host::Ipv4(ip))
+        );
+        assert_eq!(settings.stacks.endpoints[0].port(), Some(5678));
+
+        set_var("SIGNER_STACKS__ENDPOINTS", "http://[::1]:9101");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let ip: std::net::Ipv6Addr = "::1".parse().unwrap();
+        assert_eq!(
+            settings.stacks.endpoints[0].host(),
+            Some(url::Host::Ipv6(ip))
+        );
+        assert_eq!(settings.stacks.endpoints[0].port(), Some(9101));
+
+        let delay = 42;
+        set_var("SIGNER_SIGNER__BITCOIN_PROCESSING_DELAY", delay.to_string());
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.bitcoin_processing_delay,
+            std::time::Duration::from_secs(delay),
+        );
+
+        let delay = 42;
+        set_var(
+            "SIGNER_SIGNER__REQUESTS_PROCESSING_DELAY",
+            delay.to_string(),
         );
 
         let settings = Settings::new_from_default_config().unwrap();
@@ -1077,6 +2434,11 @@ mod tests {
             settings.signer.requests_processing_delay,
             std::time::Duration::from_secs(delay),
         );
+
+        set_var("SIGNER_SIGNER__STACKS_TENURE_WAIT", "0");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.stacks_tenure_wait, Duration::ZERO);
     }
 
     #[test]
@@ -1104,6 +2466,8 @@ mod tests {
         remove_parameter("signer", "bitcoin_presign_request_max_duration");
         remove_parameter("signer", "dkg_max_duration");
         remove_parameter("signer", "max_deposits_per_bitcoin_tx");
+        remove_parameter("signer", "max_withdrawal_rejects_per_tenure");
+        remove_parameter("signer", "request_quarantine_threshold");
 
         remove_parameter("emily", "pagination_timeout");
 
@@ -1114,6 +2478,7 @@ mod tests {
         let settings = Settings::new(Some(&new_config.path())).unwrap();
 
         assert_eq!(settings.signer.context_window, 1000);
+        assert_eq!(settings.signer.stacks_tenure_wait, Duration::from_secs(30));
         assert_eq!(settings.signer.deposit_decisions_retry_window, 3);
         assert_eq!(settings.signer.withdrawal_decisions_retry_window, 3);
         assert_eq!(
@@ -1149,7 +2514,17 @@ mod tests {
     #[test_case("dkg_max_duration" ; "dkg_max_duration")]
     #[test_case("bitcoin_presign_request_max_duration" ; "bitcoin_presign_request_max_duration")]
     #[test_case("signer_round_max_duration" ; "signer_round_max_duration")]
+    #[test_case("integrity_check_interval" ; "integrity_check_interval")]
+    #[test_case("stale_deposit_check_interval" ; "stale_deposit_check_interval")]
+    #[test_case("stale_deposit_min_age" ; "stale_deposit_min_age")]
     #[test_case("stacks_fees_max_ustx" ; "stacks_fees_max_ustx")]
+    #[test_case("stacks_fee_budget_ustx" ; "stacks_fee_budget_ustx")]
+    #[test_case("stacks_fee_budget_period" ; "stacks_fee_budget_period")]
+    #[test_case("stx_low_balance_threshold_ustx" ; "stx_low_balance_threshold_ustx")]
+    #[test_case("sweep_fee_max_sats" ; "sweep_fee_max_sats")]
+    #[test_case("sweep_fee_max_fraction_bps" ; "sweep_fee_max_fraction_bps")]
+    #[test_case("high_impact_signatures_required" ; "high_impact_signatures_required")]
+    #[test_case("high_impact_sweep_amount" ; "high_impact_sweep_amount")]
     fn zero_values_for_nonzero_fields_fail_in_signer_config(field: &str) {
         clear_env();
 
@@ -1170,6 +2545,566 @@ mod tests {
         assert_eq!(actual_endpoint, url::Url::parse(endpoint).unwrap());
     }
 
+    #[test]
+    fn webhook_endpoints() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.webhooks.is_none());
+
+        let endpoint = "https://example.com/sbtc";
+        set_var("SIGNER_WEBHOOKS__ENDPOINTS", endpoint);
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let webhooks = settings.webhooks.unwrap();
+        assert_eq!(webhooks.endpoints, vec![url::Url::parse(endpoint).unwrap()]);
+        assert_eq!(webhooks.max_attempts.get(), 5);
+
+        set_var("SIGNER_WEBHOOKS__ENDPOINTS", "ftp://example.com");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn analytics_export() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.analytics_export.is_none());
+
+        set_var(
+            "SIGNER_ANALYTICS_EXPORT__ENDPOINT",
+            "https://s3.example.com",
+        );
+        set_var("SIGNER_ANALYTICS_EXPORT__BUCKET", "sbtc-analytics");
+        set_var("SIGNER_ANALYTICS_EXPORT__ACCESS_KEY_ID", "AKIDEXAMPLE");
+        set_var("SIGNER_ANALYTICS_EXPORT__SECRET_ACCESS_KEY", "a-secret");
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let export = settings.analytics_export.unwrap();
+        assert_eq!(export.bucket, "sbtc-analytics");
+        assert_eq!(export.region, "us-east-1");
+        assert_eq!(export.prefix, "");
+        assert_eq!(export.interval, Duration::from_secs(3600));
+        assert_eq!(export.format, ExportFormat::Csv);
+        assert!(!format!("{export:?}").contains("a-secret"));
+
+        set_var("SIGNER_ANALYTICS_EXPORT__FORMAT", "json");
+        set_var("SIGNER_ANALYTICS_EXPORT__INTERVAL", "86400");
+        let export = Settings::new_from_default_config()
+            .unwrap()
+            .analytics_export
+            .unwrap();
+        assert_eq!(export.format, ExportFormat::Json);
+        assert_eq!(export.interval, Duration::from_secs(86400));
+
+        set_var("SIGNER_ANALYTICS_EXPORT__INTERVAL", "10");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn event_observer_latency_budget() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.event_observer.latency_budget,
+            Duration::from_millis(500)
+        );
+
+        set_var("SIGNER_SIGNER__EVENT_OBSERVER__LATENCY_BUDGET", "250");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.event_observer.latency_budget,
+            Duration::from_millis(250)
+        );
+    }
+
+    #[test]
+    fn event_observer_max_block_failures() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.event_observer.max_block_failures.get(), 3);
+
+        set_var("SIGNER_SIGNER__EVENT_OBSERVER__MAX_BLOCK_FAILURES", "10");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.event_observer.max_block_failures.get(), 10);
+
+        set_var("SIGNER_SIGNER__EVENT_OBSERVER__MAX_BLOCK_FAILURES", "0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn event_observer_access_log() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let access_log = settings.signer.event_observer.access_log;
+        assert_eq!(access_log, AccessLogConfig::default());
+        assert!(!access_log.is_logged("/new_block"));
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ACCESS_LOG__ENDPOINTS",
+            "/new_block,/info",
+        );
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ACCESS_LOG__REDACT_FIELDS",
+            "raw_tx,signature",
+        );
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ACCESS_LOG__MAX_BODY_BYTES",
+            "1024",
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let access_log = settings.signer.event_observer.access_log;
+        assert_eq!(access_log.endpoints, vec!["/new_block", "/info"]);
+        assert_eq!(access_log.redact_fields, vec!["raw_tx", "signature"]);
+        assert_eq!(access_log.max_body_bytes, 1024);
+        assert!(access_log.is_logged("/new_block"));
+        assert!(!access_log.is_logged("/peers"));
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ACCESS_LOG__ENDPOINTS",
+            "new_block",
+        );
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn api_limits() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let api = settings.signer.api;
+        assert_eq!(api, ApiConfig::default());
+        assert_eq!(api.rate_limit_burst(), None);
+        assert_eq!(api.request_timeout(), None);
+
+        set_var("SIGNER_SIGNER__API__RATE_LIMIT", "50");
+        set_var("SIGNER_SIGNER__API__RATE_LIMIT_EXEMPT", "127.0.0.1,::1");
+        set_var("SIGNER_SIGNER__API__MAX_CONCURRENT_REQUESTS", "64");
+        set_var("SIGNER_SIGNER__API__MAX_CONNECTIONS", "256");
+        set_var("SIGNER_SIGNER__API__REQUEST_TIMEOUT", "10000");
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let api = settings.signer.api;
+        assert_eq!(api.rate_limit, 50);
+        assert_eq!(api.rate_limit_burst(), Some(50));
+        assert_eq!(api.rate_limit_exempt.len(), 2);
+        assert_eq!(api.max_concurrent_requests, 64);
+        assert_eq!(api.max_connections, 256);
+        assert_eq!(api.request_timeout(), Some(Duration::from_secs(10)));
+
+        set_var("SIGNER_SIGNER__API__RATE_LIMIT_BURST", "100");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.api.rate_limit_burst(), Some(100));
+
+        // The timeout must leave room for the latency budget of the
+        // `/new_block` endpoint.
+        set_var("SIGNER_SIGNER__API__REQUEST_TIMEOUT", "500");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn api_tls() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.api.tls, None);
+
+        set_var("SIGNER_SIGNER__API__TLS__CERTIFICATE", "api.crt");
+        set_var("SIGNER_SIGNER__API__TLS__PRIVATE_KEY", "api.key");
+        let settings = Settings::new_from_default_config().unwrap();
+        let tls = settings.signer.api.tls.unwrap();
+        assert_eq!(tls.certificate, PathBuf::from("api.crt"));
+        assert_eq!(tls.private_key, PathBuf::from("api.key"));
+        assert_eq!(tls.client_ca, None);
+
+        set_var("SIGNER_SIGNER__API__TLS__CLIENT_CA", "ca.crt");
+        let settings = Settings::new_from_default_config().unwrap();
+        let client_ca = settings.signer.api.tls.unwrap().client_ca;
+        assert_eq!(client_ca, Some(PathBuf::from("ca.crt")));
+    }
+
+    #[test]
+    fn api_admin_secret() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.api.admin_secret, None);
+
+        let secret = "a-shared-secret-for-admin-requests";
+        set_var("SIGNER_SIGNER__API__ADMIN_SECRET", secret);
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let api = settings.signer.api;
+        assert_eq!(api.admin_secret.as_deref(), Some(secret));
+        assert!(!format!("{api:?}").contains(secret));
+
+        set_var("SIGNER_SIGNER__API__ADMIN_SECRET", "short");
+        assert!(Settings::new_from_default_config().is_err());
+
+        // Whatever sends the `/new_block` webhooks must not be able to
+        // make admin requests.
+        set_var("SIGNER_SIGNER__API__ADMIN_SECRET", secret);
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__NEW_BLOCK_AUTH__SECRET",
+            secret,
+        );
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn event_observer_signed_responses() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let signed_responses = settings.signer.event_observer.signed_responses;
+        assert_eq!(signed_responses, SignedResponsesConfig::default());
+        assert!(!signed_responses.is_signed("/info"));
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__SIGNED_RESPONSES__ENDPOINTS",
+            "/info,/queue",
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let signed_responses = settings.signer.event_observer.signed_responses;
+        assert_eq!(signed_responses.endpoints, vec!["/info", "/queue"]);
+        assert!(signed_responses.is_signed("/queue"));
+        assert!(!signed_responses.is_signed("/peers"));
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__SIGNED_RESPONSES__ENDPOINTS",
+            "info",
+        );
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn event_observer_new_block_auth() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let new_block_auth = settings.signer.event_observer.new_block_auth;
+        assert_eq!(new_block_auth, NewBlockAuthConfig::default());
+
+        let secret = "a-shared-secret-for-new-blocks";
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__NEW_BLOCK_AUTH__SECRET",
+            secret,
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let new_block_auth = settings.signer.event_observer.new_block_auth;
+        assert_eq!(new_block_auth.secret.as_deref(), Some(secret));
+        assert!(!format!("{new_block_auth:?}").contains(secret));
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__NEW_BLOCK_AUTH__SECRET",
+            "short",
+        );
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn outbound_proxy() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.outbound, OutboundConfig::default());
+        assert!(
+            settings
+                .outbound
+                .proxy_for(&url("http://example.com"))
+                .is_none()
+        );
+
+        set_var("SIGNER_OUTBOUND__PROXY", "socks5h://127.0.0.1:9050");
+        set_var(
+            "SIGNER_OUTBOUND__NO_PROXY",
+            "localhost,.internal.example.com",
+        );
+        set_var("SIGNER_OUTBOUND__DNS_RESOLVER", "async");
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let outbound = settings.outbound;
+        let proxy = url("socks5h://127.0.0.1:9050");
+        assert_eq!(outbound.proxy, Some(proxy.clone()));
+        assert_eq!(outbound.dns_resolver, DnsResolver::Async);
+        assert_eq!(
+            outbound.proxy_for(&url("https://example.com")),
+            Some(&proxy)
+        );
+        assert!(outbound.proxy_for(&url("http://localhost:20443")).is_none());
+        assert!(
+            outbound
+                .proxy_for(&url("http://internal.example.com"))
+                .is_none()
+        );
+        assert!(
+            outbound
+                .proxy_for(&url("http://emily.INTERNAL.example.com"))
+                .is_none()
+        );
+        assert!(
+            outbound
+                .proxy_for(&url("http://notinternal.example.com"))
+                .is_some()
+        );
+
+        // The bitcoin RPC client only supports SOCKS5 proxies, so an HTTP
+        // proxy is only allowed if the bitcoin RPC hosts bypass it.
+        set_var("SIGNER_OUTBOUND__PROXY", "http://proxy.example.com:3128");
+        assert!(Settings::new_from_default_config().is_err());
+
+        set_var("SIGNER_OUTBOUND__NO_PROXY", "127.0.0.1");
+        assert!(Settings::new_from_default_config().is_ok());
+
+        set_var("SIGNER_OUTBOUND__PROXY", "ftp://proxy.example.com:21");
+        assert!(Settings::new_from_default_config().is_err());
+
+        set_var("SIGNER_OUTBOUND__PROXY", "socks5://proxy.example.com");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn outbound_connection_pool() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let outbound = settings.outbound;
+        assert_eq!(outbound.pool_max_idle_per_host, 32);
+        assert_eq!(outbound.pool_idle_timeout, Duration::from_secs(90));
+        assert_eq!(outbound.keep_alive(), Some(Duration::from_secs(30)));
+        assert!(!outbound.http2);
+
+        set_var("SIGNER_OUTBOUND__POOL_MAX_IDLE_PER_HOST", "4");
+        set_var("SIGNER_OUTBOUND__POOL_IDLE_TIMEOUT", "10");
+        set_var("SIGNER_OUTBOUND__KEEP_ALIVE_INTERVAL", "0");
+        set_var("SIGNER_OUTBOUND__HTTP2", "true");
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let outbound = settings.outbound;
+        assert_eq!(outbound.pool_max_idle_per_host, 4);
+        assert_eq!(outbound.pool_idle_timeout, Duration::from_secs(10));
+        assert_eq!(outbound.keep_alive(), None);
+        assert!(outbound.http2);
+
+        let endpoint = url("https://emily.example.com");
+        let client = outbound.http_client_builder(&endpoint).unwrap().build();
+        assert!(client.is_ok());
+        let client = outbound.emily_http_client_builder(&endpoint).unwrap();
+        assert!(client.build().is_ok());
+    }
+
+    #[test]
+    fn stacks_fee_budget() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.stacks_fee_budget_ustx.is_none());
+        assert_eq!(
+            settings.signer.stacks_fee_budget_period,
+            Duration::from_secs(DEFAULT_STACKS_FEE_BUDGET_PERIOD_SECONDS)
+        );
+
+        set_var("SIGNER_SIGNER__STACKS_FEE_BUDGET_USTX", "50000000");
+        set_var("SIGNER_SIGNER__STACKS_FEE_BUDGET_PERIOD", "3600");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.stacks_fee_budget_ustx,
+            NonZeroU64::new(50_000_000)
+        );
+        assert_eq!(
+            settings.signer.stacks_fee_budget_period,
+            Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn stx_low_balance_threshold() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.stx_low_balance_threshold_ustx.get(),
+            DEFAULT_STX_LOW_BALANCE_THRESHOLD_USTX
+        );
+
+        set_var("SIGNER_SIGNER__STX_LOW_BALANCE_THRESHOLD_USTX", "42000000");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.stx_low_balance_threshold_ustx.get(),
+            42_000_000
+        );
+    }
+
+    #[test]
+    fn sweep_fee_limits() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let limits = settings.signer.sweep_fee_limits();
+        assert_eq!(limits.max_fee_sats, DEFAULT_SWEEP_FEE_MAX_SATS);
+        assert_eq!(
+            limits.max_fee_fraction_bps,
+            DEFAULT_SWEEP_FEE_MAX_FRACTION_BPS
+        );
+
+        set_var("SIGNER_SIGNER__SWEEP_FEE_MAX_SATS", "50000");
+        set_var("SIGNER_SIGNER__SWEEP_FEE_MAX_FRACTION_BPS", "100");
+        let settings = Settings::new_from_default_config().unwrap();
+        let limits = settings.signer.sweep_fee_limits();
+        assert_eq!(limits.max_fee_sats, 50_000);
+        assert_eq!(limits.max_fee_fraction_bps, 100);
+
+        set_var("SIGNER_SIGNER__SWEEP_FEE_MAX_FRACTION_BPS", "10001");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn quorum_thresholds() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let thresholds = settings.signer.quorum_thresholds();
+        assert_eq!(thresholds, QuorumThresholds::default());
+
+        // The default config has a signing set of three signers, two of
+        // which must sign.
+        set_var("SIGNER_SIGNER__HIGH_IMPACT_SIGNATURES_REQUIRED", "3");
+        set_var("SIGNER_SIGNER__HIGH_IMPACT_SWEEP_AMOUNT", "100000000");
+        let settings = Settings::new_from_default_config().unwrap();
+        let thresholds = settings.signer.quorum_thresholds();
+        assert_eq!(thresholds.high_impact_signatures_required, Some(3));
+        assert_eq!(thresholds.high_impact_sweep_amount, Some(100_000_000));
+
+        set_var("SIGNER_SIGNER__HIGH_IMPACT_SIGNATURES_REQUIRED", "4");
+        assert!(Settings::new_from_default_config().is_err());
+
+        set_var("SIGNER_SIGNER__HIGH_IMPACT_SIGNATURES_REQUIRED", "1");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn integrity_key() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.integrity_key.is_none());
+        assert_eq!(
+            settings.signer.integrity_check_interval,
+            Duration::from_secs(DEFAULT_INTEGRITY_CHECK_INTERVAL_SECONDS)
+        );
+
+        set_var("SIGNER_SIGNER__INTEGRITY_KEY", "ab".repeat(32));
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.integrity_key.is_some());
+
+        set_var("SIGNER_SIGNER__INTEGRITY_KEY", "abcd");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn stale_deposit_settings() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.stale_deposit_check_interval,
+            Duration::from_secs(DEFAULT_STALE_DEPOSIT_CHECK_INTERVAL_SECONDS)
+        );
+        assert_eq!(
+            settings.signer.stale_deposit_min_age,
+            Duration::from_secs(DEFAULT_STALE_DEPOSIT_MIN_AGE_SECONDS)
+        );
+
+        set_var("SIGNER_SIGNER__STALE_DEPOSIT_MIN_AGE", "86400");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.stale_deposit_min_age,
+            Duration::from_secs(86_400)
+        );
+    }
+
+    #[test]
+    fn max_sweep_tx_vsize() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            u64::from(settings.signer.max_sweep_tx_vsize.get()),
+            MAX_STANDARD_TX_VSIZE
+        );
+
+        set_var("SIGNER_SIGNER__MAX_SWEEP_TX_VSIZE", "25000");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.max_sweep_tx_vsize.get(), 25_000);
+
+        set_var("SIGNER_SIGNER__MAX_SWEEP_TX_VSIZE", "0");
+        assert!(Settings::new_from_default_config().is_err());
+
+        set_var("SIGNER_SIGNER__MAX_SWEEP_TX_VSIZE", "100001");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn sweep_completion_deadline() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.sweep_completion_deadline.get(),
+            DEFAULT_SWEEP_COMPLETION_DEADLINE
+        );
+
+        set_var("SIGNER_SIGNER__SWEEP_COMPLETION_DEADLINE", "12");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.sweep_completion_deadline.get(), 12);
+
+        set_var("SIGNER_SIGNER__SWEEP_COMPLETION_DEADLINE", "0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn emily_deposit_polling() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.emily.deposit_poll_min_interval,
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            settings.emily.deposit_poll_max_interval,
+            Duration::from_secs(60)
+        );
+        assert_eq!(settings.emily.deposit_full_sync_blocks.get(), 6);
+
+        set_var("SIGNER_EMILY__DEPOSIT_POLL_MIN_INTERVAL", "2");
+        set_var("SIGNER_EMILY__DEPOSIT_POLL_MAX_INTERVAL", "120");
+        set_var("SIGNER_EMILY__DEPOSIT_FULL_SYNC_BLOCKS", "3");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.emily.deposit_poll_min_interval,
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            settings.emily.deposit_poll_max_interval,
+            Duration::from_secs(120)
+        );
+        assert_eq!(settings.emily.deposit_full_sync_blocks.get(), 3);
+
+        // The minimum interval may not exceed the maximum interval.
+        set_var("SIGNER_EMILY__DEPOSIT_POLL_MIN_INTERVAL", "180");
+        assert!(Settings::new_from_default_config().is_err());
+
+        set_var("SIGNER_EMILY__DEPOSIT_POLL_MIN_INTERVAL", "0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
     #[test]
     fn invalid_private_key_length_returns_correct_error() {
         clear_env();
@@ -1498,6 +3433,7 @@ mod tests {
 
     #[test_case::test_case(NetworkKind::Mainnet; "mainnet network, testnet deployer")]
     #[test_case::test_case(NetworkKind::Testnet; "testnet network, mainnet deployer")]
+    #[test_case::test_case(NetworkKind::Signet; "signet network, mainnet deployer")]
     fn network_mismatch_network_of_deployer(network: NetworkKind) {
         clear_env();
 
@@ -1509,6 +3445,8 @@ mod tests {
         let network = match network {
             NetworkKind::Mainnet => "mainnet",
             NetworkKind::Testnet => "testnet",
+            NetworkKind::Testnet4 => "testnet4",
+            NetworkKind::Signet => "signet",
             NetworkKind::Regtest => "regtest",
         };
         set_var("SIGNER_SIGNER__NETWORK", network);
@@ -1523,6 +3461,8 @@ mod tests {
 
     #[test_case::test_case(NetworkKind::Mainnet; "mainnet")]
     #[test_case::test_case(NetworkKind::Testnet; "testnet")]
+    #[test_case::test_case(NetworkKind::Testnet4; "testnet4")]
+    #[test_case::test_case(NetworkKind::Signet; "signet")]
     #[test_case::test_case(NetworkKind::Regtest; "regtest")]
     fn network_matches_network_of_deployer(network: NetworkKind) {
         clear_env();
@@ -1535,6 +3475,8 @@ mod tests {
         let network = match network {
             NetworkKind::Mainnet => "mainnet",
             NetworkKind::Testnet => "testnet",
+            NetworkKind::Testnet4 => "testnet4",
+            NetworkKind::Signet => "signet",
             NetworkKind::Regtest => "regtest",
         };
         set_var("SIGNER_SIGNER__NETWORK", network);
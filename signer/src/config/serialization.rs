@@ -1,10 +1,14 @@
 use std::{net::IpAddr, str::FromStr as _};
 
-use clarity::{types::chainstate::StacksAddress, vm::types::PrincipalData};
+use clarity::{
+    types::chainstate::StacksAddress,
+    vm::types::{PrincipalData, QualifiedContractIdentifier},
+};
 use libp2p::Multiaddr;
 use serde::{Deserialize as _, Deserializer};
 use url::Url;
 
+use crate::integrity::IntegrityKey;
 use crate::keys::PrivateKey;
 
 use super::error::SignerConfigError;
@@ -22,6 +26,21 @@ where
     Ok(v)
 }
 
+/// A deserializer for a list of clarity contract identifiers, like
+/// `SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry`.
+pub fn contract_identifier_deserializer_vec<'de, D>(
+    deserializer: D,
+) -> Result<Vec<QualifiedContractIdentifier>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut v = Vec::new();
+    for s in Vec::<String>::deserialize(deserializer)? {
+        v.push(QualifiedContractIdentifier::parse(&s).map_err(serde::de::Error::custom)?);
+    }
+    Ok(v)
+}
+
 /// A deserializer for the url::Url type. Does not support deserializing a list,
 /// only a single URL.
 pub fn url_deserializer_single<'de, D>(deserializer: D) -> Result<url::Url, D::Error>
@@ -33,6 +52,14 @@ where
         .map_err(serde::de::Error::custom)
 }
 
+/// A deserializer for an optional [`url::Url`].
+pub fn optional_url_deserializer<'de, D>(deserializer: D) -> Result<Option<url::Url>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    url_deserializer_single(deserializer).map(Some)
+}
+
 /// A deserializer for the std::time::Duration type.
 /// Serde includes a default deserializer, but it expects a struct.
 pub fn duration_seconds_deserializer<'de, D>(
@@ -97,6 +124,33 @@ where
     }
 }
 
+/// A deserializer for an optional [`PrivateKey`]. Applies the same
+/// validation as [`private_key_deserializer`] when a value is present.
+pub fn optional_private_key_deserializer<'de, D>(
+    deserializer: D,
+) -> Result<Option<PrivateKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    private_key_deserializer(deserializer).map(Some)
+}
+
+/// A deserializer for an optional [`IntegrityKey`]. Returns an error if
+/// the key is not 32 bytes of hex when a value is present.
+pub fn optional_integrity_key_deserializer<'de, D>(
+    deserializer: D,
+) -> Result<Option<IntegrityKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(&s, &mut bytes)
+        .map_err(|_| serde::de::Error::custom(SignerConfigError::InvalidIntegrityKey))?;
+
+    Ok(Some(IntegrityKey::new(bytes)))
+}
+
 pub fn try_parse_p2p_multiaddr(s: &str) -> Result<Multiaddr, SignerConfigError> {
     // Keeping these local here as this is the only place these should need to be used.
     use SignerConfigError::{
@@ -0,0 +1,287 @@
+//! # Confirmation watches
+//!
+//! A [`ConfirmationWatch`] asks to be told once a bitcoin transaction has
+//! a given number of confirmations. Subsystems of the signer register
+//! watches with [`DbWrite::write_confirmation_watch`] and subscribe to the
+//! [`TransactionConfirmed`] topic of the event bus, while external
+//! integrators, like the ones awaiting the finality of a sweep, register
+//! them through the `/confirmations` endpoint of the API along with a URL
+//! to notify.
+//!
+//! The [`ConfirmationWatcher`] checks the watches whenever the block
+//! observer has processed a new bitcoin block. A watch fires once the
+//! bitcoin node reports at least its number of confirmations for the
+//! transaction: the [`TransactionConfirmed`] event is published, the
+//! callback URL of the watch, if any, is notified like a webhook
+//! subscriber, and the watch is deleted.
+//!
+//! [`DbWrite::write_confirmation_watch`]: crate::storage::DbWrite::write_confirmation_watch
+
+use std::collections::BTreeMap;
+
+use tokio::sync::broadcast::error::RecvError;
+use url::Url;
+
+use crate::bitcoin::BitcoinInteract as _;
+use crate::context::BitcoinBlockObserved;
+use crate::context::Context;
+use crate::context::TransactionConfirmed;
+use crate::error::Error;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::ConfirmationWatch;
+use crate::webhooks::WebhookEvent;
+
+/// A fired confirmation watch that has a URL to notify.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionConfirmation {
+    /// The confirmed transaction and the block that confirmed it.
+    pub event: TransactionConfirmed,
+    /// The URL that is notified.
+    pub callback_url: Url,
+}
+
+/// Fires confirmation watches once their transactions have enough
+/// confirmations.
+pub struct ConfirmationWatcher<C> {
+    /// Signer context.
+    context: C,
+}
+
+impl<C> ConfirmationWatcher<C>
+where
+    C: Context,
+{
+    /// Creates a new ConfirmationWatcher with the given context.
+    pub fn new(context: C) -> Self {
+        Self { context }
+    }
+
+    /// Runs the ConfirmationWatcher, which checks the watches whenever the
+    /// block observer has processed a new bitcoin block.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        let mut blocks = self.context.events().subscribe::<BitcoinBlockObserved>();
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                event = blocks.recv() => match event {
+                    Ok(BitcoinBlockObserved { chain_tip }) => self.check(&chain_tip).await,
+                    Err(RecvError::Lagged(_)) => {
+                        if let Some(chain_tip) = self.context.state().bitcoin_chain_tip() {
+                            self.check(&chain_tip).await;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        tracing::info!("confirmation watcher has stopped");
+    }
+
+    /// Fire the watches whose transactions have enough confirmations.
+    #[tracing::instrument(
+        skip_all,
+        name = "confirmation-watcher",
+        fields(chain_tip = %chain_tip.block_hash)
+    )]
+    pub async fn check(&self, chain_tip: &BitcoinBlockRef) {
+        let watches = match self.context.get_storage().get_confirmation_watches().await {
+            Ok(watches) => watches,
+            Err(error) => {
+                tracing::warn!(%error, "could not look up confirmation watches");
+                return;
+            }
+        };
+
+        // There can be more than one watch on a transaction, and we only
+        // need to ask the bitcoin node about it once.
+        let mut by_txid: BTreeMap<BitcoinTxId, Vec<ConfirmationWatch>> = BTreeMap::new();
+        for watch in watches {
+            by_txid.entry(watch.txid).or_default().push(watch);
+        }
+
+        for (txid, watches) in by_txid {
+            if let Err(error) = self.check_transaction(&txid, &watches).await {
+                tracing::warn!(%error, %txid, "could not check confirmation watches");
+            }
+        }
+    }
+
+    async fn check_transaction(
+        &self,
+        txid: &BitcoinTxId,
+        watches: &[ConfirmationWatch],
+    ) -> Result<(), Error> {
+        let response = self
+            .context
+            .get_bitcoin_client()
+            .get_tx(&(*txid).into())
+            .await?;
+        let Some(response) = response else {
+            return Ok(());
+        };
+        let (Some(block_hash), Some(confirmations)) = (response.block_hash, response.confirmations)
+        else {
+            return Ok(());
+        };
+
+        let event = TransactionConfirmed {
+            txid: *txid,
+            block_hash: block_hash.into(),
+            confirmations,
+        };
+        for watch in watches {
+            if confirmations >= watch.confirmations {
+                self.fire(watch, event).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn fire(
+        &self,
+        watch: &ConfirmationWatch,
+        event: TransactionConfirmed,
+    ) -> Result<(), Error> {
+        // The watch may have been removed through the API since we read
+        // it, in which case nobody is waiting for it anymore.
+        if !self
+            .context
+            .get_storage_mut()
+            .delete_confirmation_watch(watch)
+            .await?
+        {
+            return Ok(());
+        }
+
+        tracing::info!(
+            txid = %event.txid,
+            block_hash = %event.block_hash,
+            confirmations = event.confirmations,
+            required = watch.confirmations,
+            "confirmation watch fired"
+        );
+        self.context.events().publish(event);
+
+        let Some(callback_url) = watch.callback_url.as_deref() else {
+            return Ok(());
+        };
+        match callback_url.parse() {
+            Ok(callback_url) => {
+                let confirmation = TransactionConfirmation { event, callback_url };
+                let event = WebhookEvent::TransactionConfirmed(confirmation);
+                let _ = self.context.get_signal_sender().send(event.into());
+            }
+            Err(error) => {
+                tracing::warn!(%error, "ignoring invalid confirmation callback URL");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::bitcoin::rpc::GetTxResponse;
+    use crate::context::SignerEvent;
+    use crate::context::SignerSignal;
+    use crate::storage::model::BitcoinBlockHash;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// Watches fire once their transaction has enough confirmations, and
+    /// only the ones with a callback URL are sent to the webhook
+    /// dispatcher.
+    #[tokio::test]
+    async fn watches_fire_once_confirmed() {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+        let mut rng = get_rng();
+
+        let txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+        let block_hash: BitcoinBlockHash = Faker.fake_with_rng(&mut rng);
+        let internal = ConfirmationWatch {
+            txid,
+            confirmations: 1,
+            callback_url: None,
+        };
+        let external = ConfirmationWatch {
+            txid,
+            confirmations: 3,
+            callback_url: Some("https://example.com/confirmed".to_string()),
+        };
+        for watch in [&internal, &external] {
+            db.write_confirmation_watch(watch).await.unwrap();
+        }
+
+        ctx.with_bitcoin_client(|client| {
+            let mut confirmations = 1..;
+            client.expect_get_tx().times(3).returning(move |_| {
+                let response = GetTxResponse {
+                    tx: bitcoin::Transaction {
+                        version: bitcoin::transaction::Version::TWO,
+                        lock_time: bitcoin::absolute::LockTime::ZERO,
+                        input: Vec::new(),
+                        output: Vec::new(),
+                    },
+                    block_hash: Some(block_hash.into()),
+                    confirmations: confirmations.next(),
+                    block_time: None,
+                };
+                Box::pin(std::future::ready(Ok(Some(response))))
+            });
+        })
+        .await;
+
+        let mut confirmed = ctx.events().subscribe::<TransactionConfirmed>();
+        let mut signals = ctx.get_signal_receiver();
+        let watcher = ConfirmationWatcher::new(ctx.clone());
+        let chain_tip = BitcoinBlockRef::genesis();
+
+        // With one confirmation only the internal watch fires.
+        watcher.check(&chain_tip).await;
+        assert_eq!(
+            db.get_confirmation_watches().await.unwrap(),
+            [external.clone()]
+        );
+        assert_eq!(confirmed.try_recv().unwrap().confirmations, 1);
+
+        // With two the external one does not fire yet.
+        watcher.check(&chain_tip).await;
+        assert_eq!(
+            db.get_confirmation_watches().await.unwrap(),
+            [external.clone()]
+        );
+
+        watcher.check(&chain_tip).await;
+        assert!(db.get_confirmation_watches().await.unwrap().is_empty());
+        let event = confirmed.try_recv().unwrap();
+        assert_eq!(event.confirmations, 3);
+        assert!(confirmed.try_recv().is_err());
+
+        let mut notified = Vec::new();
+        while let Ok(signal) = signals.try_recv() {
+            if let SignerSignal::Event(SignerEvent::WebhookEvent(event)) = signal {
+                notified.push(*event);
+            }
+        }
+        let confirmation = TransactionConfirmation {
+            event,
+            callback_url: "https://example.com/confirmed".parse().unwrap(),
+        };
+        assert_eq!(
+            notified,
+            vec![WebhookEvent::TransactionConfirmed(confirmation)]
+        );
+    }
+}
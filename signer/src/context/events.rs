@@ -0,0 +1,205 @@
+//! This module contains the typed event bus of the [`Context`].
+//!
+//! The signalling channel of the context carries commands and the events
+//! that drive the main event loops, and every receiver sees every signal.
+//! The event bus is for notifications about what happened in the signer,
+//! which other subsystems, like monitors, webhooks or caches, may want to
+//! react to. Each kind of event is published on its own topic, and
+//! subscribers receive only the events of the topics they subscribe to,
+//! so a new subsystem can subscribe to a topic without any changes to the
+//! code that publishes on it.
+//!
+//! [`Context`]: super::Context
+
+use tokio::sync::broadcast;
+
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::StacksBlock;
+
+/// The capacity of the channel of each topic. Subscribers that fall
+/// further behind than this miss the oldest events.
+const TOPIC_CAPACITY: usize = 128;
+
+/// A topic of the [`EventBus`], with the type of the topic's events.
+pub trait Topic: Clone + Send + 'static {
+    /// The name of the topic, used in logs.
+    const NAME: &'static str;
+
+    /// Return the channel that carries the events of this topic.
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self>;
+}
+
+/// Published by the block observer once it has processed a new bitcoin
+/// chain tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitcoinBlockObserved {
+    /// The new bitcoin chain tip.
+    pub chain_tip: BitcoinBlockRef,
+}
+
+/// Published when the stacks node notifies us about a new stacks block,
+/// including when it delivers the webhook of a block again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StacksBlockObserved {
+    /// The new stacks block.
+    pub block: StacksBlock,
+}
+
+/// Published by the request decider once it has handled the pending
+/// deposit and withdrawal requests for a bitcoin chain tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestsUpdated {
+    /// The bitcoin chain tip that the requests were handled for.
+    pub chain_tip: BitcoinBlockRef,
+}
+
+/// The kinds of rounds that the coordinator runs with the other signers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundKind {
+    /// Distributed key generation.
+    Dkg,
+    /// A signing round for an input of a bitcoin transaction.
+    BitcoinSigning,
+    /// A signing round for a stacks transaction.
+    StacksSigning,
+}
+
+impl RoundKind {
+    /// The name of the kind of round, as it appears in the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dkg => "dkg",
+            Self::BitcoinSigning => "bitcoin_signing",
+            Self::StacksSigning => "stacks_signing",
+        }
+    }
+}
+
+/// Published by the coordinator when a round with the other signers has
+/// finished, whether it succeeded or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundCompleted {
+    /// The kind of round.
+    pub kind: RoundKind,
+    /// The bitcoin chain tip that the round was run at.
+    pub chain_tip: BitcoinBlockHash,
+    /// Whether the round produced a result.
+    pub success: bool,
+}
+
+/// Published by the confirmation watcher when a bitcoin transaction
+/// reaches the number of confirmations of a watch on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionConfirmed {
+    /// The ID of the transaction.
+    pub txid: BitcoinTxId,
+    /// The bitcoin block that confirmed the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The number of confirmations of the transaction.
+    pub confirmations: u32,
+}
+
+/// A publish/subscribe event bus with a channel for each topic. Clones of
+/// the bus share the same channels.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    bitcoin_block_observed: broadcast::Sender<BitcoinBlockObserved>,
+    stacks_block_observed: broadcast::Sender<StacksBlockObserved>,
+    requests_updated: broadcast::Sender<RequestsUpdated>,
+    round_completed: broadcast::Sender<RoundCompleted>,
+    transaction_confirmed: broadcast::Sender<TransactionConfirmed>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self {
+            bitcoin_block_observed: broadcast::channel(TOPIC_CAPACITY).0,
+            stacks_block_observed: broadcast::channel(TOPIC_CAPACITY).0,
+            requests_updated: broadcast::channel(TOPIC_CAPACITY).0,
+            round_completed: broadcast::channel(TOPIC_CAPACITY).0,
+            transaction_confirmed: broadcast::channel(TOPIC_CAPACITY).0,
+        }
+    }
+}
+
+impl EventBus {
+    /// Publish the event to the subscribers of its topic, returning the
+    /// number of subscribers that will receive it. Publishing never fails;
+    /// an event without subscribers is dropped.
+    pub fn publish<T: Topic>(&self, event: T) -> usize {
+        let subscribers = T::channel(self).send(event).unwrap_or(0);
+        tracing::trace!(topic = T::NAME, %subscribers, "published event");
+        subscribers
+    }
+
+    /// Subscribe to the events of a topic. The receiver gets the events
+    /// that are published after this call.
+    pub fn subscribe<T: Topic>(&self) -> broadcast::Receiver<T> {
+        T::channel(self).subscribe()
+    }
+}
+
+impl Topic for BitcoinBlockObserved {
+    const NAME: &'static str = "bitcoin-block-observed";
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self> {
+        &bus.bitcoin_block_observed
+    }
+}
+
+impl Topic for StacksBlockObserved {
+    const NAME: &'static str = "stacks-block-observed";
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self> {
+        &bus.stacks_block_observed
+    }
+}
+
+impl Topic for RequestsUpdated {
+    const NAME: &'static str = "requests-updated";
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self> {
+        &bus.requests_updated
+    }
+}
+
+impl Topic for RoundCompleted {
+    const NAME: &'static str = "round-completed";
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self> {
+        &bus.round_completed
+    }
+}
+
+impl Topic for TransactionConfirmed {
+    const NAME: &'static str = "transaction-confirmed";
+    fn channel(bus: &EventBus) -> &broadcast::Sender<Self> {
+        &bus.transaction_confirmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn subscribers_only_receive_their_topic() {
+        let bus = EventBus::default();
+        let mut blocks = bus.subscribe::<BitcoinBlockObserved>();
+        let mut rounds = bus.clone().subscribe::<RoundCompleted>();
+
+        let chain_tip = BitcoinBlockRef::genesis();
+        assert_eq!(bus.publish(BitcoinBlockObserved { chain_tip }), 1);
+        assert_eq!(bus.publish(RequestsUpdated { chain_tip }), 0);
+
+        let round = RoundCompleted {
+            kind: RoundKind::Dkg,
+            chain_tip: chain_tip.block_hash,
+            success: true,
+        };
+        assert_eq!(bus.publish(round), 1);
+
+        assert_eq!(blocks.recv().await.unwrap().chain_tip, chain_tip);
+        assert!(blocks.try_recv().is_err());
+        assert_eq!(rounds.recv().await.unwrap(), round);
+        assert!(rounds.try_recv().is_err());
+    }
+}
@@ -28,6 +28,9 @@ impl SignerSignal {
 pub enum SignerCommand {
     /// Signals to the application to publish a message to the P2P network.
     P2PPublish(Box<crate::network::Msg>),
+    /// Signals to the application to publish a message to the bulk sync
+    /// topic of the P2P network.
+    P2PPublishSync(Box<crate::network::sync::SyncMessage>),
     /// Signal to shut down the application
     Shutdown,
 }
@@ -45,6 +48,8 @@ pub enum SignerEvent {
     TxSigner(TxSignerEvent),
     /// Transaction coordinator events
     TxCoordinator(TxCoordinatorEvent),
+    /// An event that webhook subscribers should be notified about.
+    WebhookEvent(Box<crate::webhooks::WebhookEvent>),
 }
 
 /// Events that can be triggered from the P2P network.
@@ -61,6 +66,9 @@ pub enum P2PEvent {
     PeerConnected(libp2p::PeerId),
     /// Event which occurs when the P2P network has started its event loop.
     EventLoopStarted,
+    /// Signals to the application that a batch of bitcoin block summaries
+    /// was received on the bulk sync topic.
+    BlockSummariesReceived(Box<crate::network::sync::BlockSummaryBatch>),
 }
 
 /// Events that can be triggered from the request decider.
@@ -1,6 +1,9 @@
 //! Context module for the signer binary.
 
+mod events;
 mod messaging;
+mod rng;
+mod scheduler;
 mod signer_context;
 mod signer_state;
 mod termination;
@@ -18,7 +21,10 @@ use crate::storage::DbRead;
 use crate::storage::DbWrite;
 use crate::storage::Transactable;
 
+pub use events::*;
 pub use messaging::*;
+pub use rng::ContextRng;
+pub use scheduler::*;
 pub use signer_context::SignerContext;
 pub use signer_state::*;
 pub use termination::*;
@@ -29,6 +35,9 @@ pub trait Context: Clone + Sync + Send {
     fn config(&self) -> &Settings;
     /// Get the current state for the signer.
     fn state(&self) -> &SignerState;
+    /// Get the event bus, for publishing and subscribing to the typed
+    /// events of the signer.
+    fn events(&self) -> &EventBus;
     /// Subscribe to the application signalling channel, returning a receiver
     /// which can be used to listen for events.
     fn get_signal_receiver(&self) -> tokio::sync::broadcast::Receiver<SignerSignal>;
@@ -50,6 +59,10 @@ pub trait Context: Clone + Sync + Send {
     fn get_stacks_client(&self) -> impl StacksInteract + Clone + 'static;
     /// Get a handle to an Emily client.
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static;
+    /// Get a handle to the shared random number generator, which is
+    /// seeded from the `signer.rng_seed` configuration value when it is
+    /// set. It must not be used for key generation or signing nonces.
+    fn rng(&self) -> ContextRng;
 
     /// Create a new signal stream containing signer messages from:
     /// 1. The signer network, as defined by the given network object
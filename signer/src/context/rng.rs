@@ -0,0 +1,107 @@
+//! Module for the random number generator shared through the context.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rand::Rng as _;
+use rand::RngCore;
+use rand::SeedableRng as _;
+use rand::rngs::OsRng;
+use rand::rngs::StdRng;
+
+/// A seedable random number generator that is shared by all clones of
+/// the context.
+///
+/// This generator is for randomized behavior that is not security
+/// critical, like the jitter of retry delays or generating test data, so
+/// that all of it can be made deterministic with a single seed. It
+/// deliberately does not implement [`rand::CryptoRng`]; key generation
+/// and signing nonces must use [`OsRng`].
+#[derive(Debug, Clone)]
+pub struct ContextRng {
+    seed: u64,
+    inner: Arc<Mutex<StdRng>>,
+}
+
+impl ContextRng {
+    /// Create a new generator from the given seed, or from a random seed
+    /// taken from the operating system if there is none.
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| OsRng.next_u64());
+        Self {
+            seed,
+            inner: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// The seed that this generator was created with. Setting the
+    /// `signer.rng_seed` configuration value to it reproduces the same
+    /// sequence of random values.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Add up to 25% of random jitter to the given delay, so that signers
+    /// retrying the same operation do not do so in lockstep.
+    pub fn jitter(&self, delay: Duration) -> Duration {
+        let max_jitter = delay / 4;
+        let nanos = max_jitter.as_nanos().min(u64::MAX as u128) as u64;
+        delay + Duration::from_nanos(self.lock().gen_range(0..=nanos))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StdRng> {
+        self.inner.lock().expect("BUG: context rng lock poisoned")
+    }
+}
+
+impl RngCore for ContextRng {
+    fn next_u32(&mut self) -> u32 {
+        self.lock().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.lock().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.lock().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.lock().try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_generators_are_deterministic() {
+        let mut rng1 = ContextRng::new(Some(42));
+        let mut rng2 = ContextRng::new(Some(42));
+        assert_eq!(rng1.seed(), 42);
+
+        let values1: Vec<u64> = (0..10).map(|_| rng1.next_u64()).collect();
+        let values2: Vec<u64> = (0..10).map(|_| rng2.next_u64()).collect();
+        assert_eq!(values1, values2);
+
+        let delay = Duration::from_secs(4);
+        let jitter1 = ContextRng::new(Some(7)).jitter(delay);
+        let jitter2 = ContextRng::new(Some(7)).jitter(delay);
+        assert_eq!(jitter1, jitter2);
+        assert!(jitter1 >= delay && jitter1 <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn clones_share_the_same_sequence() {
+        let mut rng = ContextRng::new(Some(42));
+        let mut clone = rng.clone();
+        let mut expected = StdRng::seed_from_u64(42);
+
+        assert_eq!(rng.next_u64(), expected.next_u64());
+        assert_eq!(clone.next_u64(), expected.next_u64());
+        assert_eq!(rng.next_u64(), expected.next_u64());
+    }
+}
@@ -0,0 +1,212 @@
+//! # Chain event scheduler
+//!
+//! Bitcoin blocks reach the signer through the block observer, while
+//! stacks blocks reach it through `POST /new_block` webhooks, and both
+//! can arrive at the same time. If a stacks webhook is digested while the
+//! block observer is halfway through updating the bitcoin chain tip, then
+//! validators can briefly see stacks events anchored to a bitcoin block
+//! that is not yet in the database, or a bitcoin chain tip that does not
+//! yet account for the events.
+//!
+//! The [`ChainEventScheduler`] orders this work. Bitcoin chain tip
+//! updates run alone and take priority: once one is waiting, no new
+//! stacks events are digested until it is done. Stacks events are
+//! digested concurrently with each other, but only up to
+//! [`MAX_CONCURRENT_STACKS_EVENTS`](crate::MAX_CONCURRENT_STACKS_EVENTS)
+//! at a time, so that a burst of webhooks cannot exhaust the database
+//! connection pool.
+
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+/// Schedules bitcoin chain tip updates and the digestion of stacks events.
+#[derive(Debug)]
+pub struct ChainEventScheduler {
+    state: Mutex<SchedulerState>,
+    notify: Notify,
+    max_stacks_events: usize,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// The number of bitcoin chain tip updates waiting to run.
+    bitcoin_waiting: usize,
+    /// Whether a bitcoin chain tip update is running.
+    bitcoin_running: bool,
+    /// The number of stacks events being digested.
+    stacks_running: usize,
+}
+
+impl ChainEventScheduler {
+    /// Create a scheduler that digests at most `max_stacks_events` stacks
+    /// events at a time.
+    pub fn new(max_stacks_events: usize) -> Self {
+        Self {
+            state: Mutex::new(SchedulerState::default()),
+            notify: Notify::new(),
+            max_stacks_events: max_stacks_events.max(1),
+        }
+    }
+
+    /// Wait until a bitcoin chain tip update may run. The update runs
+    /// until the returned guard is dropped, and nothing else runs
+    /// alongside it.
+    pub async fn bitcoin_tip_update(&self) -> BitcoinTipUpdateGuard<'_> {
+        self.update(|state| state.bitcoin_waiting += 1);
+        // If we are cancelled while waiting, the guard removes us from the
+        // queue so that stacks events are not held back forever.
+        let waiting = WaitingBitcoinTipUpdate(self);
+
+        self.acquire(|state| {
+            let can_run = !state.bitcoin_running && state.stacks_running == 0;
+            if can_run {
+                state.bitcoin_waiting -= 1;
+                state.bitcoin_running = true;
+            }
+            can_run
+        })
+        .await;
+
+        // We are no longer waiting, and the count was updated above.
+        std::mem::forget(waiting);
+        BitcoinTipUpdateGuard(self)
+    }
+
+    /// Wait until a stacks event may be digested. The event is digested
+    /// until the returned guard is dropped.
+    pub async fn stacks_event(&self) -> StacksEventGuard<'_> {
+        let max_stacks_events = self.max_stacks_events;
+        self.acquire(|state| {
+            let can_run = state.bitcoin_waiting == 0
+                && !state.bitcoin_running
+                && state.stacks_running < max_stacks_events;
+            if can_run {
+                state.stacks_running += 1;
+            }
+            can_run
+        })
+        .await;
+
+        StacksEventGuard(self)
+    }
+
+    /// Wait until `try_acquire` returns true. It is called with the lock
+    /// on the state held, and again whenever the state changes.
+    async fn acquire<F>(&self, mut try_acquire: F)
+    where
+        F: FnMut(&mut SchedulerState) -> bool,
+    {
+        loop {
+            // We register for notifications before checking the state, so
+            // that we cannot miss a change made between the two.
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.update(&mut try_acquire) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Apply the given change to the state.
+    fn update<T>(&self, f: impl FnOnce(&mut SchedulerState) -> T) -> T {
+        let mut state = self.state.lock().expect("BUG: scheduler lock poisoned");
+        f(&mut state)
+    }
+
+    /// Apply the given change to the state and wake up everything that is
+    /// waiting on it.
+    fn release(&self, f: impl FnOnce(&mut SchedulerState)) {
+        self.update(f);
+        self.notify.notify_waiters();
+    }
+}
+
+/// Removes a cancelled bitcoin chain tip update from the queue.
+struct WaitingBitcoinTipUpdate<'a>(&'a ChainEventScheduler);
+
+impl Drop for WaitingBitcoinTipUpdate<'_> {
+    fn drop(&mut self) {
+        self.0.release(|state| state.bitcoin_waiting -= 1);
+    }
+}
+
+/// Marks a running bitcoin chain tip update, see
+/// [`ChainEventScheduler::bitcoin_tip_update`].
+#[derive(Debug)]
+pub struct BitcoinTipUpdateGuard<'a>(&'a ChainEventScheduler);
+
+impl Drop for BitcoinTipUpdateGuard<'_> {
+    fn drop(&mut self) {
+        self.0.release(|state| state.bitcoin_running = false);
+    }
+}
+
+/// Marks a stacks event being digested, see
+/// [`ChainEventScheduler::stacks_event`].
+#[derive(Debug)]
+pub struct StacksEventGuard<'a>(&'a ChainEventScheduler);
+
+impl Drop for StacksEventGuard<'_> {
+    fn drop(&mut self) {
+        self.0.release(|state| state.stacks_running -= 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::FutureExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stacks_events_are_bounded() {
+        let scheduler = ChainEventScheduler::new(2);
+
+        let first = scheduler.stacks_event().await;
+        let _second = scheduler.stacks_event().await;
+        assert!(scheduler.stacks_event().now_or_never().is_none());
+
+        drop(first);
+        assert!(scheduler.stacks_event().now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn bitcoin_tip_updates_take_priority() {
+        let scheduler = ChainEventScheduler::new(2);
+
+        let stacks = scheduler.stacks_event().await;
+
+        // The bitcoin update waits for the stacks event that is already
+        // being digested, and new stacks events wait for the update.
+        let bitcoin = scheduler.bitcoin_tip_update();
+        tokio::pin!(bitcoin);
+        assert!(bitcoin.as_mut().now_or_never().is_none());
+        assert!(scheduler.stacks_event().now_or_never().is_none());
+
+        drop(stacks);
+        let guard = tokio::time::timeout(Duration::from_secs(1), bitcoin)
+            .await
+            .unwrap();
+        assert!(scheduler.stacks_event().now_or_never().is_none());
+
+        drop(guard);
+        assert!(scheduler.stacks_event().now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn cancelled_bitcoin_tip_updates_do_not_block_stacks_events() {
+        let scheduler = ChainEventScheduler::new(1);
+
+        let stacks = scheduler.stacks_event().await;
+        assert!(scheduler.bitcoin_tip_update().now_or_never().is_none());
+
+        drop(stacks);
+        assert!(scheduler.stacks_event().now_or_never().is_some());
+    }
+}
@@ -1,18 +1,17 @@
 use std::sync::Arc;
 use tokio::sync::broadcast::Sender;
-use url::Url;
 
 use crate::{
     SIGNER_CHANNEL_CAPACITY,
     bitcoin::BitcoinInteract,
-    config::{EmilyClientConfig, Settings},
+    config::Settings,
     emily_client::EmilyInteract,
     error::Error,
     stacks::api::StacksInteract,
     storage::{DbRead, DbWrite, Transactable},
 };
 
-use super::{Context, SignerSignal, SignerState, TerminationHandle};
+use super::{Context, ContextRng, EventBus, SignerSignal, SignerState, TerminationHandle};
 
 /// Signer context which is passed to different components within the
 /// signer binary.
@@ -25,6 +24,8 @@ pub struct SignerContext<S, BC, ST, EM> {
     signal_tx: Sender<SignerSignal>,
     /// The internal state of the signer.
     state: Arc<SignerState>,
+    /// The event bus of the signer.
+    events: EventBus,
     /// Handle to the app termination channel. This keeps the channel alive
     /// for the duration of the program and is used to provide new senders
     /// and receivers for a [`TerminationHandle`].
@@ -40,6 +41,8 @@ pub struct SignerContext<S, BC, ST, EM> {
     stacks_client: ST,
     /// Handle to a Emily-API fallback-client.
     emily_client: EM,
+    /// The random number generator shared by all clones of the context.
+    rng: ContextRng,
     // /// Handle to a Blocklist-API fallback-client.
     //blocklist_client: ApiFallbackClient<BL>,
 }
@@ -47,19 +50,19 @@ pub struct SignerContext<S, BC, ST, EM> {
 impl<S, BC, ST, EM> SignerContext<S, BC, ST, EM>
 where
     S: DbRead + DbWrite + Clone + Sync + Send + 'static,
-    BC: for<'a> TryFrom<&'a [Url]> + BitcoinInteract + Clone + 'static,
+    BC: for<'a> TryFrom<&'a Settings> + BitcoinInteract + Clone + 'static,
     ST: for<'a> TryFrom<&'a Settings> + StacksInteract + Clone + Sync + Send + 'static,
-    EM: for<'a> TryFrom<&'a EmilyClientConfig> + EmilyInteract + Clone + Sync + Send + 'static,
-    Error: for<'a> From<<BC as TryFrom<&'a [Url]>>::Error>,
+    EM: for<'a> TryFrom<&'a Settings> + EmilyInteract + Clone + Sync + Send + 'static,
+    Error: for<'a> From<<BC as TryFrom<&'a Settings>>::Error>,
     Error: for<'a> From<<ST as TryFrom<&'a Settings>>::Error>,
-    Error: for<'a> From<<EM as TryFrom<&'a EmilyClientConfig>>::Error>,
+    Error: for<'a> From<<EM as TryFrom<&'a Settings>>::Error>,
 {
     /// Initializes a new [`SignerContext`], automatically creating clients
     /// based on the provided types.
     pub fn init(config: Settings, db: S) -> Result<Self, Error> {
-        let bc = BC::try_from(&config.bitcoin.rpc_endpoints)?;
+        let bc = BC::try_from(&config)?;
         let st = ST::try_from(&config)?;
-        let em = EM::try_from(&config.emily)?;
+        let em = EM::try_from(&config)?;
 
         Ok(Self::new(config, db, bc, st, em))
     }
@@ -89,16 +92,24 @@ where
         if let Some(height) = config.signer.sbtc_bitcoin_start_height {
             state.set_sbtc_bitcoin_start_height(height);
         }
+        state.set_maintenance_mode(config.signer.maintenance_mode);
+        let rng = ContextRng::new(config.signer.rng_seed);
+        tracing::debug!(
+            seed = rng.seed(),
+            "initialized the context random number generator"
+        );
 
         Self {
             config,
             state: Arc::new(state),
+            events: EventBus::default(),
             signal_tx,
             term_tx,
             storage: db,
             bitcoin_client,
             stacks_client,
             emily_client,
+            rng,
         }
     }
 }
@@ -118,6 +129,10 @@ where
         &self.state
     }
 
+    fn events(&self) -> &EventBus {
+        &self.events
+    }
+
     fn get_signal_receiver(&self) -> tokio::sync::broadcast::Receiver<SignerSignal> {
         self.signal_tx.subscribe()
     }
@@ -165,6 +180,10 @@ where
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static {
         self.emily_client.clone()
     }
+
+    fn rng(&self) -> ContextRng {
+        self.rng.clone()
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -1,19 +1,50 @@
 //! Module for signer state
 
 use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
 use std::sync::{
     RwLock,
-    atomic::{AtomicBool, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
 };
 
 use bitcoin::Amount;
+use hashbrown::HashMap;
 use hashbrown::HashSet;
 use libp2p::PeerId;
 
+use crate::MAX_CONCURRENT_STACKS_EVENTS;
+use crate::MAX_RECENT_EVENTS;
+use crate::bitcoin::queue::PendingQueue;
+use crate::bitcoin::warm_up::BitcoinNodeState;
+use crate::checkpoint::StateCheckpoint;
+use crate::context::ChainEventScheduler;
+use crate::context::RoundKind;
+use crate::dry_run::DryRunPackage;
 use crate::keys::PublicKey;
+use crate::message::CoordinatorAnnouncement;
+use crate::network::stats::PeerStatistics;
 use crate::stacks::api::SignerSetInfo;
+use crate::stacks::block_sequence::BlockOrder;
+use crate::stacks::block_sequence::StacksBlockSequence;
+use crate::stacks::tenure::StacksTenures;
+use crate::stacks::tenure::TenureChange;
+use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::StacksBlock;
+
+/// A coordinator tenure that this signer is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinatorTenure {
+    /// Our public key, which we announced the tenure with.
+    pub coordinator: PublicKey,
+    /// The announcement that we sent for the tenure.
+    pub announcement: CoordinatorAnnouncement,
+    /// The public key of the competing coordinator whose tenure won over
+    /// ours, if any.
+    pub superseded_by: Option<PublicKey>,
+}
 
 /// A struct for holding internal signer state. This struct is served by
 /// the [`SignerContext`] and can be used to cache global state instead of
@@ -29,6 +60,54 @@ pub struct SignerState {
     // The current bitcoin chain tip. This gets updated at the end of the
     // block observer's duties when it observes a new bitcoin block.
     bitcoin_chain_tip: RwLock<Option<BitcoinBlockRef>>,
+    // The most recent clock skew estimate, in milliseconds, for each peer
+    // that has sent us a heartbeat.
+    peer_clock_skews: RwLock<HashMap<PublicKey, i64>>,
+    // The most recent configuration fingerprint for each peer that has
+    // sent us a heartbeat with one.
+    peer_config_fingerprints: RwLock<HashMap<PublicKey, [u8; 32]>>,
+    // The most recent checkpoint of the state that we have processed.
+    state_checkpoint: RwLock<Option<StateCheckpoint>>,
+    // The coordinator tenure that we are currently running, if any.
+    coordinator_tenure: RwLock<Option<CoordinatorTenure>>,
+    // The round with the other signers that we are currently coordinating,
+    // if any.
+    round_in_flight: RwLock<Option<RoundKind>>,
+    // Protocol statistics for each peer, collected by the libp2p event
+    // loop.
+    peer_statistics: PeerStatistics,
+    // Orders bitcoin chain tip updates and the digestion of stacks events.
+    chain_event_scheduler: ChainEventScheduler,
+    // Whether the bitcoin anchor of the stacks node's chain tip was an
+    // ancestor of our bitcoin chain tip at the last consistency check.
+    chain_views_consistent: AtomicBool,
+    // The state of the bitcoin node at the last warm-up check. We do not
+    // participate until the node is ready.
+    bitcoin_node_state: RwLock<BitcoinNodeState>,
+    // The number of upcoming coordinator tenures that are to be run in
+    // dry-run mode, along with the outcome of the most recent one.
+    dry_run_tenures: AtomicU32,
+    last_dry_run_package: RwLock<Option<DryRunPackage>>,
+    // Whether we are in read-only maintenance mode, along with the peers
+    // that said in their most recent heartbeat that they are.
+    maintenance_mode: AtomicBool,
+    peers_in_maintenance: RwLock<HashSet<PublicKey>>,
+    // The view of the pending requests from the most recent tenure in
+    // which this signer was the coordinator.
+    pending_queue: RwLock<Option<PendingQueue>>,
+    // The peers that the operator has banned, along with when each ban
+    // expires. The bans are persisted in the database, and this is a copy
+    // of the bans that have not expired for the libp2p event loop.
+    banned_peers: RwLock<HashMap<PeerId, time::OffsetDateTime>>,
+    // The stacks tenures that we have observed in the blocks sent to the
+    // event observer.
+    stacks_tenures: RwLock<StacksTenures>,
+    // The highest stacks block whose webhook we have handled, for
+    // detecting webhooks that the stacks node skipped.
+    stacks_block_sequence: RwLock<StacksBlockSequence>,
+    // Descriptions of the most recent signals that the signer handled,
+    // for crash reports.
+    recent_events: RwLock<VecDeque<String>>,
 }
 
 impl SignerState {
@@ -110,6 +189,45 @@ impl SignerState {
         self.sbtc_contracts_deployed.store(true, Ordering::SeqCst);
     }
 
+    /// Returns false if the most recent chain consistency check found that
+    /// our views of the bitcoin and stacks blockchains disagree.
+    pub fn chain_views_consistent(&self) -> bool {
+        self.chain_views_consistent.load(Ordering::SeqCst)
+    }
+
+    /// Set whether our views of the bitcoin and stacks blockchains are
+    /// consistent with each other.
+    pub fn set_chain_views_consistent(&self, consistent: bool) {
+        self.chain_views_consistent
+            .store(consistent, Ordering::SeqCst);
+    }
+
+    /// Return the state of the bitcoin node at the most recent warm-up
+    /// check.
+    pub fn bitcoin_node_state(&self) -> BitcoinNodeState {
+        self.bitcoin_node_state
+            .read()
+            .expect("BUG: Failed to acquire read lock of bitcoin node state")
+            .clone()
+    }
+
+    /// Set the state of the bitcoin node.
+    pub fn set_bitcoin_node_state(&self, node_state: BitcoinNodeState) {
+        *self
+            .bitcoin_node_state
+            .write()
+            .expect("BUG: Failed to acquire write lock of bitcoin node state") = node_state;
+    }
+
+    /// Returns false if the most recent warm-up check found that the
+    /// bitcoin node is still bootstrapping.
+    pub fn bitcoin_node_ready(&self) -> bool {
+        self.bitcoin_node_state
+            .read()
+            .expect("BUG: Failed to acquire read lock of bitcoin node state")
+            .is_ready()
+    }
+
     /// Get the sbtc start height
     pub fn get_sbtc_bitcoin_start_height(&self) -> BitcoinBlockHeight {
         self.sbtc_bitcoin_start_height.load(Ordering::SeqCst).into()
@@ -127,6 +245,373 @@ impl SignerState {
     pub fn is_sbtc_bitcoin_start_height_set(&self) -> bool {
         self.is_sbtc_bitcoin_start_height_set.load(Ordering::SeqCst)
     }
+
+    /// Return the most recent estimate of how far ahead of our clock the
+    /// given peer's clock is, in milliseconds.
+    pub fn peer_clock_skew_millis(&self, public_key: &PublicKey) -> Option<i64> {
+        self.peer_clock_skews
+            .read()
+            .expect("BUG: Failed to acquire read lock of peer clock skews")
+            .get(public_key)
+            .copied()
+    }
+
+    /// Return the most recent clock skew estimate, in milliseconds, of
+    /// each peer that has sent us a heartbeat.
+    pub fn peer_clock_skews(&self) -> Vec<(PublicKey, i64)> {
+        self.peer_clock_skews
+            .read()
+            .expect("BUG: Failed to acquire read lock of peer clock skews")
+            .iter()
+            .map(|(public_key, skew_millis)| (*public_key, *skew_millis))
+            .collect()
+    }
+
+    /// Record a new clock skew estimate, in milliseconds, for the given
+    /// peer.
+    pub fn set_peer_clock_skew_millis(&self, public_key: PublicKey, skew_millis: i64) {
+        self.peer_clock_skews
+            .write()
+            .expect("BUG: Failed to acquire write lock of peer clock skews")
+            .insert(public_key, skew_millis);
+    }
+
+    /// Return the most recent configuration fingerprint of each peer that
+    /// has sent us one.
+    pub fn peer_config_fingerprints(&self) -> Vec<(PublicKey, [u8; 32])> {
+        self.peer_config_fingerprints
+            .read()
+            .expect("BUG: Failed to acquire read lock of peer config fingerprints")
+            .iter()
+            .map(|(public_key, fingerprint)| (*public_key, *fingerprint))
+            .collect()
+    }
+
+    /// Record the configuration fingerprint that the given peer sent us.
+    pub fn set_peer_config_fingerprint(&self, public_key: PublicKey, fingerprint: [u8; 32]) {
+        self.peer_config_fingerprints
+            .write()
+            .expect("BUG: Failed to acquire write lock of peer config fingerprints")
+            .insert(public_key, fingerprint);
+    }
+
+    /// Get the most recent checkpoint of the state that we have
+    /// processed.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn state_checkpoint(&self) -> Option<StateCheckpoint> {
+        *self
+            .state_checkpoint
+            .read()
+            .expect("BUG: Failed to acquire read lock of state checkpoint")
+    }
+
+    /// Record a checkpoint of the state that we have processed.
+    pub fn set_state_checkpoint(&self, checkpoint: StateCheckpoint) {
+        self.state_checkpoint
+            .write()
+            .expect("BUG: Failed to acquire write lock of state checkpoint")
+            .replace(checkpoint);
+    }
+
+    /// Record that we started a coordinator tenure with the given
+    /// announcement, replacing any earlier tenure.
+    pub fn begin_coordinator_tenure(
+        &self,
+        coordinator: PublicKey,
+        announcement: CoordinatorAnnouncement,
+    ) {
+        self.coordinator_tenure
+            .write()
+            .expect("BUG: Failed to acquire write lock of coordinator tenure")
+            .replace(CoordinatorTenure {
+                coordinator,
+                announcement,
+                superseded_by: None,
+            });
+    }
+
+    /// Record that our coordinator tenure has ended.
+    pub fn end_coordinator_tenure(&self) {
+        self.coordinator_tenure
+            .write()
+            .expect("BUG: Failed to acquire write lock of coordinator tenure")
+            .take();
+    }
+
+    /// Get the coordinator tenure that we are currently running, if any.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn coordinator_tenure(&self) -> Option<CoordinatorTenure> {
+        *self
+            .coordinator_tenure
+            .read()
+            .expect("BUG: Failed to acquire read lock of coordinator tenure")
+    }
+
+    /// Get the kind of round with the other signers that we are currently
+    /// coordinating, if any.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn round_in_flight(&self) -> Option<RoundKind> {
+        *self
+            .round_in_flight
+            .read()
+            .expect("BUG: Failed to acquire read lock of round in flight")
+    }
+
+    /// Record the kind of round that we started coordinating, or `None`
+    /// once it has finished.
+    pub fn set_round_in_flight(&self, kind: Option<RoundKind>) {
+        *self
+            .round_in_flight
+            .write()
+            .expect("BUG: Failed to acquire write lock of round in flight") = kind;
+    }
+
+    /// Settle a conflict between our coordinator tenure and the tenure
+    /// announced by a competing coordinator. Returns `None` if we are not
+    /// running a tenure, and otherwise whether the competing tenure won,
+    /// in which case ours is marked as superseded.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn resolve_coordinator_conflict(
+        &self,
+        coordinator: PublicKey,
+        announcement: &CoordinatorAnnouncement,
+    ) -> Option<bool> {
+        let mut guard = self
+            .coordinator_tenure
+            .write()
+            .expect("BUG: Failed to acquire write lock of coordinator tenure");
+        let tenure = guard.as_mut()?;
+
+        let superseded =
+            announcement.supersedes(&coordinator, &tenure.announcement, &tenure.coordinator);
+        if superseded {
+            tenure.superseded_by = Some(coordinator);
+        }
+        Some(superseded)
+    }
+
+    /// Return the public key of the competing coordinator that superseded
+    /// our tenure at the given bitcoin block, if it was superseded.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn coordinator_tenure_superseded_by(
+        &self,
+        bitcoin_block_hash: &BitcoinBlockHash,
+    ) -> Option<PublicKey> {
+        self.coordinator_tenure
+            .read()
+            .expect("BUG: Failed to acquire read lock of coordinator tenure")
+            .as_ref()
+            .filter(|tenure| &tenure.announcement.bitcoin_block_hash == bitcoin_block_hash)
+            .and_then(|tenure| tenure.superseded_by)
+    }
+
+    /// Get the protocol statistics that the libp2p event loop collects
+    /// for each peer.
+    pub fn peer_statistics(&self) -> &PeerStatistics {
+        &self.peer_statistics
+    }
+
+    /// Get the scheduler that orders bitcoin chain tip updates and the
+    /// digestion of stacks events.
+    pub fn chain_event_scheduler(&self) -> &ChainEventScheduler {
+        &self.chain_event_scheduler
+    }
+
+    /// Return the number of upcoming coordinator tenures that are to be
+    /// run in dry-run mode.
+    pub fn dry_run_tenures(&self) -> u32 {
+        self.dry_run_tenures.load(Ordering::SeqCst)
+    }
+
+    /// Run the next `tenures` coordinator tenures in dry-run mode. Zero
+    /// turns dry-run mode off.
+    pub fn set_dry_run_tenures(&self, tenures: u32) {
+        self.dry_run_tenures.store(tenures, Ordering::SeqCst);
+    }
+
+    /// Returns true, and uses up one of the remaining dry-run tenures, if
+    /// the coordinator tenure that is starting is to be run in dry-run
+    /// mode.
+    pub fn take_dry_run_tenure(&self) -> bool {
+        self.dry_run_tenures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Get the outcome of the most recent dry-run tenure.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn last_dry_run_package(&self) -> Option<DryRunPackage> {
+        self.last_dry_run_package
+            .read()
+            .expect("BUG: Failed to acquire read lock of dry-run package")
+            .clone()
+    }
+
+    /// Record the outcome of a dry-run tenure.
+    pub fn set_last_dry_run_package(&self, package: DryRunPackage) {
+        self.last_dry_run_package
+            .write()
+            .expect("BUG: Failed to acquire write lock of dry-run package")
+            .replace(package);
+    }
+
+    /// Return whether we are in read-only maintenance mode, where we do
+    /// not vote, sign or coordinate.
+    pub fn maintenance_mode(&self) -> bool {
+        self.maintenance_mode.load(Ordering::SeqCst)
+    }
+
+    /// Turn read-only maintenance mode on or off.
+    pub fn set_maintenance_mode(&self, enabled: bool) {
+        self.maintenance_mode.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Return the peers that said in their most recent heartbeat that they
+    /// are in maintenance mode.
+    pub fn peers_in_maintenance(&self) -> Vec<PublicKey> {
+        self.peers_in_maintenance
+            .read()
+            .expect("BUG: Failed to acquire read lock of peers in maintenance")
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Record whether the given peer said that it is in maintenance mode,
+    /// returning true if that is a change from its previous heartbeat.
+    pub fn set_peer_maintenance_mode(&self, public_key: PublicKey, enabled: bool) -> bool {
+        let mut peers = self
+            .peers_in_maintenance
+            .write()
+            .expect("BUG: Failed to acquire write lock of peers in maintenance");
+        if enabled {
+            peers.insert(public_key)
+        } else {
+            peers.remove(&public_key)
+        }
+    }
+
+    /// Get the view of the pending requests from the most recent tenure
+    /// in which this signer was the coordinator.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn pending_queue(&self) -> Option<PendingQueue> {
+        self.pending_queue
+            .read()
+            .expect("BUG: Failed to acquire read lock of pending queue")
+            .clone()
+    }
+
+    /// Record the coordinator's view of the pending requests.
+    pub fn set_pending_queue(&self, queue: PendingQueue) {
+        self.pending_queue
+            .write()
+            .expect("BUG: Failed to acquire write lock of pending queue")
+            .replace(queue);
+    }
+
+    /// Whether the given peer is banned, which means that we refuse its
+    /// connections and drop its messages. Bans stop applying once they
+    /// expire.
+    pub fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
+        let now = time::OffsetDateTime::now_utc();
+        self.banned_peers
+            .read()
+            .expect("BUG: Failed to acquire read lock of banned peers")
+            .get(peer_id)
+            .is_some_and(|expires_at| *expires_at > now)
+    }
+
+    /// Ban the given peer until the given time, replacing any ban that it
+    /// already had.
+    pub fn ban_peer(&self, peer_id: PeerId, expires_at: time::OffsetDateTime) {
+        let mut banned_peers = self
+            .banned_peers
+            .write()
+            .expect("BUG: Failed to acquire write lock of banned peers");
+        // Expired bans are only cleaned up here, since they have no effect
+        // anyway.
+        let now = time::OffsetDateTime::now_utc();
+        banned_peers.retain(|_, expires_at| *expires_at > now);
+        banned_peers.insert(peer_id, expires_at);
+    }
+
+    /// Lift the ban on the given peer, returning whether it was banned.
+    pub fn unban_peer(&self, peer_id: &PeerId) -> bool {
+        self.banned_peers
+            .write()
+            .expect("BUG: Failed to acquire write lock of banned peers")
+            .remove(peer_id)
+            .is_some()
+    }
+
+    /// Record a tenure change observed in a stacks block, returning the
+    /// heights of the bitcoin blocks that it shows to be flash blocks, if
+    /// any. Extensions are taken to be for the current bitcoin chain tip.
+    pub fn observe_tenure_change(
+        &self,
+        change: TenureChange,
+    ) -> Option<RangeInclusive<BitcoinBlockHeight>> {
+        let bitcoin_chain_tip = self.bitcoin_chain_tip().map(|tip| tip.block_height);
+        self.stacks_tenures
+            .write()
+            .expect("BUG: Failed to acquire write lock of stacks tenures")
+            .observe(change, bitcoin_chain_tip)
+    }
+
+    /// Whether a stacks tenure started or was extended in the bitcoin
+    /// block with the given height, or in a later one. This is [`None`]
+    /// when we have not observed any tenure changes.
+    pub fn has_stacks_tenure_at(&self, height: BitcoinBlockHeight) -> Option<bool> {
+        self.stacks_tenures
+            .read()
+            .expect("BUG: Failed to acquire read lock of stacks tenures")
+            .has_tenure_at(height)
+    }
+
+    /// Record that the webhook of the given stacks block arrived,
+    /// returning how the block relates to the last one whose webhook we
+    /// handled.
+    pub fn observe_stacks_block(&self, block: &StacksBlock) -> BlockOrder {
+        self.stacks_block_sequence
+            .write()
+            .expect("BUG: Failed to acquire write lock of stacks block sequence")
+            .observe(block)
+    }
+
+    /// The last stacks block whose webhook we handled, if any.
+    #[allow(clippy::unwrap_in_result)]
+    pub fn last_stacks_block(&self) -> Option<StacksBlock> {
+        self.stacks_block_sequence
+            .read()
+            .expect("BUG: Failed to acquire read lock of stacks block sequence")
+            .last()
+            .cloned()
+    }
+
+    /// Record the description of a signal that the signer handled,
+    /// forgetting the oldest one once there are more than
+    /// [`MAX_RECENT_EVENTS`].
+    pub fn record_recent_event(&self, description: String) {
+        let mut recent_events = self
+            .recent_events
+            .write()
+            .expect("BUG: Failed to acquire write lock of recent events");
+        if recent_events.len() >= MAX_RECENT_EVENTS {
+            recent_events.pop_front();
+        }
+        recent_events.push_back(description);
+    }
+
+    /// The descriptions of the most recent signals that the signer
+    /// handled, oldest first.
+    pub fn recent_events(&self) -> Vec<String> {
+        self.recent_events
+            .read()
+            .expect("BUG: Failed to acquire read lock of recent events")
+            .iter()
+            .cloned()
+            .collect()
+    }
 }
 
 impl Default for SignerState {
@@ -141,6 +626,27 @@ impl Default for SignerState {
             // The block hash here is often used as the parent block hash
             // of the genesis block on bitcoin.
             bitcoin_chain_tip: RwLock::new(None),
+            peer_clock_skews: RwLock::new(HashMap::new()),
+            peer_config_fingerprints: RwLock::new(HashMap::new()),
+            state_checkpoint: RwLock::new(None),
+            coordinator_tenure: RwLock::new(None),
+            round_in_flight: RwLock::new(None),
+            peer_statistics: PeerStatistics::default(),
+            chain_event_scheduler: ChainEventScheduler::new(MAX_CONCURRENT_STACKS_EVENTS),
+            // We only hold back once a check has found a problem.
+            chain_views_consistent: AtomicBool::new(true),
+            // Likewise, we only warm up once a check has found that the
+            // bitcoin node is bootstrapping.
+            bitcoin_node_state: RwLock::new(BitcoinNodeState::Ready),
+            dry_run_tenures: AtomicU32::new(0),
+            last_dry_run_package: RwLock::new(None),
+            maintenance_mode: AtomicBool::new(false),
+            peers_in_maintenance: RwLock::new(HashSet::new()),
+            pending_queue: RwLock::new(None),
+            banned_peers: RwLock::new(HashMap::new()),
+            stacks_tenures: RwLock::new(StacksTenures::default()),
+            stacks_block_sequence: RwLock::new(StacksBlockSequence::default()),
+            recent_events: RwLock::new(VecDeque::with_capacity(MAX_RECENT_EVENTS)),
         }
     }
 }
@@ -287,6 +793,17 @@ impl SbtcLimits {
         self.max_mintable_cap.unwrap_or(Amount::MAX_MONEY)
     }
 
+    /// Return these limits with the given amount set aside from the
+    /// maximum amount of sBTC that can currently be minted.
+    pub fn with_reserved_mintable(&self, reserved: Amount) -> Self {
+        Self {
+            max_mintable_cap: self
+                .max_mintable_cap
+                .map(|cap| cap.checked_sub(reserved).unwrap_or(Amount::ZERO)),
+            ..self.clone()
+        }
+    }
+
     /// Get the rolling withdrawal limits.
     pub fn rolling_withdrawal_limits(&self) -> RollingWithdrawalLimits {
         let withdrawn_total = self.withdrawn_total.unwrap_or(0);
@@ -414,6 +931,9 @@ impl Signer {
 pub struct SignerSet {
     signers: RwLock<HashSet<Signer>>,
     peer_ids: RwLock<HashSet<PeerId>>,
+    /// The identity rotations of signers in the set, mapping the new
+    /// public key of a signer to the public key of its slot.
+    rotations: RwLock<HashMap<PublicKey, PublicKey>>,
 }
 
 /// NOTE: We should never fail to acquire a lock from the RwLock so that it panics.
@@ -469,6 +989,77 @@ impl SignerSet {
             inner_peer_ids.insert(signer.peer_id);
             inner_public_keys.insert(signer);
         }
+
+        // Drop the identity rotations of signers that left the set
+        #[allow(clippy::expect_used)]
+        self.rotations
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .retain(|new, old| {
+                let keep = inner_public_keys.contains(old);
+                if !keep {
+                    inner_peer_ids.remove(&PeerId::from(*new));
+                }
+                keep
+            });
+    }
+
+    /// Map the new public key of a signer that rotated its identity onto
+    /// the slot of its old public key.
+    ///
+    /// The signer may then connect using its new key, and the new key is
+    /// resolved to the old one wherever signers are identified by their
+    /// slot, like in the DKG shares and the registry contract, so the
+    /// signer set does not grow. Returns `false`, leaving the set as it
+    /// is, if the old key is not in the set, has already been rotated, or
+    /// if the new key is already known.
+    pub fn rotate_signer(&self, old: PublicKey, new: PublicKey) -> bool {
+        #[allow(clippy::expect_used)]
+        let is_slot = self
+            .signers
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .contains(&old);
+
+        #[allow(clippy::expect_used)]
+        let is_rotated = self
+            .rotations
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .values()
+            .any(|slot| slot == &old);
+
+        if !is_slot || is_rotated || self.is_signer(&new) {
+            return false;
+        }
+
+        #[allow(clippy::expect_used)]
+        self.peer_ids
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .insert(new.into());
+
+        #[allow(clippy::expect_used)]
+        self.rotations
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .insert(new, old);
+
+        true
+    }
+
+    /// Returns the public key of the slot that the given public key
+    /// occupies in the signer set. This is the old public key for the new
+    /// public key of a signer that rotated its identity, and the given
+    /// public key otherwise.
+    pub fn slot_public_key(&self, public_key: &PublicKey) -> PublicKey {
+        #[allow(clippy::expect_used)]
+        self.rotations
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .get(public_key)
+            .copied()
+            .unwrap_or(*public_key)
     }
 
     /// Remove a signer (public key) from the known active signer set.
@@ -486,6 +1077,23 @@ impl SignerSet {
                 .write()
                 .expect("BUG: Failed to acquire write lock")
                 .remove(signer);
+
+            #[allow(clippy::expect_used)]
+            let mut peer_ids = self
+                .peer_ids
+                .write()
+                .expect("BUG: Failed to acquire write lock");
+            #[allow(clippy::expect_used)]
+            self.rotations
+                .write()
+                .expect("BUG: Failed to acquire write lock")
+                .retain(|new, old| {
+                    let keep = new != signer && old != signer;
+                    if !keep {
+                        peer_ids.remove(&PeerId::from(*new));
+                    }
+                    keep
+                });
         }
     }
 
@@ -501,13 +1109,24 @@ impl SignerSet {
     }
 
     /// Returns whether or not the given public key is a known signer in the
-    /// active set.
+    /// active set, either as the key of a slot or as the new key of a
+    /// signer that rotated its identity.
     pub fn is_signer(&self, signer: &PublicKey) -> bool {
         #[allow(clippy::expect_used)]
-        self.signers
+        let is_slot = self
+            .signers
             .read()
             .expect("BUG: Failed to acquire read lock")
-            .contains(signer)
+            .contains(signer);
+
+        #[allow(clippy::expect_used)]
+        let is_rotated = self
+            .rotations
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .contains_key(signer);
+
+        is_slot || is_rotated
     }
 
     /// Returns whether or not the given peer ID is a known signer in the
@@ -534,12 +1153,23 @@ impl SignerSet {
     #[allow(clippy::unwrap_in_result)]
     pub fn get_pubkey_for_peer(&self, peer_id: &PeerId) -> Option<PublicKey> {
         #[allow(clippy::expect_used)]
-        self.signers
+        let slot = self
+            .signers
             .read()
             .expect("BUG: Failed to acquire read lock")
             .iter()
             .find(|signer| signer.peer_id() == peer_id)
-            .map(|signer| *signer.public_key())
+            .map(|signer| *signer.public_key());
+
+        #[allow(clippy::expect_used)]
+        slot.or_else(|| {
+            self.rotations
+                .read()
+                .expect("BUG: Failed to acquire read lock")
+                .keys()
+                .find(|new| PeerId::from(**new) == *peer_id)
+                .copied()
+        })
     }
 }
 
@@ -549,6 +1179,26 @@ mod tests {
 
     use crate::keys::PrivateKey;
 
+    #[test]
+    fn peer_bans_expire() {
+        use super::*;
+
+        let state = SignerState::default();
+        let banned = PeerId::random();
+        let expired = PeerId::random();
+        let now = time::OffsetDateTime::now_utc();
+
+        state.ban_peer(banned, now + time::Duration::hours(1));
+        state.ban_peer(expired, now - time::Duration::seconds(1));
+        assert!(state.is_peer_banned(&banned));
+        assert!(!state.is_peer_banned(&expired));
+        assert!(!state.is_peer_banned(&PeerId::random()));
+
+        assert!(state.unban_peer(&banned));
+        assert!(!state.is_peer_banned(&banned));
+        assert!(!state.unban_peer(&banned));
+    }
+
     #[test]
     fn test_signer_set() {
         use super::*;
@@ -563,6 +1213,39 @@ mod tests {
         assert!(!signer_set.is_signer(&public_key));
     }
 
+    #[test]
+    fn rotated_signers_keep_their_slot() {
+        use super::*;
+
+        let signer_set = SignerSet::default();
+        let old = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let new = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let other = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+
+        // Only signers in the set can rotate their identity.
+        assert!(!signer_set.rotate_signer(old, new));
+        signer_set.add_signer(old);
+        assert!(signer_set.rotate_signer(old, new));
+
+        // The new key takes the slot of the old one.
+        assert!(signer_set.is_signer(&new));
+        assert!(signer_set.is_allowed_peer(&new.into()));
+        assert_eq!(signer_set.get_pubkey_for_peer(&new.into()), Some(new));
+        assert_eq!(signer_set.slot_public_key(&new), old);
+        assert_eq!(signer_set.slot_public_key(&old), old);
+        assert_eq!(signer_set.num_signers(), 1);
+
+        // A slot can only be rotated once.
+        assert!(!signer_set.rotate_signer(old, other));
+        assert!(!signer_set.is_signer(&other));
+
+        // The rotation goes away with its slot.
+        signer_set.replace_signers(BTreeSet::from([other]));
+        assert!(!signer_set.is_signer(&new));
+        assert!(!signer_set.is_allowed_peer(&new.into()));
+        assert_eq!(signer_set.slot_public_key(&new), new);
+    }
+
     #[test]
     fn test_is_allowed_peer() {
         use super::*;
@@ -576,4 +1259,55 @@ mod tests {
         signer_set.remove_signer(&public_key);
         assert!(!signer_set.is_allowed_peer(&public_key.into()));
     }
+
+    #[test]
+    fn test_coordinator_conflict_resolution() {
+        use super::*;
+
+        let state = SignerState::default();
+        let ours = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let theirs = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+
+        let announcement = |hash: u8, height: u64| CoordinatorAnnouncement {
+            bitcoin_block_hash: BitcoinBlockHash::from([hash; 32]),
+            bitcoin_block_height: height.into(),
+        };
+        let our_tenure = announcement(1, 100);
+
+        // There is nothing to settle while we are not coordinating.
+        assert_eq!(
+            state.resolve_coordinator_conflict(theirs, &our_tenure),
+            None
+        );
+
+        state.begin_coordinator_tenure(ours, our_tenure);
+        let behind = announcement(2, 99);
+        assert_eq!(
+            state.resolve_coordinator_conflict(theirs, &behind),
+            Some(false)
+        );
+        assert_eq!(
+            state.coordinator_tenure_superseded_by(&our_tenure.bitcoin_block_hash),
+            None
+        );
+
+        let ahead = announcement(3, 101);
+        assert_eq!(
+            state.resolve_coordinator_conflict(theirs, &ahead),
+            Some(true)
+        );
+        let tenure = state.coordinator_tenure().unwrap();
+        assert_eq!(tenure.superseded_by, Some(theirs));
+        assert_eq!(
+            state.coordinator_tenure_superseded_by(&our_tenure.bitcoin_block_hash),
+            Some(theirs)
+        );
+        assert_eq!(
+            state.coordinator_tenure_superseded_by(&ahead.bitcoin_block_hash),
+            None
+        );
+
+        state.end_coordinator_tenure();
+        assert_eq!(state.coordinator_tenure(), None);
+    }
 }
@@ -0,0 +1,213 @@
+//! # Crash reports
+//!
+//! The long-lived components of the signer run concurrently in one task,
+//! so without anything to stop it, a panic in any of them unwinds through
+//! all of them, leaving little more than a line in the logs. The runtime
+//! runs each component through [`isolate`], which catches a panic in the
+//! component and turns it into a [`CrashReport`] with what an operator
+//! needs to make sense of it after the fact:
+//!
+//! * the backtrace of the panic, captured by a panic hook,
+//! * descriptions of the signals that the signer handled just before the
+//!   panic, kept by [`record_recent_events`], and
+//! * the bitcoin and stacks chain tips that the signer was working with.
+//!
+//! Crash reports are written to the database and are listed by the
+//! `GET /admin/crash_reports` endpoint of the signer API. A panic in a
+//! component that the signer needs to be operational shuts the signer
+//! down like an error in it would, while the components that are not
+//! needed just stop, and leave the others running.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+use futures::FutureExt as _;
+use futures::StreamExt as _;
+use time::OffsetDateTime;
+
+use crate::context::Context;
+use crate::context::SignerCommand;
+use crate::context::SignerSignal;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbWrite as _;
+use crate::storage::model::CrashReport;
+
+/// The maximum number of characters in the description of a signal that
+/// is kept for crash reports. Signals carry messages from other signers,
+/// which can be large, and the start of the description is enough to
+/// tell what the signal was.
+const MAX_EVENT_DESCRIPTION_CHARS: usize = 256;
+
+thread_local! {
+    // The backtrace of the most recent panic on this thread. The panic
+    // hook runs on the thread that panicked, which is also the thread
+    // that catches the panic in `isolate`.
+    static PANIC_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install a panic hook that captures the backtrace of each panic for its
+/// crash report, and then runs the panic hook that was installed before.
+/// Installing it again has no effect.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = Backtrace::force_capture().to_string();
+            PANIC_BACKTRACE.with(|cell| cell.replace(Some(backtrace)));
+            previous_hook(info);
+        }));
+    });
+}
+
+/// Run the given component of the signer, catching any panic in it.
+///
+/// When the component panics, a crash report for it is written to the
+/// database and [`Error::ComponentPanicked`] is returned. Otherwise the
+/// output of the component is returned.
+pub async fn isolate<C, F>(component: &'static str, ctx: &C, future: F) -> Result<F::Output, Error>
+where
+    C: Context,
+    F: Future,
+{
+    install_panic_hook();
+
+    let payload = match AssertUnwindSafe(future).catch_unwind().await {
+        Ok(output) => return Ok(output),
+        Err(payload) => payload,
+    };
+
+    let report = crash_report(component, ctx, payload.as_ref());
+    tracing::error!(
+        %component,
+        message = %report.message,
+        "component panicked; writing a crash report"
+    );
+    Metrics::increment_component_panics(component);
+
+    let storage = ctx.get_storage_mut();
+    if let Err(error) = storage.write_crash_report(&report).await {
+        tracing::error!(%error, %component, "could not write the crash report");
+    }
+
+    Err(Error::ComponentPanicked(component))
+}
+
+/// Keep descriptions of the signals that the signer handles, for the
+/// crash reports of components that panic. This runs until the signer
+/// shuts down.
+pub async fn record_recent_events(ctx: impl Context) {
+    let mut signals = ctx.as_signal_stream(|_| true);
+
+    while let Some(signal) = signals.next().await {
+        if let SignerSignal::Command(SignerCommand::Shutdown) = signal {
+            break;
+        }
+        ctx.state().record_recent_event(describe_signal(&signal));
+    }
+}
+
+/// A description of the signal with the time that it was handled.
+fn describe_signal(signal: &SignerSignal) -> String {
+    let description: String = format!("{signal:?}")
+        .chars()
+        .take(MAX_EVENT_DESCRIPTION_CHARS)
+        .collect();
+    format!("{} {description}", OffsetDateTime::now_utc())
+}
+
+/// Put together the crash report of a panic in the given component.
+fn crash_report(
+    component: &'static str,
+    ctx: &impl Context,
+    payload: &(dyn Any + Send),
+) -> CrashReport {
+    let state = ctx.state();
+    let bitcoin_chain_tip = state.bitcoin_chain_tip();
+
+    CrashReport {
+        component: component.to_string(),
+        message: panic_message(payload),
+        backtrace: PANIC_BACKTRACE.with(RefCell::take),
+        recent_events: state.recent_events(),
+        bitcoin_chain_tip: bitcoin_chain_tip.map(|tip| tip.block_hash),
+        bitcoin_chain_tip_height: bitcoin_chain_tip.map(|tip| tip.block_height),
+        stacks_chain_tip: state.last_stacks_block().map(|block| block.block_hash),
+        created_at: OffsetDateTime::now_utc().into(),
+    }
+}
+
+/// The message of a panic. Panics raised with `panic!` and friends carry
+/// either a `&str` or a `String`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-string payload".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MAX_RECENT_EVENTS;
+    use crate::context::SignerEvent;
+    use crate::storage::DbRead as _;
+    use crate::storage::model::BitcoinBlockHash;
+    use crate::storage::model::BitcoinBlockRef;
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn panics_in_components_are_reported() {
+        let ctx = TestContext::default_mocked();
+        let chain_tip = BitcoinBlockRef {
+            block_hash: BitcoinBlockHash::from([1; 32]),
+            block_height: 100u64.into(),
+        };
+        ctx.state().set_bitcoin_chain_tip(chain_tip);
+        let signal = SignerSignal::Event(SignerEvent::BitcoinBlockObserved(chain_tip));
+        ctx.state().record_recent_event(describe_signal(&signal));
+
+        let output = isolate("healthy", &ctx, async { 42 }).await.unwrap();
+        assert_eq!(output, 42);
+
+        let result = isolate("faulty", &ctx, async {
+            panic!("something went wrong");
+        })
+        .await;
+        assert!(matches!(result, Err(Error::ComponentPanicked("faulty"))));
+
+        let reports = ctx.get_storage().get_crash_reports(10).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.component, "faulty");
+        assert_eq!(report.message, "something went wrong");
+        assert!(report.backtrace.is_some());
+        assert_eq!(report.recent_events.len(), 1);
+        assert!(report.recent_events[0].contains("BitcoinBlockObserved"));
+        assert_eq!(report.bitcoin_chain_tip, Some(chain_tip.block_hash));
+        assert_eq!(
+            report.bitcoin_chain_tip_height,
+            Some(chain_tip.block_height)
+        );
+        assert_eq!(report.stacks_chain_tip, None);
+    }
+
+    #[tokio::test]
+    async fn only_the_most_recent_events_are_kept() {
+        let ctx = TestContext::default_mocked();
+        for index in 0..MAX_RECENT_EVENTS + 2 {
+            ctx.state().record_recent_event(index.to_string());
+        }
+
+        let recent_events = ctx.state().recent_events();
+        assert_eq!(recent_events.len(), MAX_RECENT_EVENTS);
+        assert_eq!(recent_events[0], "2");
+    }
+}
@@ -0,0 +1,97 @@
+//! # Coordinator dry runs
+//!
+//! An operator can ask the signer to run its next few tenures as
+//! coordinator in dry-run mode through the `/dry-run` endpoint of the
+//! API. A dry-run tenure selects the pending requests, constructs the
+//! sweep transactions and has the other signers validate them with a
+//! bitcoin pre-sign request, just like a normal tenure, but it stops
+//! before requesting any signatures. DKG, key rotations and stacks
+//! transactions are skipped entirely. The package that would have been
+//! signed is kept in the signer state as a [`DryRunPackage`] and served
+//! by the API, which makes dry runs useful for rehearsing after a config
+//! change or during incident recovery.
+
+use serde::Serialize;
+
+use crate::bitcoin::utxo::RequestRef;
+use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::UnsignedTransaction;
+use crate::error::Error;
+use crate::storage::model::BitcoinBlockRef;
+
+/// The outcome of the most recent dry-run tenure.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DryRunPackage {
+    /// The hash of the bitcoin chain tip of the tenure.
+    pub bitcoin_chain_tip: String,
+    /// The height of the bitcoin chain tip of the tenure.
+    pub bitcoin_block_height: u64,
+    /// When the dry run finished.
+    pub created_at: String,
+    /// The fee rate, in sats per vbyte, that the transactions were
+    /// constructed with. This is `None` when there were no requests to
+    /// handle.
+    pub fee_rate: Option<f64>,
+    /// The sweep transactions that would have been signed.
+    pub transactions: Vec<DryRunTransaction>,
+    /// Why the package would not have been signed, if it would not have
+    /// been. This covers failing the fee sanity limits and the other
+    /// signers not acknowledging the pre-sign request in time.
+    pub error: Option<String>,
+}
+
+/// A sweep transaction that a dry-run tenure would have signed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DryRunTransaction {
+    /// The ID of the unsigned transaction.
+    pub txid: String,
+    /// The deposit requests swept by the transaction, as `txid:vout`
+    /// outpoints.
+    pub deposits: Vec<String>,
+    /// The IDs of the withdrawal requests fulfilled by the transaction.
+    pub withdrawals: Vec<u64>,
+    /// The fee paid by the transaction, in sats.
+    pub fee: u64,
+    /// The virtual size of the transaction.
+    pub vsize: u32,
+}
+
+impl From<&UnsignedTransaction<'_>> for DryRunTransaction {
+    fn from(transaction: &UnsignedTransaction<'_>) -> Self {
+        let mut deposits = Vec::new();
+        let mut withdrawals = Vec::new();
+        for request in transaction.requests.iter() {
+            match request {
+                RequestRef::Deposit(deposit) => deposits.push(deposit.outpoint.to_string()),
+                RequestRef::Withdrawal(withdrawal) => withdrawals.push(withdrawal.request_id),
+            }
+        }
+
+        Self {
+            txid: transaction.tx.compute_txid().to_string(),
+            deposits,
+            withdrawals,
+            fee: transaction.tx_fee,
+            vsize: transaction.tx_vsize,
+        }
+    }
+}
+
+impl DryRunPackage {
+    /// Create a package for a dry-run tenure at the given chain tip.
+    pub fn new(
+        bitcoin_chain_tip: &BitcoinBlockRef,
+        signer_state: Option<&SignerBtcState>,
+        transactions: &[UnsignedTransaction<'_>],
+        error: Option<&Error>,
+    ) -> Self {
+        Self {
+            bitcoin_chain_tip: bitcoin_chain_tip.block_hash.to_string(),
+            bitcoin_block_height: *bitcoin_chain_tip.block_height,
+            created_at: time::OffsetDateTime::now_utc().to_string(),
+            fee_rate: signer_state.map(|state| state.fee_rate),
+            transactions: transactions.iter().map(Into::into).collect(),
+            error: error.map(ToString::to_string),
+        }
+    }
+}
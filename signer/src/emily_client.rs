@@ -1,5 +1,6 @@
 //! Emily API client module
 
+use std::collections::HashMap;
 use std::str::FromStr as _;
 use std::time::Duration;
 use std::time::Instant;
@@ -28,9 +29,11 @@ use url::Url;
 
 use crate::bitcoin::utxo::RequestRef;
 use crate::bitcoin::utxo::UnsignedTransaction;
-use crate::config::EmilyClientConfig;
+use crate::config::OutboundConfig;
+use crate::config::Settings;
 use crate::context::SbtcLimits;
 use crate::error::Error;
+use crate::metrics::InFlightRequest;
 use crate::storage::model::BitcoinTxId;
 use crate::util::ApiFallbackClient;
 
@@ -45,6 +48,10 @@ pub enum EmilyClientError {
     #[error("invalid URL: host is required: {0}")]
     InvalidUrlHostRequired(String),
 
+    /// The HTTP client could not be built with the outbound settings
+    #[error("could not build the HTTP client: {0}")]
+    BuildHttpClient(#[source] emily_reqwest::Error),
+
     /// An error occurred while getting a deposit request
     #[error("error getting a deposit: {0}")]
     GetDeposit(EmilyError<deposit_api::GetDepositError>),
@@ -66,6 +73,20 @@ pub enum EmilyClientError {
     GetLimits(EmilyError<limits_api::GetLimitsError>),
 }
 
+/// A deposit request fetched from Emily, along with the stacks block
+/// height at which Emily last updated it.
+#[derive(Debug, Clone)]
+pub struct EmilyDepositRequest {
+    /// The deposit request.
+    pub request: CreateDepositRequest,
+    /// The most recent stacks block height that Emily was aware of when
+    /// the deposit request was last updated.
+    pub last_update_height: u64,
+    /// The identifier that the creator of the deposit request gave Emily
+    /// for tracing it across systems, if any.
+    pub correlation_id: Option<String>,
+}
+
 /// Trait describing the interactions with Emily API.
 #[cfg_attr(any(test, feature = "testing"), mockall::automock())]
 pub trait EmilyInteract: Sync + Send {
@@ -87,12 +108,21 @@ pub trait EmilyInteract: Sync + Send {
         status: DepositStatus,
     ) -> impl std::future::Future<Output = Result<Vec<CreateDepositRequest>, Error>> + Send;
 
+    /// Get the pending and accepted deposits that Emily last updated at or
+    /// after the given stacks block height.
+    fn get_deposits_updated_since(
+        &self,
+        last_update_height: u64,
+    ) -> impl std::future::Future<Output = Result<Vec<EmilyDepositRequest>, Error>> + Send;
+
     /// Update accepted deposits after their sweep bitcoin transaction has been
     /// confirmed (but before being finalized -- the stacks transaction minting
-    /// sBTC has not been confirmed yet).
+    /// sBTC has not been confirmed yet). The given correlation IDs of the
+    /// deposits are passed along in the updates.
     fn accept_deposits<'a>(
         &'a self,
         transaction: &'a UnsignedTransaction<'a>,
+        correlation_ids: &'a HashMap<OutPoint, String>,
     ) -> impl std::future::Future<Output = Result<UpdateDepositsResponse, Error>> + Send;
 
     /// Update accepted withdrawals after their sweep bitcoin transaction has
@@ -123,6 +153,8 @@ pub trait EmilyInteract: Sync + Send {
 #[derive(Clone)]
 pub struct EmilyClient {
     config: EmilyApiConfig,
+    /// The endpoint of the Emily API, without the API key.
+    endpoint: Url,
     pagination_timeout: Duration,
     /// Maximum items returned per page. When set, responses will be limited to this many items.
     /// Regardless of the page_size setting, responses are always capped at 1 MB total size.
@@ -141,6 +173,22 @@ impl EmilyClient {
         url: &Url,
         pagination_timeout: Duration,
         page_size: Option<u16>,
+    ) -> Result<Self, Error> {
+        Self::try_new_with_outbound(
+            url,
+            pagination_timeout,
+            page_size,
+            &OutboundConfig::default(),
+        )
+    }
+
+    /// Initialize a new Emily client that connects to the given url using
+    /// the given outbound connection settings, and validate the url.
+    pub fn try_new_with_outbound(
+        url: &Url,
+        pagination_timeout: Duration,
+        page_size: Option<u16>,
+        outbound: &OutboundConfig,
     ) -> Result<Self, Error> {
         let mut url = url.clone();
         let api_key = if url.username().is_empty() {
@@ -172,9 +220,14 @@ impl EmilyClient {
         // causing the api calls to have two leading slashes in the path (getting a 404)
         config.base_path = url.to_string().trim_end_matches("/").to_string();
         config.api_key = api_key;
+        config.client = outbound
+            .emily_http_client_builder(&url)
+            .and_then(|builder| builder.build())
+            .map_err(EmilyClientError::BuildHttpClient)?;
 
         Ok(Self {
             config,
+            endpoint: url,
             pagination_timeout,
             // Page size must be u16 despite autogenerated client using u32.
             // This limitation exists because Emily needs to pass the parameter
@@ -183,6 +236,86 @@ impl EmilyClient {
         })
     }
 
+    /// Get the deposits with the given status that Emily last updated at or
+    /// after the given stacks block height.
+    ///
+    /// Emily returns the deposits with a status in descending order of
+    /// the height at which they were last updated, so we stop paging at
+    /// the first deposit that was last updated before the given height.
+    /// This way a poll only fetches the pages with deposits that changed
+    /// since the previous poll.
+    async fn get_deposits_with_status_updated_since(
+        &self,
+        status: DepositStatus,
+        last_update_height: u64,
+    ) -> Result<Vec<EmilyDepositRequest>, Error> {
+        let mut all_deposits = Vec::new();
+        let mut next_token: Option<String> = None;
+        let start_time = Instant::now();
+        loop {
+            let _in_flight = InFlightRequest::new(&self.endpoint);
+            let resp = match deposit_api::get_deposits(
+                &self.config,
+                status,
+                next_token.as_deref(),
+                self.page_size,
+            )
+            .await
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    if all_deposits.is_empty() {
+                        return Err(Error::EmilyApi(EmilyClientError::GetDeposits(e)));
+                    }
+                    tracing::warn!("failed to fetch page of deposits: {:?}", e);
+                    break;
+                }
+            };
+
+            let mut reached_older = false;
+            for deposit in resp.deposits.iter() {
+                if deposit.last_update_height < last_update_height {
+                    reached_older = true;
+                    continue;
+                }
+                match Self::parse_deposit(deposit) {
+                    Ok(request) => all_deposits.push(EmilyDepositRequest {
+                        request,
+                        last_update_height: deposit.last_update_height,
+                        correlation_id: deposit.correlation_id.clone().flatten(),
+                    }),
+                    Err(e) => tracing::warn!(
+                        "Skipping corrupted deposit (txid: {}): {:?}",
+                        deposit.bitcoin_txid,
+                        e
+                    ),
+                }
+            }
+
+            // The remaining pages only have deposits that were last
+            // updated before the given height.
+            if reached_older {
+                break;
+            }
+
+            match resp.next_token.flatten() {
+                Some(token) => next_token = Some(token),
+                None => break,
+            }
+
+            if start_time.elapsed() > self.pagination_timeout {
+                tracing::warn!(
+                    "timeout fetching deposits, breaking at page {:?}, fetched {} deposits",
+                    next_token,
+                    all_deposits.len()
+                );
+                break;
+            }
+        }
+
+        Ok(all_deposits)
+    }
+
     fn parse_deposit(deposit: &DepositInfo) -> Result<CreateDepositRequest, Error> {
         Ok(CreateDepositRequest {
             outpoint: OutPoint {
@@ -206,6 +339,7 @@ impl EmilyInteract for EmilyClient {
         let txid_str = txid.to_string();
         let index = output_index.to_string();
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let resp = deposit_api::get_deposit(&self.config, &txid_str, &index).await;
 
         let deposit = match resp {
@@ -265,6 +399,7 @@ impl EmilyInteract for EmilyClient {
         let mut next_token: Option<String> = None;
         let start_time = Instant::now();
         loop {
+            let _in_flight = InFlightRequest::new(&self.endpoint);
             let resp = match deposit_api::get_deposits(
                 &self.config,
                 status,
@@ -313,6 +448,35 @@ impl EmilyInteract for EmilyClient {
         Ok(all_deposits)
     }
 
+    async fn get_deposits_updated_since(
+        &self,
+        last_update_height: u64,
+    ) -> Result<Vec<EmilyDepositRequest>, Error> {
+        let pending = self
+            .get_deposits_with_status_updated_since(DepositStatus::Pending, last_update_height)
+            .await;
+        let accepted = self
+            .get_deposits_with_status_updated_since(DepositStatus::Accepted, last_update_height)
+            .await;
+
+        // Like in `get_deposits`, we only fail if both calls fail.
+        match (pending, accepted) {
+            (Err(pending_err), Err(_accepted_err)) => Err(pending_err),
+            (Ok(pending), Err(accepted_err)) => {
+                tracing::warn!("failed to fetch accepted deposits: {:?}", accepted_err);
+                Ok(pending)
+            }
+            (Err(pending_err), Ok(accepted)) => {
+                tracing::warn!("failed to fetch pending deposits: {:?}", pending_err);
+                Ok(accepted)
+            }
+            (Ok(mut pending), Ok(mut accepted)) => {
+                pending.append(&mut accepted);
+                Ok(pending)
+            }
+        }
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
@@ -322,6 +486,7 @@ impl EmilyInteract for EmilyClient {
         }
 
         let update_request = UpdateDepositsRequestBody { deposits: update_deposits };
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         deposit_api::update_deposits_signer(&self.config, update_request)
             .await
             .map_err(EmilyClientError::UpdateDeposits)
@@ -352,6 +517,7 @@ impl EmilyInteract for EmilyClient {
     async fn accept_deposits<'a>(
         &'a self,
         transaction: &'a UnsignedTransaction<'a>,
+        correlation_ids: &'a HashMap<OutPoint, String>,
     ) -> Result<UpdateDepositsResponse, Error> {
         let deposits = transaction
             .requests
@@ -366,6 +532,7 @@ impl EmilyInteract for EmilyClient {
                 fulfillment: None,
                 status_message: "".to_string(),
                 replaced_by_tx: None,
+                correlation_id: correlation_ids.get(&deposit.outpoint).cloned().map(Some),
             })
             .collect();
 
@@ -383,6 +550,7 @@ impl EmilyInteract for EmilyClient {
         let update_request = UpdateWithdrawalsRequestBody {
             withdrawals: update_withdrawals,
         };
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         withdrawal_api::update_withdrawals_signer(&self.config, update_request)
             .await
             .map_err(EmilyClientError::UpdateWithdrawals)
@@ -390,6 +558,7 @@ impl EmilyInteract for EmilyClient {
     }
 
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let limits = limits_api::get_limits(&self.config)
             .await
             .map_err(EmilyClientError::GetLimits)
@@ -440,6 +609,14 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
             .await
     }
 
+    async fn get_deposits_updated_since(
+        &self,
+        last_update_height: u64,
+    ) -> Result<Vec<EmilyDepositRequest>, Error> {
+        self.exec(|client, _| client.get_deposits_updated_since(last_update_height))
+            .await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
@@ -451,8 +628,9 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
     async fn accept_deposits<'a>(
         &'a self,
         transaction: &'a UnsignedTransaction<'a>,
+        correlation_ids: &'a HashMap<OutPoint, String>,
     ) -> Result<UpdateDepositsResponse, Error> {
-        self.exec(|client, _| client.accept_deposits(transaction))
+        self.exec(|client, _| client.accept_deposits(transaction, correlation_ids))
             .await
     }
 
@@ -477,14 +655,22 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
     }
 }
 
-impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
+impl TryFrom<&Settings> for ApiFallbackClient<EmilyClient> {
     type Error = Error;
 
-    fn try_from(config: &EmilyClientConfig) -> Result<Self, Self::Error> {
+    fn try_from(settings: &Settings) -> Result<Self, Self::Error> {
+        let config = &settings.emily;
         let clients = config
             .endpoints
             .iter()
-            .map(|url| EmilyClient::try_new(url, config.pagination_timeout, None))
+            .map(|url| {
+                EmilyClient::try_new_with_outbound(
+                    url,
+                    config.pagination_timeout,
+                    None,
+                    &settings.outbound,
+                )
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         Self::new(clients).map_err(Into::into)
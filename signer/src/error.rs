@@ -4,6 +4,7 @@ use std::borrow::Cow;
 use bitcoin::script::PushBytesError;
 use blockstack_lib::types::chainstate::StacksBlockId;
 
+use crate::bitcoin::validation::BitcoinSweepErrorMsg;
 use crate::bitcoin::validation::WithdrawalCapContext;
 use crate::blocklist_client::BlocklistClientError;
 use crate::codec;
@@ -16,13 +17,15 @@ use crate::stacks::contracts::RotateKeysValidationError;
 use crate::stacks::contracts::WithdrawalAcceptValidationError;
 use crate::stacks::contracts::WithdrawalRejectValidationError;
 use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::SigHash;
 use crate::storage::model::StacksTxId;
 use crate::transaction_signer::StacksSignRequestId;
 use crate::wsts_state_machine::StateMachineId;
 
 /// Top-level signer error
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum Error {
     /// The length of bytes to write to an OP_RETURN output exceeds the maximum allowed size.
     #[error("OP_RETURN output size limit exceeded: {size} bytes, max allowed: {max_size} bytes")]
@@ -94,6 +97,101 @@ pub enum Error {
     #[error("the signer set aggregate key could not be determined for bitcoin block {0}")]
     MissingAggregateKey(bitcoin::BlockHash),
 
+    /// Submitting the stacks transaction would exceed the configured
+    /// stacks fee budget.
+    #[error(
+        "stacks fee budget exhausted: a {tx_kind} transaction with fee {fee} would exceed \
+        the budget of {budget} microSTX, of which {spent} has been spent"
+    )]
+    StacksFeeBudgetExhausted {
+        /// The kind of transaction that was refused.
+        tx_kind: &'static str,
+        /// The fee of the refused transaction, in microSTX.
+        fee: u64,
+        /// The fees already paid over the budget period, in microSTX.
+        spent: u64,
+        /// The budget for the period, in microSTX.
+        budget: u64,
+    },
+
+    /// Could not write a captured fixture to disk.
+    #[error("could not write a captured fixture to {1:?}: {0}")]
+    FixtureCapture(#[source] std::io::Error, std::path::PathBuf),
+
+    /// Could not write a file of a data export.
+    #[error("could not write export file {1:?}: {0}")]
+    Export(#[source] std::io::Error, std::path::PathBuf),
+
+    /// The fee of a reclaim transaction would leave nothing of the
+    /// deposited amount.
+    #[error("a reclaim fee of {fee} leaves nothing of the deposited {amount}")]
+    ReclaimFeeTooHigh {
+        /// The deposited amount.
+        amount: bitcoin::Amount,
+        /// The fee of the reclaim transaction.
+        fee: bitcoin::Amount,
+    },
+
+    /// The reclaim script of a deposit is not a leaf of the taproot tree
+    /// constructed from its scripts.
+    #[error("the reclaim script is missing from the taproot tree of deposit {0}")]
+    MissingReclaimLeaf(bitcoin::OutPoint),
+
+    /// No destination was given for a reclaim transaction, and we do not
+    /// know any address that funded the deposit.
+    #[error("no destination for reclaiming deposit {0}")]
+    MissingReclaimDestination(bitcoin::OutPoint),
+
+    /// Could not compress a message for the bulk sync topic.
+    #[error("could not compress a bulk sync message: {0}")]
+    SyncMessageCompression(#[source] std::io::Error),
+
+    /// Could not decompress a message received on the bulk sync topic.
+    #[error("could not decompress a bulk sync message: {0}")]
+    SyncMessageDecompression(#[source] std::io::Error),
+
+    /// Could not compress the raw body of a `/new_block` webhook.
+    #[error("could not compress the raw body of a new block webhook: {0}")]
+    RawStacksEventCompression(#[source] std::io::Error),
+
+    /// Could not decompress the stored raw body of a `/new_block` webhook.
+    #[error("could not decompress the raw body of a new block webhook: {0}")]
+    RawStacksEventDecompression(#[source] std::io::Error),
+
+    /// Could not bind the address of the signer API.
+    #[error("could not bind the signer API to {1}: {0}")]
+    ApiBind(#[source] std::io::Error, std::net::SocketAddr),
+
+    /// Could not read a TLS certificate or private key of the signer API.
+    #[error("could not read the signer API TLS file {1:?}: {0}")]
+    ApiTlsFile(#[source] std::io::Error, std::path::PathBuf),
+
+    /// The TLS certificates or private key of the signer API were
+    /// rejected.
+    #[error("invalid TLS configuration for the signer API: {0}")]
+    ApiTls(#[source] tokio_rustls::rustls::Error),
+
+    /// Could not communicate over the handover socket.
+    #[error("handover socket error: {0}")]
+    HandoverSocket(#[source] std::io::Error),
+
+    /// A message received over the handover socket was malformed.
+    #[error("invalid handover message: {0}")]
+    HandoverMessage(#[source] serde_json::Error),
+
+    /// The running signer refused to hand over to this one.
+    #[error("the running signer refused to hand over: {0}")]
+    HandoverRefused(String),
+
+    /// The running signer did not hand over in time.
+    #[error("timed out waiting for the running signer to hand over")]
+    HandoverTimeout,
+
+    /// A bitcoin block summary received from a peer does not match the
+    /// block returned by bitcoin-core.
+    #[error("bitcoin block summary from a peer does not match bitcoin-core for block {0}")]
+    BlockSummaryMismatch(bitcoin::BlockHash),
+
     /// Indicates an error when decoding a protobuf
     #[error("could not decode protobuf {0}")]
     DecodeProtobuf(#[source] prost::DecodeError),
@@ -143,6 +241,20 @@ pub enum Error {
     #[error("blocklist client error: {0}")]
     BlocklistClient(#[from] BlocklistClientError),
 
+    /// Could not build the HTTP client used to deliver webhook
+    /// notifications.
+    #[error("could not build the webhook client: {0}")]
+    WebhookClient(#[source] reqwest::Error),
+
+    /// Could not build the HTTP client that uploads analytics exports.
+    #[error("could not build the analytics export client: {0}")]
+    AnalyticsExportClient(#[source] reqwest::Error),
+
+    /// The object storage service did not accept an uploaded file of an
+    /// analytics export.
+    #[error("could not upload analytics export object {1}: {0}")]
+    AnalyticsExportUpload(#[source] reqwest::Error, String),
+
     /// Attempt to fetch a bitcoin blockhash ended in an unexpected error.
     /// This is not triggered if the block is missing.
     #[error("bitcoin-core getblock RPC error for hash {1}: {0}")]
@@ -166,6 +278,16 @@ pub enum Error {
     #[error("could not create RPC client to {1}: {0}")]
     BitcoinCoreRpcClient(#[source] bitcoincore_rpc::Error, String),
 
+    /// Error when creating the transport of an RPC client to bitcoin-core
+    /// that connects through a proxy
+    #[error("could not create RPC transport to {1}: {0}")]
+    BitcoinCoreRpcTransport(#[source] jsonrpc::simple_http::Error, String),
+
+    /// Error when the proxy for the bitcoin-core RPC client is not a
+    /// SOCKS5 proxy
+    #[error("the bitcoin-core RPC client only supports SOCKS5 proxies, got {0}")]
+    UnsupportedBitcoinRpcProxy(url::Url),
+
     /// The bitcoin transaction was not found in the mempool or on the
     /// bitcoin blockchain. This is thrown when we expect the transaction
     /// to exist in bitcoin core, but it does not.
@@ -261,13 +383,31 @@ pub enum Error {
     #[error("the signer is shutting down")]
     SignerShutdown,
 
+    /// The task running an embedded signer panicked or was cancelled.
+    #[error("the signer task did not run to completion: {0}")]
+    SignerTask(#[source] tokio::task::JoinError),
+
     /// I/O Error raised by the Tokio runtime.
     #[error("tokio i/o error: {0}")]
     TokioIo(#[from] tokio::io::Error),
 
-    /// Invalid amount
-    #[error("the change amounts for the transaction is negative: {0}")]
-    InvalidAmount(i64),
+    /// Adding two amounts overflowed.
+    #[error("adding amounts {lhs} and {rhs} overflowed")]
+    AmountOverflow {
+        /// The amount on the left hand side of the addition
+        lhs: u64,
+        /// The amount on the right hand side of the addition
+        rhs: u64,
+    },
+
+    /// Subtracting two amounts would result in a negative amount.
+    #[error("subtracting amount {rhs} from {lhs} would result in a negative amount")]
+    AmountUnderflow {
+        /// The amount being subtracted from
+        lhs: u64,
+        /// The amount being subtracted
+        rhs: u64,
+    },
 
     /// Old fee estimate
     #[error("got an old fee estimate")]
@@ -469,6 +609,27 @@ pub enum Error {
     #[error("failed to read migration script: {0}")]
     ReadSqlMigration(Cow<'static, str>),
 
+    /// Returned when asked to upgrade a legacy database that already
+    /// records its applied migrations.
+    #[error("the database already tracks its migrations, use --migrate-db instead")]
+    LegacyMigrationNotNeeded,
+
+    /// Returned when the schema of a legacy database does not match any
+    /// known schema generation.
+    #[error("unrecognized legacy database layout: {missing} is missing but {present} is present")]
+    UnrecognizedLegacyLayout {
+        /// The first migration whose changes are missing from the
+        /// database.
+        missing: &'static str,
+        /// A later migration whose changes are present in the database.
+        present: &'static str,
+    },
+
+    /// Returned when the schema of an upgraded legacy database does not
+    /// reflect the changes of the given migration.
+    #[error("legacy database upgrade failed verification for migration {0}")]
+    LegacyMigrationVerification(&'static str),
+
     /// An error when we exceeded the timeout when trying to sign a stacks
     /// transaction.
     #[error("took too long to receive enough signatures for transaction: {0}")]
@@ -479,6 +640,28 @@ pub enum Error {
     #[error("got an error wen attempting to call StacksMessageCodec::consensus_deserialize {0}")]
     StacksCodec(#[source] blockstack_lib::codec::Error),
 
+    /// The clarity values passed to a contract call do not match the
+    /// number of parameters of the contract function.
+    #[error("expected {expected} arguments to {function}, got {actual}")]
+    ContractCallArgCount {
+        /// The name of the contract function.
+        function: &'static str,
+        /// The number of parameters of the contract function.
+        expected: usize,
+        /// The number of clarity values passed to the contract call.
+        actual: usize,
+    },
+
+    /// A clarity value passed to a contract call does not have the type
+    /// of the corresponding contract function parameter.
+    #[error("invalid value for the {parameter} argument to {function}")]
+    InvalidContractCallArg {
+        /// The name of the contract function.
+        function: &'static str,
+        /// The name of the parameter.
+        parameter: &'static str,
+    },
+
     /// An error for the case where we cannot create a multi-sig
     /// StacksAddress using given public keys.
     #[error("could not create a StacksAddress from the public keys: threshold {0}, keys {1}")]
@@ -508,10 +691,31 @@ pub enum Error {
     #[error("response from stacks node did not conform to the expected schema: {0}")]
     UnexpectedStacksResponse(#[source] reqwest::Error),
 
+    /// The body of a response from the stacks node could not be
+    /// deserialized. Only used when the response body is read before it
+    /// is deserialized, which happens when fixture capture is enabled.
+    #[error("response body from stacks node did not conform to the expected schema: {0}")]
+    UnexpectedStacksResponseBody(#[source] serde_json::Error),
+
     /// The response from the Stacks node was invalid or malformed.
     #[error("invalid stacks response: {0}")]
     InvalidStacksResponse(&'static str),
 
+    /// Our coordinator tenure lost to the tenure of a competing
+    /// coordinator, so we stopped coordinating.
+    #[error("our coordinator tenure was superseded by the competing coordinator {0}")]
+    CoordinatorTenureSuperseded(PublicKey),
+
+    /// A value returned by a read-only function of the sbtc-registry
+    /// contract does not have the expected shape.
+    #[error("invalid value for the {field} field returned by {function}")]
+    InvalidRegistryValue {
+        /// The name of the read-only function.
+        function: &'static str,
+        /// The name of the field in the returned value.
+        field: &'static str,
+    },
+
     /// The stacks request was already signed in this tenure
     #[error("stacks request for {0} was already signed in tenure {1}")]
     StacksRequestAlreadySigned(StacksSignRequestId, bitcoin::BlockHash),
@@ -723,6 +927,20 @@ pub enum Error {
     #[error("unexpected operation result: {0:?}")]
     UnexpectedOperationResult(Box<wsts::state_machine::OperationResult>),
 
+    /// A WSTS round of the self-test ran out of messages before the
+    /// coordinator produced a result.
+    #[error("the self-test {0} round ended without a result")]
+    SelfTestRoundIncomplete(&'static str),
+
+    /// The two serializations of a structure in the serialization audit
+    /// differ.
+    #[error("the serialization of {0} is not deterministic")]
+    NonDeterministicSerialization(&'static str),
+
+    /// A long-lived component of the signer panicked.
+    #[error("the {0} component panicked")]
+    ComponentPanicked(&'static str),
+
     /// The smart contract has already been deployed
     #[error("smart contract already deployed, contract name: {0}")]
     ContractAlreadyDeployed(&'static str),
@@ -750,6 +968,47 @@ pub enum Error {
     #[error("the fee rate in the BitcoinPreSignRequest object is not greater than zero: {0}")]
     PreSignInvalidFeeRate(f64),
 
+    /// Indicates that the BitcoinPreSignRequest object contains a lock
+    /// time that is not a block height within the allowed window at or
+    /// below the bitcoin chain tip, or that is not zero while the
+    /// `sweep_lock_time` setting is off.
+    #[error(
+        "the lock time in the BitcoinPreSignRequest object is outside the allowed window; lock time: {lock_time}, chain tip height: {chain_tip_height}"
+    )]
+    PreSignInvalidLockTime {
+        /// The lock time in the request.
+        lock_time: bitcoin::absolute::LockTime,
+        /// The height of the bitcoin chain tip of this signer.
+        chain_tip_height: BitcoinBlockHeight,
+    },
+
+    /// Indicates that the BitcoinPreSignRequest object asks the signers
+    /// to spend an outpoint that is not an unspent donation UTXO locked by
+    /// the signers' aggregate key.
+    #[error("the BitcoinPreSignRequest object includes an invalid donation UTXO; outpoint: {0}")]
+    PreSignInvalidDonation(bitcoin::OutPoint),
+
+    /// Indicates that the BitcoinPreSignRequest object includes more
+    /// donation UTXOs than can be spent in a sweep transaction.
+    #[error("the BitcoinPreSignRequest object includes too many donation UTXOs; count: {0}")]
+    PreSignTooManyDonations(usize),
+
+    /// Indicates that the BitcoinPreSignRequest object asks for a chain of
+    /// transactions that is longer than the mempool accepts.
+    #[error("the BitcoinPreSignRequest object includes too many transactions; count: {0}")]
+    PreSignTooManyTransactions(usize),
+
+    /// Indicates that a transaction of the BitcoinPreSignRequest object
+    /// would be larger than a standard transaction.
+    #[error("a transaction of the BitcoinPreSignRequest object is too large; vsize: {0}")]
+    PreSignTransactionTooLarge(u64),
+
+    /// Indicates that the transactions of the BitcoinPreSignRequest object
+    /// would be larger, combined, than the mempool accepts for a chain of
+    /// unconfirmed transactions.
+    #[error("the BitcoinPreSignRequest object's transaction package is too large; vsize: {0}")]
+    PreSignPackageTooLarge(u64),
+
     /// Error when deposit requests would exceed sBTC supply cap
     #[error(
         "total deposit amount ({total_amount} sats) would exceed sBTC supply cap (current max mintable is {max_mintable} sats)"
@@ -761,6 +1020,19 @@ pub enum Error {
         max_mintable: u64,
     },
 
+    /// The fee of a sweep transaction exceeds the sanity limits.
+    #[error(
+        "sweep transaction fee of {fee} sats is absurd for the {swept_amount} sats that it sweeps (max fee is {max_fee} sats)"
+    )]
+    AbsurdSweepFee {
+        /// The fee of the transaction in sats
+        fee: u64,
+        /// The amount swept by the transaction in sats
+        swept_amount: u64,
+        /// The maximum fee allowed for the transaction in sats
+        max_fee: u64,
+    },
+
     /// sBTC transaction is malformed
     #[error("sbtc transaction is malformed")]
     SbtcTxMalformed,
@@ -804,4 +1076,23 @@ impl Error {
     pub fn wsts_coordinator(err: wsts::state_machine::coordinator::Error) -> Self {
         Error::WstsCoordinator(Box::new(err))
     }
+
+    /// A label for the cause of this error, suitable for use in metrics.
+    ///
+    /// Validation errors are labeled with the specific reason that
+    /// validation failed, while all other errors are labeled with the
+    /// name of their variant.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Error::BitcoinValidation(err) => match err.error {
+                BitcoinSweepErrorMsg::Deposit(result) => result.into(),
+                BitcoinSweepErrorMsg::Withdrawal(result) => result.into(),
+            },
+            Error::DepositValidation(err) => (&err.error).into(),
+            Error::RotateKeysValidation(err) => (&err.error).into(),
+            Error::WithdrawalAcceptValidation(err) => (&err.error).into(),
+            Error::WithdrawalRejectValidation(err) => (&err.error).into(),
+            err => err.into(),
+        }
+    }
 }
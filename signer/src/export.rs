@@ -0,0 +1,442 @@
+//! # Data export
+//!
+//! The `signer export` command writes everything that the signer
+//! recorded over a period of time, for operators with periodic reporting
+//! obligations. A record belongs to the period if it was written to the
+//! signer's database at or after the start and before the end of the
+//! period, so consecutive periods never overlap. An export consists of
+//! the following tables, with one row per record:
+//!
+//! * `deposits`: the deposit requests that the signer learned about.
+//! * `withdrawals`: the withdrawal requests that the signer learned
+//!   about, once for each stacks fork that they were observed in.
+//! * `deposit_votes` and `withdrawal_votes`: how each signer voted on
+//!   the requests above, as far as this signer knows.
+//! * `sweeps`: the confirmed bitcoin transactions that spent the signers'
+//!   UTXO, with the fees that they paid, once for each bitcoin fork that
+//!   confirmed them.
+//! * `stacks_fees`: the fees paid for stacks transactions that this
+//!   signer submitted as the coordinator.
+//!
+//! Amounts are in sats, except for stacks fees which are in microSTX,
+//! and times are in UTC. In JSON format the tables are written as arrays
+//! in a single `export.json` file, and in CSV format each table is
+//! written to its own file, named after the table.
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::storage::DbRead;
+use crate::storage::model;
+use crate::storage::model::Timestamp;
+
+/// The file formats that an export can be written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// A single JSON document.
+    Json,
+    /// One CSV file for each table.
+    #[default]
+    Csv,
+}
+
+/// A file of an export, held in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportFile {
+    /// The name of the file, such as `deposits.csv`.
+    pub name: String,
+    /// The contents of the file.
+    pub contents: Vec<u8>,
+}
+
+/// A row of the `deposits` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DepositRecord {
+    /// The ID of the bitcoin transaction with the deposit.
+    pub txid: String,
+    /// The index of the deposit output in the transaction.
+    pub output_index: u32,
+    /// The stacks address or contract that receives the sBTC.
+    pub recipient: String,
+    /// The amount deposited.
+    pub amount: u64,
+    /// The maximum fee that the depositor agreed to pay.
+    pub max_fee: u64,
+    /// The relative lock time of the reclaim script.
+    pub lock_time: u32,
+    /// The x-only aggregate key that the deposit was locked to.
+    pub signers_public_key: String,
+    /// The hex encoded scriptPubKeys of the inputs of the deposit
+    /// transaction, separated by spaces.
+    pub sender_script_pub_keys: String,
+    /// When the signer learned about the deposit.
+    pub recorded_at: String,
+}
+
+impl From<model::Recorded<model::DepositRequest>> for DepositRecord {
+    fn from(recorded: model::Recorded<model::DepositRequest>) -> Self {
+        let deposit = recorded.record;
+        let sender_script_pub_keys: Vec<String> = deposit
+            .sender_script_pub_keys
+            .iter()
+            .map(|script| script.to_hex_string())
+            .collect();
+
+        Self {
+            txid: deposit.txid.to_string(),
+            output_index: deposit.output_index,
+            recipient: deposit.recipient.to_string(),
+            amount: deposit.amount,
+            max_fee: deposit.max_fee,
+            lock_time: deposit.lock_time,
+            signers_public_key: deposit.signers_public_key.to_string(),
+            sender_script_pub_keys: sender_script_pub_keys.join(" "),
+            recorded_at: recorded.recorded_at.to_string(),
+        }
+    }
+}
+
+/// A row of the `withdrawals` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WithdrawalRecord {
+    /// The ID assigned to the request by the registry contract.
+    pub request_id: u64,
+    /// The ID of the stacks transaction that made the request.
+    pub txid: String,
+    /// The stacks block that confirmed the request.
+    pub block_hash: String,
+    /// The bitcoin block height at which the request was made.
+    pub bitcoin_block_height: u64,
+    /// The hex encoded scriptPubKey that receives the bitcoin.
+    pub recipient: String,
+    /// The amount withdrawn.
+    pub amount: u64,
+    /// The maximum fee that the requester agreed to pay.
+    pub max_fee: u64,
+    /// The stacks address that made the request.
+    pub sender_address: String,
+    /// When the signer learned about the withdrawal.
+    pub recorded_at: String,
+}
+
+impl From<model::Recorded<model::WithdrawalRequest>> for WithdrawalRecord {
+    fn from(recorded: model::Recorded<model::WithdrawalRequest>) -> Self {
+        let withdrawal = recorded.record;
+        Self {
+            request_id: withdrawal.request_id,
+            txid: withdrawal.txid.to_string(),
+            block_hash: withdrawal.block_hash.to_string(),
+            bitcoin_block_height: *withdrawal.bitcoin_block_height,
+            recipient: withdrawal.recipient.to_hex_string(),
+            amount: withdrawal.amount,
+            max_fee: withdrawal.max_fee,
+            sender_address: withdrawal.sender_address.to_string(),
+            recorded_at: recorded.recorded_at.to_string(),
+        }
+    }
+}
+
+/// A row of the `deposit_votes` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DepositVoteRecord {
+    /// The ID of the bitcoin transaction with the deposit.
+    pub txid: String,
+    /// The index of the deposit output in the transaction.
+    pub output_index: u32,
+    /// The public key of the signer that voted.
+    pub signer_public_key: String,
+    /// Whether the signer accepted the deposit.
+    pub can_accept: bool,
+    /// Whether the signer holds a share of the key that the deposit was
+    /// locked to.
+    pub can_sign: bool,
+}
+
+impl From<model::DepositSigner> for DepositVoteRecord {
+    fn from(vote: model::DepositSigner) -> Self {
+        Self {
+            txid: vote.txid.to_string(),
+            output_index: vote.output_index,
+            signer_public_key: vote.signer_pub_key.to_string(),
+            can_accept: vote.can_accept,
+            can_sign: vote.can_sign,
+        }
+    }
+}
+
+/// A row of the `withdrawal_votes` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WithdrawalVoteRecord {
+    /// The ID assigned to the request by the registry contract.
+    pub request_id: u64,
+    /// The stacks block that confirmed the request.
+    pub block_hash: String,
+    /// The public key of the signer that voted.
+    pub signer_public_key: String,
+    /// Whether the signer accepted the withdrawal.
+    pub is_accepted: bool,
+}
+
+impl From<model::WithdrawalSigner> for WithdrawalVoteRecord {
+    fn from(vote: model::WithdrawalSigner) -> Self {
+        Self {
+            request_id: vote.request_id,
+            block_hash: vote.block_hash.to_string(),
+            signer_public_key: vote.signer_pub_key.to_string(),
+            is_accepted: vote.is_accepted,
+        }
+    }
+}
+
+/// A row of the `sweeps` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SweepRecord {
+    /// The ID of the bitcoin transaction.
+    pub txid: String,
+    /// The bitcoin block that confirmed the transaction.
+    pub block_hash: String,
+    /// The height of the bitcoin block that confirmed the transaction.
+    pub block_height: u64,
+    /// The total amount of the signers' UTXO and the deposits spent.
+    pub amount_in: u64,
+    /// The total amount of the outputs.
+    pub amount_out: u64,
+    /// The fee paid by the transaction.
+    pub fee: u64,
+    /// When the signer learned about the transaction.
+    pub recorded_at: String,
+}
+
+impl From<model::SweepTransactionRecord> for SweepRecord {
+    fn from(sweep: model::SweepTransactionRecord) -> Self {
+        Self {
+            txid: sweep.txid.to_string(),
+            block_hash: sweep.block_hash.to_string(),
+            block_height: *sweep.block_height,
+            amount_in: sweep.amount_in,
+            amount_out: sweep.amount_out,
+            fee: sweep.fee(),
+            recorded_at: sweep.recorded_at.to_string(),
+        }
+    }
+}
+
+/// A row of the `stacks_fees` table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StacksFeeRecord {
+    /// The ID of the stacks transaction.
+    pub txid: String,
+    /// The kind of transaction, such as `complete-deposit`.
+    pub tx_kind: String,
+    /// The fee paid, in microSTX.
+    pub fee: u64,
+    /// When the transaction was submitted.
+    pub submitted_at: String,
+}
+
+impl From<model::StacksFeeSpend> for StacksFeeRecord {
+    fn from(spend: model::StacksFeeSpend) -> Self {
+        Self {
+            txid: spend.txid.to_string(),
+            tx_kind: spend.tx_kind,
+            fee: spend.fee,
+            submitted_at: spend.submitted_at.to_string(),
+        }
+    }
+}
+
+/// A complete export of the data recorded over a period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Export {
+    /// The start of the period, inclusive.
+    pub from: String,
+    /// The end of the period, exclusive.
+    pub to: String,
+    /// The deposit requests.
+    pub deposits: Vec<DepositRecord>,
+    /// The withdrawal requests.
+    pub withdrawals: Vec<WithdrawalRecord>,
+    /// The votes on the deposit requests.
+    pub deposit_votes: Vec<DepositVoteRecord>,
+    /// The votes on the withdrawal requests.
+    pub withdrawal_votes: Vec<WithdrawalVoteRecord>,
+    /// The sweep transactions.
+    pub sweeps: Vec<SweepRecord>,
+    /// The fees paid for stacks transactions.
+    pub stacks_fees: Vec<StacksFeeRecord>,
+}
+
+impl Export {
+    /// Collect the data recorded at or after `from` and before `to`.
+    pub async fn collect<S>(storage: &S, from: Timestamp, to: Timestamp) -> Result<Self, Error>
+    where
+        S: DbRead,
+    {
+        let deposits = storage
+            .get_deposit_requests_recorded_between(from, to)
+            .await?;
+        let withdrawals = storage
+            .get_withdrawal_requests_recorded_between(from, to)
+            .await?;
+
+        let mut deposit_votes = Vec::new();
+        for deposit in deposits.iter().map(|recorded| &recorded.record) {
+            let votes = storage
+                .get_deposit_signers(&deposit.txid, deposit.output_index)
+                .await?;
+            deposit_votes.extend(votes.into_iter().map(DepositVoteRecord::from));
+        }
+
+        let mut withdrawal_votes = Vec::new();
+        for withdrawal in withdrawals.iter().map(|recorded| &recorded.record) {
+            let votes = storage
+                .get_withdrawal_signers(withdrawal.request_id, &withdrawal.block_hash)
+                .await?;
+            withdrawal_votes.extend(votes.into_iter().map(WithdrawalVoteRecord::from));
+        }
+
+        let sweeps = storage
+            .get_sweep_transactions_recorded_between(from, to)
+            .await?;
+        let stacks_fees = storage
+            .get_stacks_fee_spends_since(from)
+            .await?
+            .into_iter()
+            .filter(|spend| spend.submitted_at < to)
+            .map(StacksFeeRecord::from)
+            .collect();
+
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            deposits: deposits.into_iter().map(DepositRecord::from).collect(),
+            withdrawals: withdrawals
+                .into_iter()
+                .map(WithdrawalRecord::from)
+                .collect(),
+            deposit_votes,
+            withdrawal_votes,
+            sweeps: sweeps.into_iter().map(SweepRecord::from).collect(),
+            stacks_fees,
+        })
+    }
+
+    /// Render the files of the export in the given format.
+    pub fn files(&self, format: ExportFormat) -> Result<Vec<ExportFile>, Error> {
+        match format {
+            ExportFormat::Json => {
+                let json = serde_json::to_vec_pretty(self).map_err(Error::JsonSerialize)?;
+                Ok(vec![ExportFile {
+                    name: "export.json".to_string(),
+                    contents: json,
+                }])
+            }
+            ExportFormat::Csv => Ok(vec![
+                csv_file("deposits", &self.deposits)?,
+                csv_file("withdrawals", &self.withdrawals)?,
+                csv_file("deposit_votes", &self.deposit_votes)?,
+                csv_file("withdrawal_votes", &self.withdrawal_votes)?,
+                csv_file("sweeps", &self.sweeps)?,
+                csv_file("stacks_fees", &self.stacks_fees)?,
+            ]),
+        }
+    }
+
+    /// Write the export to the given directory in the given format,
+    /// creating the directory if necessary.
+    pub fn write(&self, dir: &Path, format: ExportFormat) -> Result<(), Error> {
+        std::fs::create_dir_all(dir).map_err(|err| Error::Export(err, dir.to_path_buf()))?;
+
+        for file in self.files(format)? {
+            let path = dir.join(&file.name);
+            std::fs::write(&path, &file.contents).map_err(|err| Error::Export(err, path))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a date in `YYYY-MM-DD` form into the timestamp of midnight UTC
+/// at the start of that day.
+pub fn parse_date(date: &str) -> Result<Timestamp, String> {
+    let invalid = || format!("invalid date {date:?}, expected YYYY-MM-DD");
+
+    let mut parts = date.splitn(3, '-');
+    let mut next = || parts.next().ok_or_else(invalid);
+    let year: i32 = next()?.parse().map_err(|_| invalid())?;
+    let month: u8 = next()?.parse().map_err(|_| invalid())?;
+    let day: u8 = next()?.parse().map_err(|_| invalid())?;
+
+    let month = time::Month::try_from(month).map_err(|_| invalid())?;
+    let date = time::Date::from_calendar_date(year, month, day).map_err(|_| invalid())?;
+    Ok(Timestamp::from(date.midnight().assume_utc()))
+}
+
+/// Render the given records as the file `<table>.csv`, with a header row
+/// holding the field names. The columns are in alphabetical order, since
+/// the records go through a [`serde_json::Value`] on the way.
+fn csv_file<T: Serialize>(table: &str, records: &[T]) -> Result<ExportFile, Error> {
+    let mut contents = String::new();
+    for (index, record) in records.iter().enumerate() {
+        let serde_json::Value::Object(fields) =
+            serde_json::to_value(record).map_err(Error::JsonSerialize)?
+        else {
+            continue;
+        };
+        if index == 0 {
+            let header: Vec<String> = fields.keys().map(|key| csv_field(key)).collect();
+            contents.push_str(&header.join(","));
+            contents.push('\n');
+        }
+        let row: Vec<String> = fields
+            .values()
+            .map(|value| match value {
+                serde_json::Value::String(value) => csv_field(value),
+                value => value.to_string(),
+            })
+            .collect();
+        contents.push_str(&row.join(","));
+        contents.push('\n');
+    }
+
+    Ok(ExportFile {
+        name: format!("{table}.csv"),
+        contents: contents.into_bytes(),
+    })
+}
+
+/// Quote a CSV field if it contains characters with special meaning.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_are_parsed_as_midnight_utc() {
+        let timestamp = parse_date("2024-02-29").unwrap();
+        assert_eq!(timestamp.unix_timestamp(), 1_709_164_800);
+
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("2024-01").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,537 @@
+//! # Zero-downtime upgrades
+//!
+//! Restarting a signer to upgrade it fails the signing rounds that it is
+//! coordinating, drops the stacks node's events until the API is back,
+//! and loses the state that the signer only keeps in memory. When
+//! [`handover_socket`](crate::config::SignerConfig::handover_socket) is
+//! set, a new signer process takes over from a running one instead:
+//!
+//! 1. The new process binds the API address. Both processes bind it with
+//!    `SO_REUSEPORT`, so the kernel keeps accepting the stacks node's
+//!    events throughout the upgrade.
+//! 2. The new process connects to the handover socket of the old one and
+//!    asks it to hand over, identifying itself with its public key. The
+//!    old process refuses processes with another key, since they would
+//!    not have its network identity.
+//! 3. The old process stops all of its components, so that the two
+//!    processes never coordinate or sign at the same time, and sends a
+//!    [`HandoverState`] with its in-memory state.
+//! 4. The new process imports the state, binds the handover socket
+//!    itself and starts. If the old process was coordinating a tenure,
+//!    the new process runs the tenure again once it has connected to a
+//!    peer, which resumes its signing rounds from the messages persisted
+//!    in the database.
+//!
+//! The libp2p listeners are bound once the old process has stopped, and
+//! peers reconnect on their own. The state of the signing rounds that
+//! other coordinators run is not migrated, so a round that this signer
+//! takes part in during the handover needs the other signers to reach
+//! the threshold.
+//!
+//! Messages are exchanged as newline-delimited JSON. The handover socket
+//! is a unix domain socket, so on other platforms setting a handover
+//! socket only makes the signer fail to start.
+
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt as _;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt as _;
+use std::path::Path;
+#[cfg(unix)]
+use std::time::Duration;
+
+use futures::StreamExt as _;
+use serde::Deserialize;
+use serde::Serialize;
+#[cfg(unix)]
+use tokio::io::AsyncBufReadExt as _;
+#[cfg(unix)]
+use tokio::io::AsyncWriteExt as _;
+#[cfg(unix)]
+use tokio::io::BufReader;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+use crate::context::Context;
+use crate::context::P2PEvent;
+use crate::context::RequestDeciderEvent;
+use crate::context::SignerCommand;
+use crate::context::SignerEvent;
+use crate::context::SignerSignal;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinBlockRef;
+
+/// The version of the handover protocol. A process only hands over to
+/// processes that speak the same version.
+pub const HANDOVER_PROTOCOL_VERSION: u32 = 1;
+
+/// How long the new process waits for the old one to stop its components
+/// and send its state. The old process stops its coordinator between
+/// signing rounds, so this covers the longest round.
+#[cfg(unix)]
+const HANDOVER_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// How long the old process waits for the request of a process that
+/// connected to the handover socket.
+#[cfg(unix)]
+const HANDOVER_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The messages exchanged over the handover socket.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(not(unix), allow(dead_code))]
+enum HandoverMessage {
+    /// Sent by the new process to ask the old one to hand over.
+    Request {
+        /// The [`HANDOVER_PROTOCOL_VERSION`] of the new process.
+        version: u32,
+        /// The public key of the new process.
+        public_key: PublicKey,
+    },
+    /// Sent by the old process once it has stopped.
+    State(Box<HandoverState>),
+    /// Sent by the old process when it does not hand over.
+    Refused(String),
+}
+
+/// The in-memory state of a signer that is handed over to the process
+/// that takes over from it. Everything else is either persisted in the
+/// database or refreshed as new blocks arrive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoverState {
+    /// The bitcoin chain tip that the old process had processed.
+    pub bitcoin_chain_tip: Option<BitcoinBlockRef>,
+    /// The chain tip of the coordinator tenure that the old process was
+    /// running when it was asked to hand over, if any.
+    pub interrupted_tenure: Option<BitcoinBlockRef>,
+    /// The public keys of the current signer set.
+    pub signer_set: Vec<PublicKey>,
+    /// Whether the sBTC contracts have been deployed.
+    pub sbtc_contracts_deployed: bool,
+    /// The bitcoin block height at which sBTC starts, if it has been set.
+    pub sbtc_bitcoin_start_height: Option<BitcoinBlockHeight>,
+    /// The most recent clock skew estimate of each peer, in milliseconds.
+    pub peer_clock_skews: Vec<(PublicKey, i64)>,
+    /// The most recent configuration fingerprint of each peer.
+    pub peer_config_fingerprints: Vec<(PublicKey, [u8; 32])>,
+    /// The number of upcoming coordinator tenures to run in dry-run mode.
+    pub dry_run_tenures: u32,
+    /// Whether the old process was in maintenance mode.
+    #[serde(default)]
+    pub maintenance_mode: bool,
+}
+
+impl HandoverState {
+    /// Capture the in-memory state of the signer.
+    pub fn capture(ctx: &impl Context, interrupted_tenure: Option<BitcoinBlockRef>) -> Self {
+        let state = ctx.state();
+        let signer_set = state
+            .current_signer_set()
+            .get_signers()
+            .iter()
+            .map(|signer| *signer.public_key())
+            .collect();
+
+        Self {
+            bitcoin_chain_tip: state.bitcoin_chain_tip(),
+            interrupted_tenure,
+            signer_set,
+            sbtc_contracts_deployed: state.sbtc_contracts_deployed(),
+            sbtc_bitcoin_start_height: state
+                .is_sbtc_bitcoin_start_height_set()
+                .then(|| state.get_sbtc_bitcoin_start_height()),
+            peer_clock_skews: state.peer_clock_skews(),
+            peer_config_fingerprints: state.peer_config_fingerprints(),
+            dry_run_tenures: state.dry_run_tenures(),
+            maintenance_mode: state.maintenance_mode(),
+        }
+    }
+
+    /// Import the state into the signer. This must happen before any of
+    /// the signer's components start.
+    pub fn restore(&self, ctx: &impl Context) {
+        let state = ctx.state();
+        if let Some(chain_tip) = self.bitcoin_chain_tip {
+            state.set_bitcoin_chain_tip(chain_tip);
+        }
+        for public_key in &self.signer_set {
+            state.current_signer_set().add_signer(*public_key);
+        }
+        if self.sbtc_contracts_deployed {
+            state.set_sbtc_contracts_deployed();
+        }
+        if let Some(height) = self.sbtc_bitcoin_start_height {
+            state.set_sbtc_bitcoin_start_height(height);
+        }
+        for (public_key, skew_millis) in &self.peer_clock_skews {
+            state.set_peer_clock_skew_millis(*public_key, *skew_millis);
+        }
+        for (public_key, fingerprint) in &self.peer_config_fingerprints {
+            state.set_peer_config_fingerprint(*public_key, *fingerprint);
+        }
+        state.set_dry_run_tenures(self.dry_run_tenures);
+        // Maintenance mode that was turned on through the API stays on,
+        // but the new process may also have been configured to start in
+        // it.
+        if self.maintenance_mode {
+            state.set_maintenance_mode(true);
+        }
+    }
+}
+
+/// Listens on the handover socket for a new process that wants to take
+/// over from this one.
+#[derive(Debug)]
+pub struct HandoverListener {
+    #[cfg(unix)]
+    listener: UnixListener,
+    #[cfg(not(unix))]
+    listener: std::convert::Infallible,
+}
+
+impl HandoverListener {
+    /// Bind the handover socket at the given path. A socket file left
+    /// behind by a process that handed over, or that crashed, is replaced.
+    ///
+    /// Any process that can connect to the socket can stop the signer, so
+    /// only the user that the signer runs as may connect to it. Sockets
+    /// are created with the permissions that the umask allows, so the
+    /// socket is bound in a directory that only this user can enter, and
+    /// only moved to the given path once its permissions are restricted.
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> Result<Self, Error> {
+        let mut private_dir = path.as_os_str().to_owned();
+        private_dir.push(".bind");
+        let private_dir = std::path::PathBuf::from(private_dir);
+        // A directory left behind by a process that crashed while binding.
+        match std::fs::remove_dir_all(&private_dir) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(Error::HandoverSocket(error)),
+        }
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&private_dir)
+            .map_err(Error::HandoverSocket)?;
+
+        let bind = || -> std::io::Result<UnixListener> {
+            let private_path = private_dir.join("handover.sock");
+            let listener = UnixListener::bind(&private_path)?;
+            let permissions = std::fs::Permissions::from_mode(0o600);
+            std::fs::set_permissions(&private_path, permissions)?;
+            // Renaming replaces a stale socket file at the path, and the
+            // listener keeps accepting connections on the moved socket.
+            std::fs::rename(&private_path, path)?;
+            Ok(listener)
+        };
+        let listener = bind();
+        let removed = std::fs::remove_dir_all(&private_dir);
+        let listener = listener.map_err(Error::HandoverSocket)?;
+        removed.map_err(Error::HandoverSocket)?;
+        Ok(Self { listener })
+    }
+
+    /// Handover sockets are unix domain sockets, so they cannot be bound
+    /// on other platforms.
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path) -> Result<Self, Error> {
+        Err(Error::HandoverSocket(
+            std::io::ErrorKind::Unsupported.into(),
+        ))
+    }
+
+    /// Wait for a new process to ask for a handover. Once one does, the
+    /// shutdown signal is sent to the signer and the pending handover is
+    /// returned, so that the state is sent once all of the components have
+    /// stopped. Returns `None` if the signer shuts down for another reason.
+    #[cfg(unix)]
+    #[tracing::instrument(skip_all, name = "handover")]
+    pub async fn wait_for_request(self, ctx: &impl Context) -> Option<PendingHandover> {
+        let mut term = ctx.get_termination_handle();
+        loop {
+            let accepted = tokio::select! {
+                _ = term.wait_for_shutdown() => return None,
+                accepted = self.listener.accept() => accepted,
+            };
+            let stream = match accepted {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    tracing::warn!(%error, "could not accept a handover connection");
+                    continue;
+                }
+            };
+
+            match accept_request(ctx, stream).await {
+                Ok(Some(pending)) => {
+                    tracing::info!("handing over to a new signer process; shutting down");
+                    term.signal_shutdown();
+                    return Some(pending);
+                }
+                Ok(None) => {}
+                Err(error) => tracing::warn!(%error, "could not read a handover request"),
+            }
+        }
+    }
+
+    /// Wait for a new process to ask for a handover. A listener cannot be
+    /// bound on this platform, so there is never one to wait on.
+    #[cfg(not(unix))]
+    pub async fn wait_for_request(self, _ctx: &impl Context) -> Option<PendingHandover> {
+        match self.listener {}
+    }
+}
+
+/// A handover that has been requested and accepted, waiting for the
+/// signer's components to stop.
+#[derive(Debug)]
+pub struct PendingHandover {
+    #[cfg(unix)]
+    stream: BufReader<UnixStream>,
+    #[cfg(not(unix))]
+    stream: std::convert::Infallible,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    interrupted_tenure: Option<BitcoinBlockRef>,
+}
+
+impl PendingHandover {
+    /// Send the state of the signer to the new process. This must only be
+    /// called once all of the signer's components have stopped.
+    #[cfg(unix)]
+    pub async fn complete(mut self, ctx: &impl Context) -> Result<(), Error> {
+        let state = HandoverState::capture(ctx, self.interrupted_tenure);
+        let message = HandoverMessage::State(Box::new(state));
+        write_message(&mut self.stream, &message).await?;
+        tracing::info!("handed over to the new signer process");
+        Ok(())
+    }
+
+    /// Send the state of the signer to the new process. Handovers are
+    /// never accepted on this platform, so there is nothing to send.
+    #[cfg(not(unix))]
+    pub async fn complete(self, _ctx: &impl Context) -> Result<(), Error> {
+        match self.stream {}
+    }
+}
+
+/// Read a handover request from the stream and decide whether to accept
+/// it, telling the new process when we do not.
+#[cfg(unix)]
+async fn accept_request(
+    ctx: &impl Context,
+    stream: UnixStream,
+) -> Result<Option<PendingHandover>, Error> {
+    let mut stream = BufReader::new(stream);
+    let request = tokio::time::timeout(HANDOVER_REQUEST_TIMEOUT, read_message(&mut stream));
+    let Ok(message) = request.await else {
+        tracing::warn!("no handover request received in time");
+        return Ok(None);
+    };
+    let (version, public_key) = match message? {
+        HandoverMessage::Request { version, public_key } => (version, public_key),
+        _ => return Ok(None),
+    };
+
+    let reason = if version != HANDOVER_PROTOCOL_VERSION {
+        format!("unsupported protocol version {version}, expected {HANDOVER_PROTOCOL_VERSION}")
+    } else if public_key != ctx.config().signer.public_key() {
+        format!("public key {public_key} does not match ours")
+    } else {
+        // The coordinator clears its tenure when it stops, so we take
+        // note of it before we send the shutdown signal.
+        let state = ctx.state();
+        let interrupted_tenure = state
+            .coordinator_tenure()
+            .and_then(|_| state.bitcoin_chain_tip());
+        return Ok(Some(PendingHandover { stream, interrupted_tenure }));
+    };
+
+    tracing::warn!(%reason, "refusing to hand over to a new signer process");
+    write_message(&mut stream, &HandoverMessage::Refused(reason)).await?;
+    Ok(None)
+}
+
+/// Take over from the signer process listening on the handover socket at
+/// the given path, if there is one, and import its state into the
+/// context. Returns `None` when there is no process to take over from.
+#[cfg(unix)]
+#[tracing::instrument(skip_all, name = "handover")]
+pub async fn take_over(ctx: &impl Context, path: &Path) -> Result<Option<HandoverState>, Error> {
+    let stream = match UnixStream::connect(path).await {
+        Ok(stream) => stream,
+        Err(error)
+            if matches!(
+                error.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            tracing::debug!("no running signer to take over from");
+            return Ok(None);
+        }
+        Err(error) => return Err(Error::HandoverSocket(error)),
+    };
+
+    tracing::info!("asking the running signer to hand over");
+    let mut stream = BufReader::new(stream);
+    let request = HandoverMessage::Request {
+        version: HANDOVER_PROTOCOL_VERSION,
+        public_key: ctx.config().signer.public_key(),
+    };
+    write_message(&mut stream, &request).await?;
+
+    let reply = tokio::time::timeout(HANDOVER_TIMEOUT, read_message(&mut stream))
+        .await
+        .map_err(|_| Error::HandoverTimeout)??;
+
+    match reply {
+        HandoverMessage::State(state) => {
+            state.restore(ctx);
+            tracing::info!(
+                bitcoin_chain_tip = ?state.bitcoin_chain_tip,
+                interrupted_tenure = ?state.interrupted_tenure,
+                "took over from the running signer"
+            );
+            Ok(Some(*state))
+        }
+        HandoverMessage::Refused(reason) => Err(Error::HandoverRefused(reason)),
+        HandoverMessage::Request { .. } => Err(Error::HandoverRefused(
+            "the running signer sent a request instead of its state".to_string(),
+        )),
+    }
+}
+
+/// Take over from the signer process listening on the handover socket at
+/// the given path. Handover sockets are unix domain sockets, so this
+/// always fails on other platforms.
+#[cfg(not(unix))]
+pub async fn take_over(_ctx: &impl Context, _path: &Path) -> Result<Option<HandoverState>, Error> {
+    Err(Error::HandoverSocket(
+        std::io::ErrorKind::Unsupported.into(),
+    ))
+}
+
+/// Run the coordinator tenure that was interrupted by the handover again,
+/// once we have connected to a peer. The tenure is not run if a new
+/// bitcoin block arrives first, since the coordinator runs a new tenure
+/// for it anyway.
+pub async fn resume_interrupted_tenure(ctx: impl Context, chain_tip: Option<BitcoinBlockRef>) {
+    let Some(chain_tip) = chain_tip else {
+        return;
+    };
+    let mut signals = ctx.as_signal_stream(|signal| {
+        matches!(
+            signal,
+            SignerSignal::Event(SignerEvent::P2P(P2PEvent::PeerConnected(_)))
+                | SignerSignal::Command(SignerCommand::Shutdown)
+        )
+    });
+
+    while let Some(signal) = signals.next().await {
+        match signal {
+            SignerSignal::Command(SignerCommand::Shutdown) => return,
+            SignerSignal::Event(SignerEvent::P2P(P2PEvent::PeerConnected(_))) => break,
+            _ => {}
+        }
+    }
+
+    if ctx.state().bitcoin_chain_tip() != Some(chain_tip) {
+        return;
+    }
+    tracing::info!(%chain_tip.block_hash, "resuming the tenure interrupted by the handover");
+    let signal = RequestDeciderEvent::NewRequestsHandled(chain_tip).into();
+    if let Err(error) = ctx.signal(signal) {
+        tracing::warn!(%error, "could not resume the interrupted coordinator tenure");
+    }
+}
+
+#[cfg(unix)]
+async fn write_message(
+    stream: &mut BufReader<UnixStream>,
+    message: &HandoverMessage,
+) -> Result<(), Error> {
+    let mut bytes = serde_json::to_vec(message).map_err(Error::JsonSerialize)?;
+    bytes.push(b'\n');
+    let stream = stream.get_mut();
+    stream
+        .write_all(&bytes)
+        .await
+        .map_err(Error::HandoverSocket)?;
+    stream.flush().await.map_err(Error::HandoverSocket)
+}
+
+#[cfg(unix)]
+async fn read_message(stream: &mut BufReader<UnixStream>) -> Result<HandoverMessage, Error> {
+    let mut line = String::new();
+    let read = stream
+        .read_line(&mut line)
+        .await
+        .map_err(Error::HandoverSocket)?;
+    if read == 0 {
+        let error = std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+        return Err(Error::HandoverSocket(error));
+    }
+    serde_json::from_str(&line).map_err(Error::HandoverMessage)
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// The state captured from one signer is restored as is into another.
+    #[test]
+    fn handover_state_round_trips() {
+        let mut rng = get_rng();
+        let old = TestContext::default_mocked();
+        let new = TestContext::default_mocked();
+
+        let chain_tip: BitcoinBlockRef = Faker.fake_with_rng(&mut rng);
+        let peer: PublicKey = Faker.fake_with_rng(&mut rng);
+        old.state().set_bitcoin_chain_tip(chain_tip);
+        old.state().current_signer_set().add_signer(peer);
+        old.state().set_sbtc_contracts_deployed();
+        old.state().set_peer_clock_skew_millis(peer, -250);
+        old.state().set_peer_config_fingerprint(peer, [7; 32]);
+        old.state().set_dry_run_tenures(2);
+        old.state().set_maintenance_mode(true);
+
+        let state = HandoverState::capture(&old, Some(chain_tip));
+        let json = serde_json::to_string(&HandoverMessage::State(Box::new(state.clone()))).unwrap();
+        let HandoverMessage::State(received) = serde_json::from_str(&json).unwrap() else {
+            panic!("expected the state");
+        };
+        assert_eq!(*received, state);
+        received.restore(&new);
+
+        assert_eq!(new.state().bitcoin_chain_tip(), Some(chain_tip));
+        assert!(new.state().current_signer_set().is_signer(&peer));
+        assert_eq!(new.state().peer_clock_skew_millis(&peer), Some(-250));
+        assert_eq!(new.state().dry_run_tenures(), 2);
+        assert!(new.state().maintenance_mode());
+    }
+
+    /// The socket replaces a stale socket file, only its owner can access
+    /// it, and nothing is left behind from binding it.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bound_socket_is_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("handover.sock");
+        std::fs::write(&path, b"stale").unwrap();
+
+        let _listener = HandoverListener::bind(&path).unwrap();
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        UnixStream::connect(&path).await.unwrap();
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+}
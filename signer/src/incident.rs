@@ -0,0 +1,453 @@
+//! # Incident runbooks
+//!
+//! Guided diagnostics for the incidents that operators run into most
+//! often. Each runbook gathers the relevant state from the database and
+//! from the bitcoin and stacks nodes, and turns it into an
+//! [`IncidentReport`] of findings and recommended next actions. Some
+//! incidents have a remediation that is safe to carry out without
+//! further investigation, which the report carries so that the operator
+//! can run it after confirming.
+//!
+//! The runbooks are run through the `signer incident` commands:
+//!
+//! * `signer incident stuck-sweep <txid>` for a sweep transaction that is
+//!   taking long to confirm.
+//! * `signer incident stalled-tenure` for when the signers stop
+//!   fulfilling requests.
+
+use std::fmt;
+
+use crate::bitcoin::BitcoinInteract as _;
+use crate::bitcoin::TransactionLookupHint;
+use crate::chain_consistency::ChainConsistency;
+use crate::chain_consistency::check_chain_consistency;
+use crate::context::Context;
+use crate::error::Error;
+use crate::stacks::api::StacksInteract as _;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+use crate::transaction_coordinator::coordinator_public_key;
+
+/// The outcome of a single check of a runbook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// What was checked.
+    pub check: &'static str,
+    /// Whether the check found a problem.
+    pub problem: bool,
+    /// What the check found.
+    pub detail: String,
+}
+
+/// A remediation that is safe to carry out without further
+/// investigation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Remediation {
+    /// Broadcast the given transaction to our bitcoin node again.
+    Rebroadcast(bitcoin::Transaction),
+}
+
+impl Remediation {
+    /// A description of the remediation, for confirming it with the
+    /// operator.
+    pub fn description(&self) -> String {
+        match self {
+            Self::Rebroadcast(tx) => format!("rebroadcast transaction {}", tx.compute_txid()),
+        }
+    }
+
+    /// Carry out the remediation.
+    pub async fn execute<C: Context>(&self, ctx: &C) -> Result<(), Error> {
+        match self {
+            Self::Rebroadcast(tx) => ctx.get_bitcoin_client().broadcast_transaction(tx).await,
+        }
+    }
+}
+
+/// The findings and recommended next actions of a runbook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncidentReport {
+    /// The incident that was diagnosed.
+    pub title: String,
+    /// The outcomes of the checks, in the order that they were run.
+    pub findings: Vec<Finding>,
+    /// The recommended next actions, most important first.
+    pub actions: Vec<String>,
+    /// A remediation that is safe to carry out, if there is one.
+    pub remediation: Option<Remediation>,
+}
+
+impl IncidentReport {
+    fn new(title: String) -> Self {
+        Self {
+            title,
+            findings: Vec::new(),
+            actions: Vec::new(),
+            remediation: None,
+        }
+    }
+
+    fn ok(&mut self, check: &'static str, detail: impl Into<String>) {
+        let detail = detail.into();
+        self.findings
+            .push(Finding { check, problem: false, detail });
+    }
+
+    fn problem(
+        &mut self,
+        check: &'static str,
+        detail: impl Into<String>,
+        action: impl Into<String>,
+    ) {
+        let detail = detail.into();
+        self.findings.push(Finding { check, problem: true, detail });
+        self.actions.push(action.into());
+    }
+
+    /// Whether any of the checks found a problem.
+    pub fn has_problems(&self) -> bool {
+        self.findings.iter().any(|finding| finding.problem)
+    }
+}
+
+impl fmt::Display for IncidentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Incident: {}", self.title)?;
+        writeln!(f)?;
+        writeln!(f, "Findings:")?;
+        for finding in &self.findings {
+            let status = if finding.problem { "problem" } else { "ok" };
+            writeln!(f, "  [{status:>7}] {}: {}", finding.check, finding.detail)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Recommended actions:")?;
+        if self.actions.is_empty() {
+            writeln!(f, "  None, the checks did not find a problem.")?;
+        }
+        for (index, action) in self.actions.iter().enumerate() {
+            writeln!(f, "  {}. {action}", index + 1)?;
+        }
+        if let Some(remediation) = &self.remediation {
+            writeln!(f)?;
+            writeln!(f, "Safe remediation: {}", remediation.description())?;
+        }
+        Ok(())
+    }
+}
+
+/// Diagnose a sweep transaction that is taking long to confirm.
+pub async fn stuck_sweep<C: Context>(ctx: &C, txid: BitcoinTxId) -> Result<IncidentReport, Error> {
+    let db = ctx.get_storage();
+    let bitcoin_client = ctx.get_bitcoin_client();
+    let mut report = IncidentReport::new(format!("stuck sweep {txid}"));
+
+    let chain_tip = db
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await?
+        .ok_or(Error::NoChainTip)?;
+
+    // Look for the blocks that we have seen the transaction confirmed in.
+    let mut orphaned_in = None;
+    for block_hash in db.get_bitcoin_blocks_with_transaction(&txid).await? {
+        let Some(block) = db.get_bitcoin_block(&block_hash).await? else {
+            continue;
+        };
+        let block_ref = BitcoinBlockRef::from(&block);
+        if db
+            .in_canonical_bitcoin_blockchain(&chain_tip, &block_ref)
+            .await?
+        {
+            report.ok(
+                "confirmation",
+                format!(
+                    "confirmed in block {} at height {}",
+                    block.block_hash, block.block_height
+                ),
+            );
+            report.actions.push(
+                "No action is needed for the sweep. If its deposits are not minted yet, \
+                run `signer incident stalled-tenure`."
+                    .to_string(),
+            );
+            return Ok(report);
+        }
+        orphaned_in = Some(block_hash);
+    }
+    match orphaned_in {
+        Some(block_hash) => report.problem(
+            "confirmation",
+            format!("confirmed only in block {block_hash}, which a reorg has orphaned"),
+            "The completions of the deposits that the sweep swept in are provisional until it \
+            confirms again.",
+        ),
+        None => report.ok("confirmation", "not confirmed in any block that we know of"),
+    }
+
+    // The coordinator only bumps the fee of the sweep spending the
+    // signers' UTXO, so a sweep that does not do that will never confirm.
+    let signer_utxo = db.get_signer_utxo(&chain_tip.block_hash).await?;
+    let Some(signer_utxo) = signer_utxo else {
+        report.problem(
+            "signers' UTXO",
+            "no signers' UTXO on the canonical bitcoin blockchain",
+            "Check that DKG has completed and that the signers' UTXO has been funded.",
+        );
+        return Ok(report);
+    };
+    let spenders = bitcoin_client
+        .find_mempool_transactions_spending_output(&signer_utxo.outpoint)
+        .await?;
+
+    let txid = bitcoin::Txid::from(txid);
+    if bitcoin_client.get_mempool_entry(&txid).await?.is_some() {
+        let fees = bitcoin_client
+            .get_transaction_fee(&txid, Some(TransactionLookupHint::Mempool))
+            .await?;
+        let estimate = bitcoin_client.estimate_fee_rate().await?;
+        if fees.fee_rate < estimate {
+            report.problem(
+                "mempool",
+                format!(
+                    "in the mempool at {:.2} sats/vB, below the estimate of {estimate:.2} sats/vB",
+                    fees.fee_rate
+                ),
+                "The coordinator replaces the sweep with one paying a higher fee at the next \
+                bitcoin block. If that does not happen, check that the coordinator is online \
+                and that `sweep_fee_max_sats` and `sweep_fee_max_fraction_bps` allow the fee.",
+            );
+        } else {
+            report.ok(
+                "mempool",
+                format!(
+                    "in the mempool at {:.2} sats/vB, at or above the estimate of \
+                    {estimate:.2} sats/vB",
+                    fees.fee_rate
+                ),
+            );
+        }
+        if !spenders.contains(&txid) {
+            report.problem(
+                "signers' UTXO",
+                format!("does not spend the signers' UTXO {}", signer_utxo.outpoint),
+                "The coordinator does not bump the fee of this sweep. Check whether it spends \
+                an output of another unconfirmed sweep, and diagnose that one first.",
+            );
+        }
+        return Ok(report);
+    }
+
+    let confirmed_in = bitcoin_client
+        .get_tx(&txid)
+        .await?
+        .and_then(|response| response.block_hash);
+    match confirmed_in {
+        Some(block_hash) => {
+            let block_hash = BitcoinBlockHash::from(block_hash);
+            report.problem(
+                "bitcoin node",
+                format!("bitcoin-core has it confirmed in block {block_hash}"),
+                "The block observer has not processed the block yet. Run \
+                `signer incident stalled-tenure` to check whether it is behind.",
+            );
+        }
+        None => {
+            let replacements: Vec<_> = spenders.iter().map(ToString::to_string).collect();
+            let detail = "not in the mempool of bitcoin-core";
+            if !replacements.is_empty() {
+                report.problem(
+                    "mempool",
+                    detail,
+                    format!(
+                        "The sweep was replaced by {}. Diagnose the replacement instead.",
+                        replacements.join(", ")
+                    ),
+                );
+            } else if let Some(block_hash) = orphaned_in {
+                let tx_info = bitcoin_client
+                    .get_tx_info(&txid, &block_hash.into())
+                    .await?;
+                report.problem(
+                    "mempool",
+                    detail,
+                    "Rebroadcast the sweep, so that the coordinator can bump its fee.",
+                );
+                report.remediation = tx_info.map(|info| Remediation::Rebroadcast(info.tx));
+            } else {
+                report.problem(
+                    "mempool",
+                    detail,
+                    "The coordinator constructs a new sweep at the next bitcoin block if \
+                    requests are still pending. If none appears, check the coordinator logs.",
+                );
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Diagnose signers that have stopped fulfilling requests.
+pub async fn stalled_tenure<C: Context>(ctx: &C) -> Result<IncidentReport, Error> {
+    let db = ctx.get_storage();
+    let bitcoin_client = ctx.get_bitcoin_client();
+    let stacks_client = ctx.get_stacks_client();
+    let config = ctx.config();
+    let mut report = IncidentReport::new("stalled tenure".to_string());
+
+    let Some(chain_tip) = db.get_bitcoin_canonical_chain_tip_ref().await? else {
+        report.problem(
+            "bitcoin chain tip",
+            "no bitcoin blocks have been observed",
+            "Check the connection of the signer to bitcoin-core.",
+        );
+        return Ok(report);
+    };
+
+    // Bitcoin.
+    let blockchain_info = bitcoin_client.get_blockchain_info().await?;
+    let best_block_hash = BitcoinBlockHash::from(blockchain_info.best_block_hash);
+    if blockchain_info.initial_block_download {
+        report.problem(
+            "bitcoin node",
+            format!(
+                "bitcoin-core is syncing, at height {}",
+                blockchain_info.blocks
+            ),
+            "Wait for bitcoin-core to finish syncing.",
+        );
+    } else if best_block_hash != chain_tip.block_hash {
+        report.problem(
+            "bitcoin chain tip",
+            format!(
+                "our chain tip is {} at height {}, bitcoin-core's is {best_block_hash} at \
+                height {}",
+                chain_tip.block_hash, chain_tip.block_height, blockchain_info.blocks
+            ),
+            "The block observer is behind bitcoin-core. Check the signer's connection to \
+            bitcoin-core and the block observer logs.",
+        );
+    } else {
+        report.ok(
+            "bitcoin chain tip",
+            format!(
+                "{} at height {}, the same as bitcoin-core",
+                chain_tip.block_hash, chain_tip.block_height
+            ),
+        );
+    }
+
+    // Stacks.
+    match check_chain_consistency(ctx).await? {
+        ChainConsistency::Inconsistent { stacks_anchor, .. } => report.problem(
+            "chain consistency",
+            format!(
+                "the stacks node is anchored to bitcoin block {}, which is not on our \
+                canonical bitcoin blockchain",
+                stacks_anchor.block_hash
+            ),
+            "Check that the stacks node and the signer follow the same bitcoin node.",
+        ),
+        ChainConsistency::Consistent | ChainConsistency::Unknown => report.ok(
+            "chain consistency",
+            "the stacks node is anchored to our canonical bitcoin blockchain",
+        ),
+    }
+    let tenure_info = stacks_client.get_tenure_info().await?;
+    let stacks_chain_tip = db.get_stacks_chain_tip(&chain_tip.block_hash).await?;
+    let stacks_height = stacks_chain_tip.map(|block| *block.block_height);
+    if stacks_height.is_none_or(|height| height < tenure_info.tip_height) {
+        report.problem(
+            "stacks chain tip",
+            format!(
+                "our stacks chain tip is at height {}, the stacks node's is at height {}",
+                stacks_height.map_or("none".to_string(), |height| height.to_string()),
+                tenure_info.tip_height
+            ),
+            "The signer is missing stacks blocks. Check that the stacks node's event observer \
+            points at the signer's `/new_block` endpoint.",
+        );
+    } else {
+        report.ok(
+            "stacks chain tip",
+            format!("at height {}", tenure_info.tip_height),
+        );
+    }
+
+    // Signing.
+    match db.get_latest_verified_dkg_shares().await? {
+        Some(shares) => report.ok(
+            "DKG",
+            format!("verified shares for aggregate key {}", shares.aggregate_key),
+        ),
+        None => report.problem(
+            "DKG",
+            "no verified DKG shares",
+            "The signers cannot sign before DKG completes and its shares are verified. Check \
+            the DKG logs of the coordinator.",
+        ),
+    }
+    let signer_set = &config.signer.bootstrap_signing_set;
+    let coordinator = coordinator_public_key(&chain_tip.block_hash, signer_set);
+    let coordinator = coordinator.map_or("none".to_string(), |key| key.to_string());
+    report.ok(
+        "coordinator",
+        format!("the coordinator for the chain tip is {coordinator}"),
+    );
+    let peers = db.get_p2p_peers().await?;
+    let required = usize::from(config.signer.bootstrap_signatures_required);
+    if peers.len() + 1 < required {
+        report.problem(
+            "peers",
+            format!(
+                "{} known peers, but {required} signers must sign",
+                peers.len()
+            ),
+            "Check the P2P seeds and the network connectivity to the other signers.",
+        );
+    } else {
+        report.ok("peers", format!("{} known peers", peers.len()));
+    }
+
+    // Requests.
+    let quarantined = db.get_quarantined_requests().await?;
+    if quarantined.is_empty() {
+        report.ok("quarantine", "no quarantined requests");
+    } else {
+        report.problem(
+            "quarantine",
+            format!("{} quarantined requests", quarantined.len()),
+            "List them with `GET /quarantine`, and release them once the cause of their \
+            signing failures is fixed.",
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_findings_and_numbered_actions() {
+        let mut report = IncidentReport::new("test".to_string());
+        report.ok("first", "fine");
+        assert!(!report.has_problems());
+        report.problem("second", "broken", "fix it");
+
+        let expected = "\
+Incident: test
+
+Findings:
+  [     ok] first: fine
+  [problem] second: broken
+
+Recommended actions:
+  1. fix it
+";
+        assert!(report.has_problems());
+        assert_eq!(report.to_string(), expected);
+    }
+}
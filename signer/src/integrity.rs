@@ -0,0 +1,388 @@
+//! Keyed integrity hashes over security-critical database rows.
+//!
+//! When `signer.integrity_key` is set in the config, the signer stores an
+//! HMAC-SHA256 of every completed deposit event and key rotation event in
+//! the same database transaction that stores the event. The key never
+//! touches the database, so someone with write access to the database
+//! alone cannot produce a valid hash for a row that they modified or
+//! inserted. The [`IntegrityVerifier`] periodically recomputes the hashes
+//! and reports any row that was modified, inserted or deleted
+//! out-of-band. The same verification can be triggered on demand through
+//! the `GET /integrity` endpoint.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use bitcoin::hashes::Hash as _;
+use bitcoin::hashes::HashEngine as _;
+use bitcoin::hashes::hmac::Hmac;
+use bitcoin::hashes::hmac::HmacEngine;
+use bitcoin::hashes::sha256;
+use serde::Serialize;
+use zeroize::Zeroize as _;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbRead;
+use crate::storage::model::CompletedDepositEvent;
+use crate::storage::model::KeyRotationEvent;
+use crate::storage::model::RowIntegrityHash;
+
+/// The secret key used to compute integrity hashes.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IntegrityKey([u8; 32]);
+
+impl IntegrityKey {
+    /// Create a new integrity key from the given secret bytes.
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Compute the integrity hash of the given row.
+    ///
+    /// The table name is part of the hashed data, so that a hash cannot
+    /// be moved from a row in one table to a row in another.
+    pub fn hash_row<R: ProtectedRow>(&self, row: &R) -> RowIntegrityHash {
+        let mut engine = HmacEngine::<sha256::Hash>::new(&self.0);
+        engine.input(R::TABLE_NAME.as_bytes());
+        engine.input(&[0]);
+        engine.input(&row.row_data());
+
+        RowIntegrityHash {
+            table_name: R::TABLE_NAME.to_string(),
+            row_key: row.row_key(),
+            integrity_hash: Hmac::from_engine(engine).to_byte_array(),
+        }
+    }
+}
+
+impl std::fmt::Debug for IntegrityKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IntegrityKey(<redacted>)")
+    }
+}
+
+impl Drop for IntegrityKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// A row of a table whose integrity is protected by a keyed hash.
+pub trait ProtectedRow {
+    /// The name of the table that holds rows of this type.
+    const TABLE_NAME: &'static str;
+
+    /// The bytes of the columns that identify the row.
+    fn row_key(&self) -> Vec<u8>;
+
+    /// The bytes of all columns of the row that are protected, including
+    /// the columns that identify it.
+    fn row_data(&self) -> Vec<u8>;
+}
+
+impl ProtectedRow for CompletedDepositEvent {
+    const TABLE_NAME: &'static str = "completed_deposit_events";
+
+    fn row_key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(100);
+        key.extend_from_slice(self.txid.to_bytes());
+        key.extend_from_slice(self.block_id.to_bytes());
+        key.extend_from_slice(&self.outpoint.txid.to_byte_array());
+        key.extend_from_slice(&self.outpoint.vout.to_be_bytes());
+        key
+    }
+
+    fn row_data(&self) -> Vec<u8> {
+        let mut data = self.row_key();
+        data.extend_from_slice(&self.amount.get().to_be_bytes());
+        data.extend_from_slice(&self.sweep_block_hash.into_bytes());
+        data.extend_from_slice(&self.sweep_block_height.to_be_bytes());
+        data.extend_from_slice(&self.sweep_txid.into_bytes());
+        data
+    }
+}
+
+impl ProtectedRow for KeyRotationEvent {
+    const TABLE_NAME: &'static str = "rotate_keys_transactions";
+
+    fn row_key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(64);
+        key.extend_from_slice(self.txid.to_bytes());
+        key.extend_from_slice(self.block_hash.to_bytes());
+        key
+    }
+
+    fn row_data(&self) -> Vec<u8> {
+        // The address and the signer set have variable lengths, so we
+        // prefix them with their lengths to keep the encoding unambiguous.
+        let address = self.address.to_string();
+        let mut data = self.row_key();
+        data.extend_from_slice(&(address.len() as u64).to_be_bytes());
+        data.extend_from_slice(address.as_bytes());
+        data.extend_from_slice(&self.aggregate_key.serialize());
+        data.extend_from_slice(&(self.signer_set.len() as u64).to_be_bytes());
+        for public_key in &self.signer_set {
+            data.extend_from_slice(&public_key.serialize());
+        }
+        data.extend_from_slice(&self.signatures_required.to_be_bytes());
+        data
+    }
+}
+
+/// The integrity hash of the given row, if an integrity key has been
+/// configured. The hash should be written in the same database
+/// transaction that writes the row.
+pub fn integrity_hash<R: ProtectedRow>(ctx: &impl Context, row: &R) -> Option<RowIntegrityHash> {
+    let key = ctx.config().signer.integrity_key.as_ref()?;
+    Some(key.hash_row(row))
+}
+
+/// The outcome of verifying the protected rows of a single table.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct TableIntegrityReport {
+    /// The name of the verified table.
+    pub table_name: &'static str,
+    /// The number of rows whose contents match their integrity hash.
+    pub verified: usize,
+    /// The hex-encoded keys of rows whose contents do not match their
+    /// integrity hash.
+    pub modified: Vec<String>,
+    /// The hex-encoded keys of rows without an integrity hash. These rows
+    /// were either inserted out-of-band, or written before an integrity
+    /// key was configured.
+    pub unhashed: Vec<String>,
+    /// The hex-encoded keys of integrity hashes whose row no longer
+    /// exists.
+    pub deleted: Vec<String>,
+}
+
+impl TableIntegrityReport {
+    /// Whether every row of the table matched its integrity hash.
+    pub fn is_intact(&self) -> bool {
+        self.modified.is_empty() && self.unhashed.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// Verify the given rows against their integrity hashes.
+///
+/// The hashes in `hashes_before` must have been read before the rows, and
+/// the ones in `hashes_after` after them. Rows and their hashes are
+/// committed together and rows are never deleted by the signer, so this
+/// way rows written while the verification is running are not reported.
+fn verify_rows<R: ProtectedRow>(
+    key: &IntegrityKey,
+    rows: &[R],
+    hashes_before: Vec<RowIntegrityHash>,
+    hashes_after: Vec<RowIntegrityHash>,
+) -> TableIntegrityReport {
+    let expected: BTreeMap<Vec<u8>, [u8; 32]> = hashes_after
+        .into_iter()
+        .map(|hash| (hash.row_key, hash.integrity_hash))
+        .collect();
+
+    let mut report = TableIntegrityReport {
+        table_name: R::TABLE_NAME,
+        ..Default::default()
+    };
+    let mut seen = BTreeSet::new();
+
+    for row in rows {
+        let actual = key.hash_row(row);
+        match expected.get(&actual.row_key) {
+            Some(hash) if *hash == actual.integrity_hash => report.verified += 1,
+            Some(_) => report.modified.push(hex::encode(&actual.row_key)),
+            None => report.unhashed.push(hex::encode(&actual.row_key)),
+        }
+        seen.insert(actual.row_key);
+    }
+
+    report.deleted = hashes_before
+        .into_iter()
+        .filter(|hash| !seen.contains(&hash.row_key))
+        .map(|hash| hex::encode(hash.row_key))
+        .collect();
+
+    report
+}
+
+/// Verify all protected rows in the database against their integrity
+/// hashes, returning a report for each protected table.
+pub async fn verify_integrity(
+    storage: &impl DbRead,
+    key: &IntegrityKey,
+) -> Result<Vec<TableIntegrityReport>, Error> {
+    let table_name = CompletedDepositEvent::TABLE_NAME;
+    let hashes_before = storage.get_row_integrity_hashes(table_name).await?;
+    let rows = storage.get_completed_deposit_events().await?;
+    let hashes_after = storage.get_row_integrity_hashes(table_name).await?;
+    let deposits = verify_rows(key, &rows, hashes_before, hashes_after);
+
+    let table_name = KeyRotationEvent::TABLE_NAME;
+    let hashes_before = storage.get_row_integrity_hashes(table_name).await?;
+    let rows = storage.get_key_rotation_events().await?;
+    let hashes_after = storage.get_row_integrity_hashes(table_name).await?;
+    let key_rotations = verify_rows(key, &rows, hashes_before, hashes_after);
+
+    Ok(vec![deposits, key_rotations])
+}
+
+/// Periodically verifies the integrity of the protected rows in the
+/// database, logging any violations.
+pub struct IntegrityVerifier<C> {
+    /// Signer context.
+    context: C,
+    /// The secret key used to compute integrity hashes.
+    key: IntegrityKey,
+    /// Verification interval.
+    interval: Duration,
+}
+
+impl<C> IntegrityVerifier<C>
+where
+    C: Context,
+{
+    /// Creates a new IntegrityVerifier with given context, key and
+    /// interval.
+    pub fn new(context: C, key: IntegrityKey, interval: Duration) -> Self {
+        Self { context, key, interval }
+    }
+
+    /// Runs the IntegrityVerifier, which verifies the database right away
+    /// and then once every interval.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        self.verify().await;
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.verify().await;
+                }
+            }
+        }
+        tracing::info!("integrity verifier has stopped");
+    }
+
+    #[tracing::instrument(skip_all, name = "integrity-verifier")]
+    async fn verify(&self) {
+        let storage = self.context.get_storage();
+        let reports = match verify_integrity(&storage, &self.key).await {
+            Ok(reports) => reports,
+            Err(error) => {
+                tracing::warn!(%error, "could not verify the integrity of the database");
+                return;
+            }
+        };
+
+        for report in reports {
+            Metrics::record_integrity_report(&report);
+
+            if report.is_intact() {
+                tracing::debug!(
+                    table = report.table_name,
+                    verified = report.verified,
+                    "database rows passed integrity verification"
+                );
+                continue;
+            }
+
+            tracing::error!(
+                table = report.table_name,
+                verified = report.verified,
+                modified = ?report.modified,
+                unhashed = ?report.unhashed,
+                deleted = ?report.deleted,
+                "database rows failed integrity verification"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::amount::SbtcAmount;
+    use crate::storage::DbWrite as _;
+    use crate::storage::memory::Store;
+
+    use super::*;
+
+    fn completed_deposit_event() -> CompletedDepositEvent {
+        Faker.fake_with_rng(&mut crate::testing::get_rng())
+    }
+
+    #[test]
+    fn hashes_depend_on_the_key_and_the_row() {
+        let key = IntegrityKey::new([1; 32]);
+        let event = completed_deposit_event();
+        let hash = key.hash_row(&event);
+
+        assert_eq!(hash, key.hash_row(&event));
+        assert_ne!(hash, IntegrityKey::new([2; 32]).hash_row(&event));
+
+        let modified = CompletedDepositEvent {
+            amount: SbtcAmount::new(1),
+            ..event
+        };
+        let modified_hash = key.hash_row(&modified);
+        assert_eq!(hash.row_key, modified_hash.row_key);
+        assert_ne!(hash.integrity_hash, modified_hash.integrity_hash);
+    }
+
+    #[tokio::test]
+    async fn verification_detects_out_of_band_changes() {
+        let key = IntegrityKey::new([1; 32]);
+        let store = Store::new_shared();
+
+        let hashed = completed_deposit_event();
+        store.write_completed_deposit_event(&hashed).await.unwrap();
+        store
+            .write_row_integrity_hash(&key.hash_row(&hashed))
+            .await
+            .unwrap();
+
+        let reports = verify_integrity(&store, &key).await.unwrap();
+        assert!(reports.iter().all(TableIntegrityReport::is_intact));
+        assert_eq!(reports[0].verified, 1);
+
+        // Modify the row that was hashed, and insert a row without a hash.
+        let modified = CompletedDepositEvent {
+            amount: SbtcAmount::new(1),
+            ..hashed.clone()
+        };
+        let inserted = completed_deposit_event();
+        {
+            let mut store = store.lock().await;
+            store
+                .completed_deposit_events
+                .insert(modified.outpoint, vec![modified.clone()]);
+            store
+                .completed_deposit_events
+                .insert(inserted.outpoint, vec![inserted.clone()]);
+        }
+
+        let reports = verify_integrity(&store, &key).await.unwrap();
+        let report = &reports[0];
+        assert_eq!(report.modified, vec![hex::encode(modified.row_key())]);
+        assert_eq!(report.unhashed, vec![hex::encode(inserted.row_key())]);
+        assert!(report.deleted.is_empty());
+
+        // Delete the row that was hashed.
+        store
+            .lock()
+            .await
+            .completed_deposit_events
+            .remove(&hashed.outpoint);
+
+        let reports = verify_integrity(&store, &key).await.unwrap();
+        assert_eq!(reports[0].deleted, vec![hex::encode(hashed.row_key())]);
+        assert!(reports[1].is_intact());
+    }
+}
@@ -36,6 +36,7 @@ use bitcoin::TapTweakHash;
 use secp256k1::SECP256K1;
 use serde::Deserialize;
 use serde::Serialize;
+use zeroize::Zeroizing;
 
 use crate::error::Error;
 
@@ -327,10 +328,27 @@ impl PublicKeyXOnly {
 }
 
 /// A private key type for the secp256k1 elliptic curve.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+///
+/// The key is erased when it is dropped, so it is not `Copy` and copies of
+/// it have to be made explicitly with `clone`. Copies of its raw bytes
+/// should be made with [`PrivateKey::to_secret_bytes`], which zeroes them
+/// when they are dropped.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
 #[serde(transparent)]
 pub struct PrivateKey(secp256k1::SecretKey);
 
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.0.non_secure_erase();
+    }
+}
+
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PrivateKey(<redacted>)")
+    }
+}
+
 impl FromStr for PrivateKey {
     type Err = Error;
 
@@ -405,6 +423,12 @@ impl PrivateKey {
         self.0.secret_bytes()
     }
 
+    /// Returns the secret key as a byte array that is zeroed when it is
+    /// dropped.
+    pub fn to_secret_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.secret_bytes())
+    }
+
     /// Constructs an ECDSA signature for `message` using the global
     /// [`SECP256K1`] context and returns it in "low S" form.
     pub fn sign_ecdsa(&self, msg: &secp256k1::Message) -> secp256k1::ecdsa::Signature {
@@ -536,6 +560,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn private_keys_are_not_printed() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let debug = format!("{private_key:?} {:?}", Some(private_key.clone()));
+        let hex = hex::encode(private_key.to_bytes());
+        assert!(!debug.contains(&hex));
+        assert!(debug.contains("PrivateKey(<redacted>)"));
+
+        assert_eq!(*private_key.to_secret_bytes(), private_key.to_bytes());
+    }
+
     #[test]
     fn zero_valid_scalar_invalid_private_key() {
         let bytes = [0; 32];
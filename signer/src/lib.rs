@@ -1,31 +1,57 @@
 #![doc = include_str!("../README.md")]
 
+pub mod amount;
+pub mod analytics_export;
 pub mod api;
+pub mod balance_monitor;
 pub mod bitcoin;
 pub mod block_observer;
 pub mod blocklist_client;
+pub mod capture;
+pub mod chain_consistency;
+pub mod checkpoint;
 pub mod codec;
 pub mod config;
+pub mod confirmation_watches;
 pub mod context;
+pub mod crash_report;
 pub mod dkg;
+pub mod dry_run;
 pub mod ecdsa;
 pub mod emily_client;
 pub mod error;
+pub mod export;
+pub mod handover;
+pub mod incident;
+pub mod integrity;
 pub mod keys;
 pub mod logging;
 pub mod message;
 pub mod metrics;
 pub mod network;
+pub mod orphaned_sweeps;
 pub mod proto;
+pub mod quorum;
+pub mod raw_stacks_events;
+pub mod reclaim;
 pub mod request_decider;
+pub mod response_latency;
+pub mod runtime;
+pub mod secrets;
+pub mod self_test;
+pub mod serialization_audit;
 pub mod signature;
 pub mod stacks;
+pub mod stacks_block_gaps;
+pub mod stale_deposits;
 pub mod storage;
+pub mod sweep_completions;
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
 pub mod transaction_coordinator;
 pub mod transaction_signer;
 pub mod util;
+pub mod webhooks;
 pub mod wsts_state_machine;
 
 /// Package version
@@ -81,6 +107,23 @@ pub const MAX_REORG_BLOCK_COUNT: u64 = 10;
 /// <https://github.com/bitcoin/bitcoin/blob/v25.0/src/policy/policy.h#L58-L59>
 pub const MAX_MEMPOOL_PACKAGE_TX_COUNT: u64 = 25;
 
+/// The maximum number of blocks that the lock time of a sweep transaction
+/// may be below the height of the bitcoin chain tip.
+///
+/// Sweep transactions set their lock time to the height of the chain tip,
+/// like bitcoin-core's wallet does, so that miners cannot take their fees
+/// by reorging the chain tip. Bitcoin-core occasionally sets the lock time
+/// up to this many blocks further back, so signers accept any lock time
+/// in this window.
+pub const MAX_SWEEP_LOCK_TIME_AGE: u64 = 100;
+
+/// The maximum number of donation UTXOs that can be spent in a sweep
+/// transaction to top up its fees.
+///
+/// Each donation input adds to the size of the transaction, and needs its
+/// own signing round, so we keep their number small.
+pub const MAX_DONATION_INPUTS_PER_SWEEP: usize = 10;
+
 /// The default maximum number of deposit inputs per bitcoin transaction.
 ///
 /// The default here is chosen so that there is a ~50% chance that the
@@ -88,6 +131,73 @@ pub const MAX_MEMPOOL_PACKAGE_TX_COUNT: u64 = 25;
 /// next bitcoin block. This assumes signing rounds take ~16 seconds.
 pub const DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX: u16 = 25;
 
+/// The default maximum number of withdrawal reject transactions that the
+/// coordinator signs and submits during a single tenure.
+///
+/// The sign requests for these transactions are sent out together, and
+/// every signer validates them one after the other, so all of them need
+/// to be validated within a single signing round.
+pub const DEFAULT_MAX_WITHDRAWAL_REJECTS_PER_TENURE: u16 = 10;
+
+/// The default number of signing failures after which the coordinator
+/// quarantines a deposit or withdrawal request and stops attempting to
+/// fulfill it.
+///
+/// Signing rounds fail for many transient reasons, like a new bitcoin
+/// block arriving mid-round, so this is set high enough that a request
+/// is only quarantined after failing across several tenures.
+pub const DEFAULT_REQUEST_QUARANTINE_THRESHOLD: u32 = 10;
+
+/// The default number of seconds between integrity verifications of the
+/// protected rows in the database.
+pub const DEFAULT_INTEGRITY_CHECK_INTERVAL_SECONDS: u64 = 3600;
+
+/// The default number of seconds between checks for stale deposit
+/// requests.
+pub const DEFAULT_STALE_DEPOSIT_CHECK_INTERVAL_SECONDS: u64 = 3600;
+
+/// The default age, in seconds, that a deposit request must reach before
+/// it can be marked as stale.
+///
+/// Deposit transactions can sit in the mempool for a long time while fee
+/// rates are high, and a deposit that is swept while we are catching up
+/// with the bitcoin blockchain looks spent before we have recorded the
+/// sweep, so this is set well beyond both.
+pub const DEFAULT_STALE_DEPOSIT_MIN_AGE_SECONDS: u64 = 7 * 86_400;
+
+/// The default number of bitcoin blocks that a sweep transaction may be
+/// confirmed for before the stacks transactions completing its requests
+/// are expected to have been observed.
+///
+/// The coordinator submits the completions in the tenure after the sweep
+/// is confirmed, and stacks blocks that include them usually follow
+/// within a block or two, so six blocks leaves room for a few failed
+/// tenures.
+pub const DEFAULT_SWEEP_COMPLETION_DEADLINE: u16 = 6;
+
+/// The default length, in seconds, of the rolling period over which the
+/// stacks fee budget applies.
+pub const DEFAULT_STACKS_FEE_BUDGET_PERIOD_SECONDS: u64 = 86_400;
+
+/// The default balance, in microSTX, of the signers' STX address below
+/// which the balance monitor alerts operators. This covers ten stacks
+/// transactions at the default maximum fee.
+pub const DEFAULT_STX_LOW_BALANCE_THRESHOLD_USTX: u64 = 15_000_000;
+
+/// The default maximum fee, in sats, of any sweep transaction. This is
+/// well above the fee of a full sweep transaction at the fee rates seen
+/// during past periods of congestion.
+pub const DEFAULT_SWEEP_FEE_MAX_SATS: u64 = 2_000_000;
+
+/// The default maximum fee of a sweep transaction, as a fraction of the
+/// amount that it sweeps, in basis points.
+pub const DEFAULT_SWEEP_FEE_MAX_FRACTION_BPS: u16 = 5_000;
+
+/// How long the observed response latencies of the other signers are
+/// kept for, and summarized over.
+pub const SIGNER_RESPONSE_LATENCY_WINDOW: std::time::Duration =
+    std::time::Duration::from_secs(7 * 24 * 3600);
+
 /// This is the dust limit for deposits in the sBTC smart contracts.
 /// Deposit amounts that is less than this amount will be rejected by the
 /// smart contract.
@@ -137,6 +247,14 @@ pub const WITHDRAWAL_EXPIRY_BUFFER: u64 = 6;
 /// <https://github.com/bitcoin/bitcoin/blob/v25.0/src/policy/policy.h#L60-L61>
 pub const MAX_MEMPOOL_PACKAGE_SIZE: u64 = 101000;
 
+/// This is the maximum virtual size of a standard bitcoin transaction.
+/// Bitcoin core neither relays nor mines transactions with a weight above
+/// `MAX_STANDARD_TX_WEIGHT`, which is 400,000 weight units, so each
+/// transaction in a sweep package must stay under this limit.
+///
+/// <https://github.com/bitcoin/bitcoin/blob/v25.0/src/policy/policy.h>
+pub const MAX_STANDARD_TX_VSIZE: u64 = 100_000;
+
 /// This is an upper bound on the number of signer state machines that we
 /// "could" need if we wanted to sign all inputs in parallel and running
 /// DKG.
@@ -155,6 +273,37 @@ pub const MAX_SIGNER_STATE_MACHINES: u64 = MAX_MEMPOOL_PACKAGE_SIZE
 /// bitcoin.
 pub const MIN_BITCOIN_INPUT_VSIZE: u64 = 58;
 
+/// The maximum estimated clock skew, in milliseconds, between this signer
+/// and another signer before we warn about it. The estimate includes the
+/// time that heartbeat messages spend in transit, so this is well above
+/// the expected network latency between signers.
+pub const MAX_PEER_CLOCK_SKEW_MILLIS: i64 = 30_000;
+
+/// The maximum number of `POST /new_block` webhooks from stacks-core
+/// whose events are written to the database at the same time. Further
+/// webhooks wait for one of these to finish.
+pub const MAX_CONCURRENT_STACKS_EVENTS: usize = 4;
+
+/// The maximum number of recently handled signals that the signer keeps
+/// descriptions of, for the crash reports of panicking components.
+pub const MAX_RECENT_EVENTS: usize = 32;
+
+/// The minimum number of bitcoin blocks that a signer must be missing
+/// before it asks its peers for block summaries over the bulk sync topic.
+/// Below this, fetching the block headers from bitcoin-core is cheap
+/// enough.
+pub const BULK_SYNC_MIN_BLOCKS: u64 = 6;
+
+/// The maximum number of block summaries in a single response on the bulk
+/// sync topic. Each compressed summary takes up fewer than 100 bytes, so
+/// a full response stays well below the maximum gossipsub message size.
+pub const BULK_SYNC_MAX_BLOCKS: u32 = 500;
+
+/// The number of seconds that a lagging signer waits for a response from
+/// its peers on the bulk sync topic before fetching the block headers from
+/// bitcoin-core.
+pub const BULK_SYNC_RESPONSE_TIMEOUT_SECS: u64 = 5;
+
 // These are all build info variables. Many of them are set in build.rs.
 
 /// The name of the binary that is being run,
@@ -1,61 +1,29 @@
 //! The main entrypoint for the sBTC signer binary.
 
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::sync::atomic::AtomicU64;
-use std::sync::atomic::Ordering;
-use std::time::Duration;
 
-use axum::http::Request;
-use axum::http::Response;
+use bitcoin::address::NetworkUnchecked;
 use cfg_if::cfg_if;
 use clap::Parser;
+use clap::Subcommand;
 use clap::ValueEnum;
-use signer::api;
-use signer::api::ApiState;
-use signer::bitcoin::poller::BitcoinChainTipPoller;
 use signer::bitcoin::rpc::BitcoinCoreClient;
-use signer::block_observer;
-use signer::blocklist_client::BlocklistClient;
 use signer::config::Settings;
 use signer::context::Context;
 use signer::context::SignerContext;
 use signer::emily_client::EmilyClient;
 use signer::error::Error;
-use signer::logging::SignerInfoLogger;
-use signer::network::P2PNetwork;
-use signer::network::libp2p::SignerSwarmBuilder;
-use signer::request_decider::RequestDeciderEventLoop;
+use signer::export::Export;
+use signer::export::ExportFormat;
+use signer::incident;
+use signer::reclaim::ReclaimSimulation;
+use signer::runtime::Signer;
+use signer::self_test::SelfTestReport;
 use signer::stacks::api::StacksClient;
-use signer::storage::DbRead as _;
+use signer::storage::model::Timestamp;
 use signer::storage::postgres::PgStore;
-use signer::transaction_coordinator;
-use signer::transaction_signer;
 use signer::util::ApiFallbackClient;
-use time::OffsetDateTime;
 use tokio::signal;
-use tower_http::trace::TraceLayer;
-use tracing::Instrument as _;
-use tracing::Span;
-
-/// This is how many seconds the P2P swarm will wait before attempting to
-/// bootstrap (i.e. connect to other peers). Three seconds is a sane default
-/// value, giving the swarm a few seconds to start up and bind listener(s)
-/// before proceeding.
-const INITIAL_BOOTSTRAP_DELAY_SECS: u64 = 3;
-
-// Timeout after which signer info logger will print new log.
-// Currently chosen to be 1 hour.
-// TODO: make this interval a config parameter.
-const SIGNER_INFO_LOGGER_INTERVAL: Duration = Duration::from_secs(3600);
-
-/// The window of time in which we consider a peer to be known and valid for
-/// inclusion in bootstrapping.
-const KNOWN_PEER_WINDOW: Duration = Duration::from_secs(60 * 60 * 24 * 30); // 30 days
-
-/// The maximum number of known peers we will attempt to bootstrap from, in
-/// addition to the seed peers.
-const MAX_KNOWN_PEERS: usize = 6;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum LogOutputFormat {
@@ -63,6 +31,21 @@ enum LogOutputFormat {
     Pretty,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportOutputFormat {
+    Csv,
+    Json,
+}
+
+impl From<ExportOutputFormat> for ExportFormat {
+    fn from(format: ExportOutputFormat) -> Self {
+        match format {
+            ExportOutputFormat::Csv => ExportFormat::Csv,
+            ExportOutputFormat::Json => ExportFormat::Json,
+        }
+    }
+}
+
 /// Command line arguments for the signer.
 #[derive(Debug, Parser)]
 #[clap(name = "sBTC Signer")]
@@ -79,6 +62,89 @@ struct SignerArgs {
 
     #[clap(short = 'o', long = "output-format", default_value = "pretty")]
     output_format: Option<LogOutputFormat>,
+
+    /// If set, the signer records the webhook bodies, clarity events, and
+    /// stacks RPC responses that it receives into this directory, in
+    /// anonymized form. This is meant for refreshing the test fixtures
+    /// from a devnet session, and should not be used in production.
+    #[clap(long, value_name = "DIR")]
+    capture_fixtures: Option<PathBuf>,
+
+    /// An optional maintenance command to run instead of the signer.
+    #[clap(subcommand)]
+    command: Option<SignerCommand>,
+}
+
+/// Maintenance commands that run and exit instead of starting the signer.
+#[derive(Debug, Subcommand)]
+enum SignerCommand {
+    /// Upgrade a database created by an earlier schema generation, whose
+    /// migrations were applied without being recorded, to the current
+    /// schema.
+    MigrateLegacy {
+        /// Carry out and verify the upgrade, then roll it back.
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Export the requests, votes, sweeps and fees recorded in the
+    /// signer's database over a period of time. See the `signer::export`
+    /// module for the tables of the export.
+    Export {
+        /// The first day of the period, as YYYY-MM-DD in UTC.
+        #[clap(long, value_parser = signer::export::parse_date)]
+        from: Timestamp,
+        /// The day after the last day of the period, as YYYY-MM-DD in UTC.
+        #[clap(long, value_parser = signer::export::parse_date)]
+        to: Timestamp,
+        /// The file format of the export.
+        #[clap(long, default_value = "json")]
+        format: ExportOutputFormat,
+        /// The directory to write the export to.
+        #[clap(long, value_name = "DIR", default_value = ".")]
+        output: PathBuf,
+    },
+    /// Construct the reclaim transaction template of a stored deposit and
+    /// check whether its reclaim path is currently spendable. The report
+    /// is printed to stdout as JSON.
+    SimulateReclaim {
+        /// The deposit UTXO, as TXID:VOUT.
+        #[clap(long)]
+        outpoint: bitcoin::OutPoint,
+        /// The address to send the reclaimed funds to. Defaults to the
+        /// first address that funded the deposit.
+        #[clap(long)]
+        destination: Option<bitcoin::Address<NetworkUnchecked>>,
+        /// The fee of the reclaim transaction, in sats.
+        #[clap(long, default_value_t = 1_000)]
+        fee_sats: u64,
+    },
+    /// Diagnose an incident and print the findings and recommended next
+    /// actions. See the `signer::incident` module for the runbooks.
+    Incident {
+        #[clap(subcommand)]
+        command: IncidentCommand,
+        /// Carry out the safe remediation of the incident, if there is
+        /// one, after asking for confirmation.
+        #[clap(long, global = true)]
+        remediate: bool,
+    },
+    /// Run a single-party DKG and signing round in memory and check that
+    /// the database can be written to and read from, for verifying a
+    /// deployment. The report is printed to stdout as JSON. See the
+    /// `signer::self_test` module for the checks.
+    SelfTest,
+}
+
+/// The incidents that can be diagnosed with `signer incident`.
+#[derive(Debug, Subcommand)]
+enum IncidentCommand {
+    /// Diagnose a sweep transaction that is taking long to confirm.
+    StuckSweep {
+        /// The ID of the sweep transaction.
+        txid: bitcoin::Txid,
+    },
+    /// Diagnose signers that have stopped fulfilling requests.
+    StalledTenure,
 }
 
 #[tokio::main]
@@ -99,6 +165,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "starting the sBTC signer",
     );
 
+    // Core dumps would contain the private key and the DKG shares.
+    if let Err(error) = signer::secrets::disable_core_dumps() {
+        tracing::warn!(%error, "could not disable core dumps");
+    }
+
+    if let Some(dir) = args.capture_fixtures {
+        signer::capture::enable(&dir).inspect_err(|error| {
+            tracing::error!(%error, "failed to enable fixture capture");
+        })?;
+        tracing::warn!(dir = %dir.display(), "capturing fixtures; do not use this mode in production");
+    }
+
     // Load the configuration file and/or environment variables.
     let settings = Settings::new(args.config).inspect_err(|error| {
         tracing::error!(%error, "failed to construct the configuration");
@@ -107,7 +185,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let signer_public_key = settings.signer.public_key();
     tracing::info!(%signer_public_key, "config loaded successfully");
 
-    signer::metrics::setup_metrics(settings.signer.prometheus_exporter_endpoint);
+    signer::metrics::setup_metrics(&settings.signer);
 
     // Open a connection to the signer db.
     let db = PgStore::connect(settings.signer.db_endpoint.as_str())
@@ -116,6 +194,120 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             tracing::error!(%err, "failed to connect to the database");
         })?;
 
+    match args.command {
+        Some(SignerCommand::MigrateLegacy { dry_run }) => {
+            let report = db.migrate_legacy(dry_run).await.inspect_err(|err| {
+                tracing::error!(%err, "failed to upgrade the legacy database");
+            })?;
+            tracing::info!(
+                already_applied = ?report.already_applied,
+                applied = ?report.applied,
+                dry_run = report.dry_run,
+                "legacy database upgraded successfully"
+            );
+            return Ok(());
+        }
+        Some(SignerCommand::Export { from, to, format, output }) => {
+            if from >= to {
+                tracing::error!("the export period must end after it starts");
+                return Err("invalid export period".into());
+            }
+            let export = Export::collect(&db, from, to).await.inspect_err(|err| {
+                tracing::error!(%err, "failed to collect the export");
+            })?;
+            export.write(&output, format.into()).inspect_err(|err| {
+                tracing::error!(%err, "failed to write the export");
+            })?;
+            tracing::info!(
+                deposits = export.deposits.len(),
+                withdrawals = export.withdrawals.len(),
+                sweeps = export.sweeps.len(),
+                stacks_fees = export.stacks_fees.len(),
+                output = %output.display(),
+                "export written successfully"
+            );
+            return Ok(());
+        }
+        Some(SignerCommand::SimulateReclaim {
+            outpoint,
+            destination,
+            fee_sats,
+        }) => {
+            let network = bitcoin::Network::from(settings.signer.network);
+            let destination = destination
+                .map(|address| address.require_network(network))
+                .transpose()?
+                .map(|address| address.script_pubkey());
+            let bitcoin_client = ApiFallbackClient::<BitcoinCoreClient>::try_from(&settings)?;
+            let fee = bitcoin::Amount::from_sat(fee_sats);
+            let simulation = ReclaimSimulation::run(
+                &db,
+                &bitcoin_client,
+                &signer_public_key,
+                outpoint,
+                destination,
+                fee,
+            )
+            .await
+            .inspect_err(|err| {
+                tracing::error!(%err, "failed to simulate the reclaim");
+            })?;
+            let Some(simulation) = simulation else {
+                tracing::error!(%outpoint, "no deposit request with this outpoint is stored");
+                return Err("unknown deposit".into());
+            };
+            println!("{:#}", simulation.to_json());
+            return Ok(());
+        }
+        Some(SignerCommand::Incident { command, remediate }) => {
+            let bitcoin_client = ApiFallbackClient::<BitcoinCoreClient>::try_from(&settings)?;
+            let stacks_client = ApiFallbackClient::<StacksClient>::try_from(&settings)?;
+            let emily_client = ApiFallbackClient::<EmilyClient>::try_from(&settings)?;
+            let ctx = SignerContext::new(settings, db, bitcoin_client, stacks_client, emily_client);
+
+            let report = match command {
+                IncidentCommand::StuckSweep { txid } => {
+                    incident::stuck_sweep(&ctx, txid.into()).await
+                }
+                IncidentCommand::StalledTenure => incident::stalled_tenure(&ctx).await,
+            };
+            let report = report.inspect_err(|err| {
+                tracing::error!(%err, "failed to diagnose the incident");
+            })?;
+            println!("{report}");
+
+            let Some(remediation) = report.remediation.filter(|_| remediate) else {
+                return Ok(());
+            };
+            print!(
+                "Carry out the remediation ({})? [y/N] ",
+                remediation.description()
+            );
+            std::io::Write::flush(&mut std::io::stdout())?;
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            if !answer.trim().eq_ignore_ascii_case("y") {
+                println!("Remediation skipped.");
+                return Ok(());
+            }
+            remediation.execute(&ctx).await.inspect_err(|err| {
+                tracing::error!(%err, "failed to carry out the remediation");
+            })?;
+            println!("Remediation done.");
+            return Ok(());
+        }
+        Some(SignerCommand::SelfTest) => {
+            let report = SelfTestReport::run(&db).await;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.passed {
+                tracing::error!("the self-test failed");
+                return Err("self-test failed".into());
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
     // Apply any pending migrations if automatic migrations are enabled.
     if args.migrate_db {
         db.apply_migrations().await.inspect_err(|err| {
@@ -123,72 +315,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         })?;
     }
 
-    // Initialize the signer context.
-    let context = SignerContext::<
-        _,
-        ApiFallbackClient<BitcoinCoreClient>,
-        ApiFallbackClient<StacksClient>,
-        ApiFallbackClient<EmilyClient>,
-    >::init(settings, db)
-    .inspect_err(|err| {
-        tracing::error!(%err, "failed to initialize the signer context");
-    })?;
-
-    // TODO: We should first check "another source of truth" for the current
-    // signing set, and only assume we are bootstrapping if that source is
-    // empty.
-    let settings = context.config();
-    for signer in &settings.signer.bootstrap_signing_set {
-        context.state().current_signer_set().add_signer(*signer);
-    }
-
-    // Run the application components concurrently. We're `join!`ing them
-    // here so that every component can shut itself down gracefully when
-    // the shutdown signal is received.
-    //
-    // Note that we must use `join` here instead of `select` as `select` would
-    // immediately abort the remaining tasks on the first completion, which
-    // deprives the other tasks of the opportunity to shut down gracefully. This
-    // is the reason we also use the `run_checked` helper method, which will
-    // intercept errors and send a shutdown signal to the other components if an error
-    // does occur, otherwise the `join` will continue running indefinitely.
-    let _ = tokio::join!(
-        // Our global termination signal watcher. This does not run using `run_checked`
-        // as it sends its own shutdown signal.
-        run_shutdown_signal_watcher(context.clone()),
-        // The rest of our services which run concurrently, and must all be
-        // running for the signer to be operational.
-        run_checked(run_api, &context),
-        run_checked(run_libp2p_swarm, &context),
-        run_checked(run_block_observer, &context),
-        run_checked(run_request_decider, &context),
-        run_checked(run_transaction_coordinator, &context),
-        run_checked(run_transaction_signer, &context),
-        // Signer info logger intentionally runned in unchecked mode,
-        // since it is not necessary for signer to be operational.
-        run_signer_info_logger(context.clone()),
+    // Initialize the clients and the signer.
+    let bitcoin_client = ApiFallbackClient::<BitcoinCoreClient>::try_from(&settings)?;
+    let stacks_client = ApiFallbackClient::<StacksClient>::try_from(&settings)?;
+    let emily_client = ApiFallbackClient::<EmilyClient>::try_from(&settings)?;
+
+    let signer = Signer::builder(settings)
+        .with_storage(db)
+        .with_bitcoin_client(bitcoin_client)
+        .with_stacks_client(stacks_client)
+        .with_emily_client(emily_client)
+        .build();
+
+    // Run the signer alongside our global termination signal watcher. The
+    // watcher does not run as part of the signer since embedders of the
+    // signer decide for themselves when to shut it down.
+    let (_, result) = tokio::join!(
+        run_shutdown_signal_watcher(signer.context().clone()),
+        signer.run(),
     );
 
-    Ok(())
-}
-
-/// A helper method that captures errors from the provided future and sends a
-/// shutdown signal to the application if an error is encountered. This is needed
-/// as otherwise the application would continue running indefinitely (since no
-/// shutdown signal is sent automatically on error).
-async fn run_checked<F, Fut, C>(f: F, ctx: &C) -> Result<(), Error>
-where
-    C: Context,
-    F: FnOnce(C) -> Fut,
-    Fut: std::future::Future<Output = Result<(), Error>>,
-{
-    if let Err(error) = f(ctx.clone()).await {
-        tracing::error!(%error, "a fatal error occurred; shutting down the application");
-        ctx.get_termination_handle().signal_shutdown();
-        return Err(error);
-    }
-
-    Ok(())
+    Ok(result?)
 }
 
 /// Runs the shutdown-signal watcher. On Unix systems, this listens for SIGHUP,
@@ -248,227 +395,3 @@ async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
 
     Ok(())
 }
-
-/// Runs the libp2p swarm.
-#[tracing::instrument(skip_all, name = "p2p")]
-async fn run_libp2p_swarm(ctx: impl Context) -> Result<(), Error> {
-    tracing::info!("initializing the p2p network");
-
-    tracing::debug!("building the libp2p swarm");
-    let config = ctx.config();
-
-    let enable_quic = config.signer.p2p.is_quic_used();
-
-    // Limit the number of signers to the maximum number of signer pubkeys we
-    // can support. Note that this value is used as a base value for swarm
-    // connection limit calculations.
-    let num_signers = ctx
-        .state()
-        .current_signer_set()
-        .num_signers()
-        .try_into()
-        .unwrap_or(signer::MAX_KEYS);
-
-    // Look for known peers in the database which will be included as part of
-    // the bootstrapping process. We will only include peers that have been
-    // dialed within the KNOWN_PEER_WINDOW, and we will limit the number of
-    // peers to MAX_KNOWN_PEERS. This filtering is done to give the signer a
-    // reasonable list of peers to connect to, increasing its likelihood of
-    // successfully bootstrapping and joining the network despite seed peer
-    // failures.
-    //
-    // NOTE: We specify seed and known peers separately as seed peers are part
-    // of the static configuration, are generally considered trusted/more stable
-    // and as such are dialed first. Known peers are gathered from the network
-    // and are dialed using their explicit known/verified peer ID for added
-    // security.
-    let known_peers = {
-        // Fetch known peers from the database.
-        let mut db_peers = ctx.get_storage()
-            .get_p2p_peers()
-            .await
-            .inspect_err(|error| {
-                tracing::warn!(%error, "failed to fetch known peers from the database; skipping known peers");
-            })
-            .unwrap_or_default();
-
-        // Sort the peers by last successful dialed time, duration-descending.
-        db_peers.sort_unstable_by(|a, b| b.last_dialed_at.cmp(&a.last_dialed_at));
-
-        // Create a list of known peers, filtering out those that have not been
-        // dialed within the KNOWN_PEER_WINDOW or are already included as seed
-        // addresses.
-        db_peers
-            .into_iter()
-            .filter_map(|peer| {
-                let time_since_last_dialed = OffsetDateTime::now_utc() - *peer.last_dialed_at;
-                let is_seed_addr = config.signer.p2p.seeds.contains(&*peer.address);
-                let is_allowed_peer = ctx
-                    .state()
-                    .current_signer_set()
-                    .is_allowed_peer(&peer.peer_id);
-
-                if time_since_last_dialed > KNOWN_PEER_WINDOW || is_seed_addr || !is_allowed_peer {
-                    None
-                } else {
-                    let peer_id = *peer.peer_id;
-                    let peer_address = (*peer.address).clone();
-                    Some((peer_id, peer_address))
-                }
-            })
-            .take(MAX_KNOWN_PEERS)
-            .collect::<Vec<_>>()
-    };
-
-    // Build the swarm.
-    let mut swarm = SignerSwarmBuilder::new(&config.signer.private_key)
-        .add_listen_endpoints(&ctx.config().signer.p2p.listen_on)
-        .add_seed_addrs(&ctx.config().signer.p2p.seeds)
-        .add_known_peers(&known_peers)
-        .add_external_addresses(&ctx.config().signer.p2p.public_endpoints)
-        .enable_mdns(config.signer.p2p.enable_mdns)
-        .enable_quic_transport(enable_quic)
-        .with_initial_bootstrap_delay(Duration::from_secs(INITIAL_BOOTSTRAP_DELAY_SECS))
-        .with_num_signers(num_signers)
-        .build()?;
-
-    // Start the libp2p swarm. This will run until either the shutdown signal is
-    // received, or an unrecoverable error has occurred.
-    tracing::info!("starting the libp2p swarm");
-    swarm
-        .start(&ctx)
-        .in_current_span()
-        .await
-        .map_err(Error::SignerSwarm)
-}
-
-/// Runs the signer's API server, which includes the Stacks event observer.
-#[tracing::instrument(skip_all, name = "api")]
-async fn run_api(ctx: impl Context + 'static) -> Result<(), Error> {
-    let socket_addr = ctx.config().signer.event_observer.bind;
-    tracing::info!(%socket_addr, "initializing the signer API server");
-
-    let state = ApiState { ctx: ctx.clone() };
-
-    let request_id = Arc::new(AtomicU64::new(0));
-
-    // Build the signer API application
-    let app = api::get_router()
-        .layer(
-            TraceLayer::new_for_http()
-                .make_span_with(|request: &Request<_>| {
-                    tracing::info_span!("api-request",
-                        uri = %request.uri(),
-                        method = %request.method(),
-                        id = tracing::field::Empty,
-                    )
-                })
-                .on_request(move |_: &Request<_>, span: &Span| {
-                    span.record("id", request_id.fetch_add(1, Ordering::SeqCst));
-                    tracing::trace!("processing request");
-                })
-                .on_response(|_: &Response<_>, duration: Duration, _: &Span| {
-                    tracing::trace!(duration_ms = duration.as_millis(), "request completed");
-                }),
-        )
-        .with_state(state);
-
-    // Bind to the configured address and port
-    let listener = tokio::net::TcpListener::bind(socket_addr)
-        .await
-        .expect("failed to bind the signer API to configured address");
-
-    // Get the termination signal handle.
-    let mut term = ctx.get_termination_handle();
-
-    // Run our app with hyper
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            // Listen for an application shutdown signal. We need to loop here
-            // because we may receive other signals (which we will ignore here).
-            term.wait_for_shutdown().await;
-            tracing::info!("stopping the signer API server");
-        })
-        .await
-        .map_err(|error| {
-            tracing::error!(%error, "error running the signer API server");
-            ctx.get_termination_handle().signal_shutdown();
-            error.into()
-        })
-}
-
-/// Run the block observer event-loop.
-async fn run_block_observer(ctx: impl Context) -> Result<(), Error> {
-    let bitcoin_client = ctx.get_bitcoin_client();
-    let chain_tip_polling_interval = ctx.config().bitcoin.chain_tip_polling_interval;
-
-    // Build and start the Bitcoin chain tip poller. This will block until it
-    // can successfully fetch the initial block hash. If ths timeout is exceeded
-    // while waiting for the initial block hash, an error will be returned.
-    let bitcoin_block_source =
-        BitcoinChainTipPoller::start_new(bitcoin_client, chain_tip_polling_interval).await;
-
-    let result = block_observer::BlockObserver::new(ctx, bitcoin_block_source.clone())
-        .run()
-        .await;
-
-    // Once the block observer has finished running, we need to stop the
-    // Bitcoin chain tip poller before returning the result from the observer.
-    bitcoin_block_source.stop();
-    result
-}
-
-/// Run the signer info logger event loop.
-async fn run_signer_info_logger(ctx: impl Context) {
-    SignerInfoLogger::new(ctx, SIGNER_INFO_LOGGER_INTERVAL)
-        .run()
-        .await
-}
-
-/// Run the transaction signer event-loop.
-async fn run_transaction_signer(ctx: impl Context) -> Result<(), Error> {
-    let network = P2PNetwork::new(&ctx);
-
-    transaction_signer::TxSignerEventLoop::new(ctx, network, rand::thread_rng())?
-        .run()
-        .await
-}
-
-/// Run the transaction coordinator event-loop.
-async fn run_transaction_coordinator(ctx: impl Context) -> Result<(), Error> {
-    let config = ctx.config().clone();
-    let private_key = config.signer.private_key;
-    let network = P2PNetwork::new(&ctx);
-
-    let coord = transaction_coordinator::TxCoordinatorEventLoop {
-        network,
-        context: ctx,
-        context_window: config.signer.context_window,
-        private_key,
-        signing_round_max_duration: config.signer.signer_round_max_duration,
-        bitcoin_presign_request_max_duration: config.signer.bitcoin_presign_request_max_duration,
-        threshold: config.signer.bootstrap_signatures_required,
-        dkg_max_duration: config.signer.dkg_max_duration,
-        is_epoch3: false,
-    };
-
-    coord.run().await
-}
-
-/// Run the request decider event-loop.
-async fn run_request_decider(ctx: impl Context) -> Result<(), Error> {
-    let config = ctx.config().clone();
-    let network = P2PNetwork::new(&ctx);
-
-    let decider = RequestDeciderEventLoop {
-        network,
-        context: ctx.clone(),
-        context_window: config.signer.context_window,
-        deposit_decisions_retry_window: config.signer.deposit_decisions_retry_window,
-        withdrawal_decisions_retry_window: config.signer.withdrawal_decisions_retry_window,
-        blocklist_checker: config.blocklist_client.as_ref().map(BlocklistClient::new),
-        signer_private_key: config.signer.private_key,
-    };
-
-    decider.run().await
-}
@@ -1,10 +1,16 @@
 //! Signer message definition for network communication
 
 use secp256k1::ecdsa::RecoverableSignature;
+use sha2::Digest as _;
 
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::validation::TxRequestIds;
+use crate::checkpoint::StateCheckpoint;
+use crate::error::Error;
+use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
+use crate::network::TrafficClass;
+use crate::quorum::Impact;
 use crate::stacks::contracts::ContractCall;
 use crate::stacks::contracts::StacksTx;
 use crate::storage::model;
@@ -38,6 +44,14 @@ pub enum Payload {
     BitcoinPreSignRequest(BitcoinPreSignRequest),
     /// An acknowledgment of a BitconPreSignRequest
     BitcoinPreSignAck(BitcoinPreSignAck),
+    /// An announcement that the sending signer is rotating its identity
+    /// key to a new one.
+    SignerIdentityRotation(SignerIdentityRotation),
+    /// A periodic liveness message carrying the sender's wall clock time.
+    SignerHeartbeat(SignerHeartbeat),
+    /// An announcement that the sending signer is the coordinator for the
+    /// tenure at a bitcoin block.
+    CoordinatorAnnouncement(CoordinatorAnnouncement),
 }
 
 impl std::fmt::Display for Payload {
@@ -69,6 +83,9 @@ impl std::fmt::Display for Payload {
             }
             Self::BitcoinPreSignRequest(_) => write!(f, "BitcoinPreSignRequest(..)"),
             Self::BitcoinPreSignAck(_) => write!(f, "BitcoinPreSignAck(..)"),
+            Self::SignerIdentityRotation(_) => write!(f, "SignerIdentityRotation(..)"),
+            Self::SignerHeartbeat(_) => write!(f, "SignerHeartbeat(..)"),
+            Self::CoordinatorAnnouncement(_) => write!(f, "CoordinatorAnnouncement(..)"),
         }
     }
 }
@@ -81,6 +98,49 @@ impl Payload {
             payload: self,
         }
     }
+
+    /// The class of traffic that the payload belongs to.
+    pub fn traffic_class(&self) -> TrafficClass {
+        match self {
+            Self::WstsMessage(msg) => match msg.inner {
+                wsts::net::Message::DkgBegin(_)
+                | wsts::net::Message::DkgEnd(_)
+                | wsts::net::Message::DkgEndBegin(_)
+                | wsts::net::Message::DkgPrivateBegin(_)
+                | wsts::net::Message::DkgPrivateShares(_)
+                | wsts::net::Message::DkgPublicShares(_) => TrafficClass::Dkg,
+                wsts::net::Message::NonceRequest(_)
+                | wsts::net::Message::NonceResponse(_)
+                | wsts::net::Message::SignatureShareRequest(_)
+                | wsts::net::Message::SignatureShareResponse(_) => TrafficClass::Signing,
+            },
+            Self::StacksTransactionSignRequest(_)
+            | Self::StacksTransactionSignature(_)
+            | Self::BitcoinPreSignRequest(_)
+            | Self::BitcoinPreSignAck(_) => TrafficClass::Signing,
+            Self::SignerDepositDecision(_)
+            | Self::SignerWithdrawalDecision(_)
+            | Self::SignerIdentityRotation(_)
+            | Self::SignerHeartbeat(_)
+            | Self::CoordinatorAnnouncement(_) => TrafficClass::Operational,
+        }
+    }
+
+    /// The name of the payload type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SignerDepositDecision(_) => "SignerDepositDecision",
+            Self::SignerWithdrawalDecision(_) => "SignerWithdrawalDecision",
+            Self::StacksTransactionSignRequest(_) => "StacksTransactionSignRequest",
+            Self::StacksTransactionSignature(_) => "StacksTransactionSignature",
+            Self::WstsMessage(_) => "WstsMessage",
+            Self::BitcoinPreSignRequest(_) => "BitcoinPreSignRequest",
+            Self::BitcoinPreSignAck(_) => "BitcoinPreSignAck",
+            Self::SignerIdentityRotation(_) => "SignerIdentityRotation",
+            Self::SignerHeartbeat(_) => "SignerHeartbeat",
+            Self::CoordinatorAnnouncement(_) => "CoordinatorAnnouncement",
+        }
+    }
 }
 
 impl From<SignerDepositDecision> for Payload {
@@ -125,6 +185,24 @@ impl From<BitcoinPreSignAck> for Payload {
     }
 }
 
+impl From<SignerIdentityRotation> for Payload {
+    fn from(value: SignerIdentityRotation) -> Self {
+        Self::SignerIdentityRotation(value)
+    }
+}
+
+impl From<SignerHeartbeat> for Payload {
+    fn from(value: SignerHeartbeat) -> Self {
+        Self::SignerHeartbeat(value)
+    }
+}
+
+impl From<CoordinatorAnnouncement> for Payload {
+    fn from(value: CoordinatorAnnouncement) -> Self {
+        Self::CoordinatorAnnouncement(value)
+    }
+}
+
 /// Represents a decision related to signer deposit
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignerDepositDecision {
@@ -207,6 +285,17 @@ impl StacksTransactionSignRequest {
             StacksTx::SmartContract(_) => "smart-contract-deployment",
         }
     }
+
+    /// The impact of the transaction that is to be signed. Key rotations
+    /// and contract deployments are high-impact.
+    pub fn impact(&self) -> Impact {
+        match &self.contract_tx {
+            StacksTx::ContractCall(ContractCall::RotateKeysV1(_)) | StacksTx::SmartContract(_) => {
+                Impact::High
+            }
+            StacksTx::ContractCall(_) => Impact::Ordinary,
+        }
+    }
 }
 
 /// Represents a signature of a Stacks transaction.
@@ -231,6 +320,16 @@ pub struct BitcoinPreSignRequest {
     /// The total fee amount and the fee rate for the last transaction that
     /// used this UTXO as an input.
     pub last_fees: Option<Fees>,
+    /// The lock time of the transactions in the package. This is a block
+    /// height at or slightly below the bitcoin chain tip, which
+    /// discourages fee sniping, or zero when the `sweep_lock_time`
+    /// setting is off.
+    pub lock_time: bitcoin::absolute::LockTime,
+    /// The donation UTXOs locked by the signers' aggregate key that are
+    /// spent as extra inputs of the first transaction in the package, to
+    /// top up its fees. This is empty unless the max fees of the requests
+    /// in that transaction are too low for the current fee rates.
+    pub donations: Vec<bitcoin::OutPoint>,
 }
 
 impl std::fmt::Display for BitcoinPreSignRequest {
@@ -244,8 +343,8 @@ impl std::fmt::Display for BitcoinPreSignRequest {
         }
         write!(
             f,
-            "], fee_rate={}, last_fees={:?})",
-            self.fee_rate, self.last_fees
+            "], fee_rate={}, last_fees={:?}, lock_time={}, donations={:?})",
+            self.fee_rate, self.last_fees, self.lock_time, self.donations
         )
     }
 }
@@ -254,6 +353,178 @@ impl std::fmt::Display for BitcoinPreSignRequest {
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct BitcoinPreSignAck;
 
+/// An announcement from a signer that it is replacing its identity
+/// keypair with a new one.
+///
+/// The announcement is sent in a [`Signed`](crate::ecdsa::Signed) message,
+/// so it is signed by the old key. The `new_key_signature` field proves
+/// that the sender also controls the private key of `new_public_key`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignerIdentityRotation {
+    /// The public key that the signer will use going forward.
+    pub new_public_key: PublicKey,
+    /// A signature, created with the new private key, over the digest
+    /// returned by [`SignerIdentityRotation::digest`].
+    pub new_key_signature: secp256k1::ecdsa::Signature,
+}
+
+impl SignerIdentityRotation {
+    /// Create a new rotation announcement for the signer with the given
+    /// current public key, proving possession of the new private key.
+    pub fn new(old_public_key: PublicKey, new_private_key: &PrivateKey) -> Self {
+        let new_public_key = PublicKey::from_private_key(new_private_key);
+        let digest = Self::digest(&old_public_key, &new_public_key);
+        let msg = secp256k1::Message::from_digest(digest);
+
+        Self {
+            new_public_key,
+            new_key_signature: new_private_key.sign_ecdsa(&msg),
+        }
+    }
+
+    /// The digest that the new private key signs over. It binds the new
+    /// key to the old one so that the proof cannot be replayed for a
+    /// different signer.
+    pub fn digest(old_public_key: &PublicKey, new_public_key: &PublicKey) -> [u8; 32] {
+        let mut hasher = sha2::Sha256::new_with_prefix(Self::DOMAIN_TAG);
+        hasher.update(old_public_key.serialize());
+        hasher.update(new_public_key.serialize());
+        hasher.finalize().into()
+    }
+
+    /// Verify that the new key signature was created by the private key
+    /// of `new_public_key` for a rotation away from `old_public_key`.
+    pub fn verify(&self, old_public_key: &PublicKey) -> Result<(), Error> {
+        let digest = Self::digest(old_public_key, &self.new_public_key);
+        let msg = secp256k1::Message::from_digest(digest);
+
+        self.new_key_signature
+            .verify(&msg, &self.new_public_key)
+            .map_err(Error::InvalidEcdsaSignature)
+    }
+
+    /// The domain separation tag used when constructing the digest.
+    const DOMAIN_TAG: &'static [u8] = b"SBTC_SIGNER_IDENTITY_ROTATION";
+}
+
+/// A periodic liveness message that carries the sender's wall clock
+/// time, the fingerprint of its consensus-critical configuration, a
+/// checkpoint of the state that it has processed, and whether it is in
+/// maintenance mode.
+///
+/// Receivers compare the timestamp against their own clock to estimate
+/// the clock skew between themselves and the sender. The estimate also
+/// includes the time that the message spent in transit, so small values
+/// are not meaningful. Receivers also compare the fingerprint against
+/// their own, since signers with different validation policies will
+/// disagree on which requests to sign, and verify the checkpoint against
+/// their own database. A signer in maintenance mode will not vote, sign
+/// or coordinate, so the other signers should not wait on it. Signers
+/// that run the serialization audit also compare its digest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SignerHeartbeat {
+    /// The sender's wall clock time when the message was created, in
+    /// milliseconds since the unix epoch.
+    pub unix_timestamp_millis: u64,
+    /// The fingerprint of the sender's consensus-critical configuration,
+    /// see [`ConsensusConfig`](crate::config::fingerprint::ConsensusConfig).
+    /// This is `None` for signers that predate configuration fingerprints.
+    pub config_fingerprint: Option<[u8; 32]>,
+    /// A checkpoint of the state that the sender has processed. This is
+    /// `None` if the sender could not take one, or predates checkpoints.
+    pub checkpoint: Option<StateCheckpoint>,
+    /// Whether the sender is in read-only maintenance mode. Signers that
+    /// predate maintenance mode never are.
+    pub maintenance_mode: bool,
+    /// The digest of the sender's serialization audit, see
+    /// [`serialization_audit`](crate::serialization_audit). This is `None`
+    /// if the sender does not run the audit, or the audit failed.
+    pub serialization_digest: Option<[u8; 32]>,
+}
+
+impl SignerHeartbeat {
+    /// Create a heartbeat carrying the current time, the given
+    /// configuration fingerprint, state checkpoint and serialization audit
+    /// digest, and whether we are in maintenance mode.
+    pub fn now(
+        config_fingerprint: [u8; 32],
+        checkpoint: Option<StateCheckpoint>,
+        maintenance_mode: bool,
+        serialization_digest: Option<[u8; 32]>,
+    ) -> Self {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000;
+        Self {
+            unix_timestamp_millis: u64::try_from(now).unwrap_or_default(),
+            config_fingerprint: Some(config_fingerprint),
+            checkpoint,
+            maintenance_mode,
+            serialization_digest,
+        }
+    }
+
+    /// Estimate how far ahead of our clock the sender's clock is, in
+    /// milliseconds, given the time that we received the heartbeat. A
+    /// negative value means that the sender's clock is behind ours.
+    pub fn clock_skew_millis(&self, received_at: time::OffsetDateTime) -> i64 {
+        let received = received_at.unix_timestamp_nanos() / 1_000_000;
+        let skew = i128::from(self.unix_timestamp_millis) - received;
+        i64::try_from(skew).unwrap_or(if skew < 0 { i64::MIN } else { i64::MAX })
+    }
+}
+
+/// An announcement by a signer that it is the coordinator for the tenure
+/// at a bitcoin block.
+///
+/// Each signer works out the coordinator from its own view of the bitcoin
+/// chain tip, so two signers can both believe that they are coordinating,
+/// say after a network partition heals. Coordinators announce their
+/// tenures so that such conflicts are noticed and settled, see
+/// [`CoordinatorAnnouncement::supersedes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct CoordinatorAnnouncement {
+    /// The hash of the bitcoin block that the tenure is for.
+    pub bitcoin_block_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block that the tenure is for.
+    pub bitcoin_block_height: model::BitcoinBlockHeight,
+}
+
+impl CoordinatorAnnouncement {
+    /// Return whether the tenure in this announcement, made by the
+    /// `coordinator`, wins over the tenure in the `other` announcement,
+    /// made by the `other_coordinator`.
+    ///
+    /// The tenure at the greater block height wins, since that block is
+    /// the one that the rest of the signers will converge on. Ties are
+    /// broken by the smaller block hash, and then by the smaller public
+    /// key, so that every signer reaches the same decision.
+    pub fn supersedes(
+        &self,
+        coordinator: &PublicKey,
+        other: &Self,
+        other_coordinator: &PublicKey,
+    ) -> bool {
+        let rank = |announcement: &Self, coordinator: &PublicKey| {
+            (
+                announcement.bitcoin_block_height,
+                std::cmp::Reverse(announcement.bitcoin_block_hash),
+                std::cmp::Reverse(*coordinator),
+            )
+        };
+        rank(self, coordinator) > rank(other, other_coordinator)
+    }
+}
+
+impl From<&model::BitcoinBlockRef> for CoordinatorAnnouncement {
+    fn from(value: &model::BitcoinBlockRef) -> Self {
+        Self {
+            bitcoin_block_hash: value.block_hash,
+            bitcoin_block_height: value.block_height,
+        }
+    }
+}
+
 /// The identifier for a WSTS message.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WstsMessageId {
@@ -336,6 +607,9 @@ mod tests {
     #[test_case(PhantomData::<StacksTransactionSignature> ; "StacksTransactionSignature")]
     #[test_case(PhantomData::<WstsMessage> ; "WstsMessage")]
     #[test_case(PhantomData::<BitcoinPreSignRequest> ; "BitcoinPreSignRequest")]
+    #[test_case(PhantomData::<SignerIdentityRotation> ; "SignerIdentityRotation")]
+    #[test_case(PhantomData::<SignerHeartbeat> ; "SignerHeartbeat")]
+    #[test_case(PhantomData::<CoordinatorAnnouncement> ; "CoordinatorAnnouncement")]
     fn signer_messages_should_be_signable_with_type<P>(_: PhantomData<P>)
     where
         P: fake::Dummy<fake::Faker> + Into<Payload>,
@@ -355,6 +629,9 @@ mod tests {
     #[test_case(PhantomData::<StacksTransactionSignature> ; "StacksTransactionSignature")]
     #[test_case(PhantomData::<WstsMessage> ; "WstsMessage")]
     #[test_case(PhantomData::<BitcoinPreSignRequest> ; "BitcoinPreSignRequest")]
+    #[test_case(PhantomData::<SignerIdentityRotation> ; "SignerIdentityRotation")]
+    #[test_case(PhantomData::<SignerHeartbeat> ; "SignerHeartbeat")]
+    #[test_case(PhantomData::<CoordinatorAnnouncement> ; "CoordinatorAnnouncement")]
     fn signer_messages_should_be_encodable_with_type<P>(_: PhantomData<P>)
     where
         P: fake::Dummy<fake::Faker> + Into<Payload>,
@@ -372,4 +649,87 @@ mod tests {
 
         assert_eq!(decoded, signed_message);
     }
+
+    #[test]
+    fn signer_identity_rotation_verifies_only_for_old_key() {
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(7);
+        let old_private_key = PrivateKey::new(rng);
+        let new_private_key = PrivateKey::new(rng);
+        let old_public_key = PublicKey::from_private_key(&old_private_key);
+
+        let rotation = SignerIdentityRotation::new(old_public_key, &new_private_key);
+
+        assert_eq!(
+            rotation.new_public_key,
+            PublicKey::from_private_key(&new_private_key)
+        );
+        assert!(rotation.verify(&old_public_key).is_ok());
+
+        let other_public_key = PublicKey::from_private_key(&PrivateKey::new(rng));
+        assert!(rotation.verify(&other_public_key).is_err());
+    }
+
+    #[test]
+    fn signer_heartbeat_clock_skew() {
+        let received_at = time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let received_millis = 1_700_000_000_000;
+
+        let ahead = SignerHeartbeat {
+            unix_timestamp_millis: received_millis + 2_500,
+            config_fingerprint: None,
+            checkpoint: None,
+            maintenance_mode: false,
+            serialization_digest: None,
+        };
+        assert_eq!(ahead.clock_skew_millis(received_at), 2_500);
+
+        let behind = SignerHeartbeat {
+            unix_timestamp_millis: received_millis - 700,
+            config_fingerprint: None,
+            checkpoint: None,
+            maintenance_mode: false,
+            serialization_digest: None,
+        };
+        assert_eq!(behind.clock_skew_millis(received_at), -700);
+
+        let far_ahead = SignerHeartbeat {
+            unix_timestamp_millis: u64::MAX,
+            config_fingerprint: None,
+            checkpoint: None,
+            maintenance_mode: false,
+            serialization_digest: None,
+        };
+        assert_eq!(far_ahead.clock_skew_millis(received_at), i64::MAX);
+    }
+
+    #[test]
+    fn coordinator_announcements_are_ranked_deterministically() {
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(7);
+        let key1 = PublicKey::from_private_key(&PrivateKey::new(rng));
+        let key2 = PublicKey::from_private_key(&PrivateKey::new(rng));
+        let (low_key, high_key) = (key1.min(key2), key1.max(key2));
+
+        let announcement = |hash: u8, height: u64| CoordinatorAnnouncement {
+            bitcoin_block_hash: BitcoinBlockHash::from([hash; 32]),
+            bitcoin_block_height: height.into(),
+        };
+
+        // The greater block height wins, whatever the keys.
+        let ahead = announcement(9, 101);
+        let behind = announcement(1, 100);
+        assert!(ahead.supersedes(&high_key, &behind, &low_key));
+        assert!(!behind.supersedes(&low_key, &ahead, &high_key));
+
+        // At the same height the smaller block hash wins.
+        let small = announcement(1, 100);
+        let large = announcement(2, 100);
+        assert!(small.supersedes(&high_key, &large, &low_key));
+        assert!(!large.supersedes(&low_key, &small, &high_key));
+
+        // For the same block the smaller public key wins, and an
+        // announcement never supersedes itself.
+        assert!(small.supersedes(&low_key, &small, &high_key));
+        assert!(!small.supersedes(&high_key, &small, &low_key));
+        assert!(!small.supersedes(&low_key, &small, &low_key));
+    }
 }
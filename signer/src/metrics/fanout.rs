@@ -0,0 +1,112 @@
+//! A recorder that forwards metrics to several other recorders, so that
+//! more than one metrics backend can be enabled at the same time.
+
+use std::sync::Arc;
+
+use metrics::Counter;
+use metrics::CounterFn;
+use metrics::Gauge;
+use metrics::GaugeFn;
+use metrics::Histogram;
+use metrics::HistogramFn;
+use metrics::Key;
+use metrics::KeyName;
+use metrics::Metadata;
+use metrics::Recorder;
+use metrics::SharedString;
+use metrics::Unit;
+
+/// A [`Recorder`] that forwards every metric to each of its recorders.
+pub struct FanoutRecorder {
+    recorders: Vec<Box<dyn Recorder + Send + Sync>>,
+}
+
+impl FanoutRecorder {
+    /// Create a recorder that forwards metrics to the given recorders.
+    pub fn new(recorders: Vec<Box<dyn Recorder + Send + Sync>>) -> Self {
+        Self { recorders }
+    }
+}
+
+struct FanoutCounter(Vec<Counter>);
+
+impl CounterFn for FanoutCounter {
+    fn increment(&self, value: u64) {
+        self.0.iter().for_each(|counter| counter.increment(value));
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.iter().for_each(|counter| counter.absolute(value));
+    }
+}
+
+struct FanoutGauge(Vec<Gauge>);
+
+impl GaugeFn for FanoutGauge {
+    fn increment(&self, value: f64) {
+        self.0.iter().for_each(|gauge| gauge.increment(value));
+    }
+
+    fn decrement(&self, value: f64) {
+        self.0.iter().for_each(|gauge| gauge.decrement(value));
+    }
+
+    fn set(&self, value: f64) {
+        self.0.iter().for_each(|gauge| gauge.set(value));
+    }
+}
+
+struct FanoutHistogram(Vec<Histogram>);
+
+impl HistogramFn for FanoutHistogram {
+    fn record(&self, value: f64) {
+        self.0.iter().for_each(|histogram| histogram.record(value));
+    }
+}
+
+impl Recorder for FanoutRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        for recorder in &self.recorders {
+            recorder.describe_counter(key.clone(), unit, description.clone());
+        }
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        for recorder in &self.recorders {
+            recorder.describe_gauge(key.clone(), unit, description.clone());
+        }
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        for recorder in &self.recorders {
+            recorder.describe_histogram(key.clone(), unit, description.clone());
+        }
+    }
+
+    fn register_counter(&self, key: &Key, metadata: &Metadata<'_>) -> Counter {
+        let counters = self
+            .recorders
+            .iter()
+            .map(|recorder| recorder.register_counter(key, metadata))
+            .collect();
+        Counter::from_arc(Arc::new(FanoutCounter(counters)))
+    }
+
+    fn register_gauge(&self, key: &Key, metadata: &Metadata<'_>) -> Gauge {
+        let gauges = self
+            .recorders
+            .iter()
+            .map(|recorder| recorder.register_gauge(key, metadata))
+            .collect();
+        Gauge::from_arc(Arc::new(FanoutGauge(gauges)))
+    }
+
+    fn register_histogram(&self, key: &Key, metadata: &Metadata<'_>) -> Histogram {
+        let histograms = self
+            .recorders
+            .iter()
+            .map(|recorder| recorder.register_histogram(key, metadata))
+            .collect();
+        Histogram::from_arc(Arc::new(FanoutHistogram(histograms)))
+    }
+}
@@ -0,0 +1,850 @@
+//! A module for setting up metrics in the APP
+//!
+//! Metrics are recorded through the [`Metrics`] enum and the macros of the
+//! `metrics` crate, and they are exported by whichever backends are
+//! enabled in the config: a Prometheus scrape endpoint, a StatsD server
+//! and an OpenTelemetry collector, in any combination.
+
+pub mod fanout;
+pub mod otlp;
+pub mod statsd;
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use metrics::Recorder;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_exporter_prometheus::PrometheusHandle;
+use reqwest::Response;
+use sbtc::events::EventError;
+use sbtc::events::RegistryEvent;
+use url::Url;
+
+use crate::config::SignerConfig;
+
+use crate::bitcoin::validation::InputValidationResult;
+use crate::bitcoin::validation::WithdrawalValidationResult;
+use crate::block_observer::Deposit;
+use crate::error::Error;
+use crate::integrity::TableIntegrityReport;
+use crate::keys::PublicKey;
+use crate::message::StacksTransactionSignRequest;
+use crate::stacks::api::ClarityName;
+use crate::stacks::contracts::SmartContract;
+use crate::storage::model::BitcoinTxSigHash;
+use crate::storage::model::BitcoinWithdrawalOutput;
+use crate::storage::model::DepositRequestSource;
+use crate::storage::model::StacksPrincipal;
+use crate::transaction_signer::AcceptedSigHash;
+
+/// A request of an HTTP client that is in flight. The gauge of in-flight
+/// requests to the host of the request is incremented when this is created
+/// and decremented when it is dropped.
+#[derive(Debug)]
+pub struct InFlightRequest {
+    host: String,
+}
+
+impl InFlightRequest {
+    /// Record the start of a request to the given endpoint.
+    pub fn new(endpoint: &Url) -> Self {
+        let host = endpoint.host_str().unwrap_or_default().to_string();
+        metrics::gauge!(Metrics::HttpRequestsInFlight, "host" => host.clone()).increment(1.0);
+        Self { host }
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        metrics::gauge!(Metrics::HttpRequestsInFlight, "host" => self.host.clone()).decrement(1.0);
+    }
+}
+
+/// The buckets used for metric histograms
+const METRIC_BUCKETS: [f64; 9] = [1e-4, 1e-3, 1e-2, 0.1, 0.5, 1.0, 5.0, 20.0, f64::INFINITY];
+
+/// The quantiles to use when rendering histograms
+const METRIC_QUANTILES: [f64; 8] = [0.0, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99, 1.0];
+
+/// All metrics captured in this crate
+#[derive(strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Metrics {
+    /// The metric for the total number of submitted transactions.
+    TransactionsSubmittedTotal,
+    /// The metric for the total number of deposit requests that have been
+    /// swept.
+    DepositsSweptTotal,
+    /// The metric for the total number of observed bitcoin or stacks
+    /// blocks. We use a label to distinguish ¡between the two. Note that
+    /// this only includes bitcoin blocks observed over the ZeroMQ
+    /// interface and stacks blocks observed from the event observer.
+    BlocksObservedTotal,
+    /// The number of deposit requests processed by the signer. This
+    /// includes duplicates. We use a label to distinguish between the
+    /// intake sources of the deposit requests.
+    DepositRequestsTotal,
+    /// The total number of signing rounds that have completed
+    /// successfully. This includes WSTS and "regular" multi-sig signing
+    /// rounds on stacks. We use a label to distinguish between the two.
+    SigningRoundsCompletedTotal,
+    /// The total number of tenures that this signer has served as
+    /// coordinator.
+    CoordinatorTenuresTotal,
+    /// The total number of sign requests received from the signer.
+    SignRequestsTotal,
+    /// The amount of time it took to complete a signing round in seconds.
+    /// This includes WSTS and "regular" multi-sig signing rounds on
+    /// stacks. We use a label to distinguish between the two.
+    SigningRoundDurationSeconds,
+    /// The amount of time, in seconds for running bitcoin or stacks
+    /// validation.
+    ValidationDurationSeconds,
+    /// The number of peers connected in the p2p network.
+    PeersConnected,
+    /// The amount of time, in seconds, it took for a call-read request to
+    /// return from the stacks node.
+    CallReadOnlyDurationSeconds,
+    /// Records total number of times that a call-read request has been
+    /// made to the stacks node.
+    CallReadOnlyRequestsTotal,
+    /// The amount of time, in seconds, it took for to read a data variable
+    /// in a smart contract by making a request to the stacks node.
+    ReadDataVarDurationSeconds,
+    /// Records the total number of times that request to read a data
+    /// variable has been made to the stacks node.
+    ReadDataVarRequestsTotal,
+    /// The amount of time, in seconds, it took read a map entry in a smart
+    /// contract by making a request to the stacks node.
+    ReadMapEntryDurationSeconds,
+    /// The total number of times that a request to read a map entry in a
+    /// smart contract has been made to the stacks node.
+    ReadMapEntryRequestsTotal,
+    /// The total number of webhook notifications delivered, or given up
+    /// on, per subscriber. We use a label to distinguish between the two.
+    WebhookDeliveriesTotal,
+    /// The number of protected database rows that failed the most recent
+    /// integrity verification, per table. We use a label to distinguish
+    /// between modified, unhashed and deleted rows.
+    IntegrityViolations,
+    /// Whether the bitcoin anchor of the stacks node's chain tip was an
+    /// ancestor of our bitcoin chain tip at the most recent chain
+    /// consistency check, as 1 or 0.
+    ChainViewsConsistent,
+    /// The total fees, in microSTX, paid for stacks transactions submitted
+    /// by this signer over the current fee budget period.
+    StacksFeesSpentMicroStx,
+    /// The total number of stacks transactions that were not submitted
+    /// because the fee budget was exhausted, labeled by transaction kind.
+    StacksFeeBudgetRefusalsTotal,
+    /// The unlocked balance, in microSTX, of the signers' STX address at
+    /// the most recent balance check.
+    StxBalanceMicroStx,
+    /// The projected number of seconds until the signers' STX balance is
+    /// spent, given the fees paid over the current fee budget period.
+    StxRunwaySeconds,
+    /// The amount, in satoshis, locked in the signers' UTXO at the most
+    /// recent balance check.
+    SignersBtcBalanceSats,
+    /// The time, in seconds, that a signer took to respond to a request
+    /// of this signer while it was the coordinator. We use labels to
+    /// distinguish between signers and kinds of responses.
+    SignerResponseLatencySeconds,
+    /// The total number of completed-deposit events observed for deposits
+    /// that had already been completed on another stacks fork.
+    DuplicateCompletedDepositsTotal,
+    /// The total number of heartbeats received from signers whose
+    /// consensus-critical configuration differs from ours, labeled by
+    /// the public key of the sender.
+    ConfigFingerprintMismatchesTotal,
+    /// The total number of gossip messages, labeled by the traffic class
+    /// of their topic and by whether they were published, failed to be
+    /// published, or were received.
+    GossipMessagesTotal,
+    /// The total number of state checkpoints received from signers that
+    /// differ from the state in our database, labeled by the public key
+    /// of the sender.
+    StateCheckpointMismatchesTotal,
+    /// The total number of conflicts with the tenure of a competing
+    /// coordinator, labeled by whether our tenure won or lost.
+    CoordinatorConflictsTotal,
+    /// The total number of stacks blocks whose events were still being
+    /// handled when the latency budget of the `/new_block` endpoint ran
+    /// out.
+    NewBlockLatencyBudgetExceededTotal,
+    /// The total number of sweep transactions that were orphaned by a
+    /// bitcoin reorg, labeled by the outcome of rebroadcasting them.
+    OrphanedSweepTransactionsTotal,
+    /// The number of sweep transactions that were orphaned by a bitcoin
+    /// reorg and are not confirmed on the canonical bitcoin blockchain,
+    /// as of the most recent check.
+    OrphanedSweepTransactions,
+    /// The total number of deposit requests that were marked as stale,
+    /// labeled by the reason.
+    StaleDepositRequestsTotal,
+    /// The total number of deposit and withdrawal requests in sweep
+    /// transactions that failed validation, labeled by the kind of
+    /// request and the reason that validation failed.
+    SweepRequestRejectionsTotal,
+    /// The total number of sbtc-registry events that could not be decoded
+    /// from their clarity values, labeled by the reason and the registry
+    /// contract that emitted them.
+    RegistryEventDecodeFailuresTotal,
+    /// The total number of sbtc-registry events observed, labeled by the
+    /// registry contract that emitted them and their topic. This shows
+    /// when events stop coming from an old deployment of the contracts and
+    /// start coming from the new one.
+    RegistryEventsTotal,
+    /// The number of canonical sweep transactions that were confirmed more
+    /// than the completion deadline ago without any recorded completion on
+    /// stacks.
+    UncompletedSweepTransactions,
+    /// The total number of bitcoin blocks reported by the stacks node
+    /// through the `/new_burn_block` webhook that disagree with the
+    /// bitcoin blocks that we observed, labeled by the reason.
+    BurnBlockDivergencesTotal,
+    /// The total number of events that the stacks node posted to event
+    /// observer endpoints that the signer does not use, labeled by the
+    /// endpoint.
+    IgnoredObserverEventsTotal,
+    /// The total number of stacks tenure changes observed in the blocks
+    /// sent to the event observer, labeled by whether a new tenure started
+    /// or the ongoing one was extended.
+    StacksTenureChangesTotal,
+    /// The total number of bitcoin blocks in which no stacks tenure
+    /// started or was extended.
+    StacksFlashBlocksTotal,
+    /// The total number of periods whose records were exported to the
+    /// analytics bucket, or failed to be. We use a label to distinguish
+    /// between the two.
+    AnalyticsExportsTotal,
+    /// The time, in seconds, from receiving the webhook of a stacks block
+    /// with sbtc events to having written the events to the database.
+    StacksBlockProcessingDurationSeconds,
+    /// The total number of stacks block webhooks that did not extend the
+    /// last block whose webhook we handled. We use a label to distinguish
+    /// between gaps, where webhooks were skipped, and regressions, where
+    /// the block is not higher than the last one.
+    StacksBlockOrderAnomaliesTotal,
+    /// The total number of stacks blocks with calls to the sBTC contracts
+    /// whose webhooks were skipped by the stacks node.
+    StacksBlocksMissedTotal,
+    /// The number of requests of the stacks RPC and Emily API clients that
+    /// are in flight, labeled by the host that they were sent to.
+    HttpRequestsInFlight,
+    /// The total number of bitcoin transactions observed that only moved
+    /// funds between the signers' addresses.
+    BitcoinInternalTransfersTotal,
+    /// The total number of contract events in stacks webhooks that were
+    /// dropped because they did not come from an observed sbtc-registry
+    /// contract, labeled by the contract that emitted them.
+    FilteredContractEventsTotal,
+    /// The total number of heartbeats received from signers whose
+    /// serialization audit digest differs from ours, labeled by the
+    /// public key of the sender.
+    SerializationDigestMismatchesTotal,
+    /// The total number of panics in the long-lived components of the
+    /// signer, labeled by the component that panicked.
+    ComponentPanicsTotal,
+    /// The total number of requests to the signer API that were rejected
+    /// by its limits, labeled by the limit that rejected them.
+    ApiRequestsRejectedTotal,
+    /// Whether the bitcoin node was synced and had built its indexes at
+    /// the most recent warm-up check, as 1 or 0.
+    BitcoinNodeReady,
+}
+
+impl From<Metrics> for metrics::KeyName {
+    fn from(value: Metrics) -> Self {
+        metrics::KeyName::from_const_str(value.into())
+    }
+}
+
+impl From<SmartContract> for metrics::SharedString {
+    fn from(value: SmartContract) -> Self {
+        metrics::SharedString::const_str(value.contract_name())
+    }
+}
+
+impl From<ClarityName> for metrics::SharedString {
+    fn from(value: ClarityName) -> Self {
+        metrics::SharedString::const_str(value.0)
+    }
+}
+
+/// The reason label for the outcome of a fallible operation, which is
+/// "none" when the operation succeeded.
+pub fn reason_label<T>(result: &Result<T, Error>) -> &'static str {
+    result.as_ref().err().map_or("none", Error::reason)
+}
+
+impl Metrics {
+    /// Increment the deposit request counter for incoming deposit
+    /// requests, labeled with the source that they came from and, for
+    /// failures, the reason. Also record the amount of time that it took
+    /// to validate the deposit request.
+    pub fn increment_deposit_total(
+        elapsed: Duration,
+        deposit: &Result<Option<Deposit>, Error>,
+        source: DepositRequestSource,
+    ) {
+        let deposit_status = match deposit {
+            Ok(Some(_)) => "success",
+            Ok(None) => "unconfirmed",
+            Err(_) => "failed",
+        };
+        let source: &'static str = source.into();
+
+        metrics::histogram!(
+            Metrics::ValidationDurationSeconds,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+            "kind" => "deposit-request",
+            "source" => source,
+        )
+        .record(elapsed);
+
+        metrics::counter!(
+            Metrics::DepositRequestsTotal,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+            "status" => deposit_status,
+            "reason" => reason_label(deposit),
+            "source" => source,
+        )
+        .increment(1);
+    }
+
+    /// Record the number of rows of a table that failed the most recent
+    /// integrity verification.
+    pub fn record_integrity_report(report: &TableIntegrityReport) {
+        let violations = [
+            ("modified", report.modified.len()),
+            ("unhashed", report.unhashed.len()),
+            ("deleted", report.deleted.len()),
+        ];
+
+        for (kind, count) in violations {
+            metrics::gauge!(
+                Metrics::IntegrityViolations,
+                "table" => report.table_name,
+                "kind" => kind,
+            )
+            .set(count as f64);
+        }
+    }
+
+    /// Record the outcome of the most recent chain consistency check.
+    pub fn record_chain_views_consistent(consistent: bool) {
+        metrics::gauge!(Metrics::ChainViewsConsistent).set(if consistent { 1.0 } else { 0.0 });
+    }
+
+    /// Record whether the bitcoin node was ready at the most recent
+    /// warm-up check.
+    pub fn record_bitcoin_node_ready(ready: bool) {
+        metrics::gauge!(Metrics::BitcoinNodeReady).set(if ready { 1.0 } else { 0.0 });
+    }
+
+    /// Record the fees paid over the current stacks fee budget period.
+    pub fn record_stacks_fees_spent(spent_ustx: u64) {
+        metrics::gauge!(Metrics::StacksFeesSpentMicroStx).set(spent_ustx as f64);
+    }
+
+    /// Increment the counter of stacks transactions that were refused
+    /// because of the fee budget.
+    pub fn increment_stacks_fee_budget_refusals(tx_kind: &'static str) {
+        metrics::counter!(Metrics::StacksFeeBudgetRefusalsTotal, "kind" => tx_kind).increment(1);
+    }
+
+    /// Record the balance of the signers' STX address and its projected
+    /// runway. The runway gauge is set to infinity when no fees were paid
+    /// over the fee budget period.
+    pub fn record_stx_balance(available_ustx: u128, runway: Option<Duration>) {
+        metrics::gauge!(Metrics::StxBalanceMicroStx).set(available_ustx as f64);
+        let runway = runway.map_or(f64::INFINITY, |runway| runway.as_secs_f64());
+        metrics::gauge!(Metrics::StxRunwaySeconds).set(runway);
+    }
+
+    /// Increment the counter of sweep transactions orphaned by a bitcoin
+    /// reorg, labeled by the outcome of rebroadcasting them.
+    pub fn increment_orphaned_sweeps(outcome: &'static str) {
+        metrics::counter!(Metrics::OrphanedSweepTransactionsTotal, "outcome" => outcome)
+            .increment(1);
+    }
+
+    /// Record the number of sweep transactions that are currently
+    /// orphaned.
+    pub fn record_orphaned_sweeps(count: usize) {
+        metrics::gauge!(Metrics::OrphanedSweepTransactions).set(count as f64);
+    }
+
+    /// Record the number of sweep transactions that are currently past
+    /// their completion deadline without being completed on stacks.
+    pub fn record_uncompleted_sweeps(count: usize) {
+        metrics::gauge!(Metrics::UncompletedSweepTransactions).set(count as f64);
+    }
+
+    /// Increment the counter of bitcoin blocks reported by the stacks node
+    /// that disagree with our view of the bitcoin blockchain, labeled by
+    /// the reason.
+    pub fn increment_burn_block_divergences(reason: &'static str) {
+        metrics::counter!(Metrics::BurnBlockDivergencesTotal, "reason" => reason).increment(1);
+    }
+
+    /// Increment the counter of events posted by the stacks node to an
+    /// event observer endpoint that the signer does not use.
+    pub fn increment_ignored_observer_events(endpoint: &str) {
+        metrics::counter!(Metrics::IgnoredObserverEventsTotal, "endpoint" => endpoint.to_owned())
+            .increment(1);
+    }
+
+    /// Increment the counter of deposit requests marked as stale, labeled
+    /// by the reason.
+    pub fn increment_stale_deposits(reason: &'static str) {
+        metrics::counter!(Metrics::StaleDepositRequestsTotal, "reason" => reason).increment(1);
+    }
+
+    /// Increment the counter of requests in a sweep transaction that
+    /// failed validation, labeled by the reason that they failed.
+    pub fn increment_sweep_request_rejections(
+        deposits: &[BitcoinTxSigHash],
+        withdrawals: &[BitcoinWithdrawalOutput],
+    ) {
+        let deposits = deposits
+            .iter()
+            .map(|row| row.validation_result)
+            .filter(|result| *result != InputValidationResult::Ok)
+            .map(|result| ("deposit", <&'static str>::from(result)));
+        let withdrawals = withdrawals
+            .iter()
+            .map(|row| row.validation_result)
+            .filter(|result| *result != WithdrawalValidationResult::Ok)
+            .map(|result| ("withdrawal", <&'static str>::from(result)));
+
+        for (kind, reason) in deposits.chain(withdrawals) {
+            metrics::counter!(
+                Metrics::SweepRequestRejectionsTotal,
+                "kind" => kind,
+                "reason" => reason,
+            )
+            .increment(1);
+        }
+    }
+
+    /// Increment the counter of sbtc-registry events that were observed
+    /// from the given registry contract.
+    pub fn increment_registry_events(event: &RegistryEvent, registry: &StacksPrincipal) {
+        let topic = match event {
+            RegistryEvent::CompletedDeposit(_) => "completed-deposit",
+            RegistryEvent::WithdrawalAccept(_) => "withdrawal-accept",
+            RegistryEvent::WithdrawalReject(_) => "withdrawal-reject",
+            RegistryEvent::WithdrawalCreate(_) => "withdrawal-create",
+            RegistryEvent::KeyRotation(_) => "key-rotation",
+        };
+        metrics::counter!(
+            Metrics::RegistryEventsTotal,
+            "registry" => registry.to_string(),
+            "topic" => topic,
+        )
+        .increment(1);
+    }
+
+    /// Increment the counter of sbtc-registry events from the given
+    /// registry contract that could not be decoded, labeled by the reason.
+    pub fn increment_registry_event_decode_failures(
+        error: &EventError,
+        registry: &StacksPrincipal,
+    ) {
+        let reason = match error {
+            EventError::ClarityIntConversion(_) => "clarity-int-conversion",
+            EventError::ClaritySliceConversion(_) => "clarity-slice-conversion",
+            EventError::ClarityStringConversion(_) => "clarity-string-conversion",
+            EventError::ClarityHashByteLength(_) => "clarity-hash-byte-length",
+            EventError::ClarityPublicKeyConversion(_) => "clarity-public-key-conversion",
+            EventError::ClarityUnexpectedEventTopic(_) => "clarity-unexpected-event-topic",
+            EventError::ClarityUnexpectedValue(..) => "clarity-unexpected-value",
+            EventError::InvalidWitnessProgram(_) => "invalid-witness-program",
+            EventError::TupleEventField(..) => "tuple-event-field",
+            EventError::UnhandledRecipient(..) => "unhandled-recipient",
+        };
+        metrics::counter!(
+            Metrics::RegistryEventDecodeFailuresTotal,
+            "reason" => reason,
+            "registry" => registry.to_string(),
+        )
+        .increment(1);
+    }
+
+    /// Increment the counter of deposits completed on more than one
+    /// stacks fork.
+    pub fn increment_duplicate_completed_deposits() {
+        metrics::counter!(Metrics::DuplicateCompletedDepositsTotal).increment(1);
+    }
+
+    /// Increment the counter of heartbeats from the given signer that
+    /// carried a configuration fingerprint different from ours.
+    pub fn increment_config_fingerprint_mismatches(signer_public_key: &PublicKey) {
+        metrics::counter!(
+            Metrics::ConfigFingerprintMismatchesTotal,
+            "signer" => signer_public_key.to_string()
+        )
+        .increment(1);
+    }
+
+    /// Increment the counter of heartbeats from the given signer that
+    /// carried a serialization audit digest different from ours.
+    pub fn increment_serialization_digest_mismatches(signer_public_key: &PublicKey) {
+        metrics::counter!(
+            Metrics::SerializationDigestMismatchesTotal,
+            "signer" => signer_public_key.to_string()
+        )
+        .increment(1);
+    }
+
+    /// Increment the counter of requests to the signer API that were
+    /// rejected by the given limit.
+    pub fn increment_api_requests_rejected(limit: &'static str) {
+        metrics::counter!(Metrics::ApiRequestsRejectedTotal, "limit" => limit).increment(1);
+    }
+
+    /// Increment the counter of panics in the given component.
+    pub fn increment_component_panics(component: &'static str) {
+        metrics::counter!(Metrics::ComponentPanicsTotal, "component" => component).increment(1);
+    }
+
+    /// Increment the counter of state checkpoints from the given signer
+    /// that differ from the state in our database.
+    pub fn increment_state_checkpoint_mismatches(signer_public_key: &PublicKey) {
+        metrics::counter!(
+            Metrics::StateCheckpointMismatchesTotal,
+            "signer" => signer_public_key.to_string()
+        )
+        .increment(1);
+    }
+
+    /// Increment the counter of conflicts between our coordinator tenure
+    /// and the tenure of a competing coordinator.
+    pub fn increment_coordinator_conflicts(outcome: &'static str) {
+        metrics::counter!(Metrics::CoordinatorConflictsTotal, "outcome" => outcome).increment(1);
+    }
+
+    /// Increment the counter of gossip messages on the topic of the given
+    /// traffic class.
+    pub fn increment_gossip_messages(class: &'static str, direction: &'static str) {
+        metrics::counter!(
+            Metrics::GossipMessagesTotal,
+            "topic" => class,
+            "direction" => direction
+        )
+        .increment(1);
+    }
+
+    /// Record the amount locked in the signers' UTXO.
+    pub fn record_signers_btc_balance(amount: u64) {
+        metrics::gauge!(Metrics::SignersBtcBalanceSats).set(amount as f64);
+    }
+
+    /// Record how long it took to handle the events of a stacks block.
+    pub fn record_stacks_block_processing_duration(elapsed: Duration) {
+        metrics::histogram!(Metrics::StacksBlockProcessingDurationSeconds).record(elapsed);
+    }
+
+    /// Record how long a signer took to respond to a request of ours.
+    pub fn record_signer_response_latency(
+        signer_public_key: &PublicKey,
+        kind: &'static str,
+        latency: Duration,
+    ) {
+        metrics::histogram!(
+            Metrics::SignerResponseLatencySeconds,
+            "signer" => signer_public_key.to_string(),
+            "kind" => kind,
+        )
+        .record(latency);
+    }
+
+    /// Increment the gauge for the number of connected peers
+    pub fn increment_peers_connected_total() {
+        metrics::gauge!(Metrics::PeersConnected).increment(1.0);
+    }
+
+    /// Decrement the gauge for the number of connected peers
+    pub fn decrement_peers_connected_total() {
+        metrics::gauge!(Metrics::PeersConnected).decrement(1.0);
+    }
+
+    /// Increment number of presign requests that were processed noting
+    /// whether the presign validation finished successfully. Also record
+    /// the amount of time that it took to run the validation.
+    ///
+    /// # Notes
+    ///
+    /// If the presign validation result is Ok, just means that the request
+    /// itself included deposits and withdrawals that we know about and
+    /// that there were no duplicates with the request. There could still
+    /// be deposits and withdrawals that failed validation.
+    pub fn increment_presign_validation(elapsed: Duration, presign_result: &Result<(), Error>) {
+        let status = if presign_result.is_ok() {
+            "success"
+        } else {
+            "failure"
+        };
+        metrics::histogram!(
+            Metrics::ValidationDurationSeconds,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+            "kind" => "sweep-presign",
+            "status" => status,
+        )
+        .record(elapsed);
+
+        metrics::counter!(
+            Metrics::SignRequestsTotal,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+            "kind" => "sweep-presign",
+            "status" => status,
+            "reason" => reason_label(presign_result),
+        )
+        .increment(1);
+    }
+
+    /// Increment the result of a request to sign stacks transaction after
+    /// validation has run, labeled with the reason for failures. Also note
+    /// the time it took to run validation.
+    pub fn increment_stacks_validation(
+        elapsed: Duration,
+        request: &StacksTransactionSignRequest,
+        validation_result: &Result<(), Error>,
+    ) {
+        metrics::histogram!(
+            Metrics::ValidationDurationSeconds,
+            "blockchain" => STACKS_BLOCKCHAIN,
+            "kind" => request.tx_kind(),
+        )
+        .record(elapsed);
+        metrics::counter!(
+            Metrics::SignRequestsTotal,
+            "blockchain" => STACKS_BLOCKCHAIN,
+            "kind" => request.tx_kind(),
+            "status" => if validation_result.is_ok() { "success" } else { "failed" },
+            "reason" => reason_label(validation_result),
+        )
+        .increment(1);
+    }
+
+    /// Increment the result of a request to sign a particular sighash of a
+    /// bitcoin transaction.
+    pub fn increment_bitcoin_validation(sighash_result: &Result<AcceptedSigHash, Error>) {
+        let validation_status = match &sighash_result {
+            Ok(_) => "success",
+            Err(Error::SigHashConversion(_)) => "improper-sighash",
+            Err(Error::UnknownSigHash(_)) => "unknown-sighash",
+            Err(Error::InvalidSigHash(_)) => "invalid-sighash",
+            Err(_) => "unexpected-failure",
+        };
+
+        metrics::counter!(
+            Metrics::SignRequestsTotal,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+            "kind" => "sweep",
+            "status" => validation_status,
+            "reason" => reason_label(sighash_result),
+        )
+        .increment(1);
+    }
+
+    /// Record the amount of time it took to complete a
+    /// /v2/contracts/call-read request from the stacks node.
+    pub fn record_call_read(
+        elapsed: Duration,
+        contract_name: SmartContract,
+        function_name: ClarityName,
+        response: &Response,
+    ) {
+        metrics::histogram!(
+            Metrics::CallReadOnlyDurationSeconds,
+            "contract_name" => contract_name,
+            "function_name" => function_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .record(elapsed);
+
+        metrics::counter!(
+            Metrics::CallReadOnlyRequestsTotal,
+            "contract_name" => contract_name,
+            "function_name" => function_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .increment(1);
+    }
+
+    /// Record the amount of time it took to complete a /v2/data_var
+    /// request from the stacks node.
+    pub fn record_data_var(
+        elapsed: Duration,
+        contract_name: SmartContract,
+        variable_name: ClarityName,
+        response: &Response,
+    ) {
+        metrics::histogram!(
+            Metrics::ReadDataVarDurationSeconds,
+            "contract_name" => contract_name,
+            "variable_name" => variable_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .record(elapsed);
+
+        metrics::counter!(
+            Metrics::ReadDataVarRequestsTotal,
+            "contract_name" => contract_name,
+            "variable_name" => variable_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .increment(1);
+    }
+
+    /// Record the amount of time it took to complete a /v2/map_entry
+    /// request from the stacks node.
+    pub fn record_map_entry(
+        elapsed: Duration,
+        contract_name: SmartContract,
+        map_name: ClarityName,
+        response: &Response,
+    ) {
+        metrics::histogram!(
+            Metrics::ReadMapEntryDurationSeconds,
+            "contract_name" => contract_name,
+            "map_name" => map_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .record(elapsed);
+
+        metrics::counter!(
+            Metrics::ReadMapEntryRequestsTotal,
+            "contract_name" => contract_name,
+            "map_name" => map_name,
+            "status_code" => response.status().as_u16().to_string(),
+            "blockchain" => STACKS_BLOCKCHAIN,
+        )
+        .increment(1);
+    }
+}
+
+/// Label for bitcoin blockchain based metrics
+pub const BITCOIN_BLOCKCHAIN: &str = "bitcoin";
+
+/// Label for stacks blockchain based metrics.
+pub const STACKS_BLOCKCHAIN: &str = "stacks";
+
+/// How often the histograms of the prometheus exporter are cleaned up.
+const PROMETHEUS_UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The handle for rendering the metrics of the prometheus recorder, set
+/// when the signer API is configured to serve them.
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Return the handle for rendering the metrics for Prometheus on the
+/// signer API, if [`setup_metrics`] installed one.
+pub fn prometheus_handle() -> Option<&'static PrometheusHandle> {
+    PROMETHEUS_HANDLE.get()
+}
+
+/// Set up the metrics backends that are enabled in the config. This must
+/// be called from within a tokio runtime.
+pub fn setup_metrics(config: &SignerConfig) {
+    let mut recorders: Vec<Box<dyn Recorder + Send + Sync>> = Vec::new();
+
+    if config.prometheus_exporter_endpoint.is_some() || config.prometheus_api_metrics {
+        let builder = PrometheusBuilder::new()
+            .add_global_label("app", crate::PACKAGE_NAME)
+            .set_buckets(&METRIC_BUCKETS)
+            .expect("received an empty slice of metric buckets")
+            .set_quantiles(&METRIC_QUANTILES)
+            .expect("received an empty slice of metric quantiles");
+
+        // Without a scrape endpoint the metrics are only rendered by the
+        // signer API, so there is no exporter to run.
+        let recorder = match config.prometheus_exporter_endpoint {
+            Some(addr) => {
+                let (recorder, exporter) = builder
+                    .with_http_listener(addr)
+                    .build()
+                    .expect("could not build the prometheus server");
+                tokio::spawn(exporter);
+                recorder
+            }
+            None => builder.build_recorder(),
+        };
+
+        let handle = recorder.handle();
+        if config.prometheus_api_metrics {
+            let _ = PROMETHEUS_HANDLE.set(handle.clone());
+        }
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PROMETHEUS_UPKEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                handle.run_upkeep();
+            }
+        });
+        recorders.push(Box::new(recorder));
+    }
+
+    if let Some(endpoint) = config.statsd_endpoint.as_deref() {
+        let recorder =
+            statsd::StatsdRecorder::new(endpoint).expect("could not set up the statsd exporter");
+        recorders.push(Box::new(recorder));
+    }
+
+    if let Some(endpoint) = config.otlp_metrics_endpoint.clone() {
+        let recorder = otlp::OtlpRecorder::new(endpoint);
+        tokio::spawn(recorder.clone().run());
+        recorders.push(Box::new(recorder));
+    }
+
+    if !recorders.is_empty()
+        && metrics::set_global_recorder(fanout::FanoutRecorder::new(recorders)).is_err()
+    {
+        panic!("could not install the metrics recorder");
+    }
+
+    metrics::gauge!(
+        "build_info",
+        "rust_version" => crate::RUSTC_VERSION,
+        "revision" => crate::GIT_COMMIT,
+        "arch" => crate::TARGET_ARCH,
+        "env_abi" => crate::TARGET_ENV_ABI,
+    )
+    .set(1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::bitcoin::validation::BitcoinTxContext;
+    use crate::storage::model::BitcoinBlockHeight;
+
+    use super::*;
+
+    #[test]
+    fn reason_labels_use_the_specific_cause() {
+        let ctx = BitcoinTxContext {
+            chain_tip: Faker.fake(),
+            chain_tip_height: BitcoinBlockHeight::default(),
+            signer_public_key: Faker.fake(),
+            aggregate_key: Faker.fake(),
+        };
+
+        let error = WithdrawalValidationResult::FeeTooHigh.into_error(&ctx);
+        assert_eq!(reason_label::<()>(&Err(error)), "fee-too-high");
+        assert_eq!(
+            reason_label::<()>(&Err(Error::NoDkgShares)),
+            "no-dkg-shares"
+        );
+        assert_eq!(reason_label(&Ok::<_, Error>(())), "none");
+    }
+}
@@ -0,0 +1,296 @@
+//! # OTLP metrics
+//!
+//! The [`OtlpRecorder`] aggregates metrics in memory and periodically
+//! pushes them to an OpenTelemetry collector over OTLP/HTTP, using its
+//! JSON encoding. Counters are exported as cumulative monotonic sums,
+//! gauges as gauges, and histograms as cumulative histograms with the same
+//! buckets that the Prometheus exporter uses. The labels of a metric are
+//! exported as the attributes of its data point.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use metrics::Counter;
+use metrics::Gauge;
+use metrics::Histogram;
+use metrics::HistogramFn;
+use metrics::Key;
+use metrics::KeyName;
+use metrics::Metadata;
+use metrics::Recorder;
+use metrics::SharedString;
+use metrics::Unit;
+use url::Url;
+
+use super::METRIC_BUCKETS;
+
+/// How often metrics are pushed to the collector.
+pub const OTLP_EXPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The OTLP `AGGREGATION_TEMPORALITY_CUMULATIVE` enum value.
+const AGGREGATION_TEMPORALITY_CUMULATIVE: u8 = 2;
+
+/// A [`Recorder`] that pushes metrics to an OpenTelemetry collector.
+#[derive(Clone)]
+pub struct OtlpRecorder {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    endpoint: Url,
+    start_time: SystemTime,
+    series: Mutex<HashMap<Key, Series>>,
+}
+
+/// The aggregated values of a single metric with a single set of labels.
+#[derive(Clone)]
+enum Series {
+    Counter(Arc<AtomicU64>),
+    Gauge(Arc<AtomicU64>),
+    Histogram(Arc<OtlpHistogram>),
+}
+
+/// A cumulative histogram over [`METRIC_BUCKETS`].
+#[derive(Default)]
+struct OtlpHistogram {
+    state: Mutex<HistogramState>,
+}
+
+#[derive(Default)]
+struct HistogramState {
+    bucket_counts: [u64; METRIC_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        // The last bucket is unbounded, so only NaN falls through here.
+        let Some(index) = METRIC_BUCKETS.iter().position(|bound| value <= *bound) else {
+            return;
+        };
+        let mut state = self.state.lock().expect("BUG: lock poisoned");
+        state.bucket_counts[index] += 1;
+        state.count += 1;
+        state.sum += value;
+    }
+}
+
+impl OtlpRecorder {
+    /// Create a recorder that pushes metrics to the given OTLP/HTTP
+    /// metrics endpoint, such as `http://localhost:4318/v1/metrics`.
+    pub fn new(endpoint: Url) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                endpoint,
+                start_time: SystemTime::now(),
+                series: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Push the metrics to the collector every [`OTLP_EXPORT_INTERVAL`],
+    /// forever. Failed pushes are logged and the metrics are sent again
+    /// with the next push, since they are cumulative.
+    pub async fn run(self) {
+        let client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(OTLP_EXPORT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let result = client
+                .post(self.inner.endpoint.clone())
+                .json(&self.payload(SystemTime::now()))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            if let Err(error) = result {
+                tracing::warn!(%error, "could not push metrics to the OTLP collector");
+            }
+        }
+    }
+
+    fn register(&self, key: &Key, new: impl FnOnce() -> Series) -> Series {
+        let mut series = self.inner.series.lock().expect("BUG: lock poisoned");
+        series.entry(key.clone()).or_insert_with(new).clone()
+    }
+
+    /// The `ExportMetricsServiceRequest` with the current values of all
+    /// metrics.
+    fn payload(&self, now: SystemTime) -> serde_json::Value {
+        let start_time = unix_nanos(self.inner.start_time);
+        let time = unix_nanos(now);
+        let series = self.inner.series.lock().expect("BUG: lock poisoned");
+
+        let metrics: Vec<serde_json::Value> = series
+            .iter()
+            .map(|(key, series)| {
+                let attributes = attributes(key);
+                let mut metric = serde_json::json!({ "name": key.name() });
+                match series {
+                    Series::Counter(value) => {
+                        metric["sum"] = serde_json::json!({
+                            "dataPoints": [{
+                                "attributes": attributes,
+                                "startTimeUnixNano": start_time,
+                                "timeUnixNano": time,
+                                "asInt": value.load(Ordering::Relaxed).to_string(),
+                            }],
+                            "aggregationTemporality": AGGREGATION_TEMPORALITY_CUMULATIVE,
+                            "isMonotonic": true,
+                        });
+                    }
+                    Series::Gauge(bits) => {
+                        metric["gauge"] = serde_json::json!({
+                            "dataPoints": [{
+                                "attributes": attributes,
+                                "timeUnixNano": time,
+                                "asDouble": f64::from_bits(bits.load(Ordering::Relaxed)),
+                            }],
+                        });
+                    }
+                    Series::Histogram(histogram) => {
+                        let state = histogram.state.lock().expect("BUG: lock poisoned");
+                        let bucket_counts: Vec<String> =
+                            state.bucket_counts.iter().map(u64::to_string).collect();
+                        // The last bucket is the implicit overflow bucket.
+                        let explicit_bounds = &METRIC_BUCKETS[..METRIC_BUCKETS.len() - 1];
+                        metric["histogram"] = serde_json::json!({
+                            "dataPoints": [{
+                                "attributes": attributes,
+                                "startTimeUnixNano": start_time,
+                                "timeUnixNano": time,
+                                "count": state.count.to_string(),
+                                "sum": state.sum,
+                                "bucketCounts": bucket_counts,
+                                "explicitBounds": explicit_bounds,
+                            }],
+                            "aggregationTemporality": AGGREGATION_TEMPORALITY_CUMULATIVE,
+                        });
+                    }
+                }
+                metric
+            })
+            .collect();
+
+        serde_json::json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [string_attribute("service.name", crate::PACKAGE_NAME)],
+                },
+                "scopeMetrics": [{
+                    "scope": { "name": crate::PACKAGE_NAME },
+                    "metrics": metrics,
+                }],
+            }],
+        })
+    }
+}
+
+/// The time since the unix epoch in nanoseconds, as a string, which is
+/// how 64-bit integers are encoded in OTLP JSON.
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn string_attribute(key: &str, value: &str) -> serde_json::Value {
+    serde_json::json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn attributes(key: &Key) -> Vec<serde_json::Value> {
+    key.labels()
+        .map(|label| string_attribute(label.key(), label.value()))
+        .collect()
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        match self.register(key, || Series::Counter(Arc::default())) {
+            Series::Counter(value) => Counter::from_arc(value),
+            _ => Counter::noop(),
+        }
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        match self.register(key, || Series::Gauge(Arc::default())) {
+            Series::Gauge(bits) => Gauge::from_arc(bits),
+            _ => Gauge::noop(),
+        }
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        match self.register(key, || Series::Histogram(Arc::default())) {
+            Series::Histogram(histogram) => Histogram::from_arc(histogram),
+            _ => Histogram::noop(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_are_encoded_as_otlp_json() {
+        let recorder = OtlpRecorder::new("http://localhost:4318/v1/metrics".parse().unwrap());
+        let metadata = metrics::Metadata::new(module_path!(), metrics::Level::INFO, None);
+        let key = Key::from_parts(
+            "signing_round_duration_seconds",
+            vec![metrics::Label::new("blockchain", "bitcoin")],
+        );
+
+        let histogram = recorder.register_histogram(&key, &metadata);
+        histogram.record(0.0625);
+        histogram.record(0.25);
+        histogram.record(100.0);
+        let counter =
+            recorder.register_counter(&Key::from_name("blocks_observed_total"), &metadata);
+        counter.increment(3);
+        // Registering the same metric again gives the same series.
+        recorder
+            .register_counter(&Key::from_name("blocks_observed_total"), &metadata)
+            .increment(1);
+
+        let payload = recorder.payload(SystemTime::now());
+        let metrics = payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap();
+        assert_eq!(metrics.len(), 2);
+
+        let find = |name: &str| {
+            metrics
+                .iter()
+                .find(|metric| metric["name"] == name)
+                .unwrap()
+        };
+
+        let sum = &find("blocks_observed_total")["sum"];
+        assert_eq!(sum["dataPoints"][0]["asInt"], "4");
+        assert_eq!(sum["isMonotonic"], true);
+
+        let histogram = &find("signing_round_duration_seconds")["histogram"];
+        let point = &histogram["dataPoints"][0];
+        assert_eq!(point["count"], "3");
+        assert_eq!(point["sum"], 100.3125);
+        assert_eq!(
+            point["bucketCounts"],
+            serde_json::json!(["0", "0", "0", "1", "1", "0", "0", "0", "1"])
+        );
+        assert_eq!(point["explicitBounds"].as_array().unwrap().len(), 8);
+        assert_eq!(point["attributes"][0]["key"], "blockchain");
+        assert_eq!(point["attributes"][0]["value"]["stringValue"], "bitcoin");
+    }
+}
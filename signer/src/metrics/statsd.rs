@@ -0,0 +1,200 @@
+//! # StatsD metrics
+//!
+//! The [`StatsdRecorder`] sends every update of a metric to a StatsD
+//! server over UDP as it happens, without any aggregation in the signer.
+//! Counters are sent as `c` metrics, gauges as `g` metrics and histograms
+//! as `h` metrics, and the labels of a metric are sent as DogStatsD tags,
+//! which most StatsD servers understand. Lost or undeliverable packets
+//! are ignored, as is usual for StatsD.
+
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::net::ToSocketAddrs as _;
+use std::net::UdpSocket;
+use std::sync::Arc;
+
+use metrics::Counter;
+use metrics::CounterFn;
+use metrics::Gauge;
+use metrics::GaugeFn;
+use metrics::Histogram;
+use metrics::HistogramFn;
+use metrics::Key;
+use metrics::KeyName;
+use metrics::Metadata;
+use metrics::Recorder;
+use metrics::SharedString;
+use metrics::Unit;
+
+/// A [`Recorder`] that sends metrics to a StatsD server.
+pub struct StatsdRecorder {
+    socket: Arc<UdpSocket>,
+}
+
+impl StatsdRecorder {
+    /// Create a recorder that sends metrics to the StatsD server at the
+    /// given `host:port` endpoint.
+    pub fn new(endpoint: &str) -> std::io::Result<Self> {
+        let addr = endpoint.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no address for endpoint")
+        })?;
+        let local_addr = match addr {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+        };
+
+        let socket = UdpSocket::bind(local_addr)?;
+        socket.connect(addr)?;
+        // Metrics are recorded from async code, which must never block on
+        // sending a packet.
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket: Arc::new(socket) })
+    }
+
+    fn metric(&self, key: &Key) -> Arc<StatsdMetric> {
+        Arc::new(StatsdMetric {
+            socket: self.socket.clone(),
+            name: sanitize(key.name()),
+            tags: tags(key),
+        })
+    }
+}
+
+/// The DogStatsD tags for the labels of the given metric, along with the
+/// `app` tag that the Prometheus exporter adds as a global label.
+fn tags(key: &Key) -> String {
+    let tags: Vec<String> = std::iter::once(("app", crate::PACKAGE_NAME))
+        .chain(key.labels().map(|label| (label.key(), label.value())))
+        .map(|(key, value)| format!("{}:{}", sanitize(key), sanitize(value)))
+        .collect();
+    format!("|#{}", tags.join(","))
+}
+
+/// Replace the characters that have a special meaning in the StatsD
+/// protocol.
+fn sanitize(value: &str) -> String {
+    value.replace([':', '|', '@', '#', ',', '\n'], "_")
+}
+
+struct StatsdMetric {
+    socket: Arc<UdpSocket>,
+    name: String,
+    tags: String,
+}
+
+impl StatsdMetric {
+    fn line(&self, value: impl Display, kind: &str) -> String {
+        format!("{}:{value}|{kind}{}", self.name, self.tags)
+    }
+
+    fn send(&self, value: impl Display, kind: &str) {
+        let _ = self.socket.send(self.line(value, kind).as_bytes());
+    }
+}
+
+impl CounterFn for StatsdMetric {
+    fn increment(&self, value: u64) {
+        self.send(value, "c");
+    }
+
+    fn absolute(&self, value: u64) {
+        // StatsD counters can only be incremented, so absolute values are
+        // sent as gauges.
+        self.send(value, "g");
+    }
+}
+
+impl GaugeFn for StatsdMetric {
+    fn increment(&self, value: f64) {
+        self.send(format_args!("{value:+}"), "g");
+    }
+
+    fn decrement(&self, value: f64) {
+        self.send(format_args!("{:+}", -value), "g");
+    }
+
+    fn set(&self, value: f64) {
+        // A gauge value with a leading sign is a change to the gauge
+        // rather than a new value, so negative values have to be set by
+        // resetting the gauge first.
+        if value.is_sign_negative() {
+            self.send(0, "g");
+        }
+        self.send(value, "g");
+    }
+}
+
+impl HistogramFn for StatsdMetric {
+    fn record(&self, value: f64) {
+        self.send(value, "h");
+    }
+}
+
+impl Recorder for StatsdRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.metric(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.metric(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.metric(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_are_sent_with_their_labels_as_tags() {
+        let server = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        server
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let endpoint = server.local_addr().unwrap().to_string();
+
+        let recorder = StatsdRecorder::new(&endpoint).unwrap();
+        let metadata = metrics::Metadata::new(module_path!(), metrics::Level::INFO, None);
+        let key = Key::from_parts(
+            "blocks_observed_total",
+            vec![metrics::Label::new("blockchain", "stacks")],
+        );
+        let tags = format!("|#app:{},blockchain:stacks", crate::PACKAGE_NAME);
+
+        let mut packet = [0; 512];
+        let mut receive = || {
+            let len = server.recv(&mut packet).unwrap();
+            String::from_utf8(packet[..len].to_vec()).unwrap()
+        };
+
+        recorder.register_counter(&key, &metadata).increment(2);
+        assert_eq!(receive(), format!("blocks_observed_total:2|c{tags}"));
+
+        let gauge = recorder.register_gauge(&key, &metadata);
+        gauge.decrement(1.5);
+        assert_eq!(receive(), format!("blocks_observed_total:-1.5|g{tags}"));
+        gauge.set(-3.0);
+        assert_eq!(receive(), format!("blocks_observed_total:0|g{tags}"));
+        assert_eq!(receive(), format!("blocks_observed_total:-3|g{tags}"));
+
+        recorder.register_histogram(&key, &metadata).record(0.25);
+        assert_eq!(receive(), format!("blocks_observed_total:0.25|h{tags}"));
+    }
+
+    #[test]
+    fn special_characters_are_replaced() {
+        assert_eq!(sanitize("a:b|c@d#e,f"), "a_b_c_d_e_f");
+        assert_eq!(sanitize("plain_name"), "plain_name");
+    }
+}
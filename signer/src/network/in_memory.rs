@@ -130,6 +130,6 @@ mod tests {
 
         let pk = PrivateKey::new(&mut OsRng);
 
-        testing::network::assert_clients_can_exchange_messages(client_1, client_2, pk, pk).await;
+        testing::network::assert_clients_can_exchange_messages(client_1, client_2, &pk, &pk).await;
     }
 }
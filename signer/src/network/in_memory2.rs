@@ -328,7 +328,7 @@ mod tests {
         let pk = PrivateKey::new(&mut OsRng);
 
         crate::testing::network::assert_clients_can_exchange_messages(
-            instance_1, instance_2, pk, pk,
+            instance_1, instance_2, &pk, &pk,
         )
         .await;
     }
@@ -5,31 +5,56 @@ use futures::StreamExt as _;
 use libp2p::core::ConnectedPoint;
 use libp2p::kad::RoutingUpdate;
 use libp2p::swarm::SwarmEvent;
-use libp2p::{Swarm, gossipsub, identify, kad, mdns};
+use libp2p::{PeerId, Swarm, gossipsub, identify, kad, mdns};
 use tokio::sync::Mutex;
 
 use crate::codec::Encode as _;
 use crate::context::{Context, P2PEvent, SignerCommand, SignerSignal};
 use crate::error::Error;
+use crate::message::Payload;
+use crate::metrics::Metrics;
 use crate::network::Msg;
+use crate::network::TrafficClass;
 use crate::network::libp2p::MultiaddrExt as _;
+use crate::network::sync;
+use crate::network::sync::BlockSummaryRequest;
+use crate::network::sync::SyncMessage;
 use crate::storage::DbWrite as _;
 
-use super::TOPIC;
+use super::SYNC_TOPIC;
 use super::swarm::{SignerBehavior, SignerBehaviorEvent};
 
 #[tracing::instrument(skip_all, name = "swarm")]
 pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
-    // Subscribe to the gossipsub topic.
-    let topic = TOPIC.clone();
-    swarm
-        .lock()
-        .await
-        .behaviour_mut()
-        .gossipsub
-        .subscribe(&TOPIC)
-        // If this doesn't succeed then nothing will work. It should never fail.
-        .expect("failed to subscribe to topic");
+    // Subscribe to the topic of each traffic class, each on its own
+    // gossipsub behavior. We listen on all of them even when we publish
+    // everything on the operational topic, so that we hear from peers
+    // that have already split their traffic.
+    let split_gossip_topics = ctx.config().signer.p2p.split_gossip_topics;
+    for class in TrafficClass::ALL {
+        swarm
+            .lock()
+            .await
+            .behaviour_mut()
+            .gossipsub_for(class)
+            .subscribe(super::topic(class))
+            // If this doesn't succeed then nothing will work. It should never fail.
+            .expect("failed to subscribe to topic");
+    }
+
+    // Subscribe to the bulk sync topic if it is enabled. Peers only send
+    // messages on a topic to the peers that are subscribed to it, so
+    // signers without bulk sync enabled never see these messages.
+    let enable_bulk_sync = ctx.config().signer.p2p.enable_bulk_sync;
+    if enable_bulk_sync {
+        swarm
+            .lock()
+            .await
+            .behaviour_mut()
+            .gossipsub
+            .subscribe(&SYNC_TOPIC)
+            .expect("failed to subscribe to the bulk sync topic");
+    }
 
     let mut term = ctx.get_termination_handle();
     let mut signal_rx = ctx.get_signal_receiver();
@@ -40,16 +65,39 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
     // This queue is then polled by the `poll_swarm` event loop to publish the
     // messages to the network.
     let outbox = Mutex::new(Vec::<Msg>::new());
+    let sync_outbox = Mutex::new(Vec::<SyncMessage>::new());
     let poll_outbound = async {
         tracing::debug!("p2p outbound message polling started");
         loop {
-            let Ok(SignerSignal::Command(SignerCommand::P2PPublish(payload))) =
-                signal_rx.recv().await
-            else {
-                continue;
-            };
+            match signal_rx.recv().await {
+                Ok(SignerSignal::Command(SignerCommand::P2PPublish(payload))) => {
+                    outbox.lock().await.push(*payload);
+                }
+                Ok(SignerSignal::Command(SignerCommand::P2PPublishSync(payload)))
+                    if enable_bulk_sync =>
+                {
+                    sync_outbox.lock().await.push(*payload);
+                }
+                _ => continue,
+            }
+        }
+    };
 
-            outbox.lock().await.push(*payload);
+    // Requests for block summaries received on the bulk sync topic are
+    // answered here, so that reading the summaries from the database does
+    // not hold up the swarm.
+    let (sync_request_tx, mut sync_request_rx) =
+        tokio::sync::mpsc::channel::<BlockSummaryRequest>(crate::SIGNER_CHANNEL_CAPACITY);
+    let serve_sync_requests = async {
+        while let Some(request) = sync_request_rx.recv().await {
+            let storage = ctx.get_storage();
+            match sync::summarize_blocks(&storage, &request).await {
+                Ok(batch) if batch.blocks.is_empty() => {
+                    tracing::debug!(chain_tip = %request.chain_tip, "no block summaries to send");
+                }
+                Ok(batch) => sync_outbox.lock().await.push(batch.into()),
+                Err(error) => tracing::warn!(%error, "could not summarize blocks"),
+            }
         }
     };
 
@@ -83,9 +131,25 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                     SwarmEvent::Behaviour(SignerBehaviorEvent::Identify(event)) => {
                         handle_identify_event(&mut swarm, ctx, event)
                     }
+                    // Messages on the bulk sync topic.
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::Gossipsub(
+                        gossipsub::Event::Message {
+                            propagation_source: peer_id,
+                            message,
+                            ..
+                        },
+                    )) if message.topic == SYNC_TOPIC.hash() => {
+                        handle_sync_message(ctx, &sync_request_tx, peer_id, message)
+                    }
                     // Gossipsub protocol events.
                     SwarmEvent::Behaviour(SignerBehaviorEvent::Gossipsub(event)) => {
-                        handle_gossipsub_event(&mut swarm, ctx, event)
+                        handle_gossipsub_event(&mut swarm, ctx, TrafficClass::Operational, event)
+                    }
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::GossipsubDkg(event)) => {
+                        handle_gossipsub_event(&mut swarm, ctx, TrafficClass::Dkg, event)
+                    }
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::GossipsubSigning(event)) => {
+                        handle_gossipsub_event(&mut swarm, ctx, TrafficClass::Signing, event)
                     }
                     SwarmEvent::NewListenAddr { address, .. } => {
                         tracing::info!(%address, "listener started");
@@ -111,8 +175,16 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                         if !ctx.state().current_signer_set().is_allowed_peer(&peer_id) {
                             tracing::warn!(%connection_id, %peer_id, ?endpoint, "connected to peer, however it is not a known signer; disconnecting");
                             let _ = swarm.disconnect_peer_id(peer_id);
+                        } else if ctx.state().is_peer_banned(&peer_id) {
+                            tracing::warn!(%connection_id, %peer_id, ?endpoint, "connected to peer, however it is banned; disconnecting");
+                            let _ = swarm.disconnect_peer_id(peer_id);
                         } else {
                             tracing::debug!(%peer_id, ?endpoint, "connected to peer");
+                            ctx.state().peer_statistics().connection_established(
+                                peer_id,
+                                connection_id,
+                                endpoint.get_remote_address(),
+                            );
 
                             // Perform operations that are only needed/possible when we are the
                             // dialer and have peer's confirmed dialable address.
@@ -156,14 +228,26 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                             }
                         }
                     }
-                    SwarmEvent::ConnectionClosed { peer_id, cause, endpoint, .. } => {
+                    SwarmEvent::ConnectionClosed {
+                        peer_id,
+                        connection_id,
+                        cause,
+                        endpoint,
+                        ..
+                    } => {
                         tracing::trace!(%peer_id, ?cause, ?endpoint, "connection closed");
+                        ctx.state()
+                            .peer_statistics()
+                            .connection_closed(peer_id, connection_id);
                     }
                     SwarmEvent::IncomingConnection { local_addr, send_back_addr, .. } => {
                         tracing::trace!(%local_addr, %send_back_addr, "incoming connection");
                     }
                     SwarmEvent::Behaviour(SignerBehaviorEvent::Ping(ping)) => {
                         tracing::trace!("ping received: {:?}", ping);
+                        if let Ok(rtt) = ping.result {
+                            ctx.state().peer_statistics().record_rtt(ping.peer, rtt);
+                        }
                     }
                     SwarmEvent::OutgoingConnectionError { connection_id, error, peer_id } => {
                         tracing::trace!(%connection_id, %error, ?peer_id, "outgoing connection error");
@@ -251,18 +335,21 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
 
                 // Encode the message payload into bytes using the signer codec.
                 let encoded_msg = payload.encode_to_vec();
+                let class = payload.payload.traffic_class();
+                let topic_class = class.publish_class(split_gossip_topics);
 
                 let _ = swarm
                     .lock()
                     .await
                     .behaviour_mut()
-                    .gossipsub
-                    .publish(topic.clone(), encoded_msg)
+                    .gossipsub_for(topic_class)
+                    .publish(super::topic(topic_class).clone(), encoded_msg)
                     .inspect_err(|error| {
                         // An error occurred while attempting to publish.
                         // Log the error and send a failure signal to the application
                         // so that it can handle the failure as needed.
                         tracing::warn!(%error, ?msg_id, "failed to publish message");
+                        Metrics::increment_gossip_messages(class.as_str(), "publish_failed");
                         let _ = signal_tx.send(P2PEvent::PublishFailure(msg_id).into());
                     })
                     .inspect(|_| {
@@ -270,12 +357,42 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                         // and send a success signal to the application so that it can
                         // handle the success as needed.
                         tracing::trace!(?msg_id, "message published successfully");
+                        Metrics::increment_gossip_messages(class.as_str(), "published");
                         let _ = signal_tx.send(P2PEvent::PublishSuccess(msg_id).into());
                     });
             }
         }
     };
 
+    // Drain the bulk sync outbox and publish the messages to the network.
+    // Nothing waits for a receipt for these messages, so we only log
+    // failures.
+    let poll_sync_outbox = async {
+        loop {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            let messages = sync_outbox.lock().await.drain(..).collect::<Vec<_>>();
+            for message in messages {
+                let encoded_msg = match message.encode_compressed() {
+                    Ok(encoded_msg) => encoded_msg,
+                    Err(error) => {
+                        tracing::warn!(%error, "could not encode bulk sync message");
+                        continue;
+                    }
+                };
+
+                let _ = swarm
+                    .lock()
+                    .await
+                    .behaviour_mut()
+                    .gossipsub
+                    .publish(SYNC_TOPIC.clone(), encoded_msg)
+                    .inspect_err(|error| {
+                        tracing::warn!(%error, "failed to publish bulk sync message");
+                    });
+            }
+        }
+    };
+
     let log = async {
         loop {
             tokio::time::sleep(Duration::from_secs(60)).await;
@@ -291,6 +408,8 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
         },
         _ = poll_outbound => {},
         _ = poll_swarm => {},
+        _ = serve_sync_requests => {},
+        _ = poll_sync_outbox => {},
         _ = log => {},
     }
 
@@ -340,9 +459,18 @@ fn handle_mdns_event(swarm: &mut Swarm<SignerBehavior>, ctx: &impl Context, even
                     tracing::debug!(%peer_id, %addr, "discovered peer via mDNS, however it is not a known signer; ignoring");
                     continue;
                 }
+                if ctx.state().is_peer_banned(&peer_id) {
+                    tracing::debug!(%peer_id, %addr, "discovered peer via mDNS, however it is banned; ignoring");
+                    continue;
+                }
 
                 tracing::debug!(%peer_id, %addr, "discovered peer via mDNS");
-                swarm.behaviour_mut().gossipsub.add_explicit_peer(&peer_id);
+                for class in TrafficClass::ALL {
+                    swarm
+                        .behaviour_mut()
+                        .gossipsub_for(class)
+                        .add_explicit_peer(&peer_id);
+                }
             }
         }
         // A multicast-DNS event indicating that a previously discovered peer
@@ -351,10 +479,12 @@ fn handle_mdns_event(swarm: &mut Swarm<SignerBehavior>, ctx: &impl Context, even
         Event::Expired(peers) => {
             for (peer_id, addr) in peers {
                 tracing::debug!(%peer_id, %addr, "expired peer via mDNS");
-                swarm
-                    .behaviour_mut()
-                    .gossipsub
-                    .remove_explicit_peer(&peer_id);
+                for class in TrafficClass::ALL {
+                    swarm
+                        .behaviour_mut()
+                        .gossipsub_for(class)
+                        .remove_explicit_peer(&peer_id);
+                }
             }
         }
     }
@@ -384,10 +514,72 @@ fn handle_identify_event(
     }
 }
 
+#[tracing::instrument(skip_all, name = "bulk-sync")]
+fn handle_sync_message(
+    ctx: &impl Context,
+    sync_request_tx: &tokio::sync::mpsc::Sender<BlockSummaryRequest>,
+    peer_id: PeerId,
+    message: gossipsub::Message,
+) {
+    let current_signer_set = ctx.state().current_signer_set();
+    if !current_signer_set.is_allowed_peer(&peer_id) {
+        tracing::warn!(%peer_id, "ignoring bulk sync message from unknown peer");
+        return;
+    }
+
+    let Some(origin_peer_id) = message.source else {
+        tracing::warn!(%peer_id, "origin peer id unknown, ignoring bulk sync message");
+        return;
+    };
+
+    if !current_signer_set.is_allowed_peer(&origin_peer_id) {
+        tracing::warn!(%origin_peer_id, "ignoring bulk sync message from unknown origin peer");
+        return;
+    }
+
+    if ctx.state().is_peer_banned(&peer_id) || ctx.state().is_peer_banned(&origin_peer_id) {
+        tracing::debug!(%peer_id, %origin_peer_id, "ignoring bulk sync message from banned peer");
+        return;
+    }
+
+    let peer_statistics = ctx.state().peer_statistics();
+    let message = SyncMessage::decode_compressed(&message.data)
+        .inspect(|message| peer_statistics.record_message(origin_peer_id, message.kind()))
+        .inspect_err(|_| peer_statistics.record_rejected_message(origin_peer_id));
+
+    match message {
+        Ok(SyncMessage::BlockSummaryRequest(request)) => {
+            tracing::debug!(
+                %origin_peer_id,
+                chain_tip = %request.chain_tip,
+                max_blocks = request.max_blocks,
+                "received request for block summaries"
+            );
+            if sync_request_tx.try_send(request).is_err() {
+                tracing::warn!(%origin_peer_id, "too many pending block summary requests; dropping request");
+            }
+        }
+        Ok(SyncMessage::BlockSummaryBatch(batch)) => {
+            tracing::debug!(
+                %origin_peer_id,
+                num_blocks = batch.blocks.len(),
+                "received block summaries"
+            );
+            let _ = ctx
+                .get_signal_sender()
+                .send(P2PEvent::BlockSummariesReceived(Box::new(batch)).into());
+        }
+        Err(error) => {
+            tracing::warn!(%origin_peer_id, %error, "failed to decode bulk sync message");
+        }
+    }
+}
+
 #[tracing::instrument(skip_all, name = "gossipsub")]
 fn handle_gossipsub_event(
     swarm: &mut Swarm<SignerBehavior>,
     ctx: &impl Context,
+    class: TrafficClass,
     event: gossipsub::Event,
 ) {
     use gossipsub::Event;
@@ -422,6 +614,18 @@ fn handle_gossipsub_event(
                 return;
             }
 
+            // A peer may have been banned while we were connected to it,
+            // in which case this is our first chance to disconnect from it.
+            if ctx.state().is_peer_banned(&peer_id) {
+                tracing::warn!(%peer_id, "ignoring message from banned peer; disconnecting");
+                let _ = swarm.disconnect_peer_id(peer_id);
+                return;
+            }
+            if ctx.state().is_peer_banned(&origin_peer_id) {
+                tracing::debug!(%origin_peer_id, "ignoring message from banned origin peer");
+                return;
+            }
+
             Msg::decode_with_digest(&message.data)
                 .and_then(|(msg, digest)| {
                     tracing::trace!(
@@ -442,6 +646,13 @@ fn handle_gossipsub_event(
                         return Err(error)
                     }
 
+                    Metrics::increment_gossip_messages(class.as_str(), "received");
+                    let peer_statistics = ctx.state().peer_statistics();
+                    peer_statistics.record_message(origin_peer_id, msg.payload.kind());
+                    if let Payload::SignerHeartbeat(_) = msg.payload {
+                        peer_statistics.record_heartbeat(origin_peer_id, msg.bitcoin_chain_tip);
+                    }
+
                     let _ = ctx.get_signal_sender()
                         .send(P2PEvent::MessageReceived(Box::new(msg)).into())
                         .inspect_err(|error| {
@@ -452,10 +663,13 @@ fn handle_gossipsub_event(
                 })
                 .unwrap_or_else(|error| {
                     tracing::warn!(%peer_id, %error, "Failed to decode message");
+                    ctx.state()
+                        .peer_statistics()
+                        .record_rejected_message(origin_peer_id);
                 });
         }
         Event::Subscribed { peer_id, topic } => {
-            tracing::debug!(%peer_id, %topic, "subscribed to topic");
+            tracing::debug!(%peer_id, %topic, class = class.as_str(), "subscribed to topic");
         }
         Event::Unsubscribed { peer_id, topic } => {
             tracing::debug!(%peer_id, %topic, "unsubscribed from topic");
@@ -4,6 +4,8 @@ use std::sync::LazyLock;
 
 use libp2p::gossipsub::IdentTopic;
 
+use crate::network::TrafficClass;
+
 mod bootstrap;
 mod errors;
 mod event_loop;
@@ -19,6 +21,29 @@ pub use self::swarm::SignerSwarmBuilder;
 /// The default port for the libp2p network
 pub const DEFAULT_P2P_PORT: u16 = 4122;
 
-/// The topic used for signer gossipsub messages
+/// The topic used for operational signer gossipsub messages. This was
+/// the only topic before traffic was split by class, so it keeps its
+/// original name, and messages of every class are published on it until
+/// split gossip topics are enabled in the config.
 // NOTE: Using LazyLock (static) instead of LazyCell (const) as IdentTopic is interior mutable.
 pub static TOPIC: LazyLock<IdentTopic> = LazyLock::new(|| IdentTopic::new("sbtc-signer"));
+
+/// The topic used for the messages of DKG rounds.
+pub static DKG_TOPIC: LazyLock<IdentTopic> = LazyLock::new(|| IdentTopic::new("sbtc-signer-dkg"));
+
+/// The topic used for the messages of signing rounds.
+pub static SIGNING_TOPIC: LazyLock<IdentTopic> =
+    LazyLock::new(|| IdentTopic::new("sbtc-signer-signing"));
+
+/// The topic used for the optional bulk sync messages, which are exchanged
+/// between signers catching up after downtime. See [`crate::network::sync`].
+pub static SYNC_TOPIC: LazyLock<IdentTopic> = LazyLock::new(|| IdentTopic::new("sbtc-signer-sync"));
+
+/// The topic used for messages of the given traffic class.
+pub fn topic(class: TrafficClass) -> &'static IdentTopic {
+    match class {
+        TrafficClass::Dkg => &DKG_TOPIC,
+        TrafficClass::Signing => &SIGNING_TOPIC,
+        TrafficClass::Operational => &TOPIC,
+    }
+}
@@ -145,7 +145,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key1;
+                settings.signer.private_key = key1.clone();
             })
             .build();
         context1
@@ -157,7 +157,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key2;
+                settings.signer.private_key = key2.clone();
             })
             .build();
         context2
@@ -203,7 +203,9 @@ mod tests {
         // Run the test with a 30-second timeout for the swarms to exchange messages.
         if tokio::time::timeout(
             tokio::time::Duration::from_secs(30),
-            testing::network::assert_clients_can_exchange_messages(network1, network2, key1, key2),
+            testing::network::assert_clients_can_exchange_messages(
+                network1, network2, &key1, &key2,
+            ),
         )
         .await
         .is_err()
@@ -234,7 +236,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key1;
+                settings.signer.private_key = key1.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -242,7 +244,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key2;
+                settings.signer.private_key = key2.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -250,7 +252,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key3;
+                settings.signer.private_key = key3.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -260,7 +262,7 @@ mod tests {
         let current_signer_set2 = context2.state().current_signer_set();
         let current_signer_set3 = context3.state().current_signer_set();
 
-        for key in [key1, key2, key3] {
+        for key in [&key1, &key2, &key3] {
             current_signer_set1.add_signer(PublicKey::from_private_key(&key));
             current_signer_set2.add_signer(PublicKey::from_private_key(&key));
             current_signer_set3.add_signer(PublicKey::from_private_key(&key));
@@ -388,7 +390,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key1;
+                settings.signer.private_key = key1.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -396,7 +398,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key2;
+                settings.signer.private_key = key2.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -404,7 +406,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key3;
+                settings.signer.private_key = key3.clone();
                 settings.signer.p2p.enable_mdns = false;
             })
             .build();
@@ -417,7 +419,7 @@ mod tests {
         current_signer_set1.add_signer(PublicKey::from_private_key(&key1));
         current_signer_set1.add_signer(PublicKey::from_private_key(&key2));
 
-        for key in [key1, key2, key3] {
+        for key in [&key1, &key2, &key3] {
             current_signer_set2.add_signer(PublicKey::from_private_key(&key));
             current_signer_set3.add_signer(PublicKey::from_private_key(&key));
         }
@@ -571,7 +573,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key1;
+                settings.signer.private_key = key1.clone();
             })
             .build();
         // Add key2 to the known signers for signer1.
@@ -585,7 +587,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key2;
+                settings.signer.private_key = key2.clone();
             })
             .build();
         // Add key1 to the known signers for signer2.
@@ -599,7 +601,7 @@ mod tests {
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = key3;
+                settings.signer.private_key = key3.clone();
             })
             .build();
         // Add key1 and key2 to the known signers for signer 3. This simulates
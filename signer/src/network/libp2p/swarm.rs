@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use crate::context::Context;
 use crate::keys::PrivateKey;
+use crate::network::TrafficClass;
 use libp2p::core::muxing::StreamMuxerBox;
 use libp2p::core::upgrade::Version;
 use libp2p::identity::Keypair;
@@ -26,7 +27,7 @@ use super::{bootstrap, event_loop};
 /// The maximum number of substreams _per connection_. This is used to limit
 /// the number of concurrent substreams that can be opened on a single
 /// connection. The general assumption at the time of writing is:
-/// * GossipSub: 1 bidirectional stream per connection
+/// * GossipSub: 1 bidirectional stream per connection for each traffic class
 /// * Kademlia: 1-3 streams during active lookups, each query can use its own stream
 /// * AutoNAT: 2 streams (one for client, one for server operations)
 /// * Identify: 1 stream for peer identification
@@ -45,7 +46,12 @@ const NEGOTIATION_TIMEOUT_SECS: u64 = 10;
 /// Define the behaviors of the [`SignerSwarm`] libp2p network.
 #[derive(NetworkBehaviour)]
 pub struct SignerBehavior {
+    /// Gossipsub for operational traffic, see [`TrafficClass::Operational`].
     pub gossipsub: gossipsub::Behaviour,
+    /// Gossipsub for the messages of DKG rounds.
+    pub gossipsub_dkg: gossipsub::Behaviour,
+    /// Gossipsub for the messages of signing rounds.
+    pub gossipsub_signing: gossipsub::Behaviour,
     mdns: Toggle<mdns::tokio::Behaviour>,
     pub kademlia: Toggle<kad::Behaviour<MemoryStore>>,
     ping: ping::Behaviour,
@@ -109,7 +115,9 @@ impl SignerBehavior {
         let bootstrap = bootstrap::Behavior::new(bootstrap_config);
 
         Ok(Self {
-            gossipsub: Self::gossipsub(&keypair)?,
+            gossipsub: Self::gossipsub(&keypair, TrafficClass::Operational)?,
+            gossipsub_dkg: Self::gossipsub(&keypair, TrafficClass::Dkg)?,
+            gossipsub_signing: Self::gossipsub(&keypair, TrafficClass::Signing)?,
             mdns,
             kademlia,
             ping: Default::default(),
@@ -165,18 +173,67 @@ impl SignerBehavior {
         connection_limits::Behaviour::new(limits)
     }
 
-    /// Create a new gossipsub behavior.
-    fn gossipsub(keypair: &Keypair) -> Result<gossipsub::Behaviour, SignerSwarmError> {
+    /// The gossipsub behavior that carries messages of the given traffic
+    /// class.
+    pub fn gossipsub_for(&mut self, class: TrafficClass) -> &mut gossipsub::Behaviour {
+        match class {
+            TrafficClass::Dkg => &mut self.gossipsub_dkg,
+            TrafficClass::Signing => &mut self.gossipsub_signing,
+            TrafficClass::Operational => &mut self.gossipsub,
+        }
+    }
+
+    /// Create a new gossipsub behavior for the given traffic class.
+    ///
+    /// Each traffic class gets its own behavior, negotiated under its own
+    /// protocol, so that every class has its own mesh and its own
+    /// substreams. A burst of large DKG messages then cannot hold up the
+    /// time-critical messages of signing rounds, and vice versa.
+    fn gossipsub(
+        keypair: &Keypair,
+        class: TrafficClass,
+    ) -> Result<gossipsub::Behaviour, SignerSwarmError> {
         let message_id_fn = |message: &gossipsub::Message| {
             let mut hasher = DefaultHasher::new();
             message.data.hash(&mut hasher);
             gossipsub::MessageId::from(hasher.finish().to_string())
         };
 
-        let gossipsub_config = gossipsub::ConfigBuilder::default()
+        let mut builder = gossipsub::ConfigBuilder::default();
+        builder
             .heartbeat_interval(Duration::from_secs(1)) // Default is 1 second
             .validation_mode(gossipsub::ValidationMode::Strict)
-            .message_id_fn(message_id_fn)
+            .message_id_fn(message_id_fn);
+
+        match class {
+            // Operational traffic keeps the default protocol and mesh
+            // parameters, so that it stays compatible with signers that do
+            // not split their traffic.
+            TrafficClass::Operational => {}
+            // DKG messages are large but rare. A slightly larger mesh
+            // than the default gets them to every signer in fewer hops.
+            TrafficClass::Dkg => {
+                builder
+                    .protocol_id_prefix("/sbtc-signer-dkg/meshsub")
+                    .mesh_n_low(6)
+                    .mesh_n(8)
+                    .mesh_n_high(12)
+                    .mesh_outbound_min(3);
+            }
+            // Signing rounds are time-critical, so we keep most signers in
+            // the mesh and repair it more often.
+            TrafficClass::Signing => {
+                builder
+                    .protocol_id_prefix("/sbtc-signer-signing/meshsub")
+                    .heartbeat_interval(Duration::from_millis(500))
+                    .mesh_n_low(8)
+                    .mesh_n(12)
+                    .mesh_n_high(16)
+                    .mesh_outbound_min(4);
+            }
+        }
+
+        let gossipsub_config = builder
             .build()
             .map_err(|e| SignerSwarmError::LibP2P(Box::new(e)))?;
 
@@ -345,7 +402,7 @@ impl<'a> SignerSwarmBuilder<'a> {
 
     /// Build the [`SignerSwarm`], consuming the builder.
     pub fn build(self) -> Result<SignerSwarm, SignerSwarmError> {
-        let keypair: Keypair = (*self.private_key).into();
+        let keypair: Keypair = self.private_key.clone().into();
         let behavior_config = SignerSwarmConfig {
             enable_mdns: self.enable_mdns,
             enable_kademlia: self.enable_kademlia,
@@ -510,11 +567,19 @@ mod tests {
 
     const MULTIADDR_NOT_SUPPORTED: &str = "Multiaddr is not supported";
 
+    #[test]
+    fn gossipsub_configs_are_valid_for_all_traffic_classes() {
+        let keypair: Keypair = PrivateKey::new(&mut rand::thread_rng()).into();
+        for class in TrafficClass::ALL {
+            SignerBehavior::gossipsub(&keypair, class).unwrap();
+        }
+    }
+
     #[tokio::test]
     async fn test_signer_swarm_builder() {
         let addr: Multiaddr = "/ip4/127.0.0.1/tcp/0".parse().unwrap();
         let private_key = PrivateKey::new(&mut rand::thread_rng());
-        let keypair: Keypair = private_key.into();
+        let keypair: Keypair = private_key.clone().into();
         let builder = SignerSwarmBuilder::new(&private_key)
             .add_listen_endpoint(addr.clone())
             .add_seed_addr(addr.clone());
@@ -711,12 +776,12 @@ mod tests {
         let context1 = TestContext::builder()
             .with_in_memory_storage()
             .with_mocked_clients()
-            .with_private_key(key1)
+            .with_private_key(key1.clone())
             .build();
         let context2 = TestContext::builder()
             .with_in_memory_storage()
             .with_mocked_clients()
-            .with_private_key(key2)
+            .with_private_key(key2.clone())
             .build();
 
         // Add each key to the other's signer set so they can connect.
@@ -821,13 +886,13 @@ mod tests {
         let context1 = TestContext::builder()
             .with_in_memory_storage()
             .with_mocked_clients()
-            .with_private_key(key1)
+            .with_private_key(key1.clone())
             .build();
 
         let context2 = TestContext::builder()
             .with_in_memory_storage()
             .with_mocked_clients()
-            .with_private_key(key2)
+            .with_private_key(key2.clone())
             .build();
 
         // Add each key to the other's signer set so they can connect.
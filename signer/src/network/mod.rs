@@ -11,6 +11,8 @@ pub mod in_memory;
 pub mod in_memory2;
 
 pub mod libp2p;
+pub mod stats;
+pub mod sync;
 
 use std::future::Future;
 
@@ -27,6 +29,50 @@ pub type Msg = ecdsa::Signed<message::SignerMessage>;
 /// The unique identifier for a message
 pub type MsgId = [u8; 32];
 
+/// The classes of traffic between signers. Each class is gossiped
+/// separately, so that a burst of messages of one class does not delay the
+/// messages of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficClass {
+    /// Messages of DKG rounds.
+    Dkg,
+    /// Messages of signing rounds, which are time-critical.
+    Signing,
+    /// Everything else, such as request decisions and heartbeats.
+    Operational,
+}
+
+impl TrafficClass {
+    /// All traffic classes.
+    pub const ALL: [TrafficClass; 3] = [
+        TrafficClass::Dkg,
+        TrafficClass::Signing,
+        TrafficClass::Operational,
+    ];
+
+    /// The name of the traffic class.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrafficClass::Dkg => "dkg",
+            TrafficClass::Signing => "signing",
+            TrafficClass::Operational => "operational",
+        }
+    }
+
+    /// The traffic class whose topic messages of this class are published
+    /// on. Unless `split_topics` is set, everything is published on the
+    /// operational topic, which is the only one that signers listened on
+    /// before traffic was split. See the `split_gossip_topics` field of
+    /// [`P2PNetworkConfig`](crate::config::P2PNetworkConfig).
+    pub fn publish_class(self, split_topics: bool) -> TrafficClass {
+        if split_topics {
+            self
+        } else {
+            TrafficClass::Operational
+        }
+    }
+}
+
 /// Represents the interaction point between signers and the signer network,
 /// allowing signers to exchange messages with each other.
 pub trait MessageTransfer: Clone {
@@ -0,0 +1,284 @@
+//! # Per-peer protocol statistics
+//!
+//! The libp2p event loop records what it sees from each peer here: the
+//! connections that are open to it, round trip times from the ping
+//! protocol, the messages that it sent us, and the chain tip in its most
+//! recent heartbeat. The statistics are only kept in memory and are meant
+//! for operators diagnosing connectivity problems, so nothing in the
+//! signer acts on them.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use libp2p::Multiaddr;
+use libp2p::PeerId;
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::ConnectionId;
+
+use crate::storage::model::BitcoinBlockHash;
+
+/// The weight given to a new round trip time sample when updating the
+/// smoothed estimate. This is the same weight that TCP uses for its
+/// smoothed round trip time.
+const RTT_SAMPLE_WEIGHT: f64 = 0.125;
+
+/// The transport that a connection to a peer uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Transport {
+    /// A TCP connection.
+    Tcp,
+    /// A QUIC connection.
+    Quic,
+    /// An in-process connection, only used in tests.
+    Memory,
+    /// Any other transport.
+    Other,
+}
+
+impl Transport {
+    /// Determine the transport used for a connection from the address of
+    /// the remote end.
+    pub fn from_multiaddr(address: &Multiaddr) -> Self {
+        let mut transport = Transport::Other;
+        for protocol in address.iter() {
+            match protocol {
+                Protocol::QuicV1 => return Transport::Quic,
+                Protocol::Tcp(_) => transport = Transport::Tcp,
+                Protocol::Memory(_) => return Transport::Memory,
+                _ => {}
+            }
+        }
+        transport
+    }
+
+    /// The name of the transport.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transport::Tcp => "tcp",
+            Transport::Quic => "quic",
+            Transport::Memory => "memory",
+            Transport::Other => "other",
+        }
+    }
+}
+
+/// The chain tip that a peer reported in a heartbeat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeartbeatTip {
+    /// The bitcoin chain tip of the peer when it sent the heartbeat.
+    pub bitcoin_chain_tip: BitcoinBlockHash,
+    /// When we received the heartbeat.
+    pub received_at: time::OffsetDateTime,
+}
+
+/// The statistics that we have collected for a single peer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeerStats {
+    /// The open connections to the peer, and the transport that each of
+    /// them uses.
+    pub connections: HashMap<ConnectionId, Transport>,
+    /// The most recent round trip time measured with the ping protocol.
+    pub last_rtt: Option<Duration>,
+    /// A smoothed estimate of the round trip time to the peer.
+    pub smoothed_rtt: Option<Duration>,
+    /// The number of messages, by message type, that originated from the
+    /// peer and that we accepted.
+    pub message_counts: BTreeMap<&'static str, u64>,
+    /// The number of messages that originated from the peer and that we
+    /// rejected because they could not be decoded or verified.
+    pub rejected_messages: u64,
+    /// The chain tip in the most recent heartbeat from the peer.
+    pub last_heartbeat: Option<HeartbeatTip>,
+}
+
+impl PeerStats {
+    /// Whether we have at least one open connection to the peer.
+    pub fn is_connected(&self) -> bool {
+        !self.connections.is_empty()
+    }
+
+    /// The transports used by the open connections to the peer, without
+    /// duplicates.
+    pub fn transports(&self) -> Vec<Transport> {
+        let mut transports: Vec<Transport> = self.connections.values().copied().collect();
+        transports.sort();
+        transports.dedup();
+        transports
+    }
+
+    /// The total number of messages that we accepted from the peer.
+    pub fn accepted_messages(&self) -> u64 {
+        self.message_counts.values().sum()
+    }
+
+    /// The fraction of the messages from the peer that we accepted, from
+    /// 0.0 to 1.0. A peer that has not sent us anything has a score of
+    /// 1.0.
+    pub fn reputation_score(&self) -> f64 {
+        let accepted = self.accepted_messages();
+        let total = accepted.saturating_add(self.rejected_messages);
+        if total == 0 {
+            return 1.0;
+        }
+        accepted as f64 / total as f64
+    }
+
+    fn record_rtt(&mut self, rtt: Duration) {
+        let smoothed = match self.smoothed_rtt {
+            Some(smoothed) => {
+                smoothed.mul_f64(1.0 - RTT_SAMPLE_WEIGHT) + rtt.mul_f64(RTT_SAMPLE_WEIGHT)
+            }
+            None => rtt,
+        };
+        self.last_rtt = Some(rtt);
+        self.smoothed_rtt = Some(smoothed);
+    }
+}
+
+/// Statistics for every peer that we have been connected to since the
+/// signer started.
+#[derive(Debug, Default)]
+pub struct PeerStatistics {
+    peers: RwLock<HashMap<PeerId, PeerStats>>,
+}
+
+/// NOTE: We should never fail to acquire a lock from the RwLock so that it panics.
+/// If we do, then things have gone very wrong.
+#[allow(clippy::expect_used)]
+impl PeerStatistics {
+    fn update(&self, peer_id: PeerId, f: impl FnOnce(&mut PeerStats)) {
+        let mut peers = self
+            .peers
+            .write()
+            .expect("BUG: Failed to acquire write lock of peer statistics");
+        f(peers.entry(peer_id).or_default());
+    }
+
+    /// Like `update`, but does nothing for peers that we have no
+    /// statistics for. Connections from peers outside of the signer set
+    /// are closed as soon as they are established, and we do not want
+    /// them to leave entries behind.
+    fn update_existing(&self, peer_id: PeerId, f: impl FnOnce(&mut PeerStats)) {
+        let mut peers = self
+            .peers
+            .write()
+            .expect("BUG: Failed to acquire write lock of peer statistics");
+        if let Some(stats) = peers.get_mut(&peer_id) {
+            f(stats);
+        }
+    }
+
+    /// Record that a connection to the peer has been established, with
+    /// the given address for the remote end.
+    pub fn connection_established(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        remote_address: &Multiaddr,
+    ) {
+        let transport = Transport::from_multiaddr(remote_address);
+        self.update(peer_id, |stats| {
+            stats.connections.insert(connection_id, transport);
+        });
+    }
+
+    /// Record that a connection to the peer has been closed.
+    pub fn connection_closed(&self, peer_id: PeerId, connection_id: ConnectionId) {
+        self.update_existing(peer_id, |stats| {
+            stats.connections.remove(&connection_id);
+        });
+    }
+
+    /// Record a round trip time to the peer measured with the ping
+    /// protocol. Only peers that we have accepted a connection from are
+    /// tracked.
+    pub fn record_rtt(&self, peer_id: PeerId, rtt: Duration) {
+        self.update_existing(peer_id, |stats| stats.record_rtt(rtt));
+    }
+
+    /// Record that we accepted a message of the given type from the peer.
+    pub fn record_message(&self, peer_id: PeerId, message_type: &'static str) {
+        self.update(peer_id, |stats| {
+            *stats.message_counts.entry(message_type).or_default() += 1;
+        });
+    }
+
+    /// Record that we rejected a message from the peer.
+    pub fn record_rejected_message(&self, peer_id: PeerId) {
+        self.update(peer_id, |stats| stats.rejected_messages += 1);
+    }
+
+    /// Record the chain tip in a heartbeat that we received from the peer.
+    pub fn record_heartbeat(&self, peer_id: PeerId, bitcoin_chain_tip: BitcoinBlockHash) {
+        let tip = HeartbeatTip {
+            bitcoin_chain_tip,
+            received_at: time::OffsetDateTime::now_utc(),
+        };
+        self.update(peer_id, |stats| stats.last_heartbeat = Some(tip));
+    }
+
+    /// Return the statistics of the peers that we are currently connected
+    /// to.
+    pub fn connected_peers(&self) -> Vec<(PeerId, PeerStats)> {
+        self.peers
+            .read()
+            .expect("BUG: Failed to acquire read lock of peer statistics")
+            .iter()
+            .filter(|(_, stats)| stats.is_connected())
+            .map(|(peer_id, stats)| (*peer_id, stats.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transports_are_detected_from_addresses() {
+        let tcp: Multiaddr = "/ip4/127.0.0.1/tcp/4122".parse().unwrap();
+        let quic: Multiaddr = "/ip4/127.0.0.1/udp/4122/quic-v1".parse().unwrap();
+        let memory: Multiaddr = "/memory/1234".parse().unwrap();
+        let other: Multiaddr = "/ip4/127.0.0.1".parse().unwrap();
+
+        assert_eq!(Transport::from_multiaddr(&tcp), Transport::Tcp);
+        assert_eq!(Transport::from_multiaddr(&quic), Transport::Quic);
+        assert_eq!(Transport::from_multiaddr(&memory), Transport::Memory);
+        assert_eq!(Transport::from_multiaddr(&other), Transport::Other);
+    }
+
+    #[test]
+    fn statistics_are_tracked_per_peer() {
+        let statistics = PeerStatistics::default();
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/4122".parse().unwrap();
+        let connection_id = ConnectionId::new_unchecked(1);
+
+        // We only report peers that we are connected to.
+        statistics.record_message(peer_id, "SignerHeartbeat");
+        assert!(statistics.connected_peers().is_empty());
+
+        statistics.connection_established(peer_id, connection_id, &address);
+        statistics.record_message(peer_id, "SignerHeartbeat");
+        statistics.record_message(peer_id, "BitcoinPreSignAck");
+        statistics.record_rejected_message(peer_id);
+        statistics.record_rtt(peer_id, Duration::from_millis(100));
+        statistics.record_rtt(peer_id, Duration::from_millis(20));
+
+        let [(reported_peer_id, stats)] = statistics.connected_peers().try_into().unwrap();
+        assert_eq!(reported_peer_id, peer_id);
+        assert_eq!(stats.transports(), vec![Transport::Tcp]);
+        assert_eq!(stats.message_counts["SignerHeartbeat"], 2);
+        assert_eq!(stats.message_counts["BitcoinPreSignAck"], 1);
+        assert_eq!(stats.reputation_score(), 0.75);
+        assert_eq!(stats.last_rtt, Some(Duration::from_millis(20)));
+        assert_eq!(stats.smoothed_rtt, Some(Duration::from_millis(90)));
+
+        // The statistics are kept after the peer disconnects, but the
+        // peer is no longer reported.
+        statistics.connection_closed(peer_id, connection_id);
+        assert!(statistics.connected_peers().is_empty());
+    }
+}
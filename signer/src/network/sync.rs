@@ -0,0 +1,305 @@
+//! # Bulk data sync between signers
+//!
+//! A signer that has been offline for a while has to walk back from the
+//! new bitcoin chain tip, one block header at a time, until it reaches a
+//! block that it already knows about. When the optional bulk sync topic
+//! is enabled, a lagging signer can instead ask its peers for summaries of
+//! the blocks that it missed, and receive them in one compressed batch.
+//!
+//! Block summaries are only ever used as hints for which blocks to fetch
+//! from bitcoin-core. The block observer checks each summary against the
+//! block that it fetches from its own bitcoin-core node, so a peer that
+//! sends bogus summaries can slow a signer down, but cannot make it accept
+//! a block that is not on its own node's view of the blockchain.
+
+use crate::bitcoin::rpc::BitcoinBlockHeader;
+use crate::codec::Decode as _;
+use crate::codec::Encode as _;
+use crate::error::Error;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlock;
+use crate::storage::model::BitcoinBlockActivity;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+
+/// The zstd compression level used for messages on the bulk sync topic.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// The maximum size of a decompressed bulk sync message. Messages that
+/// decompress to more than this are rejected, so a peer cannot exhaust
+/// our memory with a small, highly compressible message.
+const MAX_DECOMPRESSED_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// A message exchanged between signers on the bulk sync topic.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum SyncMessage {
+    /// A request for summaries of recent bitcoin blocks.
+    BlockSummaryRequest(BlockSummaryRequest),
+    /// A response with summaries of recent bitcoin blocks.
+    BlockSummaryBatch(BlockSummaryBatch),
+}
+
+impl SyncMessage {
+    /// The name of the message type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::BlockSummaryRequest(_) => "BlockSummaryRequest",
+            Self::BlockSummaryBatch(_) => "BlockSummaryBatch",
+        }
+    }
+
+    /// Encode this message and compress it with zstd.
+    pub fn encode_compressed(self) -> Result<Vec<u8>, Error> {
+        zstd::bulk::compress(&self.encode_to_vec(), COMPRESSION_LEVEL)
+            .map_err(Error::SyncMessageCompression)
+    }
+
+    /// Decompress and decode a message that was created with
+    /// [`SyncMessage::encode_compressed`].
+    pub fn decode_compressed(data: &[u8]) -> Result<Self, Error> {
+        let encoded = zstd::bulk::decompress(data, MAX_DECOMPRESSED_MESSAGE_SIZE)
+            .map_err(Error::SyncMessageDecompression)?;
+        Self::decode(encoded.as_slice())
+    }
+}
+
+impl From<BlockSummaryRequest> for SyncMessage {
+    fn from(value: BlockSummaryRequest) -> Self {
+        Self::BlockSummaryRequest(value)
+    }
+}
+
+impl From<BlockSummaryBatch> for SyncMessage {
+    fn from(value: BlockSummaryBatch) -> Self {
+        Self::BlockSummaryBatch(value)
+    }
+}
+
+/// A request, from a lagging signer, for summaries of the bitcoin blocks
+/// leading up to and including the given block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct BlockSummaryRequest {
+    /// The hash of the most recent bitcoin block to summarize.
+    pub chain_tip: BitcoinBlockHash,
+    /// The maximum number of blocks to summarize.
+    pub max_blocks: u32,
+}
+
+/// Summaries of consecutive bitcoin blocks, ordered from the block with
+/// the greatest height to the block with the least height.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct BlockSummaryBatch {
+    /// The block summaries.
+    pub blocks: Vec<BlockSummary>,
+}
+
+impl BlockSummaryBatch {
+    /// Whether this batch answers a request for blocks leading up to the
+    /// given chain tip, and the summaries form an unbroken chain of
+    /// blocks.
+    ///
+    /// This only checks that the batch is internally consistent. Whether
+    /// the blocks are actually on the bitcoin blockchain is checked when
+    /// they are fetched from bitcoin-core.
+    pub fn is_chain_ending_at(&self, chain_tip: &BitcoinBlockHash) -> bool {
+        let Some(first) = self.blocks.first() else {
+            return false;
+        };
+
+        let is_contiguous = self.blocks.windows(2).all(|pair| {
+            pair[0].parent_hash == pair[1].block_hash
+                && pair[0].block_height == pair[1].block_height + 1
+        });
+
+        &first.block_hash == chain_tip && is_contiguous
+    }
+}
+
+/// A summary of the header of a bitcoin block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct BlockSummary {
+    /// The hash of the block.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the block.
+    pub block_height: BitcoinBlockHeight,
+    /// The hash of the parent block.
+    pub parent_hash: BitcoinBlockHash,
+    /// The time field of the block header, in seconds since the unix
+    /// epoch.
+    pub block_time: u64,
+}
+
+impl BlockSummary {
+    /// Summarize the given block, using the activity that the block
+    /// observer recorded for it.
+    pub fn new(block: &BitcoinBlock, activity: &BitcoinBlockActivity) -> Self {
+        Self {
+            block_hash: block.block_hash,
+            block_height: block.block_height,
+            parent_hash: block.parent_hash,
+            block_time: activity.block_time,
+        }
+    }
+}
+
+impl From<BlockSummary> for BitcoinBlockHeader {
+    fn from(value: BlockSummary) -> Self {
+        Self {
+            hash: value.block_hash.into(),
+            height: value.block_height,
+            time: value.block_time,
+            previous_block_hash: value.parent_hash.into(),
+        }
+    }
+}
+
+/// Summarize the blocks leading up to and including the requested chain
+/// tip, using the bitcoin blocks in the database.
+///
+/// The summaries stop at the first block that is not in the database, or
+/// whose time was not recorded, so the response is empty if the chain tip
+/// is unknown.
+pub async fn summarize_blocks<D>(
+    db: &D,
+    request: &BlockSummaryRequest,
+) -> Result<BlockSummaryBatch, Error>
+where
+    D: DbRead,
+{
+    let max_blocks = request.max_blocks.min(crate::BULK_SYNC_MAX_BLOCKS) as usize;
+    let mut blocks = Vec::with_capacity(max_blocks);
+    let mut block_hash = request.chain_tip;
+
+    while blocks.len() < max_blocks {
+        let Some(block) = db.get_bitcoin_block(&block_hash).await? else {
+            break;
+        };
+        let Some(activity) = db.get_bitcoin_block_activity(&block_hash).await? else {
+            break;
+        };
+        block_hash = block.parent_hash;
+        blocks.push(BlockSummary::new(&block, &activity));
+    }
+
+    Ok(BlockSummaryBatch { blocks })
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::storage::DbWrite as _;
+    use crate::storage::memory::Store;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    fn chain(length: u64) -> Vec<BlockSummary> {
+        let mut rng = get_rng();
+        let mut blocks: Vec<BlockSummary> = Vec::new();
+        for height in (100..100 + length).rev() {
+            let block_hash = match blocks.last() {
+                Some(child) => child.parent_hash,
+                None => Faker.fake_with_rng(&mut rng),
+            };
+            blocks.push(BlockSummary {
+                block_hash,
+                block_height: height.into(),
+                parent_hash: Faker.fake_with_rng(&mut rng),
+                block_time: height,
+            });
+        }
+        blocks
+    }
+
+    #[test]
+    fn compressed_messages_round_trip() {
+        let batch = BlockSummaryBatch { blocks: chain(500) };
+        let message = SyncMessage::from(batch);
+
+        let uncompressed = message.clone().encode_to_vec();
+        let compressed = message.clone().encode_compressed().unwrap();
+        // The parent hash of each block is the block hash of the next
+        // one, so a chain of summaries compresses well.
+        assert!(compressed.len() < uncompressed.len() * 3 / 4);
+
+        let decoded = SyncMessage::decode_compressed(&compressed).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn garbage_is_not_decompressed() {
+        let result = SyncMessage::decode_compressed(&[1, 2, 3, 4]);
+        assert!(matches!(result, Err(Error::SyncMessageDecompression(_))));
+    }
+
+    #[tokio::test]
+    async fn summaries_follow_the_stored_blocks() {
+        let db = Store::new_shared();
+        let mut rng = get_rng();
+
+        // The parent of the first block has no recorded activity, like a
+        // block that was processed before activity was recorded.
+        let mut parent: BitcoinBlock = Faker.fake_with_rng(&mut rng);
+        parent.block_height = 99u64.into();
+        db.write_bitcoin_block(&parent).await.unwrap();
+        for _ in 0..10 {
+            let block = BitcoinBlock {
+                block_hash: Faker.fake_with_rng(&mut rng),
+                block_height: parent.block_height + 1,
+                parent_hash: parent.block_hash,
+            };
+            let activity = BitcoinBlockActivity {
+                block_hash: block.block_hash,
+                ..Faker.fake_with_rng(&mut rng)
+            };
+            db.write_bitcoin_block(&block).await.unwrap();
+            db.write_bitcoin_block_activity(&activity).await.unwrap();
+            parent = block;
+        }
+        let chain_tip = parent.block_hash;
+
+        let request = BlockSummaryRequest { chain_tip, max_blocks: 4 };
+        let batch = summarize_blocks(&db, &request).await.unwrap();
+        assert_eq!(batch.blocks.len(), 4);
+        assert!(batch.is_chain_ending_at(&chain_tip));
+
+        // We stop at the first block without any recorded activity.
+        let request = BlockSummaryRequest { chain_tip, max_blocks: 100 };
+        let batch = summarize_blocks(&db, &request).await.unwrap();
+        assert_eq!(batch.blocks.len(), 10);
+        assert!(batch.is_chain_ending_at(&chain_tip));
+
+        let request = BlockSummaryRequest {
+            chain_tip: Faker.fake_with_rng(&mut rng),
+            max_blocks: 100,
+        };
+        let batch = summarize_blocks(&db, &request).await.unwrap();
+        assert!(batch.blocks.is_empty());
+    }
+
+    #[test]
+    fn batches_must_form_a_chain_ending_at_the_tip() {
+        let blocks = chain(10);
+        let chain_tip = blocks[0].block_hash;
+
+        let batch = BlockSummaryBatch { blocks: blocks.clone() };
+        assert!(batch.is_chain_ending_at(&chain_tip));
+        assert!(!batch.is_chain_ending_at(&blocks[1].block_hash));
+
+        let mut gap = blocks.clone();
+        gap.remove(5);
+        assert!(!BlockSummaryBatch { blocks: gap }.is_chain_ending_at(&chain_tip));
+
+        let mut wrong_height = blocks.clone();
+        wrong_height[3].block_height = 0u64.into();
+        assert!(!BlockSummaryBatch { blocks: wrong_height }.is_chain_ending_at(&chain_tip));
+
+        assert!(!BlockSummaryBatch { blocks: Vec::new() }.is_chain_ending_at(&chain_tip));
+    }
+}
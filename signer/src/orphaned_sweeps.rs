@@ -0,0 +1,327 @@
+//! # Orphaned sweep transactions
+//!
+//! A sweep transaction that was confirmed in a bitcoin block can be
+//! orphaned by a reorg, leaving it unconfirmed on the canonical bitcoin
+//! blockchain. Once that happens the signers' UTXO on the canonical
+//! blockchain is the one that the sweep spent, and the requests that it
+//! fulfilled are pending again. Bitcoin core usually returns the
+//! transactions of disconnected blocks to its mempool, but not always,
+//! for example when the node restarted in the meantime or the
+//! transaction conflicts with one in the new blocks.
+//!
+//! The [`OrphanedSweepMonitor`] looks for orphaned sweeps whenever the
+//! block observer has processed a new bitcoin block. For each one it
+//! logs an error, records the [`Metrics::OrphanedSweepTransactionsTotal`]
+//! metric, and rebroadcasts the transaction if it is not in the mempool,
+//! so that the coordinator picks it up as the mempool sweep spending the
+//! signers' UTXO and bumps its fees if necessary. Webhook subscribers are
+//! notified that the completions of the deposits that the sweep swept in
+//! are provisional, since the sBTC was minted against a bitcoin block
+//! that is no longer canonical.
+
+use std::collections::HashSet;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::MAX_REORG_BLOCK_COUNT;
+use crate::bitcoin::BitcoinInteract as _;
+use crate::context::BitcoinBlockObserved;
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::OrphanedSweepTransaction;
+use crate::webhooks::WebhookEvent;
+
+/// The number of blocks below the chain tip to look for orphaned sweeps
+/// in. Reorgs deeper than this are not handled by the signers.
+const ORPHAN_WINDOW: u16 = MAX_REORG_BLOCK_COUNT as u16;
+
+/// What happened when we tried to rebroadcast an orphaned sweep
+/// transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebroadcastOutcome {
+    /// The transaction was already in the mempool of our bitcoin node.
+    InMempool,
+    /// The transaction was rebroadcast.
+    Rebroadcast,
+    /// Our bitcoin node does not know about the transaction anymore.
+    NotFound,
+    /// The bitcoin node rejected the transaction, most likely because
+    /// another transaction spending the same signers' UTXO has replaced
+    /// it.
+    Rejected,
+}
+
+impl RebroadcastOutcome {
+    /// The label of the outcome in metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InMempool => "in_mempool",
+            Self::Rebroadcast => "rebroadcast",
+            Self::NotFound => "not_found",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// Detects sweep transactions that were orphaned by bitcoin reorgs and
+/// puts them back into the mempool.
+pub struct OrphanedSweepMonitor<C> {
+    /// Signer context.
+    context: C,
+    /// The orphaned sweeps found in the most recent check, so that each
+    /// one is only handled once.
+    orphans: HashSet<BitcoinTxId>,
+}
+
+impl<C> OrphanedSweepMonitor<C>
+where
+    C: Context,
+{
+    /// Creates a new OrphanedSweepMonitor with the given context.
+    pub fn new(context: C) -> Self {
+        Self {
+            context,
+            orphans: HashSet::new(),
+        }
+    }
+
+    /// Runs the OrphanedSweepMonitor, which checks for orphaned sweeps
+    /// whenever the block observer has processed a new bitcoin block.
+    pub async fn run(mut self) {
+        let mut term = self.context.get_termination_handle();
+        let mut blocks = self.context.events().subscribe::<BitcoinBlockObserved>();
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                event = blocks.recv() => match event {
+                    Ok(BitcoinBlockObserved { chain_tip }) => self.check(&chain_tip).await,
+                    Err(RecvError::Lagged(_)) => {
+                        if let Some(chain_tip) = self.context.state().bitcoin_chain_tip() {
+                            self.check(&chain_tip).await;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        tracing::info!("orphaned sweep monitor has stopped");
+    }
+
+    /// Look for sweeps orphaned relative to the given chain tip, and
+    /// handle the ones that were not orphaned at the previous check.
+    #[tracing::instrument(
+        skip_all,
+        name = "orphaned-sweep-monitor",
+        fields(chain_tip = %chain_tip.block_hash)
+    )]
+    pub async fn check(&mut self, chain_tip: &BitcoinBlockRef) {
+        let orphaned = match self
+            .context
+            .get_storage()
+            .get_orphaned_sweep_transactions(chain_tip, ORPHAN_WINDOW)
+            .await
+        {
+            Ok(orphaned) => orphaned,
+            Err(error) => {
+                tracing::warn!(%error, "could not look up orphaned sweep transactions");
+                return;
+            }
+        };
+
+        let mut current: HashSet<BitcoinTxId> = orphaned.iter().map(|orphan| orphan.txid).collect();
+        Metrics::record_orphaned_sweeps(current.len());
+
+        for txid in self.orphans.difference(&current) {
+            tracing::info!(%txid, "previously orphaned sweep transaction is no longer orphaned");
+        }
+
+        let mut handled = HashSet::new();
+        for orphan in orphaned {
+            // A transaction can be confirmed in more than one orphaned
+            // block, and we only handle new orphans.
+            if self.orphans.contains(&orphan.txid) || !handled.insert(orphan.txid) {
+                continue;
+            }
+            if let Err(error) = self.handle_orphan(&orphan).await {
+                tracing::warn!(%error, txid = %orphan.txid, "could not handle orphaned sweep");
+                // Try again at the next check.
+                current.remove(&orphan.txid);
+            }
+        }
+
+        self.orphans = current;
+    }
+
+    async fn handle_orphan(&self, orphan: &OrphanedSweepTransaction) -> Result<(), Error> {
+        tracing::error!(
+            txid = %orphan.txid,
+            orphaned_block_hash = %orphan.block_hash,
+            orphaned_block_height = %orphan.block_height,
+            "sweep transaction was orphaned by a bitcoin reorg"
+        );
+
+        let outcome = self.rebroadcast(orphan).await?.as_str();
+        Metrics::increment_orphaned_sweeps(outcome);
+        tracing::info!(txid = %orphan.txid, outcome, "handled orphaned sweep transaction");
+
+        let completions = self
+            .context
+            .get_storage()
+            .get_completed_deposit_events_for_sweep(&orphan.txid)
+            .await?;
+        for event in completions {
+            tracing::warn!(
+                outpoint = %event.outpoint,
+                stacks_txid = %event.txid,
+                "deposit completion is provisional again after its sweep was orphaned"
+            );
+            let event = WebhookEvent::DepositCompletionProvisional(event);
+            let _ = self.context.get_signal_sender().send(event.into());
+        }
+        Ok(())
+    }
+
+    async fn rebroadcast(
+        &self,
+        orphan: &OrphanedSweepTransaction,
+    ) -> Result<RebroadcastOutcome, Error> {
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let txid = orphan.txid.into();
+        if bitcoin_client.get_mempool_entry(&txid).await?.is_some() {
+            return Ok(RebroadcastOutcome::InMempool);
+        }
+
+        let block_hash = orphan.block_hash.into();
+        let Some(tx_info) = bitcoin_client.get_tx_info(&txid, &block_hash).await? else {
+            return Ok(RebroadcastOutcome::NotFound);
+        };
+
+        match bitcoin_client.broadcast_transaction(&tx_info.tx).await {
+            Ok(()) => Ok(RebroadcastOutcome::Rebroadcast),
+            Err(error) => {
+                tracing::warn!(%error, %txid, "bitcoin node rejected the orphaned sweep");
+                Ok(RebroadcastOutcome::Rejected)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::bitcoin::rpc::BitcoinTxInfo;
+    use crate::context::SignerEvent;
+    use crate::context::SignerSignal;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// A sweep that was confirmed in a block that a reorg replaced is
+    /// rebroadcast once, and the completions of its deposits are reported
+    /// as provisional.
+    #[tokio::test]
+    async fn orphaned_sweeps_are_rebroadcast_and_reported() {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+        let mut rng = get_rng();
+
+        let genesis = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: 0u64.into(),
+            parent_hash: Faker.fake_with_rng(&mut rng),
+        };
+        let orphaned = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: 1u64.into(),
+            parent_hash: genesis.block_hash,
+        };
+        let canonical = model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            ..orphaned
+        };
+        for block in [&genesis, &orphaned, &canonical] {
+            db.write_bitcoin_block(block).await.unwrap();
+        }
+
+        let txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+        let prevout = model::TxPrevout {
+            txid,
+            prevout_type: model::TxPrevoutType::SignersInput,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_tx_prevout(&prevout).await.unwrap();
+        let tx_ref = model::BitcoinTxRef {
+            txid,
+            block_hash: orphaned.block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+
+        let completion = model::CompletedDepositEvent {
+            sweep_txid: txid,
+            sweep_block_hash: orphaned.block_hash,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_completed_deposit_event(&completion).await.unwrap();
+
+        // The sweep is not orphaned relative to the block that confirmed
+        // it.
+        let mut monitor = OrphanedSweepMonitor::new(ctx.clone());
+        monitor.check(&(&orphaned).into()).await;
+        assert!(monitor.orphans.is_empty());
+
+        ctx.with_bitcoin_client(|client| {
+            client
+                .expect_get_mempool_entry()
+                .times(1)
+                .returning(|_| Box::pin(std::future::ready(Ok(None))));
+            client.expect_get_tx_info().times(1).returning(|_, _| {
+                let tx_info = BitcoinTxInfo {
+                    fee: None,
+                    tx: bitcoin::Transaction {
+                        version: bitcoin::transaction::Version::TWO,
+                        lock_time: bitcoin::absolute::LockTime::ZERO,
+                        input: Vec::new(),
+                        output: Vec::new(),
+                    },
+                    vin: Vec::new(),
+                };
+                Box::pin(std::future::ready(Ok(Some(tx_info))))
+            });
+            client
+                .expect_broadcast_transaction()
+                .times(1)
+                .returning(|_| Box::pin(std::future::ready(Ok(()))));
+        })
+        .await;
+
+        let mut signals = ctx.get_signal_receiver();
+
+        // Checking the canonical chain tip twice only handles the orphan
+        // once.
+        monitor.check(&(&canonical).into()).await;
+        monitor.check(&(&canonical).into()).await;
+        assert_eq!(monitor.orphans, HashSet::from([txid]));
+
+        let mut notified = Vec::new();
+        while let Ok(signal) = signals.try_recv() {
+            if let SignerSignal::Event(SignerEvent::WebhookEvent(event)) = signal {
+                notified.push(*event);
+            }
+        }
+        assert_eq!(
+            notified,
+            vec![WebhookEvent::DepositCompletionProvisional(completion)]
+        );
+    }
+}
@@ -40,20 +40,29 @@ use wsts::traits::SignerState;
 
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::validation::TxRequestIds;
+use crate::checkpoint::StateCheckpoint;
+use crate::checkpoint::StateSummary;
 use crate::codec;
 use crate::ecdsa::Signed;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::message::BitcoinPreSignAck;
 use crate::message::BitcoinPreSignRequest;
+use crate::message::CoordinatorAnnouncement;
 use crate::message::Payload;
 use crate::message::SignerDepositDecision;
+use crate::message::SignerHeartbeat;
+use crate::message::SignerIdentityRotation;
 use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
 use crate::message::StacksTransactionSignRequest;
 use crate::message::StacksTransactionSignature;
 use crate::message::WstsMessage;
 use crate::message::WstsMessageId;
+use crate::network::sync::BlockSummary;
+use crate::network::sync::BlockSummaryBatch;
+use crate::network::sync::BlockSummaryRequest;
+use crate::network::sync::SyncMessage;
 use crate::proto;
 use crate::stacks::contracts::AcceptWithdrawalV1;
 use crate::stacks::contracts::CompleteDepositV1;
@@ -1232,6 +1241,12 @@ impl From<BitcoinPreSignRequest> for proto::BitcoinPreSignRequest {
                 .collect(),
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            lock_time: value.lock_time.to_consensus_u32(),
+            donations: value
+                .donations
+                .into_iter()
+                .map(proto::OutPoint::from)
+                .collect(),
         }
     }
 }
@@ -1247,6 +1262,12 @@ impl TryFrom<proto::BitcoinPreSignRequest> for BitcoinPreSignRequest {
                 .collect::<Result<Vec<_>, _>>()?,
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            lock_time: bitcoin::absolute::LockTime::from_consensus(value.lock_time),
+            donations: value
+                .donations
+                .into_iter()
+                .map(OutPoint::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
@@ -1263,6 +1284,100 @@ impl From<proto::BitcoinPreSignAck> for BitcoinPreSignAck {
     }
 }
 
+impl From<SignerIdentityRotation> for proto::SignerIdentityRotation {
+    fn from(value: SignerIdentityRotation) -> Self {
+        proto::SignerIdentityRotation {
+            new_public_key: Some(value.new_public_key.into()),
+            new_key_signature: Some(value.new_key_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::SignerIdentityRotation> for SignerIdentityRotation {
+    type Error = Error;
+    fn try_from(value: proto::SignerIdentityRotation) -> Result<Self, Self::Error> {
+        Ok(SignerIdentityRotation {
+            new_public_key: value.new_public_key.required()?.try_into()?,
+            new_key_signature: value.new_key_signature.required()?.try_into()?,
+        })
+    }
+}
+
+impl From<SignerHeartbeat> for proto::SignerHeartbeat {
+    fn from(value: SignerHeartbeat) -> Self {
+        proto::SignerHeartbeat {
+            unix_timestamp_millis: value.unix_timestamp_millis,
+            config_fingerprint: value.config_fingerprint.map(Into::into),
+            checkpoint: value.checkpoint.map(Into::into),
+            maintenance_mode: value.maintenance_mode,
+            serialization_digest: value.serialization_digest.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<proto::SignerHeartbeat> for SignerHeartbeat {
+    type Error = Error;
+    fn try_from(value: proto::SignerHeartbeat) -> Result<Self, Self::Error> {
+        Ok(SignerHeartbeat {
+            unix_timestamp_millis: value.unix_timestamp_millis,
+            config_fingerprint: value.config_fingerprint.map(Into::into),
+            checkpoint: value.checkpoint.map(TryInto::try_into).transpose()?,
+            maintenance_mode: value.maintenance_mode,
+            serialization_digest: value.serialization_digest.map(Into::into),
+        })
+    }
+}
+
+impl From<CoordinatorAnnouncement> for proto::CoordinatorAnnouncement {
+    fn from(value: CoordinatorAnnouncement) -> Self {
+        proto::CoordinatorAnnouncement {
+            bitcoin_block_hash: Some(value.bitcoin_block_hash.into()),
+            bitcoin_block_height: *value.bitcoin_block_height,
+        }
+    }
+}
+
+impl TryFrom<proto::CoordinatorAnnouncement> for CoordinatorAnnouncement {
+    type Error = Error;
+    fn try_from(value: proto::CoordinatorAnnouncement) -> Result<Self, Self::Error> {
+        Ok(CoordinatorAnnouncement {
+            bitcoin_block_hash: value.bitcoin_block_hash.required()?.try_into()?,
+            bitcoin_block_height: value.bitcoin_block_height.into(),
+        })
+    }
+}
+
+impl From<StateCheckpoint> for proto::StateCheckpoint {
+    fn from(value: StateCheckpoint) -> Self {
+        proto::StateCheckpoint {
+            bitcoin_block_hash: Some(value.bitcoin_block_hash.into()),
+            bitcoin_block_height: *value.bitcoin_block_height,
+            stacks_block_hash: Some(value.stacks_block_hash.into()),
+            stacks_block_height: *value.stacks_block_height,
+            completed_deposit_events: value.summary.completed_deposit_events,
+            key_rotation_events: value.summary.key_rotation_events,
+            state_hash: Some(value.summary.state_hash.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::StateCheckpoint> for StateCheckpoint {
+    type Error = Error;
+    fn try_from(value: proto::StateCheckpoint) -> Result<Self, Self::Error> {
+        Ok(StateCheckpoint {
+            bitcoin_block_hash: value.bitcoin_block_hash.required()?.try_into()?,
+            bitcoin_block_height: value.bitcoin_block_height.into(),
+            stacks_block_hash: value.stacks_block_hash.required()?.try_into()?,
+            stacks_block_height: value.stacks_block_height.into(),
+            summary: StateSummary {
+                completed_deposit_events: value.completed_deposit_events,
+                key_rotation_events: value.key_rotation_events,
+                state_hash: value.state_hash.required()?.into(),
+            },
+        })
+    }
+}
+
 impl From<SignerMessage> for proto::SignerMessage {
     fn from(value: SignerMessage) -> Self {
         proto::SignerMessage {
@@ -1306,6 +1421,15 @@ impl From<Payload> for proto::Payload {
             Payload::BitcoinPreSignAck(inner) => {
                 proto::signer_message::Payload::BitcoinPreSignAck(inner.into())
             }
+            Payload::SignerIdentityRotation(inner) => {
+                proto::signer_message::Payload::SignerIdentityRotation(inner.into())
+            }
+            Payload::SignerHeartbeat(inner) => {
+                proto::signer_message::Payload::SignerHeartbeat(inner.into())
+            }
+            Payload::CoordinatorAnnouncement(inner) => {
+                proto::signer_message::Payload::CoordinatorAnnouncement(inner.into())
+            }
         }
     }
 }
@@ -1335,6 +1459,15 @@ impl TryFrom<proto::Payload> for Payload {
             proto::signer_message::Payload::BitcoinPreSignAck(inner) => {
                 Payload::BitcoinPreSignAck(inner.into())
             }
+            proto::signer_message::Payload::SignerIdentityRotation(inner) => {
+                Payload::SignerIdentityRotation(inner.try_into()?)
+            }
+            proto::signer_message::Payload::SignerHeartbeat(inner) => {
+                Payload::SignerHeartbeat(inner.try_into()?)
+            }
+            proto::signer_message::Payload::CoordinatorAnnouncement(inner) => {
+                Payload::CoordinatorAnnouncement(inner.try_into()?)
+            }
         };
         Ok(payload)
     }
@@ -1631,6 +1764,98 @@ impl TryFrom<proto::DkgPublicShares> for BTreeMap<u32, DkgPublicShares> {
     }
 }
 
+impl From<BlockSummary> for proto::BlockSummary {
+    fn from(value: BlockSummary) -> Self {
+        proto::BlockSummary {
+            block_hash: Some(value.block_hash.into()),
+            block_height: *value.block_height,
+            parent_hash: Some(value.parent_hash.into()),
+            block_time: value.block_time,
+        }
+    }
+}
+
+impl TryFrom<proto::BlockSummary> for BlockSummary {
+    type Error = Error;
+    fn try_from(value: proto::BlockSummary) -> Result<Self, Self::Error> {
+        Ok(BlockSummary {
+            block_hash: value.block_hash.required()?.try_into()?,
+            block_height: value.block_height.into(),
+            parent_hash: value.parent_hash.required()?.try_into()?,
+            block_time: value.block_time,
+        })
+    }
+}
+
+impl From<BlockSummaryRequest> for proto::BlockSummaryRequest {
+    fn from(value: BlockSummaryRequest) -> Self {
+        proto::BlockSummaryRequest {
+            chain_tip: Some(value.chain_tip.into()),
+            max_blocks: value.max_blocks,
+        }
+    }
+}
+
+impl TryFrom<proto::BlockSummaryRequest> for BlockSummaryRequest {
+    type Error = Error;
+    fn try_from(value: proto::BlockSummaryRequest) -> Result<Self, Self::Error> {
+        Ok(BlockSummaryRequest {
+            chain_tip: value.chain_tip.required()?.try_into()?,
+            max_blocks: value.max_blocks,
+        })
+    }
+}
+
+impl From<BlockSummaryBatch> for proto::BlockSummaryBatch {
+    fn from(value: BlockSummaryBatch) -> Self {
+        proto::BlockSummaryBatch {
+            blocks: value.blocks.into_iter().map(|block| block.into()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::BlockSummaryBatch> for BlockSummaryBatch {
+    type Error = Error;
+    fn try_from(value: proto::BlockSummaryBatch) -> Result<Self, Self::Error> {
+        Ok(BlockSummaryBatch {
+            blocks: value
+                .blocks
+                .into_iter()
+                .map(|block| block.try_into())
+                .collect::<Result<Vec<_>, Error>>()?,
+        })
+    }
+}
+
+impl From<SyncMessage> for proto::SyncMessage {
+    fn from(value: SyncMessage) -> Self {
+        let payload = match value {
+            SyncMessage::BlockSummaryRequest(inner) => {
+                proto::sync_message::Payload::BlockSummaryRequest(inner.into())
+            }
+            SyncMessage::BlockSummaryBatch(inner) => {
+                proto::sync_message::Payload::BlockSummaryBatch(inner.into())
+            }
+        };
+        proto::SyncMessage { payload: Some(payload) }
+    }
+}
+
+impl TryFrom<proto::SyncMessage> for SyncMessage {
+    type Error = Error;
+    fn try_from(value: proto::SyncMessage) -> Result<Self, Self::Error> {
+        let message = match value.payload.required()? {
+            proto::sync_message::Payload::BlockSummaryRequest(inner) => {
+                SyncMessage::BlockSummaryRequest(inner.try_into()?)
+            }
+            proto::sync_message::Payload::BlockSummaryBatch(inner) => {
+                SyncMessage::BlockSummaryBatch(inner.try_into()?)
+            }
+        };
+        Ok(message)
+    }
+}
+
 impl codec::ProtoSerializable for SignerMessage {
     type Message = proto::SignerMessage;
 
@@ -1643,6 +1868,9 @@ impl codec::ProtoSerializable for SignerMessage {
             Payload::WstsMessage(_) => "SBTC_WSTS_MESSAGE",
             Payload::BitcoinPreSignRequest(_) => "SBTC_BITCOIN_PRE_SIGN_REQUEST",
             Payload::BitcoinPreSignAck(_) => "SBTC_BITCOIN_PRE_SIGN_ACK",
+            Payload::SignerIdentityRotation(_) => "SBTC_SIGNER_IDENTITY_ROTATION",
+            Payload::SignerHeartbeat(_) => "SBTC_SIGNER_HEARTBEAT",
+            Payload::CoordinatorAnnouncement(_) => "SBTC_COORDINATOR_ANNOUNCEMENT",
         }
     }
 }
@@ -1663,6 +1891,14 @@ impl codec::ProtoSerializable for SignerState {
     }
 }
 
+impl codec::ProtoSerializable for SyncMessage {
+    type Message = proto::SyncMessage;
+
+    fn type_tag(&self) -> &'static str {
+        "SBTC_SYNC_MESSAGE"
+    }
+}
+
 impl codec::ProtoSerializable for BTreeMap<u32, DkgPublicShares> {
     type Message = proto::DkgPublicShares;
 
@@ -1682,16 +1918,18 @@ mod tests {
     use fake::Dummy;
     use fake::Fake as _;
     use fake::Faker;
-    use rand::rngs::OsRng;
     use test_case::test_case;
 
+    use crate::testing::get_rng;
+
     #[test]
     fn conversion_between_bytes_and_uint256() {
+        let mut rng = get_rng();
         let number = proto::Uint256 {
-            bits_part0: Faker.fake_with_rng(&mut OsRng),
-            bits_part1: Faker.fake_with_rng(&mut OsRng),
-            bits_part2: Faker.fake_with_rng(&mut OsRng),
-            bits_part3: Faker.fake_with_rng(&mut OsRng),
+            bits_part0: Faker.fake_with_rng(&mut rng),
+            bits_part1: Faker.fake_with_rng(&mut rng),
+            bits_part2: Faker.fake_with_rng(&mut rng),
+            bits_part3: Faker.fake_with_rng(&mut rng),
         };
 
         let bytes = <[u8; 32]>::from(number);
@@ -1724,6 +1962,14 @@ mod tests {
     #[test_case(PhantomData::<(Fees, proto::Fees)>; "Fees")]
     #[test_case(PhantomData::<(BitcoinPreSignRequest, proto::BitcoinPreSignRequest)>; "BitcoinPreSignRequest")]
     #[test_case(PhantomData::<(BitcoinPreSignAck, proto::BitcoinPreSignAck)>; "BitcoinPreSignAck")]
+    #[test_case(PhantomData::<(SignerIdentityRotation, proto::SignerIdentityRotation)>; "SignerIdentityRotation")]
+    #[test_case(PhantomData::<(SignerHeartbeat, proto::SignerHeartbeat)>; "SignerHeartbeat")]
+    #[test_case(PhantomData::<(CoordinatorAnnouncement, proto::CoordinatorAnnouncement)>; "CoordinatorAnnouncement")]
+    #[test_case(PhantomData::<(StateCheckpoint, proto::StateCheckpoint)>; "StateCheckpoint")]
+    #[test_case(PhantomData::<(BlockSummary, proto::BlockSummary)>; "BlockSummary")]
+    #[test_case(PhantomData::<(BlockSummaryRequest, proto::BlockSummaryRequest)>; "BlockSummaryRequest")]
+    #[test_case(PhantomData::<(BlockSummaryBatch, proto::BlockSummaryBatch)>; "BlockSummaryBatch")]
+    #[test_case(PhantomData::<(SyncMessage, proto::SyncMessage)>; "SyncMessage")]
     fn convert_protobuf_type<T, U, E>(_: PhantomData<(T, U)>)
     where
         // `.unwrap()` requires that `E` implement `std::fmt::Debug` and
@@ -1732,11 +1978,12 @@ mod tests {
         U: From<T>,
         E: std::fmt::Debug,
     {
+        let mut rng = get_rng();
         // TODO: proptest
         for _ in 0..25 {
             // The type T originates from a signer. Let's create a random
             // instance of one.
-            let original: T = Faker.fake_with_rng(&mut OsRng);
+            let original: T = Faker.fake_with_rng(&mut rng);
             // The type U is a protobuf type. Before sending it to other
             // signers, we convert our internal type into it's protobuf
             // counterpart. We can always infallibly create U from T.
@@ -1792,9 +2039,10 @@ mod tests {
         U: From<T>,
         E: std::fmt::Debug,
     {
+        let mut rng = get_rng();
         // TODO: proptest
         for _ in 0..10 {
-            let original: T = Unit.fake_with_rng(&mut OsRng);
+            let original: T = Unit.fake_with_rng(&mut rng);
             let proto_original = U::from(original.clone());
 
             let original_from_proto = T::try_from(proto_original).unwrap();
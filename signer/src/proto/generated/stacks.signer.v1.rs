@@ -284,7 +284,7 @@ pub struct SignerMessage {
         super::super::super::bitcoin::BitcoinBlockHash,
     >,
     /// The message payload
-    #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11")]
+    #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11, 12, 13, 14")]
     pub payload: ::core::option::Option<signer_message::Payload>,
 }
 /// Nested message and enum types in `SignerMessage`.
@@ -313,6 +313,15 @@ pub mod signer_message {
         /// Represents an acknowledgment of a BitcoinPreSignRequest
         #[prost(message, tag = "11")]
         BitcoinPreSignAck(super::BitcoinPreSignAck),
+        /// An announcement that the sending signer is rotating its identity key
+        #[prost(message, tag = "12")]
+        SignerIdentityRotation(super::SignerIdentityRotation),
+        /// A periodic liveness message carrying the sender's wall clock time
+        #[prost(message, tag = "13")]
+        SignerHeartbeat(super::SignerHeartbeat),
+        /// An announcement that the sender is the coordinator for a tenure
+        #[prost(message, tag = "14")]
+        CoordinatorAnnouncement(super::CoordinatorAnnouncement),
     }
 }
 /// A wsts message.
@@ -415,10 +424,108 @@ pub struct BitcoinPreSignRequest {
     /// used this UTXO as an input.
     #[prost(message, optional, tag = "3")]
     pub last_fees: ::core::option::Option<Fees>,
+    /// The lock time of the transactions in the package. This is a block
+    /// height at or slightly below the bitcoin chain tip, which discourages
+    /// fee sniping, or zero when the signers have not turned this on.
+    #[prost(uint32, tag = "4")]
+    pub lock_time: u32,
+    /// The donation UTXOs locked by the signers' aggregate key that are
+    /// spent as extra inputs of the first transaction in the package, to
+    /// top up its fees.
+    #[prost(message, repeated, tag = "5")]
+    pub donations: ::prost::alloc::vec::Vec<super::super::super::bitcoin::OutPoint>,
 }
 /// Represents an acknowledgment of a BitcoinPreSignRequest.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct BitcoinPreSignAck {}
+/// An announcement from a signer that it is replacing its identity keypair
+/// with a new one. The enclosing message is signed by the old key.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SignerIdentityRotation {
+    /// The public key that the signer will use going forward.
+    #[prost(message, optional, tag = "1")]
+    pub new_public_key: ::core::option::Option<super::super::super::crypto::PublicKey>,
+    /// A signature, created with the new private key, over a digest that
+    /// commits to both the old and the new public keys.
+    #[prost(message, optional, tag = "2")]
+    pub new_key_signature: ::core::option::Option<
+        super::super::super::crypto::EcdsaSignature,
+    >,
+}
+/// A periodic liveness message from a signer. Receivers compare the
+/// timestamp against their own clock to estimate the clock skew between
+/// signers.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SignerHeartbeat {
+    /// The sender's wall clock time when the message was created, in
+    /// milliseconds since the unix epoch.
+    #[prost(uint64, tag = "1")]
+    pub unix_timestamp_millis: u64,
+    /// The fingerprint of the sender's consensus-critical configuration.
+    /// Signers that predate configuration fingerprints leave this unset.
+    #[prost(message, optional, tag = "2")]
+    pub config_fingerprint: ::core::option::Option<
+        super::super::super::crypto::Uint256,
+    >,
+    /// A checkpoint of the state that the sender has processed. Signers that
+    /// predate checkpoints, or could not take one, leave this unset.
+    #[prost(message, optional, tag = "3")]
+    pub checkpoint: ::core::option::Option<StateCheckpoint>,
+    /// Whether the sender is in read-only maintenance mode, where it does not
+    /// vote, sign or coordinate. Signers that predate maintenance mode leave
+    /// this unset.
+    #[prost(bool, tag = "4")]
+    pub maintenance_mode: bool,
+    /// The digest of the sender's serialization audit of consensus-critical
+    /// structures. Signers that do not run the audit leave this unset.
+    #[prost(message, optional, tag = "5")]
+    pub serialization_digest: ::core::option::Option<
+        super::super::super::crypto::Uint256,
+    >,
+}
+/// An announcement by a signer that it is the coordinator for the tenure at
+/// a bitcoin block. Receivers use it to detect competing coordinators.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CoordinatorAnnouncement {
+    /// The hash of the bitcoin block that the tenure is for.
+    #[prost(message, optional, tag = "1")]
+    pub bitcoin_block_hash: ::core::option::Option<
+        super::super::super::bitcoin::BitcoinBlockHash,
+    >,
+    /// The height of the bitcoin block that the tenure is for.
+    #[prost(uint64, tag = "2")]
+    pub bitcoin_block_height: u64,
+}
+/// A summary of the state that a signer has processed, up to and including
+/// a stacks block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StateCheckpoint {
+    /// The hash of the bitcoin chain tip when the checkpoint was taken.
+    #[prost(message, optional, tag = "1")]
+    pub bitcoin_block_hash: ::core::option::Option<
+        super::super::super::bitcoin::BitcoinBlockHash,
+    >,
+    /// The height of the bitcoin chain tip when the checkpoint was taken.
+    #[prost(uint64, tag = "2")]
+    pub bitcoin_block_height: u64,
+    /// The ID of the stacks block that the checkpoint is anchored to.
+    #[prost(message, optional, tag = "3")]
+    pub stacks_block_hash: ::core::option::Option<super::super::StacksBlockId>,
+    /// The height of the stacks block that the checkpoint is anchored to.
+    #[prost(uint64, tag = "4")]
+    pub stacks_block_height: u64,
+    /// The number of distinct completed deposit events in the stacks
+    /// blockchain ending at the anchor block.
+    #[prost(uint64, tag = "5")]
+    pub completed_deposit_events: u64,
+    /// The number of distinct key rotation events in the stacks blockchain
+    /// ending at the anchor block.
+    #[prost(uint64, tag = "6")]
+    pub key_rotation_events: u64,
+    /// A SHA-256 hash over the contents of all of these events.
+    #[prost(message, optional, tag = "7")]
+    pub state_hash: ::core::option::Option<super::super::super::crypto::Uint256>,
+}
 /// This type is a container for all deposits and withdrawals that are part
 /// of a transaction package.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -431,3 +538,63 @@ pub struct TxRequestIds {
     #[prost(message, repeated, tag = "2")]
     pub withdrawals: ::prost::alloc::vec::Vec<QualifiedRequestId>,
 }
+/// Messages exchanged between signers on the bulk sync gossip topic. These
+/// messages are compressed with zstd before they are published.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncMessage {
+    /// The message payload
+    #[prost(oneof = "sync_message::Payload", tags = "1, 2")]
+    pub payload: ::core::option::Option<sync_message::Payload>,
+}
+/// Nested message and enum types in `SyncMessage`.
+pub mod sync_message {
+    /// The message payload
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Payload {
+        /// A request for summaries of recent bitcoin blocks
+        #[prost(message, tag = "1")]
+        BlockSummaryRequest(super::BlockSummaryRequest),
+        /// A response with summaries of recent bitcoin blocks
+        #[prost(message, tag = "2")]
+        BlockSummaryBatch(super::BlockSummaryBatch),
+    }
+}
+/// A request, from a lagging signer, for summaries of the bitcoin blocks
+/// leading up to and including the given block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockSummaryRequest {
+    /// The hash of the most recent bitcoin block to summarize.
+    #[prost(message, optional, tag = "1")]
+    pub chain_tip: ::core::option::Option<super::super::super::bitcoin::BitcoinBlockHash>,
+    /// The maximum number of blocks to summarize.
+    #[prost(uint32, tag = "2")]
+    pub max_blocks: u32,
+}
+/// Summaries of consecutive bitcoin blocks, ordered from the block with
+/// the greatest height to the block with the least height.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockSummaryBatch {
+    /// The block summaries.
+    #[prost(message, repeated, tag = "1")]
+    pub blocks: ::prost::alloc::vec::Vec<BlockSummary>,
+}
+/// A summary of the header of a bitcoin block.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockSummary {
+    /// The hash of the block.
+    #[prost(message, optional, tag = "1")]
+    pub block_hash: ::core::option::Option<
+        super::super::super::bitcoin::BitcoinBlockHash,
+    >,
+    /// The height of the block.
+    #[prost(uint64, tag = "2")]
+    pub block_height: u64,
+    /// The hash of the parent block.
+    #[prost(message, optional, tag = "3")]
+    pub parent_hash: ::core::option::Option<
+        super::super::super::bitcoin::BitcoinBlockHash,
+    >,
+    /// The time field of the block header, in seconds since the unix epoch.
+    #[prost(uint64, tag = "4")]
+    pub block_time: u64,
+}
@@ -0,0 +1,98 @@
+//! # Quorum thresholds
+//!
+//! Ordinary sweeps and stacks transactions go ahead once the signing
+//! threshold of the signer set has approved them: the WSTS threshold for
+//! bitcoin and the `signatures-required` of the multi-sig wallet for
+//! stacks. Some actions are more consequential than that, and operators
+//! can require a larger quorum for them. The high-impact actions are
+//!
+//! * key rotations and contract deployments on stacks, and
+//! * sweep packages that move more than a configured amount of bitcoin.
+//!
+//! The coordinator enforces the larger quorum when it tallies the
+//! pre-sign acknowledgements of a sweep package and the signatures of a
+//! stacks transaction. It still only puts `signatures-required`
+//! signatures into a stacks transaction, since that is what its fee was
+//! estimated for; the extra signatures only serve as approvals.
+
+/// How consequential an action approved by the signers is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Impact {
+    /// An action that needs the ordinary signing threshold.
+    Ordinary,
+    /// An action that needs the high-impact threshold, when one is
+    /// configured.
+    High,
+}
+
+/// The quorum thresholds for actions approved by the signers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuorumThresholds {
+    /// The number of signers that must approve high-impact actions. The
+    /// ordinary threshold applies to all actions when this is `None`.
+    pub high_impact_signatures_required: Option<u16>,
+    /// The total amount, in sats, above which a sweep package is a
+    /// high-impact action. Sweeps are never high-impact when this is
+    /// `None`.
+    pub high_impact_sweep_amount: Option<u64>,
+}
+
+impl QuorumThresholds {
+    /// The number of signers that must approve an action with the given
+    /// impact, where `ordinary` is the ordinary signing threshold and
+    /// `num_signers` is the size of the signer set.
+    ///
+    /// The result is never lower than `ordinary`, and never higher than
+    /// `num_signers` unless `ordinary` is, so that a high-impact threshold
+    /// cannot make an action less strict or impossible to approve.
+    pub fn signatures_required(&self, impact: Impact, ordinary: u16, num_signers: u16) -> u16 {
+        match (impact, self.high_impact_signatures_required) {
+            (Impact::High, Some(high)) => high.min(num_signers).max(ordinary),
+            _ => ordinary,
+        }
+    }
+
+    /// The impact of a sweep package that moves the given amount of sats.
+    pub fn sweep_impact(&self, swept_amount: u64) -> Impact {
+        match self.high_impact_sweep_amount {
+            Some(limit) if swept_amount > limit => Impact::High,
+            _ => Impact::Ordinary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    const THRESHOLDS: QuorumThresholds = QuorumThresholds {
+        high_impact_signatures_required: Some(5),
+        high_impact_sweep_amount: Some(1_000_000),
+    };
+
+    #[test_case(THRESHOLDS, Impact::Ordinary, 3, 7, 3; "ordinary actions need the ordinary threshold")]
+    #[test_case(THRESHOLDS, Impact::High, 3, 7, 5; "high-impact actions need the high-impact threshold")]
+    #[test_case(THRESHOLDS, Impact::High, 3, 4, 4; "capped at the number of signers")]
+    #[test_case(THRESHOLDS, Impact::High, 6, 7, 6; "never below the ordinary threshold")]
+    #[test_case(QuorumThresholds::default(), Impact::High, 3, 7, 3; "no high-impact threshold")]
+    fn signatures_required(
+        thresholds: QuorumThresholds,
+        impact: Impact,
+        ordinary: u16,
+        num_signers: u16,
+        expected: u16,
+    ) {
+        let required = thresholds.signatures_required(impact, ordinary, num_signers);
+        assert_eq!(required, expected);
+    }
+
+    #[test]
+    fn large_sweeps_are_high_impact() {
+        assert_eq!(THRESHOLDS.sweep_impact(1_000_000), Impact::Ordinary);
+        assert_eq!(THRESHOLDS.sweep_impact(1_000_001), Impact::High);
+        let thresholds = QuorumThresholds::default();
+        assert_eq!(thresholds.sweep_impact(u64::MAX), Impact::Ordinary);
+    }
+}
@@ -0,0 +1,218 @@
+//! # Raw stacks events
+//!
+//! The raw bodies of the `POST /new_block` webhooks are kept, compressed
+//! with zstd, in the `raw_stacks_events` table for the configured
+//! [`raw_events_retention`](crate::config::EventObserverConfig::raw_events_retention).
+//! Operators use them to audit exactly what the stacks node sent, and to
+//! replay the events of a block through the API when a bug caused some of
+//! them to be dropped, since the stacks node never sends a webhook again
+//! once it has been acknowledged.
+//!
+//! Webhook bodies can be tens of megabytes, so they are compressed and
+//! written in a task of their own rather than while the stacks node waits
+//! for a response, and the bodies that fall out of the retention window
+//! are deleted periodically by the [`RawStacksEventPruner`].
+
+use std::time::Duration;
+
+use axum::body::Bytes;
+
+use crate::api::EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT;
+use crate::context::Context;
+use crate::error::Error;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::StacksBlockHash;
+
+/// The zstd compression level of the raw webhook bodies. Webhook bodies
+/// are JSON, which compresses well even at the fast levels.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Store the compressed raw body of the webhook of the stacks block with
+/// the given index block hash. Nothing is stored when the retention window
+/// is zero, and failures are only logged, since the bodies are not needed
+/// to handle the events of the block.
+pub async fn store(ctx: &impl Context, block_hash: StacksBlockHash, body: Bytes) {
+    let event_observer = &ctx.config().signer.event_observer;
+    if event_observer.raw_events_retention.is_zero() {
+        return;
+    }
+
+    // Compressing large bodies would hold up other tasks on the async
+    // runtime.
+    let compression = tokio::task::spawn_blocking(move || {
+        zstd::bulk::compress(&body, COMPRESSION_LEVEL).map_err(Error::RawStacksEventCompression)
+    });
+    let compressed = match compression.await {
+        Ok(Ok(compressed)) => compressed,
+        Ok(Err(error)) => {
+            tracing::warn!(%error, "could not compress the raw body of the webhook");
+            return;
+        }
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(error) => {
+            tracing::warn!(%error, "compressing the raw body of the webhook was cancelled");
+            return;
+        }
+    };
+
+    let storage = ctx.get_storage_mut();
+    if let Err(error) = storage
+        .write_raw_stacks_event(&block_hash, &compressed)
+        .await
+    {
+        tracing::warn!(%error, "could not store the raw body of the webhook");
+    }
+}
+
+/// Fetch and decompress the stored raw body of the webhook of the stacks
+/// block with the given index block hash, if it is still stored.
+pub async fn load(
+    ctx: &impl Context,
+    block_hash: &StacksBlockHash,
+) -> Result<Option<Vec<u8>>, Error> {
+    let Some(compressed) = ctx.get_storage().get_raw_stacks_event(block_hash).await? else {
+        return Ok(None);
+    };
+
+    // Bodies were held to this limit when they were received, so anything
+    // larger is not a body that we stored.
+    let decompression = tokio::task::spawn_blocking(move || {
+        zstd::bulk::decompress(&compressed, EVENT_OBSERVER_DECOMPRESSED_BODY_LIMIT)
+            .map_err(Error::RawStacksEventDecompression)
+    });
+    match decompression.await {
+        Ok(body) => body.map(Some),
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(error) => {
+            let error = std::io::Error::other(error);
+            Err(Error::RawStacksEventDecompression(error))
+        }
+    }
+}
+
+/// Deletes the raw webhook bodies that are older than the retention
+/// window.
+pub struct RawStacksEventPruner<C> {
+    /// Signer context.
+    context: C,
+    /// Pruning interval.
+    interval: Duration,
+}
+
+impl<C> RawStacksEventPruner<C>
+where
+    C: Context,
+{
+    /// Creates a new RawStacksEventPruner with the given context and
+    /// interval.
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self { context, interval }
+    }
+
+    /// Runs the RawStacksEventPruner, which prunes the raw webhook bodies
+    /// right away and then once every interval.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        self.prune().await;
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.prune().await;
+                }
+            }
+        }
+        tracing::info!("raw stacks event pruner has stopped");
+    }
+
+    /// Delete the raw webhook bodies that were stored before the start of
+    /// the retention window. When the retention window is zero nothing is
+    /// stored, and whatever was stored before is kept until the window is
+    /// set again.
+    #[tracing::instrument(skip_all, name = "raw-stacks-event-pruner")]
+    pub async fn prune(&self) {
+        let event_observer = &self.context.config().signer.event_observer;
+        let retention = event_observer.raw_events_retention;
+        if retention.is_zero() {
+            return;
+        }
+
+        // A retention window that reaches back before the earliest time
+        // that can be represented means that nothing has expired.
+        let Some(before) = time::Duration::try_from(retention)
+            .ok()
+            .and_then(|retention| time::OffsetDateTime::now_utc().checked_sub(retention))
+        else {
+            return;
+        };
+
+        let storage = self.context.get_storage_mut();
+        if let Err(error) = storage.delete_raw_stacks_events_before(before.into()).await {
+            tracing::warn!(%error, "could not delete the expired raw bodies of webhooks");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+
+    use crate::testing::context::*;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// Check that the raw bodies of webhooks are stored compressed when a
+    /// retention window is configured, and that the ones that are older
+    /// than the window are pruned.
+    #[tokio::test]
+    async fn raw_webhook_bodies_are_kept_for_the_retention_window() {
+        let retention = Duration::from_secs(3600);
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.raw_events_retention = retention;
+            })
+            .build();
+
+        let db = ctx.inner_storage();
+        let mut rng = get_rng();
+        let expired_block_hash: StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let expired_at = time::OffsetDateTime::now_utc() - 2 * retention;
+        db.lock()
+            .await
+            .raw_stacks_events
+            .insert(expired_block_hash, (Vec::new(), expired_at.into()));
+
+        let block_hash: StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let body = Bytes::from_static(br#"{"events": []}"#);
+        store(&ctx, block_hash, body.clone()).await;
+
+        let (compressed, _) = db.lock().await.raw_stacks_events[&block_hash].clone();
+        assert_ne!(compressed, body.to_vec());
+        let loaded = load(&ctx, &block_hash).await.unwrap();
+        assert_eq!(loaded.as_deref(), Some(body.as_ref()));
+
+        RawStacksEventPruner::new(ctx.clone(), Duration::from_secs(60))
+            .prune()
+            .await;
+
+        let stored = db.lock().await;
+        assert!(!stored.raw_stacks_events.contains_key(&expired_block_hash));
+        assert!(stored.raw_stacks_events.contains_key(&block_hash));
+    }
+
+    #[tokio::test]
+    async fn raw_webhook_bodies_are_not_kept_by_default() {
+        let ctx = TestContext::default_mocked();
+        let block_hash: StacksBlockHash = fake::Faker.fake_with_rng(&mut get_rng());
+
+        store(&ctx, block_hash, Bytes::from_static(b"{}")).await;
+
+        assert!(load(&ctx, &block_hash).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,355 @@
+//! # Reclaim simulation
+//!
+//! Depositors can take their funds back through the reclaim script-path
+//! of the deposit UTXO once the lock time in the reclaim script has
+//! elapsed. This module constructs the reclaim transaction template for a
+//! stored deposit request and reports whether the reclaim path is
+//! currently spendable. It is meant for monitoring deposits that are
+//! about to become reclaimable and for helping depositors whose deposits
+//! were not swept.
+//!
+//! The signers do not know the inputs to the depositor's part of the
+//! reclaim script, typically a signature, so the template carries the
+//! reclaim script and its control block but not the inputs to the
+//! script. The depositor has to add them to the front of the witness
+//! before broadcasting the transaction.
+
+use bitcoin::Amount;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Sequence;
+use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::TxOut;
+use bitcoin::Witness;
+use bitcoin::absolute;
+use bitcoin::consensus::encode::serialize_hex;
+use bitcoin::hex::DisplayHex as _;
+use bitcoin::taproot::ControlBlock;
+use bitcoin::taproot::LeafVersion;
+use bitcoin::transaction::Version;
+use sbtc::deposits::ReclaimScriptInputs;
+
+use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::validation::DepositConfirmationStatus;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::DepositRequest;
+
+/// An unsigned transaction that spends a deposit UTXO through the reclaim
+/// script-path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReclaimTemplate {
+    /// The reclaim transaction. Its witness holds the reclaim script and
+    /// the control block, but not the inputs to the reclaim script.
+    pub transaction: Transaction,
+    /// The full reclaim script of the deposit.
+    pub reclaim_script: ScriptBuf,
+    /// The control block proving that the reclaim script is a leaf of the
+    /// deposit UTXO's taproot tree.
+    pub control_block: ControlBlock,
+}
+
+impl ReclaimTemplate {
+    /// Construct the template of a transaction that sends the deposited
+    /// amount, less the given fee, to the given destination through the
+    /// reclaim script-path of the deposit.
+    pub fn new(
+        deposit: &DepositRequest,
+        destination: ScriptBuf,
+        fee: Amount,
+    ) -> Result<Self, Error> {
+        let reclaim_script = ScriptBuf::from_bytes(deposit.reclaim_script.clone());
+        let deposit_script = ScriptBuf::from_bytes(deposit.spend_script.clone());
+        let reclaim = ReclaimScriptInputs::parse(&reclaim_script)?;
+
+        let amount = Amount::from_sat(deposit.amount);
+        let value = amount
+            .checked_sub(fee)
+            .filter(|value| *value > Amount::ZERO)
+            .ok_or(Error::ReclaimFeeTooHigh { amount, fee })?;
+
+        let taproot = sbtc::deposits::to_taproot(deposit_script, reclaim_script.clone());
+        let control_block = taproot
+            .control_block(&(reclaim_script.clone(), LeafVersion::TapScript))
+            .ok_or(Error::MissingReclaimLeaf(deposit.outpoint()))?;
+
+        let witness = [reclaim_script.to_bytes(), control_block.serialize()];
+        let transaction = Transaction {
+            // OP_CHECKSEQUENCEVERIFY only enforces relative lock times in
+            // version 2 transactions and later.
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: deposit.outpoint(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::from_consensus(reclaim.lock_time()),
+                witness: Witness::from_slice(&witness),
+            }],
+            output: vec![TxOut {
+                value,
+                script_pubkey: destination,
+            }],
+        };
+
+        Ok(Self {
+            transaction,
+            reclaim_script,
+            control_block,
+        })
+    }
+}
+
+/// Whether the reclaim path of a deposit can be spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReclaimStatus {
+    /// The deposit transaction has not been confirmed on the canonical
+    /// bitcoin blockchain, so the lock time has not started.
+    Unconfirmed,
+    /// The lock time has not elapsed. The reclaim transaction can first be
+    /// confirmed in the block at the given height.
+    Locked(BitcoinBlockHeight),
+    /// The lock time has elapsed, so the reclaim transaction can be
+    /// confirmed in the next block.
+    Spendable,
+    /// The signers swept the deposit in the given transaction.
+    Swept(BitcoinTxId),
+    /// The deposit UTXO was spent by a transaction other than a sweep,
+    /// most likely a reclaim.
+    Reclaimed,
+}
+
+impl ReclaimStatus {
+    /// The status of a deposit confirmed at `confirmed_height` whose
+    /// reclaim script has the given lock time, in blocks, given the
+    /// height of the bitcoin chain tip.
+    ///
+    /// A transaction spending the deposit through the reclaim path can be
+    /// confirmed in blocks at least `lock_time` blocks above the block
+    /// confirming the deposit.
+    pub fn from_lock_time(
+        confirmed_height: BitcoinBlockHeight,
+        lock_time: u32,
+        chain_tip_height: BitcoinBlockHeight,
+    ) -> Self {
+        let spendable_height = confirmed_height + u64::from(lock_time);
+        if chain_tip_height + 1 >= spendable_height {
+            ReclaimStatus::Spendable
+        } else {
+            ReclaimStatus::Locked(spendable_height)
+        }
+    }
+}
+
+/// The outcome of simulating a reclaim of a stored deposit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReclaimSimulation {
+    /// The deposit UTXO.
+    pub outpoint: OutPoint,
+    /// The bitcoin chain tip that the status was computed against.
+    pub chain_tip: BitcoinBlockRef,
+    /// Whether the reclaim path can be spent.
+    pub status: ReclaimStatus,
+    /// The reclaim transaction template.
+    pub template: ReclaimTemplate,
+}
+
+impl ReclaimSimulation {
+    /// Simulate a reclaim of the stored deposit with the given outpoint,
+    /// sending the reclaimed funds to the given destination, or to the
+    /// first address that funded the deposit if there is none. Returns
+    /// `Ok(None)` if we do not have a record of the deposit.
+    pub async fn run<S, B>(
+        storage: &S,
+        bitcoin_client: &B,
+        signer_public_key: &PublicKey,
+        outpoint: OutPoint,
+        destination: Option<ScriptBuf>,
+        fee: Amount,
+    ) -> Result<Option<Self>, Error>
+    where
+        S: DbRead,
+        B: BitcoinInteract,
+    {
+        let txid = outpoint.txid.into();
+        let Some(deposit) = storage.get_deposit_request(&txid, outpoint.vout).await? else {
+            return Ok(None);
+        };
+
+        let destination = destination
+            .or_else(|| {
+                let sender = deposit.sender_script_pub_keys.first()?;
+                Some(ScriptBuf::from(sender.clone()))
+            })
+            .ok_or(Error::MissingReclaimDestination(outpoint))?;
+        let template = ReclaimTemplate::new(&deposit, destination, fee)?;
+
+        let chain_tip = storage
+            .get_bitcoin_canonical_chain_tip_ref()
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let report = storage
+            .get_deposit_request_report(
+                &chain_tip.block_hash,
+                &txid,
+                outpoint.vout,
+                signer_public_key,
+            )
+            .await?
+            .ok_or(Error::MissingDepositRequest(outpoint))?;
+
+        let status = match report.status {
+            DepositConfirmationStatus::Unconfirmed => ReclaimStatus::Unconfirmed,
+            DepositConfirmationStatus::Spent(txid) => ReclaimStatus::Swept(txid),
+            DepositConfirmationStatus::Reclaimed => ReclaimStatus::Reclaimed,
+            DepositConfirmationStatus::Confirmed(confirmed_height, _) => {
+                // Only the signers and the depositor can spend the deposit
+                // UTXO, and we already know about the signers' sweeps.
+                let txout = bitcoin_client
+                    .get_transaction_output(&outpoint, false)
+                    .await?;
+                if txout.is_none() {
+                    ReclaimStatus::Reclaimed
+                } else {
+                    let lock_time = report.lock_time.to_consensus_u32();
+                    ReclaimStatus::from_lock_time(
+                        confirmed_height,
+                        lock_time,
+                        chain_tip.block_height,
+                    )
+                }
+            }
+        };
+
+        Ok(Some(Self {
+            outpoint,
+            chain_tip,
+            status,
+            template,
+        }))
+    }
+
+    /// A JSON report of the simulation, for sharing with depositors.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (status, spendable_height, sweep_txid) = match self.status {
+            ReclaimStatus::Unconfirmed => ("unconfirmed", None, None),
+            ReclaimStatus::Locked(height) => ("locked", Some(height), None),
+            ReclaimStatus::Spendable => ("spendable", None, None),
+            ReclaimStatus::Swept(txid) => ("swept", None, Some(txid.to_string())),
+            ReclaimStatus::Reclaimed => ("reclaimed", None, None),
+        };
+
+        serde_json::json!({
+            "outpoint": self.outpoint.to_string(),
+            "chain_tip": {
+                "block_hash": self.chain_tip.block_hash.to_string(),
+                "block_height": self.chain_tip.block_height,
+            },
+            "status": status,
+            "spendable_height": spendable_height,
+            "sweep_txid": sweep_txid,
+            "reclaim_script": self.template.reclaim_script.to_hex_string(),
+            "control_block": self.template.control_block.serialize().to_lower_hex_string(),
+            "transaction": serialize_hex(&self.template.transaction),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::consensus::Encodable as _;
+    use bitcoin::opcodes::OP_TRUE;
+    use bitcoin::opcodes::all::OP_DROP;
+    use fake::Fake as _;
+    use fake::Faker;
+    use test_case::test_case;
+
+    use super::*;
+
+    /// A deposit whose reclaim script anyone can spend once the lock time
+    /// has elapsed, so that the template can be checked by the script
+    /// interpreter without a signature.
+    fn anyone_can_reclaim_deposit(lock_time: u32, amount: u64) -> (DepositRequest, TxOut) {
+        let user_script = ScriptBuf::builder()
+            .push_opcode(OP_DROP)
+            .push_opcode(OP_TRUE)
+            .into_script();
+        let setup = sbtc::testing::deposits::tx_setup_with_reclaim_user_script(
+            lock_time,
+            1_000,
+            &[amount],
+            &user_script,
+        );
+
+        let mut deposit: DepositRequest = Faker.fake();
+        deposit.txid = setup.tx.compute_txid().into();
+        deposit.output_index = 0;
+        deposit.amount = amount;
+        deposit.lock_time = lock_time;
+        deposit.spend_script = setup.deposits[0].deposit_script().to_bytes();
+        deposit.reclaim_script = setup.reclaims[0].reclaim_script().to_bytes();
+        (deposit, setup.tx.output[0].clone())
+    }
+
+    fn verify(template: &ReclaimTemplate, prevout: &TxOut) -> Result<(), bitcoinconsensus::Error> {
+        let mut tx_bytes = Vec::new();
+        template
+            .transaction
+            .consensus_encode(&mut tx_bytes)
+            .unwrap();
+        let script_pubkey = prevout.script_pubkey.as_bytes();
+        let utxo = bitcoinconsensus::Utxo {
+            script_pubkey: script_pubkey.as_ptr(),
+            script_pubkey_len: script_pubkey.len() as u32,
+            value: prevout.value.to_sat() as i64,
+        };
+        let flags = bitcoinconsensus::VERIFY_ALL_PRE_TAPROOT | bitcoinconsensus::VERIFY_TAPROOT;
+        bitcoinconsensus::verify_with_flags(
+            script_pubkey,
+            prevout.value.to_sat(),
+            &tx_bytes,
+            Some(&[utxo]),
+            0,
+            flags,
+        )
+    }
+
+    #[test]
+    fn reclaim_template_spends_the_deposit() {
+        let (deposit, prevout) = anyone_can_reclaim_deposit(144, 100_000);
+        let destination = ScriptBuf::new_op_return([1, 2, 3]);
+        let fee = Amount::from_sat(2_000);
+
+        let template = ReclaimTemplate::new(&deposit, destination.clone(), fee).unwrap();
+        let tx = &template.transaction;
+        assert_eq!(tx.input[0].previous_output, deposit.outpoint());
+        assert_eq!(tx.input[0].sequence, Sequence::from_height(144));
+        assert_eq!(tx.output[0].value, Amount::from_sat(98_000));
+        assert_eq!(tx.output[0].script_pubkey, destination);
+        verify(&template, &prevout).unwrap();
+
+        // The reclaim script rejects spends before the lock time.
+        let mut early = template.clone();
+        early.transaction.input[0].sequence = Sequence::from_height(143);
+        assert!(verify(&early, &prevout).is_err());
+    }
+
+    #[test]
+    fn reclaim_fee_cannot_exceed_the_deposit() {
+        let (deposit, _) = anyone_can_reclaim_deposit(144, 1_000);
+        let fee = Amount::from_sat(1_000);
+        let result = ReclaimTemplate::new(&deposit, ScriptBuf::new(), fee);
+        assert!(matches!(result, Err(Error::ReclaimFeeTooHigh { .. })));
+    }
+
+    #[test_case(100, 144, 242 => ReclaimStatus::Locked(244u64.into()); "two blocks left")]
+    #[test_case(100, 144, 243 => ReclaimStatus::Spendable; "next block")]
+    #[test_case(100, 144, 300 => ReclaimStatus::Spendable; "long elapsed")]
+    fn reclaim_status_from_lock_time(confirmed: u64, lock_time: u32, tip: u64) -> ReclaimStatus {
+        ReclaimStatus::from_lock_time(confirmed.into(), lock_time, tip.into())
+    }
+}
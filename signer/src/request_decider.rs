@@ -7,11 +7,16 @@
 
 use std::time::Duration;
 
+use crate::MAX_PEER_CLOCK_SKEW_MILLIS;
 use crate::block_observer::BlockObserver;
 use crate::blocklist_client::BlocklistChecker;
+use crate::checkpoint::CheckpointVerification;
+use crate::checkpoint::StateCheckpoint;
+use crate::config::fingerprint::ConsensusConfig;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::RequestDeciderEvent;
+use crate::context::RequestsUpdated;
 use crate::context::SignerCommand;
 use crate::context::SignerEvent;
 use crate::context::SignerSignal;
@@ -21,11 +26,16 @@ use crate::emily_client::EmilyInteract as _;
 use crate::error::Error;
 use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
+use crate::message::CoordinatorAnnouncement;
 use crate::message::Payload;
 use crate::message::SignerDepositDecision;
+use crate::message::SignerHeartbeat;
+use crate::message::SignerIdentityRotation;
 use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
+use crate::metrics::Metrics;
 use crate::network::MessageTransfer;
+use crate::serialization_audit;
 use crate::storage::DbRead as _;
 use crate::storage::DbWrite as _;
 use crate::storage::model;
@@ -33,6 +43,7 @@ use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinBlockRef;
 use crate::storage::model::DepositSigner;
 use crate::storage::model::WithdrawalSigner;
+use crate::transaction_coordinator::given_key_is_coordinator;
 
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
@@ -47,7 +58,8 @@ pub struct RequestDeciderEventLoop<C, N, B> {
     pub network: N,
     /// Blocklist checker.
     pub blocklist_checker: Option<B>,
-    /// Private key of the signer for network communication.
+    /// Private key of the slot of the signer in the signer set, used for
+    /// network communication.
     pub signer_private_key: PrivateKey,
     /// How many bitcoin blocks back from the chain tip the signer will look for requests.
     pub context_window: u16,
@@ -106,6 +118,23 @@ where
                             tracing::warn!(%error, "error handling new requests; skipping this round");
                         }
 
+                        if let Err(error) = self.announce_identity_rotation(&chain_tip).await {
+                            tracing::warn!(%error, "error announcing signer identity rotation");
+                        }
+
+                        let fingerprint = ConsensusConfig::new(self.context.config()).fingerprint();
+                        let checkpoint = self.take_state_checkpoint(&chain_tip).await;
+                        let maintenance_mode = self.context.state().maintenance_mode();
+                        let digest = self.serialization_digest();
+                        let heartbeat =
+                            SignerHeartbeat::now(fingerprint, checkpoint, maintenance_mode, digest);
+                        if let Err(error) =
+                            self.send_message(heartbeat, &chain_tip.block_hash).await
+                        {
+                            tracing::warn!(%error, "error sending signer heartbeat");
+                        }
+
+                        self.context.events().publish(RequestsUpdated { chain_tip });
                         let message = RequestDeciderEvent::NewRequestsHandled(chain_tip).into();
                         // If there is an error here then the application
                         // is on its way down since
@@ -131,6 +160,30 @@ where
         bitcoin_tip_height = %block_ref.block_height,
     ))]
     pub async fn handle_new_requests(&mut self, block_ref: BitcoinBlockRef) -> Result<(), Error> {
+        // Votes cast on inconsistent views of the bitcoin and stacks
+        // blockchains can be invalid, so we hold back until the views
+        // agree again.
+        if !self.context.state().chain_views_consistent() {
+            tracing::warn!(
+                "our bitcoin and stacks chain views are inconsistent; not voting on requests"
+            );
+            return Ok(());
+        }
+
+        // Our view of the bitcoin blockchain is incomplete while the
+        // bitcoin node is bootstrapping.
+        if !self.context.state().bitcoin_node_ready() {
+            tracing::info!("the bitcoin node is warming up; not voting on requests");
+            return Ok(());
+        }
+
+        // In maintenance mode we keep recording the votes of the other
+        // signers, but abstain from voting ourselves.
+        if self.context.state().maintenance_mode() {
+            tracing::debug!("we are in maintenance mode; not voting on requests");
+            return Ok(());
+        }
+
         let requests_processing_delay = self.context.config().signer.requests_processing_delay;
         if requests_processing_delay > Duration::ZERO {
             tracing::debug!("sleeping before processing new requests");
@@ -214,15 +267,41 @@ where
     #[tracing::instrument(skip_all)]
     async fn handle_signer_message(&mut self, msg: &Signed<SignerMessage>) -> Result<(), Error> {
         tracing::trace!(payload = %msg.inner.payload, "handling message");
+        // Signers that rotated their identity are known by the key of
+        // their slot, which is the key that our DKG shares and the votes
+        // that we store use.
+        let sender = self
+            .context
+            .state()
+            .current_signer_set()
+            .slot_public_key(&msg.signer_public_key);
+
         match &msg.inner.payload {
             Payload::SignerDepositDecision(decision) => {
-                self.persist_received_deposit_decision(decision, msg.signer_public_key)
+                self.persist_received_deposit_decision(decision, sender)
                     .await?;
             }
             Payload::SignerWithdrawalDecision(decision) => {
-                self.persist_received_withdraw_decision(decision, msg.signer_public_key)
+                self.persist_received_withdraw_decision(decision, sender)
                     .await?;
             }
+            Payload::SignerIdentityRotation(rotation) => {
+                self.persist_received_identity_rotation(
+                    rotation,
+                    msg.signer_public_key,
+                    msg.inner.bitcoin_chain_tip,
+                )
+                .await?;
+            }
+            Payload::SignerHeartbeat(heartbeat) => {
+                self.handle_heartbeat(heartbeat, sender);
+                if let Some(checkpoint) = &heartbeat.checkpoint {
+                    self.verify_state_checkpoint(checkpoint, sender).await?;
+                }
+            }
+            Payload::CoordinatorAnnouncement(announcement) => {
+                self.handle_coordinator_announcement(announcement, sender);
+            }
             Payload::StacksTransactionSignRequest(_)
             | Payload::BitcoinPreSignRequest(_)
             | Payload::BitcoinPreSignAck(_)
@@ -244,7 +323,10 @@ where
     ///
     /// If the block list client is not configured then the first check
     /// always passes.
-    #[tracing::instrument(skip_all)]
+    #[tracing::instrument(skip_all, fields(
+        outpoint = %request.outpoint(),
+        correlation_id = tracing::field::Empty,
+    ))]
     pub async fn handle_pending_deposit_request(
         &mut self,
         request: model::DepositRequest,
@@ -252,6 +334,13 @@ where
     ) -> Result<(), Error> {
         let db = self.context.get_storage_mut();
 
+        let correlation_id = db
+            .get_deposit_correlation_id(&request.txid, request.output_index)
+            .await?;
+        if let Some(correlation_id) = correlation_id {
+            tracing::Span::current().record("correlation_id", correlation_id);
+        }
+
         let signer_public_key = self.signer_public_key();
         // Let's find out whether or not we can even sign for this deposit
         // request. If we cannot then we do not even reach out to the
@@ -466,7 +555,9 @@ where
                 .await?;
 
             if let Some(request) = deposit_request {
-                processor.load_requests(&[request]).await?;
+                processor
+                    .load_requests(&[request], model::DepositRequestSource::P2p)
+                    .await?;
             }
         }
         // We still might not have a record of the deposit request (perhaps
@@ -517,6 +608,310 @@ where
         Ok(())
     }
 
+    /// Announce to the other signers that this signer is rotating its
+    /// identity key to the one configured in `signer.next_private_key`.
+    ///
+    /// The announcement is resent on every new bitcoin block so that
+    /// signers that were offline also learn about it. Once the operator
+    /// has switched `signer.private_key` to the new key and removed
+    /// `signer.next_private_key`, nothing is sent.
+    #[tracing::instrument(skip_all)]
+    pub async fn announce_identity_rotation(
+        &mut self,
+        chain_tip: &BitcoinBlockRef,
+    ) -> Result<(), Error> {
+        let Some(next_private_key) = &self.context.config().signer.next_private_key else {
+            return Ok(());
+        };
+
+        let old_public_key = self.signer_public_key();
+        let msg = SignerIdentityRotation::new(old_public_key, next_private_key);
+        if msg.new_public_key == old_public_key {
+            return Ok(());
+        }
+
+        tracing::info!(
+            new_public_key = %msg.new_public_key,
+            "announcing signer identity rotation"
+        );
+        self.send_message(msg, &chain_tip.block_hash).await
+    }
+
+    /// Persist an identity rotation announced by another signer and map
+    /// the new public key onto the slot of the old one in the signer set.
+    ///
+    /// Only the first rotation announced with an old public key is
+    /// accepted. Later announcements with a different new public key are
+    /// ignored, so that a signer cannot add more than one key to the
+    /// allowlist.
+    ///
+    /// The old public key keeps its slot, since it is the key in the
+    /// signer set of the registry contract and in our DKG shares. The new
+    /// key resolves to that slot, so the signer set does not grow and no
+    /// new DKG round is needed.
+    #[tracing::instrument(skip_all, fields(sender = %signer_pub_key))]
+    pub async fn persist_received_identity_rotation(
+        &mut self,
+        rotation: &SignerIdentityRotation,
+        signer_pub_key: PublicKey,
+        bitcoin_chain_tip: BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let signer_set = self.context.state().current_signer_set();
+        if !signer_set.is_signer(&signer_pub_key) {
+            tracing::warn!("identity rotation announced by a non-signer; ignoring");
+            return Ok(());
+        }
+        // A signer rotates the key of its slot, and only once.
+        if signer_set.slot_public_key(&signer_pub_key) != signer_pub_key {
+            tracing::warn!("identity rotation announced with a rotated key; ignoring");
+            return Ok(());
+        }
+
+        rotation.verify(&signer_pub_key)?;
+
+        let record = model::SignerIdentityRotation {
+            old_public_key: signer_pub_key,
+            new_public_key: rotation.new_public_key,
+            bitcoin_chain_tip,
+        };
+        self.context
+            .get_storage_mut()
+            .write_signer_identity_rotation(&record)
+            .await?;
+
+        // The write is a no-op if the old key has already been rotated, or
+        // if the new key is taken, so we check that the stored rotation is
+        // this one.
+        let accepted = self
+            .context
+            .get_storage()
+            .get_signer_identity_rotations()
+            .await?
+            .into_iter()
+            .any(|stored| {
+                stored.old_public_key == record.old_public_key
+                    && stored.new_public_key == record.new_public_key
+            });
+        if !accepted {
+            tracing::warn!(
+                new_public_key = %rotation.new_public_key,
+                "signer announced a conflicting identity rotation; ignoring"
+            );
+            return Ok(());
+        }
+
+        if signer_set.rotate_signer(signer_pub_key, rotation.new_public_key) {
+            tracing::info!(
+                new_public_key = %rotation.new_public_key,
+                "accepted signer identity rotation"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Estimate the clock skew between this signer and the sender of the
+    /// heartbeat, warning if it is larger than we expect, record whether
+    /// the sender is in maintenance mode, and compare the sender's
+    /// serialization audit digest and configuration fingerprint against
+    /// ours.
+    ///
+    /// Large clock skew, divergent serialization and divergent validation
+    /// policies between signers all show up as confusing timeouts and rejections elsewhere, so we
+    /// surface them here.
+    #[tracing::instrument(skip_all, fields(sender = %signer_pub_key))]
+    pub fn handle_heartbeat(&self, heartbeat: &SignerHeartbeat, signer_pub_key: PublicKey) {
+        let skew_millis = heartbeat.clock_skew_millis(time::OffsetDateTime::now_utc());
+
+        if skew_millis.abs() > MAX_PEER_CLOCK_SKEW_MILLIS {
+            tracing::warn!(
+                skew_millis,
+                max_skew_millis = MAX_PEER_CLOCK_SKEW_MILLIS,
+                "clock skew with peer exceeds the threshold"
+            );
+        } else {
+            tracing::trace!(skew_millis, "estimated clock skew with peer");
+        }
+
+        self.context
+            .state()
+            .set_peer_clock_skew_millis(signer_pub_key, skew_millis);
+
+        let maintenance_mode = heartbeat.maintenance_mode;
+        let state = self.context.state();
+        if state.set_peer_maintenance_mode(signer_pub_key, maintenance_mode) {
+            if maintenance_mode {
+                tracing::info!("peer is in maintenance mode and will not vote, sign or coordinate");
+            } else {
+                tracing::info!("peer left maintenance mode");
+            }
+        }
+
+        // Signers that do not run the serialization audit do not send a
+        // digest, and we only compare it when we run the audit ourselves.
+        let mismatch = heartbeat
+            .serialization_digest
+            .zip(self.serialization_digest())
+            .filter(|(peer_digest, digest)| peer_digest != digest);
+        if let Some((peer_digest, digest)) = mismatch {
+            tracing::warn!(
+                digest = %hex::encode(digest),
+                peer_digest = %hex::encode(peer_digest),
+                "peer serializes consensus-critical structures differently"
+            );
+            Metrics::increment_serialization_digest_mismatches(&signer_pub_key);
+        }
+
+        // Signers that predate configuration fingerprints do not send one.
+        let Some(peer_fingerprint) = heartbeat.config_fingerprint else {
+            return;
+        };
+
+        let fingerprint = ConsensusConfig::new(self.context.config()).fingerprint();
+        if peer_fingerprint != fingerprint {
+            tracing::warn!(
+                fingerprint = %hex::encode(fingerprint),
+                peer_fingerprint = %hex::encode(peer_fingerprint),
+                "peer runs a different consensus-critical configuration"
+            );
+            Metrics::increment_config_fingerprint_mismatches(&signer_pub_key);
+        }
+
+        self.context
+            .state()
+            .set_peer_config_fingerprint(signer_pub_key, peer_fingerprint);
+    }
+
+    /// Settle a conflict between the coordinator tenure that we are
+    /// running, if any, and the tenure announced by the sender.
+    ///
+    /// The sender must be the coordinator for the announced block, as far
+    /// as we can tell, or the announcement is ignored. If its tenure wins
+    /// then ours is marked as superseded, and our transaction coordinator
+    /// aborts its in-flight signing rounds when it notices.
+    #[tracing::instrument(skip_all, fields(sender = %signer_pub_key))]
+    pub fn handle_coordinator_announcement(
+        &self,
+        announcement: &CoordinatorAnnouncement,
+        signer_pub_key: PublicKey,
+    ) {
+        let block_hash = &announcement.bitcoin_block_hash;
+        let signer_set = &self.context.config().signer.bootstrap_signing_set;
+        if !given_key_is_coordinator(signer_pub_key, block_hash, signer_set) {
+            tracing::warn!(
+                %block_hash,
+                "ignoring announcement from a signer that is not the coordinator for the block"
+            );
+            return;
+        }
+
+        let outcome = self
+            .context
+            .state()
+            .resolve_coordinator_conflict(signer_pub_key, announcement);
+        match outcome {
+            // We are not coordinating, so there is nothing to settle.
+            None => {}
+            Some(true) => {
+                tracing::warn!(
+                    %block_hash,
+                    block_height = %announcement.bitcoin_block_height,
+                    "the tenure of a competing coordinator supersedes ours; yielding"
+                );
+                Metrics::increment_coordinator_conflicts("lost");
+            }
+            Some(false) => {
+                tracing::info!(
+                    %block_hash,
+                    block_height = %announcement.bitcoin_block_height,
+                    "our tenure supersedes the tenure of a competing coordinator"
+                );
+                Metrics::increment_coordinator_conflicts("won");
+            }
+        }
+    }
+
+    /// The digest of our serialization audit, if it is enabled.
+    ///
+    /// The audit is computed once. A failed audit is logged and leaves us
+    /// without a digest, so we do not flag our peers for our own problem.
+    fn serialization_digest(&self) -> Option<[u8; 32]> {
+        if !self.context.config().signer.serialization_audit {
+            return None;
+        }
+        serialization_audit::digest()
+    }
+
+    /// Take a checkpoint of the state that we have processed as of the
+    /// given bitcoin chain tip, and remember it so that we can compare it
+    /// against the checkpoints of our peers.
+    ///
+    /// Checkpoints are informational, so we log errors instead of
+    /// returning them.
+    async fn take_state_checkpoint(&self, chain_tip: &BitcoinBlockRef) -> Option<StateCheckpoint> {
+        let storage = self.context.get_storage();
+        match StateCheckpoint::new(&storage, chain_tip).await {
+            Ok(Some(checkpoint)) => {
+                self.context.state().set_state_checkpoint(checkpoint);
+                Some(checkpoint)
+            }
+            Ok(None) => None,
+            Err(error) => {
+                tracing::warn!(%error, "could not take a state checkpoint");
+                None
+            }
+        }
+    }
+
+    /// Verify the state checkpoint that a peer sent in its heartbeat
+    /// against the state in our database, warning if they differ.
+    ///
+    /// A signer whose database silently diverged from the rest of the
+    /// federation will eventually fail to agree on what to sign, so we
+    /// surface the divergence here, long before that happens.
+    #[tracing::instrument(skip_all, fields(
+        sender = %signer_pub_key,
+        stacks_block_hash = %checkpoint.stacks_block_hash,
+        stacks_block_height = %checkpoint.stacks_block_height,
+    ))]
+    pub async fn verify_state_checkpoint(
+        &self,
+        checkpoint: &StateCheckpoint,
+        signer_pub_key: PublicKey,
+    ) -> Result<(), Error> {
+        // Our own checkpoint is usually anchored to the same stacks block
+        // as the peer's, in which case we do not need the database.
+        let verification = match self.context.state().state_checkpoint() {
+            Some(ours) if ours.stacks_block_hash == checkpoint.stacks_block_hash => {
+                checkpoint.compare(ours.summary)
+            }
+            _ => checkpoint.verify(&self.context.get_storage()).await?,
+        };
+
+        match verification {
+            CheckpointVerification::Matches => {
+                tracing::trace!("state checkpoint of peer matches ours");
+            }
+            CheckpointVerification::UnknownStacksBlock => {
+                tracing::debug!("cannot verify state checkpoint of peer; unknown stacks block");
+            }
+            CheckpointVerification::Diverges(ours) => {
+                tracing::warn!(
+                    completed_deposit_events = ours.completed_deposit_events,
+                    peer_completed_deposit_events = checkpoint.summary.completed_deposit_events,
+                    key_rotation_events = ours.key_rotation_events,
+                    peer_key_rotation_events = checkpoint.summary.key_rotation_events,
+                    state_hash = %hex::encode(ours.state_hash),
+                    peer_state_hash = %hex::encode(checkpoint.summary.state_hash),
+                    "state checkpoint of peer differs from the state in our database"
+                );
+                Metrics::increment_state_checkpoint_mismatches(&signer_pub_key);
+            }
+        }
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip_all)]
     async fn send_message(
         &mut self,
@@ -540,12 +935,22 @@ where
 
 #[cfg(test)]
 mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
     use crate::bitcoin::MockBitcoinInteract;
+    use crate::context::Context as _;
     use crate::emily_client::MockEmilyInteract;
+    use crate::keys::PrivateKey;
+    use crate::keys::PublicKey;
+    use crate::message::SignerIdentityRotation;
+    use crate::network::in_memory::InMemoryNetwork;
+    use crate::request_decider::RequestDeciderEventLoop;
     use crate::stacks::api::MockStacksInteract;
     use crate::storage::memory::SharedStore;
     use crate::testing;
     use crate::testing::context::*;
+    use crate::testing::get_rng;
 
     #[allow(clippy::type_complexity)]
     fn test_environment() -> testing::request_decider::TestEnvironment<
@@ -602,4 +1007,46 @@ mod tests {
             .assert_should_store_decisions_received_from_other_signers()
             .await;
     }
+
+    /// Check that a signer can only rotate its identity key once, so that
+    /// announcing a second rotation with the same old key does not add
+    /// another key to the allowlist, and that the new key takes the slot
+    /// of the old one.
+    #[tokio::test]
+    async fn conflicting_identity_rotations_are_ignored() {
+        let mut rng = get_rng();
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let network = InMemoryNetwork::new();
+
+        let sender: PublicKey = Faker.fake_with_rng(&mut rng);
+        let signer_set = context.state().current_signer_set();
+        signer_set.add_signer(sender);
+
+        let mut event_loop = RequestDeciderEventLoop {
+            context: context.clone(),
+            network: network.connect(),
+            blocklist_checker: Some(()),
+            signer_private_key: PrivateKey::new(&mut rng),
+            context_window: 1,
+            deposit_decisions_retry_window: 1,
+            withdrawal_decisions_retry_window: 1,
+        };
+
+        let rotation = SignerIdentityRotation::new(sender, &PrivateKey::new(&mut rng));
+        let conflicting = SignerIdentityRotation::new(sender, &PrivateKey::new(&mut rng));
+        for announcement in [&rotation, &conflicting, &rotation] {
+            event_loop
+                .persist_received_identity_rotation(announcement, sender, Faker.fake())
+                .await
+                .unwrap();
+        }
+
+        assert!(signer_set.is_signer(&rotation.new_public_key));
+        assert!(!signer_set.is_signer(&conflicting.new_public_key));
+        assert_eq!(signer_set.slot_public_key(&rotation.new_public_key), sender);
+        assert_eq!(signer_set.num_signers(), 1);
+    }
 }
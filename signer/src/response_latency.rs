@@ -0,0 +1,182 @@
+//! # Signer response latency
+//!
+//! While it is the coordinator, a signer observes how long each member of
+//! the signer set takes to respond to its requests: the acknowledgements
+//! of bitcoin pre-sign requests, and the nonces and signature shares of
+//! WSTS signing rounds. A round can only complete once a threshold of
+//! signers have responded, so a member that is consistently slow drags
+//! out every round. The latencies are recorded as metrics and kept in the
+//! database for [`SIGNER_RESPONSE_LATENCY_WINDOW`], and the `GET /peers`
+//! endpoint summarizes them per signer.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::SIGNER_RESPONSE_LATENCY_WINDOW;
+use crate::context::Context;
+use crate::ecdsa::Signed;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::message::Payload;
+use crate::message::SignerMessage;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::SignerResponseLatency;
+use crate::storage::model::Timestamp;
+
+/// The kinds of responses whose latency is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    /// An acknowledgement of a bitcoin pre-sign request.
+    PreSignAck,
+    /// A nonce in a WSTS signing round.
+    NonceResponse,
+    /// A signature share in a WSTS signing round.
+    SignatureShareResponse,
+}
+
+impl ResponseKind {
+    /// The name of the response kind, as it is stored and labeled.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResponseKind::PreSignAck => "presign-ack",
+            ResponseKind::NonceResponse => "nonce",
+            ResponseKind::SignatureShareResponse => "signature-share",
+        }
+    }
+
+    /// The kind of response that the given message is, if its latency is
+    /// tracked.
+    pub fn from_message(msg: &Signed<SignerMessage>) -> Option<Self> {
+        match &msg.inner.payload {
+            Payload::BitcoinPreSignAck(_) => Some(ResponseKind::PreSignAck),
+            Payload::WstsMessage(wsts_msg) => match &wsts_msg.inner {
+                wsts::net::Message::NonceResponse(_) => Some(ResponseKind::NonceResponse),
+                wsts::net::Message::SignatureShareResponse(_) => {
+                    Some(ResponseKind::SignatureShareResponse)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A summary of the latencies of one kind of response from a signer.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResponseLatencySummary {
+    /// The number of responses observed.
+    pub responses: u64,
+    /// The mean latency of the responses, in milliseconds.
+    pub mean_millis: f64,
+    /// The largest latency of the responses, in milliseconds.
+    pub max_millis: u64,
+}
+
+/// Record how long a signer took to respond to a request of ours.
+pub async fn record_response_latency<C: Context>(
+    ctx: &C,
+    signer_public_key: PublicKey,
+    kind: ResponseKind,
+    latency: Duration,
+) -> Result<(), Error> {
+    Metrics::record_signer_response_latency(&signer_public_key, kind.as_str(), latency);
+
+    let latency = SignerResponseLatency {
+        signer_public_key,
+        response_kind: kind.as_str().to_string(),
+        latency_millis: u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+        observed_at: Timestamp::from(time::OffsetDateTime::now_utc()),
+    };
+    ctx.get_storage_mut()
+        .write_signer_response_latency(&latency)
+        .await
+}
+
+/// Delete the response latencies that fell out of the window that they
+/// are kept for.
+pub async fn prune_response_latencies<C: Context>(ctx: &C) -> Result<(), Error> {
+    let before = time::OffsetDateTime::now_utc() - SIGNER_RESPONSE_LATENCY_WINDOW;
+    ctx.get_storage_mut()
+        .delete_signer_response_latencies_before(before.into())
+        .await
+}
+
+/// Load the response latencies observed over the window that they are
+/// kept for, summarized per signer and response kind.
+pub async fn load_response_latency_summaries<C: Context>(
+    ctx: &C,
+) -> Result<HashMap<PublicKey, BTreeMap<String, ResponseLatencySummary>>, Error> {
+    let since = time::OffsetDateTime::now_utc() - SIGNER_RESPONSE_LATENCY_WINDOW;
+    let latencies = ctx
+        .get_storage()
+        .get_signer_response_latencies_since(since.into())
+        .await?;
+
+    Ok(summarize(latencies))
+}
+
+/// Summarize the given response latencies per signer and response kind.
+pub fn summarize(
+    latencies: Vec<SignerResponseLatency>,
+) -> HashMap<PublicKey, BTreeMap<String, ResponseLatencySummary>> {
+    let mut summaries: HashMap<PublicKey, BTreeMap<String, ResponseLatencySummary>> =
+        HashMap::new();
+
+    for latency in latencies {
+        let summary = summaries
+            .entry(latency.signer_public_key)
+            .or_default()
+            .entry(latency.response_kind)
+            .or_default();
+
+        let total = summary.mean_millis * summary.responses as f64;
+        summary.responses += 1;
+        summary.mean_millis = (total + latency.latency_millis as f64) / summary.responses as f64;
+        summary.max_millis = summary.max_millis.max(latency.latency_millis);
+    }
+
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use super::*;
+
+    #[test]
+    fn latencies_are_summarized_per_signer_and_kind() {
+        let signer: PublicKey = Faker.fake();
+        let other: PublicKey = Faker.fake();
+        let latency =
+            |signer_public_key, kind: ResponseKind, latency_millis| SignerResponseLatency {
+                signer_public_key,
+                response_kind: kind.as_str().to_string(),
+                latency_millis,
+                observed_at: time::OffsetDateTime::now_utc().into(),
+            };
+
+        let summaries = summarize(vec![
+            latency(signer, ResponseKind::PreSignAck, 100),
+            latency(signer, ResponseKind::PreSignAck, 300),
+            latency(signer, ResponseKind::NonceResponse, 50),
+            latency(other, ResponseKind::PreSignAck, 20),
+        ]);
+
+        let expected = ResponseLatencySummary {
+            responses: 2,
+            mean_millis: 200.0,
+            max_millis: 300,
+        };
+        assert_eq!(summaries[&signer]["presign-ack"], expected);
+        assert_eq!(summaries[&signer]["nonce"].responses, 1);
+        assert_eq!(summaries[&other]["presign-ack"].max_millis, 20);
+        assert!(!summaries[&other].contains_key("nonce"));
+    }
+}
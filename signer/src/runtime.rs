@@ -0,0 +1,927 @@
+//! # Signer runtime
+//!
+//! This module starts and runs all of the components of a signer, so that
+//! the signer can be embedded as a library as well as run from the
+//! `signer` binary. A signer is put together with a [`SignerBuilder`]:
+//!
+//! ```ignore
+//! let handle = Signer::builder(settings)
+//!     .with_storage(db)
+//!     .with_bitcoin_client(bitcoin_client)
+//!     .with_stacks_client(stacks_client)
+//!     .with_emily_client(emily_client)
+//!     .spawn();
+//!
+//! // ... later
+//! handle.shutdown();
+//! handle.join().await?;
+//! ```
+//!
+//! Any implementation of the storage and client traits may be used, so
+//! downstream projects can swap in their own, say for a simulation.
+//! Anything that already implements [`Context`] can also be run with
+//! [`Signer::new`].
+//!
+//! The runtime does not listen for OS signals; the embedding application
+//! decides when to stop the signer, using [`SignerHandle::shutdown`] or
+//! the termination handle of the context.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use axum::http::Request;
+use axum::http::Response;
+use time::OffsetDateTime;
+use tower_http::trace::TraceLayer;
+use tracing::Instrument as _;
+use tracing::Span;
+
+use crate::analytics_export::AnalyticsExporter;
+use crate::api;
+use crate::api::ApiState;
+use crate::balance_monitor::BalanceMonitor;
+use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::poller::BitcoinChainTipPoller;
+use crate::bitcoin::warm_up::BitcoinWarmUpMonitor;
+use crate::block_observer;
+use crate::blocklist_client::BlocklistClient;
+use crate::chain_consistency::ChainConsistencyChecker;
+use crate::config::Settings;
+use crate::confirmation_watches::ConfirmationWatcher;
+use crate::context::Context;
+use crate::context::SignerContext;
+use crate::crash_report;
+use crate::crash_report::isolate;
+use crate::emily_client::EmilyInteract;
+use crate::error::Error;
+use crate::handover;
+use crate::handover::HandoverListener;
+use crate::handover::PendingHandover;
+use crate::integrity::IntegrityVerifier;
+use crate::logging::SignerInfoLogger;
+use crate::network::P2PNetwork;
+use crate::network::libp2p::SignerSwarmBuilder;
+use crate::orphaned_sweeps::OrphanedSweepMonitor;
+use crate::raw_stacks_events::RawStacksEventPruner;
+use crate::request_decider::RequestDeciderEventLoop;
+use crate::stacks::api::StacksInteract;
+use crate::stacks_block_gaps::StacksBlockGapFiller;
+use crate::stale_deposits::StaleDepositCollector;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+use crate::storage::Transactable;
+use crate::storage::model::BitcoinBlockRef;
+use crate::sweep_completions::SweepCompletionMonitor;
+use crate::transaction_coordinator;
+use crate::transaction_signer;
+use crate::webhooks::WebhookDispatcher;
+
+/// This is how many seconds the P2P swarm will wait before attempting to
+/// bootstrap (i.e. connect to other peers). Three seconds is a sane default
+/// value, giving the swarm a few seconds to start up and bind listener(s)
+/// before proceeding.
+const INITIAL_BOOTSTRAP_DELAY_SECS: u64 = 3;
+
+// Timeout after which signer info logger will print new log.
+// Currently chosen to be 1 hour.
+// TODO: make this interval a config parameter.
+const SIGNER_INFO_LOGGER_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How often we check that the bitcoin anchor of the stacks chain tip is
+/// on our bitcoin blockchain. Bitcoin blocks arrive about every ten
+/// minutes, so a minute is enough to resume soon after the nodes agree.
+const CHAIN_CONSISTENCY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often we check whether the bitcoin node is still bootstrapping.
+/// Initial block download takes hours, so this is only about how quickly
+/// we resume once the node is ready, and how often its progress is
+/// logged.
+const BITCOIN_WARM_UP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the raw webhook bodies that fell out of the retention window
+/// are deleted. Retention windows are days long, so keeping some bodies
+/// for up to an hour longer does not matter.
+const RAW_STACKS_EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How often we try again to fetch the stacks blocks whose webhooks the
+/// stacks node skipped, when the first attempt failed.
+const STACKS_BLOCK_GAP_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often we check the balances of the signers' STX address and UTXO.
+const BALANCE_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The window of time in which we consider a peer to be known and valid for
+/// inclusion in bootstrapping.
+const KNOWN_PEER_WINDOW: Duration = Duration::from_secs(60 * 60 * 24 * 30); // 30 days
+
+/// The maximum number of known peers we will attempt to bootstrap from, in
+/// addition to the seed peers.
+const MAX_KNOWN_PEERS: usize = 6;
+
+/// The maximum number of pending connections to the signer API. This is
+/// the backlog that [`tokio::net::TcpListener::bind`] uses.
+const API_LISTEN_BACKLOG: u32 = 1024;
+
+/// A builder for a [`Signer`]. The storage and each of the clients must
+/// be set before the signer can be built.
+#[derive(Debug)]
+pub struct SignerBuilder<S, BC, ST, EM> {
+    settings: Settings,
+    storage: S,
+    bitcoin_client: BC,
+    stacks_client: ST,
+    emily_client: EM,
+}
+
+impl<BC, ST, EM> SignerBuilder<(), BC, ST, EM> {
+    /// Use the given storage for the signer.
+    pub fn with_storage<S>(self, storage: S) -> SignerBuilder<S, BC, ST, EM> {
+        SignerBuilder {
+            settings: self.settings,
+            storage,
+            bitcoin_client: self.bitcoin_client,
+            stacks_client: self.stacks_client,
+            emily_client: self.emily_client,
+        }
+    }
+}
+
+impl<S, ST, EM> SignerBuilder<S, (), ST, EM> {
+    /// Use the given client for interacting with bitcoin-core.
+    pub fn with_bitcoin_client<BC>(self, bitcoin_client: BC) -> SignerBuilder<S, BC, ST, EM> {
+        SignerBuilder {
+            settings: self.settings,
+            storage: self.storage,
+            bitcoin_client,
+            stacks_client: self.stacks_client,
+            emily_client: self.emily_client,
+        }
+    }
+}
+
+impl<S, BC, EM> SignerBuilder<S, BC, (), EM> {
+    /// Use the given client for interacting with the stacks node.
+    pub fn with_stacks_client<ST>(self, stacks_client: ST) -> SignerBuilder<S, BC, ST, EM> {
+        SignerBuilder {
+            settings: self.settings,
+            storage: self.storage,
+            bitcoin_client: self.bitcoin_client,
+            stacks_client,
+            emily_client: self.emily_client,
+        }
+    }
+}
+
+impl<S, BC, ST> SignerBuilder<S, BC, ST, ()> {
+    /// Use the given client for interacting with Emily.
+    pub fn with_emily_client<EM>(self, emily_client: EM) -> SignerBuilder<S, BC, ST, EM> {
+        SignerBuilder {
+            settings: self.settings,
+            storage: self.storage,
+            bitcoin_client: self.bitcoin_client,
+            stacks_client: self.stacks_client,
+            emily_client,
+        }
+    }
+}
+
+impl<S, BC, ST, EM> SignerBuilder<S, BC, ST, EM>
+where
+    S: DbRead + DbWrite + Transactable + Clone + Sync + Send + 'static,
+    BC: BitcoinInteract + Clone + 'static,
+    ST: StacksInteract + Clone + Sync + Send + 'static,
+    EM: EmilyInteract + Clone + Sync + Send + 'static,
+{
+    /// Build the signer without starting it.
+    pub fn build(self) -> Signer<SignerContext<S, BC, ST, EM>> {
+        let context = SignerContext::new(
+            self.settings,
+            self.storage,
+            self.bitcoin_client,
+            self.stacks_client,
+            self.emily_client,
+        );
+        Signer::new(context)
+    }
+
+    /// Build the signer and run it in a new task.
+    pub fn spawn(self) -> SignerHandle<SignerContext<S, BC, ST, EM>> {
+        self.build().spawn()
+    }
+}
+
+/// A signer with all of its components, ready to be run.
+#[derive(Debug, Clone)]
+pub struct Signer<C> {
+    context: C,
+}
+
+impl Signer<()> {
+    /// Create a builder for a signer with the given settings.
+    pub fn builder(settings: Settings) -> SignerBuilder<(), (), (), ()> {
+        SignerBuilder {
+            settings,
+            storage: (),
+            bitcoin_client: (),
+            stacks_client: (),
+            emily_client: (),
+        }
+    }
+}
+
+impl<C> Signer<C>
+where
+    C: Context + 'static,
+{
+    /// Create a signer that runs with the given context.
+    pub fn new(context: C) -> Self {
+        Self { context }
+    }
+
+    /// The context that the signer runs with.
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    /// Run the signer in a new task.
+    pub fn spawn(self) -> SignerHandle<C> {
+        let context = self.context.clone();
+        let task = tokio::spawn(self.run());
+        SignerHandle { context, task }
+    }
+
+    /// Run all of the components of the signer until the context's
+    /// termination signal is sent, or until one of the components that
+    /// the signer needs to be operational fails, in which case the
+    /// termination signal is sent to the other components and the error
+    /// is returned.
+    pub async fn run(self) -> Result<(), Error> {
+        let context = self.context;
+
+        // The API is bound before we take over from a running signer, so
+        // that the stacks node's events are accepted throughout.
+        let api_listener = match bind_api(&context) {
+            Ok(listener) => listener,
+            Err(error) => {
+                tracing::error!(%error, "failed to bind the signer API; shutting down");
+                context.get_termination_handle().signal_shutdown();
+                return Err(error);
+            }
+        };
+        let (handover_listener, interrupted_tenure) = match take_over(&context).await {
+            Ok(handover) => handover,
+            Err(error) => {
+                tracing::error!(%error, "failed to take over from the old signer; shutting down");
+                context.get_termination_handle().signal_shutdown();
+                return Err(error);
+            }
+        };
+
+        if let Err(error) = load_signer_set(&context).await {
+            tracing::error!(%error, "failed to load the signer set; shutting down the application");
+            context.get_termination_handle().signal_shutdown();
+            return Err(error);
+        }
+
+        // Stay in warm-up mode while the bitcoin node is bootstrapping,
+        // instead of failing to process blocks from it.
+        let warm_up_monitor =
+            BitcoinWarmUpMonitor::new(context.clone(), BITCOIN_WARM_UP_CHECK_INTERVAL);
+        warm_up_monitor.check().await;
+
+        // Make sure that our views of the bitcoin and stacks blockchains
+        // agree before we start participating in signing.
+        let consistency_checker =
+            ChainConsistencyChecker::new(context.clone(), CHAIN_CONSISTENCY_CHECK_INTERVAL);
+        consistency_checker.check().await;
+
+        // Run the application components concurrently. We're `join!`ing them
+        // here so that every component can shut itself down gracefully when
+        // the shutdown signal is received.
+        //
+        // Note that we must use `join` here instead of `select` as `select` would
+        // immediately abort the remaining tasks on the first completion, which
+        // deprives the other tasks of the opportunity to shut down gracefully. This
+        // is the reason we also use the `run_checked` helper method, which will
+        // intercept errors and send a shutdown signal to the other components if an error
+        // does occur, otherwise the `join` will continue running indefinitely.
+        //
+        // Every component is isolated, so that a panic in one of them is
+        // written down in a crash report instead of unwinding through all
+        // of them. A panic in a checked component is treated like an error.
+        let results = tokio::join!(
+            // The services which run concurrently, and must all be running
+            // for the signer to be operational.
+            run_checked("api", |ctx| run_api(ctx, api_listener), &context),
+            run_checked("p2p", run_libp2p_swarm, &context),
+            run_checked("block-observer", run_block_observer, &context),
+            run_checked("request-decider", run_request_decider, &context),
+            run_checked(
+                "transaction-coordinator",
+                run_transaction_coordinator,
+                &context,
+            ),
+            run_checked("transaction-signer", run_transaction_signer, &context),
+            run_checked("webhook-dispatcher", run_webhook_dispatcher, &context),
+            // Signer info logger intentionally runned in unchecked mode,
+            // since it is not necessary for signer to be operational.
+            isolate(
+                "signer-info-logger",
+                &context,
+                run_signer_info_logger(context.clone()),
+            ),
+            // Likewise for the integrity verifier, which only reports on the
+            // state of the database.
+            isolate(
+                "integrity-verifier",
+                &context,
+                run_integrity_verifier(context.clone()),
+            ),
+            // And for the chain consistency checker, which only holds back
+            // the components above while it finds a problem.
+            isolate(
+                "chain-consistency-checker",
+                &context,
+                consistency_checker.run(),
+            ),
+            // And for the bitcoin warm-up monitor, which only holds back the
+            // components above while the bitcoin node is bootstrapping.
+            isolate("bitcoin-warm-up-monitor", &context, warm_up_monitor.run()),
+            // And for the balance monitor, which only reports on the signers'
+            // funds.
+            isolate(
+                "balance-monitor",
+                &context,
+                BalanceMonitor::new(context.clone(), BALANCE_CHECK_INTERVAL).run(),
+            ),
+            // And for the orphaned sweep monitor, which only puts orphaned
+            // sweeps back into the mempool for the coordinator to pick up.
+            isolate(
+                "orphaned-sweep-monitor",
+                &context,
+                OrphanedSweepMonitor::new(context.clone()).run(),
+            ),
+            // And for the stale deposit collector, which only clears out
+            // deposit requests that can no longer be fulfilled.
+            isolate(
+                "stale-deposit-collector",
+                &context,
+                run_stale_deposit_collector(context.clone()),
+            ),
+            // And for the raw stacks event pruner, which only deletes the
+            // raw webhook bodies kept for auditing.
+            isolate(
+                "raw-stacks-event-pruner",
+                &context,
+                RawStacksEventPruner::new(context.clone(), RAW_STACKS_EVENT_PRUNE_INTERVAL).run(),
+            ),
+            // And for the stacks block gap filler, which only fetches the
+            // headers of the stacks blocks whose webhooks were skipped.
+            isolate(
+                "stacks-block-gap-filler",
+                &context,
+                StacksBlockGapFiller::new(context.clone(), STACKS_BLOCK_GAP_RETRY_INTERVAL).run(),
+            ),
+            // And for the analytics exporter, which only copies data out
+            // of the database.
+            isolate(
+                "analytics-exporter",
+                &context,
+                run_analytics_exporter(context.clone()),
+            ),
+            // And for the sweep completion monitor, which only reports on
+            // sweeps that were never completed on stacks.
+            isolate(
+                "sweep-completion-monitor",
+                &context,
+                SweepCompletionMonitor::new(context.clone()).run(),
+            ),
+            // And for the confirmation watcher, which only notifies those
+            // waiting on bitcoin transactions to be confirmed.
+            isolate(
+                "confirmation-watcher",
+                &context,
+                ConfirmationWatcher::new(context.clone()).run(),
+            ),
+            // And for the recorder of recent events, which only keeps
+            // them for crash reports.
+            isolate(
+                "recent-events-recorder",
+                &context,
+                crash_report::record_recent_events(context.clone()),
+            ),
+            // And for resuming the coordinator tenure that the signer we
+            // took over from was running, which is only an optimization.
+            isolate(
+                "tenure-resumption",
+                &context,
+                handover::resume_interrupted_tenure(context.clone(), interrupted_tenure),
+            ),
+            // And for the handover listener, which only stops the signer
+            // when a new signer process takes over.
+            isolate(
+                "handover-listener",
+                &context,
+                wait_for_handover(handover_listener, &context),
+            ),
+        );
+
+        let (api, swarm, observer, decider, coordinator, signer, webhooks, .., handover) = results;
+        // All of the components have stopped, so the new process can
+        // safely start its own.
+        if let Ok(Some(handover)) = handover {
+            handover.complete(&context).await.unwrap_or_else(|error| {
+                tracing::error!(%error, "failed to hand over to the new signer process");
+            });
+        }
+
+        api.and(swarm)
+            .and(observer)
+            .and(decider)
+            .and(coordinator)
+            .and(signer)
+            .and(webhooks)
+    }
+}
+
+/// A handle to a signer that is running in its own task.
+#[derive(Debug)]
+pub struct SignerHandle<C> {
+    context: C,
+    task: tokio::task::JoinHandle<Result<(), Error>>,
+}
+
+impl<C: Context> SignerHandle<C> {
+    /// The context that the signer runs with.
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    /// Tell all of the signer's components to shut down. Use
+    /// [`SignerHandle::join`] to wait for them to finish.
+    pub fn shutdown(&self) {
+        self.context.get_termination_handle().signal_shutdown();
+    }
+
+    /// Wait for the signer to stop running, returning the error that
+    /// stopped it, if any.
+    pub async fn join(self) -> Result<(), Error> {
+        self.task.await.map_err(Error::SignerTask)?
+    }
+}
+
+/// Bind the address of the signer API. With a handover socket, the
+/// address is bound with `SO_REUSEPORT`, so that the process that takes
+/// over from this one can bind it while this one is still running.
+fn bind_api(ctx: &impl Context) -> Result<tokio::net::TcpListener, Error> {
+    let socket_addr = ctx.config().signer.event_observer.bind;
+    let bind = || {
+        let socket = if socket_addr.is_ipv4() {
+            tokio::net::TcpSocket::new_v4()?
+        } else {
+            tokio::net::TcpSocket::new_v6()?
+        };
+        socket.set_reuseaddr(true)?;
+        // Handovers are only supported on unix, and so is `SO_REUSEPORT`.
+        #[cfg(unix)]
+        if ctx.config().signer.handover_socket.is_some() {
+            socket.set_reuseport(true)?;
+        }
+        socket.bind(socket_addr)?;
+        socket.listen(API_LISTEN_BACKLOG)
+    };
+    bind().map_err(|error| Error::ApiBind(error, socket_addr))
+}
+
+/// Take over from the signer process listening on the handover socket,
+/// if a handover socket is configured and there is such a process, and
+/// bind the handover socket for the process that takes over from us.
+/// Returns the handover listener and the coordinator tenure that the
+/// process we took over from was running, if any.
+async fn take_over(
+    ctx: &impl Context,
+) -> Result<(Option<HandoverListener>, Option<BitcoinBlockRef>), Error> {
+    let Some(path) = ctx.config().signer.handover_socket.clone() else {
+        return Ok((None, None));
+    };
+    let state = handover::take_over(ctx, &path).await?;
+    let listener = HandoverListener::bind(&path)?;
+    Ok((
+        Some(listener),
+        state.and_then(|state| state.interrupted_tenure),
+    ))
+}
+
+/// Wait for a new signer process to ask us to hand over to it.
+async fn wait_for_handover(
+    listener: Option<HandoverListener>,
+    ctx: &impl Context,
+) -> Option<PendingHandover> {
+    listener?.wait_for_request(ctx).await
+}
+
+/// Load the current signer set into the signer state.
+async fn load_signer_set(ctx: &impl Context) -> Result<(), Error> {
+    // TODO: We should first check "another source of truth" for the current
+    // signing set, and only assume we are bootstrapping if that source is
+    // empty.
+    let signer_set = ctx.state().current_signer_set();
+    for signer in &ctx.config().signer.bootstrap_signing_set {
+        signer_set.add_signer(*signer);
+    }
+
+    // Signers that have rotated their identity take the slot of their old
+    // key with their new one, and so do we if we have rotated ours.
+    let rotations = ctx.get_storage().get_signer_identity_rotations().await?;
+    for rotation in rotations {
+        signer_set.rotate_signer(rotation.old_public_key, rotation.new_public_key);
+    }
+    let config = &ctx.config().signer;
+    if config.previous_private_key.is_some() {
+        signer_set.rotate_signer(config.slot_public_key(), config.public_key());
+    }
+
+    Ok(())
+}
+
+/// A helper method that captures errors and panics from the provided future and
+/// sends a shutdown signal to the application if one is encountered. This is needed
+/// as otherwise the application would continue running indefinitely (since no
+/// shutdown signal is sent automatically on error).
+async fn run_checked<F, Fut, C>(component: &'static str, f: F, ctx: &C) -> Result<(), Error>
+where
+    C: Context,
+    F: FnOnce(C) -> Fut,
+    Fut: std::future::Future<Output = Result<(), Error>>,
+{
+    let result = isolate(component, ctx, f(ctx.clone())).await;
+    if let Err(error) = result.flatten() {
+        tracing::error!(%error, "a fatal error occurred; shutting down the application");
+        ctx.get_termination_handle().signal_shutdown();
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Runs the libp2p swarm.
+#[tracing::instrument(skip_all, name = "p2p")]
+async fn run_libp2p_swarm(ctx: impl Context) -> Result<(), Error> {
+    tracing::info!("initializing the p2p network");
+
+    tracing::debug!("building the libp2p swarm");
+    let config = ctx.config();
+
+    let enable_quic = config.signer.p2p.is_quic_used();
+
+    // Limit the number of signers to the maximum number of signer pubkeys we
+    // can support. Note that this value is used as a base value for swarm
+    // connection limit calculations.
+    let num_signers = ctx
+        .state()
+        .current_signer_set()
+        .num_signers()
+        .try_into()
+        .unwrap_or(crate::MAX_KEYS);
+
+    // Restore any peer bans that were made by an operator before we last
+    // shut down, so that banned peers are refused from the start.
+    let peer_bans = ctx
+        .get_storage()
+        .get_peer_bans()
+        .await
+        .inspect_err(|error| {
+            tracing::warn!(%error, "failed to fetch peer bans from the database; skipping peer bans");
+        })
+        .unwrap_or_default();
+    for ban in peer_bans {
+        ctx.state().ban_peer(*ban.peer_id, *ban.expires_at);
+    }
+
+    // Look for known peers in the database which will be included as part of
+    // the bootstrapping process. We will only include peers that have been
+    // dialed within the KNOWN_PEER_WINDOW, and we will limit the number of
+    // peers to MAX_KNOWN_PEERS. This filtering is done to give the signer a
+    // reasonable list of peers to connect to, increasing its likelihood of
+    // successfully bootstrapping and joining the network despite seed peer
+    // failures.
+    //
+    // NOTE: We specify seed and known peers separately as seed peers are part
+    // of the static configuration, are generally considered trusted/more stable
+    // and as such are dialed first. Known peers are gathered from the network
+    // and are dialed using their explicit known/verified peer ID for added
+    // security.
+    let known_peers = {
+        // Fetch known peers from the database.
+        let mut db_peers = ctx.get_storage()
+            .get_p2p_peers()
+            .await
+            .inspect_err(|error| {
+                tracing::warn!(%error, "failed to fetch known peers from the database; skipping known peers");
+            })
+            .unwrap_or_default();
+
+        // Sort the peers by last successful dialed time, duration-descending.
+        db_peers.sort_unstable_by(|a, b| b.last_dialed_at.cmp(&a.last_dialed_at));
+
+        // Create a list of known peers, filtering out those that have not been
+        // dialed within the KNOWN_PEER_WINDOW or are already included as seed
+        // addresses.
+        db_peers
+            .into_iter()
+            .filter_map(|peer| {
+                let time_since_last_dialed = OffsetDateTime::now_utc() - *peer.last_dialed_at;
+                let is_seed_addr = config.signer.p2p.seeds.contains(&*peer.address);
+                let is_allowed_peer = ctx
+                    .state()
+                    .current_signer_set()
+                    .is_allowed_peer(&peer.peer_id);
+                let is_banned = ctx.state().is_peer_banned(&peer.peer_id);
+
+                if time_since_last_dialed > KNOWN_PEER_WINDOW
+                    || is_seed_addr
+                    || !is_allowed_peer
+                    || is_banned
+                {
+                    None
+                } else {
+                    let peer_id = *peer.peer_id;
+                    let peer_address = (*peer.address).clone();
+                    Some((peer_id, peer_address))
+                }
+            })
+            .take(MAX_KNOWN_PEERS)
+            .collect::<Vec<_>>()
+    };
+
+    // Build the swarm.
+    let mut swarm = SignerSwarmBuilder::new(&config.signer.private_key)
+        .add_listen_endpoints(&ctx.config().signer.p2p.listen_on)
+        .add_seed_addrs(&ctx.config().signer.p2p.seeds)
+        .add_known_peers(&known_peers)
+        .add_external_addresses(&ctx.config().signer.p2p.public_endpoints)
+        .enable_mdns(config.signer.p2p.enable_mdns)
+        .enable_quic_transport(enable_quic)
+        .with_initial_bootstrap_delay(Duration::from_secs(INITIAL_BOOTSTRAP_DELAY_SECS))
+        .with_num_signers(num_signers)
+        .build()?;
+
+    // Start the libp2p swarm. This will run until either the shutdown signal is
+    // received, or an unrecoverable error has occurred.
+    tracing::info!("starting the libp2p swarm");
+    swarm
+        .start(&ctx)
+        .in_current_span()
+        .await
+        .map_err(Error::SignerSwarm)
+}
+
+/// Runs the signer's API server, which includes the Stacks event observer.
+#[tracing::instrument(skip_all, name = "api")]
+async fn run_api(
+    ctx: impl Context + 'static,
+    listener: tokio::net::TcpListener,
+) -> Result<(), Error> {
+    let socket_addr = ctx.config().signer.event_observer.bind;
+    tracing::info!(%socket_addr, "initializing the signer API server");
+
+    let state = ApiState { ctx: ctx.clone() };
+
+    let request_id = Arc::new(AtomicU64::new(0));
+    let access_log = Arc::new(ctx.config().signer.event_observer.access_log.clone());
+    let response_signer = Arc::new(api::ResponseSigner::new(
+        ctx.config().signer.event_observer.signed_responses.clone(),
+        ctx.config().signer.private_key.clone(),
+    ));
+    let limits = Arc::new(api::ApiLimits::new(ctx.config().signer.api.clone()));
+
+    // Build the signer API application
+    let app = api::get_router()
+        .layer(axum::middleware::from_fn_with_state(
+            response_signer,
+            api::sign_response,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            access_log,
+            api::log_access,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            limits,
+            api::enforce_limits,
+        ))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|request: &Request<_>| {
+                    tracing::info_span!("api-request",
+                        uri = %request.uri(),
+                        method = %request.method(),
+                        id = tracing::field::Empty,
+                    )
+                })
+                .on_request(move |_: &Request<_>, span: &Span| {
+                    span.record("id", request_id.fetch_add(1, Ordering::SeqCst));
+                    tracing::trace!("processing request");
+                })
+                .on_response(|_: &Response<_>, duration: Duration, _: &Span| {
+                    tracing::trace!(duration_ms = duration.as_millis(), "request completed");
+                }),
+        )
+        .with_state(state);
+
+    // Load the TLS certificates and key, if the API is served over TLS.
+    let tls_acceptor = match &ctx.config().signer.api.tls {
+        Some(tls) => match api::tls_acceptor(tls) {
+            Ok(acceptor) => Some(acceptor),
+            Err(error) => {
+                tracing::error!(%error, "could not set up TLS for the signer API server");
+                ctx.get_termination_handle().signal_shutdown();
+                return Err(error);
+            }
+        },
+        None => None,
+    };
+
+    // Get the termination signal handle.
+    let mut term = ctx.get_termination_handle();
+    let shutdown = async move {
+        // Listen for an application shutdown signal. We need to loop here
+        // because we may receive other signals (which we will ignore here).
+        term.wait_for_shutdown().await;
+        tracing::info!("stopping the signer API server");
+    };
+
+    // Run our app with hyper. The address of each client is needed for
+    // its rate limit.
+    let max_connections = ctx.config().signer.api.max_connections;
+    let listener = api::CappedListener::new(listener, max_connections);
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+    let result = match tls_acceptor {
+        Some(acceptor) => {
+            let listener = api::TlsListener::new(listener, acceptor);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+        }
+        None => {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+        }
+    };
+
+    result.map_err(|error| {
+        tracing::error!(%error, "error running the signer API server");
+        ctx.get_termination_handle().signal_shutdown();
+        error.into()
+    })
+}
+
+/// Run the block observer event-loop.
+async fn run_block_observer(ctx: impl Context) -> Result<(), Error> {
+    let bitcoin_client = ctx.get_bitcoin_client();
+    let chain_tip_polling_interval = ctx.config().bitcoin.chain_tip_polling_interval;
+
+    // Build and start the Bitcoin chain tip poller. This will block until it
+    // can successfully fetch the initial block hash. If ths timeout is exceeded
+    // while waiting for the initial block hash, an error will be returned.
+    let bitcoin_block_source =
+        BitcoinChainTipPoller::start_new(bitcoin_client, chain_tip_polling_interval).await;
+
+    let result = block_observer::BlockObserver::new(ctx, bitcoin_block_source.clone())
+        .run()
+        .await;
+
+    // Once the block observer has finished running, we need to stop the
+    // Bitcoin chain tip poller before returning the result from the observer.
+    bitcoin_block_source.stop();
+    result
+}
+
+/// Run the signer info logger event loop.
+async fn run_signer_info_logger(ctx: impl Context) {
+    SignerInfoLogger::new(ctx, SIGNER_INFO_LOGGER_INTERVAL)
+        .run()
+        .await
+}
+
+/// Run the integrity verifier, if an integrity key is configured.
+async fn run_integrity_verifier(ctx: impl Context) {
+    let config = ctx.config();
+    let Some(key) = config.signer.integrity_key.clone() else {
+        return;
+    };
+    let interval = config.signer.integrity_check_interval;
+
+    IntegrityVerifier::new(ctx, key, interval).run().await
+}
+
+/// Run the stale deposit collector.
+async fn run_stale_deposit_collector(ctx: impl Context) {
+    let config = ctx.config();
+    let interval = config.signer.stale_deposit_check_interval;
+    let min_age = config.signer.stale_deposit_min_age;
+
+    StaleDepositCollector::new(ctx, interval, min_age)
+        .run()
+        .await
+}
+
+/// Run the analytics exporter, if an analytics bucket is configured.
+async fn run_analytics_exporter(ctx: impl Context) {
+    let Some(config) = ctx.config().analytics_export.clone() else {
+        return;
+    };
+
+    match AnalyticsExporter::new(ctx, config) {
+        Ok(exporter) => exporter.run().await,
+        Err(error) => tracing::error!(%error, "could not start the analytics exporter"),
+    }
+}
+
+/// Run the transaction signer event-loop.
+async fn run_transaction_signer(ctx: impl Context) -> Result<(), Error> {
+    let network = P2PNetwork::new(&ctx);
+
+    // We use the OS random number generator, rather than a thread-local
+    // one, so that the signer can be run in a spawned task.
+    transaction_signer::TxSignerEventLoop::new(ctx, network, rand::rngs::OsRng)?
+        .run()
+        .await
+}
+
+/// Run the transaction coordinator event-loop.
+async fn run_transaction_coordinator(ctx: impl Context) -> Result<(), Error> {
+    let config = ctx.config().clone();
+    let private_key = config.signer.slot_private_key().clone();
+    let network = P2PNetwork::new(&ctx);
+
+    let coord = transaction_coordinator::TxCoordinatorEventLoop {
+        network,
+        context: ctx,
+        context_window: config.signer.context_window,
+        private_key,
+        signing_round_max_duration: config.signer.signer_round_max_duration,
+        bitcoin_presign_request_max_duration: config.signer.bitcoin_presign_request_max_duration,
+        threshold: config.signer.bootstrap_signatures_required,
+        dkg_max_duration: config.signer.dkg_max_duration,
+        is_epoch3: false,
+    };
+
+    coord.run().await
+}
+
+/// Run the webhook dispatcher event-loop.
+async fn run_webhook_dispatcher(ctx: impl Context + 'static) -> Result<(), Error> {
+    WebhookDispatcher::new(ctx)?.run().await
+}
+
+/// Run the request decider event-loop.
+async fn run_request_decider(ctx: impl Context) -> Result<(), Error> {
+    let config = ctx.config().clone();
+    let network = P2PNetwork::new(&ctx);
+
+    let decider = RequestDeciderEventLoop {
+        network,
+        context: ctx.clone(),
+        context_window: config.signer.context_window,
+        deposit_decisions_retry_window: config.signer.deposit_decisions_retry_window,
+        withdrawal_decisions_retry_window: config.signer.withdrawal_decisions_retry_window,
+        blocklist_checker: config.blocklist_client.as_ref().map(BlocklistClient::new),
+        signer_private_key: config.signer.slot_private_key().clone(),
+    };
+
+    decider.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::memory::Store;
+    use crate::testing::context::WrappedMockBitcoinInteract;
+    use crate::testing::context::WrappedMockEmilyInteract;
+    use crate::testing::context::WrappedMockStacksInteract;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn builder_loads_the_signer_set() {
+        let settings = Settings::new_from_default_config().unwrap();
+        let bootstrap_signing_set = settings.signer.bootstrap_signing_set.clone();
+
+        let signer = Signer::builder(settings)
+            .with_storage(Store::new_shared())
+            .with_bitcoin_client(WrappedMockBitcoinInteract::default())
+            .with_stacks_client(WrappedMockStacksInteract::default())
+            .with_emily_client(WrappedMockEmilyInteract::default())
+            .build();
+
+        let ctx = signer.context();
+        load_signer_set(ctx).await.unwrap();
+        let signer_set = ctx.state().current_signer_set();
+        for public_key in bootstrap_signing_set {
+            assert!(signer_set.is_signer(&public_key));
+        }
+    }
+}
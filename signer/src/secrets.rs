@@ -0,0 +1,214 @@
+//! Helpers for keeping secret material out of logs, swap and core dumps.
+//!
+//! Buffers holding secrets, like the decrypted DKG shares of the signer,
+//! are wrapped in [`SecretBytes`], which zeroes them when they are dropped
+//! so that they do not linger in freed memory. On unix, the buffers are
+//! also locked into RAM with `mlock(2)` where the process is allowed to,
+//! so that they are never written to swap. Locking is best effort, since
+//! the amount of memory that an unprivileged process may lock is often
+//! small, and failing to lock a buffer is not an error.
+//!
+//! Memory is locked a page at a time and locks do not nest, so small
+//! buffers can share a locked page. The number of buffers on each locked
+//! page is tracked, and a page is only unlocked when the last of them is
+//! dropped.
+//!
+//! Types that hold secrets implement [`std::fmt::Debug`] by hand, so that
+//! the secrets never end up in logs, and [`disable_core_dumps`] keeps them
+//! out of core dumps.
+
+#[cfg(unix)]
+use std::collections::BTreeMap;
+#[cfg(unix)]
+use std::sync::Mutex;
+#[cfg(unix)]
+use std::sync::PoisonError;
+
+use zeroize::Zeroize as _;
+
+/// The number of locked buffers on each locked page, keyed by the address
+/// of the start of the page.
+#[cfg(unix)]
+static LOCKED_PAGES: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+/// A heap allocated buffer of secret bytes that is zeroed on drop and, if
+/// possible, locked into memory for as long as it lives.
+pub struct SecretBytes {
+    /// The secret bytes.
+    bytes: Vec<u8>,
+    /// Whether the allocation of `bytes` is locked into memory.
+    locked: bool,
+}
+
+impl SecretBytes {
+    /// Take ownership of the given secret bytes.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let locked = lock_memory(bytes.as_ptr(), bytes.capacity());
+        if !locked {
+            tracing::trace!("could not lock a buffer with secret bytes into memory");
+        }
+        Self { bytes, locked }
+    }
+
+    /// Return the secret bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Whether the bytes are locked into memory.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        // Zeroing a vector keeps its allocation, so the region that we
+        // locked is still ours to unlock afterwards.
+        let (ptr, capacity) = (self.bytes.as_ptr(), self.bytes.capacity());
+        self.bytes.zeroize();
+        if self.locked {
+            unlock_memory(ptr, capacity);
+        }
+    }
+}
+
+impl std::fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretBytes(<redacted>)")
+    }
+}
+
+/// Disable core dumps for this process, so that the secrets in its memory
+/// cannot be read from one. This is a no-op on platforms other than unix.
+pub fn disable_core_dumps() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+        // SAFETY: `setrlimit` only reads the given limit, which is valid
+        // for the duration of the call.
+        if unsafe { libc::setrlimit(libc::RLIMIT_CORE, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// The size of a page of memory.
+#[cfg(unix)]
+fn page_size() -> usize {
+    // SAFETY: `sysconf` has no preconditions.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    usize::try_from(page_size)
+        .ok()
+        .filter(|page_size| *page_size > 0)
+        .unwrap_or(4096)
+}
+
+/// The addresses of the start of the pages that the given region of
+/// memory spans.
+#[cfg(unix)]
+fn pages(ptr: *const u8, len: usize) -> impl Iterator<Item = usize> {
+    let page_size = page_size();
+    let start = ptr as usize;
+    let first_page = start - start % page_size;
+    (first_page..start + len).step_by(page_size)
+}
+
+/// Lock the given region of memory into RAM, returning whether it worked.
+#[cfg(unix)]
+fn lock_memory(ptr: *const u8, len: usize) -> bool {
+    if len == 0 {
+        return false;
+    }
+    // The pages are counted while the lock on the map is held, so that a
+    // buffer on the same page cannot unlock it in between.
+    let mut locked_pages = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    // SAFETY: `mlock` only changes how the pages of the region are paged,
+    // and the region is a live allocation of the caller.
+    if unsafe { libc::mlock(ptr.cast(), len) } != 0 {
+        return false;
+    }
+    for page in pages(ptr, len) {
+        *locked_pages.entry(page).or_default() += 1;
+    }
+    true
+}
+
+/// Locking memory is only supported on unix.
+#[cfg(not(unix))]
+fn lock_memory(_ptr: *const u8, _len: usize) -> bool {
+    false
+}
+
+/// Unlock the pages of the given region of memory, which was locked with
+/// [`lock_memory`], that no other locked buffer is on.
+#[cfg(unix)]
+fn unlock_memory(ptr: *const u8, len: usize) {
+    let page_size = page_size();
+    let mut locked_pages = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    for page in pages(ptr, len) {
+        let Some(count) = locked_pages.get_mut(&page) else {
+            continue;
+        };
+        *count -= 1;
+        if *count == 0 {
+            locked_pages.remove(&page);
+            // SAFETY: `munlock` only changes how the page is paged, and
+            // the page holds part of a live allocation of the caller.
+            unsafe {
+                libc::munlock(page as *const libc::c_void, page_size);
+            }
+        }
+    }
+}
+
+/// Locking memory is only supported on unix.
+#[cfg(not(unix))]
+fn unlock_memory(_ptr: *const u8, _len: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_bytes_are_not_printed() {
+        let secret = SecretBytes::new(b"a very secret value".to_vec());
+        assert_eq!(secret.as_slice(), b"a very secret value");
+
+        let debug = format!("{secret:?}");
+        assert!(!debug.contains("secret value"));
+        assert_eq!(debug, "SecretBytes(<redacted>)");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shared_pages_stay_locked_until_the_last_buffer_is_dropped() {
+        // Take two buffers at the start of a page, so that they share it.
+        let page_size = page_size();
+        let memory = vec![0u8; 2 * page_size];
+        let offset = memory.as_ptr().align_offset(page_size);
+        let first = memory[offset..].as_ptr();
+        let second = memory[offset + 32..].as_ptr();
+        let page = first as usize;
+
+        // Locking is not allowed everywhere that the tests run.
+        if !lock_memory(first, 32) {
+            return;
+        }
+        assert!(lock_memory(second, 32));
+
+        unlock_memory(first, 32);
+        assert!(LOCKED_PAGES.lock().unwrap().contains_key(&page));
+
+        unlock_memory(second, 32);
+    }
+
+    #[test]
+    fn empty_secret_bytes_are_not_locked() {
+        let secret = SecretBytes::new(Vec::new());
+        assert!(!secret.is_locked());
+        assert!(secret.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+//! # Self-test
+//!
+//! The `signer self-test` command checks, after a deployment, that the
+//! signer can do its part of the signing work and reach its database,
+//! without involving the other signers. It runs the following checks:
+//!
+//! * `dkg`: a single-party WSTS DKG round, run in memory between a
+//!   coordinator and a signer state machine with an ephemeral key.
+//! * `signing`: a taproot signing round of a dummy sighash with the shares
+//!   from the DKG round, and the validation of the signature against the
+//!   tweaked aggregate key that DKG derived.
+//! * `storage`: a write of a dummy bitcoin block to the database within a
+//!   transaction, a read of it, and a rollback of the transaction.
+//!
+//! None of the checks use the signer's own key or leave anything behind.
+//! The [`SelfTestReport`] is printed as JSON and the command fails if any
+//! of the checks failed.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use rand::rngs::OsRng;
+use secp256k1::SECP256K1;
+use serde::Serialize;
+use sha2::Digest as _;
+use wsts::net::Message;
+use wsts::net::Packet;
+use wsts::net::SignatureType;
+use wsts::state_machine::OperationResult;
+use wsts::state_machine::StateMachine as _;
+use wsts::state_machine::coordinator::State as WstsCoordinatorState;
+
+use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
+use crate::signature::TaprootSignature;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::Transactable;
+use crate::storage::TransactionHandle as _;
+use crate::storage::model;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockRef;
+use crate::wsts_state_machine::FireCoordinator;
+use crate::wsts_state_machine::SignerStateMachine;
+use crate::wsts_state_machine::WstsCoordinator as _;
+
+/// The outcome of a self-test.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfTestReport {
+    /// Whether all of the checks passed.
+    pub passed: bool,
+    /// The outcome of each check, in the order that they ran.
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// The outcome of one of the checks of a self-test.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfTestCheck {
+    /// The name of the check.
+    pub name: &'static str,
+    /// Whether the check passed.
+    pub passed: bool,
+    /// How long the check took, in milliseconds.
+    pub duration_ms: u64,
+    /// What the check produced when it passed, like the aggregate key of
+    /// the DKG round.
+    pub detail: Option<String>,
+    /// Why the check failed, if it did.
+    pub error: Option<String>,
+}
+
+impl SelfTestCheck {
+    fn new<T, E>(name: &'static str, started_at: Instant, result: &Result<T, E>) -> Self
+    where
+        T: std::fmt::Display,
+        E: std::fmt::Display,
+    {
+        let duration_ms = started_at
+            .elapsed()
+            .as_millis()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        Self {
+            name,
+            passed: result.is_ok(),
+            duration_ms,
+            detail: result.as_ref().ok().map(ToString::to_string),
+            error: result.as_ref().err().map(ToString::to_string),
+        }
+    }
+}
+
+impl SelfTestReport {
+    /// Run all of the checks of the self-test against the given storage.
+    pub async fn run<S: Transactable>(storage: &S) -> Self {
+        let mut checks = Vec::new();
+
+        let started_at = Instant::now();
+        let dkg = SelfTestRound::run_dkg();
+        let aggregate_key = dkg.as_ref().map(|round| round.aggregate_key);
+        checks.push(SelfTestCheck::new("dkg", started_at, &aggregate_key));
+
+        // Signing needs the shares from DKG, so there is nothing to sign
+        // with when it failed.
+        let started_at = Instant::now();
+        let signing = match dkg {
+            Ok(mut round) => round.sign(),
+            Err(_) => Err(Error::SelfTestRoundIncomplete("dkg")),
+        };
+        checks.push(SelfTestCheck::new("signing", started_at, &signing));
+
+        let started_at = Instant::now();
+        let storage = check_storage(storage).await;
+        checks.push(SelfTestCheck::new("storage", started_at, &storage));
+
+        Self {
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+}
+
+/// The state machines of a single-party WSTS round, after DKG.
+struct SelfTestRound {
+    coordinator: FireCoordinator,
+    signer: SignerStateMachine,
+    aggregate_key: PublicKey,
+}
+
+impl SelfTestRound {
+    /// Run DKG between a coordinator and a single signer, both with the
+    /// same ephemeral key.
+    fn run_dkg() -> Result<Self, Error> {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let signers = [PublicKey::from_private_key(&private_key)];
+        let started_at = BitcoinBlockRef {
+            block_hash: BitcoinBlockHash::from([0; 32]),
+            block_height: 0u64.into(),
+        };
+
+        let mut coordinator =
+            FireCoordinator::new(signers, 1, private_key.clone(), started_at.block_height);
+        let mut signer = SignerStateMachine::new(signers, 1, started_at, private_key)?;
+
+        coordinator
+            .move_to(WstsCoordinatorState::DkgPublicDistribute)
+            .map_err(Error::wsts_coordinator)?;
+        let outbound = coordinator
+            .start_public_shares()
+            .map_err(Error::wsts_coordinator)?;
+
+        let aggregate_key = match drive_round("dkg", &mut coordinator, &mut signer, outbound)? {
+            OperationResult::Dkg(aggregate_key) => PublicKey::try_from(&aggregate_key)?,
+            result => return Err(Error::UnexpectedOperationResult(Box::new(result))),
+        };
+
+        Ok(Self {
+            coordinator,
+            signer,
+            aggregate_key,
+        })
+    }
+
+    /// Sign a dummy sighash with a key-spend taproot signature, and
+    /// validate the signature against the tweaked aggregate key.
+    fn sign(&mut self) -> Result<secp256k1::schnorr::Signature, Error> {
+        let sighash: [u8; 32] = sha2::Sha256::digest(b"sBTC signer self-test").into();
+        let outbound = self.coordinator.start_signing_round(
+            &sighash,
+            &BitcoinBlockHash::from([0; 32]),
+            SignatureType::Taproot(None),
+        )?;
+
+        let signature: TaprootSignature =
+            match drive_round("signing", &mut self.coordinator, &mut self.signer, outbound)? {
+                OperationResult::SignTaproot(signature) => signature.into(),
+                result => return Err(Error::UnexpectedOperationResult(Box::new(result))),
+            };
+
+        let tweaked_key = self.aggregate_key.signers_tweaked_pubkey()?;
+        let message = secp256k1::Message::from_digest(sighash);
+        SECP256K1
+            .verify_schnorr(
+                &signature.signature,
+                &message,
+                &secp256k1::XOnlyPublicKey::from(&tweaked_key),
+            )
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(signature.signature)
+    }
+}
+
+/// Deliver the messages of a round between the coordinator and the signer
+/// until the coordinator produces a result, starting with the given
+/// message of the coordinator.
+///
+/// Like on the network, the signer also gets the DKG shares that it sends
+/// itself, while the coordinator only gets the messages of the signer.
+fn drive_round(
+    round: &'static str,
+    coordinator: &mut FireCoordinator,
+    signer: &mut SignerStateMachine,
+    outbound: Packet,
+) -> Result<OperationResult, Error> {
+    let mut messages = VecDeque::from([outbound.msg]);
+    while let Some(message) = messages.pop_front() {
+        for response in signer.process(&message)? {
+            if matches!(
+                response,
+                Message::DkgPublicShares(_) | Message::DkgPrivateShares(_)
+            ) {
+                messages.push_back(response.clone());
+            }
+
+            let (outbound, result) = coordinator.process_message(&response)?;
+            if let Some(result) = result {
+                return Ok(result);
+            }
+            messages.extend(outbound.map(|packet| packet.msg));
+        }
+    }
+
+    Err(Error::SelfTestRoundIncomplete(round))
+}
+
+/// Write a dummy bitcoin block within a transaction, read it back and roll
+/// the transaction back.
+async fn check_storage<S: Transactable>(storage: &S) -> Result<BitcoinBlockHash, Error> {
+    let block = model::BitcoinBlock {
+        block_hash: BitcoinBlockHash::from(rand::random::<[u8; 32]>()),
+        block_height: 0u64.into(),
+        parent_hash: BitcoinBlockHash::from([0; 32]),
+    };
+
+    let tx = storage.begin_transaction().await?;
+    tx.write_bitcoin_block(&block).await?;
+    let stored = tx.get_bitcoin_block(&block.block_hash).await?;
+    tx.rollback().await?;
+
+    match stored {
+        Some(stored) if stored == block => Ok(block.block_hash),
+        _ => Err(Error::MissingBitcoinBlock(block.block_hash)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::memory::Store;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn self_test_passes() {
+        let store = Store::new_shared();
+        let report = SelfTestReport::run(&store).await;
+
+        let names: Vec<_> = report.checks.iter().map(|check| check.name).collect();
+        assert_eq!(names, ["dkg", "signing", "storage"]);
+        assert!(report.passed, "{report:?}");
+
+        // The storage check does not leave the dummy block behind.
+        let block_hash = report.checks[2].detail.as_deref().unwrap();
+        let block_hash: bitcoin::BlockHash = block_hash.parse().unwrap();
+        let block = store.get_bitcoin_block(&block_hash.into()).await.unwrap();
+        assert!(block.is_none());
+    }
+}
@@ -0,0 +1,259 @@
+//! # Serialization audit
+//!
+//! Signers only agree on what to sign when they serialize the same
+//! consensus-critical structures to the same bytes. Non-determinism, like
+//! an iteration over a `HashMap` or a float that is formatted differently,
+//! usually only shows up as a signing round that fails to complete. The
+//! serialization audit catches it earlier by serializing fixed instances
+//! of these structures on two code paths each, and checking that the
+//! bytes match:
+//!
+//! * `sweep-template`: an unsigned sweep transaction, built from the same
+//!   withdrawal requests given in opposite orders.
+//! * `deposit-decision`, `withdrawal-decision` and `pre-sign-request`:
+//!   signer messages with ballot payloads, encoded directly and after a
+//!   decode round trip.
+//! * `complete-deposit-args`: the payload of a contract call, serialized
+//!   directly and after a decode round trip.
+//!
+//! The audit produces a digest of all of the serializations, which is the
+//! same for every signer that serializes like we do. When the audit is
+//! enabled, the digest is sent to the other signers in heartbeats, so a
+//! signer that serializes differently is flagged before it breaks a
+//! signing round.
+
+use std::sync::OnceLock;
+
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use bitcoin::WPubkeyHash;
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash as _;
+use bitvec::array::BitArray;
+use blockstack_lib::chainstate::stacks::TransactionPayload;
+use blockstack_lib::clarity::vm::types::PrincipalData;
+use blockstack_lib::codec::StacksMessageCodec as _;
+use blockstack_lib::types::chainstate::StacksAddress;
+use secp256k1::XOnlyPublicKey;
+use sha2::Digest as _;
+
+use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::utxo::RequestRef;
+use crate::bitcoin::utxo::Requests;
+use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::utxo::UnsignedTransaction;
+use crate::bitcoin::utxo::WithdrawalRequest;
+use crate::bitcoin::validation::TxRequestIds;
+use crate::codec::Decode as _;
+use crate::codec::Encode as _;
+use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::message::BitcoinPreSignRequest;
+use crate::message::Payload;
+use crate::message::SignerDepositDecision;
+use crate::message::SignerMessage;
+use crate::message::SignerWithdrawalDecision;
+use crate::stacks::contracts::AsContractCall as _;
+use crate::stacks::contracts::AsTxPayload as _;
+use crate::stacks::contracts::CompleteDepositV1;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::QualifiedRequestId;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksTxId;
+
+/// The two serializations of an audited structure.
+type Serializations = (Vec<u8>, Vec<u8>);
+
+/// Run the serialization audit and return the digest of the
+/// serializations of all of the audited structures.
+///
+/// An error is returned if the two serializations of any structure
+/// differ.
+pub fn run() -> Result<[u8; 32], Error> {
+    let mut structures = vec![
+        ("sweep-template", sweep_template()?),
+        ("complete-deposit-args", complete_deposit_args()?),
+    ];
+    for (name, message) in ballots() {
+        structures.push((name, message_round_trip(message)?));
+    }
+
+    let mut hasher = sha2::Sha256::new_with_prefix(b"SBTC_SERIALIZATION_AUDIT");
+    for (name, (first, second)) in structures {
+        if first != second {
+            return Err(Error::NonDeterministicSerialization(name));
+        }
+        hasher.update(name.as_bytes());
+        hasher.update(sha2::Sha256::digest(&first));
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// The digest of the serialization audit, see [`run`].
+///
+/// The audited structures are fixed, so the audit only runs the first
+/// time that this function is called. This is `None` if the audit failed,
+/// which is logged.
+pub fn digest() -> Option<[u8; 32]> {
+    static DIGEST: OnceLock<Option<[u8; 32]>> = OnceLock::new();
+    *DIGEST.get_or_init(|| {
+        run()
+            .inspect_err(|error| tracing::error!(%error, "serialization audit failed"))
+            .ok()
+    })
+}
+
+/// The x-only public key of the signers in the audited structures.
+fn signers_public_key() -> Result<XOnlyPublicKey, Error> {
+    let private_key = PrivateKey::from_slice(&[1; 32])?;
+    let public_key = PublicKey::from_private_key(&private_key);
+    Ok(XOnlyPublicKey::from(&public_key))
+}
+
+/// Build an unsigned sweep transaction from the same withdrawal requests,
+/// once in order and once in reverse order, and serialize both.
+fn sweep_template() -> Result<Serializations, Error> {
+    let public_key = signers_public_key()?;
+    let state = SignerBtcState {
+        utxo: SignerUtxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            amount: 10_000_000,
+            public_key,
+        },
+        fee_rate: 12.5,
+        public_key,
+        last_fees: None,
+        magic_bytes: [b'T', b'3'],
+        lock_time: LockTime::ZERO,
+    };
+
+    let withdrawals: Vec<WithdrawalRequest> = (1..=3u8)
+        .map(|index| WithdrawalRequest {
+            request_id: u64::from(index),
+            txid: StacksTxId::from([index; 32]),
+            block_hash: StacksBlockHash::from([index; 32]),
+            amount: 100_000 * u64::from(index),
+            max_fee: 10_000,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([index; 20])).into(),
+            signer_bitmap: BitArray::ZERO,
+        })
+        .collect();
+
+    let requests: Vec<RequestRef> = withdrawals.iter().map(RequestRef::Withdrawal).collect();
+    let reversed = requests.iter().rev().copied().collect();
+
+    let forward = UnsignedTransaction::new(Requests::new(requests), &state)?;
+    let backward = UnsignedTransaction::new(Requests::new(reversed), &state)?;
+
+    Ok((
+        bitcoin::consensus::serialize(&forward.tx),
+        bitcoin::consensus::serialize(&backward.tx),
+    ))
+}
+
+/// Signer messages with the payloads of the ballots that signers send
+/// about requests.
+fn ballots() -> Vec<(&'static str, SignerMessage)> {
+    let bitcoin_chain_tip = BitcoinBlockHash::from([2; 32]);
+    let deposit_outpoint = OutPoint::new(Txid::from_byte_array([3; 32]), 1);
+    let withdrawal_id = QualifiedRequestId {
+        request_id: 4,
+        txid: StacksTxId::from([4; 32]),
+        block_hash: StacksBlockHash::from([4; 32]),
+    };
+
+    let deposit_decision = SignerDepositDecision {
+        txid: deposit_outpoint.txid,
+        output_index: deposit_outpoint.vout,
+        can_accept: true,
+        can_sign: true,
+    };
+    let withdrawal_decision = SignerWithdrawalDecision {
+        request_id: withdrawal_id.request_id,
+        block_hash: withdrawal_id.block_hash,
+        txid: withdrawal_id.txid,
+        accepted: true,
+    };
+    // The fee rates are floats, which makes this the payload where
+    // non-determinism is most likely to creep in.
+    let pre_sign_request = BitcoinPreSignRequest {
+        request_package: vec![TxRequestIds {
+            deposits: vec![deposit_outpoint],
+            withdrawals: vec![withdrawal_id],
+        }],
+        fee_rate: 12.5,
+        last_fees: Some(Fees { total: 1_250, rate: 0.1 }),
+        lock_time: LockTime::from_consensus(850_000),
+        donations: Vec::new(),
+    };
+
+    [
+        ("deposit-decision", Payload::from(deposit_decision)),
+        ("withdrawal-decision", Payload::from(withdrawal_decision)),
+        ("pre-sign-request", Payload::from(pre_sign_request)),
+    ]
+    .into_iter()
+    .map(|(name, payload)| (name, SignerMessage { bitcoin_chain_tip, payload }))
+    .collect()
+}
+
+/// Encode the message, and encode it again after decoding the encoded
+/// bytes.
+fn message_round_trip(message: SignerMessage) -> Result<Serializations, Error> {
+    let bytes = message.encode_to_vec();
+    let decoded = SignerMessage::decode(bytes.as_slice())?;
+    Ok((bytes, decoded.encode_to_vec()))
+}
+
+/// Serialize the payload of a complete-deposit contract call, and
+/// serialize the contract call again after decoding the serialized
+/// bytes.
+fn complete_deposit_args() -> Result<Serializations, Error> {
+    let complete_deposit = CompleteDepositV1 {
+        outpoint: OutPoint::new(Txid::from_byte_array([5; 32]), 2),
+        amount: 99_000,
+        recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+        deployer: StacksAddress::burn_address(false),
+        sweep_txid: BitcoinTxId::from([6; 32]),
+        sweep_block_hash: BitcoinBlockHash::from([7; 32]),
+        sweep_block_height: 850_000u64.into(),
+    };
+
+    let bytes = complete_deposit.tx_payload().serialize_to_vec();
+    let payload = TransactionPayload::ContractCall(complete_deposit.as_contract_call());
+    let decoded = TransactionPayload::consensus_deserialize(&mut &*payload.serialize_to_vec())
+        .map_err(Error::StacksCodec)?;
+
+    Ok((bytes, decoded.serialize_to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audited_structures_serialize_deterministically() {
+        let (forward, backward) = sweep_template().unwrap();
+        assert_eq!(forward, backward);
+
+        let (first, second) = complete_deposit_args().unwrap();
+        assert_eq!(first, second);
+
+        for (name, message) in ballots() {
+            let (first, second) = message_round_trip(message).unwrap();
+            assert_eq!(first, second, "{name}");
+        }
+    }
+
+    #[test]
+    fn audit_digest_is_stable() {
+        let digest = run().unwrap();
+        assert_eq!(run().unwrap(), digest);
+        assert_eq!(super::digest(), Some(digest));
+    }
+}
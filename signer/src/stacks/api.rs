@@ -37,12 +37,15 @@ use clarity::vm::types::{BuffData, ListData, SequenceData};
 use reqwest::StatusCode;
 use reqwest::header::CONTENT_LENGTH;
 use reqwest::header::CONTENT_TYPE;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer};
 use url::Url;
 
+use crate::config::OutboundConfig;
 use crate::config::Settings;
 use crate::error::Error;
 use crate::keys::PublicKey;
+use crate::metrics::InFlightRequest;
 use crate::metrics::Metrics;
 use crate::storage::DbRead;
 use crate::storage::model::BitcoinBlockHash;
@@ -230,6 +233,20 @@ pub trait StacksInteract: Send + Sync {
         request_id: u64,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
 
+    /// Call a read-only function on one of the sbtc smart contracts and
+    /// return the resulting clarity value.
+    ///
+    /// The request is made to `POST
+    /// /v2/contracts/call-read/<contract-principal>/<contract-name>/<fn-name>`.
+    fn call_read(
+        &self,
+        contract_principal: &StacksAddress,
+        contract_name: SmartContract,
+        fn_name: ClarityName,
+        sender: &StacksAddress,
+        arguments: &[Value],
+    ) -> impl Future<Output = Result<Value, Error>> + Send;
+
     /// Get the latest account info for the given address.
     fn get_account(
         &self,
@@ -751,11 +768,40 @@ pub struct StacksClient {
     pub client: reqwest::Client,
 }
 
+/// Deserialize the JSON body of a response from the stacks node. When
+/// fixture capture is enabled, the body is also captured as a fixture
+/// with the given name.
+async fn read_json<T: DeserializeOwned>(
+    response: reqwest::Response,
+    fixture_name: &str,
+) -> Result<T, Error> {
+    let Some(capture) = crate::capture::get() else {
+        return response
+            .json()
+            .await
+            .map_err(Error::UnexpectedStacksResponse);
+    };
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(Error::UnexpectedStacksResponse)?;
+    capture.record_stacks_response(fixture_name, &body);
+    serde_json::from_slice(&body).map_err(Error::UnexpectedStacksResponseBody)
+}
+
 impl StacksClient {
     /// Create a new instance of the Stacks client using the given
     /// StacksSettings.
     pub fn new(url: Url) -> Result<Self, Error> {
-        let client = reqwest::Client::builder()
+        Self::new_with_outbound(url, &OutboundConfig::default())
+    }
+
+    /// Create a new instance of the Stacks client that connects to the
+    /// given URL using the given outbound connection settings.
+    pub fn new_with_outbound(url: Url, outbound: &OutboundConfig) -> Result<Self, Error> {
+        let client = outbound
+            .http_client_builder(&url)?
             .timeout(REQUEST_TIMEOUT)
             .build()?;
 
@@ -804,6 +850,7 @@ impl StacksClient {
         );
 
         let instant = Instant::now();
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .post(url)
@@ -815,12 +862,12 @@ impl StacksClient {
 
         Metrics::record_call_read(instant.elapsed(), contract_name, fn_name, &response);
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json::<CallReadResponse>()
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json::<CallReadResponse>(response, "call-read")
             .await
-            .map_err(Error::UnexpectedStacksResponse)
             .map(|x| x.result)
     }
 
@@ -852,6 +899,7 @@ impl StacksClient {
         );
 
         let instant = Instant::now();
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url)
@@ -862,12 +910,12 @@ impl StacksClient {
 
         Metrics::record_data_var(instant.elapsed(), contract_name, var_name, &response);
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json::<DataVarResponse>()
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json::<DataVarResponse>(response, "get-data-var")
             .await
-            .map_err(Error::UnexpectedStacksResponse)
             .map(|x| x.data)
     }
 
@@ -908,6 +956,7 @@ impl StacksClient {
             .map_err(Error::ClarityValueSerialization)?;
 
         let instant = Instant::now();
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .post(url)
@@ -925,12 +974,12 @@ impl StacksClient {
             return Ok(None);
         }
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json::<DataVarResponse>()
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json::<DataVarResponse>(response, "get-map-entry")
             .await
-            .map_err(Error::UnexpectedStacksResponse)
             .map(|x| Some(x.data))
     }
 
@@ -949,6 +998,7 @@ impl StacksClient {
 
         tracing::debug!(%address, "fetching the latest account information");
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url)
@@ -957,12 +1007,12 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json::<AccountEntryResponse>()
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json::<AccountEntryResponse>(response, "get-account")
             .await
-            .map_err(Error::UnexpectedStacksResponse)
             .and_then(AccountInfo::try_from)
     }
 
@@ -987,6 +1037,7 @@ impl StacksClient {
             .join(&path)
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url)
@@ -995,12 +1046,11 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json()
-            .await
-            .map_err(Error::UnexpectedStacksResponse)
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json(response, "get-contract-source").await
     }
 
     /// Submit a transaction to a Stacks node.
@@ -1020,6 +1070,7 @@ impl StacksClient {
         tracing::debug!(txid = %tx.txid(), "submitting transaction to the stacks node");
         let body = tx.serialize_to_vec();
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response: reqwest::Response = self
             .client
             .post(url)
@@ -1071,6 +1122,7 @@ impl StacksClient {
         let body = serde_json::to_string(&request_body).map_err(Error::JsonSerialize)?;
 
         tracing::debug!("making request to the stacks node for a tx fee estimate");
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response: reqwest::Response = self
             .client
             .post(url)
@@ -1084,12 +1136,11 @@ impl StacksClient {
 
         // Only parse the JSON if it's a success status, otherwise return
         // an error.
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json()
-            .await
-            .map_err(Error::UnexpectedStacksResponse)
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json(response, "get-fee-estimate").await
     }
 
     /// Fetch the raw stacks nakamoto block from a Stacks node given the
@@ -1109,6 +1160,7 @@ impl StacksClient {
 
         tracing::debug!("making request to the stacks node for the raw nakamoto block");
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url)
@@ -1211,6 +1263,7 @@ impl StacksClient {
 
         tracing::debug!("making request to the stacks node for the raw nakamoto block");
 
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url)
@@ -1256,6 +1309,7 @@ impl StacksClient {
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Borrowed(path)))?;
 
         tracing::debug!("making request to the stacks node for the current tenure info");
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url.clone())
@@ -1264,12 +1318,11 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json()
-            .await
-            .map_err(Error::UnexpectedStacksResponse)
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json(response, "v3-tenures-info").await
     }
 
     /// Get information about the sortition related to a consensus hash.
@@ -1288,6 +1341,7 @@ impl StacksClient {
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
 
         tracing::debug!("making request to the stacks node for sortition info");
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url.clone())
@@ -1296,12 +1350,12 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json::<Vec<SortitionInfo>>()
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json::<Vec<SortitionInfo>>(response, "v3-sortitions")
             .await
-            .map_err(Error::UnexpectedStacksResponse)
             .and_then(|result| {
                 // For `consensus` lookups we expect to get a list with a single element
                 // https://github.com/stacks-network/stacks-core/blob/40059a57cd27e740c5e9d91a833fb2c975b0bf0b/docs/rpc/openapi.yaml#L693
@@ -1322,6 +1376,7 @@ impl StacksClient {
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Borrowed(path)))?;
 
         tracing::debug!("making request to the stacks node for the current PoX info");
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url.clone())
@@ -1330,12 +1385,11 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json()
-            .await
-            .map_err(Error::UnexpectedStacksResponse)
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json(response, "get-pox-info").await
     }
 
     /// Get information about the current node.
@@ -1348,6 +1402,7 @@ impl StacksClient {
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Borrowed(path)))?;
 
         tracing::debug!("making request to the stacks node for the current node info");
+        let _in_flight = InFlightRequest::new(&self.endpoint);
         let response = self
             .client
             .get(url.clone())
@@ -1356,12 +1411,11 @@ impl StacksClient {
             .await
             .map_err(Error::StacksNodeRequest)?;
 
-        response
+        let response = response
             .error_for_status()
-            .map_err(Error::StacksNodeResponse)?
-            .json()
-            .await
-            .map_err(Error::UnexpectedStacksResponse)
+            .map_err(Error::StacksNodeResponse)?;
+
+        read_json(response, "get-node-info").await
     }
 }
 
@@ -1593,6 +1647,25 @@ impl StacksInteract for StacksClient {
         }
     }
 
+    async fn call_read(
+        &self,
+        contract_principal: &StacksAddress,
+        contract_name: SmartContract,
+        fn_name: ClarityName,
+        sender: &StacksAddress,
+        arguments: &[Value],
+    ) -> Result<Value, Error> {
+        StacksClient::call_read(
+            self,
+            contract_principal,
+            contract_name,
+            fn_name,
+            sender,
+            arguments,
+        )
+        .await
+    }
+
     async fn is_withdrawal_completed(
         &self,
         deployer: &StacksAddress,
@@ -1843,6 +1916,26 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         .await
     }
 
+    async fn call_read(
+        &self,
+        contract_principal: &StacksAddress,
+        contract_name: SmartContract,
+        fn_name: ClarityName,
+        sender: &StacksAddress,
+        arguments: &[Value],
+    ) -> Result<Value, Error> {
+        self.exec(|client, _| {
+            client.call_read(
+                contract_principal,
+                contract_name,
+                fn_name,
+                sender,
+                arguments,
+            )
+        })
+        .await
+    }
+
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
         self.exec(|client, _| client.get_account(address)).await
     }
@@ -1922,7 +2015,7 @@ impl TryFrom<&Settings> for ApiFallbackClient<StacksClient> {
             .stacks
             .endpoints
             .iter()
-            .map(|url| StacksClient::new(url.clone()))
+            .map(|url| StacksClient::new_with_outbound(url.clone(), &settings.outbound))
             .collect::<Result<Vec<_>, _>>()?;
 
         ApiFallbackClient::new(clients).map_err(Error::FallbackClient)
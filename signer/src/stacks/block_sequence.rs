@@ -0,0 +1,118 @@
+//! Tracking of the order in which the stacks node delivers blocks to the
+//! event observer.
+//!
+//! The stacks node sends a `POST /new_block` webhook for every block that
+//! it processes, in order, so each block normally extends the one before
+//! it. A node that is restored from a snapshot, or whose queue of pending
+//! webhooks is cleared, can skip deliveries, leaving a gap between the
+//! last block that we handled and the next one. A node that switches to
+//! another stacks fork, or redelivers a webhook, sends a block that is not
+//! higher than the last one.
+
+use crate::storage::model::StacksBlock;
+
+/// How a stacks block relates to the last block whose webhook we handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockOrder {
+    /// The block extends the last block, or it is the first block since
+    /// the signer started.
+    Next,
+    /// The block is higher than the last block but does not extend it, so
+    /// the webhooks of the blocks in between were skipped.
+    Gap {
+        /// The last block whose webhook we handled.
+        last: StacksBlock,
+    },
+    /// The block is not higher than the last block, so the webhook is
+    /// either delivered again or for a block of another stacks fork.
+    Regression {
+        /// The last block whose webhook we handled.
+        last: StacksBlock,
+    },
+}
+
+/// The highest stacks block whose webhook the signer has handled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StacksBlockSequence {
+    last: Option<StacksBlock>,
+}
+
+impl StacksBlockSequence {
+    /// Record that the webhook of the given block arrived, returning how
+    /// it relates to the last block.
+    ///
+    /// The last block only ever moves up, so that a redelivered webhook
+    /// or a block of a shorter fork does not make us think that blocks we
+    /// already handled were skipped.
+    pub fn observe(&mut self, block: &StacksBlock) -> BlockOrder {
+        let Some(last) = self.last.clone() else {
+            self.last = Some(block.clone());
+            return BlockOrder::Next;
+        };
+
+        if block.block_height <= last.block_height {
+            return BlockOrder::Regression { last };
+        }
+
+        self.last = Some(block.clone());
+        if block.parent_hash == last.block_hash {
+            BlockOrder::Next
+        } else {
+            BlockOrder::Gap { last }
+        }
+    }
+
+    /// The last block, if any block has been observed.
+    pub fn last(&self) -> Option<&StacksBlock> {
+        self.last.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_that_extend_the_last_block_are_in_order() {
+        let mut sequence = StacksBlockSequence::default();
+        let genesis = StacksBlock::new_genesis();
+        let child = genesis.new_child();
+
+        assert_eq!(sequence.observe(&genesis), BlockOrder::Next);
+        assert_eq!(sequence.observe(&child), BlockOrder::Next);
+    }
+
+    #[test]
+    fn skipped_blocks_are_gaps() {
+        let mut sequence = StacksBlockSequence::default();
+        let genesis = StacksBlock::new_genesis();
+        let skipped = genesis.new_child();
+        let block = skipped.new_child();
+
+        sequence.observe(&genesis);
+        let order = sequence.observe(&block);
+        assert_eq!(order, BlockOrder::Gap { last: genesis });
+
+        // The gap is only reported once.
+        assert_eq!(sequence.observe(&block.new_child()), BlockOrder::Next);
+    }
+
+    #[test]
+    fn lower_blocks_are_regressions() {
+        let mut sequence = StacksBlockSequence::default();
+        let genesis = StacksBlock::new_genesis();
+        let child = genesis.new_child();
+        let sibling = genesis.new_child();
+
+        sequence.observe(&genesis);
+        sequence.observe(&child);
+
+        let order = sequence.observe(&sibling);
+        assert_eq!(order, BlockOrder::Regression { last: child.clone() });
+        let order = sequence.observe(&child);
+        assert_eq!(order, BlockOrder::Regression { last: child.clone() });
+
+        // Regressions leave the last block as it was.
+        assert_eq!(sequence.observe(&child.new_child()), BlockOrder::Next);
+    }
+}
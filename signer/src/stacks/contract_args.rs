@@ -0,0 +1,617 @@
+//! Typed arguments for the sBTC contract calls that the signers make.
+//!
+//! Each public contract function that the signers call has a struct here
+//! whose fields mirror the function's parameters, in order. The structs
+//! convert to and from the clarity values that are sent on chain, and
+//! declare the parameter names and types as they are written in the
+//! contract source. The tests in this module check those declarations
+//! against the contracts that we deploy, so a change to a contract
+//! function's signature, or to the order in which we build its
+//! arguments, fails the build instead of a contract call on chain.
+
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use blockstack_lib::clarity::vm::Value as ClarityValue;
+use blockstack_lib::clarity::vm::types::BuffData;
+use blockstack_lib::clarity::vm::types::ListData;
+use blockstack_lib::clarity::vm::types::ListTypeData;
+use blockstack_lib::clarity::vm::types::PrincipalData;
+use blockstack_lib::clarity::vm::types::SequenceData;
+use clarity::vm::types::TypeSignature;
+
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::StacksPrincipal;
+use crate::storage::model::ToLittleEndianOrder as _;
+
+use super::contracts::SmartContract;
+
+/// The type of a parameter of a clarity function, as it is written in the
+/// contract source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClarityArgType {
+    /// A `uint`.
+    UInt,
+    /// A `principal`.
+    Principal,
+    /// A `(buff n)` holding at most `n` bytes.
+    Buffer(u32),
+    /// A `(list n t)` holding at most `n` elements of type `t`.
+    List(u32, &'static ClarityArgType),
+}
+
+impl ClarityArgType {
+    /// The type as it is written in clarity source code.
+    pub fn repr(&self) -> String {
+        match self {
+            ClarityArgType::UInt => "uint".to_string(),
+            ClarityArgType::Principal => "principal".to_string(),
+            ClarityArgType::Buffer(max_len) => format!("(buff {max_len})"),
+            ClarityArgType::List(max_len, item) => format!("(list {max_len} {})", item.repr()),
+        }
+    }
+
+    /// Whether the value is of this type.
+    pub fn admits(&self, value: &ClarityValue) -> bool {
+        match (self, value) {
+            (ClarityArgType::UInt, ClarityValue::UInt(_)) => true,
+            (ClarityArgType::Principal, ClarityValue::Principal(_)) => true,
+            (
+                ClarityArgType::Buffer(max_len),
+                ClarityValue::Sequence(SequenceData::Buffer(buff)),
+            ) => buff.data.len() <= *max_len as usize,
+            (
+                ClarityArgType::List(max_len, item),
+                ClarityValue::Sequence(SequenceData::List(list)),
+            ) => {
+                list.data.len() <= *max_len as usize
+                    && list.data.iter().all(|value| item.admits(value))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The arguments to a public function of one of the sBTC contracts.
+pub trait ContractCallArgs: Sized {
+    /// The contract that defines the function.
+    const CONTRACT: SmartContract;
+    /// The name of the function.
+    const FUNCTION_NAME: &'static str;
+    /// The names and types of the function's parameters, in order.
+    const PARAMETERS: &'static [(&'static str, ClarityArgType)];
+
+    /// Convert the arguments to the clarity values passed to the
+    /// function.
+    fn to_clarity_values(&self) -> Vec<ClarityValue>;
+
+    /// Convert the clarity values passed to the function back into the
+    /// arguments.
+    fn from_clarity_values(values: &[ClarityValue]) -> Result<Self, Error>;
+}
+
+/// Reads the clarity values passed to a contract function, one parameter
+/// at a time, in the order given by [`ContractCallArgs::PARAMETERS`].
+struct ArgReader<'a, T> {
+    args: std::iter::Zip<
+        std::slice::Iter<'static, (&'static str, ClarityArgType)>,
+        std::slice::Iter<'a, ClarityValue>,
+    >,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T: ContractCallArgs> ArgReader<'a, T> {
+    fn new(values: &'a [ClarityValue]) -> Result<Self, Error> {
+        if values.len() != T::PARAMETERS.len() {
+            return Err(Error::ContractCallArgCount {
+                function: T::FUNCTION_NAME,
+                expected: T::PARAMETERS.len(),
+                actual: values.len(),
+            });
+        }
+
+        Ok(Self {
+            args: T::PARAMETERS.iter().zip(values),
+            _phantom: PhantomData,
+        })
+    }
+
+    fn invalid(parameter: &'static str) -> Error {
+        Error::InvalidContractCallArg {
+            function: T::FUNCTION_NAME,
+            parameter,
+        }
+    }
+
+    /// Return the next value, after checking that it has the type of its
+    /// parameter.
+    fn next(&mut self) -> Result<(&'static str, &'a ClarityValue), Error> {
+        let ((parameter, arg_type), value) = self
+            .args
+            .next()
+            .ok_or(Self::invalid("<missing parameter>"))?;
+
+        if !arg_type.admits(value) {
+            return Err(Self::invalid(parameter));
+        }
+        Ok((parameter, value))
+    }
+
+    fn uint<N: TryFrom<u128>>(&mut self) -> Result<N, Error> {
+        match self.next()? {
+            (parameter, ClarityValue::UInt(value)) => {
+                N::try_from(*value).map_err(|_| Self::invalid(parameter))
+            }
+            (parameter, _) => Err(Self::invalid(parameter)),
+        }
+    }
+
+    fn principal(&mut self) -> Result<PrincipalData, Error> {
+        match self.next()? {
+            (_, ClarityValue::Principal(principal)) => Ok(principal.clone()),
+            (parameter, _) => Err(Self::invalid(parameter)),
+        }
+    }
+
+    fn buffer<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (parameter, value) = self.next()?;
+        buffer_bytes(value).ok_or(Self::invalid(parameter))
+    }
+
+    fn buffer_list<const N: usize>(&mut self) -> Result<Vec<[u8; N]>, Error> {
+        match self.next()? {
+            (parameter, ClarityValue::Sequence(SequenceData::List(list))) => list
+                .data
+                .iter()
+                .map(|value| buffer_bytes(value).ok_or(Self::invalid(parameter)))
+                .collect(),
+            (parameter, _) => Err(Self::invalid(parameter)),
+        }
+    }
+
+    /// Bitcoin transaction IDs and block hashes are passed in the byte
+    /// order that they are in on the bitcoin wire, which is the reverse
+    /// of how they are usually displayed.
+    fn little_endian_hash<H: From<[u8; 32]>>(&mut self) -> Result<H, Error> {
+        let mut bytes: [u8; 32] = self.buffer()?;
+        bytes.reverse();
+        Ok(H::from(bytes))
+    }
+}
+
+/// Return the bytes of a clarity buffer of exactly `N` bytes.
+fn buffer_bytes<const N: usize>(value: &ClarityValue) -> Option<[u8; N]> {
+    match value {
+        ClarityValue::Sequence(SequenceData::Buffer(buff)) => buff.data.as_slice().try_into().ok(),
+        _ => None,
+    }
+}
+
+fn buffer(data: impl Into<Vec<u8>>) -> ClarityValue {
+    ClarityValue::Sequence(SequenceData::Buffer(BuffData { data: data.into() }))
+}
+
+/// The arguments to the complete-deposit-wrapper function in the
+/// sbtc-deposit contract.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct CompleteDepositArgs {
+    /// The transaction ID of the deposit request.
+    pub txid: BitcoinTxId,
+    /// The output index of the deposit request.
+    pub vout_index: u32,
+    /// The amount of sBTC to mint, in sats.
+    pub amount: u64,
+    /// The principal that receives the minted sBTC.
+    pub recipient: StacksPrincipal,
+    /// The hash of the bitcoin block that contains the sweep transaction.
+    pub burn_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block that contains the sweep
+    /// transaction.
+    pub burn_height: BitcoinBlockHeight,
+    /// The transaction ID of the sweep transaction.
+    pub sweep_txid: BitcoinTxId,
+}
+
+impl ContractCallArgs for CompleteDepositArgs {
+    const CONTRACT: SmartContract = SmartContract::SbtcDeposit;
+    const FUNCTION_NAME: &'static str = "complete-deposit-wrapper";
+    const PARAMETERS: &'static [(&'static str, ClarityArgType)] = &[
+        ("txid", ClarityArgType::Buffer(32)),
+        ("vout-index", ClarityArgType::UInt),
+        ("amount", ClarityArgType::UInt),
+        ("recipient", ClarityArgType::Principal),
+        ("burn-hash", ClarityArgType::Buffer(32)),
+        ("burn-height", ClarityArgType::UInt),
+        ("sweep-txid", ClarityArgType::Buffer(32)),
+    ];
+
+    fn to_clarity_values(&self) -> Vec<ClarityValue> {
+        vec![
+            buffer(self.txid.to_le_bytes()),
+            ClarityValue::UInt(self.vout_index as u128),
+            ClarityValue::UInt(self.amount as u128),
+            ClarityValue::Principal(PrincipalData::from(self.recipient.clone())),
+            buffer(self.burn_hash.to_le_bytes()),
+            ClarityValue::UInt(self.burn_height.into()),
+            buffer(self.sweep_txid.to_le_bytes()),
+        ]
+    }
+
+    fn from_clarity_values(values: &[ClarityValue]) -> Result<Self, Error> {
+        let mut reader = ArgReader::<Self>::new(values)?;
+        Ok(Self {
+            txid: reader.little_endian_hash()?,
+            vout_index: reader.uint()?,
+            amount: reader.uint()?,
+            recipient: StacksPrincipal::from(reader.principal()?),
+            burn_hash: reader.little_endian_hash()?,
+            burn_height: reader.uint::<u64>()?.into(),
+            sweep_txid: reader.little_endian_hash()?,
+        })
+    }
+}
+
+/// The arguments to the accept-withdrawal-request function in the
+/// sbtc-withdrawal contract.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct AcceptWithdrawalArgs {
+    /// The ID of the withdrawal request.
+    pub request_id: u64,
+    /// The transaction ID of the sweep transaction that fulfilled the
+    /// request.
+    pub bitcoin_txid: BitcoinTxId,
+    /// The bitmap of the signers' votes on the request.
+    pub signer_bitmap: u128,
+    /// The output index of the withdrawal in the sweep transaction.
+    pub output_index: u32,
+    /// The part of the sweep transaction's fee paid by the request, in
+    /// sats.
+    pub fee: u64,
+    /// The hash of the bitcoin block that contains the sweep transaction.
+    pub burn_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block that contains the sweep
+    /// transaction.
+    pub burn_height: BitcoinBlockHeight,
+    /// The transaction ID of the sweep transaction.
+    pub sweep_txid: BitcoinTxId,
+}
+
+impl ContractCallArgs for AcceptWithdrawalArgs {
+    const CONTRACT: SmartContract = SmartContract::SbtcWithdrawal;
+    const FUNCTION_NAME: &'static str = "accept-withdrawal-request";
+    const PARAMETERS: &'static [(&'static str, ClarityArgType)] = &[
+        ("request-id", ClarityArgType::UInt),
+        ("bitcoin-txid", ClarityArgType::Buffer(32)),
+        ("signer-bitmap", ClarityArgType::UInt),
+        ("output-index", ClarityArgType::UInt),
+        ("fee", ClarityArgType::UInt),
+        ("burn-hash", ClarityArgType::Buffer(32)),
+        ("burn-height", ClarityArgType::UInt),
+        ("sweep-txid", ClarityArgType::Buffer(32)),
+    ];
+
+    fn to_clarity_values(&self) -> Vec<ClarityValue> {
+        vec![
+            ClarityValue::UInt(self.request_id as u128),
+            buffer(self.bitcoin_txid.to_le_bytes()),
+            ClarityValue::UInt(self.signer_bitmap),
+            ClarityValue::UInt(self.output_index as u128),
+            ClarityValue::UInt(self.fee as u128),
+            buffer(self.burn_hash.to_le_bytes()),
+            ClarityValue::UInt(self.burn_height.into()),
+            buffer(self.sweep_txid.to_le_bytes()),
+        ]
+    }
+
+    fn from_clarity_values(values: &[ClarityValue]) -> Result<Self, Error> {
+        let mut reader = ArgReader::<Self>::new(values)?;
+        Ok(Self {
+            request_id: reader.uint()?,
+            bitcoin_txid: reader.little_endian_hash()?,
+            signer_bitmap: reader.uint()?,
+            output_index: reader.uint()?,
+            fee: reader.uint()?,
+            burn_hash: reader.little_endian_hash()?,
+            burn_height: reader.uint::<u64>()?.into(),
+            sweep_txid: reader.little_endian_hash()?,
+        })
+    }
+}
+
+/// The arguments to the reject-withdrawal-request function in the
+/// sbtc-withdrawal contract.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct RejectWithdrawalArgs {
+    /// The ID of the withdrawal request.
+    pub request_id: u64,
+    /// The bitmap of the signers' votes on the request.
+    pub signer_bitmap: u128,
+}
+
+impl ContractCallArgs for RejectWithdrawalArgs {
+    const CONTRACT: SmartContract = SmartContract::SbtcWithdrawal;
+    const FUNCTION_NAME: &'static str = "reject-withdrawal-request";
+    const PARAMETERS: &'static [(&'static str, ClarityArgType)] = &[
+        ("request-id", ClarityArgType::UInt),
+        ("signer-bitmap", ClarityArgType::UInt),
+    ];
+
+    fn to_clarity_values(&self) -> Vec<ClarityValue> {
+        vec![
+            ClarityValue::UInt(self.request_id as u128),
+            ClarityValue::UInt(self.signer_bitmap),
+        ]
+    }
+
+    fn from_clarity_values(values: &[ClarityValue]) -> Result<Self, Error> {
+        let mut reader = ArgReader::<Self>::new(values)?;
+        Ok(Self {
+            request_id: reader.uint()?,
+            signer_bitmap: reader.uint()?,
+        })
+    }
+}
+
+/// The arguments to the rotate-keys-wrapper function in the
+/// sbtc-bootstrap-signers contract.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct RotateKeysArgs {
+    /// The public keys of the new signer set.
+    pub new_keys: Vec<PublicKey>,
+    /// The aggregate public key of the new signer set.
+    pub new_aggregate_pubkey: PublicKey,
+    /// The number of signatures required by the new signer set.
+    pub new_signature_threshold: u16,
+}
+
+impl RotateKeysArgs {
+    /// The clarity description of the new-keys parameter, which is a
+    /// `(list 128 (buff 33))`.
+    pub fn list_data_type() -> &'static ListTypeData {
+        static KEYS_ARGUMENT_DATA_TYPE: OnceLock<ListTypeData> = OnceLock::new();
+        KEYS_ARGUMENT_DATA_TYPE.get_or_init(|| {
+            // A Result::Err is returned whenever the "depth" of the type
+            // is too large or if the maximum size of an input with the
+            // given type is too large. None of this is true for us, the
+            // depth is 1 or 2 and the size is 128 * 33 bytes, which is
+            // under the limit of 1 MB.
+            ListTypeData::new_list(TypeSignature::BUFFER_33.clone(), crate::MAX_KEYS as u32)
+                .expect("Error: legal ListTypeData marked as invalid")
+        })
+    }
+}
+
+impl ContractCallArgs for RotateKeysArgs {
+    const CONTRACT: SmartContract = SmartContract::SbtcBootstrapSigners;
+    const FUNCTION_NAME: &'static str = "rotate-keys-wrapper";
+    const PARAMETERS: &'static [(&'static str, ClarityArgType)] = &[
+        (
+            "new-keys",
+            ClarityArgType::List(crate::MAX_KEYS as u32, &ClarityArgType::Buffer(33)),
+        ),
+        ("new-aggregate-pubkey", ClarityArgType::Buffer(33)),
+        ("new-signature-threshold", ClarityArgType::UInt),
+    ];
+
+    fn to_clarity_values(&self) -> Vec<ClarityValue> {
+        let new_keys = ListData {
+            data: self
+                .new_keys
+                .iter()
+                .map(|key| buffer(key.serialize()))
+                .collect(),
+            type_signature: Self::list_data_type().clone(),
+        };
+
+        vec![
+            ClarityValue::Sequence(SequenceData::List(new_keys)),
+            // The public key needs to be exactly 33 bytes in this contract
+            // call.
+            buffer(self.new_aggregate_pubkey.serialize()),
+            ClarityValue::UInt(self.new_signature_threshold as u128),
+        ]
+    }
+
+    fn from_clarity_values(values: &[ClarityValue]) -> Result<Self, Error> {
+        let mut reader = ArgReader::<Self>::new(values)?;
+        let new_keys = reader
+            .buffer_list::<33>()?
+            .iter()
+            .map(|key| PublicKey::from_slice(key))
+            .collect::<Result<_, _>>()?;
+        let new_aggregate_pubkey = PublicKey::from_slice(&reader.buffer::<33>()?)?;
+
+        Ok(Self {
+            new_keys,
+            new_aggregate_pubkey,
+            new_signature_threshold: reader.uint()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockstack_lib::chainstate::stacks::TransactionContractCall;
+    use blockstack_lib::chainstate::stacks::TransactionPayload;
+    use blockstack_lib::clarity::vm::ClarityName;
+    use blockstack_lib::clarity::vm::ContractName;
+    use blockstack_lib::codec::StacksMessageCodec as _;
+    use blockstack_lib::types::chainstate::StacksAddress;
+    use fake::Fake as _;
+    use fake::Faker;
+    use test_case::test_case;
+
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// Return the names and types of the parameters of a public function,
+    /// as they are written in the contract source, with whitespace
+    /// normalized.
+    fn contract_parameters(contract: SmartContract, function: &str) -> Vec<(String, String)> {
+        let body = contract.contract_body();
+        let signature = format!("(define-public ({function}");
+        let start = body
+            .find(&signature)
+            .unwrap_or_else(|| panic!("{function} is not defined in {contract:?}"));
+
+        let rest = &body[start + signature.len()..];
+        let mut parameters = Vec::new();
+        let mut param_start = 0;
+        // We start just after the function name, inside the list that
+        // makes up the function signature. Each parameter is a
+        // `(name type)` list one level down.
+        let mut depth = 1;
+        for (index, c) in rest.char_indices() {
+            match c {
+                '(' => {
+                    depth += 1;
+                    if depth == 2 {
+                        param_start = index + 1;
+                    }
+                }
+                ')' => {
+                    depth -= 1;
+                    if depth == 1 {
+                        let param = rest[param_start..index]
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        let (name, arg_type) = param.split_once(' ').unwrap();
+                        parameters.push((name.to_string(), arg_type.to_string()));
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        parameters
+    }
+
+    /// Check that the parameters that we declare match the contract
+    /// source.
+    fn check_parameters<T: ContractCallArgs>() {
+        let declared: Vec<(String, String)> = T::PARAMETERS
+            .iter()
+            .map(|(name, arg_type)| (name.to_string(), arg_type.repr()))
+            .collect();
+
+        assert_eq!(declared, contract_parameters(T::CONTRACT, T::FUNCTION_NAME));
+    }
+
+    /// Check that the arguments have the declared types and survive being
+    /// put in a contract call, serialized, deserialized, and decoded.
+    fn check_round_trip<T>(args: T)
+    where
+        T: ContractCallArgs + PartialEq + std::fmt::Debug,
+    {
+        let values = args.to_clarity_values();
+        assert_eq!(values.len(), T::PARAMETERS.len());
+        for ((name, arg_type), value) in T::PARAMETERS.iter().zip(&values) {
+            assert!(arg_type.admits(value), "{name} has the wrong type");
+        }
+
+        let payload = TransactionPayload::ContractCall(TransactionContractCall {
+            address: StacksAddress::burn_address(false),
+            function_name: ClarityName::from(T::FUNCTION_NAME),
+            contract_name: ContractName::from(T::CONTRACT.contract_name()),
+            function_args: values,
+        });
+        let bytes = payload.serialize_to_vec();
+        let TransactionPayload::ContractCall(call) =
+            TransactionPayload::consensus_deserialize(&mut bytes.as_slice()).unwrap()
+        else {
+            panic!("expected a contract call payload");
+        };
+
+        assert_eq!(T::from_clarity_values(&call.function_args).unwrap(), args);
+    }
+
+    #[test_case(check_parameters::<CompleteDepositArgs>; "complete-deposit-wrapper")]
+    #[test_case(check_parameters::<AcceptWithdrawalArgs>; "accept-withdrawal-request")]
+    #[test_case(check_parameters::<RejectWithdrawalArgs>; "reject-withdrawal-request")]
+    #[test_case(check_parameters::<RotateKeysArgs>; "rotate-keys-wrapper")]
+    fn parameters_match_the_contract_source(check: fn()) {
+        check();
+    }
+
+    #[test]
+    fn complete_deposit_args_round_trip() {
+        let mut rng = get_rng();
+        for _ in 0..20 {
+            check_round_trip(Faker.fake_with_rng::<CompleteDepositArgs, _>(&mut rng));
+        }
+    }
+
+    #[test]
+    fn accept_withdrawal_args_round_trip() {
+        let mut rng = get_rng();
+        for _ in 0..20 {
+            check_round_trip(Faker.fake_with_rng::<AcceptWithdrawalArgs, _>(&mut rng));
+        }
+    }
+
+    #[test]
+    fn reject_withdrawal_args_round_trip() {
+        let mut rng = get_rng();
+        for _ in 0..20 {
+            check_round_trip(Faker.fake_with_rng::<RejectWithdrawalArgs, _>(&mut rng));
+        }
+    }
+
+    #[test]
+    fn rotate_keys_args_round_trip() {
+        let mut rng = get_rng();
+        for num_keys in [0, 1, 15, crate::MAX_KEYS as usize] {
+            let args = RotateKeysArgs {
+                new_keys: (0..num_keys)
+                    .map(|_| Faker.fake_with_rng(&mut rng))
+                    .collect(),
+                new_aggregate_pubkey: Faker.fake_with_rng(&mut rng),
+                new_signature_threshold: Faker.fake_with_rng(&mut rng),
+            };
+            check_round_trip(args);
+        }
+    }
+
+    #[test]
+    fn arguments_of_the_wrong_type_are_rejected() {
+        let args: CompleteDepositArgs = Faker.fake_with_rng(&mut get_rng());
+        let mut values = args.to_clarity_values();
+
+        // Swapping two arguments is the kind of mistake that we want to
+        // catch.
+        values.swap(1, 3);
+        let result = CompleteDepositArgs::from_clarity_values(&values);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidContractCallArg { parameter: "vout-index", .. })
+        ));
+
+        let result = CompleteDepositArgs::from_clarity_values(&values[..6]);
+        assert!(matches!(
+            result,
+            Err(Error::ContractCallArgCount { expected: 7, actual: 6, .. })
+        ));
+
+        let values = vec![
+            ClarityValue::UInt(u64::MAX as u128 + 1),
+            ClarityValue::UInt(0),
+        ];
+        let result = RejectWithdrawalArgs::from_clarity_values(&values);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidContractCallArg { parameter: "request-id", .. })
+        ));
+    }
+}
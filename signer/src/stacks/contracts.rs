@@ -19,7 +19,6 @@
 use std::collections::BTreeSet;
 use std::future::Future;
 use std::ops::Deref as _;
-use std::sync::OnceLock;
 
 use bitcoin::Amount;
 use bitcoin::OutPoint;
@@ -32,20 +31,17 @@ use blockstack_lib::chainstate::stacks::TransactionSmartContract;
 use blockstack_lib::clarity::vm::ClarityName;
 use blockstack_lib::clarity::vm::ContractName;
 use blockstack_lib::clarity::vm::Value as ClarityValue;
-use blockstack_lib::clarity::vm::types::BuffData;
-use blockstack_lib::clarity::vm::types::ListData;
-use blockstack_lib::clarity::vm::types::ListTypeData;
 use blockstack_lib::clarity::vm::types::PrincipalData;
-use blockstack_lib::clarity::vm::types::SequenceData;
 use blockstack_lib::types::chainstate::StacksAddress;
 use blockstack_lib::util_lib::strings::StacksString;
 use clarity::vm::ClarityVersion;
-use clarity::vm::types::TypeSignature;
 
 use crate::DEPOSIT_DUST_LIMIT;
 use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
+use crate::amount::SbtcAmount;
 use crate::bitcoin::BitcoinInteract as _;
+use crate::bitcoin::utxo::TxDeconstructor as _;
 use crate::bitcoin::validation::WithdrawalRequestStatus;
 use crate::context::Context;
 use crate::error::Error;
@@ -59,9 +55,13 @@ use crate::storage::model::BitcoinTxId;
 use crate::storage::model::DkgSharesStatus;
 use crate::storage::model::QualifiedRequestId;
 use crate::storage::model::StacksBlockHash;
-use crate::storage::model::ToLittleEndianOrder as _;
 
 use super::api::StacksInteract;
+use super::contract_args::AcceptWithdrawalArgs;
+use super::contract_args::CompleteDepositArgs;
+use super::contract_args::ContractCallArgs as _;
+use super::contract_args::RejectWithdrawalArgs;
+use super::contract_args::RotateKeysArgs;
 
 /// The collection sBTC smart contract objects.
 ///
@@ -108,6 +108,31 @@ pub struct ReqContext {
     pub deployer: StacksAddress,
 }
 
+/// Return the deployer of the sbtc-registry contract that emitted the
+/// withdrawal request with the given ID.
+///
+/// During a contract migration window the signers observe more than one
+/// registry contract, and a withdrawal request must be completed against
+/// the contracts that created it. Requests without a recorded source
+/// registry were emitted by the registry of the given default deployer.
+pub async fn withdrawal_request_deployer<S>(
+    db: &S,
+    id: &QualifiedRequestId,
+    default: &StacksAddress,
+) -> Result<StacksAddress, Error>
+where
+    S: DbRead,
+{
+    let registry = db
+        .get_registry_event_source(&id.txid, &id.block_hash)
+        .await?;
+
+    match registry.map(PrincipalData::from) {
+        Some(PrincipalData::Contract(contract)) => Ok(StacksAddress::from(contract.issuer)),
+        _ => Ok(default.clone()),
+    }
+}
+
 /// A struct describing any transaction post-execution conditions that we'd
 /// like to enforce.
 ///
@@ -325,8 +350,8 @@ impl AsTxPayload for CompleteDepositV1 {
 }
 
 impl AsContractCall for CompleteDepositV1 {
-    const CONTRACT_NAME: &'static str = "sbtc-deposit";
-    const FUNCTION_NAME: &'static str = "complete-deposit-wrapper";
+    const CONTRACT_NAME: &'static str = CompleteDepositArgs::CONTRACT.contract_name();
+    const FUNCTION_NAME: &'static str = CompleteDepositArgs::FUNCTION_NAME;
 
     fn deployer_address(&self) -> &StacksAddress {
         &self.deployer
@@ -334,22 +359,16 @@ impl AsContractCall for CompleteDepositV1 {
     /// Construct the input arguments to the complete-deposit-wrapper
     /// contract call.
     fn as_contract_args(&self) -> Vec<ClarityValue> {
-        let txid_data = self.outpoint.txid.to_le_bytes().to_vec();
-        let txid = BuffData { data: txid_data };
-        let sweep_txid_data = self.sweep_txid.to_le_bytes().to_vec();
-        let sweep_txid = BuffData { data: sweep_txid_data };
-        let burn_hash_data = self.sweep_block_hash.to_le_bytes().to_vec();
-        let burn_hash_buff = BuffData { data: burn_hash_data };
-
-        vec![
-            ClarityValue::Sequence(SequenceData::Buffer(txid)),
-            ClarityValue::UInt(self.outpoint.vout as u128),
-            ClarityValue::UInt(self.amount as u128),
-            ClarityValue::Principal(self.recipient.clone()),
-            ClarityValue::Sequence(SequenceData::Buffer(burn_hash_buff)),
-            ClarityValue::UInt(self.sweep_block_height.into()),
-            ClarityValue::Sequence(SequenceData::Buffer(sweep_txid)),
-        ]
+        CompleteDepositArgs {
+            txid: self.outpoint.txid.into(),
+            vout_index: self.outpoint.vout,
+            amount: self.amount,
+            recipient: self.recipient.clone().into(),
+            burn_hash: self.sweep_block_hash,
+            burn_height: self.sweep_block_height,
+            sweep_txid: self.sweep_txid,
+        }
+        .to_clarity_values()
     }
     /// Validates that the Complete deposit request satisfies the following
     /// criteria:
@@ -450,7 +469,10 @@ impl CompleteDepositV1 {
             return Err(DepositErrorMsg::AmountBelowDustLimit.into_error(req_ctx, self));
         }
         // 7. That the fee matches the expected assessed fee for the outpoint.
-        if fee.to_sat() + self.amount != deposit_request.amount {
+        //    The amount to mint comes from another signer, so the sum
+        //    here may overflow.
+        let minted = Amount::from(SbtcAmount::new(self.amount));
+        if fee.checked_add(minted) != Some(Amount::from_sat(deposit_request.amount)) {
             return Err(DepositErrorMsg::IncorrectFee.into_error(req_ctx, self));
         }
         // 8. Check that the fee is less than the specified max-fee.
@@ -571,7 +593,8 @@ impl std::error::Error for DepositValidationError {
 
 /// The responses for validation of a complete-deposit smart contract call
 /// transactions.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum DepositErrorMsg {
     /// The smart contract has a dust limit which is used to rejects
     /// contract calls if the mint amount is below that limit. We check for
@@ -674,37 +697,33 @@ impl AsTxPayload for AcceptWithdrawalV1 {
 }
 
 impl AsContractCall for AcceptWithdrawalV1 {
-    const CONTRACT_NAME: &'static str = "sbtc-withdrawal";
-    const FUNCTION_NAME: &'static str = "accept-withdrawal-request";
+    const CONTRACT_NAME: &'static str = AcceptWithdrawalArgs::CONTRACT.contract_name();
+    const FUNCTION_NAME: &'static str = AcceptWithdrawalArgs::FUNCTION_NAME;
 
     fn deployer_address(&self) -> &StacksAddress {
         &self.deployer
     }
     fn as_contract_args(&self) -> Vec<ClarityValue> {
-        let txid_data = self.outpoint.txid.to_le_bytes().to_vec();
-        let txid = BuffData { data: txid_data };
-        let burn_hash_data = self.sweep_block_hash.to_le_bytes().to_vec();
-        let burn_hash_buff = BuffData { data: burn_hash_data };
-
-        vec![
-            ClarityValue::UInt(self.id.request_id as u128),
-            ClarityValue::Sequence(SequenceData::Buffer(txid.clone())),
-            // This is the signer bitmap field. See the following for more
-            // on why this is fixed at zero.
+        AcceptWithdrawalArgs {
+            request_id: self.id.request_id,
+            bitcoin_txid: self.outpoint.txid.into(),
+            // See the following for more on why the signer bitmap is
+            // fixed at zero.
             // https://github.com/stacks-network/sbtc/issues/1505
-            ClarityValue::UInt(0),
-            ClarityValue::UInt(self.outpoint.vout as u128),
-            ClarityValue::UInt(self.tx_fee as u128),
-            ClarityValue::Sequence(SequenceData::Buffer(burn_hash_buff)),
-            ClarityValue::UInt(self.sweep_block_height.into()),
-            ClarityValue::Sequence(SequenceData::Buffer(txid)),
-        ]
+            signer_bitmap: 0,
+            output_index: self.outpoint.vout,
+            fee: self.tx_fee,
+            burn_hash: self.sweep_block_hash,
+            burn_height: self.sweep_block_height,
+            sweep_txid: self.outpoint.txid.into(),
+        }
+        .to_clarity_values()
     }
     /// Validates that the accept-withdrawal-request satisfies the
     /// following criteria:
     ///
-    ///  1. That the smart contract deployer matches the deployer in our
-    ///     context.
+    ///  1. That the smart contract deployer matches the deployer of the
+    ///     registry contract that emitted the withdrawal request.
     ///  2. That the signer has a record of the withdrawal request in its
     ///     list of pending and accepted withdrawal requests.
     ///  3. That the signer bitcoin transaction sweeping out the users'
@@ -714,7 +733,8 @@ impl AsContractCall for AcceptWithdrawalV1 {
     ///  5. The `scriptPubKey` of the UTXO matches the one in the
     ///     withdrawal request.
     ///  6. The `amount` of the UTXO matches the one in the withdrawal
-    ///     request.
+    ///     request, or covers it if the UTXO pays more than one
+    ///     withdrawal request.
     ///  7. That the fee is less than the desired max-fee.
     ///  8. That the fee matches the expected assessed fee for the output.
     ///  9. That the first input into the sweep transaction is the signers'
@@ -724,10 +744,13 @@ impl AsContractCall for AcceptWithdrawalV1 {
     where
         C: Context + Send + Sync,
     {
+        let db = ctx.get_storage();
+        let deployer = withdrawal_request_deployer(&db, &self.id, &req_ctx.deployer).await?;
+
         // 10. Check whether the withdrawal request is already completed.
         let withdrawal_completed = ctx
             .get_stacks_client()
-            .is_withdrawal_completed(&req_ctx.deployer, self.id.request_id)
+            .is_withdrawal_completed(&deployer, self.id.request_id)
             .await?;
 
         if withdrawal_completed {
@@ -735,9 +758,16 @@ impl AsContractCall for AcceptWithdrawalV1 {
         }
 
         // Covers points 3-4 & 8-9
-        let tx_out = self.validate_sweep(ctx, req_ctx).await?;
-        // Covers points 1-2 & 5-7, & 10
-        self.validate_utxo(ctx, req_ctx, tx_out).await
+        let (tx_out, shares) = self.validate_sweep(ctx, req_ctx).await?;
+
+        // 1. That the smart contract deployer matches the deployer of the
+        //    registry contract that emitted the withdrawal request.
+        if self.deployer != deployer {
+            return Err(WithdrawalErrorMsg::DeployerMismatch.into_error(req_ctx, self));
+        }
+
+        // Covers points 2 & 5-7
+        self.validate_utxo(ctx, req_ctx, tx_out, shares).await
     }
 }
 
@@ -748,32 +778,26 @@ impl AcceptWithdrawalV1 {
     ///
     /// Specifically, this function checks the following points (from the
     /// docs of [`AcceptWithdrawalV1::validate`]):
-    ///  1. That the smart contract deployer matches the deployer in our
-    ///     context.
     ///  2. That the signer has a record of the withdrawal request in its
     ///     list of pending and accepted withdrawal requests.
     ///  5. The `scriptPubKey` of the UTXO matches the recipient in the
     ///     withdrawal request.
     ///  6. The `amount` of the UTXO matches the one in the withdrawal
-    ///     request.
+    ///     request, or covers it if the UTXO pays `shares` withdrawal
+    ///     requests.
     ///  7. That the fee is less than the desired max-fee.
     async fn validate_utxo<C>(
         &self,
         ctx: &C,
         req_ctx: &ReqContext,
         tx_out: TxOut,
+        shares: usize,
     ) -> Result<(), Error>
     where
         C: Context + Send + Sync,
     {
         let db = ctx.get_storage();
-        // 1. That the smart contract deployer matches the deployer in our
-        //    context.
-        if self.deployer != req_ctx.deployer {
-            return Err(WithdrawalErrorMsg::DeployerMismatch.into_error(req_ctx, self));
-        }
-
-        let signer_public_key = ctx.config().signer.public_key();
+        let signer_public_key = ctx.config().signer.slot_public_key();
         let withdrawal_request = db.get_withdrawal_request_report(
             &req_ctx.chain_tip.block_hash,
             &req_ctx.stacks_chain_tip,
@@ -808,7 +832,16 @@ impl AcceptWithdrawalV1 {
         }
         // 6. The `amount` of the UTXO matches the one in the withdrawal
         //    request.
-        if tx_out.value.to_sat() != report.amount {
+        //
+        // A UTXO paying more than one withdrawal request pays the sum of
+        // their amounts. The signers checked the sum when they signed the
+        // sweep transaction, so here we only check that it covers this
+        // withdrawal request.
+        let amount_matches = match shares {
+            0 | 1 => tx_out.value.to_sat() == report.amount,
+            _ => tx_out.value.to_sat() >= report.amount,
+        };
+        if !amount_matches {
             return Err(WithdrawalErrorMsg::InvalidAmount.into_error(req_ctx, self));
         }
         // 7. Check that the fee is less than the desired max-fee.
@@ -832,7 +865,14 @@ impl AcceptWithdrawalV1 {
     /// 8. That the fee matches the expected assessed fee for the output.
     /// 9. That the first input into the sweep transaction is the signers'
     ///    UTXO.
-    async fn validate_sweep<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<TxOut, Error>
+    ///
+    /// It returns the UTXO along with the number of withdrawal requests
+    /// that it pays.
+    async fn validate_sweep<C>(
+        &self,
+        ctx: &C,
+        req_ctx: &ReqContext,
+    ) -> Result<(TxOut, usize), Error>
     where
         C: Context + Send + Sync,
     {
@@ -870,10 +910,20 @@ impl AcceptWithdrawalV1 {
         // b) When the output index points to an output that is not in
         //    the transaction.
         // Both cases indicate that the UTXO is missing from the transaction.
-        let Some(expected_fee) = sweep_tx.assess_output_fee(self.outpoint.vout as usize) else {
+        let vout = self.outpoint.vout as usize;
+        let Some(expected_fee) = sweep_tx.assess_withdrawal_fee(vout) else {
             return Err(WithdrawalErrorMsg::UtxoMissingFromSweep.into_error(req_ctx, self));
         };
 
+        // A UTXO that pays more than one withdrawal request must have this
+        // withdrawal request among the ones that the OP_RETURN output of
+        // the sweep transaction maps onto it.
+        let request_ids = sweep_tx.withdrawal_request_ids(vout).unwrap_or_default();
+        let shares = request_ids.len().max(1);
+        if shares > 1 && !request_ids.contains(&self.id.request_id) {
+            return Err(WithdrawalErrorMsg::UtxoMissingFromSweep.into_error(req_ctx, self));
+        }
+
         // 8. That the fee matches the expected assessed fee for the output.
         if expected_fee.to_sat() != self.tx_fee {
             return Err(WithdrawalErrorMsg::IncorrectFee.into_error(req_ctx, self));
@@ -899,8 +949,9 @@ impl AcceptWithdrawalV1 {
         sweep_tx
             .tx
             .output
-            .get(self.outpoint.vout as usize)
+            .get(vout)
             .cloned()
+            .map(|tx_out| (tx_out, shares))
             .ok_or_else(|| WithdrawalErrorMsg::UtxoMissingFromSweep.into_error(req_ctx, self))
     }
 }
@@ -961,7 +1012,8 @@ impl std::error::Error for WithdrawalRejectValidationError {
 
 /// The responses for validation of an accept-withdrawal-request smart
 /// contract call transaction.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum WithdrawalErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("the deployer in the transaction does not match the expected deployer")]
@@ -1025,7 +1077,8 @@ impl WithdrawalErrorMsg {
 
 /// The responses for validation of a reject-withdrawal-request smart
 /// contract call transaction.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum WithdrawalRejectErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("the deployer in the transaction does not match the expected deployer")]
@@ -1101,27 +1154,28 @@ impl AsTxPayload for RejectWithdrawalV1 {
 }
 
 impl AsContractCall for RejectWithdrawalV1 {
-    const CONTRACT_NAME: &'static str = "sbtc-withdrawal";
-    const FUNCTION_NAME: &'static str = "reject-withdrawal-request";
+    const CONTRACT_NAME: &'static str = RejectWithdrawalArgs::CONTRACT.contract_name();
+    const FUNCTION_NAME: &'static str = RejectWithdrawalArgs::FUNCTION_NAME;
 
     fn deployer_address(&self) -> &StacksAddress {
         &self.deployer
     }
     fn as_contract_args(&self) -> Vec<ClarityValue> {
-        vec![
-            ClarityValue::UInt(self.id.request_id as u128),
-            // This is the signer bitmap field. See the following for more
-            // on why this is fixed at zero.
+        RejectWithdrawalArgs {
+            request_id: self.id.request_id,
+            // See the following for more on why the signer bitmap is
+            // fixed at zero.
             // https://github.com/stacks-network/sbtc/issues/1505
-            ClarityValue::UInt(0),
-        ]
+            signer_bitmap: 0,
+        }
+        .to_clarity_values()
     }
     /// Validates that the reject-withdrawal-request satisfies the
     /// following criteria:
     ///
     /// 1. That the withdrawal request is not already completed.
-    /// 2. Whether the smart contract deployer matches the deployer in our
-    ///    context.
+    /// 2. Whether the smart contract deployer matches the deployer of the
+    ///    registry contract that emitted the withdrawal request.
     /// 3. Whether the associated withdrawal request transaction is
     ///    confirmed on the canonical stacks blockchain. Fail if it is not
     ///    on the canonical stacks blockchain.
@@ -1137,19 +1191,21 @@ impl AsContractCall for RejectWithdrawalV1 {
         C: Context + Send + Sync,
     {
         let db = ctx.get_storage();
+        let deployer = withdrawal_request_deployer(&db, &self.id, &req_ctx.deployer).await?;
+
         // 1. Check whether the withdrawal request is already completed.
         let withdrawal_completed = ctx
             .get_stacks_client()
-            .is_withdrawal_completed(&req_ctx.deployer, self.id.request_id)
+            .is_withdrawal_completed(&deployer, self.id.request_id)
             .await?;
 
         if withdrawal_completed {
             return Err(WithdrawalRejectErrorMsg::RequestCompleted.into_error(req_ctx, self));
         }
 
-        // 2. Whether the smart contract deployer matches the deployer in
-        //    our context.
-        if self.deployer != req_ctx.deployer {
+        // 2. Whether the smart contract deployer matches the deployer of
+        //    the registry contract that emitted the withdrawal request.
+        if self.deployer != deployer {
             return Err(WithdrawalRejectErrorMsg::DeployerMismatch.into_error(req_ctx, self));
         }
 
@@ -1161,7 +1217,7 @@ impl AsContractCall for RejectWithdrawalV1 {
                 &req_ctx.chain_tip.block_hash,
                 &req_ctx.stacks_chain_tip,
                 &self.id,
-                &ctx.config().signer.public_key(),
+                &ctx.config().signer.slot_public_key(),
             )
             .await?;
 
@@ -1263,26 +1319,6 @@ impl RotateKeysV1 {
             None => Err(Error::MissingDkgShares(aggregate_key.into())),
         }
     }
-
-    /// This function returns the clarity description of one of the inputs
-    /// to the contract call.
-    ///
-    /// # Notes
-    ///
-    /// One of the inputs, new-keys, is a (list 128 (buff 33)). This
-    /// function represents this data type.
-    fn list_data_type() -> &'static ListTypeData {
-        static KEYS_ARGUMENT_DATA_TYPE: OnceLock<ListTypeData> = OnceLock::new();
-        KEYS_ARGUMENT_DATA_TYPE.get_or_init(|| {
-            // A Result::Err is returned whenever the "depth" of the type
-            // is too large or if the maximum size of an input with the
-            // given type is too large. None of this is true for us, the
-            // depth is 1 or 2 and the size is 128 * 33 bytes, which is
-            // under the limit of 1 MB.
-            ListTypeData::new_list(TypeSignature::BUFFER_33.clone(), crate::MAX_KEYS as u32)
-                .expect("Error: legal ListTypeData marked as invalid")
-        })
-    }
 }
 
 impl AsTxPayload for RotateKeysV1 {
@@ -1295,44 +1331,20 @@ impl AsTxPayload for RotateKeysV1 {
 }
 
 impl AsContractCall for RotateKeysV1 {
-    const CONTRACT_NAME: &'static str = "sbtc-bootstrap-signers";
-    const FUNCTION_NAME: &'static str = "rotate-keys-wrapper";
+    const CONTRACT_NAME: &'static str = RotateKeysArgs::CONTRACT.contract_name();
+    const FUNCTION_NAME: &'static str = RotateKeysArgs::FUNCTION_NAME;
 
     fn deployer_address(&self) -> &StacksAddress {
         &self.deployer
     }
 
-    /// The arguments to the contract call function
-    ///
-    /// # Notes
-    ///
-    /// The signature to this function is:
-    ///
-    ///   (new-keys (list 128 (buff 33))) (new-aggregate-pubkey (buff 33))
     fn as_contract_args(&self) -> Vec<ClarityValue> {
-        let new_key_data: Vec<ClarityValue> = self
-            .new_keys
-            .iter()
-            .map(|pk| {
-                let data = pk.serialize().to_vec();
-                ClarityValue::Sequence(SequenceData::Buffer(BuffData { data }))
-            })
-            .collect();
-
-        let new_keys = ListData {
-            data: new_key_data,
-            type_signature: Self::list_data_type().clone(),
-        };
-
-        // The public key needs to be exactly 33 bytes in this contract
-        // call.
-        let key: [u8; 33] = self.aggregate_key.serialize();
-
-        vec![
-            ClarityValue::Sequence(SequenceData::List(new_keys)),
-            ClarityValue::Sequence(SequenceData::Buffer(BuffData { data: key.to_vec() })),
-            ClarityValue::UInt(self.signatures_required as u128),
-        ]
+        RotateKeysArgs {
+            new_keys: self.new_keys.iter().copied().collect(),
+            new_aggregate_pubkey: self.aggregate_key,
+            new_signature_threshold: self.signatures_required,
+        }
+        .to_clarity_values()
     }
 
     /// Validates that the rotate-keys-wrapper satisfies the following
@@ -1434,7 +1446,8 @@ impl std::error::Error for RotateKeysValidationError {
 
 /// The responses for validation of a rotate-keys smart contract call
 /// transactions.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum RotateKeysErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("The deployer in the transaction does not match the expected deployer")]
@@ -1663,10 +1676,10 @@ mod tests {
 
     #[test]
     fn rotate_keys_wrapper_contract_call_creation() {
-        // This is to check that the RotateKeysV1::list_data_type function
-        // doesn't panic. If it doesn't panic now, it can never panic at
-        // runtime.
-        let _ = RotateKeysV1::list_data_type();
+        // This is to check that the RotateKeysArgs::list_data_type
+        // function doesn't panic. If it doesn't panic now, it can never
+        // panic at runtime.
+        let _ = RotateKeysArgs::list_data_type();
 
         let mut rng = get_rng();
         let secret_keys = [
@@ -0,0 +1,211 @@
+//! # Stacks fee budget
+//!
+//! The fees of the stacks transactions that the signers submit are paid
+//! from the signers' STX address, which operators keep funded. To give
+//! operators visibility into, and a bound on, that spending, the
+//! coordinator records the fee of each stacks transaction that it
+//! submits, and refuses to start signing rounds for non-critical
+//! transactions once the fees paid over the last budget period would
+//! exceed the configured budget.
+//!
+//! Key rotations and contract deployments are critical, since the signers
+//! cannot operate without them, and are always allowed through. Each
+//! signer only accounts for the transactions that it submitted while it
+//! was the coordinator.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::StacksFeeSpend;
+use crate::storage::model::StacksTxId;
+use crate::storage::model::Timestamp;
+
+/// The kinds of stacks transactions that are submitted regardless of the
+/// fee budget.
+const CRITICAL_TX_KINDS: [&str; 2] = ["rotate-keys", "smart-contract-deployment"];
+
+/// Whether transactions of the given kind are submitted even when the fee
+/// budget has been exhausted.
+pub fn is_critical(tx_kind: &str) -> bool {
+    CRITICAL_TX_KINDS.contains(&tx_kind)
+}
+
+/// The fees paid for stacks transactions over the current budget period.
+#[derive(Debug, Clone, Serialize)]
+pub struct StacksFeeReport {
+    /// The maximum amount of fees, in microSTX, to spend on non-critical
+    /// transactions over a budget period, if there is a budget.
+    pub budget_ustx: Option<u64>,
+    /// The length of the budget period in seconds. The period ends now.
+    pub period_seconds: u64,
+    /// The total fees, in microSTX, paid over the budget period.
+    pub spent_ustx: u64,
+    /// The amount of the budget that is left, if there is a budget.
+    pub remaining_ustx: Option<u64>,
+    /// The transactions submitted over the budget period.
+    pub transactions: Vec<StacksFeeSpendInfo>,
+}
+
+/// The fee paid for a single stacks transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct StacksFeeSpendInfo {
+    /// The ID of the transaction.
+    pub txid: String,
+    /// The kind of transaction, such as `complete-deposit`.
+    pub tx_kind: String,
+    /// The fee paid for the transaction, in microSTX.
+    pub fee_ustx: u64,
+    /// When the transaction was submitted.
+    pub submitted_at: String,
+}
+
+impl From<StacksFeeSpend> for StacksFeeSpendInfo {
+    fn from(spend: StacksFeeSpend) -> Self {
+        Self {
+            txid: spend.txid.to_string(),
+            tx_kind: spend.tx_kind,
+            fee_ustx: spend.fee,
+            submitted_at: spend.submitted_at.to_string(),
+        }
+    }
+}
+
+impl StacksFeeReport {
+    /// Load the fees paid over the current budget period from the
+    /// database.
+    pub async fn load<C: Context>(ctx: &C) -> Result<Self, Error> {
+        let config = &ctx.config().signer;
+        let period = config.stacks_fee_budget_period;
+        let since = time::OffsetDateTime::now_utc() - period;
+
+        let spends = ctx
+            .get_storage()
+            .get_stacks_fee_spends_since(since.into())
+            .await?;
+
+        Ok(Self::new(
+            config.stacks_fee_budget_ustx.map(|budget| budget.get()),
+            period,
+            spends,
+        ))
+    }
+
+    /// Create a report from the fees paid over the budget period.
+    pub fn new(budget_ustx: Option<u64>, period: Duration, spends: Vec<StacksFeeSpend>) -> Self {
+        let spent_ustx = spends
+            .iter()
+            .fold(0u64, |total, spend| total.saturating_add(spend.fee));
+        Self {
+            budget_ustx,
+            period_seconds: period.as_secs(),
+            spent_ustx,
+            remaining_ustx: budget_ustx.map(|budget| budget.saturating_sub(spent_ustx)),
+            transactions: spends.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether a transaction of the given kind and fee may be submitted.
+    pub fn allows(&self, tx_kind: &str, fee: u64) -> bool {
+        match self.remaining_ustx {
+            Some(remaining) => is_critical(tx_kind) || fee <= remaining,
+            None => true,
+        }
+    }
+}
+
+/// Return an error if submitting a transaction of the given kind and fee
+/// would exceed the stacks fee budget.
+pub async fn check_stacks_fee_budget<C: Context>(
+    ctx: &C,
+    tx_kind: &'static str,
+    fee: u64,
+) -> Result<(), Error> {
+    if ctx.config().signer.stacks_fee_budget_ustx.is_none() {
+        return Ok(());
+    }
+
+    let report = StacksFeeReport::load(ctx).await?;
+    Metrics::record_stacks_fees_spent(report.spent_ustx);
+    if report.allows(tx_kind, fee) {
+        return Ok(());
+    }
+
+    let budget = report.budget_ustx.unwrap_or_default();
+    Metrics::increment_stacks_fee_budget_refusals(tx_kind);
+    tracing::error!(
+        %tx_kind,
+        %fee,
+        spent = %report.spent_ustx,
+        %budget,
+        period_seconds = %report.period_seconds,
+        "the stacks fee budget has been exhausted; refusing to submit the transaction"
+    );
+    Err(Error::StacksFeeBudgetExhausted {
+        tx_kind,
+        fee,
+        spent: report.spent_ustx,
+        budget,
+    })
+}
+
+/// Record the fee paid for a stacks transaction that was accepted by the
+/// stacks node.
+pub async fn record_stacks_fee_spend<C: Context>(
+    ctx: &C,
+    txid: StacksTxId,
+    tx_kind: &str,
+    fee: u64,
+) -> Result<(), Error> {
+    let spend = StacksFeeSpend {
+        txid,
+        tx_kind: tx_kind.to_string(),
+        fee,
+        submitted_at: Timestamp::from(time::OffsetDateTime::now_utc()),
+    };
+    ctx.get_storage_mut().write_stacks_fee_spend(&spend).await
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use super::*;
+
+    fn spend(fee: u64) -> StacksFeeSpend {
+        StacksFeeSpend {
+            txid: Faker.fake(),
+            tx_kind: "complete-deposit".to_string(),
+            fee,
+            submitted_at: time::OffsetDateTime::now_utc().into(),
+        }
+    }
+
+    #[test]
+    fn budget_is_enforced_for_non_critical_transactions() {
+        let period = Duration::from_secs(3600);
+        let report = StacksFeeReport::new(Some(1000), period, vec![spend(400), spend(500)]);
+        assert_eq!(report.spent_ustx, 900);
+        assert_eq!(report.remaining_ustx, Some(100));
+
+        assert!(report.allows("complete-deposit", 100));
+        assert!(!report.allows("complete-deposit", 101));
+        assert!(!report.allows("accept-withdrawal", 101));
+        // Critical transactions are always allowed.
+        assert!(report.allows("rotate-keys", 10_000));
+
+        let report = StacksFeeReport::new(Some(1000), period, vec![spend(2000)]);
+        assert_eq!(report.remaining_ustx, Some(0));
+        assert!(!report.allows("complete-deposit", 1));
+
+        // Without a budget everything is allowed.
+        let report = StacksFeeReport::new(None, period, vec![spend(2000)]);
+        assert!(report.allows("complete-deposit", 10_000));
+    }
+}
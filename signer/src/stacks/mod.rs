@@ -2,7 +2,12 @@
 
 /// Contains an interface for interacting with a stacks node.
 pub mod api;
+pub mod block_sequence;
+pub mod contract_args;
 pub mod contracts;
+pub mod fee_budget;
+pub mod registry;
+pub mod tenure;
 /// Contains structs for signing stacks transactions using the signers'
 /// multi-sig wallet.
 pub mod wallet;
@@ -0,0 +1,550 @@
+//! A typed client for the read-only functions of the sbtc-registry
+//! contract.
+//!
+//! The [`StacksInteract`] trait exposes the raw `call-read` endpoint of a
+//! stacks node, which takes and returns untyped clarity values. The
+//! [`RegistryContractClient`] wraps it with functions that encode their
+//! arguments the way the sbtc contracts expect and decode the responses
+//! into rust types. Facts that cannot change once they are recorded in
+//! the contract, like a completed deposit or a finalized withdrawal
+//! request, are cached so that repeated lookups do not hit the stacks
+//! node.
+
+use std::num::NonZeroUsize;
+
+use bitcoin::Amount;
+use bitcoin::OutPoint;
+use blockstack_lib::types::chainstate::StacksAddress;
+use clarity::vm::Value;
+use clarity::vm::types::BuffData;
+use clarity::vm::types::OptionalData;
+use clarity::vm::types::SequenceData;
+use clarity::vm::types::TupleData;
+use lru::LruCache;
+use tokio::sync::Mutex;
+
+use crate::amount::SbtcAmount;
+use crate::error::Error;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::StacksPrincipal;
+use crate::storage::model::ToLittleEndianOrder as _;
+
+use super::api::ClarityName;
+use super::api::SignerSetInfo;
+use super::api::StacksInteract;
+use super::contracts::SmartContract;
+
+/// The name of the read-only function in the sbtc-registry contract that
+/// returns a completed deposit.
+const GET_COMPLETED_DEPOSIT_FN_NAME: &str = "get-completed-deposit";
+
+/// The name of the read-only function in the sbtc-registry contract that
+/// returns a withdrawal request along with its status.
+const GET_WITHDRAWAL_REQUEST_FN_NAME: &str = "get-withdrawal-request";
+
+/// The maximum number of entries in each of the caches of the client.
+const REGISTRY_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(1024).expect("1024 is non zero");
+
+/// A deposit that has been completed in the sbtc-registry contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedDeposit {
+    /// The amount of sBTC that was minted.
+    pub amount: SbtcAmount,
+    /// The principal that received the minted sBTC.
+    pub recipient: StacksPrincipal,
+    /// The transaction ID of the sweep transaction that swept in the
+    /// deposit.
+    pub sweep_txid: BitcoinTxId,
+    /// The hash of the bitcoin block that contains the sweep transaction.
+    pub sweep_block_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block that contains the sweep
+    /// transaction.
+    pub sweep_block_height: BitcoinBlockHeight,
+}
+
+/// The status of a withdrawal request in the sbtc-registry contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryWithdrawalStatus {
+    /// The withdrawal request has neither been accepted nor rejected.
+    Pending,
+    /// The withdrawal request has been accepted.
+    Accepted,
+    /// The withdrawal request has been rejected.
+    Rejected,
+}
+
+/// The bitcoin recipient of a withdrawal request, in the format used by
+/// the pox contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalRecipient {
+    /// The version byte of the address.
+    pub version: u8,
+    /// The hash bytes of the address, at most 32 bytes.
+    pub hashbytes: Vec<u8>,
+}
+
+/// A withdrawal request as recorded in the sbtc-registry contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryWithdrawal {
+    /// The ID of the withdrawal request.
+    pub request_id: u64,
+    /// The amount of sBTC to withdraw.
+    pub amount: SbtcAmount,
+    /// The maximum bitcoin fee that the user is willing to pay.
+    pub max_fee: Amount,
+    /// The principal that made the withdrawal request.
+    pub sender: StacksPrincipal,
+    /// The bitcoin recipient of the withdrawal.
+    pub recipient: WithdrawalRecipient,
+    /// The height of the bitcoin block when the withdrawal request was
+    /// created.
+    pub block_height: BitcoinBlockHeight,
+    /// The status of the withdrawal request.
+    pub status: RegistryWithdrawalStatus,
+}
+
+/// A typed client for the read-only functions of the sbtc-registry
+/// contract.
+pub struct RegistryContractClient<S> {
+    /// The client used to make the read-only calls.
+    stacks: S,
+    /// The address that deployed the sbtc smart contracts.
+    deployer: StacksAddress,
+    /// The outpoints of deposits that are known to be completed.
+    completed_deposit_outpoints: Mutex<LruCache<OutPoint, ()>>,
+    /// Completed deposits that we have fetched before.
+    completed_deposits: Mutex<LruCache<OutPoint, CompletedDeposit>>,
+    /// Withdrawal requests that have been accepted or rejected.
+    finalized_withdrawals: Mutex<LruCache<u64, RegistryWithdrawal>>,
+}
+
+impl<S: StacksInteract> RegistryContractClient<S> {
+    /// Create a new client for the sbtc-registry contract deployed by the
+    /// given address.
+    pub fn new(stacks: S, deployer: StacksAddress) -> Self {
+        Self {
+            stacks,
+            deployer,
+            completed_deposit_outpoints: Mutex::new(LruCache::new(REGISTRY_CACHE_SIZE)),
+            completed_deposits: Mutex::new(LruCache::new(REGISTRY_CACHE_SIZE)),
+            finalized_withdrawals: Mutex::new(LruCache::new(REGISTRY_CACHE_SIZE)),
+        }
+    }
+
+    /// The address that deployed the sbtc smart contracts.
+    pub fn deployer(&self) -> &StacksAddress {
+        &self.deployer
+    }
+
+    /// Return whether sBTC has been minted for the deposit request with
+    /// the given outpoint.
+    pub async fn get_deposit_status(&self, outpoint: &OutPoint) -> Result<bool, Error> {
+        if self
+            .completed_deposit_outpoints
+            .lock()
+            .await
+            .contains(outpoint)
+            || self.completed_deposits.lock().await.contains(outpoint)
+        {
+            return Ok(true);
+        }
+
+        let completed = self
+            .stacks
+            .is_deposit_completed(&self.deployer, outpoint)
+            .await?;
+
+        // A deposit cannot be un-completed, so only the positive answers
+        // may be cached.
+        if completed {
+            self.completed_deposit_outpoints
+                .lock()
+                .await
+                .put(*outpoint, ());
+        }
+        Ok(completed)
+    }
+
+    /// Return the completed deposit with the given outpoint, if sBTC has
+    /// been minted for it.
+    pub async fn get_completed_deposit(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Option<CompletedDeposit>, Error> {
+        if let Some(deposit) = self.completed_deposits.lock().await.get(outpoint) {
+            return Ok(Some(deposit.clone()));
+        }
+
+        // The transaction IDs are written in little endian format by the
+        // complete-deposit contract call.
+        let arguments = [
+            buffer(outpoint.txid.to_le_bytes()),
+            Value::UInt(outpoint.vout as u128),
+        ];
+        let value = self
+            .call_read(GET_COMPLETED_DEPOSIT_FN_NAME, &arguments)
+            .await?;
+
+        let Some(mut reader) = TupleReader::from_optional(GET_COMPLETED_DEPOSIT_FN_NAME, value)?
+        else {
+            return Ok(None);
+        };
+        let deposit = CompletedDeposit {
+            amount: reader.uint("amount")?,
+            recipient: reader.principal("recipient")?,
+            sweep_txid: reader.little_endian_hash("sweep-txid")?,
+            sweep_block_hash: reader.little_endian_hash("sweep-burn-hash")?,
+            sweep_block_height: reader.uint::<u64>("sweep-burn-height")?.into(),
+        };
+
+        self.completed_deposits
+            .lock()
+            .await
+            .put(*outpoint, deposit.clone());
+        Ok(Some(deposit))
+    }
+
+    /// Return the withdrawal request with the given ID, if it exists.
+    pub async fn get_withdrawal(
+        &self,
+        request_id: u64,
+    ) -> Result<Option<RegistryWithdrawal>, Error> {
+        if let Some(withdrawal) = self.finalized_withdrawals.lock().await.get(&request_id) {
+            return Ok(Some(withdrawal.clone()));
+        }
+
+        let arguments = [Value::UInt(request_id as u128)];
+        let value = self
+            .call_read(GET_WITHDRAWAL_REQUEST_FN_NAME, &arguments)
+            .await?;
+
+        let function = GET_WITHDRAWAL_REQUEST_FN_NAME;
+        let Some(mut reader) = TupleReader::from_optional(function, value)? else {
+            return Ok(None);
+        };
+
+        // The status is `none` until the request is either accepted,
+        // which sets it to `true`, or rejected, which sets it to `false`.
+        let status = match reader.take("status")? {
+            Value::Optional(OptionalData { data: None }) => RegistryWithdrawalStatus::Pending,
+            Value::Optional(OptionalData { data: Some(status) }) => match *status {
+                Value::Bool(true) => RegistryWithdrawalStatus::Accepted,
+                Value::Bool(false) => RegistryWithdrawalStatus::Rejected,
+                _ => return Err(invalid(function, "status")),
+            },
+            _ => return Err(invalid(function, "status")),
+        };
+
+        let mut recipient = match reader.take("recipient")? {
+            Value::Tuple(tuple) => TupleReader::new(function, tuple),
+            _ => return Err(invalid(function, "recipient")),
+        };
+        let [version] = recipient.buffer::<1>("version")?;
+        let hashbytes = recipient.buffer_data("hashbytes")?;
+        if hashbytes.len() > 32 {
+            return Err(invalid(function, "hashbytes"));
+        }
+
+        let withdrawal = RegistryWithdrawal {
+            request_id,
+            amount: reader.uint("amount")?,
+            max_fee: Amount::from_sat(reader.uint("max-fee")?),
+            sender: reader.principal("sender")?,
+            recipient: WithdrawalRecipient { version, hashbytes },
+            block_height: reader.uint::<u64>("block-height")?.into(),
+            status,
+        };
+
+        // Once a withdrawal request is accepted or rejected that decision
+        // is final, but pending requests may change at any time.
+        if withdrawal.status != RegistryWithdrawalStatus::Pending {
+            self.finalized_withdrawals
+                .lock()
+                .await
+                .put(request_id, withdrawal.clone());
+        }
+        Ok(Some(withdrawal))
+    }
+
+    /// Return the current signer set, its aggregate key and the number
+    /// of signatures required. Returns `None` if the signer set has not
+    /// been set in the contract yet.
+    ///
+    /// The signer set changes with key rotations, so it is never cached.
+    pub async fn current_signer_set(&self) -> Result<Option<SignerSetInfo>, Error> {
+        self.stacks
+            .get_current_signer_set_info(&self.deployer)
+            .await
+    }
+
+    async fn call_read(&self, fn_name: &'static str, arguments: &[Value]) -> Result<Value, Error> {
+        self.stacks
+            .call_read(
+                &self.deployer,
+                SmartContract::SbtcRegistry,
+                ClarityName(fn_name),
+                &self.deployer,
+                arguments,
+            )
+            .await
+    }
+}
+
+fn buffer(data: impl Into<Vec<u8>>) -> Value {
+    Value::Sequence(SequenceData::Buffer(BuffData { data: data.into() }))
+}
+
+fn invalid(function: &'static str, field: &'static str) -> Error {
+    Error::InvalidRegistryValue { function, field }
+}
+
+/// Reads the fields of a clarity tuple returned by a read-only function.
+struct TupleReader {
+    function: &'static str,
+    tuple: TupleData,
+}
+
+impl TupleReader {
+    fn new(function: &'static str, tuple: TupleData) -> Self {
+        Self { function, tuple }
+    }
+
+    /// Read the response of a read-only function that returns an
+    /// optional tuple, like the ones that look up an entry of a map.
+    fn from_optional(function: &'static str, value: Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::Optional(OptionalData { data: None }) => Ok(None),
+            Value::Optional(OptionalData { data: Some(data) }) => match *data {
+                Value::Tuple(tuple) => Ok(Some(Self::new(function, tuple))),
+                _ => Err(Error::InvalidStacksResponse("expected an optional tuple")),
+            },
+            _ => Err(Error::InvalidStacksResponse("did not get optional data")),
+        }
+    }
+
+    fn take(&mut self, field: &'static str) -> Result<Value, Error> {
+        self.tuple
+            .data_map
+            .remove(field)
+            .ok_or_else(|| invalid(self.function, field))
+    }
+
+    fn uint<T: TryFrom<u128>>(&mut self, field: &'static str) -> Result<T, Error> {
+        match self.take(field)? {
+            Value::UInt(value) => T::try_from(value).map_err(|_| invalid(self.function, field)),
+            _ => Err(invalid(self.function, field)),
+        }
+    }
+
+    fn principal(&mut self, field: &'static str) -> Result<StacksPrincipal, Error> {
+        match self.take(field)? {
+            Value::Principal(principal) => Ok(StacksPrincipal::from(principal)),
+            _ => Err(invalid(self.function, field)),
+        }
+    }
+
+    fn buffer_data(&mut self, field: &'static str) -> Result<Vec<u8>, Error> {
+        match self.take(field)? {
+            Value::Sequence(SequenceData::Buffer(BuffData { data })) => Ok(data),
+            _ => Err(invalid(self.function, field)),
+        }
+    }
+
+    fn buffer<const N: usize>(&mut self, field: &'static str) -> Result<[u8; N], Error> {
+        let data = self.buffer_data(field)?;
+        data.try_into().map_err(|_| invalid(self.function, field))
+    }
+
+    /// Read a 32 byte hash that the sbtc contracts store in little endian
+    /// order.
+    fn little_endian_hash<H: From<[u8; 32]>>(&mut self, field: &'static str) -> Result<H, Error> {
+        let mut bytes: [u8; 32] = self.buffer(field)?;
+        bytes.reverse();
+        Ok(H::from(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use clarity::vm::types::PrincipalData;
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::stacks::api::MockStacksInteract;
+
+    use super::*;
+
+    fn some_tuple(fields: Vec<(&str, Value)>) -> Value {
+        let fields = fields
+            .into_iter()
+            .map(|(name, value)| (name.into(), value))
+            .collect();
+        Value::some(Value::Tuple(TupleData::from_data(fields).unwrap())).unwrap()
+    }
+
+    fn withdrawal_request(status: Option<bool>) -> Value {
+        let recipient = TupleData::from_data(vec![
+            ("version".into(), buffer([0])),
+            ("hashbytes".into(), buffer([1; 20])),
+        ])
+        .unwrap();
+        let status = match status {
+            Some(status) => Value::some(Value::Bool(status)).unwrap(),
+            None => Value::none(),
+        };
+        some_tuple(vec![
+            ("amount", Value::UInt(100_000)),
+            ("max-fee", Value::UInt(1_000)),
+            (
+                "sender",
+                Value::Principal(PrincipalData::from(Faker.fake::<StacksPrincipal>())),
+            ),
+            ("recipient", Value::Tuple(recipient)),
+            ("block-height", Value::UInt(250)),
+            ("status", status),
+        ])
+    }
+
+    #[tokio::test]
+    async fn get_withdrawal_decodes_and_caches_finalized_requests() {
+        let mut stacks = MockStacksInteract::new();
+        stacks
+            .expect_call_read()
+            .withf(|_, contract, fn_name, _, args| {
+                *contract == SmartContract::SbtcRegistry
+                    && fn_name.0 == GET_WITHDRAWAL_REQUEST_FN_NAME
+                    && args == [Value::UInt(1)]
+            })
+            .times(1)
+            .returning(|_, _, _, _, _| Box::pin(async { Ok(withdrawal_request(Some(true))) }));
+        stacks
+            .expect_call_read()
+            .withf(|_, _, _, _, args| args == [Value::UInt(2)])
+            .times(2)
+            .returning(|_, _, _, _, _| Box::pin(async { Ok(withdrawal_request(None)) }));
+        stacks
+            .expect_call_read()
+            .withf(|_, _, _, _, args| args == [Value::UInt(3)])
+            .times(1)
+            .returning(|_, _, _, _, _| Box::pin(async { Ok(Value::none()) }));
+
+        let client = RegistryContractClient::new(stacks, StacksAddress::burn_address(false));
+
+        let withdrawal = client.get_withdrawal(1).await.unwrap().unwrap();
+        assert_eq!(withdrawal.request_id, 1);
+        assert_eq!(withdrawal.amount, SbtcAmount::new(100_000));
+        assert_eq!(withdrawal.max_fee, Amount::from_sat(1_000));
+        assert_eq!(withdrawal.recipient.version, 0);
+        assert_eq!(withdrawal.recipient.hashbytes, vec![1; 20]);
+        assert_eq!(withdrawal.block_height, 250u64.into());
+        assert_eq!(withdrawal.status, RegistryWithdrawalStatus::Accepted);
+
+        // Accepted requests are served from the cache, pending ones are
+        // not.
+        assert_eq!(client.get_withdrawal(1).await.unwrap(), Some(withdrawal));
+        for _ in 0..2 {
+            let pending = client.get_withdrawal(2).await.unwrap().unwrap();
+            assert_eq!(pending.status, RegistryWithdrawalStatus::Pending);
+        }
+
+        assert_eq!(client.get_withdrawal(3).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_completed_deposit_reverses_little_endian_hashes() {
+        let outpoint = OutPoint::new(bitcoin::Txid::from_byte_array([2; 32]), 3);
+        let mut sweep_txid = [0; 32];
+        sweep_txid[0] = 1;
+
+        let mut stacks = MockStacksInteract::new();
+        stacks
+            .expect_call_read()
+            .withf(move |_, _, fn_name, _, args| {
+                fn_name.0 == GET_COMPLETED_DEPOSIT_FN_NAME
+                    && args == [buffer([2; 32]), Value::UInt(3)]
+            })
+            .times(1)
+            .returning(move |_, _, _, _, _| {
+                let value = some_tuple(vec![
+                    ("amount", Value::UInt(50_000)),
+                    (
+                        "recipient",
+                        Value::Principal(PrincipalData::from(Faker.fake::<StacksPrincipal>())),
+                    ),
+                    ("sweep-txid", buffer(sweep_txid)),
+                    ("sweep-burn-hash", buffer([4; 32])),
+                    ("sweep-burn-height", Value::UInt(123)),
+                ]);
+                Box::pin(async { Ok(value) })
+            });
+
+        let client = RegistryContractClient::new(stacks, StacksAddress::burn_address(false));
+
+        let deposit = client
+            .get_completed_deposit(&outpoint)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deposit.amount, SbtcAmount::new(50_000));
+        assert_eq!(deposit.sweep_txid.into_bytes()[31], 1);
+        assert_eq!(deposit.sweep_block_hash, BitcoinBlockHash::from([4; 32]));
+        assert_eq!(deposit.sweep_block_height, 123u64.into());
+
+        // The completed deposit is cached, and implies that the deposit
+        // status is completed.
+        assert_eq!(
+            client.get_completed_deposit(&outpoint).await.unwrap(),
+            Some(deposit)
+        );
+        assert!(client.get_deposit_status(&outpoint).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_deposit_status_caches_completed_deposits() {
+        let completed = OutPoint::new(bitcoin::Txid::from_byte_array([1; 32]), 0);
+        let pending = OutPoint::new(bitcoin::Txid::from_byte_array([2; 32]), 0);
+
+        let mut stacks = MockStacksInteract::new();
+        stacks
+            .expect_is_deposit_completed()
+            .withf(move |_, outpoint| *outpoint == completed)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(true) }));
+        stacks
+            .expect_is_deposit_completed()
+            .withf(move |_, outpoint| *outpoint == pending)
+            .times(2)
+            .returning(|_, _| Box::pin(async { Ok(false) }));
+
+        let client = RegistryContractClient::new(stacks, StacksAddress::burn_address(false));
+
+        for _ in 0..2 {
+            assert!(client.get_deposit_status(&completed).await.unwrap());
+            assert!(!client.get_deposit_status(&pending).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_responses_name_the_offending_field() {
+        let mut stacks = MockStacksInteract::new();
+        stacks.expect_call_read().returning(|_, _, _, _, _| {
+            let value = some_tuple(vec![("amount", Value::Bool(true))]);
+            Box::pin(async { Ok(value) })
+        });
+
+        let client = RegistryContractClient::new(stacks, StacksAddress::burn_address(false));
+        let outpoint = OutPoint::null();
+
+        let err = client.get_completed_deposit(&outpoint).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidRegistryValue {
+                function: GET_COMPLETED_DEPOSIT_FN_NAME,
+                field: "amount",
+            }
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+//! Tracking of stacks tenures from the blocks that the stacks node sends
+//! to the event observer.
+//!
+//! Since Nakamoto, the winner of the sortition in a bitcoin block starts a
+//! tenure, in which it mines stacks blocks until the next tenure starts.
+//! The first block of a tenure has a tenure-change transaction with the
+//! `BlockFound` cause. When the winner of a sortition does not mine any
+//! blocks, the miner of the ongoing tenure may extend its tenure instead,
+//! with a tenure-change transaction with the `Extended` cause. Bitcoin
+//! blocks in which no tenure starts and no tenure is extended are flash
+//! blocks, which usually arrive too quickly after the previous bitcoin
+//! block for their miner to mine anything.
+//!
+//! The stacks chain only learns about a bitcoin block once a tenure starts
+//! or is extended in it. Until then, contract calls that depend on the
+//! bitcoin block, like the ones that complete deposits swept in it, cannot
+//! be mined, which the coordinator takes into account when it decides
+//! when to submit its stacks transactions.
+
+use std::ops::RangeInclusive;
+
+use blockstack_lib::chainstate::stacks::TenureChangeCause;
+use blockstack_lib::chainstate::stacks::TransactionPayload;
+use sbtc::webhooks::NewBlockEvent;
+
+use crate::storage::model::BitcoinBlockHeight;
+
+/// The reason for a tenure change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, strum::IntoStaticStr)]
+#[sqlx(type_name = "tenure_change_kind", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TenureChangeKind {
+    /// The miner won the sortition of a bitcoin block and started a new
+    /// tenure.
+    BlockFound,
+    /// The miner of the ongoing tenure extended it.
+    Extended,
+}
+
+/// A tenure change in a stacks block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TenureChange {
+    /// The reason for the tenure change.
+    pub kind: TenureChangeKind,
+    /// The height of the bitcoin block whose sortition elected the miner
+    /// of the tenure. Extending a tenure leaves this unchanged.
+    pub burn_block_height: BitcoinBlockHeight,
+}
+
+impl TenureChange {
+    /// Return the tenure change in the stacks block of the given webhook,
+    /// if it has one.
+    ///
+    /// Only Nakamoto blocks, which are signed by their miner, have tenure
+    /// changes, so blocks without a miner signature are skipped.
+    pub fn from_block_event(event: &NewBlockEvent) -> Option<Self> {
+        event.miner_signature.as_ref()?;
+
+        let cause = event
+            .transactions
+            .iter()
+            .filter_map(|receipt| receipt.tx.as_ref())
+            .find_map(|tx| match &tx.payload {
+                TransactionPayload::TenureChange(payload) => Some(payload.cause),
+                _ => None,
+            })?;
+
+        let kind = if matches!(cause, TenureChangeCause::BlockFound) {
+            TenureChangeKind::BlockFound
+        } else {
+            TenureChangeKind::Extended
+        };
+
+        Some(Self {
+            kind,
+            burn_block_height: event.burn_block_height.into(),
+        })
+    }
+}
+
+/// The stacks tenures that the signer has observed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StacksTenures {
+    /// The height of the bitcoin block whose sortition started the most
+    /// recent tenure.
+    started_at: Option<BitcoinBlockHeight>,
+    /// The height of the most recent bitcoin block in which a tenure
+    /// started or was extended.
+    covered_through: Option<BitcoinBlockHeight>,
+}
+
+impl StacksTenures {
+    /// Record the given tenure change, returning the heights of the
+    /// bitcoin blocks that it shows to be flash blocks, if any.
+    ///
+    /// The tenure-change transaction of an extension does not carry the
+    /// height of the bitcoin block that it extends the tenure into, so an
+    /// extension is taken to be for the given bitcoin chain tip.
+    pub fn observe(
+        &mut self,
+        change: TenureChange,
+        bitcoin_chain_tip: Option<BitcoinBlockHeight>,
+    ) -> Option<RangeInclusive<BitcoinBlockHeight>> {
+        let height = change.burn_block_height;
+        match change.kind {
+            TenureChangeKind::BlockFound => {
+                // Replayed webhooks, and the blocks of a stacks fork, may
+                // start tenures that we have already moved past.
+                if self
+                    .started_at
+                    .is_some_and(|started_at| height <= started_at)
+                {
+                    return None;
+                }
+                let flash_blocks = self
+                    .covered_through
+                    .map(|covered_through| covered_through + 1)
+                    .filter(|first| *first < height)
+                    .map(|first| first..=height - 1);
+
+                self.started_at = Some(height);
+                self.covered_through = self.covered_through.max(Some(height));
+                flash_blocks
+            }
+            TenureChangeKind::Extended => {
+                let extended_to = bitcoin_chain_tip.map_or(height, |tip| tip.max(height));
+                self.covered_through = self.covered_through.max(Some(extended_to));
+                None
+            }
+        }
+    }
+
+    /// Whether a tenure started or was extended in the bitcoin block with
+    /// the given height, or in a later one.
+    ///
+    /// This is [`None`] when no tenure changes have been observed, which is
+    /// the case before Nakamoto and until the stacks node sends us a block
+    /// with a tenure change after the signer starts.
+    pub fn has_tenure_at(&self, height: BitcoinBlockHeight) -> Option<bool> {
+        self.covered_through
+            .map(|covered_through| covered_through >= height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_found(height: u64) -> TenureChange {
+        TenureChange {
+            kind: TenureChangeKind::BlockFound,
+            burn_block_height: height.into(),
+        }
+    }
+
+    fn extended(height: u64) -> TenureChange {
+        TenureChange {
+            kind: TenureChangeKind::Extended,
+            burn_block_height: height.into(),
+        }
+    }
+
+    fn heights(first: u64, last: u64) -> RangeInclusive<BitcoinBlockHeight> {
+        BitcoinBlockHeight::from(first)..=BitcoinBlockHeight::from(last)
+    }
+
+    #[test]
+    fn nothing_is_known_before_the_first_tenure_change() {
+        let mut tenures = StacksTenures::default();
+        assert_eq!(tenures.has_tenure_at(100u64.into()), None);
+
+        // There is nothing to compare the first tenure with, so it cannot
+        // show any flash blocks.
+        assert_eq!(tenures.observe(block_found(100), None), None);
+        assert_eq!(tenures.has_tenure_at(100u64.into()), Some(true));
+        assert_eq!(tenures.has_tenure_at(101u64.into()), Some(false));
+    }
+
+    #[test]
+    fn skipped_bitcoin_blocks_are_flash_blocks() {
+        let mut tenures = StacksTenures::default();
+        tenures.observe(block_found(100), None);
+        assert_eq!(tenures.observe(block_found(101), None), None);
+
+        let flash_blocks = tenures.observe(block_found(104), None);
+        assert_eq!(flash_blocks, Some(heights(102, 103)));
+        assert_eq!(tenures.has_tenure_at(104u64.into()), Some(true));
+
+        // Tenures that we have moved past are ignored.
+        assert_eq!(tenures.observe(block_found(102), None), None);
+        assert_eq!(tenures.observe(block_found(104), None), None);
+        assert_eq!(tenures.has_tenure_at(105u64.into()), Some(false));
+    }
+
+    #[test]
+    fn extended_tenures_cover_the_bitcoin_chain_tip() {
+        let mut tenures = StacksTenures::default();
+        tenures.observe(block_found(100), None);
+
+        // The miner of the tenure started at 100 extends it into 101.
+        assert_eq!(tenures.observe(extended(100), Some(101u64.into())), None);
+        assert_eq!(tenures.has_tenure_at(101u64.into()), Some(true));
+
+        // Only bitcoin block 102 had neither a new nor an extended tenure.
+        let flash_blocks = tenures.observe(block_found(103), Some(103u64.into()));
+        assert_eq!(flash_blocks, Some(heights(102, 102)));
+
+        // An extension is never taken to be for an earlier bitcoin block
+        // than its tenure.
+        tenures.observe(extended(103), Some(99u64.into()));
+        assert_eq!(tenures.has_tenure_at(103u64.into()), Some(true));
+        assert_eq!(tenures.has_tenure_at(104u64.into()), Some(false));
+    }
+}
@@ -361,6 +361,21 @@ impl MultisigTx {
             .ok_or_else(|| Error::UnknownPublicKey(public_key, self.digest))
     }
 
+    /// Drop all but the first `limit` of the signatures received so far.
+    ///
+    /// The coordinator may collect more signatures than the wallet
+    /// requires as approvals for high-impact transactions, see
+    /// [`crate::quorum`], but only the required number go into the
+    /// transaction, since its fee was estimated for a transaction with
+    /// that many signatures.
+    pub fn retain_signatures(&mut self, limit: u16) {
+        self.signatures
+            .values_mut()
+            .filter(|maybe_sig| maybe_sig.is_some())
+            .skip(limit as usize)
+            .for_each(|maybe_sig| *maybe_sig = None);
+    }
+
     /// Creates a signed transaction with the available signatures
     pub fn finalize_transaction(mut self) -> StacksTransaction {
         use TransactionSpendingCondition::OrderIndependentMultisig;
@@ -398,7 +413,7 @@ where
     let private_keys = DUMMY_PRIVATE_KEYS
         .iter()
         .take(num_signers)
-        .copied()
+        .cloned()
         .collect::<Vec<_>>();
 
     let public_keys: Vec<_> = private_keys
@@ -572,6 +587,30 @@ mod tests {
         tx.verify().unwrap();
     }
 
+    #[test]
+    fn retain_signatures_drops_excess_signatures() {
+        let key_pairs: Vec<Keypair> = std::iter::repeat_with(|| Keypair::new_global(&mut OsRng))
+            .take(5)
+            .collect();
+
+        let public_keys: Vec<_> = key_pairs.iter().map(|kp| kp.public_key().into()).collect();
+        let wallet = SignerWallet::new(&public_keys, 3, NetworkKind::Testnet, 1).unwrap();
+
+        let mut tx_signer =
+            MultisigTx::new_contract_call(TestContractCall::default(), &wallet, TX_FEE);
+        for kp in key_pairs.iter() {
+            let signature = sign_stacks_tx(tx_signer.tx(), &kp.secret_key().into());
+            tx_signer.add_signature(signature).unwrap();
+        }
+        assert_eq!(tx_signer.num_signatures(), 5);
+
+        tx_signer.retain_signatures(3);
+        assert_eq!(tx_signer.num_signatures(), 3);
+
+        let tx = tx_signer.finalize_transaction();
+        tx.verify().unwrap();
+    }
+
     /// If one of the signers signs a digest with the wrong key, then we
     /// will reject it. We also reject the case where they sign the wrong
     /// digest with a "correct" key.
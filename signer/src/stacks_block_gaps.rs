@@ -0,0 +1,258 @@
+//! # Stacks block gaps
+//!
+//! The stacks node can skip the `POST /new_block` webhooks of some blocks,
+//! or deliver blocks out of order, see [`crate::stacks::block_sequence`].
+//! The [`StacksBlockGapFiller`] follows the stacks blocks that the webhooks
+//! tell us about, and when the webhooks of some blocks were skipped, it
+//! fetches the headers of the skipped blocks from the stacks node, so that
+//! the signer's view of the stacks blockchain has no holes.
+//!
+//! This happens in a task of its own rather than while the stacks node
+//! waits for a response to the webhook, since walking back over the
+//! skipped blocks takes a request to the stacks node for each of them.
+//! Gaps that cannot be filled, say because the stacks node is not
+//! responding, are tried again periodically.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use blockstack_lib::chainstate::stacks::TransactionPayload;
+use stacks_common::types::chainstate::StacksBlockId;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::MissedTickBehavior;
+
+use crate::context::Context;
+use crate::context::StacksBlockObserved;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::stacks::api::StacksInteract as _;
+use crate::stacks::api::TenureBlockHeaders;
+use crate::stacks::block_sequence::BlockOrder;
+use crate::storage::DbWrite as _;
+use crate::storage::model::StacksBlock;
+
+/// The maximum number of skipped stacks blocks that we fetch from the
+/// stacks node for a single gap.
+const MAX_STACKS_BLOCK_GAP: usize = 100;
+
+/// The maximum number of gaps that are kept to be tried again. When there
+/// are more, the oldest gap is given up on.
+const MAX_PENDING_GAPS: usize = 100;
+
+/// The stacks blocks between the last block whose webhook we handled and
+/// a later block, whose webhooks the stacks node skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StacksBlockGap {
+    /// The block whose webhook arrived after the skipped ones.
+    block: StacksBlock,
+    /// The last block whose webhook we handled before the skipped ones.
+    last: StacksBlock,
+}
+
+/// Detects the stacks blocks whose webhooks the stacks node skipped and
+/// fetches them from the stacks node.
+pub struct StacksBlockGapFiller<C> {
+    /// Signer context.
+    context: C,
+    /// Interval at which the gaps that could not be filled are tried
+    /// again.
+    interval: Duration,
+    /// The gaps that could not be filled yet, oldest first.
+    gaps: VecDeque<StacksBlockGap>,
+}
+
+impl<C> StacksBlockGapFiller<C>
+where
+    C: Context,
+{
+    /// Creates a new StacksBlockGapFiller with the given context and retry
+    /// interval.
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self {
+            context,
+            interval,
+            gaps: VecDeque::new(),
+        }
+    }
+
+    /// Runs the StacksBlockGapFiller, which checks every stacks block that
+    /// the stacks node tells us about, and tries to fill the gaps that
+    /// could not be filled yet once every interval.
+    pub async fn run(mut self) {
+        let mut term = self.context.get_termination_handle();
+        let mut blocks = self.context.events().subscribe::<StacksBlockObserved>();
+        let mut retries = tokio::time::interval(self.interval);
+        retries.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = retries.tick() => {
+                    self.fill_gaps().await;
+                }
+                event = blocks.recv() => match event {
+                    Ok(StacksBlockObserved { block }) => self.observe(&block).await,
+                    // The blocks that we missed show up as a gap before
+                    // the next block that we receive, and blocks that are
+                    // already known are not fetched again.
+                    Err(RecvError::Lagged(count)) => {
+                        tracing::warn!(%count, "the stacks block gap filler fell behind");
+                    }
+                    // The bus lives as long as the context, so this only
+                    // happens on the way down.
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        tracing::info!("stacks block gap filler has stopped");
+    }
+
+    /// Check how the given stacks block relates to the last one whose
+    /// webhook arrived, and fetch the skipped blocks if there are any.
+    #[tracing::instrument(skip_all, name = "stacks-block-gap-filler", fields(
+        block_hash = %block.block_hash,
+        block_height = %block.block_height,
+    ))]
+    async fn observe(&mut self, block: &StacksBlock) {
+        match self.context.state().observe_stacks_block(block) {
+            BlockOrder::Next => {}
+            // This is either a webhook that was delivered again or a block
+            // on another stacks fork, both of which are handled as usual.
+            BlockOrder::Regression { last } => {
+                tracing::warn!(
+                    last_block_hash = %last.block_hash,
+                    last_block_height = %last.block_height,
+                    "the stacks block is not higher than the last one whose webhook we handled"
+                );
+                metrics::counter!(Metrics::StacksBlockOrderAnomaliesTotal, "kind" => "regression")
+                    .increment(1);
+            }
+            BlockOrder::Gap { last } => {
+                tracing::warn!(
+                    last_block_hash = %last.block_hash,
+                    last_block_height = %last.block_height,
+                    "the stacks node skipped the webhooks of some stacks blocks"
+                );
+                metrics::counter!(Metrics::StacksBlockOrderAnomaliesTotal, "kind" => "gap")
+                    .increment(1);
+
+                let overflow = (self.gaps.len() + 1).saturating_sub(MAX_PENDING_GAPS);
+                for gap in self.gaps.drain(..overflow) {
+                    tracing::error!(
+                        block_hash = %gap.block.block_hash,
+                        last_block_hash = %gap.last.block_hash,
+                        "giving up on fetching the skipped stacks blocks of a gap"
+                    );
+                }
+                self.gaps
+                    .push_back(StacksBlockGap { block: block.clone(), last });
+                self.fill_gaps().await;
+            }
+        }
+    }
+
+    /// Try to fill each of the gaps that were not filled yet, keeping the
+    /// ones that still could not be filled.
+    async fn fill_gaps(&mut self) {
+        for _ in 0..self.gaps.len() {
+            let Some(gap) = self.gaps.pop_front() else {
+                break;
+            };
+            if let Err(error) = fill_stacks_block_gap(&self.context, &gap).await {
+                tracing::warn!(
+                    %error,
+                    block_hash = %gap.block.block_hash,
+                    last_block_hash = %gap.last.block_hash,
+                    "could not fetch the skipped stacks blocks; trying again later"
+                );
+                self.gaps.push_back(gap);
+            }
+        }
+    }
+}
+
+/// Fetch the stacks blocks of the given gap, whose webhooks the stacks
+/// node skipped.
+///
+/// The headers of the skipped blocks are written to the database, so that
+/// the signer's view of the stacks blockchain has no holes. The events of
+/// the skipped blocks cannot be recovered, since the RPC endpoints of the
+/// stacks node do not return them, so we report the skipped blocks with
+/// calls to the sBTC contracts loudly, as their events need to be
+/// replayed by an operator.
+async fn fill_stacks_block_gap(ctx: &impl Context, gap: &StacksBlockGap) -> Result<(), Error> {
+    let stacks = ctx.get_stacks_client();
+    let db = ctx.get_storage_mut();
+    let parent_id = StacksBlockId::from(gap.block.parent_hash);
+
+    let headers = crate::stacks::api::fetch_unknown_ancestors(&stacks, &db, &parent_id)
+        .await?
+        .into_iter()
+        .flat_map(TenureBlockHeaders::into_iter)
+        .collect::<Vec<_>>();
+    db.write_stacks_block_headers(headers).await?;
+
+    let deployer = &ctx.config().signer.deployer;
+    let mut block_id = parent_id;
+    for _ in 0..MAX_STACKS_BLOCK_GAP {
+        let nakamoto_block = stacks.get_block(&block_id).await?;
+        if nakamoto_block.header.chain_length <= *gap.last.block_height {
+            break;
+        }
+
+        let calls_sbtc_contracts = nakamoto_block.txs.iter().any(|tx| {
+            matches!(
+                &tx.payload,
+                TransactionPayload::ContractCall(call) if &call.address == deployer
+            )
+        });
+        if calls_sbtc_contracts {
+            tracing::error!(
+                skipped_block_hash = %block_id,
+                skipped_block_height = %nakamoto_block.header.chain_length,
+                "the webhook of a stacks block with calls to the sBTC contracts was skipped"
+            );
+            metrics::counter!(Metrics::StacksBlocksMissedTotal).increment(1);
+        }
+        block_id = nakamoto_block.header.parent_block_id;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::context::TestContext;
+
+    use super::*;
+
+    /// Check that the gaps that cannot be filled, because the stacks node
+    /// does not respond, are kept and tried again.
+    #[tokio::test]
+    async fn gaps_that_cannot_be_filled_are_tried_again() {
+        let ctx = TestContext::default_mocked();
+        ctx.with_stacks_client(|client| {
+            client
+                .expect_get_tenure()
+                .times(2)
+                .returning(|_| Box::pin(async { Err(Error::Dummy) }));
+        })
+        .await;
+
+        let mut filler = StacksBlockGapFiller::new(ctx.clone(), Duration::from_secs(60));
+        let genesis = StacksBlock::new_genesis();
+        let child = genesis.new_child();
+        filler.observe(&genesis).await;
+        filler.observe(&child).await;
+        assert!(filler.gaps.is_empty());
+
+        let block = child.new_child().new_child();
+        filler.observe(&block).await;
+        let gap = StacksBlockGap { block, last: child };
+        assert_eq!(filler.gaps, [gap.clone()]);
+
+        filler.fill_gaps().await;
+        assert_eq!(filler.gaps, [gap]);
+    }
+}
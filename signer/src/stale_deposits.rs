@@ -0,0 +1,356 @@
+//! # Stale deposit requests
+//!
+//! A deposit request stays pending for as long as its deposit UTXO can be
+//! swept. Some deposit UTXOs never become sweepable: the deposit
+//! transaction is dropped from the mempool, or reorged out and never
+//! confirmed again, or the depositor reclaims the deposit after its lock
+//! time. Nothing else marks these requests as done, so over months of
+//! operation they pile up in the pending queue and in Emily.
+//!
+//! The [`StaleDepositCollector`] periodically looks for deposit requests
+//! that were recorded more than
+//! [`stale_deposit_min_age`](crate::config::SignerConfig::stale_deposit_min_age)
+//! ago and have neither been swept nor minted against. It marks the ones
+//! whose deposit UTXO bitcoin-core does not know about as stale, which
+//! the coordinator then leaves out, and reports them to Emily as failed.
+
+use std::time::Duration;
+
+use bitcoin::OutPoint;
+use emily_client::models::DepositStatus;
+use emily_client::models::DepositUpdate;
+
+use crate::bitcoin::BitcoinInteract;
+use crate::context::Context;
+use crate::emily_client::EmilyInteract as _;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::StaleDepositReason;
+use crate::storage::model::StaleDepositRequest;
+
+/// The maximum number of deposits updated in a single request to Emily.
+const EMILY_UPDATE_BATCH_SIZE: usize = 100;
+
+/// Marks deposit requests whose deposit UTXO can no longer be swept as
+/// stale, and reports them to Emily as failed.
+pub struct StaleDepositCollector<C> {
+    /// Signer context.
+    context: C,
+    /// Collection interval.
+    interval: Duration,
+    /// How long ago a deposit request must have been recorded before it
+    /// can be marked as stale.
+    min_age: Duration,
+}
+
+impl<C> StaleDepositCollector<C>
+where
+    C: Context,
+{
+    /// Creates a new StaleDepositCollector with the given context,
+    /// interval and minimum age of stale deposit requests.
+    pub fn new(context: C, interval: Duration, min_age: Duration) -> Self {
+        Self { context, interval, min_age }
+    }
+
+    /// Runs the StaleDepositCollector, which collects stale deposit
+    /// requests right away and then once every interval.
+    pub async fn run(self) {
+        let mut term = self.context.get_termination_handle();
+        self.collect().await;
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {
+                    self.collect().await;
+                }
+            }
+        }
+        tracing::info!("stale deposit collector has stopped");
+    }
+
+    /// Mark stale deposit requests and report the ones that Emily has not
+    /// accepted yet. Stale deposit requests whose report fails are
+    /// reported again on the next collection.
+    #[tracing::instrument(skip_all, name = "stale-deposit-collector")]
+    pub async fn collect(&self) {
+        if let Err(error) = self.mark_stale_deposits().await {
+            tracing::warn!(%error, "could not mark stale deposit requests");
+        }
+        if let Err(error) = self.sync_with_emily().await {
+            tracing::warn!(%error, "could not report stale deposit requests to Emily");
+        }
+    }
+
+    async fn mark_stale_deposits(&self) -> Result<(), Error> {
+        // A deposit that the signers swept in a block that we have not
+        // processed yet looks reclaimed to bitcoin-core, so we only look
+        // for stale deposits while we are caught up with it, and throw
+        // away what we found if a new block arrived in the meantime.
+        let Some(chain_tip) = self.context.state().bitcoin_chain_tip() else {
+            return Ok(());
+        };
+        if !self.is_caught_up(&chain_tip.block_hash).await? {
+            tracing::debug!("not caught up with bitcoin-core, skipping stale deposit check");
+            return Ok(());
+        }
+
+        let storage = self.context.get_storage_mut();
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let recorded_before = (time::OffsetDateTime::now_utc() - self.min_age).into();
+
+        let mut stale = Vec::new();
+        for deposit in storage
+            .get_stale_deposit_candidates(recorded_before)
+            .await?
+        {
+            let outpoint = deposit.outpoint();
+            if let Some(reason) = stale_deposit_reason(&bitcoin_client, &outpoint).await? {
+                stale.push(StaleDepositRequest {
+                    txid: deposit.txid,
+                    output_index: deposit.output_index,
+                    reason,
+                    emily_synced_at: None,
+                });
+            }
+        }
+
+        if !self.is_caught_up(&chain_tip.block_hash).await? {
+            tracing::debug!("bitcoin-core moved on, discarding stale deposit check");
+            return Ok(());
+        }
+
+        for request in stale {
+            tracing::info!(
+                txid = %request.txid,
+                output_index = request.output_index,
+                reason = %request.reason,
+                "marking deposit request as stale"
+            );
+            storage.write_stale_deposit_request(&request).await?;
+            Metrics::increment_stale_deposits(request.reason.into());
+        }
+
+        Ok(())
+    }
+
+    async fn sync_with_emily(&self) -> Result<(), Error> {
+        let storage = self.context.get_storage_mut();
+        let unsynced: Vec<_> = storage
+            .get_stale_deposit_requests()
+            .await?
+            .into_iter()
+            .filter(|stale| stale.emily_synced_at.is_none())
+            .collect();
+
+        let emily_client = self.context.get_emily_client();
+        for batch in unsynced.chunks(EMILY_UPDATE_BATCH_SIZE) {
+            let mut updates = Vec::with_capacity(batch.len());
+            for stale in batch {
+                let correlation_id = storage
+                    .get_deposit_correlation_id(&stale.txid, stale.output_index)
+                    .await?;
+                updates.push(DepositUpdate {
+                    bitcoin_tx_output_index: stale.output_index,
+                    bitcoin_txid: stale.txid.to_string(),
+                    status: DepositStatus::Failed,
+                    fulfillment: None,
+                    status_message: status_message(stale.reason).to_string(),
+                    replaced_by_tx: None,
+                    correlation_id: correlation_id.map(Some),
+                });
+            }
+
+            // Emily returns the deposits in the order of the updates.
+            let response = emily_client.update_deposits(updates).await?;
+            for (stale, deposit) in batch.iter().zip(response.deposits) {
+                // Emily does not know about deposits that were only
+                // submitted to the signers, so there is nothing to update.
+                if deposit.status != 200 && deposit.status != 404 {
+                    tracing::warn!(
+                        txid = %stale.txid,
+                        output_index = stale.output_index,
+                        status = deposit.status,
+                        error = ?deposit.error.flatten(),
+                        "Emily did not accept the failure of a stale deposit request"
+                    );
+                    continue;
+                }
+                storage
+                    .mark_stale_deposit_request_synced(&stale.txid, stale.output_index)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the block observer has processed the chain tip of our
+    /// bitcoin node.
+    async fn is_caught_up(&self, chain_tip: &BitcoinBlockHash) -> Result<bool, Error> {
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let best_block_hash = bitcoin_client.get_best_block_hash().await?;
+        Ok(&BitcoinBlockHash::from(best_block_hash) == chain_tip)
+    }
+}
+
+/// Return why the deposit with the given outpoint is stale, or `None` if
+/// its deposit UTXO may still be swept.
+///
+/// This must only be called for deposits that we have not recorded a
+/// sweep or a mint of, otherwise a swept deposit is reported as
+/// reclaimed.
+pub async fn stale_deposit_reason<B>(
+    bitcoin_client: &B,
+    outpoint: &OutPoint,
+) -> Result<Option<StaleDepositReason>, Error>
+where
+    B: BitcoinInteract,
+{
+    let Some(response) = bitcoin_client.get_tx(&outpoint.txid).await? else {
+        return Ok(Some(StaleDepositReason::NeverConfirmed));
+    };
+    // The deposit transaction is in the mempool, so it may yet confirm.
+    if response.block_hash.is_none() {
+        return Ok(None);
+    }
+    // We leave out the mempool, since our own sweep transactions may be
+    // spending the deposit there.
+    let txout = bitcoin_client
+        .get_transaction_output(outpoint, false)
+        .await?;
+    Ok(txout.is_none().then_some(StaleDepositReason::Reclaimed))
+}
+
+/// The status message reported to Emily for a stale deposit request.
+fn status_message(reason: StaleDepositReason) -> &'static str {
+    match reason {
+        StaleDepositReason::NeverConfirmed => "the deposit transaction was never confirmed",
+        StaleDepositReason::Reclaimed => "the deposit was reclaimed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use emily_client::models::DepositWithStatus;
+    use emily_client::models::UpdateDepositsResponse;
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::bitcoin::MockBitcoinInteract;
+    use crate::bitcoin::rpc::GetTxResponse;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    use bitcoin::hashes::Hash as _;
+
+    fn get_tx_response(block_hash: Option<bitcoin::BlockHash>) -> GetTxResponse {
+        GetTxResponse {
+            tx: bitcoin::Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: bitcoin::absolute::LockTime::ZERO,
+                input: Vec::new(),
+                output: Vec::new(),
+            },
+            block_hash,
+            confirmations: block_hash.map(|_| 1),
+            block_time: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_deposits_are_classified() {
+        let outpoint = OutPoint::null();
+        let block_hash = bitcoin::BlockHash::from_byte_array([1; 32]);
+
+        let mut client = MockBitcoinInteract::new();
+        client
+            .expect_get_tx()
+            .returning(|_| Box::pin(std::future::ready(Ok(None))));
+        let reason = stale_deposit_reason(&client, &outpoint).await.unwrap();
+        assert_eq!(reason, Some(StaleDepositReason::NeverConfirmed));
+
+        let mut client = MockBitcoinInteract::new();
+        client
+            .expect_get_tx()
+            .returning(|_| Box::pin(std::future::ready(Ok(Some(get_tx_response(None))))));
+        client.expect_get_transaction_output().never();
+        let reason = stale_deposit_reason(&client, &outpoint).await.unwrap();
+        assert_eq!(reason, None);
+
+        let mut client = MockBitcoinInteract::new();
+        client.expect_get_tx().returning(move |_| {
+            Box::pin(std::future::ready(Ok(Some(get_tx_response(Some(
+                block_hash,
+            ))))))
+        });
+        client
+            .expect_get_transaction_output()
+            .withf(|_, include_mempool| !include_mempool)
+            .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
+        let reason = stale_deposit_reason(&client, &outpoint).await.unwrap();
+        assert_eq!(reason, Some(StaleDepositReason::Reclaimed));
+    }
+
+    /// Stale deposit requests are reported to Emily until Emily accepts
+    /// their failure.
+    #[tokio::test]
+    async fn stale_deposits_are_synced_with_emily() {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+        let mut rng = get_rng();
+
+        let accepted = StaleDepositRequest {
+            txid: Faker.fake_with_rng(&mut rng),
+            output_index: 0,
+            reason: StaleDepositReason::Reclaimed,
+            emily_synced_at: None,
+        };
+        let rejected = StaleDepositRequest {
+            txid: Faker.fake_with_rng(&mut rng),
+            output_index: 1,
+            reason: StaleDepositReason::NeverConfirmed,
+            emily_synced_at: None,
+        };
+        db.write_stale_deposit_request(&accepted).await.unwrap();
+        db.write_stale_deposit_request(&rejected).await.unwrap();
+
+        ctx.with_emily_client(|client| {
+            client
+                .expect_update_deposits()
+                .times(1)
+                .withf(|updates| {
+                    updates.len() == 2
+                        && updates
+                            .iter()
+                            .all(|update| update.status == DepositStatus::Failed)
+                })
+                .returning(|_| {
+                    let response = UpdateDepositsResponse {
+                        deposits: vec![DepositWithStatus::new(200), DepositWithStatus::new(500)],
+                    };
+                    Box::pin(std::future::ready(Ok(response)))
+                });
+        })
+        .await;
+
+        let collector = StaleDepositCollector::new(ctx.clone(), Duration::ZERO, Duration::ZERO);
+        collector.sync_with_emily().await.unwrap();
+
+        let stale = db.get_stale_deposit_requests().await.unwrap();
+        let synced: Vec<model::BitcoinTxId> = stale
+            .iter()
+            .filter(|stale| stale.emily_synced_at.is_some())
+            .map(|stale| stale.txid)
+            .collect();
+        assert_eq!(synced, vec![accepted.txid]);
+    }
+}
@@ -5,6 +5,7 @@ use libp2p::PeerId;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use time::OffsetDateTime;
@@ -42,6 +43,9 @@ pub struct Store {
     /// Bitcoin blocks
     pub bitcoin_blocks: HashMap<model::BitcoinBlockHash, model::BitcoinBlock>,
 
+    /// Bitcoin block activity
+    pub bitcoin_block_activity: HashMap<model::BitcoinBlockHash, model::BitcoinBlockActivity>,
+
     /// Stacks blocks
     pub stacks_blocks: HashMap<model::StacksBlockHash, model::StacksBlock>,
 
@@ -99,10 +103,10 @@ pub struct Store {
     /// more than one withdrawal-reject event because of reorgs.
     pub withdrawal_reject_events: HashMap<u64, WithdrawalRejectEvent>,
 
-    /// A mapping between request_ids and completed-deposit events. Note
-    /// that in prod we can have a single outpoint be associated with
-    /// more than one completed-deposit event because of reorgs.
-    pub completed_deposit_events: HashMap<OutPoint, CompletedDepositEvent>,
+    /// A mapping between outpoints and completed-deposit events. A single
+    /// outpoint can be associated with more than one completed-deposit
+    /// event because of reorgs.
+    pub completed_deposit_events: HashMap<OutPoint, Vec<CompletedDepositEvent>>,
 
     /// Bitcoin transaction outputs
     pub bitcoin_outputs: HashMap<model::BitcoinTxId, Vec<model::TxOutput>>,
@@ -119,6 +123,106 @@ pub struct Store {
 
     /// Stored P2P peers
     pub p2p_peers: HashMap<(PeerId, PublicKey), model::P2PPeer>,
+
+    /// Peer bans, by the peer ID of the banned peer.
+    pub peer_bans: HashMap<PeerId, model::PeerBan>,
+
+    /// Signer identity rotations, keyed by the old public key
+    pub signer_identity_rotations: BTreeMap<PublicKey, model::SignerIdentityRotation>,
+
+    /// The intake sources of deposit requests, in the order that they
+    /// were first seen
+    pub deposit_request_sources: HashMap<DepositRequestPk, Vec<model::DepositRequestSource>>,
+
+    /// Signing failures of deposit and withdrawal requests
+    pub request_failures: HashMap<model::SbtcRequestKey, model::RequestFailures>,
+
+    /// The URLs of webhook subscribers, in the order that they were
+    /// registered
+    pub webhook_subscribers: Vec<String>,
+
+    /// Integrity hashes of protected rows, keyed by table name and row key
+    pub row_integrity_hashes: BTreeMap<(String, Vec<u8>), model::RowIntegrityHash>,
+
+    /// Fees paid for submitted stacks transactions, keyed by txid
+    pub stacks_fee_spends: HashMap<model::StacksTxId, model::StacksFeeSpend>,
+
+    /// Messages collected for WSTS signing rounds, in the order that they
+    /// were written
+    pub wsts_round_messages: Vec<model::WstsRoundMessage>,
+
+    /// Observed signer response latencies, in the order that they were
+    /// written
+    pub signer_response_latencies: Vec<model::SignerResponseLatency>,
+
+    /// Indexed inputs of peg wallet transactions, keyed by txid and input
+    /// index
+    pub peg_wallet_tx_inputs: BTreeMap<(model::BitcoinTxId, u32), model::PegWalletTxInput>,
+
+    /// Indexed outputs of peg wallet transactions, keyed by txid and
+    /// output index
+    pub peg_wallet_tx_outputs: BTreeMap<(model::BitcoinTxId, u32), model::PegWalletTxOutput>,
+
+    /// Bitcoin transactions that only moved funds between the signers'
+    /// addresses
+    pub bitcoin_internal_transfers: HashSet<model::BitcoinInternalTransfer>,
+
+    /// Contract events filtered out of stacks webhooks, keyed by the
+    /// stacks block and the index of the event in the block
+    pub quarantined_contract_events:
+        BTreeMap<(model::StacksBlockHash, u64), model::QuarantinedContractEvent>,
+
+    /// The tenures of stacks blocks and the signers that signed them
+    pub stacks_block_tenures: HashMap<model::StacksBlockHash, model::StacksBlockTenure>,
+
+    /// Reports of panics in the components of the signer, in the order
+    /// that they were written
+    pub crash_reports: Vec<model::CrashReport>,
+
+    /// Deposit requests that were marked as stale, in the order that they
+    /// were marked
+    pub stale_deposit_requests: Vec<model::StaleDepositRequest>,
+
+    /// The registry contract that emitted the events of each stacks
+    /// transaction, keyed by txid and block hash
+    pub registry_event_sources:
+        HashMap<(model::StacksTxId, model::StacksBlockHash), model::StacksPrincipal>,
+
+    /// The index block hashes of the stacks blocks whose events have all
+    /// been handled
+    pub processed_stacks_blocks: HashSet<model::StacksBlockHash>,
+
+    /// The stacks transactions that completed requests fulfilled by each
+    /// sweep transaction, keyed by the txid of the sweep
+    pub sweep_completions: HashMap<model::BitcoinTxId, Vec<model::SweepCompletion>>,
+
+    /// The number of times that handling the events of each stacks block
+    /// failed, along with the error of the most recent failure
+    pub failed_stacks_blocks: HashMap<model::StacksBlockHash, (u32, String)>,
+
+    /// The zstd compressed raw bodies of the `POST /new_block` webhooks of
+    /// stacks blocks, along with when they were stored
+    pub raw_stacks_events: HashMap<model::StacksBlockHash, (Vec<u8>, model::Timestamp)>,
+
+    /// The largest stacks block height at which Emily last updated one of
+    /// the deposits fetched from it
+    pub emily_deposit_cursor: Option<u64>,
+
+    /// The periods whose records were exported to the analytics bucket,
+    /// as the start and the end of each period
+    pub analytics_exports: Vec<(model::Timestamp, model::Timestamp)>,
+
+    /// The bitcoin blocks that the stacks node reported through the
+    /// `/new_burn_block` webhook
+    pub stacks_node_burn_blocks: HashMap<model::BitcoinBlockHash, model::StacksNodeBurnBlock>,
+
+    /// The correlation IDs that Emily gave for deposit requests, keyed by
+    /// the outpoint of the deposit request
+    pub deposit_correlation_ids: HashMap<(model::BitcoinTxId, u32), String>,
+
+    /// The confirmation watches that have not fired yet, in the order that
+    /// they were written
+    pub confirmation_watches: Vec<model::ConfirmationWatch>,
 }
 
 impl Store {
@@ -24,6 +24,24 @@ impl DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn write_bitcoin_block_activity(
+        &self,
+        activity: &model::BitcoinBlockActivity,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        // The activity is stored alongside the block, so there is nothing
+        // to record for blocks that we do not know about.
+        if store.bitcoin_blocks.contains_key(&activity.block_hash) {
+            store
+                .bitcoin_block_activity
+                .insert(activity.block_hash, *activity);
+        }
+
+        Ok(())
+    }
+
     async fn write_bitcoin_transactions(&self, txs: Vec<model::BitcoinTxRef>) -> Result<(), Error> {
         let mut store = self.lock().await;
         store.version += 1;
@@ -257,9 +275,31 @@ impl DbWrite for SharedStore {
         let mut store = self.lock().await;
         store.version += 1;
 
-        store
+        let events = store
             .completed_deposit_events
-            .insert(event.outpoint, event.clone());
+            .entry(event.outpoint)
+            .or_default();
+        if !events.contains(event) {
+            events.push(event.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn write_sweep_completion(
+        &self,
+        completion: &model::SweepCompletion,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let completions = store
+            .sweep_completions
+            .entry(completion.sweep_txid)
+            .or_default();
+        if !completions.contains(completion) {
+            completions.push(*completion);
+        }
 
         Ok(())
     }
@@ -387,6 +427,525 @@ impl DbWrite for SharedStore {
 
         Ok(())
     }
+
+    async fn write_peer_ban(&self, ban: &model::PeerBan) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+        store.peer_bans.insert(*ban.peer_id, ban.clone());
+        Ok(())
+    }
+
+    async fn delete_peer_ban(&self, peer_id: &PeerId) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+        let now = time::OffsetDateTime::now_utc();
+        let ban = store.peer_bans.remove(peer_id);
+        Ok(ban.is_some_and(|ban| *ban.expires_at > now))
+    }
+
+    async fn write_signer_identity_rotation(
+        &self,
+        rotation: &model::SignerIdentityRotation,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let new_key_taken = store
+            .signer_identity_rotations
+            .values()
+            .any(|existing| existing.new_public_key == rotation.new_public_key);
+        if !new_key_taken {
+            store
+                .signer_identity_rotations
+                .entry(rotation.old_public_key)
+                .or_insert_with(|| rotation.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn write_deposit_request_intake(
+        &self,
+        intake: &model::DepositRequestIntake,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let sources = store
+            .deposit_request_sources
+            .entry((intake.txid, intake.output_index))
+            .or_default();
+        if !sources.contains(&intake.source) {
+            sources.push(intake.source);
+        }
+
+        Ok(())
+    }
+
+    async fn write_request_failure(
+        &self,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> Result<model::RequestFailures, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let failures =
+            store
+                .request_failures
+                .entry(*request)
+                .or_insert_with(|| model::RequestFailures {
+                    request: *request,
+                    failure_count: 0,
+                    last_error: String::new(),
+                    quarantined_at: None,
+                });
+
+        failures.failure_count += 1;
+        failures.last_error = error.to_string();
+        if failures.quarantined_at.is_none() && failures.failure_count >= quarantine_threshold {
+            failures.quarantined_at = Some(time::OffsetDateTime::now_utc().into());
+        }
+
+        Ok(failures.clone())
+    }
+
+    async fn release_request_failures(
+        &self,
+        request: &model::SbtcRequestKey,
+    ) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        Ok(store.request_failures.remove(request).is_some())
+    }
+
+    async fn write_stale_deposit_request(
+        &self,
+        stale: &model::StaleDepositRequest,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let exists = store.stale_deposit_requests.iter().any(|existing| {
+            existing.txid == stale.txid && existing.output_index == stale.output_index
+        });
+        if !exists {
+            store.stale_deposit_requests.push(stale.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn mark_stale_deposit_request_synced(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let synced_at = time::OffsetDateTime::now_utc().into();
+        for stale in store.stale_deposit_requests.iter_mut() {
+            let is_match = &stale.txid == txid && stale.output_index == output_index;
+            if is_match && stale.emily_synced_at.is_none() {
+                stale.emily_synced_at = Some(synced_at);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_registry_event_source(
+        &self,
+        source: &model::RegistryEventSource,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .registry_event_sources
+            .entry((source.txid, source.block_hash))
+            .or_insert_with(|| source.registry.clone());
+
+        Ok(())
+    }
+
+    async fn write_processed_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.processed_stacks_blocks.insert(*block_hash);
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_failure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> Result<u32, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let (failure_count, last_error) =
+            store.failed_stacks_blocks.entry(*block_hash).or_default();
+        *failure_count += 1;
+        *last_error = error.to_string();
+
+        Ok(*failure_count)
+    }
+
+    async fn write_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let created_at = time::OffsetDateTime::now_utc().into();
+        store
+            .raw_stacks_events
+            .entry(*block_hash)
+            .or_insert_with(|| (body.to_vec(), created_at));
+
+        Ok(())
+    }
+
+    async fn delete_raw_stacks_events_before(&self, before: model::Timestamp) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .raw_stacks_events
+            .retain(|_, (_, created_at)| *created_at >= before);
+
+        Ok(())
+    }
+
+    async fn write_emily_deposit_cursor(&self, last_update_height: u64) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.emily_deposit_cursor = Some(last_update_height);
+
+        Ok(())
+    }
+
+    async fn write_analytics_export(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        if !store
+            .analytics_exports
+            .iter()
+            .any(|(start, _)| *start == from)
+        {
+            store.analytics_exports.push((from, to));
+        }
+
+        Ok(())
+    }
+
+    async fn write_stacks_node_burn_block(
+        &self,
+        block: &model::StacksNodeBurnBlock,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .stacks_node_burn_blocks
+            .entry(block.block_hash)
+            .or_insert(*block);
+
+        Ok(())
+    }
+
+    async fn write_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        if !store.confirmation_watches.contains(watch) {
+            store.confirmation_watches.push(watch.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn delete_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let count = store.confirmation_watches.len();
+        store.confirmation_watches.retain(|stored| stored != watch);
+
+        Ok(store.confirmation_watches.len() < count)
+    }
+
+    async fn write_deposit_correlation_id(
+        &self,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.deposit_correlation_ids.insert(
+            (correlation_id.txid, correlation_id.output_index),
+            correlation_id.correlation_id.clone(),
+        );
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        // Writes to the in-memory store cannot fail, so writing the rows
+        // one after the other never leaves only some of them written.
+        for (event, source) in &events.events {
+            self.write_registry_event_source(source).await?;
+            match event {
+                model::StacksBlockEvent::CompletedDeposit(event) => {
+                    self.write_completed_deposit_event(event).await?;
+                    self.write_sweep_completion(&event.into()).await?;
+                }
+                model::StacksBlockEvent::WithdrawalCreate(request) => {
+                    self.write_withdrawal_request(request).await?;
+                }
+                model::StacksBlockEvent::WithdrawalAccept(event) => {
+                    self.write_withdrawal_accept_event(event).await?;
+                    self.write_sweep_completion(&event.into()).await?;
+                }
+                model::StacksBlockEvent::WithdrawalReject(event) => {
+                    self.write_withdrawal_reject_event(event).await?;
+                }
+                model::StacksBlockEvent::KeyRotation(event) => {
+                    self.write_rotate_keys_transaction(event).await?;
+                }
+            }
+        }
+
+        for hash in &events.integrity_hashes {
+            self.write_row_integrity_hash(hash).await?;
+        }
+
+        self.write_processed_stacks_block(&events.block_hash).await
+    }
+
+    async fn write_webhook_subscriber(&self, url: &str) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        if !store
+            .webhook_subscribers
+            .iter()
+            .any(|existing| existing == url)
+        {
+            store.webhook_subscribers.push(url.to_string());
+        }
+
+        Ok(())
+    }
+
+    async fn delete_webhook_subscriber(&self, url: &str) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let count = store.webhook_subscribers.len();
+        store.webhook_subscribers.retain(|existing| existing != url);
+        Ok(store.webhook_subscribers.len() < count)
+    }
+
+    async fn write_row_integrity_hash(&self, hash: &model::RowIntegrityHash) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .row_integrity_hashes
+            .entry((hash.table_name.clone(), hash.row_key.clone()))
+            .or_insert_with(|| hash.clone());
+
+        Ok(())
+    }
+
+    async fn write_stacks_fee_spend(&self, spend: &model::StacksFeeSpend) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .stacks_fee_spends
+            .entry(spend.txid)
+            .or_insert_with(|| spend.clone());
+
+        Ok(())
+    }
+
+    async fn write_wsts_round_message(
+        &self,
+        message: &model::WstsRoundMessage,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.wsts_round_messages.push(message.clone());
+
+        Ok(())
+    }
+
+    async fn delete_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .wsts_round_messages
+            .retain(|msg| &msg.bitcoin_chain_tip != bitcoin_chain_tip || msg.message != message);
+
+        Ok(())
+    }
+
+    async fn delete_stale_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .wsts_round_messages
+            .retain(|msg| &msg.bitcoin_chain_tip == bitcoin_chain_tip);
+
+        Ok(())
+    }
+
+    async fn write_signer_response_latency(
+        &self,
+        latency: &model::SignerResponseLatency,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.signer_response_latencies.push(latency.clone());
+
+        Ok(())
+    }
+
+    async fn delete_signer_response_latencies_before(
+        &self,
+        before: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .signer_response_latencies
+            .retain(|latency| latency.observed_at >= before);
+
+        Ok(())
+    }
+
+    async fn write_peg_wallet_tx_input(
+        &self,
+        input: &model::PegWalletTxInput,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .peg_wallet_tx_inputs
+            .entry((input.txid, input.input_index))
+            .and_modify(|indexed| indexed.signers_script |= input.signers_script)
+            .or_insert_with(|| input.clone());
+
+        Ok(())
+    }
+
+    async fn write_peg_wallet_tx_output(
+        &self,
+        output: &model::PegWalletTxOutput,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .peg_wallet_tx_outputs
+            .entry((output.txid, output.output_index))
+            .and_modify(|indexed| indexed.signers_script |= output.signers_script)
+            .or_insert_with(|| output.clone());
+
+        Ok(())
+    }
+
+    async fn write_bitcoin_internal_transfer(
+        &self,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.bitcoin_internal_transfers.insert(transfer.clone());
+
+        Ok(())
+    }
+
+    async fn write_quarantined_contract_event(
+        &self,
+        event: &model::QuarantinedContractEvent,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .quarantined_contract_events
+            .entry((event.block_hash, event.event_index))
+            .or_insert_with(|| event.clone());
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_tenure(
+        &self,
+        tenure: &model::StacksBlockTenure,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .stacks_block_tenures
+            .entry(tenure.block_hash)
+            .or_insert_with(|| tenure.clone());
+
+        Ok(())
+    }
+
+    async fn write_crash_report(&self, report: &model::CrashReport) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store.crash_reports.push(report.clone());
+
+        Ok(())
+    }
 }
 
 impl DbWrite for InMemoryTransaction {
@@ -394,6 +953,13 @@ impl DbWrite for InMemoryTransaction {
         self.store.write_bitcoin_block(block).await
     }
 
+    async fn write_bitcoin_block_activity(
+        &self,
+        activity: &model::BitcoinBlockActivity,
+    ) -> Result<(), Error> {
+        self.store.write_bitcoin_block_activity(activity).await
+    }
+
     async fn write_stacks_block(&self, block: &model::StacksBlock) -> Result<(), Error> {
         self.store.write_stacks_block(block).await
     }
@@ -488,6 +1054,13 @@ impl DbWrite for InMemoryTransaction {
         self.store.write_completed_deposit_event(event).await
     }
 
+    async fn write_sweep_completion(
+        &self,
+        completion: &model::SweepCompletion,
+    ) -> Result<(), Error> {
+        self.store.write_sweep_completion(completion).await
+    }
+
     async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
         self.store.write_tx_output(output).await
     }
@@ -543,4 +1116,245 @@ impl DbWrite for InMemoryTransaction {
             .update_peer_connection(pub_key, peer_id, address)
             .await
     }
+
+    async fn write_peer_ban(&self, ban: &model::PeerBan) -> Result<(), Error> {
+        self.store.write_peer_ban(ban).await
+    }
+
+    async fn delete_peer_ban(&self, peer_id: &PeerId) -> Result<bool, Error> {
+        self.store.delete_peer_ban(peer_id).await
+    }
+
+    async fn write_signer_identity_rotation(
+        &self,
+        rotation: &model::SignerIdentityRotation,
+    ) -> Result<(), Error> {
+        self.store.write_signer_identity_rotation(rotation).await
+    }
+
+    async fn write_deposit_request_intake(
+        &self,
+        intake: &model::DepositRequestIntake,
+    ) -> Result<(), Error> {
+        self.store.write_deposit_request_intake(intake).await
+    }
+
+    async fn write_request_failure(
+        &self,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> Result<model::RequestFailures, Error> {
+        self.store
+            .write_request_failure(request, error, quarantine_threshold)
+            .await
+    }
+
+    async fn release_request_failures(
+        &self,
+        request: &model::SbtcRequestKey,
+    ) -> Result<bool, Error> {
+        self.store.release_request_failures(request).await
+    }
+
+    async fn write_stale_deposit_request(
+        &self,
+        stale: &model::StaleDepositRequest,
+    ) -> Result<(), Error> {
+        self.store.write_stale_deposit_request(stale).await
+    }
+
+    async fn mark_stale_deposit_request_synced(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<(), Error> {
+        self.store
+            .mark_stale_deposit_request_synced(txid, output_index)
+            .await
+    }
+
+    async fn write_registry_event_source(
+        &self,
+        source: &model::RegistryEventSource,
+    ) -> Result<(), Error> {
+        self.store.write_registry_event_source(source).await
+    }
+
+    async fn write_processed_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        self.store.write_processed_stacks_block(block_hash).await
+    }
+
+    async fn write_stacks_block_failure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> Result<u32, Error> {
+        self.store
+            .write_stacks_block_failure(block_hash, error)
+            .await
+    }
+
+    async fn write_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        self.store.write_raw_stacks_event(block_hash, body).await
+    }
+
+    async fn delete_raw_stacks_events_before(&self, before: model::Timestamp) -> Result<(), Error> {
+        self.store.delete_raw_stacks_events_before(before).await
+    }
+
+    async fn write_emily_deposit_cursor(&self, last_update_height: u64) -> Result<(), Error> {
+        self.store
+            .write_emily_deposit_cursor(last_update_height)
+            .await
+    }
+
+    async fn write_analytics_export(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<(), Error> {
+        self.store.write_analytics_export(from, to).await
+    }
+
+    async fn write_stacks_node_burn_block(
+        &self,
+        block: &model::StacksNodeBurnBlock,
+    ) -> Result<(), Error> {
+        self.store.write_stacks_node_burn_block(block).await
+    }
+
+    async fn write_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<(), Error> {
+        self.store.write_confirmation_watch(watch).await
+    }
+
+    async fn delete_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<bool, Error> {
+        self.store.delete_confirmation_watch(watch).await
+    }
+
+    async fn write_deposit_correlation_id(
+        &self,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> Result<(), Error> {
+        self.store
+            .write_deposit_correlation_id(correlation_id)
+            .await
+    }
+
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        self.store.write_stacks_block_events(events).await
+    }
+
+    async fn write_webhook_subscriber(&self, url: &str) -> Result<(), Error> {
+        self.store.write_webhook_subscriber(url).await
+    }
+
+    async fn delete_webhook_subscriber(&self, url: &str) -> Result<bool, Error> {
+        self.store.delete_webhook_subscriber(url).await
+    }
+
+    async fn write_row_integrity_hash(&self, hash: &model::RowIntegrityHash) -> Result<(), Error> {
+        self.store.write_row_integrity_hash(hash).await
+    }
+
+    async fn write_stacks_fee_spend(&self, spend: &model::StacksFeeSpend) -> Result<(), Error> {
+        self.store.write_stacks_fee_spend(spend).await
+    }
+
+    async fn write_wsts_round_message(
+        &self,
+        message: &model::WstsRoundMessage,
+    ) -> Result<(), Error> {
+        self.store.write_wsts_round_message(message).await
+    }
+
+    async fn delete_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<(), Error> {
+        self.store
+            .delete_wsts_round_messages(bitcoin_chain_tip, message)
+            .await
+    }
+
+    async fn delete_stale_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        self.store
+            .delete_stale_wsts_round_messages(bitcoin_chain_tip)
+            .await
+    }
+
+    async fn write_signer_response_latency(
+        &self,
+        latency: &model::SignerResponseLatency,
+    ) -> Result<(), Error> {
+        self.store.write_signer_response_latency(latency).await
+    }
+
+    async fn delete_signer_response_latencies_before(
+        &self,
+        before: model::Timestamp,
+    ) -> Result<(), Error> {
+        self.store
+            .delete_signer_response_latencies_before(before)
+            .await
+    }
+
+    async fn write_peg_wallet_tx_input(
+        &self,
+        input: &model::PegWalletTxInput,
+    ) -> Result<(), Error> {
+        self.store.write_peg_wallet_tx_input(input).await
+    }
+
+    async fn write_peg_wallet_tx_output(
+        &self,
+        output: &model::PegWalletTxOutput,
+    ) -> Result<(), Error> {
+        self.store.write_peg_wallet_tx_output(output).await
+    }
+
+    async fn write_bitcoin_internal_transfer(
+        &self,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> Result<(), Error> {
+        self.store.write_bitcoin_internal_transfer(transfer).await
+    }
+
+    async fn write_quarantined_contract_event(
+        &self,
+        event: &model::QuarantinedContractEvent,
+    ) -> Result<(), Error> {
+        self.store.write_quarantined_contract_event(event).await
+    }
+
+    async fn write_stacks_block_tenure(
+        &self,
+        tenure: &model::StacksBlockTenure,
+    ) -> Result<(), Error> {
+        self.store.write_stacks_block_tenure(tenure).await
+    }
+
+    async fn write_crash_report(&self, report: &model::CrashReport) -> Result<(), Error> {
+        self.store.write_crash_report(report).await
+    }
 }
@@ -16,6 +16,7 @@ pub mod util;
 use std::collections::BTreeSet;
 use std::future::Future;
 
+use bitcoin::OutPoint;
 use blockstack_lib::types::chainstate::StacksBlockId;
 use libp2p::Multiaddr;
 use libp2p::PeerId;
@@ -61,6 +62,16 @@ pub trait DbRead {
         block_hash: &model::BitcoinBlockHash,
     ) -> impl Future<Output = Result<Option<model::BitcoinBlock>, Error>> + Send;
 
+    /// Get the activity that the block observer recorded for the bitcoin
+    /// block with the given block hash.
+    ///
+    /// `Ok(None)` is returned if the block is unknown, or if it was
+    /// processed before the activity of blocks was recorded.
+    fn get_bitcoin_block_activity(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> impl Future<Output = Result<Option<model::BitcoinBlockActivity>, Error>> + Send;
+
     /// Get the stacks block with the given block hash.
     fn get_stacks_block(
         &self,
@@ -74,7 +85,6 @@ pub trait DbRead {
     ) -> impl Future<Output = Result<Option<model::BitcoinBlockHash>, Error>> + Send;
 
     /// Get the bitcoin canonical chain tip.
-    #[cfg(any(test, feature = "testing"))]
     fn get_bitcoin_canonical_chain_tip_ref(
         &self,
     ) -> impl Future<Output = Result<Option<model::BitcoinBlockRef>, Error>> + Send;
@@ -342,6 +352,15 @@ pub trait DbRead {
         &self,
     ) -> impl Future<Output = Result<Vec<model::Bytes>, Error>> + Send;
 
+    /// Get the signers' `scriptPubkey`s that were in use at the given
+    /// bitcoin block height. These are the ones of the DKG rounds that had
+    /// started by then, along with the ones that the signers had locked
+    /// UTXOs with in blocks up to and including that height.
+    fn get_signers_script_pubkeys_at(
+        &self,
+        bitcoin_block_height: model::BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<Vec<model::Bytes>, Error>> + Send;
+
     /// Get the outstanding signer UTXO.
     ///
     /// Under normal conditions, the signer will have only one UTXO they
@@ -358,6 +377,18 @@ pub trait DbRead {
         chain_tip: &model::BitcoinBlockHash,
     ) -> impl Future<Output = Result<Option<SignerUtxo>, Error>> + Send;
 
+    /// Return the unspent donation UTXOs locked by the given aggregate
+    /// key, that were confirmed on the canonical bitcoin blockchain
+    /// identified by the chain tip, ordered by amount descending.
+    ///
+    /// A donation is spent if it is an input into a sweep transaction
+    /// that has been confirmed on the canonical bitcoin blockchain.
+    fn get_donation_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> impl Future<Output = Result<Vec<SignerUtxo>, Error>> + Send;
+
     /// For the given outpoint and aggregate key, get the list all signer
     /// votes in the signer set.
     fn get_deposit_request_signer_votes(
@@ -464,6 +495,310 @@ pub trait DbRead {
 
     /// Returns the list of stored peers.
     fn get_p2p_peers(&self) -> impl Future<Output = Result<Vec<model::P2PPeer>, Error>> + Send;
+
+    /// Returns the peer bans that have not expired.
+    fn get_peer_bans(&self) -> impl Future<Output = Result<Vec<model::PeerBan>, Error>> + Send;
+
+    /// Returns all signer identity rotations that have been accepted.
+    fn get_signer_identity_rotations(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::SignerIdentityRotation>, Error>> + Send;
+
+    /// Returns the intake sources through which the given deposit request
+    /// has been received.
+    fn get_deposit_request_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<Vec<model::DepositRequestSource>, Error>> + Send;
+
+    /// Returns the failure records of all requests that have been
+    /// quarantined.
+    fn get_quarantined_requests(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::RequestFailures>, Error>> + Send;
+
+    /// Returns the deposit requests that were written to the database
+    /// before `recorded_before`, that are not already marked as stale, and
+    /// whose deposit UTXO has neither been spent by a transaction that we
+    /// have recorded nor been minted against, on any fork.
+    fn get_stale_deposit_candidates(
+        &self,
+        recorded_before: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::DepositRequest>, Error>> + Send;
+
+    /// Returns all deposit requests that have been marked as stale.
+    fn get_stale_deposit_requests(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::StaleDepositRequest>, Error>> + Send;
+
+    /// Returns the registry contract that emitted the events of the given
+    /// stacks transaction, if it was recorded.
+    fn get_registry_event_source(
+        &self,
+        txid: &model::StacksTxId,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Option<model::StacksPrincipal>, Error>> + Send;
+
+    /// Returns whether all the events of the stacks block with the given
+    /// index block hash have been handled.
+    fn is_stacks_block_processed(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Returns whether the given event of a stacks block is already in the
+    /// database.
+    fn is_stacks_block_event_stored(
+        &self,
+        event: &model::StacksBlockEvent,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Returns the number of times that handling the events of the stacks
+    /// block with the given index block hash failed.
+    fn get_stacks_block_failure_count(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<u32, Error>> + Send;
+
+    /// Returns the stored, zstd compressed, raw body of the `POST
+    /// /new_block` webhook for the stacks block with the given index block
+    /// hash, if it is still within the retention window.
+    fn get_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, Error>> + Send;
+
+    /// Returns the recorded tenure of the stacks block with the given
+    /// index block hash, if there is one.
+    fn get_stacks_block_tenure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Option<model::StacksBlockTenure>, Error>> + Send;
+
+    /// Returns the most recent crash reports, newest first, returning at
+    /// most `limit` of them.
+    fn get_crash_reports(
+        &self,
+        limit: u16,
+    ) -> impl Future<Output = Result<Vec<model::CrashReport>, Error>> + Send;
+
+    /// Returns the largest stacks block height at which Emily last updated
+    /// one of the deposits fetched from it, if deposits were ever fetched.
+    fn get_emily_deposit_cursor(&self) -> impl Future<Output = Result<Option<u64>, Error>> + Send;
+
+    /// Returns the end of the latest period whose records were exported
+    /// to the analytics bucket, if any period was ever exported.
+    fn get_last_analytics_export_end(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::Timestamp>, Error>> + Send;
+
+    /// Returns the highest bitcoin block that the stacks node reported
+    /// through the `/new_burn_block` webhook, if it reported any.
+    fn get_latest_stacks_node_burn_block(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::StacksNodeBurnBlock>, Error>> + Send;
+
+    /// Check that the storage backend is reachable, by making the simplest
+    /// possible round trip to it.
+    fn ping(&self) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Returns all of the confirmation watches that have not fired yet.
+    fn get_confirmation_watches(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::ConfirmationWatch>, Error>> + Send;
+
+    /// Returns the correlation ID that Emily gave for the deposit request
+    /// with the given outpoint, if it gave one.
+    fn get_deposit_correlation_id(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<Option<String>, Error>> + Send;
+
+    /// Returns the URLs of all webhook subscribers that were registered
+    /// through the API.
+    fn get_webhook_subscribers(&self) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
+
+    /// Returns the integrity hashes of all protected rows in the given
+    /// table.
+    fn get_row_integrity_hashes(
+        &self,
+        table_name: &str,
+    ) -> impl Future<Output = Result<Vec<model::RowIntegrityHash>, Error>> + Send;
+
+    /// Returns the fees paid for stacks transactions submitted at or
+    /// after the given time, ordered by submission time.
+    fn get_stacks_fee_spends_since(
+        &self,
+        since: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::StacksFeeSpend>, Error>> + Send;
+
+    /// Returns the deposit requests written to the database at or after
+    /// `from` and before `to`, ordered by when they were written.
+    fn get_deposit_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::Recorded<model::DepositRequest>>, Error>> + Send;
+
+    /// Returns the withdrawal requests written to the database at or
+    /// after `from` and before `to`, ordered by when they were written.
+    /// A request observed in several stacks forks is returned once per
+    /// fork.
+    fn get_withdrawal_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::Recorded<model::WithdrawalRequest>>, Error>> + Send;
+
+    /// Returns the confirmed transactions that spend the signers' UTXO and
+    /// were written to the database at or after `from` and before `to`,
+    /// ordered by when they were written. A transaction confirmed in
+    /// several bitcoin forks is returned once per fork.
+    fn get_sweep_transactions_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::SweepTransactionRecord>, Error>> + Send;
+
+    /// Returns the signer response latencies observed at or after the
+    /// given time, ordered by observation time.
+    fn get_signer_response_latencies_since(
+        &self,
+        since: model::Timestamp,
+    ) -> impl Future<Output = Result<Vec<model::SignerResponseLatency>, Error>> + Send;
+
+    /// Returns the payloads of the messages collected for the WSTS
+    /// signing round over the given message at the given chain tip, in
+    /// the order that they were written.
+    fn get_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> impl Future<Output = Result<Vec<Vec<u8>>, Error>> + Send;
+
+    /// Returns all completed deposit events, across all forks.
+    fn get_completed_deposit_events(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::CompletedDepositEvent>, Error>> + Send;
+
+    /// Returns the completed deposit events for the deposit with the given
+    /// outpoint, across all forks.
+    fn get_completed_deposit_events_for_outpoint(
+        &self,
+        outpoint: &OutPoint,
+    ) -> impl Future<Output = Result<Vec<model::CompletedDepositEvent>, Error>> + Send;
+
+    /// Returns the completed deposit event for the deposit with the given
+    /// outpoint that is on the canonical stacks blockchain, where the
+    /// canonical stacks blockchain is the one anchored to the bitcoin
+    /// blockchain identified by the given `chain_tip`.
+    fn get_canonical_completed_deposit_event(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        outpoint: &OutPoint,
+    ) -> impl Future<Output = Result<Option<model::CompletedDepositEvent>, Error>> + Send;
+
+    /// Returns the completed deposit events, across all forks, for the
+    /// deposits that were swept in by the bitcoin transaction with the
+    /// given txid.
+    fn get_completed_deposit_events_for_sweep(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<model::CompletedDepositEvent>, Error>> + Send;
+
+    /// Returns the transactions that spend the signers' UTXO and were
+    /// confirmed in a bitcoin block that is at most `context_window`
+    /// blocks below the given chain tip but not on its blockchain, where
+    /// the transaction is not confirmed on that blockchain either. These
+    /// are sweep transactions that a bitcoin reorg has orphaned.
+    fn get_orphaned_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        context_window: u16,
+    ) -> impl Future<Output = Result<Vec<model::OrphanedSweepTransaction>, Error>> + Send;
+
+    /// Returns the stacks transactions, across all forks, that completed
+    /// requests fulfilled by the sweep transaction with the given txid.
+    fn get_sweep_completions(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<model::SweepCompletion>, Error>> + Send;
+
+    /// Returns the sweep transactions that fulfilled at least one request
+    /// and were confirmed on the blockchain of the given chain tip, at
+    /// least `deadline` blocks below it but at most `context_window`
+    /// blocks below it, where no stacks transaction has completed any of
+    /// their requests.
+    fn get_uncompleted_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        deadline: u16,
+        context_window: u16,
+    ) -> impl Future<Output = Result<Vec<model::UncompletedSweepTransaction>, Error>> + Send;
+
+    /// Returns all key rotation events, across all forks.
+    fn get_key_rotation_events(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::KeyRotationEvent>, Error>> + Send;
+
+    /// Returns the completed deposit events in the stacks blockchain that
+    /// ends at the stacks block with the given hash.
+    fn get_stacks_chain_completed_deposit_events(
+        &self,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Vec<model::CompletedDepositEvent>, Error>> + Send;
+
+    /// Returns the key rotation events in the stacks blockchain that ends
+    /// at the stacks block with the given hash.
+    fn get_stacks_chain_key_rotation_events(
+        &self,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Vec<model::KeyRotationEvent>, Error>> + Send;
+
+    /// Returns the indexed inputs of the peg wallet transaction with the
+    /// given ID, ordered by input index.
+    fn get_peg_wallet_tx_inputs(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<model::PegWalletTxInput>, Error>> + Send;
+
+    /// Returns the indexed outputs of the peg wallet transaction with the
+    /// given ID, ordered by output index.
+    fn get_peg_wallet_tx_outputs(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<model::PegWalletTxOutput>, Error>> + Send;
+
+    /// Returns whether the bitcoin transaction with the given ID was
+    /// recorded as an internal transfer between the signers' addresses.
+    fn is_bitcoin_internal_transfer(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Trace where the funds locked in the given output went. This follows
+    /// the spend of the output and then, transitively, the spends of the
+    /// outputs locked by a scriptPubKey of the signers in each spending
+    /// transaction, up to `max_depth` spends away from the output. The
+    /// links are ordered by depth.
+    fn trace_peg_wallet_spends(
+        &self,
+        outpoint: &OutPoint,
+        max_depth: u32,
+    ) -> impl Future<Output = Result<Vec<model::PegWalletSpendLink>, Error>> + Send;
+
+    /// Trace where the funds spent by the given transaction came from.
+    /// This follows the inputs of the transaction and then, transitively,
+    /// the inputs of the transactions that created the inputs locked by a
+    /// scriptPubKey of the signers, up to `max_depth` spends away from the
+    /// transaction. The links are ordered by depth.
+    fn trace_peg_wallet_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        max_depth: u32,
+    ) -> impl Future<Output = Result<Vec<model::PegWalletSpendLink>, Error>> + Send;
 }
 
 /// Represents the ability to write data to the signer storage.
@@ -474,6 +809,13 @@ pub trait DbWrite {
         block: &model::BitcoinBlock,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Record the activity of a bitcoin block processed by the block
+    /// observer. The block itself must already have been written.
+    fn write_bitcoin_block_activity(
+        &self,
+        activity: &model::BitcoinBlockActivity,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write a stacks block.
     fn write_stacks_block(
         &self,
@@ -552,12 +894,21 @@ pub trait DbWrite {
         event: &WithdrawalAcceptEvent,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
-    /// Write the completed deposit event to the database.
+    /// Write the completed deposit event to the database. Writing an
+    /// event that is already in the database is a no-op.
     fn write_completed_deposit_event(
         &self,
         event: &CompletedDepositEvent,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Write the link between a sweep transaction and a stacks transaction
+    /// that completed one of its requests. Writing a link that is already
+    /// in the database is a no-op.
+    fn write_sweep_completion(
+        &self,
+        completion: &model::SweepCompletion,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write the bitcoin transaction output to the database.
     fn write_tx_output(
         &self,
@@ -618,4 +969,262 @@ pub trait DbWrite {
         peer_id: &PeerId,
         address: Multiaddr,
     ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write a peer ban, replacing any ban that the peer already has.
+    fn write_peer_ban(
+        &self,
+        ban: &model::PeerBan,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the ban of the given peer. Returns whether the peer had a ban
+    /// that had not expired.
+    fn delete_peer_ban(&self, peer_id: &PeerId)
+    -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Write a signer identity rotation. If the old public key has already
+    /// been rotated then this is a no-op.
+    fn write_signer_identity_rotation(
+        &self,
+        rotation: &model::SignerIdentityRotation,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that a deposit request was received through the given
+    /// intake source. The deposit request must already be stored.
+    fn write_deposit_request_intake(
+        &self,
+        intake: &model::DepositRequestIntake,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a signing failure for the given request, quarantining it if
+    /// it has now failed at least `quarantine_threshold` times. Returns
+    /// the updated failure record for the request.
+    fn write_request_failure(
+        &self,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> impl Future<Output = Result<model::RequestFailures, Error>> + Send;
+
+    /// Remove all recorded failures for the given request, releasing it
+    /// from quarantine. Returns whether any failures had been recorded.
+    fn release_request_failures(
+        &self,
+        request: &model::SbtcRequestKey,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Mark the given deposit request as stale. The deposit request must
+    /// already be stored, and if it is already marked as stale then this
+    /// is a no-op.
+    fn write_stale_deposit_request(
+        &self,
+        stale: &model::StaleDepositRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that Emily has accepted the failure of the given stale
+    /// deposit request.
+    fn mark_stale_deposit_request_synced(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record the registry contract that emitted the events of a stacks
+    /// transaction. If a source is already recorded for the transaction
+    /// then this is a no-op.
+    fn write_registry_event_source(
+        &self,
+        source: &model::RegistryEventSource,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that all the events of the stacks block with the given index
+    /// block hash have been handled. If the block is already recorded then
+    /// this is a no-op.
+    fn write_processed_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that handling the events of the stacks block with the given
+    /// index block hash failed with the given error, and return the number
+    /// of times that it has failed.
+    fn write_stacks_block_failure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> impl Future<Output = Result<u32, Error>> + Send;
+
+    /// Store the zstd compressed raw body of the `POST /new_block` webhook
+    /// for the stacks block with the given index block hash. If a body was
+    /// already stored for the block then this is a no-op.
+    fn write_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the raw webhook bodies that were stored before the given
+    /// time.
+    fn delete_raw_stacks_events_before(
+        &self,
+        before: model::Timestamp,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Set the largest stacks block height at which Emily last updated one
+    /// of the deposits fetched from it.
+    fn write_emily_deposit_cursor(
+        &self,
+        last_update_height: u64,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that the records of the period starting at `from` and
+    /// ending before `to` were exported to the analytics bucket.
+    fn write_analytics_export(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write a bitcoin block that the stacks node reported through the
+    /// `/new_burn_block` webhook.
+    fn write_stacks_node_burn_block(
+        &self,
+        block: &model::StacksNodeBurnBlock,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write a confirmation watch. Writing a watch that already exists
+    /// does nothing.
+    fn write_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete a confirmation watch, returning whether it existed.
+    fn delete_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Write the correlation ID of a deposit request, replacing the one
+    /// written before, if any. The deposit request must already be stored.
+    fn write_deposit_correlation_id(
+        &self,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write all the registry events of a stacks block, along with the
+    /// registry contracts that emitted them, the sweep completions that
+    /// they reference and their integrity hashes, and record the block as
+    /// processed. Everything is written in a single database transaction,
+    /// so either all of it is written or none of it is.
+    fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Register a webhook subscriber. If the URL is already registered
+    /// then this is a no-op.
+    fn write_webhook_subscriber(&self, url: &str)
+    -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Remove a webhook subscriber. Returns whether the URL had been
+    /// registered.
+    fn delete_webhook_subscriber(
+        &self,
+        url: &str,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Write the integrity hash of a protected row. If a hash has already
+    /// been written for the row then the existing hash is kept.
+    fn write_row_integrity_hash(
+        &self,
+        hash: &model::RowIntegrityHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record the fee paid for a submitted stacks transaction. If a fee
+    /// has already been recorded for the transaction then this is a
+    /// no-op.
+    fn write_stacks_fee_spend(
+        &self,
+        spend: &model::StacksFeeSpend,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a message collected during a WSTS signing round.
+    fn write_wsts_round_message(
+        &self,
+        message: &model::WstsRoundMessage,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the messages collected for the WSTS signing round over the
+    /// given message at the given chain tip.
+    fn delete_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the messages collected for all WSTS signing rounds that
+    /// started at a chain tip other than the given one.
+    fn delete_stale_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record how long a signer took to respond to a request of the
+    /// coordinator.
+    fn write_signer_response_latency(
+        &self,
+        latency: &model::SignerResponseLatency,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the signer response latencies observed before the given
+    /// time.
+    fn delete_signer_response_latencies_before(
+        &self,
+        before: model::Timestamp,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Index an input of a peg wallet transaction. If the input has
+    /// already been indexed then it is only marked as spending an output
+    /// of the signers, if it now is known to.
+    fn write_peg_wallet_tx_input(
+        &self,
+        input: &model::PegWalletTxInput,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Index an output of a peg wallet transaction. If the output has
+    /// already been indexed then it is only marked as locked by a
+    /// scriptPubKey of the signers, if it now is known to be.
+    fn write_peg_wallet_tx_output(
+        &self,
+        output: &model::PegWalletTxOutput,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a bitcoin transaction that only moved funds between the
+    /// signers' addresses. Recording the same transaction again is a
+    /// no-op.
+    fn write_bitcoin_internal_transfer(
+        &self,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a contract event that was filtered out of a webhook of the
+    /// stacks node because it did not come from an observed sbtc-registry
+    /// contract. Recording the same event again is a no-op.
+    fn write_quarantined_contract_event(
+        &self,
+        event: &model::QuarantinedContractEvent,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record the tenure of a stacks block and the signers that signed
+    /// it. Recording the tenure of the same block again is a no-op.
+    fn write_stacks_block_tenure(
+        &self,
+        tenure: &model::StacksBlockTenure,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a report of a panic in one of the components of the signer.
+    fn write_crash_report(
+        &self,
+        report: &model::CrashReport,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
 }
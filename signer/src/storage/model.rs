@@ -10,7 +10,7 @@ use std::ops::{Add, Sub};
 use bitcoin::hashes::Hash as _;
 use bitcoin::hex::DisplayHex as _;
 use bitcoin::hex::FromHex as _;
-use bitcoin::{OutPoint, ScriptBuf};
+use bitcoin::{Amount, OutPoint, ScriptBuf};
 use bitvec::array::BitArray;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use clarity::vm::types::PrincipalData;
@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::chainstate::StacksBlockId;
 
+use crate::amount::SbtcAmount;
 use crate::bitcoin::rpc::BitcoinBlockHeader;
 use crate::bitcoin::rpc::BitcoinBlockInfo;
 use crate::bitcoin::validation::InputValidationResult;
@@ -28,6 +29,7 @@ use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::stacks::api::SignerSetInfo;
+use crate::stacks::tenure::TenureChangeKind;
 
 /// A P2P peer which the signer has successfully connected to.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
@@ -42,6 +44,205 @@ pub struct P2PPeer {
     pub last_dialed_at: Timestamp,
 }
 
+/// A ban of a P2P peer by the operator of the signer.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct PeerBan {
+    /// The peer ID of the banned peer.
+    pub peer_id: DbPeerId,
+    /// Why the peer was banned, if the operator said.
+    pub reason: Option<String>,
+    /// When the ban stops applying.
+    pub expires_at: Timestamp,
+}
+
+/// A signer identity key rotation that was announced by a peer over the
+/// P2P network.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SignerIdentityRotation {
+    /// The public key that the signer used before the rotation.
+    pub old_public_key: PublicKey,
+    /// The public key that the signer uses after the rotation.
+    pub new_public_key: PublicKey,
+    /// The bitcoin chain tip of the signer when it announced the rotation.
+    pub bitcoin_chain_tip: BitcoinBlockHash,
+}
+
+/// A keyed integrity hash over a security-critical row of another table.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+pub struct RowIntegrityHash {
+    /// The name of the table that holds the protected row.
+    pub table_name: String,
+    /// The columns that identify the protected row, encoded as bytes.
+    pub row_key: Vec<u8>,
+    /// The HMAC-SHA256 of the contents of the protected row.
+    pub integrity_hash: [u8; 32],
+}
+
+/// The fee paid for a stacks transaction that the signer submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StacksFeeSpend {
+    /// The ID of the submitted transaction.
+    pub txid: StacksTxId,
+    /// The kind of transaction, such as `complete-deposit`.
+    pub tx_kind: String,
+    /// The fee paid for the transaction, in microSTX.
+    pub fee: u64,
+    /// When the transaction was accepted by the stacks node.
+    pub submitted_at: Timestamp,
+}
+
+/// A message that the coordinator collected during a WSTS signing round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WstsRoundMessage {
+    /// The bitcoin chain tip that the signing round started at.
+    pub bitcoin_chain_tip: BitcoinBlockHash,
+    /// The message being signed in the signing round.
+    pub message: Vec<u8>,
+    /// The protobuf encoded signed message from the signer.
+    pub payload: Vec<u8>,
+}
+
+/// How long a signer took to respond to a request of the coordinator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerResponseLatency {
+    /// The public key of the signer that responded.
+    pub signer_public_key: PublicKey,
+    /// The kind of response, such as `presign-ack`.
+    pub response_kind: String,
+    /// The time between the request and the response, in milliseconds.
+    pub latency_millis: u64,
+    /// When the response was received.
+    pub observed_at: Timestamp,
+}
+
+/// A record together with the time at which it was written to the
+/// database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recorded<T> {
+    /// The record itself.
+    pub record: T,
+    /// When the record was written to the database.
+    pub recorded_at: Timestamp,
+}
+
+impl<'r, T> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Recorded<T>
+where
+    T: sqlx::FromRow<'r, sqlx::postgres::PgRow>,
+{
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row as _;
+        Ok(Self {
+            record: T::from_row(row)?,
+            recorded_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// A bitcoin transaction of the signers that spends their UTXO, as
+/// confirmed in a bitcoin block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepTransactionRecord {
+    /// The ID of the transaction.
+    pub txid: BitcoinTxId,
+    /// The block that confirmed the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the block that confirmed the transaction.
+    pub block_height: BitcoinBlockHeight,
+    /// The total amount of the signers' UTXO and the deposits spent by
+    /// the transaction, in sats.
+    pub amount_in: u64,
+    /// The total amount of the outputs of the transaction, in sats.
+    pub amount_out: u64,
+    /// When the transaction was written to the database.
+    pub recorded_at: Timestamp,
+}
+
+impl SweepTransactionRecord {
+    /// The fee paid by the transaction, in sats.
+    pub fn fee(&self) -> u64 {
+        self.amount_in.saturating_sub(self.amount_out)
+    }
+}
+
+/// A bitcoin transaction of the signers that spends their UTXO and was
+/// confirmed in a bitcoin block that is no longer on the canonical bitcoin
+/// blockchain, and that is not confirmed on the canonical blockchain
+/// either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OrphanedSweepTransaction {
+    /// The ID of the transaction.
+    pub txid: BitcoinTxId,
+    /// The orphaned block that confirmed the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the orphaned block that confirmed the transaction.
+    pub block_height: BitcoinBlockHeight,
+}
+
+/// A bitcoin transaction of the signers that spends their UTXO, fulfills
+/// at least one deposit or withdrawal request, and was confirmed on the
+/// canonical bitcoin blockchain without any stacks transaction completing
+/// one of its requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UncompletedSweepTransaction {
+    /// The ID of the transaction.
+    pub txid: BitcoinTxId,
+    /// The block that confirmed the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the block that confirmed the transaction.
+    pub block_height: BitcoinBlockHeight,
+}
+
+/// A bitcoin block that the stacks node reported through the
+/// `/new_burn_block` webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct StacksNodeBurnBlock {
+    /// The hash of the bitcoin block.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block.
+    pub block_height: BitcoinBlockHeight,
+}
+
+/// A link between a sweep transaction of the signers and a stacks
+/// transaction that completed one of the requests that it fulfilled,
+/// taken from a completed-deposit or withdrawal-accept event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SweepCompletion {
+    /// The ID of the sweep transaction.
+    pub sweep_txid: BitcoinTxId,
+    /// The bitcoin block that confirmed the sweep transaction, according
+    /// to the stacks event.
+    pub sweep_block_hash: BitcoinBlockHash,
+    /// The ID of the stacks transaction that emitted the event.
+    pub stacks_txid: StacksTxId,
+    /// The stacks block that included the stacks transaction.
+    pub stacks_block_hash: StacksBlockHash,
+}
+
+impl From<&CompletedDepositEvent> for SweepCompletion {
+    fn from(event: &CompletedDepositEvent) -> Self {
+        Self {
+            sweep_txid: event.sweep_txid,
+            sweep_block_hash: event.sweep_block_hash,
+            stacks_txid: event.txid,
+            stacks_block_hash: event.block_id,
+        }
+    }
+}
+
+impl From<&WithdrawalAcceptEvent> for SweepCompletion {
+    fn from(event: &WithdrawalAcceptEvent) -> Self {
+        Self {
+            sweep_txid: event.sweep_txid,
+            sweep_block_hash: event.sweep_block_hash,
+            stacks_txid: event.txid,
+            stacks_block_hash: event.block_id,
+        }
+    }
+}
+
 /// A bitcoin transaction output (TXO) relevant for the sBTC signers.
 ///
 /// This object can have a few different meanings, all of them identified
@@ -113,6 +314,155 @@ pub struct TxPrevout {
     pub prevout_type: TxPrevoutType,
 }
 
+/// An input of a bitcoin transaction that spends from, or pays to, the
+/// peg wallet. These are indexed for every input of such transactions,
+/// including the ones spending outputs that the signers do not control.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct PegWalletTxInput {
+    /// The ID of the transaction with the input.
+    pub txid: BitcoinTxId,
+    /// The index of the input in the transaction.
+    #[sqlx(try_from = "i32")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
+    pub input_index: u32,
+    /// The ID of the transaction that created the output being spent.
+    pub prevout_txid: BitcoinTxId,
+    /// The index of the output being spent in the transaction that
+    /// created it.
+    #[sqlx(try_from = "i32")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
+    pub prevout_output_index: u32,
+    /// The amount locked in the output being spent.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "1_000_000..1_000_000_000"))]
+    pub amount: u64,
+    /// The scriptPubKey locking the output being spent.
+    pub script_pubkey: ScriptPubKey,
+    /// Whether the output being spent is locked by a scriptPubKey of the
+    /// signers.
+    pub signers_script: bool,
+}
+
+/// An output of a bitcoin transaction that spends from, or pays to, the
+/// peg wallet. These are indexed for every output of such transactions.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct PegWalletTxOutput {
+    /// The ID of the transaction with the output.
+    pub txid: BitcoinTxId,
+    /// The index of the output in the transaction.
+    #[sqlx(try_from = "i32")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
+    pub output_index: u32,
+    /// The amount locked in the output.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "1_000_000..1_000_000_000"))]
+    pub amount: u64,
+    /// The scriptPubKey locking the output.
+    pub script_pubkey: ScriptPubKey,
+    /// Whether the output is locked by a scriptPubKey of the signers.
+    pub signers_script: bool,
+}
+
+/// A bitcoin transaction that only moved funds between the signers'
+/// addresses, for example from the address of a previous aggregate key to
+/// the one of the current key.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct BitcoinInternalTransfer {
+    /// The ID of the transaction.
+    pub txid: BitcoinTxId,
+    /// The block that confirmed the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The total amount of the outputs of the transaction.
+    #[cfg_attr(feature = "testing", dummy(faker = "1_000_000..1_000_000_000"))]
+    pub amount: u64,
+}
+
+/// A contract event that the stacks node sent to the event observer from
+/// a contract other than the observed sbtc-registry contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct QuarantinedContractEvent {
+    /// The ID of the stacks transaction that emitted the event.
+    pub txid: StacksTxId,
+    /// The ID of the stacks block that includes the transaction.
+    pub block_hash: StacksBlockHash,
+    /// The index of the event among the events of the block.
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
+    pub event_index: u64,
+    /// The identifier of the contract that emitted the event.
+    pub contract_identifier: String,
+    /// The topic of the event.
+    pub topic: String,
+}
+
+/// The tenure of a stacks block and the signers that signed it, as sent by
+/// the stacks node in the `POST /new_block` webhook for the block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StacksBlockTenure {
+    /// The index block hash of the stacks block.
+    pub block_hash: StacksBlockHash,
+    /// The reason for the tenure change in the block, if it has one.
+    pub tenure_change: Option<TenureChangeKind>,
+    /// The height of the tenure of the block, if the stacks node sent it.
+    pub tenure_height: Option<u64>,
+    /// Whether each signer of the reward cycle signed the block, in the
+    /// order of the signers in the reward set. This is `None` for blocks
+    /// from before Nakamoto.
+    pub signer_bitvec: Option<Vec<bool>>,
+}
+
+impl StacksBlockTenure {
+    /// The number of signers that signed the block, if it was signed by
+    /// the signers.
+    pub fn signed_count(&self) -> Option<usize> {
+        let signer_bitvec = self.signer_bitvec.as_ref()?;
+        Some(signer_bitvec.iter().filter(|signed| **signed).count())
+    }
+}
+
+/// A report of a panic in one of the long-lived components of the
+/// signer.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct CrashReport {
+    /// The name of the component that panicked.
+    pub component: String,
+    /// The message of the panic.
+    pub message: String,
+    /// The backtrace of the panic, if one was captured.
+    pub backtrace: Option<String>,
+    /// Descriptions of the most recent signals that the signer handled
+    /// before the panic, oldest first.
+    pub recent_events: Vec<String>,
+    /// The hash of the bitcoin chain tip of the signer at the time of the
+    /// panic.
+    pub bitcoin_chain_tip: Option<BitcoinBlockHash>,
+    /// The height of the bitcoin chain tip of the signer at the time of
+    /// the panic.
+    pub bitcoin_chain_tip_height: Option<BitcoinBlockHeight>,
+    /// The last stacks block whose webhook the signer handled before the
+    /// panic.
+    pub stacks_chain_tip: Option<StacksBlockHash>,
+    /// When the component panicked.
+    pub created_at: Timestamp,
+}
+
+/// A link in the flow of funds through the peg wallet, where an indexed
+/// output is spent by an input of a later transaction.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct PegWalletSpendLink {
+    /// The input that spends the output. Its prevout is the output being
+    /// spent.
+    #[sqlx(flatten)]
+    pub input: PegWalletTxInput,
+    /// The number of spends between the start of the trace and this
+    /// link, where the links adjacent to the start have a depth of one.
+    #[sqlx(try_from = "i32")]
+    pub depth: u32,
+}
+
 /// Bitcoin block.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -170,6 +520,38 @@ impl From<bitcoin::Block> for BitcoinBlock {
     }
 }
 
+/// What the block observer records about a bitcoin block in addition to
+/// the [`BitcoinBlock`] itself, when it processes the block.
+///
+/// Every block that the block observer processes gets one of these,
+/// including blocks that do not contain any sBTC related transactions.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct BitcoinBlockActivity {
+    /// Block hash.
+    pub block_hash: BitcoinBlockHash,
+    /// The time value in the block header, in seconds since the unix
+    /// epoch.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
+    pub block_time: u64,
+    /// Whether the block contained any transactions that spent to or from
+    /// one of the signers' scriptPubKeys.
+    pub has_sbtc_activity: bool,
+}
+
+impl BitcoinBlockActivity {
+    /// Create the activity record of the block with the given header
+    /// returned by bitcoin-core.
+    pub fn new(header: &BitcoinBlockHeader, has_sbtc_activity: bool) -> Self {
+        Self {
+            block_hash: header.hash.into(),
+            block_time: header.time,
+            has_sbtc_activity,
+        }
+    }
+}
+
 /// Stacks block.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -646,6 +1028,201 @@ impl From<SignerVotes> for BitArray<[u8; 16]> {
     }
 }
 
+/// The intake source through which the signer learned about a deposit
+/// request.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::Display,
+    strum::IntoStaticStr,
+)]
+#[sqlx(type_name = "deposit_request_source", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum DepositRequestSource {
+    /// The deposit request was fetched from Emily.
+    Emily,
+    /// The deposit request was submitted directly to the signer's API,
+    /// through `POST /admin/deposits`.
+    Api,
+    /// The deposit request was fetched after another signer sent a
+    /// decision for it over the P2P network.
+    P2p,
+    /// The deposit request was found by scanning the bitcoin blockchain
+    /// for deposit outputs, rather than being handed to the signer.
+    Backfill,
+}
+
+/// A record of a deposit request having been received through a
+/// particular intake source.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct DepositRequestIntake {
+    /// Transaction ID of the deposit request transaction.
+    pub txid: BitcoinTxId,
+    /// Index of the deposit request UTXO.
+    #[cfg_attr(feature = "testing", dummy(faker = "0..100"))]
+    #[sqlx(try_from = "i32")]
+    pub output_index: u32,
+    /// The source that the deposit request was received from.
+    pub source: DepositRequestSource,
+}
+
+/// A watch on a bitcoin transaction, which fires once the transaction
+/// has the given number of confirmations.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct ConfirmationWatch {
+    /// The ID of the watched transaction.
+    pub txid: BitcoinTxId,
+    /// The number of confirmations at which the watch fires.
+    #[cfg_attr(feature = "testing", dummy(faker = "1..100"))]
+    #[sqlx(try_from = "i32")]
+    pub confirmations: u32,
+    /// The URL that is notified when the watch fires. Watches without one
+    /// only publish an event within the signer.
+    #[cfg_attr(feature = "testing", dummy(default))]
+    pub callback_url: Option<String>,
+}
+
+/// The identifier that the creator of a deposit request gave Emily for
+/// tracing the request across systems.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct DepositCorrelationId {
+    /// Transaction ID of the deposit request transaction.
+    pub txid: BitcoinTxId,
+    /// Index of the deposit request UTXO.
+    #[cfg_attr(feature = "testing", dummy(faker = "0..100"))]
+    #[sqlx(try_from = "i32")]
+    pub output_index: u32,
+    /// The correlation ID of the deposit request.
+    pub correlation_id: String,
+}
+
+/// The reason that a deposit request is stale.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::Display,
+    strum::IntoStaticStr,
+)]
+#[sqlx(type_name = "stale_deposit_reason", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum StaleDepositReason {
+    /// Bitcoin-core does not know about the deposit transaction, so it
+    /// was dropped from the mempool or reorged out and never confirmed
+    /// again.
+    NeverConfirmed,
+    /// The deposit UTXO was spent by a transaction other than a sweep
+    /// transaction, most likely by the depositor through the reclaim
+    /// path.
+    Reclaimed,
+}
+
+/// A deposit request whose deposit UTXO can no longer be swept.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct StaleDepositRequest {
+    /// Transaction ID of the deposit request transaction.
+    pub txid: BitcoinTxId,
+    /// Index of the deposit request UTXO.
+    #[sqlx(try_from = "i32")]
+    pub output_index: u32,
+    /// Why the deposit request is stale.
+    pub reason: StaleDepositReason,
+    /// When Emily accepted the failure of the deposit request, if it has.
+    pub emily_synced_at: Option<Timestamp>,
+}
+
+/// The sbtc-registry contract that emitted the events of a stacks
+/// transaction.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct RegistryEventSource {
+    /// The ID of the stacks transaction that emitted the events.
+    pub txid: StacksTxId,
+    /// The ID of the stacks block that includes the transaction.
+    pub block_hash: StacksBlockHash,
+    /// The contract principal of the registry contract.
+    pub registry: StacksPrincipal,
+}
+
+/// Identifies a deposit or withdrawal request whose signing failures
+/// are tracked by the coordinator.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum SbtcRequestKey {
+    /// A deposit request, identified by its outpoint.
+    Deposit {
+        /// Transaction ID of the deposit request transaction.
+        txid: BitcoinTxId,
+        /// Index of the deposit request UTXO.
+        #[cfg_attr(feature = "testing", dummy(faker = "0..100"))]
+        output_index: u32,
+    },
+    /// A withdrawal request, identified by the request ID generated by
+    /// the smart contract.
+    Withdrawal {
+        /// The ID of the withdrawal request.
+        #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
+        request_id: u64,
+    },
+}
+
+impl std::fmt::Display for SbtcRequestKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deposit { txid, output_index } => write!(f, "deposit {txid}:{output_index}"),
+            Self::Withdrawal { request_id } => write!(f, "withdrawal {request_id}"),
+        }
+    }
+}
+
+impl From<OutPoint> for SbtcRequestKey {
+    fn from(outpoint: OutPoint) -> Self {
+        Self::Deposit {
+            txid: outpoint.txid.into(),
+            output_index: outpoint.vout,
+        }
+    }
+}
+
+/// The signing failures recorded for a deposit or withdrawal request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestFailures {
+    /// The request that failed.
+    pub request: SbtcRequestKey,
+    /// The number of times that signing for the request has failed.
+    pub failure_count: u32,
+    /// The error from the most recent failure.
+    pub last_error: String,
+    /// When the request was quarantined, if it has been. The coordinator
+    /// does not attempt to fulfill quarantined requests.
+    pub quarantined_at: Option<Timestamp>,
+}
+
+impl RequestFailures {
+    /// Whether the request has been quarantined.
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_at.is_some()
+    }
+}
+
 /// The possible states for DKG shares.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
 #[sqlx(type_name = "dkg_shares_status", rename_all = "snake_case")]
@@ -805,7 +1382,7 @@ impl std::fmt::Display for BitcoinTxId {
 }
 
 /// Bitcoin block hash
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct BitcoinBlockHash(bitcoin::BlockHash);
 
@@ -889,7 +1466,9 @@ impl std::fmt::Display for BitcoinBlockHash {
 
 /// A struct that references a specific bitcoin block is identifier and its
 /// position in the blockchain.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::FromRow)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::FromRow,
+)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub struct BitcoinBlockRef {
     /// The height of the block in the bitcoin blockchain.
@@ -1313,7 +1892,7 @@ impl From<sbtc::events::CompletedDepositEvent> for CompletedDepositEvent {
         CompletedDepositEvent {
             txid,
             block_id: sbtc_event.block_id.into(),
-            amount: sbtc_event.amount,
+            amount: SbtcAmount::new(sbtc_event.amount),
             outpoint: sbtc_event.outpoint,
             sweep_block_hash: sweep_hash,
             sweep_block_height: sbtc_event.sweep_block_height.into(),
@@ -1330,7 +1909,7 @@ impl From<sbtc::events::WithdrawalAcceptEvent> for WithdrawalAcceptEvent {
             request_id: sbtc_event.request_id,
             signer_bitmap: BitArray::new(sbtc_event.signer_bitmap.to_le_bytes()),
             outpoint: sbtc_event.outpoint,
-            fee: sbtc_event.fee,
+            fee: Amount::from_sat(sbtc_event.fee),
             sweep_block_hash: sbtc_event.sweep_block_hash.into(),
             sweep_block_height: sbtc_event.sweep_block_height.into(),
             sweep_txid: sbtc_event.sweep_txid.into(),
@@ -1379,7 +1958,7 @@ impl From<sbtc::events::KeyRotationEvent> for KeyRotationEvent {
 
 /// This is the event that is emitted from the `create-withdrawal-request`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompletedDepositEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
@@ -1387,7 +1966,7 @@ pub struct CompletedDepositEvent {
     /// The block ID of the block for this event.
     pub block_id: StacksBlockHash,
     /// This is the amount of sBTC to mint to the intended recipient.
-    pub amount: u64,
+    pub amount: SbtcAmount,
     /// This is the outpoint of the original bitcoin deposit transaction.
     pub outpoint: OutPoint,
     /// The bitcoin block hash where the sweep transaction was included.
@@ -1401,7 +1980,7 @@ pub struct CompletedDepositEvent {
 
 /// This is the event that is emitted from the `complete-withdrawal-accept`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WithdrawalAcceptEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
@@ -1419,7 +1998,7 @@ pub struct WithdrawalAcceptEvent {
     pub outpoint: OutPoint,
     /// This is the fee that was spent to the bitcoin miners to confirm the
     /// withdrawal request.
-    pub fee: u64,
+    pub fee: Amount,
     /// The bitcoin block hash where the sweep transaction was included.
     pub sweep_block_hash: BitcoinBlockHash,
     /// The bitcoin block height where the sweep transaction was included.
@@ -1431,7 +2010,7 @@ pub struct WithdrawalAcceptEvent {
 
 /// This is the event that is emitted from the `complete-withdrawal-reject`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WithdrawalRejectEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
@@ -1447,6 +2026,50 @@ pub struct WithdrawalRejectEvent {
     pub signer_bitmap: BitArray<[u8; 16]>,
 }
 
+/// An event emitted by one of the sbtc-registry contracts, in the form
+/// that it is stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StacksBlockEvent {
+    /// A deposit request was completed by minting sBTC.
+    CompletedDeposit(CompletedDepositEvent),
+    /// A withdrawal request was created.
+    WithdrawalCreate(WithdrawalRequest),
+    /// A withdrawal request was accepted.
+    WithdrawalAccept(WithdrawalAcceptEvent),
+    /// A withdrawal request was rejected.
+    WithdrawalReject(WithdrawalRejectEvent),
+    /// The signers rotated their keys.
+    KeyRotation(KeyRotationEvent),
+}
+
+impl From<sbtc::events::RegistryEvent> for StacksBlockEvent {
+    fn from(event: sbtc::events::RegistryEvent) -> Self {
+        use sbtc::events::RegistryEvent;
+        match event {
+            RegistryEvent::CompletedDeposit(event) => Self::CompletedDeposit(event.into()),
+            RegistryEvent::WithdrawalCreate(event) => Self::WithdrawalCreate(event.into()),
+            RegistryEvent::WithdrawalAccept(event) => Self::WithdrawalAccept(event.into()),
+            RegistryEvent::WithdrawalReject(event) => Self::WithdrawalReject(event.into()),
+            RegistryEvent::KeyRotation(event) => Self::KeyRotation(event.into()),
+        }
+    }
+}
+
+/// All the registry events of one stacks block, which are written to the
+/// database in a single database transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StacksBlockEvents {
+    /// The index block hash of the stacks block, which is recorded as
+    /// processed along with its events.
+    pub block_hash: StacksBlockHash,
+    /// The events of the block in the order that they were emitted, each
+    /// with the registry contract that emitted it.
+    pub events: Vec<(StacksBlockEvent, RegistryEventSource)>,
+    /// The integrity hashes of the rows written for the events, if an
+    /// integrity key is configured.
+    pub integrity_hashes: Vec<RowIntegrityHash>,
+}
+
 impl From<u8> for BitcoinBlockHeight {
     fn from(value: u8) -> Self {
         Self(value as u64)
@@ -0,0 +1,427 @@
+//! Upgrading legacy databases to the current schema.
+//!
+//! Databases created by early testnet deployments had their migration
+//! scripts applied by hand, so they have no `public.__sbtc_migrations`
+//! table recording which scripts were applied. Running
+//! [`PgStore::apply_migrations`] against such a database would attempt to
+//! re-run every script from the beginning, which fails on the first
+//! `CREATE TABLE`.
+//!
+//! This module detects which schema generation a legacy database is at by
+//! checking, for each migration script, a "fingerprint" of the changes
+//! that the script makes. The detected migrations are recorded as
+//! applied, the remaining migrations are applied on top of them, and the
+//! resulting schema is verified against all fingerprints. Everything
+//! happens within one database transaction, so a dry run is a full run
+//! that is rolled back at the end.
+
+use sqlx::PgConnection;
+
+use crate::error::Error;
+
+use super::PGSQL_MIGRATIONS;
+use super::PgStore;
+
+/// A property of the database schema that holds once a particular
+/// migration script has been applied.
+#[derive(Debug)]
+enum Fingerprint {
+    /// The `sbtc_signer` schema exists.
+    Schema,
+    /// The table, index or other relation exists. Unqualified names are
+    /// resolved using the connection's `search_path`.
+    Relation(&'static str),
+    /// The column exists on the table in the `sbtc_signer` schema.
+    Column(&'static str, &'static str),
+    /// The function exists in the `sbtc_signer` schema.
+    Function(&'static str),
+    /// The type exists.
+    Type(&'static str),
+    /// The inner fingerprint does not hold. Used for migrations that only
+    /// drop things.
+    Not(&'static Fingerprint),
+}
+
+impl Fingerprint {
+    /// Whether this fingerprint describes something that a migration
+    /// adds to the schema, rather than something that it removes.
+    fn is_positive(&self) -> bool {
+        !matches!(self, Fingerprint::Not(_))
+    }
+
+    /// Check whether the fingerprint holds for the database behind the
+    /// given connection.
+    async fn holds(&self, conn: &mut PgConnection) -> Result<bool, Error> {
+        let query = match self {
+            Fingerprint::Schema => sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = 'sbtc_signer')",
+            ),
+            Fingerprint::Relation(name) => {
+                sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL").bind(*name)
+            }
+            Fingerprint::Column(table, column) => sqlx::query_scalar(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM information_schema.columns
+                    WHERE table_schema = 'sbtc_signer'
+                      AND table_name = $1
+                      AND column_name = $2
+                )
+                "#,
+            )
+            .bind(*table)
+            .bind(*column),
+            Fingerprint::Function(name) => sqlx::query_scalar(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM pg_proc AS p
+                    JOIN pg_namespace AS n ON p.pronamespace = n.oid
+                    WHERE n.nspname = 'sbtc_signer'
+                      AND p.proname = $1
+                )
+                "#,
+            )
+            .bind(*name),
+            Fingerprint::Type(name) => {
+                sqlx::query_scalar("SELECT to_regtype($1) IS NOT NULL").bind(*name)
+            }
+            Fingerprint::Not(inner) => {
+                return Box::pin(inner.holds(conn)).await.map(|holds| !holds);
+            }
+        };
+
+        query.fetch_one(conn).await.map_err(Error::SqlxQuery)
+    }
+}
+
+/// The fingerprint of each migration script, in the order that the
+/// scripts are applied. Every script in the `signer/migrations` directory
+/// must have an entry here.
+const MIGRATION_FINGERPRINTS: &[(&str, Fingerprint)] = &[
+    ("0001__create_schema.sql", Fingerprint::Schema),
+    (
+        "0003__create_tables.sql",
+        Fingerprint::Relation("sbtc_signer.bitcoin_blocks"),
+    ),
+    (
+        "0004__create_functions.sql",
+        Fingerprint::Function("bitcoin_blockchain_of"),
+    ),
+    (
+        "0005__create_sighashes_and_withdrawal_outputs_tables.sql",
+        Fingerprint::Relation("sbtc_signer.bitcoin_tx_sighashes"),
+    ),
+    (
+        "0006__fix_stacks_event_block_hashes.sql",
+        Fingerprint::Function("reverse_bytea"),
+    ),
+    (
+        "0007__create_bitcoin_blockchain_from.sql",
+        Fingerprint::Function("bitcoin_blockchain_until"),
+    ),
+    (
+        "0008__create_indices_bitcoin_tx_tables.sql",
+        Fingerprint::Relation("sbtc_signer.ix_bitcoin_blocks_block_height"),
+    ),
+    (
+        "0009__drop_sweep_tables.sql",
+        Fingerprint::Not(&Fingerprint::Relation("sbtc_signer.sweep_transactions")),
+    ),
+    (
+        "0010__add_bitcoin_tx_related_indices.sql",
+        Fingerprint::Relation("sbtc_signer.ix_stacks_blocks_parent_hash"),
+    ),
+    (
+        "0011__add_aggregate_key_sighashes_table.sql",
+        Fingerprint::Column("bitcoin_tx_sighashes", "x_only_public_key"),
+    ),
+    (
+        "0012__dkg_verification_extensions.sql",
+        Fingerprint::Type("sbtc_signer.dkg_shares_status"),
+    ),
+    (
+        "0013__consolidate_withdrawal_tables.sql",
+        Fingerprint::Column("withdrawal_requests", "bitcoin_block_height"),
+    ),
+    (
+        "0014__add_bitcoin_tx_outputs_.sql",
+        Fingerprint::Relation("sbtc_signer.bitcoin_withdrawal_tx_outputs"),
+    ),
+    (
+        "0015__drop_transactions_tx_column.sql",
+        Fingerprint::Not(&Fingerprint::Column("transactions", "tx")),
+    ),
+    (
+        "0016__modify_rotate_keys_transactions_table.sql",
+        Fingerprint::Column("rotate_keys_transactions", "block_hash"),
+    ),
+    (
+        "0017__remove_stacks_transactions_table.sql",
+        Fingerprint::Not(&Fingerprint::Relation("sbtc_signer.stacks_transactions")),
+    ),
+    (
+        "0018__store_reclaim_script_hash.sql",
+        Fingerprint::Column("deposit_requests", "reclaim_script_hash"),
+    ),
+    // This table was created without a schema, so it lives in whichever
+    // schema comes first in the `search_path` of the connection that ran
+    // the script.
+    ("0019__p2p_peers.sql", Fingerprint::Relation("p2p_peers")),
+    (
+        "0020__bitcoin_block_activity.sql",
+        Fingerprint::Column("bitcoin_blocks", "has_sbtc_activity"),
+    ),
+    (
+        "0021__signer_identity_rotations.sql",
+        Fingerprint::Relation("sbtc_signer.signer_identity_rotations"),
+    ),
+    (
+        "0022__deposit_request_sources.sql",
+        Fingerprint::Relation("sbtc_signer.deposit_request_sources"),
+    ),
+    (
+        "0023__request_quarantine.sql",
+        Fingerprint::Relation("sbtc_signer.deposit_request_failures"),
+    ),
+    (
+        "0024__webhook_subscribers.sql",
+        Fingerprint::Relation("sbtc_signer.webhook_subscribers"),
+    ),
+    (
+        "0025__row_integrity_hashes.sql",
+        Fingerprint::Relation("sbtc_signer.row_integrity_hashes"),
+    ),
+    (
+        "0026__stacks_fee_spends.sql",
+        Fingerprint::Relation("sbtc_signer.stacks_fee_spends"),
+    ),
+    (
+        "0027__wsts_round_messages.sql",
+        Fingerprint::Relation("sbtc_signer.wsts_round_messages"),
+    ),
+    (
+        "0029__signer_response_latencies.sql",
+        Fingerprint::Relation("sbtc_signer.signer_response_latencies"),
+    ),
+    (
+        "0030__completed_deposit_events_unique.sql",
+        Fingerprint::Relation("sbtc_signer.uix_completed_deposit_events_outpoint_block_hash"),
+    ),
+    (
+        "0031__peg_wallet_tx_index.sql",
+        Fingerprint::Relation("sbtc_signer.peg_wallet_tx_inputs"),
+    ),
+    (
+        "0032__completed_deposit_events_sweep_txid.sql",
+        Fingerprint::Relation("sbtc_signer.ix_completed_deposit_events_sweep_txid"),
+    ),
+    (
+        "0033__stale_deposit_requests.sql",
+        Fingerprint::Relation("sbtc_signer.stale_deposit_requests"),
+    ),
+    (
+        "0034__registry_event_sources.sql",
+        Fingerprint::Relation("sbtc_signer.registry_event_sources"),
+    ),
+    (
+        "0035__aggregated_withdrawal_outputs.sql",
+        Fingerprint::Relation("sbtc_signer.bitcoin_withdrawal_tx_outputs_request_pkey"),
+    ),
+    (
+        "0036__processed_stacks_blocks.sql",
+        Fingerprint::Relation("sbtc_signer.processed_stacks_blocks"),
+    ),
+    (
+        "0037__sweep_completions.sql",
+        Fingerprint::Relation("sbtc_signer.sweep_completions"),
+    ),
+    (
+        "0038__failed_stacks_blocks.sql",
+        Fingerprint::Relation("sbtc_signer.failed_stacks_blocks"),
+    ),
+    (
+        "0039__emily_sync_cursors.sql",
+        Fingerprint::Relation("sbtc_signer.emily_sync_cursors"),
+    ),
+    (
+        "0040__stacks_node_burn_blocks.sql",
+        Fingerprint::Relation("sbtc_signer.stacks_node_burn_blocks"),
+    ),
+    (
+        "0041__deposit_correlation_ids.sql",
+        Fingerprint::Relation("sbtc_signer.deposit_correlation_ids"),
+    ),
+    (
+        "0042__confirmation_watches.sql",
+        Fingerprint::Relation("sbtc_signer.confirmation_watches"),
+    ),
+    (
+        "0043__peer_bans.sql",
+        Fingerprint::Relation("sbtc_signer.peer_bans"),
+    ),
+    (
+        "0045__raw_stacks_events.sql",
+        Fingerprint::Relation("sbtc_signer.raw_stacks_events"),
+    ),
+    (
+        "0046__analytics_exports.sql",
+        Fingerprint::Relation("sbtc_signer.analytics_exports"),
+    ),
+    (
+        "0047__bitcoin_internal_transfers.sql",
+        Fingerprint::Relation("sbtc_signer.bitcoin_internal_transfers"),
+    ),
+    (
+        "0048__quarantined_contract_events.sql",
+        Fingerprint::Relation("sbtc_signer.quarantined_contract_events"),
+    ),
+    (
+        "0049__stacks_block_tenures.sql",
+        Fingerprint::Relation("sbtc_signer.stacks_block_tenures"),
+    ),
+    (
+        "0050__crash_reports.sql",
+        Fingerprint::Relation("sbtc_signer.crash_reports"),
+    ),
+];
+
+/// The outcome of upgrading a legacy database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyMigrationReport {
+    /// The migrations that were detected as already applied to the legacy
+    /// database, and were recorded as such.
+    pub already_applied: Vec<&'static str>,
+    /// The migrations that were applied on top of the legacy database.
+    pub applied: Vec<String>,
+    /// Whether this was a dry run, in which case all changes were rolled
+    /// back.
+    pub dry_run: bool,
+}
+
+impl PgStore {
+    /// Upgrade a database whose migrations were applied without being
+    /// recorded in the `public.__sbtc_migrations` table to the current
+    /// schema.
+    ///
+    /// When `dry_run` is true the whole upgrade, including the final
+    /// verification, is carried out and then rolled back, so the returned
+    /// report describes what a real run would do.
+    pub async fn migrate_legacy(&self, dry_run: bool) -> Result<LegacyMigrationReport, Error> {
+        let mut trx = self
+            .pool()
+            .begin()
+            .await
+            .map_err(Error::SqlxBeginTransaction)?;
+
+        let is_tracked = sqlx::query_scalar::<_, bool>(
+            "SELECT to_regclass('public.__sbtc_migrations') IS NOT NULL",
+        )
+        .fetch_one(&mut *trx)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        if is_tracked {
+            let count =
+                sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM public.__sbtc_migrations")
+                    .fetch_one(&mut *trx)
+                    .await
+                    .map_err(Error::SqlxQuery)?;
+            if count > 0 {
+                return Err(Error::LegacyMigrationNotNeeded);
+            }
+        }
+
+        let mut holds = Vec::with_capacity(MIGRATION_FINGERPRINTS.len());
+        for (_, fingerprint) in MIGRATION_FINGERPRINTS {
+            holds.push(fingerprint.holds(&mut trx).await?);
+        }
+
+        // The legacy database is at the schema generation given by the
+        // longest run of migrations, starting from the first one, whose
+        // fingerprints hold. If anything added by a later migration is
+        // present then the schema does not match any generation that we
+        // know of, and we bail rather than guess.
+        let prefix_len = holds.iter().take_while(|holds| **holds).count();
+        let unexpected = MIGRATION_FINGERPRINTS
+            .iter()
+            .zip(&holds)
+            .skip(prefix_len)
+            .find(|((_, fingerprint), holds)| **holds && fingerprint.is_positive());
+
+        if let Some(((present, _), _)) = unexpected {
+            let (missing, _) = MIGRATION_FINGERPRINTS[prefix_len];
+            return Err(Error::UnrecognizedLegacyLayout { missing, present });
+        }
+
+        let already_applied: Vec<&'static str> = MIGRATION_FINGERPRINTS[..prefix_len]
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+
+        tracing::info!(
+            migrations = already_applied.len(),
+            "Detected migrations already applied to the legacy database"
+        );
+
+        sqlx::raw_sql(
+            r#"
+                CREATE TABLE IF NOT EXISTS public.__sbtc_migrations (
+                    key TEXT PRIMARY KEY
+                );
+            "#,
+        )
+        .execute(&mut *trx)
+        .await
+        .map_err(Error::SqlxMigrate)?;
+
+        for key in already_applied.iter() {
+            self.insert_migration(&mut *trx, key).await?;
+        }
+
+        let applied = self.apply_pending_migrations(&mut trx).await?;
+
+        for (key, fingerprint) in MIGRATION_FINGERPRINTS {
+            if !fingerprint.holds(&mut trx).await? {
+                return Err(Error::LegacyMigrationVerification(key));
+            }
+        }
+
+        if dry_run {
+            trx.rollback()
+                .await
+                .map_err(Error::SqlxRollbackTransaction)?;
+        } else {
+            trx.commit().await.map_err(Error::SqlxCommitTransaction)?;
+        }
+
+        Ok(LegacyMigrationReport {
+            already_applied,
+            applied,
+            dry_run,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_migration_has_a_fingerprint() {
+        let mut migrations = PGSQL_MIGRATIONS
+            .files()
+            .filter_map(|file| file.path().file_name()?.to_str())
+            .filter(|key| key.ends_with(".sql"))
+            .collect::<Vec<_>>();
+        migrations.sort();
+
+        let fingerprinted = MIGRATION_FINGERPRINTS
+            .iter()
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        assert_eq!(migrations, fingerprinted);
+    }
+}
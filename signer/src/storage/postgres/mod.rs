@@ -1,9 +1,11 @@
 //! Postgres storage implementation.
 
+mod legacy;
 mod read;
 mod store;
 mod write;
 
+pub use legacy::LegacyMigrationReport;
 pub use store::PgStore;
 pub use store::PgTransaction;
 
@@ -6,6 +6,7 @@ use clarity::types::chainstate::StacksBlockId;
 use crate::{
     DEPOSIT_LOCKTIME_BLOCK_BUFFER, MAX_MEMPOOL_PACKAGE_TX_COUNT, MAX_REORG_BLOCK_COUNT,
     WITHDRAWAL_BLOCKS_EXPIRY,
+    amount::SbtcAmount,
     bitcoin::{
         utxo::SignerUtxo,
         validation::{
@@ -15,6 +16,7 @@ use crate::{
     },
     error::Error,
     keys::{PublicKey, PublicKeyXOnly},
+    stacks::tenure::TenureChangeKind,
     storage::{
         DbRead,
         model::{self, BitcoinBlockHeight, StacksBlockHeight},
@@ -105,6 +107,35 @@ impl From<PgSignerUtxo> for SignerUtxo {
         }
     }
 }
+// A convenience struct for retrieving completed deposit events
+#[derive(sqlx::FromRow)]
+struct PgCompletedDepositEvent {
+    txid: model::StacksTxId,
+    block_hash: model::StacksBlockHash,
+    #[sqlx(try_from = "i64")]
+    amount: SbtcAmount,
+    bitcoin_txid: model::BitcoinTxId,
+    #[sqlx(try_from = "i64")]
+    output_index: u32,
+    sweep_block_hash: model::BitcoinBlockHash,
+    sweep_block_height: BitcoinBlockHeight,
+    sweep_txid: model::BitcoinTxId,
+}
+
+impl From<PgCompletedDepositEvent> for model::CompletedDepositEvent {
+    fn from(pg_event: PgCompletedDepositEvent) -> Self {
+        model::CompletedDepositEvent {
+            txid: pg_event.txid,
+            block_id: pg_event.block_hash,
+            amount: pg_event.amount,
+            outpoint: OutPoint::new(*pg_event.bitcoin_txid, pg_event.output_index),
+            sweep_block_hash: pg_event.sweep_block_hash,
+            sweep_block_height: pg_event.sweep_block_height,
+            sweep_txid: pg_event.sweep_txid,
+        }
+    }
+}
+
 /// Read-accessors to the Postgres database.
 pub struct PgRead;
 
@@ -240,6 +271,62 @@ impl PgRead {
         Self::get_utxo(executor, chain_tip, output_type, min_block_height).await
     }
 
+    async fn get_donation_utxos<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> Result<Vec<SignerUtxo>, Error>
+    where
+        E: 'static,
+        for<'c> &'c mut E: sqlx::PgExecutor<'c>,
+    {
+        let Some(min_block_height) = Self::minimum_donation_txo_height(executor).await? else {
+            return Ok(Vec::new());
+        };
+
+        let pg_utxos = sqlx::query_as::<_, PgSignerUtxo>(
+            r#"
+            WITH bitcoin_blockchain AS (
+                SELECT block_hash
+                FROM bitcoin_blockchain_until($1, $2)
+            ),
+            confirmed_sweeps AS (
+                SELECT
+                    prevout_txid
+                  , prevout_output_index
+                FROM sbtc_signer.bitcoin_tx_inputs
+                JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+                JOIN bitcoin_blockchain AS bb USING (block_hash)
+                WHERE prevout_type = 'signers_input'
+            )
+            SELECT
+                bo.txid
+              , bo.output_index
+              , bo.amount
+              , ds.aggregate_key
+            FROM sbtc_signer.bitcoin_tx_outputs AS bo
+            JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+            JOIN bitcoin_blockchain AS bb USING (block_hash)
+            JOIN sbtc_signer.dkg_shares AS ds USING (script_pubkey)
+            LEFT JOIN confirmed_sweeps AS cs
+              ON cs.prevout_txid = bo.txid
+              AND cs.prevout_output_index = bo.output_index
+            WHERE cs.prevout_txid IS NULL
+              AND bo.output_type = 'donation'
+              AND ds.aggregate_key = $3
+            ORDER BY bo.amount DESC, bo.txid, bo.output_index;
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(i64::try_from(min_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .bind(aggregate_key)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(pg_utxos.into_iter().map(SignerUtxo::from).collect())
+    }
+
     /// Fetch the bitcoin transaction ID that swept the withdrawal along
     /// with the block hash that confirmed the transaction.
     ///
@@ -699,6 +786,29 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_bitcoin_block_activity<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> Result<Option<model::BitcoinBlockActivity>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::BitcoinBlockActivity>(
+            "SELECT
+                block_hash
+              , block_time
+              , has_sbtc_activity
+            FROM sbtc_signer.bitcoin_blocks
+            WHERE block_hash = $1
+              AND block_time IS NOT NULL
+              AND has_sbtc_activity IS NOT NULL;",
+        )
+        .bind(block_hash)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     pub async fn get_stacks_block<'e, E>(
         executor: &'e mut E,
         block_hash: &model::StacksBlockHash,
@@ -743,7 +853,6 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
-    #[cfg(any(test, feature = "testing"))]
     async fn get_bitcoin_canonical_chain_tip_ref<'e, E>(
         executor: &'e mut E,
     ) -> Result<Option<model::BitcoinBlockRef>, Error>
@@ -1967,6 +2076,36 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_signers_script_pubkeys_at<'e, E>(
+        executor: &'e mut E,
+        bitcoin_block_height: model::BitcoinBlockHeight,
+    ) -> Result<Vec<model::Bytes>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, model::Bytes>(
+            r#"
+            SELECT script_pubkey
+            FROM sbtc_signer.dkg_shares
+            WHERE started_at_bitcoin_block_height <= $1
+              AND dkg_shares_status != 'failed'
+
+            UNION
+
+            SELECT bo.script_pubkey
+            FROM sbtc_signer.bitcoin_tx_outputs AS bo
+            JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+            JOIN sbtc_signer.bitcoin_blocks AS bb USING (block_hash)
+            WHERE bo.output_type = 'signers_output'
+              AND bb.block_height <= $1
+            "#,
+        )
+        .bind(bitcoin_block_height)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn get_signer_utxo<'e, E>(
         executor: &'e mut E,
         chain_tip: &model::BitcoinBlockHash,
@@ -2563,104 +2702,1595 @@ impl PgRead {
         .await
         .map_err(Error::SqlxQuery)
     }
-}
 
-impl DbRead for PgStore {
-    async fn get_bitcoin_block(
-        &self,
-        block_hash: &model::BitcoinBlockHash,
-    ) -> Result<Option<model::BitcoinBlock>, Error> {
-        PgRead::get_bitcoin_block(self.get_connection().await?.as_mut(), block_hash).await
+    async fn get_peer_bans<'e, E>(executor: &'e mut E) -> Result<Vec<model::PeerBan>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::PeerBan>(
+            r#"
+            SELECT
+                peer_id
+              , reason
+              , expires_at
+            FROM sbtc_signer.peer_bans
+            WHERE expires_at > CURRENT_TIMESTAMP
+            ORDER BY expires_at
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
     }
 
-    async fn get_stacks_block(
-        &self,
-        block_hash: &model::StacksBlockHash,
-    ) -> Result<Option<model::StacksBlock>, Error> {
-        PgRead::get_stacks_block(self.get_connection().await?.as_mut(), block_hash).await
+    async fn get_signer_identity_rotations<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::SignerIdentityRotation>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::SignerIdentityRotation>(
+            r#"
+            SELECT
+                old_public_key
+              , new_public_key
+              , bitcoin_chain_tip
+            FROM sbtc_signer.signer_identity_rotations
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
     }
 
-    #[cfg(any(test, feature = "testing"))]
-    async fn get_bitcoin_canonical_chain_tip(
-        &self,
-    ) -> Result<Option<model::BitcoinBlockHash>, Error> {
-        PgRead::get_bitcoin_canonical_chain_tip(self.get_connection().await?.as_mut()).await
+    async fn get_deposit_request_sources<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Vec<model::DepositRequestSource>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, model::DepositRequestSource>(
+            r#"
+            SELECT source
+            FROM sbtc_signer.deposit_request_sources
+            WHERE txid = $1
+              AND output_index = $2
+            ORDER BY created_at
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
     }
 
-    #[cfg(any(test, feature = "testing"))]
-    async fn get_bitcoin_canonical_chain_tip_ref(
-        &self,
-    ) -> Result<Option<model::BitcoinBlockRef>, Error> {
-        PgRead::get_bitcoin_canonical_chain_tip_ref(self.get_connection().await?.as_mut()).await
-    }
+    async fn get_quarantined_requests<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::RequestFailures>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // Deposits and withdrawals are keyed differently, so we fetch
+        // them together by putting the deposit output index and the
+        // withdrawal request ID into the same column.
+        let rows = sqlx::query_as::<
+            _,
+            (
+                Option<model::BitcoinTxId>,
+                i64,
+                i32,
+                String,
+                model::Timestamp,
+            ),
+        >(
+            r#"
+            SELECT
+                txid
+              , output_index::BIGINT
+              , failure_count
+              , last_error
+              , quarantined_at
+            FROM sbtc_signer.deposit_request_failures
+            WHERE quarantined_at IS NOT NULL
 
-    async fn get_stacks_chain_tip(
-        &self,
-        bitcoin_chain_tip: &model::BitcoinBlockHash,
-    ) -> Result<Option<model::StacksBlock>, Error> {
-        PgRead::get_stacks_chain_tip(self.get_connection().await?.as_mut(), bitcoin_chain_tip).await
-    }
+            UNION ALL
 
-    async fn get_pending_deposit_requests(
-        &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-        signer_public_key: &PublicKey,
-    ) -> Result<Vec<model::DepositRequest>, Error> {
-        PgRead::get_pending_deposit_requests(
-            self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
-            signer_public_key,
+            SELECT
+                NULL::BYTEA
+              , request_id
+              , failure_count
+              , last_error
+              , quarantined_at
+            FROM sbtc_signer.withdrawal_request_failures
+            WHERE quarantined_at IS NOT NULL
+
+            ORDER BY quarantined_at
+            "#,
         )
+        .fetch_all(executor)
         .await
-    }
+        .map_err(Error::SqlxQuery)?;
 
-    async fn get_pending_accepted_deposit_requests(
-        &self,
-        chain_tip: &model::BitcoinBlockRef,
-        context_window: u16,
-        threshold: u16,
-    ) -> Result<Vec<model::DepositRequest>, Error> {
-        PgRead::get_pending_accepted_deposit_requests(
-            self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
-            threshold,
+        rows.into_iter()
+            .map(|(txid, id, failure_count, last_error, quarantined_at)| {
+                let request = match txid {
+                    Some(txid) => model::SbtcRequestKey::Deposit {
+                        txid,
+                        output_index: u32::try_from(id).map_err(Error::ConversionDatabaseInt)?,
+                    },
+                    None => model::SbtcRequestKey::Withdrawal {
+                        request_id: u64::try_from(id).map_err(Error::ConversionDatabaseInt)?,
+                    },
+                };
+                Ok(model::RequestFailures {
+                    request,
+                    failure_count: u32::try_from(failure_count)
+                        .map_err(Error::ConversionDatabaseInt)?,
+                    last_error,
+                    quarantined_at: Some(quarantined_at),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_stale_deposit_candidates<'e, E>(
+        executor: &'e mut E,
+        recorded_before: model::Timestamp,
+    ) -> Result<Vec<model::DepositRequest>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // We leave out deposits spent by any transaction that we have
+        // recorded, and deposits that were minted against, on any fork.
+        // Those were swept by the signers at some point, and reorgs of
+        // sweeps are handled elsewhere.
+        sqlx::query_as::<_, model::DepositRequest>(
+            r#"
+            SELECT dr.txid
+                 , dr.output_index
+                 , dr.spend_script
+                 , dr.reclaim_script
+                 , dr.reclaim_script_hash
+                 , dr.recipient
+                 , dr.amount
+                 , dr.max_fee
+                 , dr.lock_time
+                 , dr.signers_public_key
+                 , dr.sender_script_pub_keys
+            FROM sbtc_signer.deposit_requests AS dr
+            WHERE dr.created_at < $1
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.stale_deposit_requests AS sdr
+                WHERE sdr.txid = dr.txid
+                  AND sdr.output_index = dr.output_index
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.bitcoin_tx_inputs AS bti
+                WHERE bti.prevout_txid = dr.txid
+                  AND bti.prevout_output_index = dr.output_index
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.completed_deposit_events AS cde
+                WHERE cde.bitcoin_txid = dr.txid
+                  AND cde.output_index = dr.output_index
+              )
+            ORDER BY dr.created_at, dr.txid, dr.output_index
+            "#,
         )
+        .bind(recorded_before)
+        .fetch_all(executor)
         .await
+        .map_err(Error::SqlxQuery)
     }
 
-    async fn get_deposit_request_signer_votes(
-        &self,
-        txid: &model::BitcoinTxId,
-        output_index: u32,
-        aggregate_key: &PublicKey,
-    ) -> Result<model::SignerVotes, Error> {
-        PgRead::get_deposit_request_signer_votes(
-            self.get_connection().await?.as_mut(),
-            txid,
-            output_index,
-            aggregate_key,
+    async fn get_stale_deposit_requests<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::StaleDepositRequest>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::StaleDepositRequest>(
+            r#"
+            SELECT txid
+                 , output_index
+                 , reason
+                 , emily_synced_at
+            FROM sbtc_signer.stale_deposit_requests
+            ORDER BY created_at, txid, output_index
+            "#,
         )
+        .fetch_all(executor)
         .await
+        .map_err(Error::SqlxQuery)
     }
 
-    async fn get_withdrawal_request_signer_votes(
-        &self,
-        id: &model::QualifiedRequestId,
-        aggregate_key: &PublicKey,
-    ) -> Result<model::SignerVotes, Error> {
-        PgRead::get_withdrawal_request_signer_votes(
-            self.get_connection().await?.as_mut(),
-            id,
-            aggregate_key,
+    async fn get_registry_event_source<'e, E>(
+        executor: &'e mut E,
+        txid: &model::StacksTxId,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksPrincipal>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, model::StacksPrincipal>(
+            r#"
+            SELECT registry
+            FROM sbtc_signer.registry_event_sources
+            WHERE txid = $1
+              AND block_hash = $2
+            "#,
         )
+        .bind(txid)
+        .bind(block_hash)
+        .fetch_optional(executor)
         .await
+        .map_err(Error::SqlxQuery)
     }
 
-    async fn get_deposit_request_report(
-        &self,
+    async fn is_stacks_block_processed<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.processed_stacks_blocks
+                WHERE block_hash = $1
+            )
+            "#,
+        )
+        .bind(block_hash)
+        .fetch_one(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn is_stacks_block_event_stored<'e, E>(
+        executor: &'e mut E,
+        event: &model::StacksBlockEvent,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // None of the event tables has a unique constraint on the event
+        // itself, so we match on the columns that identify an event.
+        let query = match event {
+            model::StacksBlockEvent::CompletedDeposit(event) => sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT TRUE
+                    FROM sbtc_signer.completed_deposit_events
+                    WHERE txid = $1
+                      AND block_hash = $2
+                      AND bitcoin_txid = $3
+                      AND output_index = $4
+                )
+                "#,
+            )
+            .bind(event.txid)
+            .bind(event.block_id)
+            .bind(model::BitcoinTxId::from(event.outpoint.txid))
+            .bind(i64::from(event.outpoint.vout)),
+            model::StacksBlockEvent::WithdrawalCreate(request) => sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT TRUE
+                    FROM sbtc_signer.withdrawal_requests
+                    WHERE request_id = $1
+                      AND block_hash = $2
+                )
+                "#,
+            )
+            .bind(i64::try_from(request.request_id).map_err(Error::ConversionDatabaseInt)?)
+            .bind(request.block_hash),
+            model::StacksBlockEvent::WithdrawalAccept(event) => sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT TRUE
+                    FROM sbtc_signer.withdrawal_accept_events
+                    WHERE txid = $1
+                      AND block_hash = $2
+                      AND request_id = $3
+                )
+                "#,
+            )
+            .bind(event.txid)
+            .bind(event.block_id)
+            .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?),
+            model::StacksBlockEvent::WithdrawalReject(event) => sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT TRUE
+                    FROM sbtc_signer.withdrawal_reject_events
+                    WHERE txid = $1
+                      AND block_hash = $2
+                      AND request_id = $3
+                )
+                "#,
+            )
+            .bind(event.txid)
+            .bind(event.block_id)
+            .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?),
+            model::StacksBlockEvent::KeyRotation(event) => sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT TRUE
+                    FROM sbtc_signer.rotate_keys_transactions
+                    WHERE txid = $1
+                )
+                "#,
+            )
+            .bind(event.txid),
+        };
+
+        query.fetch_one(executor).await.map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_block_failure_count<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<u32, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let failure_count = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT failure_count
+            FROM sbtc_signer.failed_stacks_blocks
+            WHERE block_hash = $1
+            "#,
+        )
+        .bind(block_hash)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u32::try_from(failure_count.unwrap_or_default()).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn get_raw_stacks_event<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<Vec<u8>>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, Vec<u8>>(
+            r#"
+            SELECT body
+            FROM sbtc_signer.raw_stacks_events
+            WHERE block_hash = $1
+            "#,
+        )
+        .bind(block_hash)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_block_tenure<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksBlockTenure>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let row = sqlx::query_as::<_, (Option<TenureChangeKind>, Option<i64>, Option<Vec<bool>>)>(
+            r#"
+            SELECT
+                tenure_change
+              , tenure_height
+              , signer_bitvec
+            FROM sbtc_signer.stacks_block_tenures
+            WHERE block_hash = $1
+            "#,
+        )
+        .bind(block_hash)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        let Some((tenure_change, tenure_height, signer_bitvec)) = row else {
+            return Ok(None);
+        };
+        let tenure_height = tenure_height
+            .map(u64::try_from)
+            .transpose()
+            .map_err(Error::ConversionDatabaseInt)?;
+
+        Ok(Some(model::StacksBlockTenure {
+            block_hash: *block_hash,
+            tenure_change,
+            tenure_height,
+            signer_bitvec,
+        }))
+    }
+
+    async fn get_crash_reports<'e, E>(
+        executor: &'e mut E,
+        limit: u16,
+    ) -> Result<Vec<model::CrashReport>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::CrashReport>(
+            r#"
+            SELECT
+                component
+              , message
+              , backtrace
+              , recent_events
+              , bitcoin_chain_tip
+              , bitcoin_chain_tip_height
+              , stacks_chain_tip
+              , created_at
+            FROM sbtc_signer.crash_reports
+            ORDER BY created_at DESC, id DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(i32::from(limit))
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_emily_deposit_cursor<'e, E>(executor: &'e mut E) -> Result<Option<u64>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let last_update_height = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT last_update_height
+            FROM sbtc_signer.emily_sync_cursors
+            WHERE name = 'deposits'
+            "#,
+        )
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        last_update_height
+            .map(u64::try_from)
+            .transpose()
+            .map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn get_last_analytics_export_end<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Option<model::Timestamp>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, model::Timestamp>(
+            r#"
+            SELECT period_end
+            FROM sbtc_signer.analytics_exports
+            ORDER BY period_end DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_latest_stacks_node_burn_block<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Option<model::StacksNodeBurnBlock>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::StacksNodeBurnBlock>(
+            r#"
+            SELECT
+                block_hash
+              , block_height
+            FROM sbtc_signer.stacks_node_burn_blocks
+            ORDER BY block_height DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn ping<'e, E>(executor: &'e mut E) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query("SELECT 1")
+            .execute(executor)
+            .await
+            .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn get_confirmation_watches<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::ConfirmationWatch>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::ConfirmationWatch>(
+            r#"
+            SELECT
+                txid
+              , confirmations
+              , callback_url
+            FROM sbtc_signer.confirmation_watches
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_deposit_correlation_id<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<String>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT correlation_id
+            FROM sbtc_signer.deposit_correlation_ids
+            WHERE txid = $1
+              AND output_index = $2
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_webhook_subscribers<'e, E>(executor: &'e mut E) -> Result<Vec<String>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT url
+            FROM sbtc_signer.webhook_subscribers
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_row_integrity_hashes<'e, E>(
+        executor: &'e mut E,
+        table_name: &str,
+    ) -> Result<Vec<model::RowIntegrityHash>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::RowIntegrityHash>(
+            r#"
+            SELECT
+                table_name
+              , row_key
+              , integrity_hash
+            FROM sbtc_signer.row_integrity_hashes
+            WHERE table_name = $1
+            "#,
+        )
+        .bind(table_name)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_wsts_round_messages<'e, E>(
+        executor: &'e mut E,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, Vec<u8>>(
+            r#"
+            SELECT payload
+            FROM sbtc_signer.wsts_round_messages
+            WHERE bitcoin_chain_tip = $1
+              AND message = $2
+            ORDER BY id
+            "#,
+        )
+        .bind(bitcoin_chain_tip)
+        .bind(message)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_fee_spends_since<'e, E>(
+        executor: &'e mut E,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::StacksFeeSpend>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let rows = sqlx::query_as::<_, (model::StacksTxId, String, i64, model::Timestamp)>(
+            r#"
+            SELECT
+                txid
+              , tx_kind
+              , fee_ustx
+              , submitted_at
+            FROM sbtc_signer.stacks_fee_spends
+            WHERE submitted_at >= $1
+            ORDER BY submitted_at
+            "#,
+        )
+        .bind(since)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(txid, tx_kind, fee, submitted_at)| {
+                Ok(model::StacksFeeSpend {
+                    txid,
+                    tx_kind,
+                    fee: u64::try_from(fee).map_err(Error::ConversionDatabaseInt)?,
+                    submitted_at,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_deposit_requests_recorded_between<'e, E>(
+        executor: &'e mut E,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::DepositRequest>>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::Recorded<model::DepositRequest>>(
+            r#"
+            SELECT txid
+                 , output_index
+                 , spend_script
+                 , reclaim_script
+                 , reclaim_script_hash
+                 , recipient
+                 , amount
+                 , max_fee
+                 , lock_time
+                 , signers_public_key
+                 , sender_script_pub_keys
+                 , created_at
+            FROM sbtc_signer.deposit_requests
+            WHERE created_at >= $1
+              AND created_at < $2
+            ORDER BY created_at, txid, output_index
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_withdrawal_requests_recorded_between<'e, E>(
+        executor: &'e mut E,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::WithdrawalRequest>>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::Recorded<model::WithdrawalRequest>>(
+            r#"
+            SELECT request_id
+                 , txid
+                 , block_hash
+                 , recipient
+                 , amount
+                 , max_fee
+                 , sender_address
+                 , bitcoin_block_height
+                 , created_at
+            FROM sbtc_signer.withdrawal_requests
+            WHERE created_at >= $1
+              AND created_at < $2
+            ORDER BY created_at, request_id
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_sweep_transactions_recorded_between<'e, E>(
+        executor: &'e mut E,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::SweepTransactionRecord>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        type Row = (
+            model::BitcoinTxId,
+            model::BitcoinBlockHash,
+            i64,
+            i64,
+            i64,
+            model::Timestamp,
+        );
+        let rows = sqlx::query_as::<_, Row>(
+            r#"
+            WITH sweeps AS (
+                SELECT
+                    txid
+                  , SUM(amount)::BIGINT AS amount_in
+                  , MIN(created_at) AS created_at
+                FROM sbtc_signer.bitcoin_tx_inputs
+                GROUP BY txid
+                HAVING bool_or(prevout_type = 'signers_input')
+            )
+            SELECT
+                sweeps.txid
+              , bb.block_hash
+              , bb.block_height
+              , sweeps.amount_in
+              , COALESCE((
+                    SELECT SUM(bo.amount)
+                    FROM sbtc_signer.bitcoin_tx_outputs AS bo
+                    WHERE bo.txid = sweeps.txid
+                ), 0)::BIGINT AS amount_out
+              , sweeps.created_at
+            FROM sweeps
+            JOIN sbtc_signer.bitcoin_transactions AS bt
+              ON bt.txid = sweeps.txid
+            JOIN sbtc_signer.bitcoin_blocks AS bb
+              ON bb.block_hash = bt.block_hash
+            LEFT JOIN sbtc_signer.bitcoin_internal_transfers AS it
+              ON it.txid = sweeps.txid
+            WHERE sweeps.created_at >= $1
+              AND sweeps.created_at < $2
+              AND it.txid IS NULL
+            ORDER BY sweeps.created_at, sweeps.txid
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(
+                |(txid, block_hash, block_height, amount_in, amount_out, recorded_at)| {
+                    Ok(model::SweepTransactionRecord {
+                        txid,
+                        block_hash,
+                        block_height: u64::try_from(block_height)
+                            .map_err(Error::ConversionDatabaseInt)?
+                            .into(),
+                        amount_in: u64::try_from(amount_in)
+                            .map_err(Error::ConversionDatabaseInt)?,
+                        amount_out: u64::try_from(amount_out)
+                            .map_err(Error::ConversionDatabaseInt)?,
+                        recorded_at,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    async fn get_signer_response_latencies_since<'e, E>(
+        executor: &'e mut E,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::SignerResponseLatency>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let rows = sqlx::query_as::<_, (PublicKey, String, i64, model::Timestamp)>(
+            r#"
+            SELECT
+                signer_public_key
+              , response_kind
+              , latency_ms
+              , observed_at
+            FROM sbtc_signer.signer_response_latencies
+            WHERE observed_at >= $1
+            ORDER BY observed_at
+            "#,
+        )
+        .bind(since)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(signer_public_key, response_kind, latency, observed_at)| {
+                Ok(model::SignerResponseLatency {
+                    signer_public_key,
+                    response_kind,
+                    latency_millis: u64::try_from(latency).map_err(Error::ConversionDatabaseInt)?,
+                    observed_at,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_completed_deposit_events<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, PgCompletedDepositEvent>(
+            r#"
+            SELECT
+                txid
+              , block_hash
+              , amount
+              , bitcoin_txid
+              , output_index
+              , sweep_block_hash
+              , sweep_block_height
+              , sweep_txid
+            FROM sbtc_signer.completed_deposit_events
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map(|events| events.into_iter().map(Into::into).collect())
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_completed_deposit_events_for_outpoint<'e, E>(
+        executor: &'e mut E,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, PgCompletedDepositEvent>(
+            r#"
+            SELECT
+                txid
+              , block_hash
+              , amount
+              , bitcoin_txid
+              , output_index
+              , sweep_block_hash
+              , sweep_block_height
+              , sweep_txid
+            FROM sbtc_signer.completed_deposit_events
+            WHERE bitcoin_txid = $1
+              AND output_index = $2
+            ORDER BY id
+            "#,
+        )
+        .bind(model::BitcoinTxId::from(outpoint.txid))
+        .bind(i64::from(outpoint.vout))
+        .fetch_all(executor)
+        .await
+        .map(|events| events.into_iter().map(Into::into).collect())
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_completed_deposit_events_for_sweep<'e, E>(
+        executor: &'e mut E,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, PgCompletedDepositEvent>(
+            r#"
+            SELECT
+                txid
+              , block_hash
+              , amount
+              , bitcoin_txid
+              , output_index
+              , sweep_block_hash
+              , sweep_block_height
+              , sweep_txid
+            FROM sbtc_signer.completed_deposit_events
+            WHERE sweep_txid = $1
+            ORDER BY id
+            "#,
+        )
+        .bind(sweep_txid)
+        .fetch_all(executor)
+        .await
+        .map(|events| events.into_iter().map(Into::into).collect())
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_orphaned_sweep_transactions<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockRef,
+        context_window: u16,
+    ) -> Result<Vec<model::OrphanedSweepTransaction>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let min_block_height = chain_tip
+            .block_height
+            .saturating_sub(u64::from(context_window));
+
+        // A transaction can be confirmed in more than one fork, so we
+        // leave out the ones that are also confirmed on the canonical
+        // blockchain, since those have not been orphaned.
+        let rows = sqlx::query_as::<_, (model::BitcoinTxId, model::BitcoinBlockHash, i64)>(
+            r#"
+            WITH canonical AS (
+                SELECT block_hash
+                FROM sbtc_signer.bitcoin_blockchain_until($1, $2)
+            ),
+            sweeps AS (
+                SELECT DISTINCT txid
+                FROM sbtc_signer.bitcoin_tx_inputs
+                WHERE prevout_type = 'signers_input'
+            )
+            SELECT
+                bt.txid
+              , bb.block_hash
+              , bb.block_height
+            FROM sweeps
+            JOIN sbtc_signer.bitcoin_transactions AS bt
+              ON bt.txid = sweeps.txid
+            JOIN sbtc_signer.bitcoin_blocks AS bb
+              ON bb.block_hash = bt.block_hash
+            WHERE bb.block_height >= $2
+              AND bb.block_hash NOT IN (SELECT block_hash FROM canonical)
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.bitcoin_transactions AS confirmed
+                JOIN canonical
+                  ON canonical.block_hash = confirmed.block_hash
+                WHERE confirmed.txid = bt.txid
+              )
+            ORDER BY bb.block_height, bt.txid
+            "#,
+        )
+        .bind(chain_tip.block_hash)
+        .bind(i64::try_from(min_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(txid, block_hash, block_height)| {
+                Ok(model::OrphanedSweepTransaction {
+                    txid,
+                    block_hash,
+                    block_height: u64::try_from(block_height)
+                        .map_err(Error::ConversionDatabaseInt)?
+                        .into(),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_sweep_completions<'e, E>(
+        executor: &'e mut E,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::SweepCompletion>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::SweepCompletion>(
+            r#"
+            SELECT
+                sweep_txid
+              , sweep_block_hash
+              , stacks_txid
+              , stacks_block_hash
+            FROM sbtc_signer.sweep_completions
+            WHERE sweep_txid = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(sweep_txid)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_uncompleted_sweep_transactions<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockRef,
+        deadline: u16,
+        context_window: u16,
+    ) -> Result<Vec<model::UncompletedSweepTransaction>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let min_block_height = chain_tip
+            .block_height
+            .saturating_sub(u64::from(context_window));
+        let max_block_height = chain_tip.block_height.saturating_sub(u64::from(deadline));
+        if max_block_height < min_block_height {
+            return Ok(Vec::new());
+        }
+
+        // Sweeps that only moved the signers' UTXO, say after a key
+        // rotation, fulfill no requests and so have nothing to complete.
+        let rows = sqlx::query_as::<_, (model::BitcoinTxId, model::BitcoinBlockHash, i64)>(
+            r#"
+            WITH sweeps AS (
+                SELECT DISTINCT txid
+                FROM sbtc_signer.bitcoin_tx_inputs
+                WHERE prevout_type = 'signers_input'
+            )
+            SELECT
+                bt.txid
+              , canonical.block_hash
+              , canonical.block_height
+            FROM sbtc_signer.bitcoin_blockchain_until($1, $2) AS canonical
+            JOIN sbtc_signer.bitcoin_transactions AS bt
+              ON bt.block_hash = canonical.block_hash
+            JOIN sweeps
+              ON sweeps.txid = bt.txid
+            WHERE canonical.block_height <= $3
+              AND (
+                EXISTS (
+                    SELECT 1
+                    FROM sbtc_signer.bitcoin_tx_inputs AS bti
+                    WHERE bti.txid = bt.txid
+                      AND bti.prevout_type = 'deposit'
+                )
+                OR EXISTS (
+                    SELECT 1
+                    FROM sbtc_signer.bitcoin_tx_outputs AS bto
+                    WHERE bto.txid = bt.txid
+                      AND bto.output_type = 'withdrawal'
+                )
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.sweep_completions AS sc
+                WHERE sc.sweep_txid = bt.txid
+              )
+            ORDER BY canonical.block_height, bt.txid
+            "#,
+        )
+        .bind(chain_tip.block_hash)
+        .bind(i64::try_from(min_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(max_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(txid, block_hash, block_height)| {
+                Ok(model::UncompletedSweepTransaction {
+                    txid,
+                    block_hash,
+                    block_height: u64::try_from(block_height)
+                        .map_err(Error::ConversionDatabaseInt)?
+                        .into(),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_canonical_completed_deposit_event<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        outpoint: &OutPoint,
+    ) -> Result<Option<model::CompletedDepositEvent>, Error>
+    where
+        E: 'static,
+        for<'c> &'c mut E: sqlx::PgExecutor<'c>,
+    {
+        let Some(stacks_chain_tip) = Self::get_stacks_chain_tip(executor, chain_tip).await? else {
+            return Ok(None);
+        };
+
+        // We only need to walk the stacks blockchain back to the lowest
+        // block with an event for the outpoint, since the events can only
+        // be in blocks at or above that height.
+        sqlx::query_as::<_, PgCompletedDepositEvent>(
+            r#"
+            WITH RECURSIVE events AS (
+                SELECT
+                    cde.txid
+                  , cde.block_hash
+                  , cde.amount
+                  , cde.bitcoin_txid
+                  , cde.output_index
+                  , cde.sweep_block_hash
+                  , cde.sweep_block_height
+                  , cde.sweep_txid
+                  , sb.block_height
+                FROM sbtc_signer.completed_deposit_events AS cde
+                JOIN sbtc_signer.stacks_blocks AS sb
+                  ON sb.block_hash = cde.block_hash
+                WHERE cde.bitcoin_txid = $2
+                  AND cde.output_index = $3
+            ),
+            stacks_blockchain AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , block_height
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , parent.block_height
+                FROM sbtc_signer.stacks_blocks AS parent
+                JOIN stacks_blockchain AS last
+                  ON parent.block_hash = last.parent_hash
+                WHERE parent.block_height >= (SELECT MIN(block_height) FROM events)
+            )
+            SELECT
+                events.txid
+              , events.block_hash
+              , events.amount
+              , events.bitcoin_txid
+              , events.output_index
+              , events.sweep_block_hash
+              , events.sweep_block_height
+              , events.sweep_txid
+            FROM events
+            JOIN stacks_blockchain
+              ON stacks_blockchain.block_hash = events.block_hash
+            LIMIT 1
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(model::BitcoinTxId::from(outpoint.txid))
+        .bind(i64::from(outpoint.vout))
+        .fetch_optional(executor)
+        .await
+        .map(|event| event.map(Into::into))
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_key_rotation_events<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::KeyRotationEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::KeyRotationEvent>(
+            r#"
+            SELECT
+                txid
+              , block_hash
+              , address
+              , aggregate_key
+              , signer_set
+              , signatures_required
+            FROM sbtc_signer.rotate_keys_transactions
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_chain_completed_deposit_events<'e, E>(
+        executor: &'e mut E,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, PgCompletedDepositEvent>(
+            r#"
+            WITH RECURSIVE stacks_blockchain AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                FROM sbtc_signer.stacks_blocks AS parent
+                JOIN stacks_blockchain AS last
+                  ON parent.block_hash = last.parent_hash
+            )
+            SELECT
+                cde.txid
+              , cde.block_hash
+              , cde.amount
+              , cde.bitcoin_txid
+              , cde.output_index
+              , cde.sweep_block_hash
+              , cde.sweep_block_height
+              , cde.sweep_txid
+            FROM sbtc_signer.completed_deposit_events AS cde
+            JOIN stacks_blockchain
+              ON stacks_blockchain.block_hash = cde.block_hash
+            ORDER BY cde.id
+            "#,
+        )
+        .bind(stacks_chain_tip)
+        .fetch_all(executor)
+        .await
+        .map(|events| events.into_iter().map(Into::into).collect())
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_chain_key_rotation_events<'e, E>(
+        executor: &'e mut E,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::KeyRotationEvent>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::KeyRotationEvent>(
+            r#"
+            WITH RECURSIVE stacks_blockchain AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                FROM sbtc_signer.stacks_blocks AS parent
+                JOIN stacks_blockchain AS last
+                  ON parent.block_hash = last.parent_hash
+            )
+            SELECT
+                rkt.txid
+              , rkt.block_hash
+              , rkt.address
+              , rkt.aggregate_key
+              , rkt.signer_set
+              , rkt.signatures_required
+            FROM sbtc_signer.rotate_keys_transactions AS rkt
+            JOIN stacks_blockchain
+              ON stacks_blockchain.block_hash = rkt.block_hash
+            ORDER BY rkt.created_at
+            "#,
+        )
+        .bind(stacks_chain_tip)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_peg_wallet_tx_inputs<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::PegWalletTxInput>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::PegWalletTxInput>(
+            r#"
+            SELECT
+                pwi.txid
+              , pwi.input_index
+              , pwi.prevout_txid
+              , pwi.prevout_output_index
+              , pwi.amount
+              , pwi.script_pubkey
+              , pwi.signers_script
+            FROM sbtc_signer.peg_wallet_tx_inputs AS pwi
+            WHERE pwi.txid = $1
+            ORDER BY pwi.input_index
+            "#,
+        )
+        .bind(txid)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_peg_wallet_tx_outputs<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::PegWalletTxOutput>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::PegWalletTxOutput>(
+            r#"
+            SELECT
+                pwo.txid
+              , pwo.output_index
+              , pwo.amount
+              , pwo.script_pubkey
+              , pwo.signers_script
+            FROM sbtc_signer.peg_wallet_tx_outputs AS pwo
+            WHERE pwo.txid = $1
+            ORDER BY pwo.output_index
+            "#,
+        )
+        .bind(txid)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn is_bitcoin_internal_transfer<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.bitcoin_internal_transfers
+                WHERE txid = $1
+            )
+            "#,
+        )
+        .bind(txid)
+        .fetch_one(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn trace_peg_wallet_spends<'e, E>(
+        executor: &'e mut E,
+        outpoint: &OutPoint,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // A transaction can be reached along more than one path, so we
+        // only keep the shallowest link for each input.
+        sqlx::query_as::<_, model::PegWalletSpendLink>(
+            r#"
+            WITH RECURSIVE flow AS (
+                SELECT
+                pwi.txid
+              , pwi.input_index
+              , pwi.prevout_txid
+              , pwi.prevout_output_index
+              , pwi.amount
+              , pwi.script_pubkey
+              , pwi.signers_script
+                  , 1 AS depth
+                FROM sbtc_signer.peg_wallet_tx_inputs AS pwi
+                WHERE pwi.prevout_txid = $1
+                  AND pwi.prevout_output_index = $2
+
+                UNION
+
+                SELECT
+                pwi.txid
+              , pwi.input_index
+              , pwi.prevout_txid
+              , pwi.prevout_output_index
+              , pwi.amount
+              , pwi.script_pubkey
+              , pwi.signers_script
+                  , flow.depth + 1
+                FROM flow
+                JOIN sbtc_signer.peg_wallet_tx_outputs AS pwo
+                  ON pwo.txid = flow.txid
+                 AND pwo.signers_script
+                JOIN sbtc_signer.peg_wallet_tx_inputs AS pwi
+                  ON pwi.prevout_txid = pwo.txid
+                 AND pwi.prevout_output_index = pwo.output_index
+                WHERE flow.depth < $3
+            )
+            SELECT *
+            FROM (
+                SELECT DISTINCT ON (flow.txid, flow.input_index) *
+                FROM flow
+                ORDER BY flow.txid, flow.input_index, flow.depth
+            ) AS links
+            ORDER BY links.depth, links.txid, links.input_index
+            "#,
+        )
+        .bind(model::BitcoinTxId::from(outpoint.txid))
+        .bind(i32::try_from(outpoint.vout).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i32::try_from(max_depth).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn trace_peg_wallet_sources<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::PegWalletSpendLink>(
+            r#"
+            WITH RECURSIVE flow AS (
+                SELECT
+                pwi.txid
+              , pwi.input_index
+              , pwi.prevout_txid
+              , pwi.prevout_output_index
+              , pwi.amount
+              , pwi.script_pubkey
+              , pwi.signers_script
+                  , 1 AS depth
+                FROM sbtc_signer.peg_wallet_tx_inputs AS pwi
+                WHERE pwi.txid = $1
+
+                UNION
+
+                SELECT
+                pwi.txid
+              , pwi.input_index
+              , pwi.prevout_txid
+              , pwi.prevout_output_index
+              , pwi.amount
+              , pwi.script_pubkey
+              , pwi.signers_script
+                  , flow.depth + 1
+                FROM flow
+                JOIN sbtc_signer.peg_wallet_tx_inputs AS pwi
+                  ON pwi.txid = flow.prevout_txid
+                WHERE flow.signers_script
+                  AND flow.depth < $2
+            )
+            SELECT *
+            FROM (
+                SELECT DISTINCT ON (flow.txid, flow.input_index) *
+                FROM flow
+                ORDER BY flow.txid, flow.input_index, flow.depth
+            ) AS links
+            ORDER BY links.depth, links.txid, links.input_index
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(max_depth).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+}
+
+impl DbRead for PgStore {
+    async fn get_bitcoin_block(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> Result<Option<model::BitcoinBlock>, Error> {
+        PgRead::get_bitcoin_block(self.get_connection().await?.as_mut(), block_hash).await
+    }
+
+    async fn get_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksBlock>, Error> {
+        PgRead::get_stacks_block(self.get_connection().await?.as_mut(), block_hash).await
+    }
+
+    async fn get_bitcoin_block_activity(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> Result<Option<model::BitcoinBlockActivity>, Error> {
+        PgRead::get_bitcoin_block_activity(self.get_connection().await?.as_mut(), block_hash).await
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    async fn get_bitcoin_canonical_chain_tip(
+        &self,
+    ) -> Result<Option<model::BitcoinBlockHash>, Error> {
+        PgRead::get_bitcoin_canonical_chain_tip(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_bitcoin_canonical_chain_tip_ref(
+        &self,
+    ) -> Result<Option<model::BitcoinBlockRef>, Error> {
+        PgRead::get_bitcoin_canonical_chain_tip_ref(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_stacks_chain_tip(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<model::StacksBlock>, Error> {
+        PgRead::get_stacks_chain_tip(self.get_connection().await?.as_mut(), bitcoin_chain_tip).await
+    }
+
+    async fn get_pending_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signer_public_key: &PublicKey,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_pending_deposit_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+            signer_public_key,
+        )
+        .await
+    }
+
+    async fn get_pending_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        context_window: u16,
+        threshold: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_pending_accepted_deposit_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+            threshold,
+        )
+        .await
+    }
+
+    async fn get_deposit_request_signer_votes(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+        aggregate_key: &PublicKey,
+    ) -> Result<model::SignerVotes, Error> {
+        PgRead::get_deposit_request_signer_votes(
+            self.get_connection().await?.as_mut(),
+            txid,
+            output_index,
+            aggregate_key,
+        )
+        .await
+    }
+
+    async fn get_withdrawal_request_signer_votes(
+        &self,
+        id: &model::QualifiedRequestId,
+        aggregate_key: &PublicKey,
+    ) -> Result<model::SignerVotes, Error> {
+        PgRead::get_withdrawal_request_signer_votes(
+            self.get_connection().await?.as_mut(),
+            id,
+            aggregate_key,
+        )
+        .await
+    }
+
+    async fn get_deposit_request_report(
+        &self,
         chain_tip: &model::BitcoinBlockHash,
         txid: &model::BitcoinTxId,
         output_index: u32,
@@ -2869,6 +4499,14 @@ impl DbRead for PgStore {
         PgRead::get_signers_script_pubkeys(self.get_connection().await?.as_mut()).await
     }
 
+    async fn get_signers_script_pubkeys_at(
+        &self,
+        bitcoin_block_height: model::BitcoinBlockHeight,
+    ) -> Result<Vec<model::Bytes>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_signers_script_pubkeys_at(conn.as_mut(), bitcoin_block_height).await
+    }
+
     async fn get_signer_utxo(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -2876,6 +4514,15 @@ impl DbRead for PgStore {
         PgRead::get_signer_utxo(self.get_connection().await?.as_mut(), chain_tip).await
     }
 
+    async fn get_donation_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> Result<Vec<SignerUtxo>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_donation_utxos(conn.as_mut(), chain_tip, aggregate_key).await
+    }
+
     async fn is_known_bitcoin_block_hash(
         &self,
         block_hash: &model::BitcoinBlockHash,
@@ -2924,79 +4571,402 @@ impl DbRead for PgStore {
         .await
     }
 
-    async fn get_swept_deposit_requests(
+    async fn get_swept_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::SweptDepositRequest>, Error> {
+        PgRead::get_swept_deposit_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_swept_withdrawal_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::SweptWithdrawalRequest>, Error> {
+        PgRead::get_swept_withdrawal_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_deposit_request(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<model::DepositRequest>, Error> {
+        PgRead::get_deposit_request(self.get_connection().await?.as_mut(), txid, output_index).await
+    }
+
+    async fn will_sign_bitcoin_tx_sighash(
+        &self,
+        sighash: &model::SigHash,
+    ) -> Result<Option<(bool, PublicKeyXOnly)>, Error> {
+        PgRead::will_sign_bitcoin_tx_sighash(self.get_connection().await?.as_mut(), sighash).await
+    }
+
+    async fn get_withdrawal_signer_decisions(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signer_public_key: &PublicKey,
+    ) -> Result<Vec<model::WithdrawalSigner>, Error> {
+        PgRead::get_withdrawal_signer_decisions(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+            signer_public_key,
+        )
+        .await
+    }
+
+    async fn get_deposit_signer_decisions(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signer_public_key: &PublicKey,
+    ) -> Result<Vec<model::DepositSigner>, Error> {
+        PgRead::get_deposit_signer_decisions(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+            signer_public_key,
+        )
+        .await
+    }
+
+    async fn get_p2p_peers(&self) -> Result<Vec<model::P2PPeer>, Error> {
+        PgRead::get_p2p_peers(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_peer_bans(&self) -> Result<Vec<model::PeerBan>, Error> {
+        PgRead::get_peer_bans(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_signer_identity_rotations(
+        &self,
+    ) -> Result<Vec<model::SignerIdentityRotation>, Error> {
+        PgRead::get_signer_identity_rotations(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_deposit_request_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Vec<model::DepositRequestSource>, Error> {
+        PgRead::get_deposit_request_sources(
+            self.get_connection().await?.as_mut(),
+            txid,
+            output_index,
+        )
+        .await
+    }
+
+    async fn get_quarantined_requests(&self) -> Result<Vec<model::RequestFailures>, Error> {
+        PgRead::get_quarantined_requests(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_stale_deposit_candidates(
+        &self,
+        recorded_before: model::Timestamp,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_stale_deposit_candidates(self.get_connection().await?.as_mut(), recorded_before)
+            .await
+    }
+
+    async fn get_stale_deposit_requests(&self) -> Result<Vec<model::StaleDepositRequest>, Error> {
+        PgRead::get_stale_deposit_requests(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_registry_event_source(
+        &self,
+        txid: &model::StacksTxId,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksPrincipal>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_registry_event_source(conn.as_mut(), txid, block_hash).await
+    }
+
+    async fn is_stacks_block_processed(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<bool, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::is_stacks_block_processed(conn.as_mut(), block_hash).await
+    }
+
+    async fn is_stacks_block_event_stored(
+        &self,
+        event: &model::StacksBlockEvent,
+    ) -> Result<bool, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::is_stacks_block_event_stored(conn.as_mut(), event).await
+    }
+
+    async fn get_stacks_block_failure_count(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<u32, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_stacks_block_failure_count(conn.as_mut(), block_hash).await
+    }
+
+    async fn get_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_raw_stacks_event(conn.as_mut(), block_hash).await
+    }
+
+    async fn get_stacks_block_tenure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksBlockTenure>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_stacks_block_tenure(conn.as_mut(), block_hash).await
+    }
+
+    async fn get_crash_reports(&self, limit: u16) -> Result<Vec<model::CrashReport>, Error> {
+        PgRead::get_crash_reports(self.get_connection().await?.as_mut(), limit).await
+    }
+
+    async fn get_emily_deposit_cursor(&self) -> Result<Option<u64>, Error> {
+        PgRead::get_emily_deposit_cursor(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_last_analytics_export_end(&self) -> Result<Option<model::Timestamp>, Error> {
+        PgRead::get_last_analytics_export_end(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_latest_stacks_node_burn_block(
+        &self,
+    ) -> Result<Option<model::StacksNodeBurnBlock>, Error> {
+        PgRead::get_latest_stacks_node_burn_block(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        PgRead::ping(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_confirmation_watches(&self) -> Result<Vec<model::ConfirmationWatch>, Error> {
+        PgRead::get_confirmation_watches(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_deposit_correlation_id(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<String>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_deposit_correlation_id(conn.as_mut(), txid, output_index).await
+    }
+
+    async fn get_webhook_subscribers(&self) -> Result<Vec<String>, Error> {
+        PgRead::get_webhook_subscribers(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_row_integrity_hashes(
+        &self,
+        table_name: &str,
+    ) -> Result<Vec<model::RowIntegrityHash>, Error> {
+        PgRead::get_row_integrity_hashes(self.get_connection().await?.as_mut(), table_name).await
+    }
+
+    async fn get_stacks_fee_spends_since(
+        &self,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::StacksFeeSpend>, Error> {
+        PgRead::get_stacks_fee_spends_since(self.get_connection().await?.as_mut(), since).await
+    }
+
+    async fn get_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_wsts_round_messages(conn.as_mut(), bitcoin_chain_tip, message).await
+    }
+
+    async fn get_deposit_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::DepositRequest>>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_deposit_requests_recorded_between(conn.as_mut(), from, to).await
+    }
+
+    async fn get_withdrawal_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::WithdrawalRequest>>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_withdrawal_requests_recorded_between(conn.as_mut(), from, to).await
+    }
+
+    async fn get_sweep_transactions_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::SweepTransactionRecord>, Error> {
+        let mut conn = self.get_connection().await?;
+        PgRead::get_sweep_transactions_recorded_between(conn.as_mut(), from, to).await
+    }
+
+    async fn get_signer_response_latencies_since(
+        &self,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::SignerResponseLatency>, Error> {
+        PgRead::get_signer_response_latencies_since(self.get_connection().await?.as_mut(), since)
+            .await
+    }
+
+    async fn get_completed_deposit_events(
+        &self,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        PgRead::get_completed_deposit_events(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_completed_deposit_events_for_outpoint(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        PgRead::get_completed_deposit_events_for_outpoint(
+            self.get_connection().await?.as_mut(),
+            outpoint,
+        )
+        .await
+    }
+
+    async fn get_canonical_completed_deposit_event(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        outpoint: &OutPoint,
+    ) -> Result<Option<model::CompletedDepositEvent>, Error> {
+        PgRead::get_canonical_completed_deposit_event(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            outpoint,
+        )
+        .await
+    }
+
+    async fn get_completed_deposit_events_for_sweep(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        PgRead::get_completed_deposit_events_for_sweep(
+            self.get_connection().await?.as_mut(),
+            sweep_txid,
+        )
+        .await
+    }
+
+    async fn get_orphaned_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        context_window: u16,
+    ) -> Result<Vec<model::OrphanedSweepTransaction>, Error> {
+        PgRead::get_orphaned_sweep_transactions(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_sweep_completions(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::SweepCompletion>, Error> {
+        PgRead::get_sweep_completions(self.get_connection().await?.as_mut(), sweep_txid).await
+    }
+
+    async fn get_uncompleted_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        deadline: u16,
+        context_window: u16,
+    ) -> Result<Vec<model::UncompletedSweepTransaction>, Error> {
+        PgRead::get_uncompleted_sweep_transactions(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            deadline,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_key_rotation_events(&self) -> Result<Vec<model::KeyRotationEvent>, Error> {
+        PgRead::get_key_rotation_events(self.get_connection().await?.as_mut()).await
+    }
+
+    async fn get_stacks_chain_completed_deposit_events(
         &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-    ) -> Result<Vec<model::SweptDepositRequest>, Error> {
-        PgRead::get_swept_deposit_requests(
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        PgRead::get_stacks_chain_completed_deposit_events(
             self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
+            stacks_chain_tip,
         )
         .await
     }
 
-    async fn get_swept_withdrawal_requests(
+    async fn get_stacks_chain_key_rotation_events(
         &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-    ) -> Result<Vec<model::SweptWithdrawalRequest>, Error> {
-        PgRead::get_swept_withdrawal_requests(
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::KeyRotationEvent>, Error> {
+        PgRead::get_stacks_chain_key_rotation_events(
             self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
+            stacks_chain_tip,
         )
         .await
     }
 
-    async fn get_deposit_request(
+    async fn get_peg_wallet_tx_inputs(
         &self,
         txid: &model::BitcoinTxId,
-        output_index: u32,
-    ) -> Result<Option<model::DepositRequest>, Error> {
-        PgRead::get_deposit_request(self.get_connection().await?.as_mut(), txid, output_index).await
+    ) -> Result<Vec<model::PegWalletTxInput>, Error> {
+        PgRead::get_peg_wallet_tx_inputs(self.get_connection().await?.as_mut(), txid).await
     }
 
-    async fn will_sign_bitcoin_tx_sighash(
+    async fn get_peg_wallet_tx_outputs(
         &self,
-        sighash: &model::SigHash,
-    ) -> Result<Option<(bool, PublicKeyXOnly)>, Error> {
-        PgRead::will_sign_bitcoin_tx_sighash(self.get_connection().await?.as_mut(), sighash).await
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::PegWalletTxOutput>, Error> {
+        PgRead::get_peg_wallet_tx_outputs(self.get_connection().await?.as_mut(), txid).await
     }
 
-    async fn get_withdrawal_signer_decisions(
-        &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-        signer_public_key: &PublicKey,
-    ) -> Result<Vec<model::WithdrawalSigner>, Error> {
-        PgRead::get_withdrawal_signer_decisions(
-            self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
-            signer_public_key,
-        )
-        .await
+    async fn is_bitcoin_internal_transfer(&self, txid: &model::BitcoinTxId) -> Result<bool, Error> {
+        PgRead::is_bitcoin_internal_transfer(self.get_connection().await?.as_mut(), txid).await
     }
 
-    async fn get_deposit_signer_decisions(
+    async fn trace_peg_wallet_spends(
         &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-        signer_public_key: &PublicKey,
-    ) -> Result<Vec<model::DepositSigner>, Error> {
-        PgRead::get_deposit_signer_decisions(
-            self.get_connection().await?.as_mut(),
-            chain_tip,
-            context_window,
-            signer_public_key,
-        )
-        .await
+        outpoint: &OutPoint,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error> {
+        PgRead::trace_peg_wallet_spends(self.get_connection().await?.as_mut(), outpoint, max_depth)
+            .await
     }
 
-    async fn get_p2p_peers(&self) -> Result<Vec<model::P2PPeer>, Error> {
-        PgRead::get_p2p_peers(self.get_connection().await?.as_mut()).await
+    async fn trace_peg_wallet_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error> {
+        PgRead::trace_peg_wallet_sources(self.get_connection().await?.as_mut(), txid, max_depth)
+            .await
     }
 }
 
@@ -3016,6 +4986,14 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_stacks_block(self.tx.lock().await.as_mut(), block_hash).await
     }
 
+    async fn get_bitcoin_block_activity(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> Result<Option<model::BitcoinBlockActivity>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_bitcoin_block_activity(tx.as_mut(), block_hash).await
+    }
+
     #[cfg(any(test, feature = "testing"))]
     async fn get_bitcoin_canonical_chain_tip(
         &self,
@@ -3024,7 +5002,6 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_bitcoin_canonical_chain_tip(tx.as_mut()).await
     }
 
-    #[cfg(any(test, feature = "testing"))]
     async fn get_bitcoin_canonical_chain_tip_ref(
         &self,
     ) -> Result<Option<model::BitcoinBlockRef>, Error> {
@@ -3307,6 +5284,14 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_signers_script_pubkeys(tx.as_mut()).await
     }
 
+    async fn get_signers_script_pubkeys_at(
+        &self,
+        bitcoin_block_height: model::BitcoinBlockHeight,
+    ) -> Result<Vec<model::Bytes>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_signers_script_pubkeys_at(tx.as_mut(), bitcoin_block_height).await
+    }
+
     async fn get_signer_utxo(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -3314,6 +5299,15 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_signer_utxo(self.tx.lock().await.as_mut(), chain_tip).await
     }
 
+    async fn get_donation_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> Result<Vec<crate::bitcoin::utxo::SignerUtxo>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_donation_utxos(tx.as_mut(), chain_tip, aggregate_key).await
+    }
+
     async fn get_deposit_request_signer_votes(
         &self,
         txid: &model::BitcoinTxId,
@@ -3430,4 +5424,318 @@ impl DbRead for PgTransaction<'_> {
         let mut tx = self.tx.lock().await;
         PgRead::get_p2p_peers(tx.as_mut()).await
     }
+
+    async fn get_peer_bans(&self) -> Result<Vec<model::PeerBan>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_peer_bans(tx.as_mut()).await
+    }
+
+    async fn get_signer_identity_rotations(
+        &self,
+    ) -> Result<Vec<model::SignerIdentityRotation>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_signer_identity_rotations(tx.as_mut()).await
+    }
+
+    async fn get_deposit_request_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Vec<model::DepositRequestSource>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_deposit_request_sources(tx.as_mut(), txid, output_index).await
+    }
+
+    async fn get_quarantined_requests(&self) -> Result<Vec<model::RequestFailures>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_quarantined_requests(tx.as_mut()).await
+    }
+
+    async fn get_stale_deposit_candidates(
+        &self,
+        recorded_before: model::Timestamp,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stale_deposit_candidates(tx.as_mut(), recorded_before).await
+    }
+
+    async fn get_stale_deposit_requests(&self) -> Result<Vec<model::StaleDepositRequest>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stale_deposit_requests(tx.as_mut()).await
+    }
+
+    async fn get_registry_event_source(
+        &self,
+        txid: &model::StacksTxId,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksPrincipal>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_registry_event_source(tx.as_mut(), txid, block_hash).await
+    }
+
+    async fn is_stacks_block_processed(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::is_stacks_block_processed(tx.as_mut(), block_hash).await
+    }
+
+    async fn is_stacks_block_event_stored(
+        &self,
+        event: &model::StacksBlockEvent,
+    ) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::is_stacks_block_event_stored(tx.as_mut(), event).await
+    }
+
+    async fn get_stacks_block_failure_count(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<u32, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stacks_block_failure_count(tx.as_mut(), block_hash).await
+    }
+
+    async fn get_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_raw_stacks_event(tx.as_mut(), block_hash).await
+    }
+
+    async fn get_stacks_block_tenure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Option<model::StacksBlockTenure>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stacks_block_tenure(tx.as_mut(), block_hash).await
+    }
+
+    async fn get_crash_reports(&self, limit: u16) -> Result<Vec<model::CrashReport>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_crash_reports(tx.as_mut(), limit).await
+    }
+
+    async fn get_emily_deposit_cursor(&self) -> Result<Option<u64>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_emily_deposit_cursor(tx.as_mut()).await
+    }
+
+    async fn get_last_analytics_export_end(&self) -> Result<Option<model::Timestamp>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_last_analytics_export_end(tx.as_mut()).await
+    }
+
+    async fn get_latest_stacks_node_burn_block(
+        &self,
+    ) -> Result<Option<model::StacksNodeBurnBlock>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_latest_stacks_node_burn_block(tx.as_mut()).await
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::ping(tx.as_mut()).await
+    }
+
+    async fn get_confirmation_watches(&self) -> Result<Vec<model::ConfirmationWatch>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_confirmation_watches(tx.as_mut()).await
+    }
+
+    async fn get_deposit_correlation_id(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<String>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_deposit_correlation_id(tx.as_mut(), txid, output_index).await
+    }
+
+    async fn get_webhook_subscribers(&self) -> Result<Vec<String>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_webhook_subscribers(tx.as_mut()).await
+    }
+
+    async fn get_row_integrity_hashes(
+        &self,
+        table_name: &str,
+    ) -> Result<Vec<model::RowIntegrityHash>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_row_integrity_hashes(tx.as_mut(), table_name).await
+    }
+
+    async fn get_stacks_fee_spends_since(
+        &self,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::StacksFeeSpend>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stacks_fee_spends_since(tx.as_mut(), since).await
+    }
+
+    async fn get_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_wsts_round_messages(tx.as_mut(), bitcoin_chain_tip, message).await
+    }
+
+    async fn get_deposit_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::DepositRequest>>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_deposit_requests_recorded_between(tx.as_mut(), from, to).await
+    }
+
+    async fn get_withdrawal_requests_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::Recorded<model::WithdrawalRequest>>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_withdrawal_requests_recorded_between(tx.as_mut(), from, to).await
+    }
+
+    async fn get_sweep_transactions_recorded_between(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<Vec<model::SweepTransactionRecord>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_sweep_transactions_recorded_between(tx.as_mut(), from, to).await
+    }
+
+    async fn get_signer_response_latencies_since(
+        &self,
+        since: model::Timestamp,
+    ) -> Result<Vec<model::SignerResponseLatency>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_signer_response_latencies_since(tx.as_mut(), since).await
+    }
+
+    async fn get_completed_deposit_events(
+        &self,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_completed_deposit_events(tx.as_mut()).await
+    }
+
+    async fn get_completed_deposit_events_for_outpoint(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_completed_deposit_events_for_outpoint(tx.as_mut(), outpoint).await
+    }
+
+    async fn get_canonical_completed_deposit_event(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        outpoint: &OutPoint,
+    ) -> Result<Option<model::CompletedDepositEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_canonical_completed_deposit_event(tx.as_mut(), chain_tip, outpoint).await
+    }
+
+    async fn get_completed_deposit_events_for_sweep(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_completed_deposit_events_for_sweep(tx.as_mut(), sweep_txid).await
+    }
+
+    async fn get_orphaned_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        context_window: u16,
+    ) -> Result<Vec<model::OrphanedSweepTransaction>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_orphaned_sweep_transactions(tx.as_mut(), chain_tip, context_window).await
+    }
+
+    async fn get_sweep_completions(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::SweepCompletion>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_sweep_completions(tx.as_mut(), sweep_txid).await
+    }
+
+    async fn get_uncompleted_sweep_transactions(
+        &self,
+        chain_tip: &model::BitcoinBlockRef,
+        deadline: u16,
+        context_window: u16,
+    ) -> Result<Vec<model::UncompletedSweepTransaction>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_uncompleted_sweep_transactions(tx.as_mut(), chain_tip, deadline, context_window)
+            .await
+    }
+
+    async fn get_key_rotation_events(&self) -> Result<Vec<model::KeyRotationEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_key_rotation_events(tx.as_mut()).await
+    }
+
+    async fn get_stacks_chain_completed_deposit_events(
+        &self,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::CompletedDepositEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stacks_chain_completed_deposit_events(tx.as_mut(), stacks_chain_tip).await
+    }
+
+    async fn get_stacks_chain_key_rotation_events(
+        &self,
+        stacks_chain_tip: &model::StacksBlockHash,
+    ) -> Result<Vec<model::KeyRotationEvent>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_stacks_chain_key_rotation_events(tx.as_mut(), stacks_chain_tip).await
+    }
+
+    async fn get_peg_wallet_tx_inputs(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::PegWalletTxInput>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_peg_wallet_tx_inputs(tx.as_mut(), txid).await
+    }
+
+    async fn get_peg_wallet_tx_outputs(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::PegWalletTxOutput>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_peg_wallet_tx_outputs(tx.as_mut(), txid).await
+    }
+
+    async fn is_bitcoin_internal_transfer(&self, txid: &model::BitcoinTxId) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::is_bitcoin_internal_transfer(tx.as_mut(), txid).await
+    }
+
+    async fn trace_peg_wallet_spends(
+        &self,
+        outpoint: &OutPoint,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::trace_peg_wallet_spends(tx.as_mut(), outpoint, max_depth).await
+    }
+
+    async fn trace_peg_wallet_sources(
+        &self,
+        txid: &model::BitcoinTxId,
+        max_depth: u32,
+    ) -> Result<Vec<model::PegWalletSpendLink>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::trace_peg_wallet_sources(tx.as_mut(), txid, max_depth).await
+    }
 }
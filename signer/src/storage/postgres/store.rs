@@ -57,6 +57,26 @@ impl PgStore {
             .await
             .map_err(Error::SqlxBeginTransaction)?;
 
+        self.apply_pending_migrations(&mut trx).await?;
+
+        trx.commit().await.map_err(Error::SqlxCommitTransaction)?;
+
+        Ok(())
+    }
+
+    /// Apply all migrations that have not been recorded as applied, using
+    /// the given connection, and return the keys of the ones that were
+    /// applied. The `public.__sbtc_migrations` table must already exist.
+    ///
+    /// If this returns an error then some migrations may have been
+    /// applied, so the connection should be part of a transaction that is
+    /// rolled back.
+    pub(super) async fn apply_pending_migrations(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> Result<Vec<String>, Error> {
+        let mut applied = Vec::new();
+
         // Collect all migration scripts and sort them by filename. It is important
         // that the migration scripts are named in a way that they are executed in
         // the correct order, i.e. the current naming of `0001__`, `0002__`, etc.
@@ -77,42 +97,36 @@ impl PgStore {
 
             // Check if the migration has already been applied. If so, we should
             // be able to safely skip it.
-            if self.check_migration_existence(&mut *trx, &key).await? {
+            if self.check_migration_existence(&mut *conn, &key).await? {
                 tracing::debug!(migration = %key, "Database migration already applied");
                 continue;
             }
 
             // Attempt to apply the migration. If we encounter an error, we abort
             // the entire migration process.
-            if let Some(script) = migration.contents_utf8() {
-                tracing::info!(migration = %key, "Applying database migration");
-
-                // Execute the migration.
-                sqlx::raw_sql(script)
-                    .execute(&mut *trx)
-                    .await
-                    .map_err(Error::SqlxMigrate)?;
-
-                // Save the migration as applied.
-                self.insert_migration(&mut *trx, &key).await?;
-            } else {
-                // The trx should be rolled back on drop but let's be explicit.
-                trx.rollback()
-                    .await
-                    .map_err(Error::SqlxRollbackTransaction)?;
-
+            let Some(script) = migration.contents_utf8() else {
                 // We failed to read the migration script as valid UTF-8. This
                 // shouldn't happen since it's our own migration scripts, but
                 // just in case...
                 return Err(Error::ReadSqlMigration(
                     migration.path().as_os_str().to_string_lossy(),
                 ));
-            }
-        }
+            };
 
-        trx.commit().await.map_err(Error::SqlxCommitTransaction)?;
+            tracing::info!(migration = %key, "Applying database migration");
 
-        Ok(())
+            // Execute the migration.
+            sqlx::raw_sql(script)
+                .execute(&mut *conn)
+                .await
+                .map_err(Error::SqlxMigrate)?;
+
+            // Save the migration as applied.
+            self.insert_migration(&mut *conn, &key).await?;
+            applied.push(key.into_owned());
+        }
+
+        Ok(applied)
     }
 
     /// Check if a migration with the given `key` exists.
@@ -139,7 +153,7 @@ impl PgStore {
     }
 
     /// Insert a migration with the given `key`.
-    async fn insert_migration(
+    pub(super) async fn insert_migration(
         &self,
         executor: impl PgExecutor<'_>,
         key: &str,
@@ -41,6 +41,29 @@ impl PgWrite {
         Ok(())
     }
 
+    async fn write_bitcoin_block_activity<'e, E>(
+        executor: &'e mut E,
+        activity: &model::BitcoinBlockActivity,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            "UPDATE sbtc_signer.bitcoin_blocks
+            SET block_time = $2
+              , has_sbtc_activity = $3
+            WHERE block_hash = $1",
+        )
+        .bind(activity.block_hash)
+        .bind(i64::try_from(activity.block_time).map_err(Error::ConversionDatabaseInt)?)
+        .bind(activity.has_sbtc_activity)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
     async fn write_stacks_block<'e, E>(
         executor: &'e mut E,
         block: &model::StacksBlock,
@@ -553,7 +576,8 @@ impl PgWrite {
           , sweep_block_height
           , sweep_txid
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT DO NOTHING",
         )
         .bind(event.txid)
         .bind(event.block_id)
@@ -570,6 +594,36 @@ impl PgWrite {
         Ok(())
     }
 
+    async fn write_sweep_completion<'e, E>(
+        executor: &'e mut E,
+        completion: &model::SweepCompletion,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.sweep_completions (
+                sweep_txid
+              , sweep_block_hash
+              , stacks_txid
+              , stacks_block_hash
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(completion.sweep_txid)
+        .bind(completion.sweep_block_hash)
+        .bind(completion.stacks_txid)
+        .bind(completion.stacks_block_hash)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
     async fn write_withdrawal_accept_event<'e, E>(
         executor: &'e mut E,
         event: &WithdrawalAcceptEvent,
@@ -599,7 +653,7 @@ impl PgWrite {
         .bind(event.signer_bitmap.into_inner())
         .bind(event.outpoint.txid.to_byte_array())
         .bind(i64::from(event.outpoint.vout))
-        .bind(i64::try_from(event.fee).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(event.fee.to_sat()).map_err(Error::ConversionDatabaseInt)?)
         .bind(event.sweep_block_hash.to_byte_array())
         .bind(i64::try_from(event.sweep_block_height).map_err(Error::ConversionDatabaseInt)?)
         .bind(event.sweep_txid.to_byte_array())
@@ -1002,167 +1056,1452 @@ impl PgWrite {
 
         Ok(())
     }
-}
-
-impl DbWrite for PgStore {
-    async fn write_bitcoin_block(&self, block: &model::BitcoinBlock) -> Result<(), Error> {
-        PgWrite::write_bitcoin_block(self.get_connection().await?.as_mut(), block).await
-    }
-
-    async fn write_stacks_block(&self, block: &model::StacksBlock) -> Result<(), Error> {
-        PgWrite::write_stacks_block(self.get_connection().await?.as_mut(), block).await
-    }
-
-    async fn write_deposit_request(
-        &self,
-        deposit_request: &model::DepositRequest,
-    ) -> Result<(), Error> {
-        PgWrite::write_deposit_request(self.get_connection().await?.as_mut(), deposit_request).await
-    }
-
-    async fn write_deposit_requests(
-        &self,
-        deposit_requests: Vec<model::DepositRequest>,
-    ) -> Result<(), Error> {
-        PgWrite::write_deposit_requests(self.get_connection().await?.as_mut(), deposit_requests)
-            .await
-    }
 
-    async fn write_withdrawal_request(
-        &self,
-        request: &model::WithdrawalRequest,
-    ) -> Result<(), Error> {
-        PgWrite::write_withdrawal_request(self.get_connection().await?.as_mut(), request).await
-    }
+    async fn write_peer_ban<'e, E>(executor: &'e mut E, ban: &model::PeerBan) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.peer_bans (
+                peer_id
+              , reason
+              , expires_at
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT (peer_id) DO UPDATE SET
+                reason = EXCLUDED.reason
+              , expires_at = EXCLUDED.expires_at
+              , created_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(ban.peer_id)
+        .bind(&ban.reason)
+        .bind(ban.expires_at)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
 
-    #[tracing::instrument(skip(self))]
-    async fn write_deposit_signer_decision(
-        &self,
-        decision: &model::DepositSigner,
-    ) -> Result<(), Error> {
-        PgWrite::write_deposit_signer_decision(self.get_connection().await?.as_mut(), decision)
-            .await
+        Ok(())
     }
 
-    async fn write_withdrawal_signer_decision(
-        &self,
-        decision: &model::WithdrawalSigner,
-    ) -> Result<(), Error> {
-        PgWrite::write_withdrawal_signer_decision(self.get_connection().await?.as_mut(), decision)
-            .await
+    async fn delete_peer_ban<'e, E>(
+        executor: &'e mut E,
+        peer_id: &libp2p::PeerId,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // Expired bans are deleted along the way, but they do not count
+        // as the peer having had a ban.
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            DELETE FROM sbtc_signer.peer_bans
+            WHERE peer_id = $1
+            RETURNING expires_at > CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(DbPeerId::from(*peer_id))
+        .fetch_optional(executor)
+        .await
+        .map(|unexpired| unexpired.unwrap_or(false))
+        .map_err(Error::SqlxQuery)
     }
 
-    async fn write_bitcoin_transaction(&self, tx_ref: &model::BitcoinTxRef) -> Result<(), Error> {
-        PgWrite::write_bitcoin_transaction(self.get_connection().await?.as_mut(), tx_ref).await
-    }
+    async fn write_signer_identity_rotation<'e, E>(
+        executor: &'e mut E,
+        rotation: &model::SignerIdentityRotation,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.signer_identity_rotations (
+                old_public_key
+              , new_public_key
+              , bitcoin_chain_tip
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(rotation.old_public_key)
+        .bind(rotation.new_public_key)
+        .bind(rotation.bitcoin_chain_tip)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
 
-    async fn write_bitcoin_transactions(&self, txs: Vec<model::BitcoinTxRef>) -> Result<(), Error> {
-        PgWrite::write_bitcoin_transactions(self.get_connection().await?.as_mut(), txs).await
+        Ok(())
     }
 
-    async fn write_stacks_block_headers(
-        &self,
-        blocks: Vec<model::StacksBlock>,
-    ) -> Result<(), Error> {
-        PgWrite::write_stacks_block_headers(self.get_connection().await?.as_mut(), blocks).await
-    }
+    async fn write_deposit_request_intake<'e, E>(
+        executor: &'e mut E,
+        intake: &model::DepositRequestIntake,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.deposit_request_sources (
+                txid
+              , output_index
+              , source
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(intake.txid)
+        .bind(i32::try_from(intake.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(intake.source)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
 
-    async fn write_encrypted_dkg_shares(
-        &self,
-        shares: &model::EncryptedDkgShares,
-    ) -> Result<(), Error> {
-        PgWrite::write_encrypted_dkg_shares(self.get_connection().await?.as_mut(), shares).await
+        Ok(())
     }
 
-    async fn write_rotate_keys_transaction(
-        &self,
-        key_rotation: &model::KeyRotationEvent,
-    ) -> Result<(), Error> {
-        PgWrite::write_rotate_keys_transaction(self.get_connection().await?.as_mut(), key_rotation)
+    async fn write_request_failure<'e, E>(
+        executor: &'e mut E,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> Result<model::RequestFailures, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let threshold =
+            i32::try_from(quarantine_threshold).map_err(Error::ConversionDatabaseInt)?;
+        let query = match request {
+            model::SbtcRequestKey::Deposit { txid, output_index } => {
+                sqlx::query_as::<_, (i32, String, Option<model::Timestamp>)>(
+                    r#"
+                INSERT INTO sbtc_signer.deposit_request_failures AS f (
+                    txid
+                  , output_index
+                  , failure_count
+                  , last_error
+                  , quarantined_at
+                )
+                VALUES ($1, $2, 1, $3, CASE WHEN 1 >= $4 THEN CURRENT_TIMESTAMP END)
+                ON CONFLICT (txid, output_index) DO UPDATE SET
+                    failure_count = f.failure_count + 1
+                  , last_error = EXCLUDED.last_error
+                  , quarantined_at = COALESCE(
+                        f.quarantined_at,
+                        CASE WHEN f.failure_count + 1 >= $4 THEN CURRENT_TIMESTAMP END
+                    )
+                RETURNING failure_count, last_error, quarantined_at
+                "#,
+                )
+                .bind(txid)
+                .bind(i32::try_from(*output_index).map_err(Error::ConversionDatabaseInt)?)
+            }
+            model::SbtcRequestKey::Withdrawal { request_id } => {
+                sqlx::query_as::<_, (i32, String, Option<model::Timestamp>)>(
+                    r#"
+                INSERT INTO sbtc_signer.withdrawal_request_failures AS f (
+                    request_id
+                  , failure_count
+                  , last_error
+                  , quarantined_at
+                )
+                VALUES ($1, 1, $2, CASE WHEN 1 >= $3 THEN CURRENT_TIMESTAMP END)
+                ON CONFLICT (request_id) DO UPDATE SET
+                    failure_count = f.failure_count + 1
+                  , last_error = EXCLUDED.last_error
+                  , quarantined_at = COALESCE(
+                        f.quarantined_at,
+                        CASE WHEN f.failure_count + 1 >= $3 THEN CURRENT_TIMESTAMP END
+                    )
+                RETURNING failure_count, last_error, quarantined_at
+                "#,
+                )
+                .bind(i64::try_from(*request_id).map_err(Error::ConversionDatabaseInt)?)
+            }
+        };
+
+        let (failure_count, last_error, quarantined_at) = query
+            .bind(error)
+            .bind(threshold)
+            .fetch_one(executor)
             .await
-    }
-
-    async fn write_completed_deposit_event(
-        &self,
-        event: &CompletedDepositEvent,
-    ) -> Result<(), Error> {
-        PgWrite::write_completed_deposit_event(self.get_connection().await?.as_mut(), event).await
-    }
+            .map_err(Error::SqlxQuery)?;
 
-    async fn write_withdrawal_accept_event(
-        &self,
-        event: &WithdrawalAcceptEvent,
-    ) -> Result<(), Error> {
-        PgWrite::write_withdrawal_accept_event(self.get_connection().await?.as_mut(), event).await
+        Ok(model::RequestFailures {
+            request: *request,
+            failure_count: u32::try_from(failure_count).map_err(Error::ConversionDatabaseInt)?,
+            last_error,
+            quarantined_at,
+        })
     }
 
-    async fn write_withdrawal_reject_event(
-        &self,
-        event: &WithdrawalRejectEvent,
-    ) -> Result<(), Error> {
-        PgWrite::write_withdrawal_reject_event(self.get_connection().await?.as_mut(), event).await
-    }
+    async fn release_request_failures<'e, E>(
+        executor: &'e mut E,
+        request: &model::SbtcRequestKey,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let query = match request {
+            model::SbtcRequestKey::Deposit { txid, output_index } => sqlx::query(
+                r#"
+                DELETE FROM sbtc_signer.deposit_request_failures
+                WHERE txid = $1
+                  AND output_index = $2
+                "#,
+            )
+            .bind(txid)
+            .bind(i32::try_from(*output_index).map_err(Error::ConversionDatabaseInt)?),
+            model::SbtcRequestKey::Withdrawal { request_id } => sqlx::query(
+                r#"
+                DELETE FROM sbtc_signer.withdrawal_request_failures
+                WHERE request_id = $1
+                "#,
+            )
+            .bind(i64::try_from(*request_id).map_err(Error::ConversionDatabaseInt)?),
+        };
 
-    async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
-        PgWrite::write_tx_output(self.get_connection().await?.as_mut(), output).await
-    }
+        let result = query.execute(executor).await.map_err(Error::SqlxQuery)?;
 
-    async fn write_withdrawal_tx_output(
-        &self,
-        output: &model::WithdrawalTxOutput,
-    ) -> Result<(), Error> {
-        PgWrite::write_withdrawal_tx_output(self.get_connection().await?.as_mut(), output).await
+        Ok(result.rows_affected() > 0)
     }
 
-    async fn write_tx_prevout(&self, prevout: &model::TxPrevout) -> Result<(), Error> {
-        PgWrite::write_tx_prevout(self.get_connection().await?.as_mut(), prevout).await
-    }
+    async fn write_stale_deposit_request<'e, E>(
+        executor: &'e mut E,
+        stale: &model::StaleDepositRequest,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stale_deposit_requests
+                ( txid
+                , output_index
+                , reason
+                , emily_synced_at
+                )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(stale.txid)
+        .bind(i32::try_from(stale.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(stale.reason)
+        .bind(stale.emily_synced_at)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
 
-    async fn write_bitcoin_txs_sighashes(
-        &self,
-        sighashes: &[model::BitcoinTxSigHash],
-    ) -> Result<(), Error> {
-        PgWrite::write_bitcoin_txs_sighashes(self.get_connection().await?.as_mut(), sighashes).await
+        Ok(())
     }
 
-    async fn write_bitcoin_withdrawals_outputs(
-        &self,
-        withdrawal_outputs: &[model::BitcoinWithdrawalOutput],
-    ) -> Result<(), Error> {
-        PgWrite::write_bitcoin_withdrawals_outputs(
-            self.get_connection().await?.as_mut(),
-            withdrawal_outputs,
+    async fn mark_stale_deposit_request_synced<'e, E>(
+        executor: &'e mut E,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.stale_deposit_requests
+            SET emily_synced_at = CURRENT_TIMESTAMP
+            WHERE txid = $1
+              AND output_index = $2
+              AND emily_synced_at IS NULL
+            "#,
         )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .execute(executor)
         .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
     }
 
-    async fn revoke_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
+    async fn write_registry_event_source<'e, E>(
+        executor: &'e mut E,
+        source: &model::RegistryEventSource,
+    ) -> Result<(), Error>
     where
-        X: Into<PublicKeyXOnly>,
+        &'e mut E: sqlx::PgExecutor<'e>,
     {
-        PgWrite::revoke_dkg_shares(self.get_connection().await?.as_mut(), aggregate_key).await
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.registry_event_sources
+                ( txid
+                , block_hash
+                , registry
+                )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(source.txid)
+        .bind(source.block_hash)
+        .bind(&source.registry)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
     }
 
-    async fn verify_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
+    async fn write_processed_stacks_block<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.processed_stacks_blocks (block_hash)
+            VALUES ($1)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(block_hash)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_failure<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> Result<u32, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let failure_count = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO sbtc_signer.failed_stacks_blocks (
+                block_hash
+              , failure_count
+              , last_error
+            )
+            VALUES ($1, 1, $2)
+            ON CONFLICT (block_hash) DO UPDATE
+            SET failure_count = failed_stacks_blocks.failure_count + 1
+              , last_error = EXCLUDED.last_error
+              , updated_at = CURRENT_TIMESTAMP
+            RETURNING failure_count
+            "#,
+        )
+        .bind(block_hash)
+        .bind(error)
+        .fetch_one(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u32::try_from(failure_count).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn write_raw_stacks_event<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.raw_stacks_events (block_hash, body)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(block_hash)
+        .bind(body)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_raw_stacks_events_before<'e, E>(
+        executor: &'e mut E,
+        before: model::Timestamp,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.raw_stacks_events
+            WHERE created_at < $1
+            "#,
+        )
+        .bind(before)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_emily_deposit_cursor<'e, E>(
+        executor: &'e mut E,
+        last_update_height: u64,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let last_update_height =
+            i64::try_from(last_update_height).map_err(Error::ConversionDatabaseInt)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.emily_sync_cursors (name, last_update_height)
+            VALUES ('deposits', $1)
+            ON CONFLICT (name) DO UPDATE
+            SET last_update_height = EXCLUDED.last_update_height
+              , updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(last_update_height)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_analytics_export<'e, E>(
+        executor: &'e mut E,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.analytics_exports (period_start, period_end)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_node_burn_block<'e, E>(
+        executor: &'e mut E,
+        block: &model::StacksNodeBurnBlock,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_node_burn_blocks (block_hash, block_height)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(block.block_hash)
+        .bind(i64::try_from(block.block_height).map_err(Error::ConversionDatabaseInt)?)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_confirmation_watch<'e, E>(
+        executor: &'e mut E,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.confirmation_watches (
+                txid
+              , confirmations
+              , callback_url
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(watch.txid)
+        .bind(i32::try_from(watch.confirmations).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&watch.callback_url)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_confirmation_watch<'e, E>(
+        executor: &'e mut E,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.confirmation_watches
+            WHERE txid = $1
+              AND confirmations = $2
+              AND callback_url IS NOT DISTINCT FROM $3
+            "#,
+        )
+        .bind(watch.txid)
+        .bind(i32::try_from(watch.confirmations).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&watch.callback_url)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn write_deposit_correlation_id<'e, E>(
+        executor: &'e mut E,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.deposit_correlation_ids (
+                txid
+              , output_index
+              , correlation_id
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT (txid, output_index) DO UPDATE
+            SET correlation_id = EXCLUDED.correlation_id
+            "#,
+        )
+        .bind(correlation_id.txid)
+        .bind(i32::try_from(correlation_id.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&correlation_id.correlation_id)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_events<E>(
+        executor: &mut E,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error>
+    where
+        for<'c> &'c mut E: sqlx::PgExecutor<'c>,
+    {
+        for (event, source) in &events.events {
+            Self::write_registry_event_source(&mut *executor, source).await?;
+            match event {
+                model::StacksBlockEvent::CompletedDeposit(event) => {
+                    Self::write_completed_deposit_event(&mut *executor, event).await?;
+                    Self::write_sweep_completion(&mut *executor, &event.into()).await?;
+                }
+                model::StacksBlockEvent::WithdrawalCreate(request) => {
+                    Self::write_withdrawal_request(&mut *executor, request).await?;
+                }
+                model::StacksBlockEvent::WithdrawalAccept(event) => {
+                    Self::write_withdrawal_accept_event(&mut *executor, event).await?;
+                    Self::write_sweep_completion(&mut *executor, &event.into()).await?;
+                }
+                model::StacksBlockEvent::WithdrawalReject(event) => {
+                    Self::write_withdrawal_reject_event(&mut *executor, event).await?;
+                }
+                model::StacksBlockEvent::KeyRotation(event) => {
+                    Self::write_rotate_keys_transaction(&mut *executor, event).await?;
+                }
+            }
+        }
+
+        for hash in &events.integrity_hashes {
+            Self::write_row_integrity_hash(&mut *executor, hash).await?;
+        }
+
+        Self::write_processed_stacks_block(executor, &events.block_hash).await
+    }
+
+    async fn write_webhook_subscriber<'e, E>(executor: &'e mut E, url: &str) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.webhook_subscribers (url)
+            VALUES ($1)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(url)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_webhook_subscriber<'e, E>(executor: &'e mut E, url: &str) -> Result<bool, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.webhook_subscribers
+            WHERE url = $1
+            "#,
+        )
+        .bind(url)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn write_row_integrity_hash<'e, E>(
+        executor: &'e mut E,
+        hash: &model::RowIntegrityHash,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.row_integrity_hashes (
+                table_name
+              , row_key
+              , integrity_hash
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&hash.table_name)
+        .bind(&hash.row_key)
+        .bind(hash.integrity_hash)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_fee_spend<'e, E>(
+        executor: &'e mut E,
+        spend: &model::StacksFeeSpend,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_fee_spends (
+                txid
+              , tx_kind
+              , fee_ustx
+              , submitted_at
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(spend.txid)
+        .bind(&spend.tx_kind)
+        .bind(i64::try_from(spend.fee).map_err(Error::ConversionDatabaseInt)?)
+        .bind(spend.submitted_at)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_wsts_round_message<'e, E>(
+        executor: &'e mut E,
+        message: &model::WstsRoundMessage,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.wsts_round_messages (
+                bitcoin_chain_tip
+              , message
+              , payload
+            )
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(message.bitcoin_chain_tip)
+        .bind(&message.message)
+        .bind(&message.payload)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_wsts_round_messages<'e, E>(
+        executor: &'e mut E,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.wsts_round_messages
+            WHERE bitcoin_chain_tip = $1
+              AND message = $2
+            "#,
+        )
+        .bind(bitcoin_chain_tip)
+        .bind(message)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_stale_wsts_round_messages<'e, E>(
+        executor: &'e mut E,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.wsts_round_messages
+            WHERE bitcoin_chain_tip <> $1
+            "#,
+        )
+        .bind(bitcoin_chain_tip)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_signer_response_latency<'e, E>(
+        executor: &'e mut E,
+        latency: &model::SignerResponseLatency,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.signer_response_latencies (
+                signer_public_key
+              , response_kind
+              , latency_ms
+              , observed_at
+            )
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(latency.signer_public_key)
+        .bind(&latency.response_kind)
+        .bind(i64::try_from(latency.latency_millis).map_err(Error::ConversionDatabaseInt)?)
+        .bind(latency.observed_at)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_signer_response_latencies_before<'e, E>(
+        executor: &'e mut E,
+        before: model::Timestamp,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.signer_response_latencies
+            WHERE observed_at < $1
+            "#,
+        )
+        .bind(before)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_peg_wallet_tx_input<'e, E>(
+        executor: &'e mut E,
+        input: &model::PegWalletTxInput,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.peg_wallet_tx_inputs (
+                txid
+              , input_index
+              , prevout_txid
+              , prevout_output_index
+              , amount
+              , script_pubkey
+              , signers_script
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (txid, input_index) DO UPDATE
+            SET signers_script = peg_wallet_tx_inputs.signers_script
+                              OR EXCLUDED.signers_script
+            "#,
+        )
+        .bind(input.txid)
+        .bind(i32::try_from(input.input_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(input.prevout_txid)
+        .bind(i32::try_from(input.prevout_output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(input.amount).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&input.script_pubkey)
+        .bind(input.signers_script)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_peg_wallet_tx_output<'e, E>(
+        executor: &'e mut E,
+        output: &model::PegWalletTxOutput,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.peg_wallet_tx_outputs (
+                txid
+              , output_index
+              , amount
+              , script_pubkey
+              , signers_script
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (txid, output_index) DO UPDATE
+            SET signers_script = peg_wallet_tx_outputs.signers_script
+                              OR EXCLUDED.signers_script
+            "#,
+        )
+        .bind(output.txid)
+        .bind(i32::try_from(output.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(output.amount).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&output.script_pubkey)
+        .bind(output.signers_script)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_bitcoin_internal_transfer<'e, E>(
+        executor: &'e mut E,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.bitcoin_internal_transfers (txid, block_hash, amount)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(transfer.txid)
+        .bind(transfer.block_hash)
+        .bind(i64::try_from(transfer.amount).map_err(Error::ConversionDatabaseInt)?)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_quarantined_contract_event<'e, E>(
+        executor: &'e mut E,
+        event: &model::QuarantinedContractEvent,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.quarantined_contract_events (
+                txid
+              , block_hash
+              , event_index
+              , contract_identifier
+              , topic
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(event.txid)
+        .bind(event.block_hash)
+        .bind(i64::try_from(event.event_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&event.contract_identifier)
+        .bind(&event.topic)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_tenure<'e, E>(
+        executor: &'e mut E,
+        tenure: &model::StacksBlockTenure,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let tenure_height = tenure
+            .tenure_height
+            .map(i64::try_from)
+            .transpose()
+            .map_err(Error::ConversionDatabaseInt)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_block_tenures (
+                block_hash
+              , tenure_change
+              , tenure_height
+              , signer_bitvec
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(tenure.block_hash)
+        .bind(tenure.tenure_change)
+        .bind(tenure_height)
+        .bind(&tenure.signer_bitvec)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_crash_report<'e, E>(
+        executor: &'e mut E,
+        report: &model::CrashReport,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.crash_reports (
+                component
+              , message
+              , backtrace
+              , recent_events
+              , bitcoin_chain_tip
+              , bitcoin_chain_tip_height
+              , stacks_chain_tip
+              , created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(&report.component)
+        .bind(&report.message)
+        .bind(&report.backtrace)
+        .bind(&report.recent_events)
+        .bind(report.bitcoin_chain_tip)
+        .bind(report.bitcoin_chain_tip_height)
+        .bind(report.stacks_chain_tip)
+        .bind(report.created_at)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+}
+
+impl DbWrite for PgStore {
+    async fn write_bitcoin_block(&self, block: &model::BitcoinBlock) -> Result<(), Error> {
+        PgWrite::write_bitcoin_block(self.get_connection().await?.as_mut(), block).await
+    }
+
+    async fn write_stacks_block(&self, block: &model::StacksBlock) -> Result<(), Error> {
+        PgWrite::write_stacks_block(self.get_connection().await?.as_mut(), block).await
+    }
+
+    async fn write_bitcoin_block_activity(
+        &self,
+        activity: &model::BitcoinBlockActivity,
+    ) -> Result<(), Error> {
+        PgWrite::write_bitcoin_block_activity(self.get_connection().await?.as_mut(), activity).await
+    }
+
+    async fn write_deposit_request(
+        &self,
+        deposit_request: &model::DepositRequest,
+    ) -> Result<(), Error> {
+        PgWrite::write_deposit_request(self.get_connection().await?.as_mut(), deposit_request).await
+    }
+
+    async fn write_deposit_requests(
+        &self,
+        deposit_requests: Vec<model::DepositRequest>,
+    ) -> Result<(), Error> {
+        PgWrite::write_deposit_requests(self.get_connection().await?.as_mut(), deposit_requests)
+            .await
+    }
+
+    async fn write_withdrawal_request(
+        &self,
+        request: &model::WithdrawalRequest,
+    ) -> Result<(), Error> {
+        PgWrite::write_withdrawal_request(self.get_connection().await?.as_mut(), request).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn write_deposit_signer_decision(
+        &self,
+        decision: &model::DepositSigner,
+    ) -> Result<(), Error> {
+        PgWrite::write_deposit_signer_decision(self.get_connection().await?.as_mut(), decision)
+            .await
+    }
+
+    async fn write_withdrawal_signer_decision(
+        &self,
+        decision: &model::WithdrawalSigner,
+    ) -> Result<(), Error> {
+        PgWrite::write_withdrawal_signer_decision(self.get_connection().await?.as_mut(), decision)
+            .await
+    }
+
+    async fn write_bitcoin_transaction(&self, tx_ref: &model::BitcoinTxRef) -> Result<(), Error> {
+        PgWrite::write_bitcoin_transaction(self.get_connection().await?.as_mut(), tx_ref).await
+    }
+
+    async fn write_bitcoin_transactions(&self, txs: Vec<model::BitcoinTxRef>) -> Result<(), Error> {
+        PgWrite::write_bitcoin_transactions(self.get_connection().await?.as_mut(), txs).await
+    }
+
+    async fn write_stacks_block_headers(
+        &self,
+        blocks: Vec<model::StacksBlock>,
+    ) -> Result<(), Error> {
+        PgWrite::write_stacks_block_headers(self.get_connection().await?.as_mut(), blocks).await
+    }
+
+    async fn write_encrypted_dkg_shares(
+        &self,
+        shares: &model::EncryptedDkgShares,
+    ) -> Result<(), Error> {
+        PgWrite::write_encrypted_dkg_shares(self.get_connection().await?.as_mut(), shares).await
+    }
+
+    async fn write_rotate_keys_transaction(
+        &self,
+        key_rotation: &model::KeyRotationEvent,
+    ) -> Result<(), Error> {
+        PgWrite::write_rotate_keys_transaction(self.get_connection().await?.as_mut(), key_rotation)
+            .await
+    }
+
+    async fn write_completed_deposit_event(
+        &self,
+        event: &CompletedDepositEvent,
+    ) -> Result<(), Error> {
+        PgWrite::write_completed_deposit_event(self.get_connection().await?.as_mut(), event).await
+    }
+
+    async fn write_sweep_completion(
+        &self,
+        completion: &model::SweepCompletion,
+    ) -> Result<(), Error> {
+        PgWrite::write_sweep_completion(self.get_connection().await?.as_mut(), completion).await
+    }
+
+    async fn write_withdrawal_accept_event(
+        &self,
+        event: &WithdrawalAcceptEvent,
+    ) -> Result<(), Error> {
+        PgWrite::write_withdrawal_accept_event(self.get_connection().await?.as_mut(), event).await
+    }
+
+    async fn write_withdrawal_reject_event(
+        &self,
+        event: &WithdrawalRejectEvent,
+    ) -> Result<(), Error> {
+        PgWrite::write_withdrawal_reject_event(self.get_connection().await?.as_mut(), event).await
+    }
+
+    async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
+        PgWrite::write_tx_output(self.get_connection().await?.as_mut(), output).await
+    }
+
+    async fn write_withdrawal_tx_output(
+        &self,
+        output: &model::WithdrawalTxOutput,
+    ) -> Result<(), Error> {
+        PgWrite::write_withdrawal_tx_output(self.get_connection().await?.as_mut(), output).await
+    }
+
+    async fn write_tx_prevout(&self, prevout: &model::TxPrevout) -> Result<(), Error> {
+        PgWrite::write_tx_prevout(self.get_connection().await?.as_mut(), prevout).await
+    }
+
+    async fn write_bitcoin_txs_sighashes(
+        &self,
+        sighashes: &[model::BitcoinTxSigHash],
+    ) -> Result<(), Error> {
+        PgWrite::write_bitcoin_txs_sighashes(self.get_connection().await?.as_mut(), sighashes).await
+    }
+
+    async fn write_bitcoin_withdrawals_outputs(
+        &self,
+        withdrawal_outputs: &[model::BitcoinWithdrawalOutput],
+    ) -> Result<(), Error> {
+        PgWrite::write_bitcoin_withdrawals_outputs(
+            self.get_connection().await?.as_mut(),
+            withdrawal_outputs,
+        )
+        .await
+    }
+
+    async fn revoke_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
+    where
+        X: Into<PublicKeyXOnly>,
+    {
+        PgWrite::revoke_dkg_shares(self.get_connection().await?.as_mut(), aggregate_key).await
+    }
+
+    async fn verify_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
     where
         X: Into<PublicKeyXOnly>,
     {
         PgWrite::verify_dkg_shares(self.get_connection().await?.as_mut(), aggregate_key).await
     }
 
-    async fn update_peer_connection(
+    async fn update_peer_connection(
+        &self,
+        pub_key: &PublicKey,
+        peer_id: &libp2p::PeerId,
+        address: libp2p::Multiaddr,
+    ) -> Result<(), Error> {
+        PgWrite::update_peer_connection(
+            self.get_connection().await?.as_mut(),
+            pub_key,
+            peer_id,
+            address,
+        )
+        .await
+    }
+
+    async fn write_peer_ban(&self, ban: &model::PeerBan) -> Result<(), Error> {
+        PgWrite::write_peer_ban(self.get_connection().await?.as_mut(), ban).await
+    }
+
+    async fn delete_peer_ban(&self, peer_id: &libp2p::PeerId) -> Result<bool, Error> {
+        PgWrite::delete_peer_ban(self.get_connection().await?.as_mut(), peer_id).await
+    }
+
+    async fn write_signer_identity_rotation(
+        &self,
+        rotation: &model::SignerIdentityRotation,
+    ) -> Result<(), Error> {
+        PgWrite::write_signer_identity_rotation(self.get_connection().await?.as_mut(), rotation)
+            .await
+    }
+
+    async fn write_deposit_request_intake(
+        &self,
+        intake: &model::DepositRequestIntake,
+    ) -> Result<(), Error> {
+        PgWrite::write_deposit_request_intake(self.get_connection().await?.as_mut(), intake).await
+    }
+
+    async fn write_request_failure(
+        &self,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> Result<model::RequestFailures, Error> {
+        PgWrite::write_request_failure(
+            self.get_connection().await?.as_mut(),
+            request,
+            error,
+            quarantine_threshold,
+        )
+        .await
+    }
+
+    async fn release_request_failures(
+        &self,
+        request: &model::SbtcRequestKey,
+    ) -> Result<bool, Error> {
+        PgWrite::release_request_failures(self.get_connection().await?.as_mut(), request).await
+    }
+
+    async fn write_stale_deposit_request(
+        &self,
+        stale: &model::StaleDepositRequest,
+    ) -> Result<(), Error> {
+        PgWrite::write_stale_deposit_request(self.get_connection().await?.as_mut(), stale).await
+    }
+
+    async fn mark_stale_deposit_request_synced(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<(), Error> {
+        PgWrite::mark_stale_deposit_request_synced(
+            self.get_connection().await?.as_mut(),
+            txid,
+            output_index,
+        )
+        .await
+    }
+
+    async fn write_registry_event_source(
+        &self,
+        source: &model::RegistryEventSource,
+    ) -> Result<(), Error> {
+        PgWrite::write_registry_event_source(self.get_connection().await?.as_mut(), source).await
+    }
+
+    async fn write_processed_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_processed_stacks_block(conn.as_mut(), block_hash).await
+    }
+
+    async fn write_stacks_block_failure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> Result<u32, Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_stacks_block_failure(conn.as_mut(), block_hash, error).await
+    }
+
+    async fn write_raw_stacks_event(
         &self,
-        pub_key: &PublicKey,
-        peer_id: &libp2p::PeerId,
-        address: libp2p::Multiaddr,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
     ) -> Result<(), Error> {
-        PgWrite::update_peer_connection(
-            self.get_connection().await?.as_mut(),
-            pub_key,
-            peer_id,
-            address,
-        )
-        .await
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_raw_stacks_event(conn.as_mut(), block_hash, body).await
+    }
+
+    async fn delete_raw_stacks_events_before(&self, before: model::Timestamp) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::delete_raw_stacks_events_before(conn.as_mut(), before).await
+    }
+
+    async fn write_emily_deposit_cursor(&self, last_update_height: u64) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_emily_deposit_cursor(conn.as_mut(), last_update_height).await
+    }
+
+    async fn write_analytics_export(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_analytics_export(conn.as_mut(), from, to).await
+    }
+
+    async fn write_stacks_node_burn_block(
+        &self,
+        block: &model::StacksNodeBurnBlock,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_stacks_node_burn_block(conn.as_mut(), block).await
+    }
+
+    async fn write_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_confirmation_watch(conn.as_mut(), watch).await
+    }
+
+    async fn delete_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<bool, Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::delete_confirmation_watch(conn.as_mut(), watch).await
+    }
+
+    async fn write_deposit_correlation_id(
+        &self,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_deposit_correlation_id(conn.as_mut(), correlation_id).await
+    }
+
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .pool()
+            .begin()
+            .await
+            .map_err(Error::SqlxBeginTransaction)?;
+
+        PgWrite::write_stacks_block_events(&mut *tx, events).await?;
+
+        tx.commit().await.map_err(Error::SqlxCommitTransaction)
+    }
+
+    async fn write_webhook_subscriber(&self, url: &str) -> Result<(), Error> {
+        PgWrite::write_webhook_subscriber(self.get_connection().await?.as_mut(), url).await
+    }
+
+    async fn delete_webhook_subscriber(&self, url: &str) -> Result<bool, Error> {
+        PgWrite::delete_webhook_subscriber(self.get_connection().await?.as_mut(), url).await
+    }
+
+    async fn write_row_integrity_hash(&self, hash: &model::RowIntegrityHash) -> Result<(), Error> {
+        PgWrite::write_row_integrity_hash(self.get_connection().await?.as_mut(), hash).await
+    }
+
+    async fn write_stacks_fee_spend(&self, spend: &model::StacksFeeSpend) -> Result<(), Error> {
+        PgWrite::write_stacks_fee_spend(self.get_connection().await?.as_mut(), spend).await
+    }
+
+    async fn write_wsts_round_message(
+        &self,
+        message: &model::WstsRoundMessage,
+    ) -> Result<(), Error> {
+        PgWrite::write_wsts_round_message(self.get_connection().await?.as_mut(), message).await
+    }
+
+    async fn delete_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::delete_wsts_round_messages(conn.as_mut(), bitcoin_chain_tip, message).await
+    }
+
+    async fn delete_stale_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::delete_stale_wsts_round_messages(conn.as_mut(), bitcoin_chain_tip).await
+    }
+
+    async fn write_signer_response_latency(
+        &self,
+        latency: &model::SignerResponseLatency,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_signer_response_latency(conn.as_mut(), latency).await
+    }
+
+    async fn delete_signer_response_latencies_before(
+        &self,
+        before: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::delete_signer_response_latencies_before(conn.as_mut(), before).await
+    }
+
+    async fn write_peg_wallet_tx_input(
+        &self,
+        input: &model::PegWalletTxInput,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_peg_wallet_tx_input(conn.as_mut(), input).await
+    }
+
+    async fn write_peg_wallet_tx_output(
+        &self,
+        output: &model::PegWalletTxOutput,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_peg_wallet_tx_output(conn.as_mut(), output).await
+    }
+
+    async fn write_bitcoin_internal_transfer(
+        &self,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_bitcoin_internal_transfer(conn.as_mut(), transfer).await
+    }
+
+    async fn write_quarantined_contract_event(
+        &self,
+        event: &model::QuarantinedContractEvent,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_quarantined_contract_event(conn.as_mut(), event).await
+    }
+
+    async fn write_stacks_block_tenure(
+        &self,
+        tenure: &model::StacksBlockTenure,
+    ) -> Result<(), Error> {
+        let mut conn = self.get_connection().await?;
+        PgWrite::write_stacks_block_tenure(conn.as_mut(), tenure).await
+    }
+
+    async fn write_crash_report(&self, report: &model::CrashReport) -> Result<(), Error> {
+        PgWrite::write_crash_report(self.get_connection().await?.as_mut(), report).await
     }
 }
 
@@ -1177,6 +2516,14 @@ impl DbWrite for PgTransaction<'_> {
         PgWrite::write_stacks_block(tx.as_mut(), block).await
     }
 
+    async fn write_bitcoin_block_activity(
+        &self,
+        activity: &model::BitcoinBlockActivity,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_bitcoin_block_activity(tx.as_mut(), activity).await
+    }
+
     async fn write_deposit_request(
         &self,
         deposit_request: &model::DepositRequest,
@@ -1278,6 +2625,14 @@ impl DbWrite for PgTransaction<'_> {
         PgWrite::write_completed_deposit_event(tx.as_mut(), event).await
     }
 
+    async fn write_sweep_completion(
+        &self,
+        completion: &model::SweepCompletion,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_sweep_completion(tx.as_mut(), completion).await
+    }
+
     async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
         let mut tx = self.tx.lock().await;
         PgWrite::write_tx_output(tx.as_mut(), output).await
@@ -1337,4 +2692,264 @@ impl DbWrite for PgTransaction<'_> {
         let mut tx = self.tx.lock().await;
         PgWrite::update_peer_connection(tx.as_mut(), pub_key, peer_id, address).await
     }
+
+    async fn write_peer_ban(&self, ban: &model::PeerBan) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_peer_ban(tx.as_mut(), ban).await
+    }
+
+    async fn delete_peer_ban(&self, peer_id: &libp2p::PeerId) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_peer_ban(tx.as_mut(), peer_id).await
+    }
+
+    async fn write_signer_identity_rotation(
+        &self,
+        rotation: &model::SignerIdentityRotation,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_signer_identity_rotation(tx.as_mut(), rotation).await
+    }
+
+    async fn write_deposit_request_intake(
+        &self,
+        intake: &model::DepositRequestIntake,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_deposit_request_intake(tx.as_mut(), intake).await
+    }
+
+    async fn write_request_failure(
+        &self,
+        request: &model::SbtcRequestKey,
+        error: &str,
+        quarantine_threshold: u32,
+    ) -> Result<model::RequestFailures, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_request_failure(tx.as_mut(), request, error, quarantine_threshold).await
+    }
+
+    async fn release_request_failures(
+        &self,
+        request: &model::SbtcRequestKey,
+    ) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::release_request_failures(tx.as_mut(), request).await
+    }
+
+    async fn write_stale_deposit_request(
+        &self,
+        stale: &model::StaleDepositRequest,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stale_deposit_request(tx.as_mut(), stale).await
+    }
+
+    async fn mark_stale_deposit_request_synced(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::mark_stale_deposit_request_synced(tx.as_mut(), txid, output_index).await
+    }
+
+    async fn write_registry_event_source(
+        &self,
+        source: &model::RegistryEventSource,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_registry_event_source(tx.as_mut(), source).await
+    }
+
+    async fn write_processed_stacks_block(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_processed_stacks_block(tx.as_mut(), block_hash).await
+    }
+
+    async fn write_stacks_block_failure(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        error: &str,
+    ) -> Result<u32, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stacks_block_failure(tx.as_mut(), block_hash, error).await
+    }
+
+    async fn write_raw_stacks_event(
+        &self,
+        block_hash: &model::StacksBlockHash,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_raw_stacks_event(tx.as_mut(), block_hash, body).await
+    }
+
+    async fn delete_raw_stacks_events_before(&self, before: model::Timestamp) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_raw_stacks_events_before(tx.as_mut(), before).await
+    }
+
+    async fn write_emily_deposit_cursor(&self, last_update_height: u64) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_emily_deposit_cursor(tx.as_mut(), last_update_height).await
+    }
+
+    async fn write_analytics_export(
+        &self,
+        from: model::Timestamp,
+        to: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_analytics_export(tx.as_mut(), from, to).await
+    }
+
+    async fn write_stacks_node_burn_block(
+        &self,
+        block: &model::StacksNodeBurnBlock,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stacks_node_burn_block(tx.as_mut(), block).await
+    }
+
+    async fn write_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_confirmation_watch(tx.as_mut(), watch).await
+    }
+
+    async fn delete_confirmation_watch(
+        &self,
+        watch: &model::ConfirmationWatch,
+    ) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_confirmation_watch(tx.as_mut(), watch).await
+    }
+
+    async fn write_deposit_correlation_id(
+        &self,
+        correlation_id: &model::DepositCorrelationId,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_deposit_correlation_id(tx.as_mut(), correlation_id).await
+    }
+
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stacks_block_events(tx.as_mut(), events).await
+    }
+
+    async fn write_webhook_subscriber(&self, url: &str) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_webhook_subscriber(tx.as_mut(), url).await
+    }
+
+    async fn delete_webhook_subscriber(&self, url: &str) -> Result<bool, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_webhook_subscriber(tx.as_mut(), url).await
+    }
+
+    async fn write_row_integrity_hash(&self, hash: &model::RowIntegrityHash) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_row_integrity_hash(tx.as_mut(), hash).await
+    }
+
+    async fn write_stacks_fee_spend(&self, spend: &model::StacksFeeSpend) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stacks_fee_spend(tx.as_mut(), spend).await
+    }
+
+    async fn write_wsts_round_message(
+        &self,
+        message: &model::WstsRoundMessage,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_wsts_round_message(tx.as_mut(), message).await
+    }
+
+    async fn delete_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        message: &[u8],
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_wsts_round_messages(tx.as_mut(), bitcoin_chain_tip, message).await
+    }
+
+    async fn delete_stale_wsts_round_messages(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_stale_wsts_round_messages(tx.as_mut(), bitcoin_chain_tip).await
+    }
+
+    async fn write_signer_response_latency(
+        &self,
+        latency: &model::SignerResponseLatency,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_signer_response_latency(tx.as_mut(), latency).await
+    }
+
+    async fn delete_signer_response_latencies_before(
+        &self,
+        before: model::Timestamp,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::delete_signer_response_latencies_before(tx.as_mut(), before).await
+    }
+
+    async fn write_peg_wallet_tx_input(
+        &self,
+        input: &model::PegWalletTxInput,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_peg_wallet_tx_input(tx.as_mut(), input).await
+    }
+
+    async fn write_peg_wallet_tx_output(
+        &self,
+        output: &model::PegWalletTxOutput,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_peg_wallet_tx_output(tx.as_mut(), output).await
+    }
+
+    async fn write_bitcoin_internal_transfer(
+        &self,
+        transfer: &model::BitcoinInternalTransfer,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_bitcoin_internal_transfer(tx.as_mut(), transfer).await
+    }
+
+    async fn write_quarantined_contract_event(
+        &self,
+        event: &model::QuarantinedContractEvent,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_quarantined_contract_event(tx.as_mut(), event).await
+    }
+
+    async fn write_stacks_block_tenure(
+        &self,
+        tenure: &model::StacksBlockTenure,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_stacks_block_tenure(tx.as_mut(), tenure).await
+    }
+
+    async fn write_crash_report(&self, report: &model::CrashReport) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_crash_report(tx.as_mut(), report).await
+    }
 }
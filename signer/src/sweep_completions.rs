@@ -0,0 +1,213 @@
+//! # Sweep completions
+//!
+//! Every deposit swept in by a sweep transaction is completed on stacks
+//! by minting sBTC, and every withdrawal paid out by one is completed by
+//! accepting the withdrawal request. The stacks transactions that do so
+//! emit completed-deposit and withdrawal-accept events, which reference
+//! the sweep, and each of them is recorded as a
+//! [`SweepCompletion`](crate::storage::model::SweepCompletion) linking the
+//! sweep to the stacks transaction.
+//!
+//! A sweep that is confirmed on bitcoin but never completed on stacks
+//! means that depositors did not get their sBTC, or that the sBTC of
+//! withdrawals was paid out without being burned. The coordinator should
+//! submit the completions in the tenure after the sweep is confirmed, so
+//! the [`SweepCompletionMonitor`] looks, whenever the block observer has
+//! processed a new bitcoin block, for sweeps that have been confirmed for
+//! more than
+//! [`sweep_completion_deadline`](crate::config::SignerConfig::sweep_completion_deadline)
+//! blocks without any recorded completion. It logs an error for each one
+//! and records the [`Metrics::UncompletedSweepTransactions`] metric.
+
+use std::collections::HashSet;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::context::BitcoinBlockObserved;
+use crate::context::Context;
+use crate::metrics::Metrics;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::BitcoinTxId;
+
+/// Flags sweep transactions that were confirmed on bitcoin without their
+/// requests being completed on stacks.
+pub struct SweepCompletionMonitor<C> {
+    /// Signer context.
+    context: C,
+    /// The uncompleted sweeps found in the most recent check, so that each
+    /// one is only flagged once.
+    uncompleted: HashSet<BitcoinTxId>,
+}
+
+impl<C> SweepCompletionMonitor<C>
+where
+    C: Context,
+{
+    /// Creates a new SweepCompletionMonitor with the given context.
+    pub fn new(context: C) -> Self {
+        Self {
+            context,
+            uncompleted: HashSet::new(),
+        }
+    }
+
+    /// Runs the SweepCompletionMonitor, which checks for uncompleted
+    /// sweeps whenever the block observer has processed a new bitcoin
+    /// block.
+    pub async fn run(mut self) {
+        let mut term = self.context.get_termination_handle();
+        let mut blocks = self.context.events().subscribe::<BitcoinBlockObserved>();
+        loop {
+            tokio::select! {
+                _ = term.wait_for_shutdown() => {
+                    break;
+                }
+                event = blocks.recv() => match event {
+                    Ok(BitcoinBlockObserved { chain_tip }) => self.check(&chain_tip).await,
+                    Err(RecvError::Lagged(_)) => {
+                        if let Some(chain_tip) = self.context.state().bitcoin_chain_tip() {
+                            self.check(&chain_tip).await;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        tracing::info!("sweep completion monitor has stopped");
+    }
+
+    /// Look for sweeps on the blockchain of the given chain tip whose
+    /// completion deadline has passed, and flag the ones that were not
+    /// flagged at the previous check.
+    #[tracing::instrument(
+        skip_all,
+        name = "sweep-completion-monitor",
+        fields(chain_tip = %chain_tip.block_hash)
+    )]
+    pub async fn check(&mut self, chain_tip: &BitcoinBlockRef) {
+        let config = &self.context.config().signer;
+        let deadline = config.sweep_completion_deadline.get();
+        let uncompleted = match self
+            .context
+            .get_storage()
+            .get_uncompleted_sweep_transactions(chain_tip, deadline, config.context_window)
+            .await
+        {
+            Ok(uncompleted) => uncompleted,
+            Err(error) => {
+                tracing::warn!(%error, "could not look up uncompleted sweep transactions");
+                return;
+            }
+        };
+
+        let current: HashSet<BitcoinTxId> = uncompleted.iter().map(|sweep| sweep.txid).collect();
+        Metrics::record_uncompleted_sweeps(current.len());
+
+        for txid in self.uncompleted.difference(&current) {
+            tracing::info!(%txid, "previously uncompleted sweep transaction is no longer flagged");
+        }
+
+        for sweep in uncompleted {
+            if self.uncompleted.contains(&sweep.txid) {
+                continue;
+            }
+            tracing::error!(
+                txid = %sweep.txid,
+                block_hash = %sweep.block_hash,
+                block_height = %sweep.block_height,
+                deadline,
+                "sweep transaction was never completed on stacks before its deadline"
+            );
+        }
+
+        self.uncompleted = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::TestContext;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    /// A sweep that fulfilled a deposit is flagged once it has been
+    /// confirmed for longer than the deadline, until a completion that
+    /// references it is recorded.
+    #[tokio::test]
+    async fn sweeps_without_completions_are_flagged() {
+        let ctx = TestContext::default_mocked();
+        let db = ctx.get_storage_mut();
+        let mut rng = get_rng();
+        let deadline = u64::from(ctx.config().signer.sweep_completion_deadline.get());
+
+        let mut blocks = vec![model::BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: 0u64.into(),
+            parent_hash: Faker.fake_with_rng(&mut rng),
+        }];
+        for height in 1..=deadline + 1 {
+            let parent = blocks.last().unwrap();
+            blocks.push(model::BitcoinBlock {
+                block_hash: Faker.fake_with_rng(&mut rng),
+                block_height: height.into(),
+                parent_hash: parent.block_hash,
+            });
+        }
+        for block in &blocks {
+            db.write_bitcoin_block(block).await.unwrap();
+        }
+
+        // A sweep of a deposit, and a sweep that only moves the signers'
+        // UTXO, both confirmed in the block after the genesis block.
+        let sweep_block = &blocks[1];
+        let txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+        let consolidation: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+        for (txid, prevout_type) in [
+            (txid, model::TxPrevoutType::SignersInput),
+            (txid, model::TxPrevoutType::Deposit),
+            (consolidation, model::TxPrevoutType::SignersInput),
+        ] {
+            let prevout = model::TxPrevout {
+                txid,
+                prevout_type,
+                ..Faker.fake_with_rng(&mut rng)
+            };
+            db.write_tx_prevout(&prevout).await.unwrap();
+        }
+        for txid in [txid, consolidation] {
+            let tx_ref = model::BitcoinTxRef {
+                txid,
+                block_hash: sweep_block.block_hash,
+            };
+            db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        }
+
+        // The deadline has not passed at the block before the last one.
+        let mut monitor = SweepCompletionMonitor::new(ctx.clone());
+        let before_deadline = &blocks[blocks.len() - 2];
+        monitor.check(&before_deadline.into()).await;
+        assert!(monitor.uncompleted.is_empty());
+
+        let chain_tip = blocks.last().unwrap();
+        monitor.check(&chain_tip.into()).await;
+        assert_eq!(monitor.uncompleted, HashSet::from([txid]));
+
+        let completion = model::SweepCompletion {
+            sweep_txid: txid,
+            sweep_block_hash: sweep_block.block_hash,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_sweep_completion(&completion).await.unwrap();
+        assert_eq!(db.get_sweep_completions(&txid).await.unwrap(), [completion]);
+
+        monitor.check(&chain_tip.into()).await;
+        assert!(monitor.uncompleted.is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+//! Testing helpers for the handlers of the signer API
+
+use axum::body::Body;
+use axum::http::Method;
+use axum::http::Request;
+use axum::http::header;
+use time::OffsetDateTime;
+
+use crate::api::ADMIN_SIGNATURE_HEADER;
+use crate::api::ADMIN_TIMESTAMP_HEADER;
+use crate::api::sign_admin_request;
+
+/// The secret that the admin endpoints of the contexts made with
+/// `with_admin_secret` accept.
+pub const ADMIN_SECRET: &str = "a-shared-secret-for-admin-requests";
+
+/// Build a request to an admin endpoint of the signer API, signed with
+/// [`ADMIN_SECRET`] at the current time, with the given JSON body, if any.
+pub fn admin_request(method: Method, uri: &str, body: Option<serde_json::Value>) -> Request<Body> {
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp() as u64;
+    let content_type = body.as_ref().map(|_| "application/json");
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    let signature = sign_admin_request(ADMIN_SECRET, &method, uri, timestamp, body.as_bytes());
+
+    let mut builder = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(ADMIN_TIMESTAMP_HEADER, timestamp)
+        .header(ADMIN_SIGNATURE_HEADER, signature);
+    if let Some(content_type) = content_type {
+        builder = builder.header(header::CONTENT_TYPE, content_type);
+    }
+    builder.body(Body::from(body)).unwrap()
+}
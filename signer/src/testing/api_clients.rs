@@ -15,6 +15,14 @@ impl TryFrom<&[Url]> for MockBitcoinInteract {
     }
 }
 
+impl TryFrom<&Settings> for MockBitcoinInteract {
+    type Error = Error;
+
+    fn try_from(_: &Settings) -> Result<Self, Self::Error> {
+        Ok(Self::default())
+    }
+}
+
 impl TryFrom<&Settings> for MockStacksInteract {
     type Error = Error;
 
@@ -19,6 +19,7 @@ use blockstack_lib::types::chainstate::StacksAddress;
 use blockstack_lib::types::chainstate::StacksBlockId;
 use clarity::types::chainstate::BurnchainHeaderHash;
 use clarity::types::chainstate::SortitionId;
+use clarity::vm::Value;
 use emily_client::models::DepositStatus;
 use rand::seq::IteratorRandom as _;
 use sbtc::deposits::CreateDepositRequest;
@@ -33,10 +34,12 @@ use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::bitcoin::rpc::GetTxResponse;
 use crate::bitcoin::utxo;
 use crate::context::SbtcLimits;
+use crate::emily_client::EmilyDepositRequest;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::stacks::api::AccountInfo;
+use crate::stacks::api::ClarityName;
 use crate::stacks::api::FeePriority;
 use crate::stacks::api::GetNodeInfoResponse;
 use crate::stacks::api::SignerSetInfo;
@@ -44,6 +47,7 @@ use crate::stacks::api::StacksEpochStatus;
 use crate::stacks::api::StacksInteract;
 use crate::stacks::api::SubmitTxResponse;
 use crate::stacks::api::TenureBlocks;
+use crate::stacks::contracts::SmartContract;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHeight;
@@ -308,6 +312,12 @@ impl BitcoinInteract for TestHarness {
     async fn get_best_block_hash(&self) -> Result<BlockHash, Error> {
         unimplemented!()
     }
+
+    async fn get_index_info(
+        &self,
+    ) -> Result<std::collections::BTreeMap<String, crate::bitcoin::rpc::IndexInfo>, Error> {
+        unimplemented!()
+    }
 }
 
 impl StacksInteract for TestHarness {
@@ -334,6 +344,16 @@ impl StacksInteract for TestHarness {
     async fn is_withdrawal_completed(&self, _: &StacksAddress, _: u64) -> Result<bool, Error> {
         unimplemented!()
     }
+    async fn call_read(
+        &self,
+        _: &StacksAddress,
+        _: SmartContract,
+        _: ClarityName,
+        _: &StacksAddress,
+        _: &[Value],
+    ) -> Result<Value, Error> {
+        unimplemented!()
+    }
     async fn get_account(&self, _address: &StacksAddress) -> Result<AccountInfo, Error> {
         // issue #118
         todo!()
@@ -507,6 +527,28 @@ impl EmilyInteract for TestHarness {
         }
     }
 
+    async fn get_deposits_updated_since(
+        &self,
+        last_update_height: u64,
+    ) -> Result<Vec<EmilyDepositRequest>, Error> {
+        // The test deposits are never updated, so they are all considered
+        // to have been last updated at height zero. Their correlation IDs
+        // are their outpoints.
+        if last_update_height > 0 {
+            return Ok(Vec::new());
+        }
+        let deposits = self
+            .pending_deposits
+            .iter()
+            .map(|request| EmilyDepositRequest {
+                request: request.clone(),
+                last_update_height: 0,
+                correlation_id: Some(request.outpoint.to_string()),
+            })
+            .collect();
+        Ok(deposits)
+    }
+
     async fn update_deposits(
         &self,
         _update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -517,6 +559,7 @@ impl EmilyInteract for TestHarness {
     async fn accept_deposits<'a>(
         &'a self,
         _transaction: &'a utxo::UnsignedTransaction<'a>,
+        _correlation_ids: &'a HashMap<bitcoin::OutPoint, String>,
     ) -> Result<emily_client::models::UpdateDepositsResponse, Error> {
         unimplemented!()
     }
@@ -72,6 +72,7 @@ impl utxo::DepositRequest {
             deposit_script: self.deposit_script.to_hex_string(),
             reclaim_script: self.reclaim_script.to_hex_string(),
             transaction_hex: serialize_hex(tx),
+            correlation_id: None,
         }
     }
 }
@@ -1,5 +1,7 @@
 //! Test Context implementation
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::time::Duration;
 use std::{ops::Deref, sync::Arc};
 
@@ -14,28 +16,34 @@ use blockstack_lib::{
     },
 };
 use clarity::types::chainstate::{StacksAddress, StacksBlockId};
+use clarity::vm::Value;
 use emily_client::models::DepositStatus;
 use tokio::sync::{Mutex, broadcast};
 use tokio::time::error::Elapsed;
 
 use crate::bitcoin::GetTransactionFeeResult;
 use crate::bitcoin::rpc::{BitcoinBlockHeader, BitcoinBlockInfo};
+use crate::context::ContextRng;
+use crate::context::EventBus;
 use crate::context::SbtcLimits;
 use crate::keys::PrivateKey;
+use crate::stacks::api::ClarityName;
 use crate::stacks::api::GetNodeInfoResponse;
 use crate::stacks::api::SignerSetInfo;
 use crate::stacks::api::StacksEpochStatus;
 use crate::stacks::api::TenureBlocks;
+use crate::stacks::contracts::SmartContract;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::Transactable;
 use crate::storage::model::BitcoinTxId;
+use crate::testing::api::ADMIN_SECRET;
 use crate::{
     bitcoin::{
         BitcoinInteract, MockBitcoinInteract, rpc::GetTxResponse, utxo::UnsignedTransaction,
     },
     config::Settings,
     context::{Context, SignerContext, SignerSignal, SignerState, TerminationHandle},
-    emily_client::{EmilyInteract, MockEmilyInteract},
+    emily_client::{EmilyDepositRequest, EmilyInteract, MockEmilyInteract},
     error::Error,
     keys::PublicKey,
     stacks::{
@@ -89,13 +97,22 @@ where
     Emily: EmilyInteract + Clone + Send + Sync + 'static,
 {
     /// Create a new test context.
+    ///
+    /// The random number generator of the context is seeded with
+    /// [`get_seed`](crate::testing::get_seed) unless the settings already
+    /// have a seed.
     pub fn new(
-        settings: Settings,
+        mut settings: Settings,
         storage: Storage,
         bitcoin_client: Bitcoin,
         stacks_client: Stacks,
         emily_client: Emily,
     ) -> Self {
+        settings
+            .signer
+            .rng_seed
+            .get_or_insert_with(crate::testing::get_seed);
+
         let context = SignerContext::new(
             settings,
             storage.clone(),
@@ -178,6 +195,22 @@ impl TestContext<(), (), (), ()> {
             .with_mocked_clients()
             .build()
     }
+
+    /// Creates a new [`TestContext`] like [`TestContext::default_mocked`],
+    /// whose admin endpoints accept the requests made with
+    /// [`crate::testing::api::admin_request`].
+    pub fn with_admin_secret() -> TestContext<
+        SharedStore,
+        WrappedMockBitcoinInteract,
+        WrappedMockStacksInteract,
+        WrappedMockEmilyInteract,
+    > {
+        Self::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .with_admin_secret()
+            .build()
+    }
 }
 
 impl<Storage, Bitcoin, Stacks, Emily> Deref for TestContext<Storage, Bitcoin, Stacks, Emily> {
@@ -267,6 +300,10 @@ where
         self.inner.state()
     }
 
+    fn events(&self) -> &EventBus {
+        self.inner.events()
+    }
+
     fn get_signal_receiver(&self) -> broadcast::Receiver<SignerSignal> {
         self.inner.get_signal_receiver()
     }
@@ -304,16 +341,26 @@ where
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static {
         self.inner.get_emily_client()
     }
+
+    fn rng(&self) -> ContextRng {
+        self.inner.rng()
+    }
 }
 
 /// A wrapper around a mock which can be cloned and shared between threads.
 pub struct WrappedMock<T> {
     inner: Arc<Mutex<T>>,
+    /// The outputs that a wrapped bitcoin client reports as spent. All
+    /// other outputs are reported as unspent.
+    spent_outputs: Arc<std::sync::Mutex<HashSet<bitcoin::OutPoint>>>,
 }
 
 impl<T> Clone for WrappedMock<T> {
     fn clone(&self) -> Self {
-        Self { inner: self.inner.clone() }
+        Self {
+            inner: self.inner.clone(),
+            spent_outputs: self.spent_outputs.clone(),
+        }
     }
 }
 
@@ -322,10 +369,19 @@ impl<T> WrappedMock<T> {
     pub fn new(mock: T) -> Self {
         Self {
             inner: Arc::new(Mutex::new(mock)),
+            spent_outputs: Arc::new(std::sync::Mutex::new(HashSet::new())),
         }
     }
 }
 
+impl WrappedMockBitcoinInteract {
+    /// Have the wrapped bitcoin client report the given output as spent
+    /// from now on.
+    pub fn spend_output(&self, outpoint: bitcoin::OutPoint) {
+        self.spent_outputs.lock().unwrap().insert(outpoint);
+    }
+}
+
 impl<T> Deref for WrappedMock<T> {
     type Target = Mutex<T>;
 
@@ -393,10 +449,30 @@ impl BitcoinInteract for WrappedMockBitcoinInteract {
 
     async fn get_transaction_output(
         &self,
-        _outpoint: &bitcoin::OutPoint,
+        outpoint: &bitcoin::OutPoint,
         _include_mempool: bool,
     ) -> Result<Option<GetTxOutResult>, Error> {
-        unimplemented!()
+        // Outputs are unspent unless a test spent them with
+        // `WrappedMockBitcoinInteract::spend_output`, so that the tests
+        // which do not care about reclaimed deposits need not set
+        // expectations for them.
+        if self.spent_outputs.lock().unwrap().contains(outpoint) {
+            return Ok(None);
+        }
+        let txout = serde_json::json!({
+            "bestblock": "0000000000000000000000000000000000000000000000000000000000000000",
+            "confirmations": 1,
+            "value": 1.0,
+            "scriptPubKey": {
+                "asm": "",
+                "hex": "",
+                "type": "nonstandard",
+            },
+            "coinbase": false,
+        });
+        Ok(Some(
+            serde_json::from_value(txout).expect("valid gettxout response"),
+        ))
     }
 
     async fn get_transaction_fee(
@@ -427,6 +503,12 @@ impl BitcoinInteract for WrappedMockBitcoinInteract {
     async fn get_best_block_hash(&self) -> Result<bitcoin::BlockHash, Error> {
         self.inner.lock().await.get_best_block_hash().await
     }
+
+    async fn get_index_info(
+        &self,
+    ) -> Result<std::collections::BTreeMap<String, crate::bitcoin::rpc::IndexInfo>, Error> {
+        self.inner.lock().await.get_index_info().await
+    }
 }
 
 impl StacksInteract for WrappedMockStacksInteract {
@@ -476,6 +558,27 @@ impl StacksInteract for WrappedMockStacksInteract {
             .await
     }
 
+    async fn call_read(
+        &self,
+        contract_principal: &StacksAddress,
+        contract_name: SmartContract,
+        fn_name: ClarityName,
+        sender: &StacksAddress,
+        arguments: &[Value],
+    ) -> Result<Value, Error> {
+        self.inner
+            .lock()
+            .await
+            .call_read(
+                contract_principal,
+                contract_name,
+                fn_name,
+                sender,
+                arguments,
+            )
+            .await
+    }
+
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
         self.inner.lock().await.get_account(address).await
     }
@@ -576,6 +679,17 @@ impl EmilyInteract for WrappedMockEmilyInteract {
             .await
     }
 
+    async fn get_deposits_updated_since(
+        &self,
+        last_update_height: u64,
+    ) -> Result<Vec<EmilyDepositRequest>, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_deposits_updated_since(last_update_height)
+            .await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -590,8 +704,13 @@ impl EmilyInteract for WrappedMockEmilyInteract {
     async fn accept_deposits<'a>(
         &'a self,
         transaction: &'a UnsignedTransaction<'a>,
+        correlation_ids: &'a HashMap<bitcoin::OutPoint, String>,
     ) -> Result<emily_client::models::UpdateDepositsResponse, Error> {
-        self.inner.lock().await.accept_deposits(transaction).await
+        self.inner
+            .lock()
+            .await
+            .accept_deposits(transaction, correlation_ids)
+            .await
     }
 
     async fn accept_withdrawals<'a>(
@@ -707,6 +826,15 @@ where
             settings.signer.private_key = private_key;
         })
     }
+
+    /// Helper for configuring the context's settings so that the admin
+    /// endpoints of the signer API accept the requests made with
+    /// [`crate::testing::api::admin_request`].
+    fn with_admin_secret(self) -> ContextBuilder<Storage, Bitcoin, Stacks, Emily> {
+        self.modify_settings(|settings| {
+            settings.signer.api.admin_secret = Some(ADMIN_SECRET.to_string());
+        })
+    }
 }
 
 impl<Storage, Bitcoin, Stacks, Emily> ConfigureSettings<Storage, Bitcoin, Stacks, Emily>
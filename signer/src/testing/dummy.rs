@@ -48,6 +48,7 @@ use wsts::net::SignatureType;
 use wsts::traits::PartyState;
 use wsts::traits::SignerState;
 
+use crate::amount::SbtcAmount;
 use crate::bitcoin::rpc::BitcoinBlockInfo;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::bitcoin::rpc::BitcoinTxVin;
@@ -65,6 +66,7 @@ use crate::keys::PublicKeyXOnly;
 use crate::keys::SignerScriptPubKey as _;
 use crate::message::BitcoinPreSignAck;
 use crate::message::BitcoinPreSignRequest;
+use crate::message::SignerIdentityRotation;
 use crate::message::SignerMessage;
 use crate::stacks::contracts::AcceptWithdrawalV1;
 use crate::stacks::contracts::CompleteDepositV1;
@@ -437,7 +439,7 @@ impl fake::Dummy<fake::Faker> for WithdrawalAcceptEvent {
                 txid: txid(config, rng),
                 vout: rng.next_u32(),
             },
-            fee: rng.next_u32() as u64,
+            fee: Amount::from_sat(rng.next_u32() as u64),
             sweep_block_hash: config.fake_with_rng(rng),
             sweep_block_height: rng.next_u32().into(),
             sweep_txid: config.fake_with_rng(rng),
@@ -466,7 +468,7 @@ impl fake::Dummy<fake::Faker> for CompletedDepositEvent {
                 txid: txid(config, rng),
                 vout: rng.next_u32(),
             },
-            amount: rng.next_u32() as u64,
+            amount: SbtcAmount::new(rng.next_u32() as u64),
             sweep_block_hash: config.fake_with_rng(rng),
             sweep_block_height: rng.next_u32().into(),
             sweep_txid: config.fake_with_rng(rng),
@@ -545,6 +547,7 @@ impl fake::Dummy<&[PublicKey]> for SignerBtcState {
             fee_rate: Faker.fake_with_rng(rng),
             last_fees: Faker.fake_with_rng(rng),
             magic_bytes: [1, 2],
+            lock_time: bitcoin::absolute::LockTime::from_consensus(Faker.fake_with_rng(rng)),
             public_key: aggregate_key_x_only,
             utxo: SignerUtxo {
                 amount: Faker.fake_with_rng(rng),
@@ -766,6 +769,13 @@ impl fake::Dummy<fake::Faker> for BitcoinPreSignRequest {
             request_package: fake::vec![TxRequestIds; 0..20],
             fee_rate: config.fake_with_rng(rng),
             last_fees: config.fake_with_rng(rng),
+            lock_time: bitcoin::absolute::LockTime::from_consensus(config.fake_with_rng(rng)),
+            donations: std::iter::repeat_with(|| OutPoint {
+                txid: txid(config, rng),
+                vout: rng.next_u32(),
+            })
+            .take((0..5).fake_with_rng(rng))
+            .collect(),
         }
     }
 }
@@ -776,6 +786,14 @@ impl fake::Dummy<fake::Faker> for BitcoinPreSignAck {
     }
 }
 
+impl fake::Dummy<fake::Faker> for SignerIdentityRotation {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let old_public_key: PublicKey = config.fake_with_rng(rng);
+        let new_private_key = PrivateKey::new(rng);
+        SignerIdentityRotation::new(old_public_key, &new_private_key)
+    }
+}
+
 impl fake::Dummy<fake::Faker> for model::Timestamp {
     fn dummy_with_rng<R: rand::RngCore + ?Sized>(_: &fake::Faker, rng: &mut R) -> Self {
         // The PostgreSQL epoch is 2000-01-01 00:00:00 UTC
@@ -50,6 +50,9 @@ impl fake::Dummy<fake::Faker> for message::Payload {
             dummy_payload::<message::StacksTransactionSignature, _>,
             dummy_payload::<message::WstsMessage, _>,
             dummy_payload::<message::BitcoinPreSignRequest, _>,
+            dummy_payload::<message::SignerIdentityRotation, _>,
+            dummy_payload::<message::SignerHeartbeat, _>,
+            dummy_payload::<message::CoordinatorAnnouncement, _>,
         ];
         variants.choose(rng).unwrap()(config, rng)
     }
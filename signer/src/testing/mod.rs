@@ -2,6 +2,7 @@
 
 #![allow(clippy::unwrap_in_result, clippy::unwrap_used, clippy::expect_used)]
 
+pub mod api;
 pub mod api_clients;
 pub mod block_observer;
 pub mod blocks;
@@ -159,15 +160,37 @@ where
 
 impl<I, T> IterTestExt<T> for I where I: IntoIterator<Item = T> + Sized {}
 
-/// Returns a seedable rng with random seed. Prints the seed to
-/// stderr so that it can be used to reproduce the test
-pub fn get_rng() -> StdRng {
-    let seed = OsRng.next_u64();
+/// The environment variable with the seed to use for the random number
+/// generators in tests. It deliberately does not start with `SIGNER_`, so
+/// that [`clear_env`] leaves it in place and [`Settings`] ignores it.
+pub const TEST_SEED_ENV_VAR: &str = "SBTC_TEST_SEED";
+
+/// Returns the seed for the random number generators of a test, which is
+/// taken from the [`TEST_SEED_ENV_VAR`] environment variable if it is set
+/// and is random otherwise. Prints the seed to stderr so that it can be
+/// used to reproduce the test.
+///
+/// # Panics
+///
+/// Panics if the environment variable is set but is not a valid `u64`.
+pub fn get_seed() -> u64 {
+    let seed = match std::env::var(TEST_SEED_ENV_VAR) {
+        Ok(seed) => seed
+            .parse()
+            .unwrap_or_else(|_| panic!("{TEST_SEED_ENV_VAR} must be a u64, got {seed:?}")),
+        Err(_) => OsRng.next_u64(),
+    };
 
     // Nextest prints stderr only for failing tests, so this message
     // will only appear if the test fails (by default).
-    eprintln!("Test executed with seed: {seed}");
-    StdRng::seed_from_u64(seed)
+    eprintln!("Test executed with seed: {seed} (set {TEST_SEED_ENV_VAR} to reproduce)");
+    seed
+}
+
+/// Returns a seedable rng seeded with [`get_seed`], so that it can be
+/// used to reproduce the test.
+pub fn get_rng() -> StdRng {
+    StdRng::seed_from_u64(get_seed())
 }
 
 /// A wrapper type used by `join_all` to ensure that the results are processed.
@@ -31,17 +31,17 @@ impl MultiaddrExt for Multiaddr {}
 pub async fn assert_clients_can_exchange_messages<C: network::MessageTransfer + Send + 'static>(
     client_1: C,
     client_2: C,
-    private_key_1: PrivateKey,
-    private_key_2: PrivateKey,
+    private_key_1: &PrivateKey,
+    private_key_2: &PrivateKey,
 ) {
     let mut rng = get_rng();
     let number_of_messages = 32;
 
     let client_1_messages: Vec<_> = (0..number_of_messages)
-        .map(|_| network::Msg::random_with_private_key(&mut rng, &private_key_1))
+        .map(|_| network::Msg::random_with_private_key(&mut rng, private_key_1))
         .collect();
     let client_2_messages: Vec<_> = (0..number_of_messages)
-        .map(|_| network::Msg::random_with_private_key(&mut rng, &private_key_2))
+        .map(|_| network::Msg::random_with_private_key(&mut rng, private_key_2))
         .collect();
 
     let handle_1 = spawn_client_task(
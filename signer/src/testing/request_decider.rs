@@ -168,7 +168,7 @@ where
             self.context_window,
             self.deposit_decisions_retry_window,
             self.withdrawal_decisions_retry_window,
-            coordinator_signer_info.signer_private_key,
+            coordinator_signer_info.signer_private_key.clone(),
         );
 
         let handle = event_loop_harness.start();
@@ -265,7 +265,7 @@ where
             self.context_window,
             self.deposit_decisions_retry_window,
             self.withdrawal_decisions_retry_window,
-            coordinator_signer_info.signer_private_key,
+            coordinator_signer_info.signer_private_key.clone(),
         );
 
         let handle = event_loop_harness.start();
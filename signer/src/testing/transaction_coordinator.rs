@@ -93,6 +93,7 @@ pub fn select_coordinator(
         .find(|info| PublicKey::from_private_key(&info.signer_private_key) == coordinator_pub_key)
         .expect("couldn't find coordinator from public key")
         .signer_private_key
+        .clone()
 }
 
 struct TxCoordinatorEventLoopHarness<C> {
@@ -415,11 +416,14 @@ where
 
         self.context
             .with_emily_client(|client| {
-                client.expect_accept_deposits().times(1..).returning(|_| {
-                    Box::pin(async {
-                        Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
-                    })
-                });
+                client
+                    .expect_accept_deposits()
+                    .times(1..)
+                    .returning(|_, _| {
+                        Box::pin(async {
+                            Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
+                        })
+                    });
             })
             .await;
 
@@ -573,11 +577,14 @@ where
 
         self.context
             .with_emily_client(|client| {
-                client.expect_accept_deposits().times(1..).returning(|_| {
-                    Box::pin(async {
-                        Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
-                    })
-                });
+                client
+                    .expect_accept_deposits()
+                    .times(1..)
+                    .returning(|_, _| {
+                        Box::pin(async {
+                            Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
+                        })
+                    });
             })
             .await;
 
@@ -775,7 +782,7 @@ where
         };
 
         let withdrawal_fee = sweep_tx_info
-            .assess_output_fee(output_index as usize)
+            .assess_withdrawal_fee(output_index as usize)
             .unwrap()
             .to_sat();
 
@@ -1421,6 +1428,40 @@ where
                 public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
             }
         );
+
+        // The donations available for topping up fees only include the
+        // donation outputs on the given chain.
+        let donations = storage
+            .get_donation_utxos(&block_a1.block_hash, &aggregate_key)
+            .await
+            .unwrap();
+        assert!(donations.is_empty());
+
+        let donations = storage
+            .get_donation_utxos(&block_a2.block_hash, &aggregate_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            donations,
+            vec![SignerUtxo {
+                outpoint: bitcoin::OutPoint::new(tx_a2.compute_txid(), 0),
+                amount: 0xA2,
+                public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
+            }]
+        );
+
+        let donations = storage
+            .get_donation_utxos(&block_b1.block_hash, &aggregate_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            donations,
+            vec![SignerUtxo {
+                outpoint: bitcoin::OutPoint::new(tx_b1.compute_txid(), 0),
+                amount: 0xB1,
+                public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
+            }]
+        );
     }
 
     async fn prepare_database_and_run_dkg<Rng>(
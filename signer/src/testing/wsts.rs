@@ -116,7 +116,7 @@ impl Coordinator {
             num_keys,
             threshold,
             dkg_threshold,
-            message_private_key: signer_info.signer_private_key.into(),
+            message_private_key: (&message_private_key).into(),
             dkg_public_timeout: None,
             dkg_private_timeout: None,
             dkg_end_timeout: None,
@@ -240,7 +240,7 @@ impl Signer {
             signer_info.signer_public_keys,
             threshold,
             created_at,
-            signer_info.signer_private_key,
+            signer_info.signer_private_key.clone(),
         )
         .expect("failed to construct state machine");
 
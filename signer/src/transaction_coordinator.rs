@@ -6,33 +6,45 @@
 //! For more details, see the [`TxCoordinatorEventLoop`] documentation.
 
 use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::time::Duration;
 
+use bitcoin::OutPoint;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
 use futures::Stream;
 use futures::StreamExt as _;
 use futures::future::try_join_all;
 use sha2::Digest as _;
 
+use crate::MAX_DONATION_INPUTS_PER_SWEEP;
 use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_DUST_LIMIT;
 use crate::WITHDRAWAL_EXPIRY_BUFFER;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
+use crate::amount::SbtcAmount;
 use crate::bitcoin::BitcoinInteract as _;
 use crate::bitcoin::TransactionLookupHint;
+use crate::bitcoin::cap_reservation;
+use crate::bitcoin::queue::PendingQueue;
 use crate::bitcoin::utxo;
 use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::utxo::RequestRef;
 use crate::bitcoin::utxo::UnsignedMockTransaction;
+use crate::codec::Decode as _;
+use crate::codec::Encode as _;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::RequestDeciderEvent;
+use crate::context::RoundCompleted;
+use crate::context::RoundKind;
 use crate::context::SbtcLimits;
 use crate::context::SignerCommand;
 use crate::context::SignerEvent;
 use crate::context::SignerSignal;
 use crate::context::TxCoordinatorEvent;
 use crate::context::TxSignerEvent;
+use crate::dry_run::DryRunPackage;
 use crate::ecdsa::SignEcdsa as _;
 use crate::ecdsa::Signed;
 use crate::emily_client::EmilyInteract as _;
@@ -41,6 +53,7 @@ use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::message;
 use crate::message::BitcoinPreSignRequest;
+use crate::message::CoordinatorAnnouncement;
 use crate::message::Payload;
 use crate::message::SignerMessage;
 use crate::message::StacksTransactionSignRequest;
@@ -48,7 +61,11 @@ use crate::message::WstsMessageId;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
 use crate::metrics::Metrics;
 use crate::metrics::STACKS_BLOCKCHAIN;
+use crate::metrics::reason_label;
 use crate::network;
+use crate::response_latency;
+use crate::response_latency::ResponseKind;
+use crate::response_latency::record_response_latency;
 use crate::signature::TaprootSignature;
 use crate::stacks::api::FeePriority;
 use crate::stacks::api::RejectionReason;
@@ -64,9 +81,13 @@ use crate::stacks::contracts::RejectWithdrawalV1;
 use crate::stacks::contracts::RotateKeysV1;
 use crate::stacks::contracts::SMART_CONTRACTS;
 use crate::stacks::contracts::SmartContract;
+use crate::stacks::contracts::withdrawal_request_deployer;
+use crate::stacks::fee_budget;
+use crate::stacks::registry::RegistryContractClient;
 use crate::stacks::wallet::MultisigTx;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::DbRead;
+use crate::storage::DbWrite as _;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockRef;
 use crate::storage::model::StacksTxId;
@@ -157,7 +178,8 @@ pub struct TxCoordinatorEventLoop<Context, Network> {
     pub context: Context,
     /// Interface to the signer network.
     pub network: Network,
-    /// Private key of the coordinator for network communication.
+    /// Private key of the slot of the coordinator in the signer set, used
+    /// for network communication.
     pub private_key: PrivateKey,
     /// the number of signatures required.
     pub threshold: u16,
@@ -179,6 +201,17 @@ pub struct TxCoordinatorEventLoop<Context, Network> {
     pub is_epoch3: bool,
 }
 
+/// The state of a signing round after replaying the messages that were
+/// collected for it before it was interrupted.
+#[derive(Debug)]
+enum ResumedSigningRound {
+    /// The collected messages were enough to complete the round.
+    Complete(WstsOperationResult),
+    /// The round is still in progress. This holds the last packet that
+    /// the coordinator state machine produced during the replay, if any.
+    InProgress(Option<wsts::net::Packet>),
+}
+
 /// The parameters for the [`TxCoordinatorEventLoop::get_pending_requests`] function.
 #[derive(Debug)]
 pub struct GetPendingRequestsParams<'a> {
@@ -196,6 +229,18 @@ pub struct GetPendingRequestsParams<'a> {
     pub signature_threshold: u16,
 }
 
+/// A stacks transaction that has been constructed, and is waiting to be
+/// signed along with other transactions.
+#[derive(Debug)]
+struct PendingStacksTx {
+    /// The deposit or withdrawal request that the transaction completes.
+    request: model::SbtcRequestKey,
+    /// The request that is sent to the signers.
+    sign_request: StacksTransactionSignRequest,
+    /// The transaction that collects the signatures of the signers.
+    multi_tx: MultisigTx,
+}
+
 /// This function defines which messages this event loop is interested
 /// in.
 fn run_loop_message_filter(signal: &SignerSignal) -> bool {
@@ -239,6 +284,7 @@ where
                     if let Err(error) = self.process_new_blocks(chain_tip).await {
                         tracing::error!(%error, "error processing requests; skipping this round");
                     }
+                    self.context.state().end_coordinator_tenure();
                     tracing::trace!("sending tenure completed signal");
                     self.context
                         .signal(TxCoordinatorEvent::TenureCompleted.into())?;
@@ -305,6 +351,15 @@ where
             return Ok(());
         }
 
+        // Signing rounds are tied to the chain tip that they started at,
+        // so the messages collected for rounds at other chain tips can
+        // never be used to resume them.
+        self.context
+            .get_storage_mut()
+            .delete_stale_wsts_round_messages(&bitcoin_chain_tip.block_hash)
+            .await?;
+        response_latency::prune_response_latencies(&self.context).await?;
+
         // If we are not the coordinator, then we have no business
         // coordinating DKG or constructing bitcoin and stacks
         // transactions, might as well return early.
@@ -313,6 +368,30 @@ where
             return Ok(());
         }
 
+        // Acting on inconsistent views of the bitcoin and stacks
+        // blockchains leads to transactions that the other signers will
+        // reject, so we hold back until the views agree again.
+        if !self.context.state().chain_views_consistent() {
+            tracing::warn!(
+                "our bitcoin and stacks chain views are inconsistent; skipping coordination"
+            );
+            return Ok(());
+        }
+
+        // Likewise while the bitcoin node is bootstrapping, since it cannot
+        // tell us about our UTXO or the requests that we would sweep.
+        if !self.context.state().bitcoin_node_ready() {
+            tracing::info!("the bitcoin node is warming up; skipping coordination");
+            return Ok(());
+        }
+
+        // The tenure goes uncoordinated, just as it would if we were
+        // offline, which the other signers already handle.
+        if self.context.state().maintenance_mode() {
+            tracing::info!("we are in maintenance mode; skipping coordination");
+            return Ok(());
+        }
+
         let bitcoin_processing_delay = self.context.config().signer.bitcoin_processing_delay;
         if bitcoin_processing_delay > Duration::ZERO {
             tracing::debug!("sleeping before processing new bitcoin block");
@@ -345,9 +424,30 @@ where
         tracing::debug!("we are the coordinator");
         metrics::counter!(Metrics::CoordinatorTenuresTotal).increment(1);
 
+        // A signer with a different view of the bitcoin chain tip may
+        // also believe that it is the coordinator, so we announce our
+        // tenure to let the two of us notice and settle the conflict.
+        let announcement = CoordinatorAnnouncement::from(&bitcoin_chain_tip);
+        self.context
+            .state()
+            .begin_coordinator_tenure(self.signer_public_key(), announcement);
+        if let Err(error) = self
+            .send_message(announcement, &bitcoin_chain_tip.block_hash)
+            .await
+        {
+            tracing::warn!(%error, "could not announce our coordinator tenure");
+        }
+
+        // A dry-run tenure only rehearses the sweep of pending requests,
+        // so it leaves DKG, contract deployments and key rotations alone.
+        let dry_run = self.context.state().take_dry_run_tenure();
+        if dry_run {
+            tracing::info!("running this tenure in dry-run mode");
+        }
+
         tracing::debug!("determining if we need to coordinate DKG");
         let should_coordinate_dkg =
-            should_coordinate_dkg(&self.context, &bitcoin_chain_tip).await?;
+            !dry_run && should_coordinate_dkg(&self.context, &bitcoin_chain_tip).await?;
         let aggregate_key = if should_coordinate_dkg {
             match self.coordinate_dkg(&bitcoin_chain_tip).await {
                 Ok(key) => key,
@@ -384,12 +484,33 @@ where
         let wallet = self.get_signer_wallet().await?;
 
         if !self.context.state().sbtc_contracts_deployed() {
+            if dry_run {
+                tracing::info!("the sBTC contracts are not deployed; nothing to dry run");
+                return Ok(());
+            }
             self.deploy_smart_contracts(chain_tip_hash, &wallet, &aggregate_key)
                 .await?;
 
             return Ok(());
         }
 
+        if dry_run {
+            let signer_public_keys = maybe_registry_signer_set_info
+                .map(|info| info.signer_set)
+                .ok_or_else(|| Error::NoKeyRotationEvent)?;
+            let bitcoin_processing_fut = self.construct_and_sign_bitcoin_sbtc_transactions(
+                &bitcoin_chain_tip,
+                &aggregate_key,
+                &signer_public_keys,
+                true,
+            );
+            if let Err(error) = bitcoin_processing_fut.await {
+                tracing::error!(%error, "dry run of the bitcoin transactions failed");
+            }
+            tracing::info!("dry-run tenure completed; no signatures were requested");
+            return Ok(());
+        }
+
         let rotate_key_txid = self.check_and_submit_rotate_key_transaction(
             &bitcoin_chain_tip,
             &wallet,
@@ -416,12 +537,15 @@ where
             &bitcoin_chain_tip,
             &aggregate_key,
             &signer_public_keys,
+            false,
         );
 
         if let Err(error) = bitcoin_processing_fut.await {
             tracing::error!(%error, "failed to construct and sign bitcoin transactions");
         }
 
+        self.wait_for_stacks_tenure(&bitcoin_chain_tip).await;
+
         self.construct_and_sign_stacks_response_transactions(
             &bitcoin_chain_tip,
             &wallet,
@@ -524,6 +648,7 @@ where
         bitcoin_chain_tip: &model::BitcoinBlockHash,
         signer_btc_state: &utxo::SignerBtcState,
         transaction_package: &[utxo::UnsignedTransaction<'_>],
+        acks_required: u16,
     ) -> Result<(), Error> {
         // Constructing a pre-sign request with empty request IDs is
         // invalid. The other signers should reject the message if we send
@@ -540,6 +665,13 @@ where
                 .collect(),
             fee_rate: signer_btc_state.fee_rate,
             last_fees: signer_btc_state.last_fees,
+            lock_time: signer_btc_state.lock_time,
+            // Only the first transaction in the package spends donations.
+            donations: transaction_package[0]
+                .donations
+                .iter()
+                .map(|donation| donation.outpoint)
+                .collect(),
         };
 
         let presign_ack_filter = |event: &SignerSignal| {
@@ -557,13 +689,15 @@ where
         // Send the presign request message
         tracing::debug!(request = %sbtc_requests, "sending pre-sign request");
         self.send_message(sbtc_requests, bitcoin_chain_tip).await?;
+        let sent_at = std::time::Instant::now();
+        let mut latencies = Vec::new();
 
         tokio::pin!(signal_stream);
         let future = async {
             let target_tip = *bitcoin_chain_tip;
             let mut acknowledged_signers = HashSet::new();
 
-            while acknowledged_signers.len() < self.threshold as usize {
+            while acknowledged_signers.len() < acks_required as usize {
                 match signal_stream.next().await {
                     None => {
                         tracing::warn!("signer signal stream closed unexpectedly, shutting down");
@@ -585,7 +719,9 @@ where
                             ..
                         }) => {
                             if bitcoin_chain_tip == target_tip {
-                                acknowledged_signers.insert(signer_public_key);
+                                if acknowledged_signers.insert(signer_public_key) {
+                                    latencies.push((signer_public_key, sent_at.elapsed()));
+                                }
                             } else {
                                 tracing::warn!(
                                     signer = %signer_public_key,
@@ -633,11 +769,25 @@ where
         )
         .increment(1);
 
+        for (signer_public_key, latency) in latencies {
+            let kind = ResponseKind::PreSignAck;
+            let _ = record_response_latency(&self.context, signer_public_key, kind, latency)
+                .await
+                .inspect_err(|error| {
+                    tracing::warn!(%error, "could not record the latency of a pre-sign ack");
+                });
+        }
+
         res?
     }
 
     /// Construct and coordinate WSTS signing rounds for sBTC transactions on Bitcoin,
     /// fulfilling pending deposit and withdraw requests.
+    ///
+    /// When `dry_run` is true we stop once the other signers have
+    /// validated the transactions, before any signatures are requested,
+    /// and record the package that would have been signed in the signer
+    /// state.
     #[tracing::instrument(skip_all, fields(
         stacks_tip_hash = tracing::field::Empty,
         stacks_tip_height = tracing::field::Empty,
@@ -647,6 +797,7 @@ where
         bitcoin_chain_tip: &model::BitcoinBlockRef,
         aggregate_key: &PublicKey,
         signer_public_keys: &BTreeSet<PublicKey>,
+        dry_run: bool,
     ) -> Result<(), Error> {
         let storage = self.context.get_storage();
 
@@ -669,10 +820,20 @@ where
             signer_public_keys,
         );
 
+        // Record our view of the pending requests for the `/queue`
+        // endpoint of the API before doing anything with them.
+        let pending_requests = pending_requests_fut.await?;
+        let queue = PendingQueue::new(bitcoin_chain_tip, pending_requests.as_ref());
+        self.context.state().set_pending_queue(queue);
+
         // If `get_pending_requests()` returns `Ok(None)` then there are no
         // eligible requests to service; we can exit early.
-        let Some(pending_requests) = pending_requests_fut.await? else {
+        let Some(pending_requests) = pending_requests else {
             tracing::debug!("no requests to handle on bitcoin");
+            if dry_run {
+                let package = DryRunPackage::new(bitcoin_chain_tip, None, &[], None);
+                self.context.state().set_last_dry_run_package(package);
+            }
             return Ok(());
         };
 
@@ -685,14 +846,57 @@ where
         // Construct the transaction package and store it in the database.
         let transaction_package = pending_requests.construct_transactions()?;
 
+        // Refuse to go any further with absurd fees, whatever the fee
+        // rate estimate said. The signers would refuse to sign them too.
+        let fee_limits = self.context.config().signer.sweep_fee_limits();
+        let fee_check = transaction_package
+            .iter()
+            .try_for_each(|transaction| fee_limits.check(transaction));
+
+        // Large sweep packages are high-impact actions, and may need more
+        // signers to acknowledge them than ordinary ones.
+        let swept_amount = transaction_package
+            .iter()
+            .map(utxo::UnsignedTransaction::swept_amount)
+            .sum();
+        let thresholds = self.context.config().signer.quorum_thresholds();
+        let acks_required = thresholds.signatures_required(
+            thresholds.sweep_impact(swept_amount),
+            self.threshold,
+            pending_requests.num_signers,
+        );
+
         // Send the pre-sign request to the signers and wait for their
         // acknowledgments.
-        self.construct_and_send_bitcoin_presign_request(
-            bitcoin_chain_tip.as_ref(),
-            &pending_requests.signer_state,
-            &transaction_package,
-        )
-        .await?;
+        let presign = match fee_check {
+            Ok(()) => {
+                self.construct_and_send_bitcoin_presign_request(
+                    bitcoin_chain_tip.as_ref(),
+                    &pending_requests.signer_state,
+                    &transaction_package,
+                    acks_required,
+                )
+                .await
+            }
+            Err(error) => Err(error),
+        };
+
+        if dry_run {
+            let package = DryRunPackage::new(
+                bitcoin_chain_tip,
+                Some(&pending_requests.signer_state),
+                &transaction_package,
+                presign.as_ref().err(),
+            );
+            tracing::info!(
+                num_transactions = package.transactions.len(),
+                validated = presign.is_ok(),
+                "dry run: stopping before requesting signatures"
+            );
+            self.context.state().set_last_dry_run_package(package);
+            return presign;
+        }
+        presign?;
 
         // Construct, sign and broadcast the bitcoin transactions.
         for mut transaction in transaction_package {
@@ -702,10 +906,11 @@ where
             // TODO: if this (considering also fallback clients) fails, we will
             // need to handle the inconsistency of having the sweep tx confirmed
             // but emily deposit still marked as pending.
+            let correlation_ids = self.deposit_correlation_ids(&transaction).await?;
             let _ = self
                 .context
                 .get_emily_client()
-                .accept_deposits(&transaction)
+                .accept_deposits(&transaction, &correlation_ids)
                 .await
                 .inspect_err(|error| {
                     tracing::warn!(%error, "could not accept deposits on Emily");
@@ -724,6 +929,35 @@ where
         Ok(())
     }
 
+    /// Get the correlation IDs that Emily gave for the deposit requests
+    /// swept by the given transaction, keyed by their outpoints.
+    async fn deposit_correlation_ids(
+        &self,
+        transaction: &utxo::UnsignedTransaction<'_>,
+    ) -> Result<HashMap<OutPoint, String>, Error> {
+        let db = self.context.get_storage();
+        let mut correlation_ids = HashMap::new();
+        let deposits = transaction
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_deposit);
+        for deposit in deposits {
+            let txid: model::BitcoinTxId = deposit.outpoint.txid.into();
+            let correlation_id = db
+                .get_deposit_correlation_id(&txid, deposit.outpoint.vout)
+                .await?;
+            if let Some(correlation_id) = correlation_id {
+                tracing::debug!(
+                    outpoint = %deposit.outpoint,
+                    %correlation_id,
+                    "sweeping deposit request"
+                );
+                correlation_ids.insert(deposit.outpoint, correlation_id);
+            }
+        }
+        Ok(correlation_ids)
+    }
+
     /// Construct and coordinate signing rounds for `deposit-accept`,
     /// `withdraw-accept` and `withdraw-reject` transactions.
     ///
@@ -779,8 +1013,8 @@ where
         bitcoin_aggregate_key: &PublicKey,
     ) -> Result<(), Error> {
         let db = self.context.get_storage();
-        let stacks = self.context.get_stacks_client();
         let deployer = self.context.config().signer.deployer.clone();
+        let registry = RegistryContractClient::new(self.context.get_stacks_client(), deployer);
 
         // Fetch deposit requests from the database where
         // there has been a confirmed bitcoin transaction associated with
@@ -804,15 +1038,20 @@ where
             "we have deposit requests that may need a response on stacks"
         );
 
+        let quarantined = self.get_quarantined_requests().await?;
+
         for req in swept_deposits {
-            if self.context.state().bitcoin_chain_tip().as_ref() != Some(chain_tip) {
-                tracing::info!("new bitcoin chain tip, stopping coordinator activities");
+            if self.tenure_interrupted(chain_tip) {
                 return Ok(());
             }
 
             let outpoint = req.deposit_outpoint();
+            if quarantined.contains(&model::SbtcRequestKey::from(outpoint)) {
+                tracing::debug!(%outpoint, "skipping quarantined deposit request");
+                continue;
+            }
 
-            let is_completed = stacks.is_deposit_completed(&deployer, &outpoint).await;
+            let is_completed = registry.get_deposit_status(&outpoint).await;
             match is_completed {
                 Err(error) => {
                     tracing::warn!(%error, %outpoint, "could not check deposit status");
@@ -832,6 +1071,7 @@ where
                 Ok(res) => res,
                 Err(error) => {
                     tracing::error!(%error, "could not construct a transaction completing the deposit request");
+                    self.record_request_failure(outpoint.into(), &error).await;
                     continue;
                 }
             };
@@ -844,15 +1084,16 @@ where
             let process_request_fut =
                 self.process_sign_request(sign_request, chain_tip.as_ref(), multi_tx, wallet);
 
-            let status = match process_request_fut.await {
+            let (status, reason) = match process_request_fut.await {
                 Ok(txid) => {
                     tracing::info!(%txid, "successfully submitted complete-deposit transaction");
-                    "success"
+                    ("success", "none")
                 }
                 Err(error) => {
                     tracing::warn!(%error, %outpoint, "could not process the stacks sign request for a deposit");
                     adjust_nonce(wallet, &error);
-                    "failure"
+                    self.record_request_failure(outpoint.into(), &error).await;
+                    ("failure", error.reason())
                 }
             };
 
@@ -860,6 +1101,7 @@ where
                 Metrics::TransactionsSubmittedTotal,
                 "blockchain" => STACKS_BLOCKCHAIN,
                 "status" => status,
+                "reason" => reason,
                 "kind" => "complete-deposit"
             )
             .increment(1);
@@ -904,13 +1146,21 @@ where
             "we have withdrawals requests that may need completion"
         );
 
+        let quarantined = self.get_quarantined_requests().await?;
+
         for swept_request in swept_withdrawals {
-            if self.context.state().bitcoin_chain_tip().as_ref() != Some(chain_tip) {
-                tracing::info!("new bitcoin chain tip, stopping coordinator activities");
+            if self.tenure_interrupted(chain_tip) {
                 return Ok(());
             }
 
             let withdrawal_id = swept_request.qualified_id();
+            let request = model::SbtcRequestKey::Withdrawal {
+                request_id: withdrawal_id.request_id,
+            };
+            if quarantined.contains(&request) {
+                tracing::debug!(%withdrawal_id, "skipping quarantined withdrawal request");
+                continue;
+            }
             let fut = self.construct_and_sign_withdrawal_accept(
                 chain_tip,
                 wallet,
@@ -924,29 +1174,179 @@ where
                     %withdrawal_id,
                     "could not construct and sign withdrawal accept"
                 );
+                self.record_request_failure(request, &error).await;
             }
         }
 
+        // Withdrawal rejections are pipelined: we construct all of them
+        // with sequential nonces, ask the signers to sign all of them at
+        // once, and then submit the signed transactions in nonce order.
+        let max_rejects = self
+            .context
+            .config()
+            .signer
+            .max_withdrawal_rejects_per_tenure
+            .get() as usize;
+        let mut pending_rejects = Vec::new();
+        let mut batch_fees = 0u64;
+
         for withdrawal in rejected_withdrawals {
-            if self.context.state().bitcoin_chain_tip().as_ref() != Some(chain_tip) {
-                tracing::info!("new bitcoin chain tip, stopping coordinator activities");
+            if self.tenure_interrupted(chain_tip) {
+                // None of the transactions will be submitted, so their
+                // nonces are free for the next coordinator tenure.
+                if let Some(first) = pending_rejects.first() {
+                    wallet.set_nonce(first.sign_request.nonce);
+                }
                 return Ok(());
             }
 
             let withdrawal_id = withdrawal.qualified_id();
-            let fut = self.construct_and_sign_withdrawal_reject(
+            let request = model::SbtcRequestKey::Withdrawal {
+                request_id: withdrawal_id.request_id,
+            };
+            if quarantined.contains(&request) {
+                tracing::debug!(%withdrawal_id, "skipping quarantined withdrawal request");
+                continue;
+            }
+            if pending_rejects.len() >= max_rejects {
+                tracing::debug!(
+                    %max_rejects,
+                    "reached the withdrawal reject cap for this tenure; deferring the rest"
+                );
+                break;
+            }
+
+            let fut = self.construct_withdrawal_reject(
                 chain_tip,
                 wallet,
                 bitcoin_aggregate_key,
                 withdrawal,
             );
-            if let Err(error) = fut.await {
-                tracing::warn!(
-                    %error,
-                    %withdrawal_id,
-                    "could not construct and sign withdrawal reject"
-                );
+            let (sign_request, multi_tx) = match fut.await {
+                Ok(Some(prepared)) => prepared,
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %withdrawal_id,
+                        "could not construct withdrawal reject"
+                    );
+                    self.record_request_failure(request, &error).await;
+                    continue;
+                }
+            };
+
+            // There is no point in a signing round for a transaction that
+            // we will not submit, and the budget must cover the fees of
+            // the whole batch.
+            let budget_check = fee_budget::check_stacks_fee_budget(
+                &self.context,
+                sign_request.tx_kind(),
+                batch_fees.saturating_add(sign_request.tx_fee),
+            );
+            if let Err(error) = budget_check.await {
+                tracing::warn!(%error, %withdrawal_id, "not signing withdrawal reject");
+                wallet.set_nonce(sign_request.nonce);
+                break;
             }
+            batch_fees = batch_fees.saturating_add(sign_request.tx_fee);
+
+            pending_rejects.push(PendingStacksTx {
+                request,
+                sign_request,
+                multi_tx,
+            });
+        }
+
+        self.sign_and_submit_withdrawal_rejects(chain_tip, wallet, pending_rejects)
+            .await
+    }
+
+    /// Ask the signers to sign all the given withdrawal reject
+    /// transactions at once, and submit the signed transactions to the
+    /// stacks node in nonce order.
+    ///
+    /// The transactions must have sequential nonces, in the order given.
+    /// Submission stops at the first transaction that could not be signed
+    /// or submitted, since the stacks node would not mine any transaction
+    /// with a higher nonce, and the wallet nonce is reset to the nonce of
+    /// that transaction.
+    #[tracing::instrument(skip_all, fields(num_rejects = pending.len()))]
+    async fn sign_and_submit_withdrawal_rejects(
+        &mut self,
+        chain_tip: &model::BitcoinBlockRef,
+        wallet: &SignerWallet,
+        pending: Vec<PendingStacksTx>,
+    ) -> Result<(), Error> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let requests: Vec<_> = pending
+            .iter()
+            .map(|tx| {
+                let req = &tx.sign_request;
+                (tx.request, req.nonce, req.tx_kind(), req.tx_fee)
+            })
+            .collect();
+        let sign_requests = pending
+            .into_iter()
+            .map(|tx| (tx.sign_request, tx.multi_tx))
+            .collect();
+        let signed_txs = self
+            .sign_stacks_transactions(sign_requests, &chain_tip.block_hash, wallet)
+            .await?;
+
+        let mut remaining = requests.into_iter().zip(signed_txs);
+        for ((request, nonce, kind, tx_fee), signed_tx) in remaining.by_ref() {
+            let submitted = match signed_tx {
+                Ok(tx) => self.submit_stacks_transaction(&tx, kind, tx_fee).await,
+                Err(error) => Err(error),
+            };
+
+            let status = if submitted.is_ok() {
+                "success"
+            } else {
+                "failure"
+            };
+            metrics::counter!(
+                Metrics::TransactionsSubmittedTotal,
+                "blockchain" => STACKS_BLOCKCHAIN,
+                "status" => status,
+                "reason" => reason_label(&submitted),
+                "kind" => "complete-withdrawal-reject",
+            )
+            .increment(1);
+
+            match submitted {
+                Ok(txid) => {
+                    tracing::info!(%txid, %request, "successfully submitted withdrawal reject transaction");
+                }
+                // Another transaction with this nonce is in the mempool,
+                // so the transactions with higher nonces can still be
+                // mined.
+                Err(
+                    error @ Error::StacksTxRejection(TxRejection {
+                        reason: RejectionReason::ConflictingNonceInMempool,
+                        ..
+                    }),
+                ) => {
+                    tracing::warn!(%error, %request, "could not submit withdrawal reject");
+                }
+                // This is not a fatal error, since we could fail to sign
+                // the transaction because someone else is now the
+                // coordinator, and all the signers are now ignoring us.
+                Err(error) => {
+                    tracing::warn!(%error, %request, "could not sign or submit withdrawal reject");
+                    wallet.set_nonce(nonce);
+                    break;
+                }
+            }
+        }
+
+        let skipped = remaining.count();
+        if skipped > 0 {
+            tracing::info!(%skipped, "deferred withdrawal rejects to a later tenure");
         }
 
         Ok(())
@@ -961,7 +1361,12 @@ where
         request: model::SweptWithdrawalRequest,
     ) -> Result<(), Error> {
         let stacks = self.context.get_stacks_client();
-        let deployer = self.context.config().signer.deployer.clone();
+        let deployer = withdrawal_request_deployer(
+            &self.context.get_storage(),
+            &request.qualified_id(),
+            &self.context.config().signer.deployer,
+        )
+        .await?;
 
         let is_completed = stacks
             .is_withdrawal_completed(&deployer, request.request_id)
@@ -992,15 +1397,15 @@ where
 
         tracing::debug!("processed withdrawal request");
 
-        let status = match process_request_fut.await {
+        let (status, reason) = match process_request_fut.await {
             Ok(txid) => {
                 tracing::info!(%txid, "successfully submitted accept-withdrawal transaction");
-                "success"
+                ("success", "none")
             }
             Err(error) => {
                 tracing::warn!(%error, "could not process the stacks sign request for a withdrawal");
                 adjust_nonce(wallet, &error);
-                "failure"
+                ("failure", error.reason())
             }
         };
 
@@ -1008,6 +1413,7 @@ where
             Metrics::TransactionsSubmittedTotal,
             "blockchain" => STACKS_BLOCKCHAIN,
             "status" => status,
+            "reason" => reason,
             "kind" => "complete-withdrawal-accept",
         )
         .increment(1);
@@ -1017,17 +1423,21 @@ where
         Ok(())
     }
 
+    /// Construct the withdrawal reject transaction for the given request,
+    /// returning `None` if the request should not be rejected right now.
     #[tracing::instrument(skip_all, fields(withdrawal_id = %request.qualified_id()))]
-    async fn construct_and_sign_withdrawal_reject(
-        &mut self,
+    async fn construct_withdrawal_reject(
+        &self,
         chain_tip: &model::BitcoinBlockRef,
         wallet: &SignerWallet,
         bitcoin_aggregate_key: &PublicKey,
         request: model::WithdrawalRequest,
-    ) -> Result<(), Error> {
+    ) -> Result<Option<(StacksTransactionSignRequest, MultisigTx)>, Error> {
         let db = self.context.get_storage();
         let stacks = self.context.get_stacks_client();
-        let deployer = self.context.config().signer.deployer.clone();
+        let default_deployer = &self.context.config().signer.deployer;
+        let deployer =
+            withdrawal_request_deployer(&db, &request.qualified_id(), default_deployer).await?;
 
         let is_completed = stacks
             .is_withdrawal_completed(&deployer, request.request_id)
@@ -1035,7 +1445,7 @@ where
 
         if is_completed {
             // The request is already completed according to the contract
-            return Ok(());
+            return Ok(None);
         }
 
         // The `DbRead::is_withdrawal_inflight` function considers whether
@@ -1047,7 +1457,7 @@ where
             .is_withdrawal_inflight(&qualified_id, &chain_tip.block_hash)
             .await?;
         if withdrawal_inflight {
-            return Ok(());
+            return Ok(None);
         }
 
         // The `DbRead::is_withdrawal_active` function considers whether
@@ -1058,46 +1468,16 @@ where
             .await?;
 
         if withdrawal_is_active {
-            return Ok(());
+            return Ok(None);
         }
 
-        let sign_request_fut = self.construct_withdrawal_reject_stacks_sign_request(
+        self.construct_withdrawal_reject_stacks_sign_request(
             &request,
             bitcoin_aggregate_key,
             wallet,
-        );
-
-        let (sign_request, multi_tx) = sign_request_fut.await?;
-
-        // If we fail to sign the transaction for some reason, we adjust the
-        // nonce and try the next transaction.
-        // This is not a fatal error, since we could fail to sign the
-        // transaction because someone else is now the coordinator, and
-        // all the signers are now ignoring us.
-        let process_request_fut =
-            self.process_sign_request(sign_request, chain_tip.as_ref(), multi_tx, wallet);
-
-        let status = match process_request_fut.await {
-            Ok(txid) => {
-                tracing::info!(%txid, "successfully submitted withdrawal reject transaction");
-                "success"
-            }
-            Err(error) => {
-                tracing::warn!(%error, "could not process the stacks sign request for a withdrawal reject");
-                adjust_nonce(wallet, &error);
-                "failure"
-            }
-        };
-
-        metrics::counter!(
-            Metrics::TransactionsSubmittedTotal,
-            "blockchain" => STACKS_BLOCKCHAIN,
-            "status" => status,
-            "kind" => "complete-withdrawal-reject",
         )
-        .increment(1);
-
-        Ok(())
+        .await
+        .map(Some)
     }
 
     /// Performs verification of the DKG process by running a FROST signing
@@ -1128,7 +1508,7 @@ where
         let mut frost_coordinator = FrostCoordinator::load(
             &self.context.get_storage(),
             aggregate_key.into(),
-            self.private_key,
+            self.private_key.clone(),
         )
         .await?;
 
@@ -1225,13 +1605,20 @@ where
         wallet: &SignerWallet,
     ) -> Result<StacksTxId, Error> {
         let kind = sign_request.tx_kind();
+        let tx_fee = sign_request.tx_fee;
+
+        // There is no point in a signing round for a transaction that we
+        // will not submit.
+        fee_budget::check_stacks_fee_budget(&self.context, kind, tx_fee).await?;
 
         let instant = std::time::Instant::now();
+        self.begin_round(RoundKind::StacksSigning);
         let tx = self
             .sign_stacks_transaction(sign_request, multi_tx, chain_tip, wallet)
             .await;
 
         let status = if tx.is_ok() { "success" } else { "failure" };
+        self.publish_round_completed(RoundKind::StacksSigning, chain_tip, tx.is_ok());
 
         metrics::histogram!(
             Metrics::SigningRoundDurationSeconds,
@@ -1248,11 +1635,28 @@ where
         )
         .increment(1);
 
-        // Submit the transaction to the Stacks node
-        let submit_tx_result = self.context.get_stacks_client().submit_tx(&tx?).await;
+        self.submit_stacks_transaction(&tx?, kind, tx_fee).await
+    }
+
+    /// Submit the signed transaction to the stacks node, recording its fee
+    /// against the stacks fee budget once it has been accepted.
+    async fn submit_stacks_transaction(
+        &self,
+        tx: &StacksTransaction,
+        kind: &'static str,
+        tx_fee: u64,
+    ) -> Result<StacksTxId, Error> {
+        let submit_tx_result = self.context.get_stacks_client().submit_tx(tx).await;
 
         match submit_tx_result {
-            Ok(SubmitTxResponse::Acceptance(txid)) => Ok(txid),
+            Ok(SubmitTxResponse::Acceptance(txid)) => {
+                let _ = fee_budget::record_stacks_fee_spend(&self.context, txid, kind, tx_fee)
+                    .await
+                    .inspect_err(|error| {
+                        tracing::warn!(%error, %txid, "could not record the stacks transaction fee");
+                    });
+                Ok(txid)
+            }
             Ok(SubmitTxResponse::Rejection(err)) => Err(err.into()),
             Err(err) => Err(err),
         }
@@ -1288,10 +1692,14 @@ where
             .assess_input_fee(&outpoint)
             .ok_or_else(|| Error::OutPointMissing(outpoint))?;
 
+        // The amount of sBTC to mint is the deposited amount less the
+        // fee assessed to the deposit.
+        let amount = SbtcAmount::new(req.amount).try_sub(assessed_bitcoin_fee.into())?;
+
         // TODO: we should validate the contract call before asking others
         // to sign it.
         let complete_deposit_v1 = CompleteDepositV1 {
-            amount: req.amount - assessed_bitcoin_fee.to_sat(),
+            amount: amount.get(),
             outpoint,
             recipient: req.recipient.into(),
             deployer: self.context.config().signer.deployer.clone(),
@@ -1346,9 +1754,15 @@ where
 
         let outpoint = req.withdrawal_outpoint();
         let qualified_id = req.qualified_id();
+        let deployer = withdrawal_request_deployer(
+            &self.context.get_storage(),
+            &qualified_id,
+            &self.context.config().signer.deployer,
+        )
+        .await?;
 
         let assessed_bitcoin_fee = tx_info
-            .assess_output_fee(outpoint.vout as usize)
+            .assess_withdrawal_fee(outpoint.vout as usize)
             .ok_or_else(|| Error::VoutMissing(outpoint.txid, outpoint.vout))?;
 
         let accept_withdrawal_v1 = AcceptWithdrawalV1 {
@@ -1356,7 +1770,7 @@ where
             outpoint,
             tx_fee: assessed_bitcoin_fee.to_sat(),
             signer_bitmap: 0,
-            deployer: self.context.config().signer.deployer.clone(),
+            deployer,
             sweep_block_hash: req.sweep_block_hash,
             sweep_block_height: req.sweep_block_height,
         };
@@ -1389,11 +1803,14 @@ where
         bitcoin_aggregate_key: &PublicKey,
         wallet: &SignerWallet,
     ) -> Result<(StacksTransactionSignRequest, MultisigTx), Error> {
-        let reject_withdrawal_v1 = RejectWithdrawalV1 {
-            id: req.qualified_id(),
-            signer_bitmap: 0,
-            deployer: self.context.config().signer.deployer.clone(),
-        };
+        let id = req.qualified_id();
+        let deployer = withdrawal_request_deployer(
+            &self.context.get_storage(),
+            &id,
+            &self.context.config().signer.deployer,
+        )
+        .await?;
+        let reject_withdrawal_v1 = RejectWithdrawalV1 { id, signer_bitmap: 0, deployer };
         let contract_call = ContractCall::RejectWithdrawalV1(Box::new(reject_withdrawal_v1));
 
         // Estimate the fee for the stacks transaction
@@ -1425,6 +1842,7 @@ where
         wallet: &SignerWallet,
     ) -> Result<StacksTransaction, Error> {
         let txid = req.txid;
+        let impact = req.impact();
 
         let signal_stream = self
             .context
@@ -1439,8 +1857,18 @@ where
 
         let max_duration = self.signing_round_max_duration;
 
+        // High-impact transactions may need more signers to approve them
+        // than the wallet requires.
+        let num_signers = u16::try_from(wallet.public_keys().len()).unwrap_or(u16::MAX);
+        let signatures_required = self
+            .context
+            .config()
+            .signer
+            .quorum_thresholds()
+            .signatures_required(impact, wallet.signatures_required(), num_signers);
+
         let future = async {
-            while multi_tx.num_signatures() < wallet.signatures_required() {
+            while multi_tx.num_signatures() < signatures_required {
                 // If signal_stream.next() returns None then one of the
                 // underlying streams has closed. That means either the
                 // network stream, the internal message stream, or the
@@ -1475,12 +1903,139 @@ where
                 }
             }
 
-            Ok::<_, Error>(multi_tx.finalize_transaction())
-        };
+            multi_tx.retain_signatures(wallet.signatures_required());
+            Ok::<_, Error>(multi_tx.finalize_transaction())
+        };
+
+        tokio::time::timeout(max_duration, future)
+            .await
+            .map_err(|_| Error::SignatureTimeout(txid))?
+    }
+
+    /// Ask the signers to sign all the given stacks transactions at once,
+    /// returning the signed transactions in the order given.
+    ///
+    /// All sign requests are sent out before any signatures are collected,
+    /// and all of them must be signed within a single signing round.
+    /// Transactions that did not get enough signatures in time are
+    /// returned as [`Error::SignatureTimeout`] errors.
+    #[tracing::instrument(skip_all, fields(num_txs = requests.len()))]
+    async fn sign_stacks_transactions(
+        &mut self,
+        requests: Vec<(StacksTransactionSignRequest, MultisigTx)>,
+        chain_tip: &model::BitcoinBlockHash,
+        wallet: &SignerWallet,
+    ) -> Result<Vec<Result<StacksTransaction, Error>>, Error> {
+        let signal_stream = self
+            .context
+            .as_signal_stream(signed_message_filter)
+            .filter_map(Self::to_signed_message);
+
+        tokio::pin!(signal_stream);
+
+        let num_signers = u16::try_from(wallet.public_keys().len()).unwrap_or(u16::MAX);
+        let quorum_thresholds = self.context.config().signer.quorum_thresholds();
+
+        let instant = std::time::Instant::now();
+        let mut txids = Vec::with_capacity(requests.len());
+        let mut unsigned = HashMap::new();
+        for (req, multi_tx) in requests {
+            let txid = req.txid;
+            let kind = req.tx_kind();
+            let signatures_required = quorum_thresholds.signatures_required(
+                req.impact(),
+                wallet.signatures_required(),
+                num_signers,
+            );
+
+            self.send_message(req, chain_tip).await?;
+            txids.push((txid, kind));
+            unsigned.insert(txid, (multi_tx, signatures_required));
+        }
+
+        let max_duration = self.signing_round_max_duration;
+        let mut signed = HashMap::new();
+        self.begin_round(RoundKind::StacksSigning);
+
+        let future = async {
+            while !unsigned.is_empty() {
+                // See the comment in `sign_stacks_transaction` for why we
+                // shut down here.
+                let Some(msg) = signal_stream.next().await else {
+                    tracing::warn!("signal stream returned None, shutting down");
+                    self.context.get_termination_handle().signal_shutdown();
+                    return Err(Error::SignerShutdown);
+                };
+
+                if &msg.bitcoin_chain_tip != chain_tip {
+                    tracing::warn!(
+                        sender = %msg.signer_public_key,
+                        "concurrent signing round message observed"
+                    );
+                    continue;
+                }
+
+                let sig = match msg.inner.payload {
+                    Payload::StacksTransactionSignature(sig) => sig,
+                    _ => continue,
+                };
+                let Some((multi_tx, signatures_required)) = unsigned.get_mut(&sig.txid) else {
+                    continue;
+                };
+
+                if let Err(error) = multi_tx.add_signature(sig.signature) {
+                    tracing::warn!(
+                        txid = %sig.txid,
+                        %error,
+                        offending_public_key = %msg.signer_public_key,
+                        "got an invalid signature"
+                    );
+                    continue;
+                }
+
+                if multi_tx.num_signatures() < *signatures_required {
+                    continue;
+                }
+                if let Some((mut multi_tx, _)) = unsigned.remove(&sig.txid) {
+                    multi_tx.retain_signatures(wallet.signatures_required());
+                    signed.insert(sig.txid, multi_tx.finalize_transaction());
+                }
+            }
+            Ok::<_, Error>(())
+        };
+
+        if let Ok(result) = tokio::time::timeout(max_duration, future).await {
+            result?;
+        }
+
+        let elapsed = instant.elapsed();
+        let signed_txs = txids
+            .into_iter()
+            .map(|(txid, kind)| {
+                let tx = signed.remove(&txid).ok_or(Error::SignatureTimeout(txid));
+                let status = if tx.is_ok() { "success" } else { "failure" };
+                self.publish_round_completed(RoundKind::StacksSigning, chain_tip, tx.is_ok());
+
+                metrics::histogram!(
+                    Metrics::SigningRoundDurationSeconds,
+                    "blockchain" => STACKS_BLOCKCHAIN,
+                    "kind" => kind,
+                    "status" => status,
+                )
+                .record(elapsed);
+                metrics::counter!(
+                    Metrics::SigningRoundsCompletedTotal,
+                    "blockchain" => STACKS_BLOCKCHAIN,
+                    "kind" => kind,
+                    "status" => status,
+                )
+                .increment(1);
+
+                tx
+            })
+            .collect();
 
-        tokio::time::timeout(max_duration, future)
-            .await
-            .map_err(|_| Error::SignatureTimeout(txid))?
+        Ok(signed_txs)
     }
 
     /// Coordinate a signing round for the given request
@@ -1495,7 +2050,7 @@ where
         let sighashes = transaction.construct_digests()?;
         let locking_public_key = sighashes.signers_aggregate_key.into();
         let mut fire_coordinator =
-            FireCoordinator::load(&db, locking_public_key, self.private_key).await?;
+            FireCoordinator::load(&db, locking_public_key, self.private_key.clone()).await?;
 
         let msg = sighashes.signers.to_raw_hash().to_byte_array();
 
@@ -1535,7 +2090,56 @@ where
 
             let locking_public_key = deposit.signers_public_key.into();
             let mut fire_coordinator =
-                FireCoordinator::load(&db, locking_public_key, self.private_key).await?;
+                FireCoordinator::load(&db, locking_public_key, self.private_key.clone()).await?;
+
+            let instant = std::time::Instant::now();
+            let signing_round_fut = self.coordinate_signing_round(
+                bitcoin_chain_tip,
+                &mut fire_coordinator,
+                message_id,
+                &msg,
+                SignatureType::Schnorr,
+            );
+            // Each deposit input gets its own signing round, so a failure
+            // here that is caused by a request can only be caused by this
+            // deposit request.
+            let signature = match signing_round_fut.await {
+                Ok(signature) => signature,
+                Err(error) => {
+                    self.record_request_failure(deposit.outpoint.into(), &error)
+                        .await;
+                    return Err(error);
+                }
+            };
+
+            metrics::histogram!(
+                Metrics::SigningRoundDurationSeconds,
+                "blockchain" => BITCOIN_BLOCKCHAIN,
+                "kind" => "sweep",
+            )
+            .record(instant.elapsed());
+            metrics::counter!(
+                Metrics::SigningRoundsCompletedTotal,
+                "blockchain" => BITCOIN_BLOCKCHAIN,
+                "kind" => "sweep",
+            )
+            .increment(1);
+
+            let witness = deposit.construct_witness_data(signature.into());
+
+            deposit_witness.push(witness);
+        }
+
+        // The donation inputs are key-spend only taproot UTXOs, just like
+        // the signers' UTXO.
+        let mut donation_witness = Vec::new();
+
+        for (donation, sighash) in sighashes.donations.into_iter() {
+            let msg = sighash.to_raw_hash().to_byte_array();
+
+            let locking_public_key = donation.public_key.into();
+            let mut fire_coordinator =
+                FireCoordinator::load(&db, locking_public_key, self.private_key.clone()).await?;
 
             let instant = std::time::Instant::now();
             let signature = self
@@ -1544,7 +2148,7 @@ where
                     &mut fire_coordinator,
                     message_id,
                     &msg,
-                    SignatureType::Schnorr,
+                    SignatureType::Taproot(None),
                 )
                 .await?;
 
@@ -1561,13 +2165,12 @@ where
             )
             .increment(1);
 
-            let witness = deposit.construct_witness_data(signature.into());
-
-            deposit_witness.push(witness);
+            donation_witness.push(bitcoin::Witness::p2tr_key_spend(&signature.into()));
         }
 
         let witness_data: Vec<bitcoin::Witness> = std::iter::once(signer_witness)
             .chain(deposit_witness)
+            .chain(donation_witness)
             .collect();
 
         transaction
@@ -1598,6 +2201,7 @@ where
             Metrics::TransactionsSubmittedTotal,
             "blockchain" => BITCOIN_BLOCKCHAIN,
             "status" => status,
+            "reason" => reason_label(&response),
         )
         .increment(1);
 
@@ -1625,17 +2229,154 @@ where
             .as_signal_stream(signed_message_filter)
             .filter_map(Self::to_signed_message);
 
-        let msg = message::WstsMessage { id, inner: outbound.msg };
-        self.send_message(msg, bitcoin_chain_tip).await?;
+        // If we have already collected messages for this round, say
+        // because we restarted or the round timed out, then we pick up
+        // where we left off. Otherwise we kick off the round with the
+        // nonce request.
+        let resumed = self
+            .resume_signing_round(bitcoin_chain_tip, coordinator, msg)
+            .await?;
+        let is_resumed = resumed.is_some();
+        let outbound = match resumed {
+            Some(ResumedSigningRound::Complete(operation_result)) => {
+                self.context
+                    .get_storage_mut()
+                    .delete_wsts_round_messages(bitcoin_chain_tip, msg)
+                    .await?;
+                return Self::signature_from_result(operation_result);
+            }
+            Some(ResumedSigningRound::InProgress(Some(packet))) => packet,
+            Some(ResumedSigningRound::InProgress(None)) | None => outbound,
+        };
+
+        let outbound = message::WstsMessage { id, inner: outbound.msg };
+        self.send_message(outbound, bitcoin_chain_tip).await?;
 
         let max_duration = self.signing_round_max_duration;
-        let run_signing_round =
-            self.drive_wsts_state_machine(signal_stream, bitcoin_chain_tip, coordinator, id);
+        self.begin_round(RoundKind::BitcoinSigning);
+        let run_signing_round = self.drive_wsts_state_machine(
+            signal_stream,
+            bitcoin_chain_tip,
+            coordinator,
+            id,
+            Some(msg),
+        );
 
         let operation_result = tokio::time::timeout(max_duration, run_signing_round)
             .await
-            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))??;
+            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))
+            .and_then(std::convert::identity);
+        self.publish_round_completed(
+            RoundKind::BitcoinSigning,
+            bitcoin_chain_tip,
+            operation_result.is_ok(),
+        );
+
+        // A resumed round that fails again may have been resumed from
+        // messages that the signers have since forgotten about, so the
+        // next attempt starts over.
+        if operation_result.is_ok() || is_resumed {
+            self.context
+                .get_storage_mut()
+                .delete_wsts_round_messages(bitcoin_chain_tip, msg)
+                .await?;
+        }
+
+        Self::signature_from_result(operation_result?)
+    }
+
+    /// Replay the messages that were collected for the signing round over
+    /// the given message at the given chain tip into the coordinator
+    /// state machine, if there are any.
+    ///
+    /// When the round is still in progress after the replay, the last
+    /// packet that the state machine produced during the replay is
+    /// returned, if any. It needs to be sent to the other signers in
+    /// place of the nonce request, since they have already answered
+    /// that.
+    async fn resume_signing_round<Coordinator>(
+        &mut self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        coordinator: &mut Coordinator,
+        msg: &[u8],
+    ) -> Result<Option<ResumedSigningRound>, Error>
+    where
+        Coordinator: WstsCoordinator,
+    {
+        let payloads = self
+            .context
+            .get_storage()
+            .get_wsts_round_messages(bitcoin_chain_tip, msg)
+            .await?;
+        if payloads.is_empty() {
+            return Ok(None);
+        }
+
+        tracing::info!(
+            messages = payloads.len(),
+            "resuming signing round from collected messages"
+        );
+        let signer_set = self.context.config().signer.bootstrap_signing_set.clone();
+        let mut last_packet = None;
+
+        for payload in payloads {
+            let signed_msg = match Signed::<SignerMessage>::decode(payload.as_slice()) {
+                Ok(signed_msg) if signed_msg.verify() => signed_msg,
+                Ok(_) | Err(_) => {
+                    tracing::warn!("ignoring an invalid collected message");
+                    continue;
+                }
+            };
+            let sender = self
+                .context
+                .state()
+                .current_signer_set()
+                .slot_public_key(&signed_msg.signer_public_key);
+            let processed = Self::process_wsts_message(
+                &signed_msg,
+                sender,
+                bitcoin_chain_tip,
+                coordinator,
+                &signer_set,
+            );
+            match processed {
+                Some((_, Some(operation_result))) => {
+                    return Ok(Some(ResumedSigningRound::Complete(operation_result)));
+                }
+                Some((Some(packet), None)) => last_packet = Some(packet),
+                Some((None, None)) | None => {}
+            }
+        }
+
+        Ok(Some(ResumedSigningRound::InProgress(last_packet)))
+    }
+
+    /// Record that we started coordinating a round with the other
+    /// signers, so that it is reported by the `/status` endpoint.
+    fn begin_round(&self, kind: RoundKind) {
+        self.context.state().set_round_in_flight(Some(kind));
+    }
+
+    /// Let the subscribers of the event bus know that a round with the
+    /// other signers has finished.
+    fn publish_round_completed(
+        &self,
+        kind: RoundKind,
+        chain_tip: &model::BitcoinBlockHash,
+        success: bool,
+    ) {
+        self.context.state().set_round_in_flight(None);
+        self.context.events().publish(RoundCompleted {
+            kind,
+            chain_tip: *chain_tip,
+            success,
+        });
+    }
 
+    /// Extract the signature from the result of a signing round.
+    fn signature_from_result(
+        operation_result: WstsOperationResult,
+    ) -> Result<TaprootSignature, Error> {
         match operation_result {
             WstsOperationResult::SignTaproot(sig) | WstsOperationResult::SignSchnorr(sig) => {
                 Ok(sig.into())
@@ -1657,8 +2398,12 @@ where
         let signer_set = self.context.config().signer.bootstrap_signing_set.clone();
 
         let block_height = chain_tip.block_height;
-        let mut state_machine =
-            FireCoordinator::new(signer_set, self.threshold, self.private_key, block_height);
+        let mut state_machine = FireCoordinator::new(
+            signer_set,
+            self.threshold,
+            self.private_key.clone(),
+            block_height,
+        );
 
         // Okay let's move the coordinator state machine to the beginning
         // of the DKG phase.
@@ -1688,12 +2433,16 @@ where
 
         // Now that DKG has "begun" we need to drive it to completion.
         let max_duration = self.dkg_max_duration;
+        self.begin_round(RoundKind::Dkg);
         let dkg_fut =
-            self.drive_wsts_state_machine(signal_stream, &block_hash, &mut state_machine, id);
+            self.drive_wsts_state_machine(signal_stream, &block_hash, &mut state_machine, id, None);
 
         let operation_result = tokio::time::timeout(max_duration, dkg_fut)
             .await
-            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))??;
+            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))
+            .and_then(std::convert::identity);
+        self.publish_round_completed(RoundKind::Dkg, &block_hash, operation_result.is_ok());
+        let operation_result = operation_result?;
 
         match operation_result {
             WstsOperationResult::Dkg(aggregate_key) => PublicKey::try_from(&aggregate_key),
@@ -1701,6 +2450,12 @@ where
         }
     }
 
+    /// Drive the coordinator state machine with the WSTS messages from
+    /// the given stream until it produces a result.
+    ///
+    /// For signing rounds, `round` is the message being signed, and the
+    /// messages that the state machine accepts are recorded in the
+    /// database so that the round can be resumed if it is interrupted.
     #[tracing::instrument(skip_all)]
     async fn drive_wsts_state_machine<S, Coordinator>(
         &mut self,
@@ -1708,6 +2463,7 @@ where
         bitcoin_chain_tip: &model::BitcoinBlockHash,
         coordinator: &mut Coordinator,
         id: WstsMessageId,
+        round: Option<&[u8]>,
     ) -> Result<WstsOperationResult, Error>
     where
         S: Stream<Item = Signed<SignerMessage>>,
@@ -1716,6 +2472,10 @@ where
         let signer_set = self.context.config().signer.bootstrap_signing_set.clone();
         tokio::pin!(signal_stream);
 
+        // The responses of the signers in signing rounds are timed from
+        // the moment that we sent the request that they respond to.
+        let mut requested_at = std::time::Instant::now();
+
         // Let's get the next message from the network or the
         // TxSignerEventLoop.
         //
@@ -1724,48 +2484,71 @@ where
         // channel, or the termination handler channel has closed. This is
         // all bad, so we trigger a shutdown.
         while let Some(msg) = signal_stream.next().await {
-            if &msg.bitcoin_chain_tip != bitcoin_chain_tip {
-                tracing::warn!(sender = %msg.signer_public_key, "concurrent WSTS activity observed");
-                continue;
+            // Once a competing coordinator wins over us the other signers
+            // follow it, so we abort the round and discard what we have
+            // collected for it instead of racing the winner.
+            let superseded_by = self
+                .context
+                .state()
+                .coordinator_tenure_superseded_by(bitcoin_chain_tip);
+            if let Some(winner) = superseded_by {
+                tracing::warn!(%winner, "our tenure was superseded, aborting the signing round");
+                if let Some(message) = round {
+                    self.context
+                        .get_storage_mut()
+                        .delete_wsts_round_messages(bitcoin_chain_tip, message)
+                        .await?;
+                }
+                return Err(Error::CoordinatorTenureSuperseded(winner));
             }
 
-            let Payload::WstsMessage(wsts_msg) = msg.inner.payload else {
-                continue;
-            };
-
-            let msg_public_key = msg.signer_public_key;
-
-            let sender_is_coordinator =
-                given_key_is_coordinator(msg_public_key, bitcoin_chain_tip, &signer_set);
-
-            let public_keys = &coordinator.get_config().signer_public_keys;
-            let public_key_point = p256k1::point::Point::from(msg_public_key);
-
-            let msg = wsts_msg.inner;
-
-            // check that messages were signed by correct key
-            let is_authenticated = Self::authenticate_message(
+            let sender = self
+                .context
+                .state()
+                .current_signer_set()
+                .slot_public_key(&msg.signer_public_key);
+            let processed = Self::process_wsts_message(
                 &msg,
-                public_keys,
-                public_key_point,
-                sender_is_coordinator,
+                sender,
+                bitcoin_chain_tip,
+                coordinator,
+                &signer_set,
             );
-
-            if !is_authenticated {
+            let Some((outbound_packet, operation_result)) = processed else {
                 continue;
-            }
+            };
 
-            let (outbound_packet, operation_result) = match coordinator.process_message(&msg) {
-                Ok(val) => val,
-                Err(err) => {
-                    tracing::warn!(?msg, reason = %err, "ignoring message");
-                    continue;
+            if let Some(message) = round {
+                let round_message = model::WstsRoundMessage {
+                    bitcoin_chain_tip: *bitcoin_chain_tip,
+                    message: message.to_vec(),
+                    payload: msg.encode_to_vec(),
+                };
+                self.context
+                    .get_storage_mut()
+                    .write_wsts_round_message(&round_message)
+                    .await?;
+
+                if let Some(kind) = ResponseKind::from_message(&msg) {
+                    let latency = requested_at.elapsed();
+                    let signer_public_key = msg.signer_public_key;
+                    let _ = record_response_latency(
+                        &self.context,
+                        signer_public_key,
+                        kind,
+                        latency,
+                    )
+                    .await
+                    .inspect_err(|error| {
+                        tracing::warn!(%error, "could not record a signer response latency");
+                    });
                 }
-            };
+            }
 
             if let Some(packet) = outbound_packet {
                 let msg = message::WstsMessage { id, inner: packet.msg };
                 self.send_message(msg, bitcoin_chain_tip).await?;
+                requested_at = std::time::Instant::now();
             }
 
             match operation_result {
@@ -1779,6 +2562,57 @@ where
         Err(Error::SignerShutdown)
     }
 
+    /// Authenticate the given message and pass it to the coordinator state
+    /// machine, returning what the state machine produced. `None` is
+    /// returned if the message is not a WSTS message for this chain tip,
+    /// fails authentication, or is rejected by the state machine.
+    ///
+    /// The `sender` is the public key of the slot in the signer set of
+    /// the signer that sent the message, which is the key in the DKG
+    /// shares even if the signer has since rotated its identity.
+    fn process_wsts_message<Coordinator>(
+        msg: &Signed<SignerMessage>,
+        sender: PublicKey,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        coordinator: &mut Coordinator,
+        signer_set: &BTreeSet<PublicKey>,
+    ) -> Option<(Option<wsts::net::Packet>, Option<WstsOperationResult>)>
+    where
+        Coordinator: WstsCoordinator,
+    {
+        if &msg.bitcoin_chain_tip != bitcoin_chain_tip {
+            tracing::warn!(sender = %msg.signer_public_key, "concurrent WSTS activity observed");
+            return None;
+        }
+
+        let Payload::WstsMessage(wsts_msg) = &msg.inner.payload else {
+            return None;
+        };
+
+        let sender_is_coordinator = given_key_is_coordinator(sender, bitcoin_chain_tip, signer_set);
+
+        let public_keys = &coordinator.get_config().signer_public_keys;
+        let public_key_point = p256k1::point::Point::from(sender);
+
+        let msg = &wsts_msg.inner;
+
+        // check that messages were signed by correct key
+        let is_authenticated =
+            Self::authenticate_message(msg, public_keys, public_key_point, sender_is_coordinator);
+
+        if !is_authenticated {
+            return None;
+        }
+
+        match coordinator.process_message(msg) {
+            Ok(val) => Some(val),
+            Err(err) => {
+                tracing::warn!(?msg, reason = %err, "ignoring message");
+                None
+            }
+        }
+    }
+
     fn authenticate_message(
         msg: &wsts::net::Message,
         public_keys: &hashbrown::HashMap<u32, p256k1::point::Point>,
@@ -1853,12 +2687,63 @@ where
         )
     }
 
+    /// Wait, for up to the configured `stacks_tenure_wait`, for a stacks
+    /// tenure to start or be extended in the bitcoin block at the given
+    /// chain tip.
+    ///
+    /// Contract calls that depend on the bitcoin block, like the ones that
+    /// complete the deposits swept in it, cannot be mined until the stacks
+    /// chain learns about the block, which happens once a tenure starts or
+    /// is extended in it. If the block turns out to be a flash block, then
+    /// the transactions confirm in the tenure of a later bitcoin block
+    /// instead. We do not wait when we have not observed any tenure
+    /// changes, since we cannot tell whether a tenure started then.
+    async fn wait_for_stacks_tenure(&self, chain_tip: &model::BitcoinBlockRef) {
+        const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+        let max_wait = self.context.config().signer.stacks_tenure_wait;
+        let state = self.context.state();
+        let start = tokio::time::Instant::now();
+
+        while state.has_stacks_tenure_at(chain_tip.block_height) == Some(false) {
+            if start.elapsed() >= max_wait {
+                tracing::info!(
+                    ?max_wait,
+                    "no stacks tenure started or was extended in the bitcoin block; \
+                    it may be a flash block"
+                );
+                return;
+            }
+            if self.tenure_interrupted(chain_tip) {
+                return;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Check whether our tenure at the given chain tip should stop,
+    /// either because there is a new bitcoin chain tip or because the
+    /// tenure of a competing coordinator superseded ours.
+    fn tenure_interrupted(&self, chain_tip: &model::BitcoinBlockRef) -> bool {
+        let state = self.context.state();
+        if state.bitcoin_chain_tip().as_ref() != Some(chain_tip) {
+            tracing::info!("new bitcoin chain tip, stopping coordinator activities");
+            return true;
+        }
+        if let Some(winner) = state.coordinator_tenure_superseded_by(&chain_tip.block_hash) {
+            tracing::warn!(%winner, "our tenure was superseded, stopping coordinator activities");
+            return true;
+        }
+        false
+    }
+
     /// Constructs a new [`utxo::SignerBtcState`] based on the current market
-    /// fee rate, the signer's UTXO, and the last sweep package.
+    /// fee rate, the signer's UTXO, the last sweep package, and the height
+    /// of the bitcoin chain tip.
     #[tracing::instrument(skip_all)]
     pub async fn get_btc_state(
         &self,
-        chain_tip: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockRef,
         aggregate_key: &PublicKey,
     ) -> Result<utxo::SignerBtcState, Error> {
         let bitcoin_client = self.context.get_bitcoin_client();
@@ -1868,11 +2753,16 @@ where
         let utxo = self
             .context
             .get_storage()
-            .get_signer_utxo(chain_tip)
+            .get_signer_utxo(&chain_tip.block_hash)
             .await?
             .ok_or(Error::MissingSignerUtxo)?;
 
         let last_fees = self.assess_mempool_sweep_transaction_fees(&utxo).await?;
+        let lock_time = if self.context.config().signer.sweep_lock_time {
+            utxo::sweep_lock_time(chain_tip.block_height)?
+        } else {
+            bitcoin::absolute::LockTime::ZERO
+        };
 
         Ok(utxo::SignerBtcState {
             fee_rate,
@@ -1880,6 +2770,7 @@ where
             public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
             last_fees,
             magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            lock_time,
         })
     }
 
@@ -2130,6 +3021,121 @@ where
         Ok(eligible_deposits)
     }
 
+    /// Remove the deposit requests whose UTXO is no longer in
+    /// bitcoin-core's UTXO set.
+    ///
+    /// Our database does not track deposits that the depositor spent
+    /// through the reclaim path, so we check with bitcoin-core right
+    /// before including them in a sweep. We leave out the mempool, since
+    /// our own sweep transactions may be spending the deposits there.
+    ///
+    /// The deposits are only left out of this sweep, rather than recorded
+    /// as stale. Bitcoin-core may be ahead of the block observer, so the
+    /// UTXO may have been spent by one of our own sweeps, and a reorg may
+    /// undo a reclaim. The [`crate::stale_deposits`] collector records
+    /// reclaimed deposits once it is caught up with bitcoin-core.
+    #[tracing::instrument(skip_all)]
+    async fn filter_reclaimed_deposits(
+        &self,
+        deposits: Vec<utxo::DepositRequest>,
+    ) -> Result<Vec<utxo::DepositRequest>, Error> {
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let mut unspent_deposits = Vec::with_capacity(deposits.len());
+
+        for deposit in deposits {
+            let txout = bitcoin_client
+                .get_transaction_output(&deposit.outpoint, false)
+                .await?;
+
+            if txout.is_none() {
+                tracing::warn!(
+                    outpoint = %deposit.outpoint,
+                    reason = "spent",
+                    message = "skipping deposit request"
+                );
+                continue;
+            }
+            unspent_deposits.push(deposit);
+        }
+
+        Ok(unspent_deposits)
+    }
+
+    /// Return the outpoints of all deposit requests whose deposit UTXO
+    /// can no longer be swept.
+    async fn get_stale_deposit_outpoints(&self) -> Result<HashSet<bitcoin::OutPoint>, Error> {
+        let stale = self
+            .context
+            .get_storage()
+            .get_stale_deposit_requests()
+            .await?;
+        Ok(stale
+            .into_iter()
+            .map(|stale| bitcoin::OutPoint::new(stale.txid.into(), stale.output_index))
+            .collect())
+    }
+
+    /// Return the keys of all requests that have been quarantined after
+    /// failing too many times.
+    async fn get_quarantined_requests(&self) -> Result<HashSet<model::SbtcRequestKey>, Error> {
+        let quarantined = self
+            .context
+            .get_storage()
+            .get_quarantined_requests()
+            .await?;
+        Ok(quarantined
+            .into_iter()
+            .map(|failures| failures.request)
+            .collect())
+    }
+
+    /// Record a signing failure for the given request, quarantining the
+    /// request once it has failed
+    /// [`request_quarantine_threshold`](crate::config::SignerConfig::request_quarantine_threshold)
+    /// times.
+    ///
+    /// Failing to record the failure is not fatal, so errors are only
+    /// logged here. Only failures that are caused by the request itself
+    /// are recorded, see [`is_attributable_to_request`].
+    async fn record_request_failure(&self, request: model::SbtcRequestKey, error: &Error) {
+        if !is_attributable_to_request(error) {
+            tracing::debug!(%error, %request, "not recording a failure not caused by the request");
+            return;
+        }
+
+        let threshold = self
+            .context
+            .config()
+            .signer
+            .request_quarantine_threshold
+            .get();
+        let result = self
+            .context
+            .get_storage_mut()
+            .write_request_failure(&request, &error.to_string(), threshold)
+            .await;
+
+        match result {
+            Ok(failures) if failures.failure_count == threshold => {
+                tracing::warn!(
+                    %request,
+                    failure_count = failures.failure_count,
+                    "request quarantined after repeated signing failures"
+                );
+            }
+            Ok(failures) => {
+                tracing::debug!(
+                    %request,
+                    failure_count = failures.failure_count,
+                    "recorded signing failure for request"
+                );
+            }
+            Err(error) => {
+                tracing::warn!(%error, %request, "could not record signing failure for request");
+            }
+        }
+    }
+
     /// Fetches pending deposit and withdrawal requests from storage and filters
     /// them based on consensus rules defined in #741 and [**missing**: deposit
     /// consensus ticket?].
@@ -2158,10 +3164,34 @@ where
             sbtc_limits: &sbtc_limits,
         };
 
-        // Fetch eligible deposit requests from storage.
+        // Fetch eligible deposit requests from storage, leaving out the
+        // ones that have been marked as stale or reclaimed by their
+        // depositors.
         let deposits =
             Self::get_eligible_pending_deposit_requests(&storage, self.context_window, &params)
                 .await?;
+        let stale = self.get_stale_deposit_outpoints().await?;
+        let deposits = deposits
+            .into_iter()
+            .filter(|deposit| !stale.contains(&deposit.outpoint))
+            .collect();
+        let deposits = self.filter_reclaimed_deposits(deposits).await?;
+
+        // Leave out requests that have been quarantined after failing too
+        // many times.
+        let quarantined = self.get_quarantined_requests().await?;
+        let deposits: Vec<_> = deposits
+            .into_iter()
+            .filter(|deposit| !quarantined.contains(&model::SbtcRequestKey::from(deposit.outpoint)))
+            .collect();
+
+        // Set aside the cap capacity that is reserved for deposits that
+        // have been swept but not minted yet, so that it is not handed
+        // out again.
+        let reserved =
+            cap_reservation::reserved_mintable_amount(&self.context, &bitcoin_chain_tip.block_hash)
+                .await?;
+        let sbtc_limits = sbtc_limits.with_reserved_mintable(reserved);
 
         // Fetch eligible withdrawal requests from storage.
         let withdrawals = Self::get_eligible_pending_withdrawal_requests(
@@ -2171,7 +3201,13 @@ where
             WITHDRAWAL_MIN_CONFIRMATIONS,
             &params,
         )
-        .await?;
+        .await?
+        .into_iter()
+        .filter(|withdrawal| {
+            let request_id = withdrawal.request_id;
+            !quarantined.contains(&model::SbtcRequestKey::Withdrawal { request_id })
+        })
+        .collect::<Vec<_>>();
 
         // If there are no pending deposit or withdrawal requests, we return
         // `None` to signal that there is no work to be done.
@@ -2180,9 +3216,18 @@ where
         }
 
         // Get the current signers' BTC state.
-        let signer_state = self
-            .get_btc_state(&bitcoin_chain_tip.block_hash, aggregate_key)
-            .await?;
+        let signer_state = self.get_btc_state(bitcoin_chain_tip, aggregate_key).await?;
+
+        // Donations to the signers can top up the fees of requests whose
+        // max fees are too low for the current fee rates. The signers'
+        // UTXO can itself be a donation, and it is already being spent.
+        let donations = storage
+            .get_donation_utxos(&bitcoin_chain_tip.block_hash, aggregate_key)
+            .await?
+            .into_iter()
+            .filter(|donation| donation.outpoint != signer_state.utxo.outpoint)
+            .take(MAX_DONATION_INPUTS_PER_SWEEP)
+            .collect();
 
         // Count the number of signers in the current signer set.
         let num_signers = signer_public_keys
@@ -2201,6 +3246,9 @@ where
             num_signers,
             sbtc_limits,
             max_deposits_per_bitcoin_tx,
+            max_tx_vsize: config.signer.max_sweep_tx_vsize.get().into(),
+            donations,
+            aggregate_withdrawal_outputs: config.signer.aggregate_withdrawal_outputs,
         }))
     }
 
@@ -2646,6 +3694,23 @@ pub fn adjust_nonce(wallet: &SignerWallet, error: &Error) {
     }
 }
 
+/// Whether the given signing failure was caused by the request that was
+/// being signed for, and so should count towards quarantining it.
+///
+/// Only validation rejections say something about the request. Timeouts
+/// and other failures of a signing round happen just as well when peers
+/// are offline or the network is slow, and refusals because of the stacks
+/// fee budget are about the signer's budget.
+pub fn is_attributable_to_request(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::BitcoinValidation(_)
+            | Error::DepositValidation(_)
+            | Error::WithdrawalAcceptValidation(_)
+            | Error::WithdrawalRejectValidation(_)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -2771,7 +3836,7 @@ mod tests {
             network: net.spawn(),
             context: ctx.clone(),
             context_window: 10000,
-            private_key: ctx.config().signer.private_key,
+            private_key: ctx.config().signer.private_key.clone(),
             signing_round_max_duration: Duration::from_secs(10),
             bitcoin_presign_request_max_duration: Duration::from_secs(10),
             threshold: ctx.config().signer.bootstrap_signatures_required,
@@ -2803,6 +3868,76 @@ mod tests {
         assert_matches::assert_matches!(error, Error::MissingAggregateKey(_));
     }
 
+    #[test]
+    fn only_validation_failures_are_attributed_to_requests() {
+        let context = crate::bitcoin::validation::BitcoinTxContext {
+            chain_tip: Faker.fake(),
+            chain_tip_height: 0u64.into(),
+            signer_public_key: Faker.fake(),
+            aggregate_key: Faker.fake(),
+        };
+        let error = crate::bitcoin::validation::BitcoinValidationError {
+            error: crate::bitcoin::validation::BitcoinSweepErrorMsg::Deposit(
+                crate::bitcoin::validation::InputValidationResult::DepositReclaimed,
+            ),
+            context,
+        };
+        assert!(is_attributable_to_request(&Error::BitcoinValidation(
+            Box::new(error)
+        )));
+
+        let timeouts = [
+            Error::CoordinatorTimeout(10),
+            Error::SignatureTimeout(StacksTxId::from([0; 32])),
+        ];
+        for error in timeouts {
+            assert!(!is_attributable_to_request(&error));
+        }
+    }
+
+    /// Check that deposits whose UTXO was spent outside of a sweep are
+    /// left out of the sweep, without being recorded as stale.
+    #[tokio::test]
+    async fn reclaimed_deposits_are_filtered_out() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let network = WanNetwork::default();
+        let net = network.connect(&ctx);
+
+        let ev = TxCoordinatorEventLoop {
+            network: net.spawn(),
+            context: ctx.clone(),
+            context_window: 10000,
+            private_key: ctx.config().signer.private_key.clone(),
+            signing_round_max_duration: Duration::from_secs(10),
+            bitcoin_presign_request_max_duration: Duration::from_secs(10),
+            threshold: ctx.config().signer.bootstrap_signatures_required,
+            dkg_max_duration: Duration::from_secs(10),
+            is_epoch3: true,
+        };
+
+        let votes = || model::SignerVotes::from(Vec::new());
+        let unspent = utxo::DepositRequest::from_model(Faker.fake(), votes());
+        let reclaimed = utxo::DepositRequest::from_model(Faker.fake(), votes());
+        ctx.inner_bitcoin_client().spend_output(reclaimed.outpoint);
+
+        let deposits = vec![unspent.clone(), reclaimed.clone()];
+        let filtered = ev.filter_reclaimed_deposits(deposits).await.unwrap();
+        assert_eq!(filtered, vec![unspent]);
+
+        // The UTXO may have been spent by a sweep that the block observer
+        // has not seen yet, so the deposit is not recorded as stale.
+        let stale = ctx
+            .get_storage()
+            .get_stale_deposit_requests()
+            .await
+            .unwrap();
+        assert!(stale.is_empty());
+    }
+
     /// Check that we skip processing bitcoin blocks if the chain tip in
     /// the state doesn't match the block hash passed in.
     #[tokio::test]
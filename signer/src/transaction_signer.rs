@@ -135,7 +135,8 @@ pub struct TxSignerEventLoop<Context, Network, Rng> {
     pub context: Context,
     /// Interface to the signer network.
     pub network: Network,
-    /// Private key of the signer for network communication.
+    /// Private key of the slot of the signer in the signer set, used for
+    /// network communication.
     pub signer_private_key: PrivateKey,
     /// WSTS state machines for active signing and DKG rounds.
     pub wsts_state_machines: LruCache<StateMachineId, SignerStateMachine>,
@@ -234,6 +235,9 @@ fn run_loop_message_filter(signal: &SignerSignal) -> bool {
                 | message::Payload::SignerWithdrawalDecision(_)
                 | message::Payload::StacksTransactionSignature(_)
                 | message::Payload::BitcoinPreSignAck(_)
+                | message::Payload::SignerIdentityRotation(_)
+                | message::Payload::SignerHeartbeat(_)
+                | message::Payload::CoordinatorAnnouncement(_)
         ),
         SignerSignal::Command(SignerCommand::Shutdown)
         | SignerSignal::Event(SignerEvent::TxCoordinator(TxCoordinatorEvent::MessageGenerated(
@@ -261,7 +265,7 @@ where
             .ok_or(Error::TypeConversion)?;
 
         let config = context.config();
-        let signer_private_key = config.signer.private_key;
+        let signer_private_key = config.signer.slot_private_key().clone();
         let context_window = config.signer.context_window;
         let threshold = config.signer.bootstrap_signatures_required.into();
         let dkg_begin_pause = config.signer.dkg_begin_pause.map(Duration::from_secs);
@@ -321,8 +325,37 @@ where
         bitcoin_tip_height = tracing::field::Empty,
     ))]
     async fn handle_signer_message(&mut self, msg: &network::Msg) -> Result<(), Error> {
+        // We do not validate or sign anything while our views of the
+        // bitcoin and stacks blockchains are inconsistent.
+        if !self.context.state().chain_views_consistent() {
+            tracing::warn!("our bitcoin and stacks chain views are inconsistent; ignoring message");
+            return Ok(());
+        }
+
+        // Nor while the bitcoin node is bootstrapping, since we cannot
+        // validate anything against it.
+        if !self.context.state().bitcoin_node_ready() {
+            tracing::debug!("the bitcoin node is warming up; ignoring message");
+            return Ok(());
+        }
+
+        // We do not take part in signing rounds or DKG while in
+        // maintenance mode.
+        if self.context.state().maintenance_mode() {
+            tracing::debug!("we are in maintenance mode; ignoring message");
+            return Ok(());
+        }
+
+        // Signers that rotated their identity are known by the key of
+        // their slot in the signer set, which is the key in our DKG shares.
+        let sender = self
+            .context
+            .state()
+            .current_signer_set()
+            .slot_public_key(&msg.signer_public_key);
+
         let chain_tip_report = self
-            .inspect_msg_chain_tip(msg.signer_public_key, &msg.bitcoin_chain_tip)
+            .inspect_msg_chain_tip(sender, &msg.bitcoin_chain_tip)
             .await?;
         let MsgChainTipReport {
             sender_is_coordinator,
@@ -346,16 +379,12 @@ where
         let payload = &msg.inner.payload;
         match (payload, sender_is_coordinator, chain_tip_status) {
             (Payload::StacksTransactionSignRequest(request), true, ChainTipStatus::Canonical) => {
-                self.handle_stacks_transaction_sign_request(
-                    request,
-                    &chain_tip,
-                    &msg.signer_public_key,
-                )
-                .await?;
+                self.handle_stacks_transaction_sign_request(request, &chain_tip, &sender)
+                    .await?;
             }
 
             (Payload::WstsMessage(wsts_msg), _, ChainTipStatus::Canonical) => {
-                self.handle_wsts_message(wsts_msg, msg.signer_public_key, &chain_tip_report)
+                self.handle_wsts_message(wsts_msg, sender, &chain_tip_report)
                     .await?;
             }
 
@@ -371,7 +400,10 @@ where
             // Message types ignored by the transaction signer
             (Payload::StacksTransactionSignature(_), _, _)
             | (Payload::SignerDepositDecision(_), _, _)
-            | (Payload::SignerWithdrawalDecision(_), _, _) => (),
+            | (Payload::SignerWithdrawalDecision(_), _, _)
+            | (Payload::SignerIdentityRotation(_), _, _)
+            | (Payload::SignerHeartbeat(_), _, _)
+            | (Payload::CoordinatorAnnouncement(_), _, _) => (),
 
             // Any other combination should be logged
             _ => {
@@ -480,6 +512,8 @@ where
             .flat_map(|s| s.to_withdrawal_rows())
             .collect();
 
+        Metrics::increment_sweep_request_rejections(&deposits_sighashes, &withdrawals_outputs);
+
         tracing::debug!("storing sighashes to the database");
         db.write_bitcoin_txs_sighashes(&deposits_sighashes).await?;
 
@@ -691,7 +725,7 @@ where
                     signer_public_keys,
                     threshold,
                     *chain_tip,
-                    self.signer_private_key,
+                    self.signer_private_key.clone(),
                 )?;
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.wsts_state_machines
@@ -893,7 +927,8 @@ where
 
                 // Create a new `SignerStateMachine`.
                 let state_machine =
-                    SignerStateMachine::load(&db, aggregate_key, self.signer_private_key).await?;
+                    SignerStateMachine::load(&db, aggregate_key, self.signer_private_key.clone())
+                        .await?;
 
                 // Put the state machine into the cache.
                 self.wsts_state_machines
@@ -1311,7 +1346,7 @@ where
             let coordinator = Self::create_dkg_verification_state_machine(
                 &storage,
                 aggregate_key,
-                self.signer_private_key,
+                self.signer_private_key.clone(),
             )
             .await?;
             self.dkg_verification_state_machines
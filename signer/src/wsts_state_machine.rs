@@ -11,6 +11,7 @@ use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::keys::SignerScriptPubKey as _;
+use crate::secrets::SecretBytes;
 use crate::storage;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHash;
@@ -39,6 +40,7 @@ use wsts::state_machine::coordinator::fire;
 use wsts::state_machine::coordinator::frost;
 use wsts::traits::Signer as _;
 use wsts::v2::Aggregator;
+use zeroize::Zeroizing;
 
 /// An identifier for signer state machines.
 ///
@@ -496,6 +498,43 @@ pub struct SignerStateMachine {
 
 type WstsSigner = wsts::state_machine::signer::Signer<wsts::v2::Party>;
 
+/// The saved state of a WSTS signer, which holds the private shares of
+/// the signer from DKG. The private shares and nonces are erased when it
+/// is dropped.
+///
+/// The private polynomial of each party is left alone, since the
+/// `polynomial` crate gives no mutable access to its coefficients.
+struct SecretSignerState(wsts::traits::SignerState);
+
+impl Drop for SecretSignerState {
+    fn drop(&mut self) {
+        for (_, party) in self.0.parties.iter_mut() {
+            for (_, private_key) in party.private_keys.iter_mut() {
+                erase_scalar(private_key);
+            }
+            erase_scalar(&mut party.nonce.d);
+            erase_scalar(&mut party.nonce.e);
+        }
+    }
+}
+
+/// Overwrite the given scalar with zero in a way that the compiler will
+/// not optimize away.
+fn erase_scalar(scalar: &mut p256k1::scalar::Scalar) {
+    // SAFETY: the pointer comes from a mutable reference, so it is valid
+    // and properly aligned for writes.
+    unsafe { std::ptr::write_volatile(scalar, p256k1::scalar::Scalar::zero()) };
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+impl Drop for SignerStateMachine {
+    fn drop(&mut self) {
+        // The private shares of the party are private to WSTS, but the
+        // network private key is a copy of our private key.
+        erase_scalar(&mut self.inner.network_private_key);
+    }
+}
+
 impl SignerStateMachine {
     /// Create a new state machine
     ///
@@ -563,7 +602,7 @@ impl SignerStateMachine {
             num_keys,
             id,
             key_ids,
-            private_key.into(),
+            (&private_key).into(),
             public_keys,
             &mut OsRng,
         )
@@ -574,10 +613,10 @@ impl SignerStateMachine {
 
     /// Create a random number generator seeded with the given bitcoin
     /// block reference and a private key.
-    fn create_rng(started_at: &BitcoinBlockHash, private_key: PrivateKey) -> ChaCha20Rng {
+    fn create_rng(started_at: &BitcoinBlockHash, private_key: &PrivateKey) -> ChaCha20Rng {
         let seed_bytes: [u8; 32] = sha2::Sha256::new_with_prefix("DKG_RNG")
             .chain_update(started_at.into_bytes())
-            .chain_update(private_key.to_bytes())
+            .chain_update(&*private_key.to_secret_bytes())
             .finalize()
             .into();
 
@@ -607,7 +646,7 @@ impl SignerStateMachine {
     pub fn process(&mut self, message: &Message) -> Result<Vec<Message>, Error> {
         let response = match message {
             Message::DkgBegin(_) => {
-                let mut rng = Self::create_rng(&self.started_at.block_hash, self.private_key);
+                let mut rng = Self::create_rng(&self.started_at.block_hash, &self.private_key);
                 self.inner.process(message, &mut rng)
             }
             _ => self.inner.process(message, &mut OsRng),
@@ -654,17 +693,19 @@ impl SignerStateMachine {
             .ok_or_else(|| Error::MissingDkgShares(aggregate_key))?;
 
         let decrypted = wsts::util::decrypt(
-            &signer_private_key.to_bytes(),
+            &*signer_private_key.to_secret_bytes(),
             &encrypted_shares.encrypted_private_shares,
         )
+        .map(SecretBytes::new)
         .map_err(|error| Error::WstsDecrypt(error, aggregate_key))?;
 
         let saved_state = wsts::traits::SignerState::decode(decrypted.as_slice())?;
+        let saved_state = SecretSignerState(saved_state);
 
         // This may panic if the saved state doesn't contain exactly one party,
         // however, that should never be the case since wsts maintains this invariant
         // when we save the state.
-        let signer = wsts::v2::Party::load(&saved_state);
+        let signer = wsts::v2::Party::load(&saved_state.0);
         let signers = encrypted_shares.signer_set_public_keys();
         // This as _ cast is a widening of a u16 to a u32, which is always fine.
         let threshold = encrypted_shares.signature_share_threshold as u32;
@@ -683,8 +724,8 @@ impl SignerStateMachine {
 
     /// Get the encrypted DKG shares
     pub fn get_encrypted_dkg_shares(&self) -> Result<model::EncryptedDkgShares, Error> {
-        let saved_state = self.inner.signer.save();
-        let aggregate_key = PublicKey::try_from(&saved_state.group_key)?;
+        let saved_state = SecretSignerState(self.inner.signer.save());
+        let aggregate_key = PublicKey::try_from(&saved_state.0.group_key)?;
 
         // When creating a new Self, the `public_keys` field gets populated
         // using the `signers` input iterator. It represents the public
@@ -701,15 +742,15 @@ impl SignerStateMachine {
         // We require the public keys to be stored sorted in db
         signer_set_public_keys.sort();
 
-        let encoded = saved_state.encode_to_vec();
+        let encoded = SecretBytes::new(saved_state.0.clone().encode_to_vec());
         let public_shares = self.inner.dkg_public_shares.clone().encode_to_vec();
 
         // After DKG, each of the signers will have "new public keys". The
         // call to `wsts::util::encrypt` can error if we are encrypting
         // more than 68719476752 bytes.
         let encrypted_private_shares = wsts::util::encrypt(
-            &self.inner.network_private_key.to_bytes(),
-            &encoded,
+            &*Zeroizing::new(self.inner.network_private_key.to_bytes()),
+            encoded.as_slice(),
             &mut OsRng,
         )
         .map_err(|error| Error::WstsEncrypt(error, aggregate_key))?;
@@ -102,8 +102,8 @@ async fn process_blocks_simple_fork() {
     // No need for deposits here
     ctx.with_emily_client(|client| {
         client
-            .expect_get_deposits()
-            .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+            .expect_get_deposits_updated_since()
+            .returning(move |_| Box::pin(std::future::ready(Ok(vec![]))));
 
         client
             .expect_get_limits()
@@ -410,6 +410,7 @@ async fn orphaned_deposit() {
         deposit_script: deposit_request.deposit_script.to_hex_string(),
         reclaim_script: deposit_request.reclaim_script.to_hex_string(),
         transaction_hex: serialize_hex(&deposit_tx),
+        correlation_id: None,
     };
 
     deposit_api::create_deposit(emily_client.config(), emily_request.clone())
@@ -10,6 +10,7 @@ use sbtc::testing::regtest;
 use signer::WITHDRAWAL_MIN_CONFIRMATIONS;
 use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
+use signer::bitcoin::utxo::sweep_lock_time;
 use signer::bitcoin::validation::BitcoinTxContext;
 use signer::bitcoin::validation::BitcoinTxValidationData;
 use signer::bitcoin::validation::InputValidationResult;
@@ -53,6 +54,7 @@ where
         public_key: btc_ctx.aggregate_key.into(),
         last_fees: request.last_fees,
         magic_bytes: [b'T', b'3'],
+        lock_time: request.lock_time,
     }
 }
 
@@ -102,6 +104,7 @@ async fn one_tx_per_request_set() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -135,6 +138,8 @@ async fn one_tx_per_request_set() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_block.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -198,6 +203,7 @@ async fn one_invalid_deposit_invalidates_tx() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -240,6 +246,8 @@ async fn one_invalid_deposit_invalidates_tx() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_block.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -361,6 +369,7 @@ async fn withdrawals_and_deposits_can_pass_validation(amounts: Vec<SweepAmounts>
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
 
     ctx.state().update_current_limits(SbtcLimits::unlimited());
@@ -406,6 +415,8 @@ async fn withdrawals_and_deposits_can_pass_validation(amounts: Vec<SweepAmounts>
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_ref.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -456,6 +467,7 @@ async fn swept_withdrawals_fail_validation() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
 
     ctx.state().update_current_limits(SbtcLimits::unlimited());
@@ -512,6 +524,8 @@ async fn swept_withdrawals_fail_validation() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_ref.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -567,6 +581,7 @@ async fn cannot_sign_deposit_is_ok() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -634,6 +649,8 @@ async fn cannot_sign_deposit_is_ok() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_block.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -706,6 +723,9 @@ async fn cannot_sign_deposit_is_ok() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_tx_vsize: ctx.config().signer.max_sweep_tx_vsize.get().into(),
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -732,6 +752,7 @@ async fn sighashes_match_from_sbtc_requests_object() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -772,6 +793,8 @@ async fn sighashes_match_from_sbtc_requests_object() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_block.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -840,6 +863,9 @@ async fn sighashes_match_from_sbtc_requests_object() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_tx_vsize: ctx.config().signer.max_sweep_tx_vsize.get().into(),
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -866,6 +892,7 @@ async fn outcome_is_independent_of_input_order() {
         .with_first_bitcoin_core_client()
         .with_mocked_stacks_client()
         .with_mocked_emily_client()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -916,6 +943,8 @@ async fn outcome_is_independent_of_input_order() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip_block.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let btc_ctx = BitcoinTxContext {
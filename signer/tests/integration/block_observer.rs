@@ -21,11 +21,13 @@ use fake::Fake as _;
 use fake::Faker;
 use rand::seq::SliceRandom as _;
 use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositScriptDecoders;
 use sbtc::deposits::DepositScriptInputs;
 use sbtc::deposits::ReclaimScriptInputs;
 use sbtc::testing::regtest;
 use sbtc::testing::regtest::Faucet;
 use sbtc::testing::regtest::Recipient;
+use signer::MAX_STANDARD_TX_VSIZE;
 use signer::bitcoin::poller::BitcoinChainTipPoller;
 use signer::bitcoin::utxo::DepositRequest;
 use signer::bitcoin::utxo::SbtcRequests;
@@ -34,6 +36,7 @@ use signer::bitcoin::utxo::SignerUtxo;
 use signer::block_observer::get_signer_set_info;
 use signer::context::SbtcLimits;
 use signer::emily_client::EmilyClient;
+use signer::emily_client::EmilyDepositRequest;
 use signer::error::Error;
 use signer::keys::PublicKey;
 use signer::keys::SignerScriptPubKey as _;
@@ -78,9 +81,9 @@ use crate::setup::fetch_canonical_bitcoin_blockchain;
 use crate::transaction_coordinator::mock_reqwests_status_code_error;
 use crate::utxo_construction::make_deposit_request;
 
-/// The [`BlockObserver::load_latest_deposit_requests`] function is
-/// supposed to fetch all deposit requests from Emily and persist the ones
-/// that pass validation, regardless of when they were confirmed.
+/// The block observer is supposed to fetch all deposit requests from Emily
+/// and persist the ones that pass validation, regardless of when they were
+/// confirmed.
 #[test_case::test_case(1; "one block ago")]
 #[test_case::test_case(5; "five blocks ago")]
 #[test_log::test(tokio::test)]
@@ -107,14 +110,21 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
 
     // Let's prep Emily with information about these deposits.
     ctx.with_emily_client(|client| {
-        let emily_client_response = vec![
+        let emily_client_response: Vec<_> = [
             setup0.emily_deposit_request(),
             setup1.emily_deposit_request(),
-        ];
+        ]
+        .into_iter()
+        .map(|request| EmilyDepositRequest {
+            request,
+            last_update_height: 0,
+            correlation_id: None,
+        })
+        .collect();
         client
-            .expect_get_deposits()
+            .expect_get_deposits_updated_since()
             .times(1..)
-            .returning(move || Box::pin(std::future::ready(Ok(emily_client_response.clone()))));
+            .returning(move |_| Box::pin(std::future::ready(Ok(emily_client_response.clone()))));
 
         client
             .expect_get_limits()
@@ -271,8 +281,8 @@ async fn link_blocks() {
     // No need for deposits here
     ctx.with_emily_client(|client| {
         client
-            .expect_get_deposits()
-            .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+            .expect_get_deposits_updated_since()
+            .returning(move |_| Box::pin(std::future::ready(Ok(vec![]))));
     })
     .await;
 
@@ -584,11 +594,15 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_tx_vsize: ctx.config().signer.max_sweep_tx_vsize.get().into(),
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     let mut transactions = requests.construct_transactions().unwrap();
@@ -610,6 +624,7 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
         deposit_script: deposit_request.deposit_script.to_hex_string(),
         reclaim_script: deposit_request.reclaim_script.to_hex_string(),
         transaction_hex: serialize_hex(&deposit_tx),
+        correlation_id: None,
     };
     deposit_api::create_deposit(emily_client.config(), body)
         .await
@@ -758,11 +773,15 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
             public_key: signers_public_key2,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: 2,
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: 25,
+        max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     // By playing around with the votes above, we set things up so that we
@@ -921,8 +940,8 @@ async fn block_observer_handles_update_limits(deployed: bool, sbtc_limits: SbtcL
 
     ctx.with_emily_client(|client| {
         client
-            .expect_get_deposits()
-            .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+            .expect_get_deposits_updated_since()
+            .returning(move |_| Box::pin(std::future::ready(Ok(vec![]))));
     })
     .await;
 
@@ -1250,8 +1269,8 @@ async fn block_observer_updates_state_after_observing_bitcoin_block() {
 
     ctx.with_emily_client(|client| {
         client
-            .expect_get_deposits()
-            .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+            .expect_get_deposits_updated_since()
+            .returning(|_| Box::pin(std::future::ready(Ok(vec![]))));
 
         client
             .expect_get_limits()
@@ -1424,8 +1443,8 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
 
     ctx.with_emily_client(|client| {
         client
-            .expect_get_deposits()
-            .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+            .expect_get_deposits_updated_since()
+            .returning(|_| Box::pin(std::future::ready(Ok(vec![]))));
 
         client
             .expect_get_limits()
@@ -1764,6 +1783,7 @@ async fn block_observer_ignores_coinbase() {
         deposit_script: deposit_request.deposit_script.to_hex_string(),
         reclaim_script: deposit_request.reclaim_script.to_hex_string(),
         transaction_hex: serialize_hex(&deposit_tx),
+        correlation_id: None,
     };
     deposit_api::create_deposit(emily_client.config(), body)
         .await
@@ -1809,8 +1829,13 @@ async fn block_observer_ignores_coinbase() {
         deposit_script: deposit_request.deposit_script.clone(),
     };
     let bitcoin_client = ctx.get_bitcoin_client();
-    let validate_result =
-        signer::block_observer::DepositRequestValidator::validate(&request, &bitcoin_client, false);
+    let decoders = DepositScriptDecoders::default();
+    let validate_result = signer::block_observer::DepositRequestValidator::validate(
+        &request,
+        &bitcoin_client,
+        false,
+        &decoders,
+    );
     match validate_result.await {
         Err(Error::BitcoinTxCoinbase(tx)) if tx == deposit_request.outpoint.txid => {}
         _ => panic!("Expected a err, got something else"),
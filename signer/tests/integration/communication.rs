@@ -44,7 +44,7 @@ async fn libp2p_clients_can_exchange_messages_given_real_network(addr1: &str, ad
         .with_in_memory_storage()
         .with_mocked_clients()
         .modify_settings(|settings| {
-            settings.signer.private_key = key1;
+            settings.signer.private_key = key1.clone();
         })
         .build();
     context1
@@ -56,7 +56,7 @@ async fn libp2p_clients_can_exchange_messages_given_real_network(addr1: &str, ad
         .with_in_memory_storage()
         .with_mocked_clients()
         .modify_settings(|settings| {
-            settings.signer.private_key = key2;
+            settings.signer.private_key = key2.clone();
         })
         .build();
     context2
@@ -114,7 +114,7 @@ async fn libp2p_clients_can_exchange_messages_given_real_network(addr1: &str, ad
     if tokio::time::timeout(
         tokio::time::Duration::from_secs(10),
         signer::testing::network::assert_clients_can_exchange_messages(
-            network1, network2, key1, key2,
+            network1, network2, &key1, &key2,
         ),
     )
     .await
@@ -162,7 +162,7 @@ async fn libp2p_limits_max_established_connections() -> Result<(), Box<dyn std::
         .with_in_memory_storage()
         .with_mocked_clients()
         .modify_settings(|settings| {
-            settings.signer.private_key = keys[0];
+            settings.signer.private_key = keys[0].clone();
         })
         .build();
     context1
@@ -215,7 +215,7 @@ async fn libp2p_limits_max_established_connections() -> Result<(), Box<dyn std::
             .with_in_memory_storage()
             .with_mocked_clients()
             .modify_settings(|settings| {
-                settings.signer.private_key = *key;
+                settings.signer.private_key = key.clone();
             })
             .build();
         peer_context
@@ -217,6 +217,7 @@ async fn deposit_flow() {
         deposit_script: deposit_request.deposit_script.to_hex_string(),
         reclaim_script: deposit_request.reclaim_script.to_hex_string(),
         transaction_hex: serialize_hex(&deposit_tx),
+        correlation_id: None,
     };
 
     // Create a fresh block for the block observer to process
@@ -563,6 +564,7 @@ async fn get_deposit_request_works() {
         deposit_script: deposit.deposit_script().to_hex_string(),
         reclaim_script: reclaim.reclaim_script().to_hex_string(),
         transaction_hex: serialize_hex(&setup.tx),
+        correlation_id: None,
     };
 
     deposit_api::create_deposit(emily_client.config(), emily_request.clone())
@@ -624,6 +626,7 @@ async fn test_get_deposits_with_status_request_paging(
                 .reclaim_script()
                 .to_hex_string(),
             transaction_hex: serialize_hex(&setup.tx),
+            correlation_id: None,
         };
         deposit_api::create_deposit(emily_client.config(), create_deposit_request_body)
     });
@@ -680,6 +683,7 @@ async fn test_get_deposits_returns_pending_and_accepted() {
                 .reclaim_script()
                 .to_hex_string(),
             transaction_hex: serialize_hex(&setup.tx),
+            correlation_id: None,
         };
         deposit_api::create_deposit(emily_client.config(), create_deposit_request_body)
     });
@@ -699,6 +703,7 @@ async fn test_get_deposits_returns_pending_and_accepted() {
             status: DepositStatus::Accepted,
             status_message: "accepted".to_string(),
             replaced_by_tx: None,
+            correlation_id: None,
         })
         .collect();
 
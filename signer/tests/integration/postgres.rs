@@ -20,6 +20,7 @@ use more_asserts::assert_le;
 use rand::seq::IteratorRandom as _;
 use rand::seq::SliceRandom as _;
 use signer::WITHDRAWAL_BLOCKS_EXPIRY;
+use signer::amount::SbtcAmount;
 use signer::bitcoin::validation::WithdrawalRequestStatus;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::context::SbtcLimits;
@@ -38,6 +39,9 @@ use signer::bitcoin::validation::DepositConfirmationStatus;
 use signer::context::Context;
 use signer::emily_client::MockEmilyInteract;
 use signer::error::Error;
+use signer::integrity;
+use signer::integrity::IntegrityKey;
+use signer::integrity::ProtectedRow as _;
 use signer::keys::PublicKey;
 use signer::keys::SignerScriptPubKey as _;
 use signer::network;
@@ -49,6 +53,7 @@ use signer::stacks::contracts::CompleteDepositV1;
 use signer::stacks::contracts::RejectWithdrawalV1;
 use signer::stacks::contracts::ReqContext;
 use signer::stacks::contracts::RotateKeysV1;
+use signer::stacks::tenure::TenureChangeKind;
 use signer::storage;
 use signer::storage::DbRead as _;
 use signer::storage::DbWrite as _;
@@ -61,6 +66,7 @@ use signer::storage::model::BitcoinWithdrawalOutput;
 use signer::storage::model::CompletedDepositEvent;
 use signer::storage::model::EncryptedDkgShares;
 use signer::storage::model::QualifiedRequestId;
+use signer::storage::model::RowIntegrityHash;
 use signer::storage::model::ScriptPubKey;
 use signer::storage::model::StacksBlock;
 use signer::storage::model::StacksBlockHash;
@@ -1076,7 +1082,7 @@ async fn writing_completed_deposit_requests_postgres() {
     let store = testing::storage::new_test_database().await;
 
     let mut rng = get_rng();
-    let event: CompletedDepositEvent = fake::Faker.fake_with_rng(&mut rng);
+    let event: CompletedDepositEvent = Faker.fake_with_rng(&mut rng);
 
     // Let's see if we can write these rows to the database.
     store.write_completed_deposit_event(&event).await.unwrap();
@@ -1099,7 +1105,7 @@ async fn writing_completed_deposit_requests_postgres() {
 
     assert_eq!(txid, event.txid.into_bytes());
     assert_eq!(block_id, event.block_id.into_bytes());
-    assert_eq!(amount as u64, event.amount);
+    assert_eq!(amount as u64, event.amount.get());
     assert_eq!(bitcoin_txid, event.outpoint.txid.to_byte_array());
     assert_eq!(vout as u32, event.outpoint.vout);
 
@@ -1190,7 +1196,7 @@ async fn writing_withdrawal_accept_requests_postgres() {
     assert_eq!(bitmap, event.signer_bitmap.into_inner());
     assert_eq!(bitcoin_txid, event.outpoint.txid.to_byte_array());
     assert_eq!(vout as u32, event.outpoint.vout);
-    assert_eq!(fee as u64, event.fee);
+    assert_eq!(fee as u64, event.fee.to_sat());
 
     signer::testing::storage::drop_db(store).await;
 }
@@ -2598,7 +2604,7 @@ async fn get_swept_deposit_requests_does_not_return_deposit_requests_with_respon
     let event = CompletedDepositEvent {
         txid: fake::Faker.fake_with_rng::<StacksTxId, _>(&mut rng),
         block_id: setup_canonical_event_block.block_hash,
-        amount: setup_canonical.deposit_request.amount,
+        amount: SbtcAmount::new(setup_canonical.deposit_request.amount),
         outpoint: setup_canonical.deposit_request.outpoint,
         sweep_block_hash: setup_canonical.deposit_block_hash.into(),
         sweep_block_height: 42u64.into(),
@@ -2610,7 +2616,7 @@ async fn get_swept_deposit_requests_does_not_return_deposit_requests_with_respon
     let event = CompletedDepositEvent {
         txid: fake::Faker.fake_with_rng::<StacksTxId, _>(&mut rng),
         block_id: setup_fork_event_block.block_hash,
-        amount: setup_fork.deposit_request.amount,
+        amount: SbtcAmount::new(setup_fork.deposit_request.amount),
         outpoint: setup_fork.deposit_request.outpoint,
         sweep_block_hash: setup_fork.deposit_block_hash.into(),
         sweep_block_height: 42u64.into(),
@@ -2642,7 +2648,7 @@ async fn get_swept_deposit_requests_does_not_return_deposit_requests_with_respon
     let event = CompletedDepositEvent {
         txid: fake::Faker.fake_with_rng::<StacksTxId, _>(&mut rng),
         block_id: setup_fork_event_block.block_hash,
-        amount: setup_fork.deposit_request.amount,
+        amount: SbtcAmount::new(setup_fork.deposit_request.amount),
         outpoint: setup_fork.deposit_request.outpoint,
         sweep_block_hash: setup_fork.deposit_block_hash.into(),
         sweep_block_height: 42u64.into(),
@@ -2907,7 +2913,7 @@ async fn get_swept_deposit_requests_response_tx_reorged() {
     let event = CompletedDepositEvent {
         txid: fake::Faker.fake_with_rng::<StacksTxId, _>(&mut rng),
         block_id: original_event_block.block_hash,
-        amount: setup.deposit_request.amount,
+        amount: SbtcAmount::new(setup.deposit_request.amount),
         outpoint: setup.deposit_request.outpoint,
         sweep_block_hash: setup.deposit_block_hash.into(),
         sweep_block_height: 42u64.into(),
@@ -3017,7 +3023,7 @@ async fn get_swept_deposit_requests_boundary() {
     let event = CompletedDepositEvent {
         txid: fake::Faker.fake_with_rng::<StacksTxId, _>(&mut rng),
         block_id: event_block.block_hash,
-        amount: setup.deposit_request.amount,
+        amount: SbtcAmount::new(setup.deposit_request.amount),
         outpoint: setup.deposit_request.outpoint,
         sweep_block_hash: setup.sweep_block_hash.into(),
         sweep_block_height: 42u64.into(),
@@ -7546,3 +7552,975 @@ mod sqlx_transactions {
         Ok(())
     }
 }
+
+/// Check that the activity that we record for bitcoin blocks can be read
+/// back, and that it is only recorded for blocks that we know about.
+#[tokio::test]
+async fn bitcoin_block_activity_is_stored_with_the_block() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&block).await.unwrap();
+
+    // Blocks start out without any recorded activity.
+    let stored = db
+        .get_bitcoin_block_activity(&block.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(stored, None);
+
+    let activity = model::BitcoinBlockActivity {
+        block_hash: block.block_hash,
+        block_time: 1_700_000_000,
+        has_sbtc_activity: true,
+    };
+    db.write_bitcoin_block_activity(&activity).await.unwrap();
+
+    let stored = db
+        .get_bitcoin_block_activity(&block.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(stored, Some(activity));
+
+    // There is nothing to record the activity of unknown blocks on.
+    let unknown = model::BitcoinBlockActivity {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        ..activity
+    };
+    db.write_bitcoin_block_activity(&unknown).await.unwrap();
+
+    let stored = db
+        .get_bitcoin_block_activity(&unknown.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(stored, None);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that signer identity rotations are persisted, and that a public
+/// key can only be rotated away from once.
+#[tokio::test]
+async fn signer_identity_rotations_are_written_once() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let rotation: model::SignerIdentityRotation = Faker.fake_with_rng(&mut rng);
+    db.write_signer_identity_rotation(&rotation).await.unwrap();
+
+    // A second announcement for the same old key is ignored.
+    let conflicting = model::SignerIdentityRotation {
+        new_public_key: Faker.fake_with_rng(&mut rng),
+        ..rotation.clone()
+    };
+    db.write_signer_identity_rotation(&conflicting)
+        .await
+        .unwrap();
+
+    let rotations = db.get_signer_identity_rotations().await.unwrap();
+    assert_eq!(rotations, vec![rotation]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the intake sources of a deposit request are recorded in the
+/// order that they were first seen, and that duplicates are ignored.
+#[tokio::test]
+async fn deposit_request_sources_are_recorded() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    db.write_deposit_request(&deposit).await.unwrap();
+
+    let sources = [
+        model::DepositRequestSource::P2p,
+        model::DepositRequestSource::Emily,
+        model::DepositRequestSource::P2p,
+    ];
+    for source in sources {
+        let intake = model::DepositRequestIntake {
+            txid: deposit.txid,
+            output_index: deposit.output_index,
+            source,
+        };
+        db.write_deposit_request_intake(&intake).await.unwrap();
+    }
+
+    let sources = db
+        .get_deposit_request_sources(&deposit.txid, deposit.output_index)
+        .await
+        .unwrap();
+    let expected = vec![
+        model::DepositRequestSource::P2p,
+        model::DepositRequestSource::Emily,
+    ];
+    assert_eq!(sources, expected);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that requests are quarantined once they have failed the given
+/// number of times, and that releasing them clears their failures.
+#[tokio::test]
+async fn requests_are_quarantined_after_repeated_failures() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    db.write_deposit_request(&deposit).await.unwrap();
+
+    let deposit_key = model::SbtcRequestKey::Deposit {
+        txid: deposit.txid,
+        output_index: deposit.output_index,
+    };
+    let withdrawal_key = model::SbtcRequestKey::Withdrawal { request_id: 7 };
+
+    let failures = db
+        .write_request_failure(&deposit_key, "first", 2)
+        .await
+        .unwrap();
+    assert_eq!(failures.failure_count, 1);
+    assert!(!failures.is_quarantined());
+    assert!(db.get_quarantined_requests().await.unwrap().is_empty());
+
+    let failures = db
+        .write_request_failure(&deposit_key, "second", 2)
+        .await
+        .unwrap();
+    assert_eq!(failures.failure_count, 2);
+    assert_eq!(failures.last_error, "second");
+    assert!(failures.is_quarantined());
+
+    let failures = db
+        .write_request_failure(&withdrawal_key, "only", 1)
+        .await
+        .unwrap();
+    assert!(failures.is_quarantined());
+
+    let quarantined: Vec<_> = db
+        .get_quarantined_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|failures| failures.request)
+        .collect();
+    assert_eq!(quarantined, vec![deposit_key, withdrawal_key]);
+
+    assert!(db.release_request_failures(&deposit_key).await.unwrap());
+    assert!(!db.release_request_failures(&deposit_key).await.unwrap());
+
+    let quarantined = db.get_quarantined_requests().await.unwrap();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].request, withdrawal_key);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that webhook subscribers can be registered and removed, and that
+/// registering the same URL twice is a no-op.
+#[tokio::test]
+async fn webhook_subscribers_can_be_registered_and_removed() {
+    let db = testing::storage::new_test_database().await;
+
+    let first = "https://example.com/first";
+    let second = "https://example.com/second";
+
+    db.write_webhook_subscriber(first).await.unwrap();
+    db.write_webhook_subscriber(second).await.unwrap();
+    db.write_webhook_subscriber(first).await.unwrap();
+
+    let subscribers = db.get_webhook_subscribers().await.unwrap();
+    assert_eq!(subscribers, vec![first, second]);
+
+    assert!(db.delete_webhook_subscriber(first).await.unwrap());
+    assert!(!db.delete_webhook_subscriber(first).await.unwrap());
+
+    let subscribers = db.get_webhook_subscribers().await.unwrap();
+    assert_eq!(subscribers, vec![second]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that integrity hashes survive a round trip through the database
+/// and that the rows they protect can be verified.
+#[tokio::test]
+async fn row_integrity_hashes_verify_protected_rows() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+    let key = IntegrityKey::new([7; 32]);
+
+    let event: CompletedDepositEvent = Faker.fake_with_rng(&mut rng);
+    db.write_completed_deposit_event(&event).await.unwrap();
+    let hash = key.hash_row(&event);
+    db.write_row_integrity_hash(&hash).await.unwrap();
+
+    // Writing a hash for the same row again keeps the original hash.
+    let forged = RowIntegrityHash {
+        integrity_hash: [0; 32],
+        ..hash.clone()
+    };
+    db.write_row_integrity_hash(&forged).await.unwrap();
+    let hashes = db
+        .get_row_integrity_hashes(CompletedDepositEvent::TABLE_NAME)
+        .await
+        .unwrap();
+    assert_eq!(hashes, vec![hash]);
+
+    let reports = integrity::verify_integrity(&db, &key).await.unwrap();
+    assert!(reports.iter().all(|report| report.is_intact()));
+    assert_eq!(reports[0].verified, 1);
+
+    // Tamper with the row directly in the database.
+    sqlx::query("UPDATE sbtc_signer.completed_deposit_events SET amount = amount + 1")
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let reports = integrity::verify_integrity(&db, &key).await.unwrap();
+    assert_eq!(reports[0].modified, vec![hex::encode(event.row_key())]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that a database whose migrations were applied without being
+/// recorded can be upgraded in place, and that a dry run leaves it
+/// untouched.
+#[tokio::test]
+async fn legacy_databases_are_upgraded_in_place() {
+    let db = testing::storage::new_test_database().await;
+
+    async fn table_exists(db: &PgStore, name: &str) -> bool {
+        sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(name)
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+    }
+
+    // Simulate a database from an earlier schema generation, whose
+    // migrations were applied by hand.
+    sqlx::raw_sql(
+        r#"
+            DROP TABLE public.__sbtc_migrations;
+            DROP TABLE sbtc_signer.row_integrity_hashes;
+            DROP TABLE sbtc_signer.webhook_subscribers;
+        "#,
+    )
+    .execute(db.pool())
+    .await
+    .unwrap();
+
+    let expected_applied = vec![
+        "0024__webhook_subscribers.sql".to_string(),
+        "0025__row_integrity_hashes.sql".to_string(),
+    ];
+
+    let report = db.migrate_legacy(true).await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.already_applied.len(), 23);
+    assert_eq!(report.already_applied[22], "0023__request_quarantine.sql");
+    assert_eq!(report.applied, expected_applied);
+
+    assert!(!table_exists(&db, "public.__sbtc_migrations").await);
+    assert!(!table_exists(&db, "sbtc_signer.webhook_subscribers").await);
+
+    let report = db.migrate_legacy(false).await.unwrap();
+    assert!(!report.dry_run);
+    assert_eq!(report.applied, expected_applied);
+
+    assert!(table_exists(&db, "sbtc_signer.webhook_subscribers").await);
+    assert!(table_exists(&db, "sbtc_signer.row_integrity_hashes").await);
+
+    // The database now tracks its migrations, so the regular migration
+    // path is a no-op and the legacy path refuses to run.
+    db.apply_migrations().await.unwrap();
+    let result = db.migrate_legacy(true).await;
+    assert!(matches!(result, Err(Error::LegacyMigrationNotNeeded)));
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the messages collected for WSTS signing rounds are returned
+/// in the order that they were written, and are deleted per round or when
+/// the chain tip moves.
+#[tokio::test]
+async fn wsts_round_messages_are_scoped_to_their_round() {
+    let db = testing::storage::new_test_database().await;
+
+    let chain_tip: BitcoinBlockHash = Faker.fake();
+    let next_chain_tip: BitcoinBlockHash = Faker.fake();
+    let round_message =
+        |bitcoin_chain_tip, message: &[u8], payload: &[u8]| model::WstsRoundMessage {
+            bitcoin_chain_tip,
+            message: message.to_vec(),
+            payload: payload.to_vec(),
+        };
+
+    let messages = [
+        round_message(chain_tip, b"first", b"a"),
+        round_message(chain_tip, b"first", b"b"),
+        round_message(chain_tip, b"second", b"c"),
+        round_message(next_chain_tip, b"first", b"d"),
+    ];
+    for message in &messages {
+        db.write_wsts_round_message(message).await.unwrap();
+    }
+
+    let payloads = db
+        .get_wsts_round_messages(&chain_tip, b"first")
+        .await
+        .unwrap();
+    assert_eq!(payloads, vec![b"a".to_vec(), b"b".to_vec()]);
+
+    db.delete_wsts_round_messages(&chain_tip, b"first")
+        .await
+        .unwrap();
+    let payloads = db
+        .get_wsts_round_messages(&chain_tip, b"first")
+        .await
+        .unwrap();
+    assert!(payloads.is_empty());
+    let payloads = db
+        .get_wsts_round_messages(&chain_tip, b"second")
+        .await
+        .unwrap();
+    assert_eq!(payloads, vec![b"c".to_vec()]);
+
+    db.delete_stale_wsts_round_messages(&next_chain_tip)
+        .await
+        .unwrap();
+    let payloads = db
+        .get_wsts_round_messages(&chain_tip, b"second")
+        .await
+        .unwrap();
+    assert!(payloads.is_empty());
+    let payloads = db
+        .get_wsts_round_messages(&next_chain_tip, b"first")
+        .await
+        .unwrap();
+    assert_eq!(payloads, vec![b"d".to_vec()]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the export queries only return deposit and withdrawal
+/// requests recorded within the given period.
+#[tokio::test]
+async fn requests_are_exported_by_recording_time() {
+    let db = testing::storage::new_test_database().await;
+
+    let deposit: model::DepositRequest = Faker.fake();
+    db.write_deposit_request(&deposit).await.unwrap();
+    let withdrawal: model::WithdrawalRequest = Faker.fake();
+    db.write_withdrawal_request(&withdrawal).await.unwrap();
+
+    let now = time::OffsetDateTime::now_utc();
+    let from = (now - time::Duration::hours(1)).into();
+    let to = (now + time::Duration::hours(1)).into();
+
+    let deposits = db
+        .get_deposit_requests_recorded_between(from, to)
+        .await
+        .unwrap();
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].record, deposit);
+
+    let withdrawals = db
+        .get_withdrawal_requests_recorded_between(from, to)
+        .await
+        .unwrap();
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!(withdrawals[0].record, withdrawal);
+
+    // The end of the period is exclusive.
+    let deposits = db
+        .get_deposit_requests_recorded_between(from, from)
+        .await
+        .unwrap();
+    assert!(deposits.is_empty());
+    let withdrawals = db
+        .get_withdrawal_requests_recorded_between(to, (now + time::Duration::hours(2)).into())
+        .await
+        .unwrap();
+    assert!(withdrawals.is_empty());
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that completed-deposit events for the same deposit on competing
+/// stacks forks are all kept, that redelivered events are not recorded
+/// twice, and that the event on the canonical fork can be found.
+#[tokio::test]
+async fn completed_deposit_events_are_resolved_by_canonical_fork() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let bitcoin_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+
+    let parent_hash: model::StacksBlockHash = Faker.fake_with_rng(&mut rng);
+    let mut stacks_block = |block_height: u64| model::StacksBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: block_height.into(),
+        parent_hash,
+        bitcoin_anchor: bitcoin_block.block_hash,
+    };
+    let canonical = stacks_block(2);
+    let orphaned = stacks_block(1);
+    for block in [&canonical, &orphaned] {
+        db.write_stacks_block(block).await.unwrap();
+    }
+
+    let outpoint = bitcoin::OutPoint::new(Faker.fake::<model::BitcoinTxId>().into(), 0);
+    let orphaned_event = CompletedDepositEvent {
+        outpoint,
+        block_id: orphaned.block_hash,
+        ..Faker.fake()
+    };
+    let canonical_event = CompletedDepositEvent {
+        outpoint,
+        block_id: canonical.block_hash,
+        ..Faker.fake()
+    };
+
+    db.write_completed_deposit_event(&orphaned_event)
+        .await
+        .unwrap();
+    let event = db
+        .get_canonical_completed_deposit_event(&bitcoin_block.block_hash, &outpoint)
+        .await
+        .unwrap();
+    assert_eq!(event, None);
+
+    for event in [&canonical_event, &orphaned_event, &canonical_event] {
+        db.write_completed_deposit_event(event).await.unwrap();
+    }
+    let events = db
+        .get_completed_deposit_events_for_outpoint(&outpoint)
+        .await
+        .unwrap();
+    assert_eq!(events, vec![orphaned_event, canonical_event.clone()]);
+
+    let event = db
+        .get_canonical_completed_deposit_event(&bitcoin_block.block_hash, &outpoint)
+        .await
+        .unwrap();
+    assert_eq!(event, Some(canonical_event));
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the events in a stacks blockchain include the events in the
+/// ancestors of its tip, and exclude the events in other forks.
+#[tokio::test]
+async fn stacks_chain_events_exclude_other_forks() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let bitcoin_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+
+    let root = model::StacksBlock {
+        block_height: 1u64.into(),
+        bitcoin_anchor: bitcoin_block.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let mut child = |block_height: u64| model::StacksBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: block_height.into(),
+        parent_hash: root.block_hash,
+        bitcoin_anchor: bitcoin_block.block_hash,
+    };
+    let canonical = child(2);
+    let orphaned = child(2);
+    for block in [&root, &canonical, &orphaned] {
+        db.write_stacks_block(block).await.unwrap();
+    }
+
+    let root_deposit = CompletedDepositEvent {
+        block_id: root.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let orphaned_deposit = CompletedDepositEvent {
+        block_id: orphaned.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    for event in [&root_deposit, &orphaned_deposit] {
+        db.write_completed_deposit_event(event).await.unwrap();
+    }
+
+    let canonical_rotation = KeyRotationEvent {
+        block_hash: canonical.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let orphaned_rotation = KeyRotationEvent {
+        block_hash: orphaned.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    for event in [&canonical_rotation, &orphaned_rotation] {
+        db.write_rotate_keys_transaction(event).await.unwrap();
+    }
+
+    let deposits = db
+        .get_stacks_chain_completed_deposit_events(&canonical.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(deposits, vec![root_deposit]);
+
+    let rotations = db
+        .get_stacks_chain_key_rotation_events(&canonical.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(rotations, vec![canonical_rotation]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the flow of funds through the peg wallet can be traced
+/// forward from a deposit and backward from a sweep, following only the
+/// outputs of the signers.
+#[tokio::test]
+async fn peg_wallet_funds_can_be_traced_across_sweeps() {
+    let db = testing::storage::new_test_database().await;
+
+    let input = |txid, input_index, prevout_txid, signers_script| model::PegWalletTxInput {
+        txid,
+        input_index,
+        prevout_txid,
+        prevout_output_index: 0,
+        signers_script,
+        ..Faker.fake()
+    };
+    let output = |txid, output_index, signers_script| model::PegWalletTxOutput {
+        txid,
+        output_index,
+        signers_script,
+        ..Faker.fake()
+    };
+
+    // A deposit is swept in by the first sweep, whose signers' output is
+    // spent by the second sweep.
+    let signers_utxo_txid: BitcoinTxId = Faker.fake();
+    let deposit_txid: BitcoinTxId = Faker.fake();
+    let sweep1_txid: BitcoinTxId = Faker.fake();
+    let sweep2_txid: BitcoinTxId = Faker.fake();
+
+    let sweep1_inputs = [
+        input(sweep1_txid, 0, signers_utxo_txid, true),
+        input(sweep1_txid, 1, deposit_txid, false),
+    ];
+    let sweep2_inputs = [input(sweep2_txid, 0, sweep1_txid, true)];
+    for input in sweep1_inputs.iter().chain(&sweep2_inputs) {
+        db.write_peg_wallet_tx_input(input).await.unwrap();
+    }
+
+    // The signers' output of the first sweep is only known to be theirs
+    // once it is indexed a second time.
+    let outputs = [
+        output(sweep1_txid, 0, false),
+        output(sweep1_txid, 1, false),
+        output(sweep1_txid, 0, true),
+        output(sweep2_txid, 0, true),
+    ];
+    for output in &outputs {
+        db.write_peg_wallet_tx_output(output).await.unwrap();
+    }
+
+    let indexed = db.get_peg_wallet_tx_outputs(&sweep1_txid).await.unwrap();
+    let flags: Vec<_> = indexed
+        .iter()
+        .map(|output| (output.output_index, output.signers_script))
+        .collect();
+    assert_eq!(flags, vec![(0, true), (1, false)]);
+    assert_eq!(
+        db.get_peg_wallet_tx_inputs(&sweep1_txid).await.unwrap(),
+        sweep1_inputs.to_vec()
+    );
+
+    let link = |input: &model::PegWalletTxInput, depth| model::PegWalletSpendLink {
+        input: input.clone(),
+        depth,
+    };
+
+    let deposit = bitcoin::OutPoint::new(*deposit_txid, 0);
+    let spends = db.trace_peg_wallet_spends(&deposit, 10).await.unwrap();
+    assert_eq!(
+        spends,
+        vec![link(&sweep1_inputs[1], 1), link(&sweep2_inputs[0], 2)]
+    );
+
+    let spends = db.trace_peg_wallet_spends(&deposit, 1).await.unwrap();
+    assert_eq!(spends, vec![link(&sweep1_inputs[1], 1)]);
+
+    // Both inputs of the first sweep are traced, but only the signers'
+    // input would be followed any further.
+    let sources = db.trace_peg_wallet_sources(&sweep2_txid, 10).await.unwrap();
+    let expected = vec![
+        link(&sweep2_inputs[0], 1),
+        link(&sweep1_inputs[0], 2),
+        link(&sweep1_inputs[1], 2),
+    ];
+    assert_eq!(sources, expected);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that a sweep transaction confirmed in a bitcoin block that was
+/// replaced by a reorg is returned as orphaned, unless it was confirmed on
+/// the canonical blockchain too, and that the completed-deposit events of
+/// the deposits it swept in can be found.
+#[tokio::test]
+async fn orphaned_sweep_transactions_are_found() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let genesis = model::BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 0u64.into(),
+        parent_hash: Faker.fake_with_rng(&mut rng),
+    };
+    let orphaned = model::BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 1u64.into(),
+        parent_hash: genesis.block_hash,
+    };
+    let canonical = model::BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        ..orphaned
+    };
+    for block in [&genesis, &orphaned, &canonical] {
+        db.write_bitcoin_block(block).await.unwrap();
+    }
+
+    // Two sweeps are confirmed in the orphaned block, and the second one
+    // is confirmed in the canonical block as well.
+    let mut sweeps = Vec::new();
+    for _ in 0..2 {
+        let prevout = model::TxPrevout {
+            prevout_type: model::TxPrevoutType::SignersInput,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        let tx_ref = model::BitcoinTxRef {
+            txid: prevout.txid,
+            block_hash: orphaned.block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        db.write_tx_prevout(&prevout).await.unwrap();
+        sweeps.push(prevout.txid);
+    }
+    let tx_ref = model::BitcoinTxRef {
+        txid: sweeps[1],
+        block_hash: canonical.block_hash,
+    };
+    db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+
+    let orphans = db
+        .get_orphaned_sweep_transactions(&(&orphaned).into(), 10)
+        .await
+        .unwrap();
+    assert!(orphans.is_empty());
+
+    let orphans = db
+        .get_orphaned_sweep_transactions(&(&canonical).into(), 10)
+        .await
+        .unwrap();
+    let expected = model::OrphanedSweepTransaction {
+        txid: sweeps[0],
+        block_hash: orphaned.block_hash,
+        block_height: orphaned.block_height,
+    };
+    assert_eq!(orphans, vec![expected]);
+
+    let completion = CompletedDepositEvent {
+        sweep_txid: sweeps[0],
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_completed_deposit_event(&completion).await.unwrap();
+    let events = db
+        .get_completed_deposit_events_for_sweep(&sweeps[0])
+        .await
+        .unwrap();
+    assert_eq!(events, vec![completion]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that only old deposit requests that were neither swept, minted
+/// against nor already marked as stale are candidates for being stale,
+/// and that stale deposit requests are synced with Emily only once.
+#[tokio::test]
+async fn stale_deposit_candidates_leave_out_handled_deposits() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let deposits: Vec<model::DepositRequest> =
+        (0..4).map(|_| Faker.fake_with_rng(&mut rng)).collect();
+    for deposit in &deposits {
+        db.write_deposit_request(deposit).await.unwrap();
+    }
+
+    // The second deposit was swept, the third was minted against and the
+    // fourth is already stale.
+    let prevout = model::TxPrevout {
+        prevout_txid: deposits[1].txid,
+        prevout_output_index: deposits[1].output_index,
+        prevout_type: model::TxPrevoutType::Deposit,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_tx_prevout(&prevout).await.unwrap();
+    let completion = CompletedDepositEvent {
+        outpoint: deposits[2].outpoint(),
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_completed_deposit_event(&completion).await.unwrap();
+    let stale = model::StaleDepositRequest {
+        txid: deposits[3].txid,
+        output_index: deposits[3].output_index,
+        reason: model::StaleDepositReason::Reclaimed,
+        emily_synced_at: None,
+    };
+    db.write_stale_deposit_request(&stale).await.unwrap();
+
+    let now = time::OffsetDateTime::now_utc();
+    let candidates = db
+        .get_stale_deposit_candidates((now - time::Duration::hours(1)).into())
+        .await
+        .unwrap();
+    assert!(candidates.is_empty());
+
+    let candidates = db
+        .get_stale_deposit_candidates((now + time::Duration::hours(1)).into())
+        .await
+        .unwrap();
+    assert_eq!(candidates, vec![deposits[0].clone()]);
+
+    // Marking a deposit as stale again keeps the original reason.
+    let again = model::StaleDepositRequest {
+        reason: model::StaleDepositReason::NeverConfirmed,
+        ..stale.clone()
+    };
+    db.write_stale_deposit_request(&again).await.unwrap();
+    let stored = db.get_stale_deposit_requests().await.unwrap();
+    assert_eq!(stored, vec![stale.clone()]);
+
+    db.mark_stale_deposit_request_synced(&stale.txid, stale.output_index)
+        .await
+        .unwrap();
+    let synced_at = db.get_stale_deposit_requests().await.unwrap()[0].emily_synced_at;
+    assert!(synced_at.is_some());
+
+    db.mark_stale_deposit_request_synced(&stale.txid, stale.output_index)
+        .await
+        .unwrap();
+    let stored = db.get_stale_deposit_requests().await.unwrap();
+    assert_eq!(stored[0].emily_synced_at, synced_at);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that all the events of a stacks block are written in one go,
+/// along with the sweep completions that they reference and the record
+/// that the block was processed, and that writing them again does not
+/// duplicate any of them.
+#[tokio::test]
+async fn stacks_block_events_are_written_together() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let block_hash: StacksBlockHash = Faker.fake_with_rng(&mut rng);
+    let source = model::RegistryEventSource {
+        block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let deposit = CompletedDepositEvent {
+        block_id: block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let accept = WithdrawalAcceptEvent {
+        block_id: block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let events = model::StacksBlockEvents {
+        block_hash,
+        events: vec![
+            (
+                model::StacksBlockEvent::CompletedDeposit(deposit.clone()),
+                source.clone(),
+            ),
+            (
+                model::StacksBlockEvent::WithdrawalAccept(accept.clone()),
+                source,
+            ),
+        ],
+        integrity_hashes: Vec::new(),
+    };
+
+    assert!(!db.is_stacks_block_processed(&block_hash).await.unwrap());
+    for (event, _) in &events.events {
+        assert!(!db.is_stacks_block_event_stored(event).await.unwrap());
+    }
+    db.write_stacks_block_events(&events).await.unwrap();
+    assert!(db.is_stacks_block_processed(&block_hash).await.unwrap());
+    for (event, _) in &events.events {
+        assert!(db.is_stacks_block_event_stored(event).await.unwrap());
+    }
+
+    let deposits = db
+        .get_completed_deposit_events_for_outpoint(&deposit.outpoint)
+        .await
+        .unwrap();
+    assert_eq!(deposits, vec![deposit.clone()]);
+    for sweep_txid in [deposit.sweep_txid, accept.sweep_txid] {
+        let completions = db.get_sweep_completions(&sweep_txid).await.unwrap();
+        assert_eq!(completions.len(), 1);
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the failures of a stacks block are counted.
+#[tokio::test]
+async fn stacks_block_failures_are_counted() {
+    let db = testing::storage::new_test_database().await;
+    let block_hash: StacksBlockHash = Faker.fake_with_rng(&mut get_rng());
+
+    let failure_count = db
+        .get_stacks_block_failure_count(&block_hash)
+        .await
+        .unwrap();
+    assert_eq!(failure_count, 0);
+
+    for expected in 1..=3 {
+        let failure_count = db
+            .write_stacks_block_failure(&block_hash, "database unavailable")
+            .await
+            .unwrap();
+        assert_eq!(failure_count, expected);
+    }
+    let failure_count = db
+        .get_stacks_block_failure_count(&block_hash)
+        .await
+        .unwrap();
+    assert_eq!(failure_count, 3);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that raw webhook bodies round trip through the database, that the
+/// first body stored for a block is kept, and that bodies are deleted once
+/// they were stored before the given time.
+#[tokio::test]
+async fn raw_stacks_events_round_trip() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let block_hash: StacksBlockHash = Faker.fake_with_rng(&mut rng);
+    let raw_event = db.get_raw_stacks_event(&block_hash).await.unwrap();
+    assert!(raw_event.is_none());
+
+    db.write_raw_stacks_event(&block_hash, b"first")
+        .await
+        .unwrap();
+    db.write_raw_stacks_event(&block_hash, b"second")
+        .await
+        .unwrap();
+    let raw_event = db.get_raw_stacks_event(&block_hash).await.unwrap();
+    assert_eq!(raw_event.as_deref(), Some(b"first".as_slice()));
+
+    let hour_ago = OffsetDateTime::now_utc() - Duration::from_secs(3600);
+    db.delete_raw_stacks_events_before(hour_ago.into())
+        .await
+        .unwrap();
+    assert!(
+        db.get_raw_stacks_event(&block_hash)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    let in_an_hour = OffsetDateTime::now_utc() + Duration::from_secs(3600);
+    db.delete_raw_stacks_events_before(in_an_hour.into())
+        .await
+        .unwrap();
+    assert!(
+        db.get_raw_stacks_event(&block_hash)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// Check that the tenure of a stacks block round trips through the
+/// database, and that recording it again does not overwrite it.
+#[tokio::test]
+async fn stacks_block_tenures_round_trip() {
+    let db = testing::storage::new_test_database().await;
+    let block_hash: StacksBlockHash = Faker.fake_with_rng(&mut get_rng());
+
+    let tenure = db.get_stacks_block_tenure(&block_hash).await.unwrap();
+    assert!(tenure.is_none());
+
+    let tenure = model::StacksBlockTenure {
+        block_hash,
+        tenure_change: Some(TenureChangeKind::BlockFound),
+        tenure_height: Some(32),
+        signer_bitvec: Some(vec![true, false, true]),
+    };
+    db.write_stacks_block_tenure(&tenure).await.unwrap();
+
+    let replayed = model::StacksBlockTenure {
+        tenure_change: None,
+        signer_bitvec: None,
+        ..tenure.clone()
+    };
+    db.write_stacks_block_tenure(&replayed).await.unwrap();
+
+    let stored = db.get_stacks_block_tenure(&block_hash).await.unwrap();
+    assert_eq!(stored, Some(tenure));
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that crash reports round trip through the database, and that
+/// they are returned newest first.
+#[tokio::test]
+async fn crash_reports_round_trip() {
+    let db = testing::storage::new_test_database().await;
+
+    let reports = db.get_crash_reports(10).await.unwrap();
+    assert!(reports.is_empty());
+
+    let created_at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+    let report = model::CrashReport {
+        component: "block-observer".to_string(),
+        message: "something went wrong".to_string(),
+        backtrace: Some("0: signer::block_observer".to_string()),
+        recent_events: vec!["first".to_string(), "second".to_string()],
+        bitcoin_chain_tip: Some(Faker.fake_with_rng(&mut get_rng())),
+        bitcoin_chain_tip_height: Some(BitcoinBlockHeight::from(850_000u64)),
+        stacks_chain_tip: Some(Faker.fake_with_rng(&mut get_rng())),
+        created_at: created_at.into(),
+    };
+    let later_report = model::CrashReport {
+        component: "balance-monitor".to_string(),
+        backtrace: None,
+        recent_events: Vec::new(),
+        bitcoin_chain_tip: None,
+        bitcoin_chain_tip_height: None,
+        stacks_chain_tip: None,
+        created_at: (created_at + Duration::from_secs(60)).into(),
+        ..report.clone()
+    };
+    db.write_crash_report(&report).await.unwrap();
+    db.write_crash_report(&later_report).await.unwrap();
+
+    let reports = db.get_crash_reports(10).await.unwrap();
+    assert_eq!(reports, vec![later_report.clone(), report]);
+
+    let reports = db.get_crash_reports(1).await.unwrap();
+    assert_eq!(reports, vec![later_report]);
+
+    testing::storage::drop_db(db).await;
+}
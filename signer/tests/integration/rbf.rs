@@ -12,6 +12,7 @@ use bitcoincore_rpc::jsonrpc::error::RpcError;
 use rand::Rng as _;
 use rand::distributions::Uniform;
 use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use signer::MAX_STANDARD_TX_VSIZE;
 use signer::bitcoin::utxo::DepositRequest;
 use signer::bitcoin::utxo::Fees;
 use signer::bitcoin::utxo::RequestRef;
@@ -226,11 +227,15 @@ pub fn transaction_with_rbf(
             // The value here isn't important, but it matches what happens
             // in Nakamoto testnet.
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: failure_threshold,
         num_signers: 2 * failure_threshold,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     // Okay, lets submit the transaction. We also do a sanity check where
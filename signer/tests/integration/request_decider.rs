@@ -389,6 +389,7 @@ async fn persist_received_deposit_decision_fetches_missing_deposit_requests() {
         deposit_script: setup.deposit_request.deposit_script.to_hex_string(),
         reclaim_script: setup.deposit_request.reclaim_script.to_hex_string(),
         transaction_hex: serialize_hex(&setup.deposit_tx_info.tx),
+        correlation_id: None,
     };
     let _ = deposit_api::create_deposit(emily_client.config(), body)
         .await
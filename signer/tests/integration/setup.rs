@@ -21,6 +21,7 @@ use sbtc::testing::regtest;
 use sbtc::testing::regtest::Faucet;
 use sbtc::testing::regtest::Recipient;
 use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use signer::MAX_STANDARD_TX_VSIZE;
 use signer::bitcoin::BitcoinInteract as _;
 use signer::bitcoin::rpc::BitcoinCoreClient;
 use signer::bitcoin::rpc::BitcoinTxInfo;
@@ -207,11 +208,15 @@ impl TestSweepSetup {
                 public_key: signers_public_key,
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                lock_time: bitcoin::absolute::LockTime::ZERO,
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // There should only be one transaction here since there is only
@@ -957,11 +962,15 @@ impl TestSweepSetup2 {
                 public_key: aggregated_signer.keypair.x_only_public_key().0,
                 last_fees,
                 magic_bytes: [b'T', b'3'],
+                lock_time: bitcoin::absolute::LockTime::ZERO,
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+            donations: Vec::new(),
+            aggregate_withdrawal_outputs: false,
         };
 
         // There should only be one transaction here since there is only
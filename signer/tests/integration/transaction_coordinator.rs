@@ -3906,6 +3906,7 @@ async fn test_get_btc_state_with_no_available_sweep_transactions() {
     let context = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_clients()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     let network = SignerNetwork::single(&context);
 
@@ -3986,15 +3987,15 @@ async fn test_get_btc_state_with_no_available_sweep_transactions() {
 
     // Get the chain tip and assert that it is the block we just wrote.
     let chain_tip = db
-        .get_bitcoin_canonical_chain_tip()
+        .get_bitcoin_canonical_chain_tip_ref()
         .await
         .unwrap()
         .expect("no chain tip");
-    assert_eq!(chain_tip, bitcoin_block.block_hash);
+    assert_eq!(chain_tip.block_hash, bitcoin_block.block_hash);
 
     // Get the signer UTXO and assert that it is the one we just wrote.
     let utxo = db
-        .get_signer_utxo(&chain_tip)
+        .get_signer_utxo(&chain_tip.block_hash)
         .await
         .unwrap()
         .expect("no signer utxo");
@@ -4013,6 +4014,10 @@ async fn test_get_btc_state_with_no_available_sweep_transactions() {
     assert_eq!(btc_state.fee_rate, 1.3);
     assert_eq!(btc_state.last_fees, None);
     assert_eq!(btc_state.magic_bytes, [b'T', b'3']);
+    assert_eq!(
+        u64::from(btc_state.lock_time.to_consensus_u32()),
+        *chain_tip.block_height
+    );
 
     testing::storage::drop_db(db).await;
 }
@@ -4105,11 +4110,15 @@ async fn test_get_btc_state_with_available_sweep_transactions_and_rbf() {
     .await
     .unwrap();
 
-    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
+    let chain_tip = db
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .unwrap()
+        .unwrap();
 
     // Get the signer UTXO and assert that it is the one we just wrote.
     let utxo = db
-        .get_signer_utxo(&chain_tip)
+        .get_signer_utxo(&chain_tip.block_hash)
         .await
         .unwrap()
         .expect("no signer utxo");
@@ -4379,11 +4388,11 @@ async fn test_conservative_initial_sbtc_limits() {
         ctx.with_emily_client(|client| {
             // We already stored the deposit, we don't need it from Emily
             client
-                .expect_get_deposits()
-                .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+                .expect_get_deposits_updated_since()
+                .returning(|_| Box::pin(std::future::ready(Ok(vec![]))));
 
             // We don't care about this
-            client.expect_accept_deposits().returning(|_| {
+            client.expect_accept_deposits().returning(|_, _| {
                 Box::pin(std::future::ready(Err(Error::InvalidStacksResponse(
                     "dummy",
                 ))))
@@ -5341,7 +5350,7 @@ async fn process_rejected_withdrawal(is_completed: bool, is_in_mempool: bool) {
     let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
 
     let config = context.config_mut();
-    config.signer.private_key = private_key;
+    config.signer.private_key = private_key.clone();
     config.signer.bootstrap_signatures_required = signing_threshold as u16;
     config.signer.bootstrap_signing_set = signer_info.first().unwrap().signer_public_keys.clone();
 
@@ -5992,7 +6001,7 @@ async fn should_handle_dkg_coordination_failure() {
     let mut coordinator = TxCoordinatorEventLoop {
         context: context.clone(),
         network: network.spawn(),
-        private_key: context.config().signer.private_key,
+        private_key: context.config().signer.private_key.clone(),
         threshold: 1,
         context_window: 5,
         signing_round_max_duration: std::time::Duration::from_secs(5),
@@ -28,6 +28,7 @@ use test_case::test_case;
 use signer::bitcoin::utxo::RequestRef;
 use signer::bitcoin::utxo::Requests;
 use signer::bitcoin::utxo::UnsignedTransaction;
+use signer::bitcoin::utxo::sweep_lock_time;
 use signer::bitcoin::validation::TxRequestIds;
 use signer::context::Context as _;
 use signer::context::SbtcLimits;
@@ -493,6 +494,7 @@ async fn assert_should_be_able_to_handle_sbtc_requests() {
     let ctx = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_clients()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
 
@@ -555,6 +557,8 @@ async fn assert_should_be_able_to_handle_sbtc_requests() {
         request_package: vec![sbtc_requests],
         fee_rate,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let sbtc_state = signer::bitcoin::utxo::SignerBtcState {
@@ -568,6 +572,7 @@ async fn assert_should_be_able_to_handle_sbtc_requests() {
         last_fees: None,
         public_key: setup.aggregated_signer.keypair.public_key().into(),
         magic_bytes: [b'T', b'3'],
+        lock_time: sbtc_context.lock_time,
     };
 
     // Create an unsigned transaction with the deposit request
@@ -631,6 +636,7 @@ async fn presign_requests_with_dkg_shares_status(status: DkgSharesStatus, is_ok:
     let ctx = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_clients()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
 
     let (rpc, faucet) = sbtc::testing::regtest::initialize_blockchain();
@@ -704,6 +710,8 @@ async fn presign_requests_with_dkg_shares_status(status: DkgSharesStatus, is_ok:
         request_package: vec![sbtc_requests],
         fee_rate: 2.0,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let result = tx_signer
@@ -728,6 +736,7 @@ pub async fn presign_request_ignore_request_if_already_processed_this_block() {
     let ctx = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_clients()
+        .modify_settings(|settings| settings.signer.sweep_lock_time = true)
         .build();
 
     let (rpc, faucet) = sbtc::testing::regtest::initialize_blockchain();
@@ -801,6 +810,8 @@ pub async fn presign_request_ignore_request_if_already_processed_this_block() {
         request_package: vec![sbtc_requests],
         fee_rate: 2.0,
         last_fees: None,
+        lock_time: sweep_lock_time(chain_tip.block_height).unwrap(),
+        donations: Vec::new(),
     };
 
     let result = tx_signer
@@ -1171,7 +1182,7 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
-        signer_private_key: ctx.config().signer.private_key,
+        signer_private_key: ctx.config().signer.private_key.clone(),
         threshold: 2,
         last_presign_block: None,
         rng: rand::rngs::StdRng::seed_from_u64(51),
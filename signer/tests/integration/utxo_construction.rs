@@ -27,6 +27,7 @@ use sbtc::deposits::DepositInfo;
 use sbtc::deposits::DepositScriptInputs;
 use sbtc::deposits::ReclaimScriptInputs;
 use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use signer::MAX_STANDARD_TX_VSIZE;
 use signer::bitcoin::rpc::BitcoinCoreClient;
 use signer::bitcoin::utxo::DepositRequest;
 use signer::bitcoin::utxo::SbtcRequests;
@@ -173,6 +174,48 @@ fn helper_struct_methods_work() {
     assert_eq!(utxo.amount.to_sat(), 500_000);
 }
 
+/// Check that the faucet can fund deposit addresses and trigger reorgs,
+/// and that transactions in orphaned blocks are mined again on the new
+/// chain.
+#[test]
+fn faucet_funds_deposits_and_triggers_reorgs() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let deposit = DepositScriptInputs {
+        signers_public_key: Recipient::new(AddressType::P2tr)
+            .keypair
+            .x_only_public_key()
+            .0,
+        max_fee: 10_000,
+        recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+    };
+    let reclaim = ReclaimScriptInputs::try_new(50, ScriptBuf::new()).unwrap();
+
+    let outpoint = faucet.fund_deposit(100_000, &deposit, &reclaim);
+    let block_hash = faucet.generate_block();
+
+    let tx_info = rpc.get_raw_transaction_info(&outpoint.txid, None).unwrap();
+    assert_eq!(tx_info.blockhash, Some(block_hash));
+    assert_eq!(tx_info.vout[outpoint.vout as usize].value.to_sat(), 100_000);
+
+    let height = rpc.get_block_count().unwrap();
+    let (orphaned, new_chain) = faucet.reorg(1);
+    assert_eq!(orphaned, vec![block_hash]);
+    assert_eq!(new_chain.len(), 2);
+    assert_eq!(rpc.get_block_hash(height).unwrap(), new_chain[0]);
+
+    // The deposit transaction was returned to the mempool and mined
+    // again on the new chain.
+    let tx_info = rpc.get_raw_transaction_info(&outpoint.txid, None).unwrap();
+    let confirmed_in = tx_info.blockhash.unwrap();
+    assert!(new_chain.contains(&confirmed_in));
+
+    // Reconsidering the orphaned block does not switch back to it, since
+    // the new chain has more work.
+    faucet.reconsider_block(&block_hash);
+    assert_eq!(rpc.get_block_hash(height).unwrap(), new_chain[0]);
+}
+
 /// Check that deposits, when sent with the expected format, are
 /// spent using the transactions generated in the utxo module.
 #[test]
@@ -233,11 +276,15 @@ fn deposits_add_to_controlled_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     // There should only be one transaction here since there is only one
@@ -298,11 +345,15 @@ fn withdrawals_reduce_to_signers_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     // There should only be one transaction here since there is only one
@@ -443,11 +494,15 @@ fn parse_withdrawal_ids(withdrawal_numbers: u64) {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            lock_time: bitcoin::absolute::LockTime::ZERO,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_tx_vsize: MAX_STANDARD_TX_VSIZE,
+        donations: Vec::new(),
+        aggregate_withdrawal_outputs: false,
     };
 
     // There should only be one transaction here since there are only